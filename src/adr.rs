@@ -0,0 +1,332 @@
+//! Architecture decision records for the `adr` tool.
+//!
+//! Each model answers the design question on its own with the options it sees,
+//! their trade-offs, and a recommendation. A synthesizer merges the proposals
+//! into the body of an ADR (context, decision drivers, considered options,
+//! decision, consequences), noting where the models disagreed. The record is
+//! numbered after the highest-numbered record already in the docs directory and
+//! written there as `NNNN-title-slug.md` with status "Proposed".
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::context::escape_xml_attr;
+
+/// Where records go when neither the request nor `[settings] adr_dir` says.
+pub const DEFAULT_DIR: &str = "docs/adr";
+
+/// Seconds for both rounds together (default; capped at `review::MAX_TIMEOUT_SECS`).
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// High-tier models proposing options when the request names none.
+pub const DEFAULT_MODELS: usize = 3;
+
+/// Longest title derived from the question.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Longest file-name slug.
+const MAX_SLUG_CHARS: usize = 60;
+
+/// Headings the synthesizer must produce for its reply to count as an ADR.
+const REQUIRED_SECTIONS: &[&str] = &["## Context", "## Decision"];
+
+/// Models to ask: the high precision tier (by name, first `DEFAULT_MODELS`),
+/// or `default_models` if no model is in that tier.
+pub fn strong_models(available: &[(String, String)], default_models: &[String]) -> Vec<String> {
+    let mut strong: Vec<String> = available
+        .iter()
+        .filter(|(_, tier)| tier == "high")
+        .map(|(name, _)| name.clone())
+        .collect();
+    strong.sort();
+    strong.truncate(DEFAULT_MODELS);
+    if strong.is_empty() {
+        default_models.to_vec()
+    } else {
+        strong
+    }
+}
+
+/// Title from the first line of the question, without a trailing `?`.
+pub fn title_from(question: &str) -> String {
+    let first = question.lines().map(str::trim).find(|l| !l.is_empty());
+    let first = first.unwrap_or_default().trim_end_matches(['?', '.', ':']);
+    match first.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((end, _)) => format!("{}…", first[..end].trim_end()),
+        None => first.to_string(),
+    }
+}
+
+/// Lowercase ASCII slug of `title` for the file name.
+pub fn slug(title: &str) -> String {
+    let mut out = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+        if out.len() >= MAX_SLUG_CHARS {
+            break;
+        }
+    }
+    let out = out.trim_end_matches('-');
+    if out.is_empty() {
+        "decision".to_string()
+    } else {
+        out.to_string()
+    }
+}
+
+fn with_context(question: &str, context: Option<&str>) -> String {
+    match context {
+        Some(ctx) => format!("{ctx}\n\n<question>\n{question}\n</question>"),
+        None => format!("<question>\n{question}\n</question>"),
+    }
+}
+
+/// Prompt asking one model for its options and recommendation.
+pub fn options_prompt(question: &str, context: Option<&str>) -> String {
+    format!(
+        "{}\n\nYou are advising on an architecture decision. Identify the realistic options \
+         (at least two), each under a `### Option: <name>` heading with its pros, cons, cost \
+         and risks for this codebase. Name the forces that should drive the choice. End with \
+         `### Recommendation` naming one option and why. Be specific to the context given; \
+         say what you would need to know where it is missing.",
+        with_context(question, context)
+    )
+}
+
+/// Prompt asking the synthesizer to merge the proposals into an ADR body.
+pub fn synthesis_prompt(
+    question: &str,
+    context: Option<&str>,
+    proposals: &[(String, String)],
+) -> String {
+    let mut prompt = with_context(question, context);
+    prompt.push_str("\n\n");
+    for (model, text) in proposals {
+        prompt.push_str(&format!(
+            "<proposal model=\"{}\">\n{}\n</proposal>\n",
+            escape_xml_attr(model),
+            text.trim()
+        ));
+    }
+    prompt.push_str(
+        "\nMerge these independent proposals into the body of an architecture decision \
+         record. Use exactly these sections, in this order:\n\
+         ## Context — the problem and constraints.\n\
+         ## Decision Drivers — the forces that decide it, as a list.\n\
+         ## Considered Options — one `### <option>` per distinct option, with **Pros** and \
+         **Cons** lists; merge options that are the same idea.\n\
+         ## Decision — the chosen option and why. Where the proposals recommended \
+         different options, say so and which model recommended what.\n\
+         ## Consequences — what becomes easier, what becomes harder, and follow-up work.\n\
+         Reply with the markdown only, starting at `## Context`. No title, no code fence.",
+    );
+    prompt
+}
+
+/// The ADR body from a synthesizer reply: fence and any title line removed.
+/// None when a required section is missing.
+pub fn parse_body(reply: &str) -> Option<String> {
+    let mut text = reply.trim();
+    if let Some(inner) = text.strip_prefix("```") {
+        let inner = inner.split_once('\n').map_or("", |(_, rest)| rest);
+        text = inner.trim_end().strip_suffix("```").unwrap_or(inner).trim();
+    }
+    let start = text.find("## Context").unwrap_or(0);
+    let body = text[start..].trim();
+    REQUIRED_SECTIONS
+        .iter()
+        .all(|section| body.lines().any(|l| l.trim_end() == *section))
+        .then(|| body.to_string())
+}
+
+/// A synthesized decision record.
+#[derive(Debug, Clone, Serialize)]
+pub struct Adr {
+    /// Record number; set when saved.
+    pub number: u32,
+    pub title: String,
+    pub status: String,
+    pub date: String,
+    /// Models whose proposals went into it.
+    pub proposed_by: Vec<String>,
+    pub synthesized_by: String,
+    pub body: String,
+}
+
+impl Adr {
+    pub fn new(
+        title: String,
+        proposed_by: Vec<String>,
+        synthesized_by: String,
+        body: String,
+    ) -> Self {
+        Self {
+            number: 0,
+            title,
+            status: "Proposed".to_string(),
+            date: crate::memory::iso_date(),
+            proposed_by,
+            synthesized_by,
+            body,
+        }
+    }
+
+    /// The record as written to disk. An unsaved record has no number.
+    pub fn to_markdown(&self) -> String {
+        let heading = if self.number > 0 {
+            format!("# {}. {}", self.number, self.title)
+        } else {
+            format!("# {}", self.title)
+        };
+        format!(
+            "{heading}\n\n- Status: {}\n- Date: {}\n- Proposals: {}\n- Synthesized by: {}\n\n{}\n",
+            self.status,
+            self.date,
+            self.proposed_by.join(", "),
+            self.synthesized_by,
+            self.body.trim_end()
+        )
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{:04}-{}.md", self.number, slug(&self.title))
+    }
+}
+
+/// Highest record number among `NNNN-*.md` files in `dir` (0 if none).
+pub async fn last_number(dir: &Path) -> u32 {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return 0;
+    };
+    let mut last = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+        if !digits.is_empty()
+            && name[digits.len()..].starts_with('-')
+            && let Ok(n) = digits.parse::<u32>()
+        {
+            last = last.max(n);
+        }
+    }
+    last
+}
+
+/// Number `adr` after the last record in `dir` and write it there. Never
+/// replaces an existing file: a number taken meanwhile moves on to the next.
+pub async fn save(dir: &Path, adr: &mut Adr) -> Result<PathBuf, String> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("{}: {e}", dir.display()))?;
+    let mut number = last_number(dir).await + 1;
+    for _ in 0..10 {
+        adr.number = number;
+        let path = dir.join(adr.file_name());
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await;
+        match file {
+            Ok(mut file) => {
+                use tokio::io::AsyncWriteExt;
+                // tokio's File writes in the background; flush so the record is
+                // on disk when we return.
+                let written = match file.write_all(adr.to_markdown().as_bytes()).await {
+                    Ok(()) => file.flush().await,
+                    Err(e) => Err(e),
+                };
+                written.map_err(|e| format!("{}: {e}", path.display()))?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => number += 1,
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        }
+    }
+    Err(format!("{}: no free record number", dir.display()))
+}
+
+/// Tool output: the record, where it went, and who answered.
+pub fn to_markdown(adr: &Adr, path: Option<&str>, notes: &[String]) -> String {
+    let mut md = match path {
+        Some(path) => format!("Saved to `{path}`.\n\n"),
+        None => String::new(),
+    };
+    md.push_str(&adr.to_markdown());
+    if !notes.is_empty() {
+        md.push_str("\n### Notes\n");
+        for note in notes {
+            md.push_str(&format!("- {note}\n"));
+        }
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_slugs_and_strong_models() {
+        assert_eq!(
+            title_from("\nShould we move sessions to Redis?\nWe run three replicas."),
+            "Should we move sessions to Redis"
+        );
+        assert_eq!(
+            slug("Use gRPC (not REST) for internal calls"),
+            "use-grpc-not-rest-for-internal-calls"
+        );
+        assert_eq!(slug("¿?"), "decision");
+
+        let available = vec![
+            ("zeta".to_string(), "high".to_string()),
+            ("grok".to_string(), "medium".to_string()),
+            ("alpha".to_string(), "high".to_string()),
+        ];
+        assert_eq!(strong_models(&available, &[]), ["alpha", "zeta"]);
+        let defaults = vec!["grok".to_string()];
+        assert_eq!(strong_models(&available[1..2], &defaults), ["grok"]);
+    }
+
+    #[test]
+    fn body_needs_context_and_decision() {
+        let reply =
+            "```markdown\n# ADR: sessions\n## Context\nThree replicas.\n\n## Decision\nRedis.\n```";
+        assert_eq!(
+            parse_body(reply).as_deref(),
+            Some("## Context\nThree replicas.\n\n## Decision\nRedis.")
+        );
+        assert_eq!(parse_body("## Context\nNo decision here."), None);
+        // "## Decision Drivers" alone is not a decision.
+        assert_eq!(parse_body("## Context\nx\n## Decision Drivers\n- y"), None);
+    }
+
+    #[tokio::test]
+    async fn save_numbers_after_existing_records() {
+        let dir = std::env::temp_dir().join(format!("squall_adr_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0007-use-postgres.md"), "# 7. Use Postgres\n").unwrap();
+        std::fs::write(dir.join("README.md"), "index").unwrap();
+
+        let mut adr = Adr::new(
+            "Cache sessions in Redis".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+            "a".to_string(),
+            "## Context\nx\n\n## Decision\ny".to_string(),
+        );
+        let path = save(&dir, &mut adr).await.unwrap();
+        assert_eq!(path, dir.join("0008-cache-sessions-in-redis.md"));
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# 8. Cache sessions in Redis\n\n- Status: Proposed\n"));
+        assert!(text.contains("- Proposals: a, b\n- Synthesized by: a\n\n## Context"));
+        assert_eq!(last_number(&dir).await, 8);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,187 @@
+//! On-disk encoding and lookup of persisted review results (`.squall/reviews/`).
+//!
+//! Deep reviews with many long model outputs run to several megabytes of JSON.
+//! Results over `COMPRESS_THRESHOLD_BYTES` are written zstd-compressed as
+//! `{stem}.json.zst`; smaller ones stay plain `{stem}.json`. With encryption at
+//! rest, the JSON is compressed first and the compressed bytes are sealed, since
+//! ciphertext does not compress. Readers go through `read_review`, which accepts
+//! both forms, and compressed files written before that order (zstd around
+//! sealed text).
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Serialized size above which review results are compressed.
+pub const COMPRESS_THRESHOLD_BYTES: usize = 256 * 1024;
+
+/// Extension of plain review results.
+pub const JSON_EXT: &str = ".json";
+
+/// Extension of compressed review results.
+pub const ZSTD_EXT: &str = ".json.zst";
+
+/// zstd level: fast, and JSON prose still compresses ~5-10x.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Largest decompressed review accepted — guards against decompression bombs.
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Encode review JSON for disk: compressed when over the threshold, then sealed
+/// if encryption is configured. Returns the bytes and the file extension.
+pub fn encode(json: &str) -> io::Result<(Vec<u8>, &'static str)> {
+    if json.len() <= COMPRESS_THRESHOLD_BYTES {
+        return Ok((crate::crypto::seal(json)?.into_bytes(), JSON_EXT));
+    }
+    Ok((compress_and_seal(json)?, ZSTD_EXT))
+}
+
+/// Encode review JSON to rewrite the existing file at `path`: same compression as
+/// the file already has, whatever the size, so the path stays valid.
+pub fn encode_like(path: &Path, json: &str) -> io::Result<Vec<u8>> {
+    if is_compressed(path) {
+        compress_and_seal(json)
+    } else {
+        Ok(crate::crypto::seal(json)?.into_bytes())
+    }
+}
+
+fn compress_and_seal(json: &str) -> io::Result<Vec<u8>> {
+    crate::crypto::seal_bytes(&zstd::encode_all(json.as_bytes(), ZSTD_LEVEL)?)
+}
+
+/// Decode file bytes read from `path` back to review JSON.
+pub fn decode(path: &Path, bytes: Vec<u8>) -> io::Result<String> {
+    let text = if is_compressed(path) {
+        // Sealed after compression; older files are plain zstd around sealed text.
+        let bytes = crate::crypto::open_bytes(bytes)?;
+        let mut out = Vec::new();
+        zstd::stream::read::Decoder::new(bytes.as_slice())?
+            .take(MAX_DECOMPRESSED_BYTES + 1)
+            .read_to_end(&mut out)?;
+        if out.len() as u64 > MAX_DECOMPRESSED_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "decompressed review exceeds size limit",
+            ));
+        }
+        out
+    } else {
+        bytes
+    };
+    let text =
+        String::from_utf8(text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    crate::crypto::open(text)
+}
+
+/// Read a review results file (plain or compressed, sealed or not).
+pub async fn read_review(path: &Path) -> io::Result<String> {
+    let bytes = tokio::fs::read(path).await?;
+    decode(path, bytes)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.to_str().is_some_and(|p| p.ends_with(ZSTD_EXT))
+}
+
+/// Prefix of the MCP resource URIs under which persisted reviews are served.
+pub const REVIEW_URI_PREFIX: &str = "squall://reviews/";
+
+/// MCP resource URI of a persisted review.
+pub fn review_uri(review_id: &str) -> String {
+    format!("{REVIEW_URI_PREFIX}{review_id}")
+}
+
+/// Stem (`{ts_ms}_{pid}_{seq}`) of a review results file name, plain or
+/// compressed. None for findings sidecars and anything else.
+pub fn review_stem(file_name: &str) -> Option<&str> {
+    let stem = file_name
+        .strip_suffix(ZSTD_EXT)
+        .or_else(|| file_name.strip_suffix(JSON_EXT))?;
+    (!stem.ends_with("_findings")).then_some(stem)
+}
+
+/// Resolve a review id (file stem like `1773157800594_76050_0`, or a path ending
+/// in `.json` / `.json.zst`) to a file in `reviews_dir`, preferring whichever form
+/// exists. Only the file name is used, so ids cannot escape the directory.
+pub fn review_path(reviews_dir: &Path, review_id: &str) -> Result<PathBuf, String> {
+    let stem = review_id.trim();
+    let stem = stem.rsplit('/').next().unwrap_or(stem);
+    let stem = stem
+        .strip_suffix(ZSTD_EXT)
+        .or_else(|| stem.strip_suffix(JSON_EXT))
+        .unwrap_or(stem);
+    if stem.is_empty()
+        || !stem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("invalid review id: {review_id}"));
+    }
+    let plain = reviews_dir.join(format!("{stem}{JSON_EXT}"));
+    let compressed = reviews_dir.join(format!("{stem}{ZSTD_EXT}"));
+    if !plain.exists() && compressed.exists() {
+        return Ok(compressed);
+    }
+    Ok(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_reviews_compress_and_round_trip() {
+        let small = r#"{"results":[]}"#;
+        let (bytes, ext) = encode(small).unwrap();
+        assert_eq!(ext, JSON_EXT);
+        assert_eq!(decode(Path::new("1_2_3.json"), bytes).unwrap(), small);
+
+        let large = serde_json::json!({
+            "results": [{"response": "The handler leaks a file descriptor.\n".repeat(10_000)}]
+        })
+        .to_string();
+        let (bytes, ext) = encode(&large).unwrap();
+        assert_eq!(ext, ZSTD_EXT);
+        assert!(bytes.len() < large.len() / 4);
+        assert_eq!(decode(Path::new("1_2_3.json.zst"), bytes).unwrap(), large);
+    }
+
+    #[test]
+    fn review_uri_resolves_to_the_review_file() {
+        let uri = review_uri("1_2_3");
+        assert_eq!(uri, "squall://reviews/1_2_3");
+        let id = uri.strip_prefix(REVIEW_URI_PREFIX).unwrap();
+        assert_eq!(
+            review_path(Path::new(".squall/reviews"), id).unwrap(),
+            Path::new(".squall/reviews/1_2_3.json")
+        );
+    }
+
+    #[test]
+    fn review_stem_accepts_both_forms() {
+        assert_eq!(review_stem("1_2_3.json"), Some("1_2_3"));
+        assert_eq!(review_stem("1_2_3.json.zst"), Some("1_2_3"));
+        assert_eq!(review_stem("1_2_3_findings.json"), None);
+        assert_eq!(review_stem("1_2_3.tmp"), None);
+    }
+
+    #[test]
+    fn review_path_rejects_traversal_and_finds_compressed() {
+        let dir = Path::new(".squall/reviews");
+        assert_eq!(
+            review_path(dir, ".squall/reviews/1_2_3.json").unwrap(),
+            dir.join("1_2_3.json")
+        );
+        assert!(review_path(dir, "..").is_err());
+        assert!(review_path(dir, "a b").is_err());
+
+        let tmp = std::env::temp_dir().join(format!("squall-test-artifact-{}", std::process::id()));
+        std::fs::create_dir_all(&tmp).unwrap();
+        std::fs::write(tmp.join("9_9_9.json.zst"), b"").unwrap();
+        assert_eq!(
+            review_path(&tmp, "9_9_9").unwrap(),
+            tmp.join("9_9_9.json.zst")
+        );
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -0,0 +1,280 @@
+//! Ownership context for a review diff: `git blame` on the lines each hunk
+//! touches, summarized per hunk as who wrote them and how long ago they last
+//! changed. Models use it to weigh freshly written code against code that has
+//! been stable for years, and to name likely owners in findings.
+//!
+//! Hunks are blamed at HEAD on their old-side range (the removed lines and the
+//! context around an insertion). New files have no history and are skipped, as
+//! are hunks git can't blame (a diff against another base, a renamed path).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Hunks blamed per review; later hunks are left unannotated.
+pub const MAX_BLAME_HUNKS: usize = 40;
+
+/// Cap on the `<blame>` block, so it never crowds out the diff it annotates.
+pub const MAX_BLAME_CONTEXT_BYTES: usize = 8 * 1024;
+
+/// Authors listed per hunk before the rest are folded into "N others".
+const MAX_AUTHORS: usize = 3;
+
+/// Per-hunk timeout, as for `context::detect_git_context`.
+const GIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A diff hunk's old-side range.
+#[derive(Debug, PartialEq, Eq)]
+struct Hunk {
+    path: String,
+    start: u64,
+    len: u64,
+}
+
+/// One blamed line.
+#[derive(Debug)]
+struct BlameLine {
+    author: String,
+    /// Unix seconds of the commit that last changed it.
+    time: u64,
+}
+
+/// Build the `<blame>` context for `diff` in the repository at `repo`. Returns
+/// None when no hunk could be blamed.
+pub async fn blame_context(repo: &Path, diff: &str) -> Option<String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut lines = Vec::new();
+    for hunk in diff_hunks(diff).into_iter().take(MAX_BLAME_HUNKS) {
+        let Some(blamed) = blame_hunk(repo, &hunk).await else {
+            continue;
+        };
+        if let Some(summary) = summarize(&blamed, now) {
+            lines.push(format!(
+                "{} -{},{}: {summary}",
+                hunk.path, hunk.start, hunk.len
+            ));
+        }
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut body = String::new();
+    let mut omitted = 0;
+    for line in &lines {
+        let line = crate::context::escape_xml_content(line);
+        if body.len() + line.len() + 1 > MAX_BLAME_CONTEXT_BYTES {
+            omitted += 1;
+            continue;
+        }
+        body.push_str(&line);
+        body.push('\n');
+    }
+    if omitted > 0 {
+        body.push_str(&format!("<!-- {omitted} hunks omitted due to budget -->\n"));
+    }
+    Some(format!(
+        "<blame note=\"authors and last-change age of the lines each diff hunk touches, at HEAD\">\n{body}</blame>"
+    ))
+}
+
+/// Old-side ranges of the hunks in a unified diff. Hunks of new files and
+/// pure insertions at the top of a file (no old lines) are dropped.
+fn diff_hunks(diff: &str) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut path: Option<String> = None;
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            path = None;
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            path = old
+                .strip_prefix("a/")
+                .map(|p| p.trim_end().to_string())
+                .filter(|p| !p.is_empty());
+        } else if let Some(range) = line.strip_prefix("@@ -")
+            && let Some(ref path) = path
+        {
+            let range = range.split_whitespace().next().unwrap_or_default();
+            let (start, len) = match range.split_once(',') {
+                Some((start, len)) => (start.parse().ok(), len.parse().ok()),
+                None => (range.parse().ok(), Some(1)),
+            };
+            if let (Some(start), Some(len)) = (start, len)
+                && start > 0
+                && len > 0
+            {
+                hunks.push(Hunk {
+                    path: path.clone(),
+                    start,
+                    len,
+                });
+            }
+        }
+    }
+    hunks
+}
+
+/// `git blame --line-porcelain` for a hunk's range at HEAD. None if git fails.
+async fn blame_hunk(repo: &Path, hunk: &Hunk) -> Option<Vec<BlameLine>> {
+    let range = format!("{},+{}", hunk.start, hunk.len);
+    let output = tokio::time::timeout(
+        GIT_TIMEOUT,
+        tokio::process::Command::new("git")
+            .args(["blame", "--line-porcelain", "-L", &range, "HEAD", "--"])
+            .arg(&hunk.path)
+            .current_dir(repo)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()
+    .filter(|o| o.status.success())?;
+    Some(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `--line-porcelain` output: every line's headers end at its
+/// tab-prefixed content line.
+fn parse_porcelain(out: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut author = String::new();
+    let mut time = 0;
+    for line in out.lines() {
+        if line.starts_with('\t') {
+            lines.push(BlameLine {
+                author: std::mem::take(&mut author),
+                time: std::mem::take(&mut time),
+            });
+        } else if let Some(name) = line.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(t) = line.strip_prefix("author-time ") {
+            time = t.parse().unwrap_or_default();
+        }
+    }
+    lines
+}
+
+/// "alice (5 lines), bob (2 lines); last changed 3 days ago, oldest 2 years ago".
+fn summarize(lines: &[BlameLine], now: u64) -> Option<String> {
+    let newest = lines.iter().map(|l| l.time).max()?;
+    let oldest = lines.iter().map(|l| l.time).min()?;
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for line in lines {
+        *counts.entry(&line.author).or_default() += 1;
+    }
+    let mut authors: Vec<(&str, usize)> = counts.into_iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let mut parts: Vec<String> = authors
+        .iter()
+        .take(MAX_AUTHORS)
+        .map(|(name, n)| format!("{name} ({n} {})", plural(*n, "line")))
+        .collect();
+    if authors.len() > MAX_AUTHORS {
+        parts.push(format!("{} others", authors.len() - MAX_AUTHORS));
+    }
+
+    let mut summary = format!("{}; last changed {}", parts.join(", "), age(now, newest));
+    if age(now, oldest) != age(now, newest) {
+        summary.push_str(&format!(", oldest {}", age(now, oldest)));
+    }
+    Some(summary)
+}
+
+/// Coarse age of a timestamp: "today", "3 days ago", "5 months ago", "2 years ago".
+fn age(now: u64, then: u64) -> String {
+    let days = now.saturating_sub(then) / 86_400;
+    let (n, unit) = match days {
+        0 => return "today".to_string(),
+        1..=59 => (days, "day"),
+        60..=729 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    format!("{n} {} ago", plural(n as usize, unit))
+}
+
+fn plural(n: usize, unit: &str) -> String {
+    if n == 1 {
+        unit.to_string()
+    } else {
+        format!("{unit}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hunks_skip_new_files_and_pure_top_insertions() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                    @@ -10,7 +10,9 @@ fn main() {\n-x\n+y\n@@ -40 +42,2 @@\n+z\n\
+                    diff --git a/new.rs b/new.rs\nnew file mode 100644\n--- /dev/null\n+++ b/new.rs\n\
+                    @@ -0,0 +1,3 @@\n+a\n";
+        assert_eq!(
+            diff_hunks(diff),
+            vec![
+                Hunk {
+                    path: "src/a.rs".into(),
+                    start: 10,
+                    len: 7
+                },
+                Hunk {
+                    path: "src/a.rs".into(),
+                    start: 40,
+                    len: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_ranks_authors_and_reports_age_range() {
+        let now = 1_000 * 86_400;
+        let line = |author: &str, days_ago: u64| BlameLine {
+            author: author.to_string(),
+            time: now - days_ago * 86_400,
+        };
+        let lines = [line("bob", 3), line("alice", 800), line("bob", 3)];
+        assert_eq!(
+            summarize(&lines, now).unwrap(),
+            "bob (2 lines), alice (1 line); last changed 3 days ago, oldest 2 years ago"
+        );
+        assert!(summarize(&[], now).is_none());
+    }
+
+    #[tokio::test]
+    async fn blames_hunks_in_a_repository() {
+        let dir = std::env::temp_dir().join(format!("squall_blame_git_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=Dana", "-c", "user.email=d@t"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+        };
+        if !git(&["init", "-q"]).status.success() {
+            return; // git unavailable
+        }
+        std::fs::write(dir.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "base"]);
+
+        let diff = "--- a/a.txt\n+++ b/a.txt\n@@ -2,2 +2,2 @@\n-two\n+TWO\n three\n\
+                    --- a/missing.txt\n+++ b/missing.txt\n@@ -1 +1 @@\n-x\n+y\n";
+        let ctx = blame_context(&dir, diff).await.unwrap();
+        assert!(ctx.starts_with("<blame "), "{ctx}");
+        assert!(
+            ctx.contains("a.txt -2,2: Dana (2 lines); last changed today"),
+            "{ctx}"
+        );
+        assert!(!ctx.contains("missing.txt"), "{ctx}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,293 @@
+//! Diversity selection for the `brainstorm` tool.
+//!
+//! Naive multi-model brainstorming returns the same handful of ideas several
+//! times over. Each response is split into individual ideas (list items, or
+//! paragraphs when there is no list), near-identical ideas are merged (word
+//! Jaccard at or above `DUPLICATE_SIMILARITY`, the same measure used to group
+//! review findings), and the K most mutually distinct survivors are picked by
+//! greedy farthest-point selection: start from the idea least similar to the
+//! rest, then repeatedly add the idea whose closest already-picked idea is
+//! furthest away.
+
+use serde::Serialize;
+
+use crate::findings::summary_similarity;
+
+/// Default sampling temperature: high, for varied ideas.
+pub const DEFAULT_TEMPERATURE: f64 = 1.0;
+
+/// Default number of ideas returned.
+pub const DEFAULT_IDEAS: usize = 5;
+
+/// Most ideas returned per request.
+pub const MAX_IDEAS: usize = 20;
+
+/// Most samples requested from each model.
+pub const MAX_SAMPLES_PER_MODEL: usize = 3;
+
+/// Word overlap at which two ideas count as the same idea.
+pub const DUPLICATE_SIMILARITY: f64 = 0.6;
+
+/// Ideas shorter than this are labels or filler, not ideas.
+const MIN_IDEA_CHARS: usize = 20;
+
+/// Ideas kept from each response, so one verbose model can't crowd out the rest.
+const MAX_IDEAS_PER_RESPONSE: usize = 15;
+
+/// One selected idea.
+#[derive(Debug, Serialize)]
+pub struct Idea {
+    pub text: String,
+    /// Models that proposed it or a near-duplicate of it, first proposer first.
+    pub models: Vec<String>,
+    /// 1 - similarity to the closest idea picked before it (1.0 for the first).
+    pub distinctness: f64,
+}
+
+/// Outcome of diversity selection.
+#[derive(Debug, Serialize)]
+pub struct Selection {
+    pub ideas: Vec<Idea>,
+    /// Ideas extracted from all responses.
+    pub candidates: usize,
+    /// Candidates merged into an earlier near-identical idea.
+    pub duplicates: usize,
+}
+
+/// Prompt asking a model for `count` distinct ideas on `topic`.
+pub fn brainstorm_prompt(topic: &str, count: usize) -> String {
+    format!(
+        "Brainstorm {count} ideas for the following. Make each idea substantively different \
+         from the others — different mechanisms or approaches, not rewordings. Reply with a \
+         markdown list, one idea per item, each a sentence or two.\n\n{topic}"
+    )
+}
+
+/// Split a response into ideas: top-level list items with their continuation
+/// lines, or paragraphs if the response has no list.
+pub fn split_ideas(response: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in response.lines() {
+        let trimmed = line.trim();
+        if let Some(body) = list_item_body(line) {
+            items.extend(current.take());
+            current = Some(body.to_string());
+        } else if trimmed.is_empty() || trimmed.starts_with('#') {
+            items.extend(current.take());
+        } else if let Some(item) = current.as_mut()
+            && line.starts_with(char::is_whitespace)
+        {
+            item.push(' ');
+            item.push_str(trimmed);
+        } else {
+            items.extend(current.take());
+        }
+    }
+    items.extend(current);
+    if items.is_empty() {
+        items = response
+            .split("\n\n")
+            .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|p| !p.starts_with('#'))
+            .collect();
+    }
+    items.retain(|i| i.chars().count() >= MIN_IDEA_CHARS);
+    items.truncate(MAX_IDEAS_PER_RESPONSE);
+    items
+}
+
+/// Body of an unindented `-`, `*`, `+`, or `1.` list item.
+fn list_item_body(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    if let Some(body) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        return Some(body.trim());
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+        .map(str::trim)
+}
+
+/// Merge near-duplicates among `(model, idea)` candidates and pick the `k` most
+/// mutually distinct ideas.
+pub fn select(candidates: Vec<(String, String)>, k: usize) -> Selection {
+    let total = candidates.len();
+    // Cluster: each candidate joins the first representative it duplicates.
+    let mut clusters: Vec<(String, Vec<String>)> = Vec::new();
+    for (model, text) in candidates {
+        match clusters
+            .iter_mut()
+            .find(|(rep, _)| summary_similarity(rep, &text) >= DUPLICATE_SIMILARITY)
+        {
+            Some((_, models)) => {
+                if !models.contains(&model) {
+                    models.push(model);
+                }
+            }
+            None => clusters.push((text, vec![model])),
+        }
+    }
+    let duplicates = total - clusters.len();
+
+    let n = clusters.len();
+    let mut sim = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let s = summary_similarity(&clusters[i].0, &clusters[j].0);
+            sim[i][j] = s;
+            sim[j][i] = s;
+        }
+    }
+
+    let mut picked: Vec<(usize, f64)> = Vec::new();
+    // Start from the idea least similar to everything else.
+    let first = (0..n).min_by(|&a, &b| {
+        let overlap = |i: usize| sim[i].iter().sum::<f64>();
+        overlap(a).total_cmp(&overlap(b))
+    });
+    if let Some(first) = first
+        && k > 0
+    {
+        picked.push((first, 1.0));
+    }
+    while picked.len() < k.min(n) {
+        let next = (0..n)
+            .filter(|i| !picked.iter().any(|(p, _)| p == i))
+            .map(|i| {
+                let closest = picked.iter().map(|(p, _)| sim[i][*p]).fold(0.0, f64::max);
+                (i, 1.0 - closest)
+            })
+            // First on ties keeps the earlier (usually more prominent) idea.
+            .fold(None, |best: Option<(usize, f64)>, c| match best {
+                Some(b) if b.1 >= c.1 => Some(b),
+                _ => Some(c),
+            });
+        match next {
+            Some(next) => picked.push(next),
+            None => break,
+        }
+    }
+
+    let mut clusters: Vec<Option<(String, Vec<String>)>> = clusters.into_iter().map(Some).collect();
+    let ideas = picked
+        .into_iter()
+        .filter_map(|(i, distinctness)| {
+            let (text, models) = clusters[i].take()?;
+            Some(Idea {
+                text,
+                models,
+                distinctness: (distinctness * 100.0).round() / 100.0,
+            })
+        })
+        .collect();
+    Selection {
+        ideas,
+        candidates: total,
+        duplicates,
+    }
+}
+
+impl Selection {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Brainstorm: {} distinct ideas\n\n_{} candidates, {} near-duplicates merged._\n\n",
+            self.ideas.len(),
+            self.candidates,
+            self.duplicates
+        );
+        for (i, idea) in self.ideas.iter().enumerate() {
+            out.push_str(&format!(
+                "{}. {}\n   _{} · distinctness {:.2}_\n",
+                i + 1,
+                idea.text,
+                idea.models.join(", "),
+                idea.distinctness
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(model: &str, text: &str) -> (String, String) {
+        (model.to_string(), text.to_string())
+    }
+
+    #[test]
+    fn split_ideas_reads_lists_and_paragraphs() {
+        let list = "Some ideas:\n\n1. Cache parsed configs between requests\n   \
+                    keyed by file mtime.\n2. ok\n- Stream results to the client as they arrive\n";
+        assert_eq!(
+            split_ideas(list),
+            vec![
+                "Cache parsed configs between requests keyed by file mtime.",
+                "Stream results to the client as they arrive",
+            ]
+        );
+        let prose = "Shard the index by repository name.\n\nPrecompute embeddings overnight\nin a batch job.";
+        assert_eq!(
+            split_ideas(prose),
+            vec![
+                "Shard the index by repository name.",
+                "Precompute embeddings overnight in a batch job.",
+            ]
+        );
+    }
+
+    #[test]
+    fn near_duplicates_merge_and_distinct_ideas_win() {
+        let selection = select(
+            vec![
+                c("grok", "Cache parsed configuration files between requests"),
+                c(
+                    "kimi",
+                    "Cache the parsed configuration files between requests",
+                ),
+                c(
+                    "kimi",
+                    "Cache parsed configuration between incoming requests too",
+                ),
+                c(
+                    "qwen",
+                    "Stream partial model results back to the client immediately",
+                ),
+                c(
+                    "grok",
+                    "Rewrite the scheduler around a priority queue of deadlines",
+                ),
+            ],
+            2,
+        );
+        assert_eq!(selection.candidates, 5);
+        assert!(selection.duplicates >= 1);
+        assert_eq!(selection.ideas.len(), 2);
+        let texts: Vec<&str> = selection.ideas.iter().map(|i| i.text.as_str()).collect();
+        assert!(!texts.iter().all(|t| t.contains("Cache")));
+        let cache = select(
+            vec![
+                c("grok", "Cache parsed configuration files between requests"),
+                c(
+                    "kimi",
+                    "Cache the parsed configuration files between requests",
+                ),
+            ],
+            5,
+        );
+        assert_eq!(cache.ideas.len(), 1);
+        assert_eq!(cache.ideas[0].models, vec!["grok", "kimi"]);
+    }
+}
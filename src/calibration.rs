@@ -0,0 +1,245 @@
+//! Confidence calibration: how often a model's high-confidence findings hold up.
+//!
+//! When `feedback` rates a model's part in a persisted review, the findings that
+//! model reported with high confidence are counted and logged to
+//! `.squall/memory/calibration.md` with the verdict. A response rated actionable
+//! (2) counts its findings as accepted; okay (1) and noise (0) do not.
+//!
+//! Once a model has `MIN_SAMPLES` rated high-confidence findings, its acceptance
+//! rate moves the confidence labels shown for it one step: down below
+//! `OVERCONFIDENT_BELOW`, up from `RELIABLE_FROM`. Composed recommendations
+//! adjust their H/M/L column, and reviews tell the synthesizer how to read each
+//! model's "High".
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Findings at or above this confidence count as high-confidence ("High" parses as 0.9).
+pub const HIGH_CONFIDENCE: f64 = 0.8;
+
+/// Rated high-confidence findings a model needs before its labels move.
+pub const MIN_SAMPLES: usize = 5;
+
+/// Acceptance rate below which a model is treated as overconfident.
+pub const OVERCONFIDENT_BELOW: f64 = 0.5;
+
+/// Acceptance rate from which a model's high confidence is trusted.
+pub const RELIABLE_FROM: f64 = 0.8;
+
+/// Most rows kept in calibration.md; older ones are dropped first.
+pub const MAX_ROWS: usize = 500;
+
+/// Header of calibration.md.
+pub const HEADER: &str = "# Confidence Calibration\n\n\
+    | Timestamp | Model | Review | High-confidence findings | Verdict |\n\
+    |-----------|-------|--------|--------------------------|---------|";
+
+/// Rated high-confidence findings of one model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub high_confidence: usize,
+    /// Those in responses rated actionable.
+    pub accepted: usize,
+}
+
+impl Calibration {
+    /// Share of high-confidence findings accepted. None below `MIN_SAMPLES`.
+    pub fn rate(&self) -> Option<f64> {
+        (self.high_confidence >= MIN_SAMPLES)
+            .then(|| self.accepted as f64 / self.high_confidence as f64)
+    }
+
+    /// Steps to move this model's confidence labels: -1, 0 or 1.
+    pub fn shift(&self) -> i8 {
+        match self.rate() {
+            Some(rate) if rate < OVERCONFIDENT_BELOW => -1,
+            Some(rate) if rate >= RELIABLE_FROM => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Number of findings in `text` the model reported with high confidence.
+pub fn high_confidence_count(model: &str, text: &str) -> usize {
+    crate::findings::extract_findings(model, text)
+        .iter()
+        .filter(|f| f.confidence.is_some_and(|c| c >= HIGH_CONFIDENCE))
+        .count()
+}
+
+/// One calibration.md row. `verdict` is the feedback label (noise/okay/actionable).
+pub fn row(timestamp: &str, model: &str, review: &str, high: usize, verdict: &str) -> String {
+    format!(
+        "| {timestamp} | {} | {} | {high} | {verdict} |",
+        model.replace('|', "\\|"),
+        review.replace('|', "\\|")
+    )
+}
+
+/// Data rows of calibration.md, oldest first.
+pub fn rows(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|l| l.starts_with("| ") && !l.starts_with("| Timestamp"))
+        .collect()
+}
+
+/// Per-model totals from calibration.md.
+pub fn parse(content: &str) -> HashMap<String, Calibration> {
+    let mut map: HashMap<String, Calibration> = HashMap::new();
+    for line in rows(content) {
+        let cols: Vec<&str> = line
+            .split(" | ")
+            .map(|s| s.trim_matches(['|', ' ']))
+            .collect();
+        let [_, model, _, high, verdict] = cols[..] else {
+            continue;
+        };
+        let Ok(high) = high.parse::<usize>() else {
+            continue;
+        };
+        let entry = map.entry(model.replace("\\|", "|")).or_default();
+        entry.high_confidence += high;
+        if verdict == "actionable" {
+            entry.accepted += high;
+        }
+    }
+    map
+}
+
+/// An H/M/L confidence level moved by the model's calibration.
+pub fn adjust_level(level: char, calibration: Option<&Calibration>) -> char {
+    const LEVELS: [char; 3] = ['L', 'M', 'H'];
+    let Some(i) = LEVELS.iter().position(|l| *l == level) else {
+        return level;
+    };
+    let shift = calibration.map_or(0, Calibration::shift);
+    LEVELS[(i as i8 + shift).clamp(0, 2) as usize]
+}
+
+/// How far to trust one model's "High", as reported with a review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCalibration {
+    pub model: String,
+    /// Rated high-confidence findings.
+    pub high_confidence: usize,
+    /// Share of them accepted (0.0-1.0).
+    pub accepted_rate: f64,
+    /// The label its "High" findings deserve: "High" or "Medium".
+    pub high_reads_as: String,
+}
+
+/// Calibrated models among `models` (all of them if None), best first.
+pub fn view(
+    calibration: &HashMap<String, Calibration>,
+    models: Option<&[&str]>,
+) -> Vec<ModelCalibration> {
+    let mut view: Vec<ModelCalibration> = calibration
+        .iter()
+        .filter(|(model, _)| models.is_none_or(|m| m.contains(&model.as_str())))
+        .filter_map(|(model, c)| {
+            Some(ModelCalibration {
+                model: model.clone(),
+                high_confidence: c.high_confidence,
+                accepted_rate: c.rate()?,
+                high_reads_as: if c.shift() < 0 { "Medium" } else { "High" }.to_string(),
+            })
+        })
+        .collect();
+    view.sort_by(|a, b| {
+        b.accepted_rate
+            .total_cmp(&a.accepted_rate)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    view
+}
+
+/// One-line markdown summary of `view`, e.g. for recommendations.
+pub fn summary_line(view: &[ModelCalibration]) -> Option<String> {
+    if view.is_empty() {
+        return None;
+    }
+    let list: Vec<String> = view
+        .iter()
+        .map(|c| {
+            let note = if c.high_reads_as == "High" {
+                String::new()
+            } else {
+                format!(", read its High as {}", c.high_reads_as)
+            };
+            format!(
+                "{} {:.0}% ({}{note})",
+                c.model,
+                c.accepted_rate * 100.0,
+                c.high_confidence
+            )
+        })
+        .collect();
+    Some(format!(
+        "**Calibration** (high-confidence findings later rated actionable): {}",
+        list.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_add_up_per_model() {
+        let content = format!(
+            "{HEADER}\n{}\n{}\n{}\n{}",
+            row("2026-10-01T00:00:00Z", "grok", "r1", 4, "actionable"),
+            row("2026-10-02T00:00:00Z", "grok", "r2", 2, "noise"),
+            row("2026-10-02T00:00:00Z", "kimi", "r2", 3, "okay"),
+            row("2026-10-03T00:00:00Z", "kimi", "r3", 3, "noise"),
+        );
+        let map = parse(&content);
+        assert_eq!(
+            map["grok"],
+            Calibration {
+                high_confidence: 6,
+                accepted: 4
+            }
+        );
+        assert_eq!(map["kimi"].accepted, 0);
+        assert_eq!(map["grok"].shift(), 0);
+        assert_eq!(map["kimi"].shift(), -1);
+
+        assert_eq!(adjust_level('H', map.get("kimi")), 'M');
+        assert_eq!(adjust_level('L', map.get("kimi")), 'L');
+        assert_eq!(adjust_level('M', None), 'M');
+        let reliable = Calibration {
+            high_confidence: 10,
+            accepted: 9,
+        };
+        assert_eq!(adjust_level('M', Some(&reliable)), 'H');
+
+        let shown = view(&map, Some(&["kimi", "grok"][..]));
+        assert_eq!(shown[0].model, "grok");
+        assert_eq!(shown[1].high_reads_as, "Medium");
+        let line = summary_line(&shown).unwrap();
+        assert!(
+            line.ends_with("grok 67% (6), kimi 0% (6, read its High as Medium)"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn too_few_samples_leave_labels_alone() {
+        let c = Calibration {
+            high_confidence: MIN_SAMPLES - 1,
+            accepted: 0,
+        };
+        assert_eq!(c.rate(), None);
+        assert_eq!(adjust_level('H', Some(&c)), 'H');
+    }
+
+    #[test]
+    fn counts_only_high_confidence_findings() {
+        let text = "### 1. **Token compared with ==** (Confidence: **High**)\nTiming leak.\n\n\
+                    ### 2. **Unused import** (Confidence: **Low**)\nCosmetic.\n";
+        assert_eq!(high_confidence_count("grok", text), 1);
+    }
+}
@@ -0,0 +1,384 @@
+//! Compare two persisted reviews (`reviews_compare`): which findings are new,
+//! resolved, or persisting — overall and per model — plus summary deltas.
+//! Typical use is before/after validation of a fix.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::artifact::{read_review, review_path};
+use crate::findings::{Finding, FindingCluster, Severity, cluster_findings, same_issue};
+use crate::incremental::review_findings;
+
+/// One side of a comparison.
+#[derive(Debug, Serialize)]
+pub struct ReviewInfo {
+    pub review_file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    pub models_succeeded: Vec<String>,
+    pub models_failed: Vec<String>,
+    pub findings: usize,
+    /// Findings per severity (`unrated` when the model gave none).
+    pub severity_counts: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_size: Option<String>,
+}
+
+/// A finding (or the same finding from several models) in a comparison.
+#[derive(Debug, Serialize)]
+pub struct FindingRef {
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    pub models: Vec<String>,
+}
+
+impl From<FindingCluster> for FindingRef {
+    fn from(c: FindingCluster) -> Self {
+        Self {
+            summary: c.summary,
+            severity: c.severity,
+            file_path: c.file_path,
+            models: c.models,
+        }
+    }
+}
+
+/// How one model's findings changed between the reviews.
+#[derive(Debug, Serialize)]
+pub struct ModelDelta {
+    pub model: String,
+    /// `compared`, or why not: `only_in_a`, `only_in_b`, `failed_in_a`,
+    /// `failed_in_b`, `failed_in_both`.
+    pub status: &'static str,
+    pub new: Vec<String>,
+    pub resolved: Vec<String>,
+    pub persisting: Vec<String>,
+}
+
+/// Result of `compare`.
+#[derive(Debug, Serialize)]
+pub struct ReviewComparison {
+    pub review_a: ReviewInfo,
+    pub review_b: ReviewInfo,
+    /// In B, not in A.
+    pub new: Vec<FindingRef>,
+    /// In A, not in B, and at least one model that reported it succeeded in B.
+    pub resolved: Vec<FindingRef>,
+    /// In both (as reported in B).
+    pub persisting: Vec<FindingRef>,
+    /// In A, not in B, but none of the models that reported it succeeded in B —
+    /// absence proves nothing.
+    pub not_rechecked: Vec<FindingRef>,
+    pub models: Vec<ModelDelta>,
+}
+
+/// Load and compare two reviews from `reviews_dir`.
+pub async fn compare_reviews(
+    reviews_dir: &Path,
+    id_a: &str,
+    id_b: &str,
+) -> Result<ReviewComparison, String> {
+    let load = |id: &str| {
+        let path = review_path(reviews_dir, id);
+        async move {
+            let path = path?;
+            let raw = read_review(&path)
+                .await
+                .map_err(|e| format!("failed to read review {}: {e}", path.display()))?;
+            let review: serde_json::Value = serde_json::from_str(&raw)
+                .map_err(|e| format!("invalid review file {}: {e}", path.display()))?;
+            Ok::<_, String>((path, review))
+        }
+    };
+    let (path_a, a) = load(id_a).await?;
+    let (path_b, b) = load(id_b).await?;
+    Ok(compare(&path_a, &a, &path_b, &b))
+}
+
+/// Compare two parsed reviews.
+pub fn compare(
+    path_a: &Path,
+    a: &serde_json::Value,
+    path_b: &Path,
+    b: &serde_json::Value,
+) -> ReviewComparison {
+    let findings_a = review_findings(a);
+    let findings_b = review_findings(b);
+    let info_a = review_info(path_a, a, &findings_a);
+    let info_b = review_info(path_b, b, &findings_b);
+
+    let matched = |f: &Finding, others: &[Finding]| others.iter().any(|o| same_issue(f, o));
+    let new: Vec<Finding> = unmatched(&findings_b, &findings_a);
+    let persisting: Vec<Finding> = findings_b
+        .iter()
+        .filter(|&f| matched(f, &findings_a))
+        .cloned()
+        .collect();
+    let (resolved, not_rechecked): (Vec<Finding>, Vec<Finding>) =
+        unmatched(&findings_a, &findings_b)
+            .into_iter()
+            .partition(|f| info_b.models_succeeded.contains(&f.model_key));
+
+    let mut model_names: Vec<&String> = info_a
+        .models_succeeded
+        .iter()
+        .chain(&info_a.models_failed)
+        .chain(&info_b.models_succeeded)
+        .chain(&info_b.models_failed)
+        .collect();
+    model_names.sort();
+    model_names.dedup();
+    let models = model_names
+        .into_iter()
+        .map(|model| {
+            let status = match (side_status(&info_a, model), side_status(&info_b, model)) {
+                (Some(true), Some(true)) => "compared",
+                (None, _) => "only_in_b",
+                (_, None) => "only_in_a",
+                (Some(false), Some(false)) => "failed_in_both",
+                (Some(false), Some(true)) => "failed_in_a",
+                (Some(true), Some(false)) => "failed_in_b",
+            };
+            let of = |findings: &[Finding]| -> Vec<Finding> {
+                findings
+                    .iter()
+                    .filter(|f| &f.model_key == model)
+                    .cloned()
+                    .collect()
+            };
+            let (mine_a, mine_b) = (of(&findings_a), of(&findings_b));
+            let summaries =
+                |fs: Vec<Finding>| -> Vec<String> { fs.into_iter().map(|f| f.summary).collect() };
+            let (new, resolved, persisting) = if status == "compared" {
+                (
+                    summaries(unmatched(&mine_b, &mine_a)),
+                    summaries(unmatched(&mine_a, &mine_b)),
+                    summaries(
+                        mine_b
+                            .iter()
+                            .filter(|&f| matched(f, &mine_a))
+                            .cloned()
+                            .collect(),
+                    ),
+                )
+            } else {
+                (Vec::new(), Vec::new(), Vec::new())
+            };
+            ModelDelta {
+                model: model.clone(),
+                status,
+                new,
+                resolved,
+                persisting,
+            }
+        })
+        .collect();
+
+    ReviewComparison {
+        review_a: info_a,
+        review_b: info_b,
+        new: clustered(&new),
+        resolved: clustered(&resolved),
+        persisting: clustered(&persisting),
+        not_rechecked: clustered(&not_rechecked),
+        models,
+    }
+}
+
+/// Findings in `findings` with no counterpart in `others`.
+fn unmatched(findings: &[Finding], others: &[Finding]) -> Vec<Finding> {
+    findings
+        .iter()
+        .filter(|f| !others.iter().any(|o| same_issue(f, o)))
+        .cloned()
+        .collect()
+}
+
+/// Merge the same issue reported by several models into one entry.
+fn clustered(findings: &[Finding]) -> Vec<FindingRef> {
+    cluster_findings(findings)
+        .into_iter()
+        .map(FindingRef::from)
+        .collect()
+}
+
+/// Some(true) = succeeded, Some(false) = failed, None = not in the review.
+fn side_status(info: &ReviewInfo, model: &str) -> Option<bool> {
+    if info.models_succeeded.iter().any(|m| m == model) {
+        Some(true)
+    } else if info.models_failed.iter().any(|m| m == model) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn review_info(path: &Path, review: &serde_json::Value, findings: &[Finding]) -> ReviewInfo {
+    let mut models_succeeded = Vec::new();
+    let mut models_failed = Vec::new();
+    for result in review["results"].as_array().into_iter().flatten() {
+        let Some(model) = result["model"].as_str() else {
+            continue;
+        };
+        if result["status"] == "success" && result["response"].is_string() {
+            models_succeeded.push(model.to_string());
+        } else {
+            models_failed.push(model.to_string());
+        }
+    }
+    let mut severity_counts = BTreeMap::new();
+    for f in findings {
+        let key = f.severity.as_ref().map_or("unrated", |s| s.as_str());
+        *severity_counts.entry(key.to_string()).or_insert(0) += 1;
+    }
+    ReviewInfo {
+        review_file: path.to_string_lossy().to_string(),
+        branch: review["git"]["branch"].as_str().map(str::to_string),
+        commit_sha: review["git"]["commit_sha"].as_str().map(str::to_string),
+        models_succeeded,
+        models_failed,
+        findings: findings.len(),
+        severity_counts,
+        elapsed_ms: review["elapsed_ms"].as_u64(),
+        diff_size: review["diff_size"].as_str().map(str::to_string),
+    }
+}
+
+impl ReviewComparison {
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("## Review Comparison\n\n");
+        for (label, info) in [("A", &self.review_a), ("B", &self.review_b)] {
+            md.push_str(&format!("- **{label}:** `{}`", info.review_file));
+            match (&info.branch, &info.commit_sha) {
+                (Some(branch), Some(sha)) => md.push_str(&format!(" ({branch} @ {sha})")),
+                (None, Some(sha)) => md.push_str(&format!(" (@ {sha})")),
+                (Some(branch), None) => md.push_str(&format!(" ({branch})")),
+                (None, None) => {}
+            }
+            md.push_str(&format!(
+                " — {} finding(s), {}/{} model(s) succeeded\n",
+                info.findings,
+                info.models_succeeded.len(),
+                info.models_succeeded.len() + info.models_failed.len()
+            ));
+        }
+        md.push_str(&format!(
+            "\n**Delta:** {} new, {} resolved, {} persisting",
+            self.new.len(),
+            self.resolved.len(),
+            self.persisting.len()
+        ));
+        if !self.not_rechecked.is_empty() {
+            md.push_str(&format!(", {} not re-checked", self.not_rechecked.len()));
+        }
+        md.push('\n');
+
+        let severities: Vec<String> = ["critical", "high", "medium", "low", "info", "unrated"]
+            .into_iter()
+            .filter_map(|s| {
+                let a = self.review_a.severity_counts.get(s).copied().unwrap_or(0);
+                let b = self.review_b.severity_counts.get(s).copied().unwrap_or(0);
+                (a + b > 0).then(|| format!("{s} {a}→{b}"))
+            })
+            .collect();
+        if !severities.is_empty() {
+            md.push_str(&format!("**Severity:** {}\n", severities.join(", ")));
+        }
+
+        for (title, refs) in [
+            ("New in B", &self.new),
+            ("Resolved", &self.resolved),
+            ("Persisting", &self.persisting),
+            (
+                "Not re-checked (reporting models did not succeed in B)",
+                &self.not_rechecked,
+            ),
+        ] {
+            if refs.is_empty() {
+                continue;
+            }
+            md.push_str(&format!("\n### {title}\n"));
+            for r in refs {
+                let severity = r.severity.as_ref().map_or("unrated", |s| s.as_str());
+                md.push_str(&format!("- [{severity}] {}", r.summary));
+                if let Some(file) = &r.file_path {
+                    md.push_str(&format!(" ({file})"));
+                }
+                md.push_str(&format!(" — {}\n", r.models.join(", ")));
+            }
+        }
+
+        if !self.models.is_empty() {
+            md.push_str("\n### Per model\n| Model | Status | New | Resolved | Persisting |\n");
+            md.push_str("|-------|--------|-----|----------|------------|\n");
+            for m in &self.models {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    m.model,
+                    m.status,
+                    m.new.len(),
+                    m.resolved.len(),
+                    m.persisting.len()
+                ));
+            }
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(results: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "results": results, "elapsed_ms": 1000 })
+    }
+
+    #[test]
+    fn compare_classifies_new_resolved_persisting_and_unchecked() {
+        let a = review(serde_json::json!([
+            {"model": "grok", "status": "success", "response":
+                "### [high] Race in cache refresh\n- File: src/cache.rs:10\n\n\
+                 ### [low] Unused import in server\n"},
+            {"model": "kimi", "status": "success", "response":
+                "### [medium] Missing timeout on upstream call\n"},
+        ]));
+        let b = review(serde_json::json!([
+            {"model": "grok", "status": "success", "response":
+                "### [high] Race in cache refresh\n- File: src/cache.rs:12\n\n\
+                 ### [critical] Token leaked in error message\n"},
+            {"model": "kimi", "status": "error", "error": "timeout"},
+        ]));
+        let cmp = compare(Path::new("a.json"), &a, Path::new("b.json"), &b);
+
+        let summaries = |refs: &[FindingRef]| -> Vec<String> {
+            refs.iter().map(|r| r.summary.clone()).collect()
+        };
+        assert_eq!(summaries(&cmp.new), vec!["Token leaked in error message"]);
+        assert_eq!(summaries(&cmp.persisting), vec!["Race in cache refresh"]);
+        assert_eq!(summaries(&cmp.resolved), vec!["Unused import in server"]);
+        assert_eq!(
+            summaries(&cmp.not_rechecked),
+            vec!["Missing timeout on upstream call"]
+        );
+
+        let kimi = cmp.models.iter().find(|m| m.model == "kimi").unwrap();
+        assert_eq!(kimi.status, "failed_in_b");
+        let grok = cmp.models.iter().find(|m| m.model == "grok").unwrap();
+        assert_eq!(grok.status, "compared");
+        assert_eq!(grok.resolved, vec!["Unused import in server"]);
+
+        let md = cmp.to_markdown();
+        assert!(md.contains("1 new, 1 resolved, 1 persisting, 1 not re-checked"));
+        assert!(md.contains("critical 0→1"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Output-format compliance: when a review asks for a specific structure (JSON,
+//! a bullet list of findings, named sections), score how closely each response
+//! follows it. Scores run 0.0-1.0 and are logged to memory per model, so
+//! recommendations can tell instruction-followers from models that ignore the
+//! requested format.
+//!
+//! - `json`: 1.0 for a bare document, 0.9 inside a code fence, 0.5 when it has to
+//!   be dug out of surrounding prose, 0.3 when it only parses after repair
+//!   (trailing commas, unclosed brackets; see `crate::json_repair`), 0 when
+//!   nothing salvageable is found.
+//! - `bullets`: share of content lines (outside code fences, headings excluded)
+//!   that are list items.
+//! - `headings`: 1.0 if any heading exists, or the share of `sections` found.
+//!
+//! `sections` (required headings, or top-level JSON keys) scale the structural
+//! score by the share present.
+
+use crate::tools::enums::ExpectedFormat;
+
+/// Score `response` against the requested format, rounded to two decimals.
+pub fn score(response: &str, format: ExpectedFormat, sections: &[String]) -> f64 {
+    let raw = match format {
+        ExpectedFormat::Json => score_json(response, sections),
+        ExpectedFormat::Bullets => score_bullets(response) * heading_coverage(response, sections),
+        ExpectedFormat::Headings => {
+            if sections.is_empty() {
+                if headings(response).next().is_some() {
+                    1.0
+                } else {
+                    0.0
+                }
+            } else {
+                heading_coverage(response, sections)
+            }
+        }
+    };
+    (raw * 100.0).round() / 100.0
+}
+
+fn score_json(response: &str, keys: &[String]) -> f64 {
+    let trimmed = response.trim();
+    let (value, structure) = if let Ok(v) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        (v, 1.0)
+    } else if let Some(v) = unfence(trimmed).and_then(|s| serde_json::from_str(s).ok()) {
+        (v, 0.9)
+    } else if let Some(v) = embedded_json(trimmed) {
+        (v, 0.5)
+    } else if let Some(v) = crate::json_repair::repair(trimmed) {
+        (v, 0.3)
+    } else {
+        return 0.0;
+    };
+    if keys.is_empty() {
+        return structure;
+    }
+    // Arrays of findings: check keys on the first element.
+    let object = value
+        .as_object()
+        .or_else(|| value.as_array()?.first()?.as_object());
+    let Some(object) = object else {
+        return 0.0;
+    };
+    let present = keys
+        .iter()
+        .filter(|k| object.contains_key(k.as_str()))
+        .count();
+    structure * present as f64 / keys.len() as f64
+}
+
+/// The body of a response that is exactly one fenced code block.
+fn unfence(text: &str) -> Option<&str> {
+    let body = text.strip_prefix("```")?.strip_suffix("```")?;
+    let (_info, body) = body.split_once('\n')?;
+    Some(body)
+}
+
+/// First parseable `{...}` or `[...]` span in surrounding prose.
+fn embedded_json(text: &str) -> Option<serde_json::Value> {
+    let start = text.find(['{', '['])?;
+    let close = if text[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = text.rfind(close)?;
+    if end <= start {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+fn score_bullets(response: &str) -> f64 {
+    let mut items = 0usize;
+    let mut prose = 0usize;
+    let mut in_item = false;
+    for line in content_lines(response) {
+        if line.trim().is_empty() {
+            in_item = false;
+            continue;
+        }
+        if is_heading(line).is_some() {
+            in_item = false;
+            continue;
+        }
+        if is_list_item(line.trim_start()) {
+            items += 1;
+            in_item = true;
+        } else if in_item && line.starts_with(char::is_whitespace) {
+            // Wrapped continuation of the previous item.
+        } else {
+            prose += 1;
+            in_item = false;
+        }
+    }
+    if items == 0 {
+        return 0.0;
+    }
+    items as f64 / (items + prose) as f64
+}
+
+fn is_list_item(line: &str) -> bool {
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") "))
+}
+
+/// Share of `sections` that appear as a heading (case-insensitive substring).
+/// 1.0 when none are required.
+fn heading_coverage(response: &str, sections: &[String]) -> f64 {
+    if sections.is_empty() {
+        return 1.0;
+    }
+    let found: Vec<String> = headings(response).map(|h| h.to_lowercase()).collect();
+    let present = sections
+        .iter()
+        .filter(|s| {
+            let s = s.trim().to_lowercase();
+            found.iter().any(|h| h.contains(&s))
+        })
+        .count();
+    present as f64 / sections.len() as f64
+}
+
+fn headings(response: &str) -> impl Iterator<Item = &str> {
+    content_lines(response).filter_map(is_heading)
+}
+
+/// Heading text of a markdown heading (`## Findings`) or a bold-only line
+/// (`**Findings:**`), which models often use in place of one.
+fn is_heading(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let text = if line.starts_with('#') {
+        let level = line.bytes().take_while(|&b| b == b'#').count();
+        if level > 6 || !line[level..].starts_with(' ') {
+            return None;
+        }
+        &line[level..]
+    } else {
+        line.strip_prefix("**")?.strip_suffix("**")?
+    };
+    let text = text.trim().trim_end_matches(':').trim();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Lines outside fenced code blocks.
+fn content_lines(response: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+    response.lines().filter(move |line| {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            return false;
+        }
+        !in_fence
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(k: &[&str]) -> Vec<String> {
+        k.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn json_scores_by_how_cleanly_it_parses() {
+        let fmt = ExpectedFormat::Json;
+        assert_eq!(score(r#"{"findings": []}"#, fmt, &[]), 1.0);
+        assert_eq!(score("```json\n{\"findings\": []}\n```", fmt, &[]), 0.9);
+        assert_eq!(
+            score("Here you go:\n{\"findings\": []}\nHope it helps.", fmt, &[]),
+            0.5
+        );
+        assert_eq!(score("{\"findings\": [\"leak\",],}", fmt, &[]), 0.3);
+        assert_eq!(score("No issues found.", fmt, &[]), 0.0);
+        assert_eq!(
+            score(
+                r#"[{"severity": "high", "file": "a.rs"}]"#,
+                fmt,
+                &keys(&["severity", "file", "line", "summary"])
+            ),
+            0.5
+        );
+    }
+
+    #[test]
+    fn bullets_score_share_of_list_items() {
+        let fmt = ExpectedFormat::Bullets;
+        let clean = "## Findings\n- [high] leak in handler\n  continues here\n1. second\n";
+        assert_eq!(score(clean, fmt, &[]), 1.0);
+        let chatty = "I looked at the code.\n\n- one issue\n\nOverall it is fine.\n";
+        assert_eq!(score(chatty, fmt, &[]), 0.33);
+        assert_eq!(score("All good, nothing to report.", fmt, &[]), 0.0);
+    }
+
+    #[test]
+    fn headings_match_required_sections() {
+        let fmt = ExpectedFormat::Headings;
+        let text = "## Summary\ntext\n**Risks:**\n- x\n```\n# Tests\n```\n";
+        assert_eq!(score(text, fmt, &[]), 1.0);
+        assert_eq!(
+            score(text, fmt, &keys(&["summary", "risks", "tests"])),
+            0.67
+        );
+        assert_eq!(score("plain prose", fmt, &[]), 0.0);
+    }
+}
@@ -0,0 +1,199 @@
+//! Self-consistency: the same model sampled several times at a non-zero
+//! temperature, its findings aggregated by vote. For some reviews a few samples
+//! of one strong model beat adding weaker distinct models.
+//!
+//! `models: ["grok#3"]` (or `samples: 3` for every model) dispatches `grok` three
+//! times. Each sample is its own result, labelled `grok#1`..`grok#3`; findings
+//! a majority of the responding samples reported are listed apart from those
+//! only some reported.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::findings::{self, SeverityMap};
+use crate::tools::review::{ModelStatus, ReviewModelResult};
+
+/// Samples one model can be asked for.
+pub const MAX_SAMPLES: usize = 5;
+
+/// Temperature for samples without one of their own: identical greedy answers
+/// would make voting pointless.
+pub const SAMPLE_TEMPERATURE: f64 = 0.7;
+
+/// Split a requested name into model and sample count: "grok#3" → ("grok", 3),
+/// "grok" → ("grok", 1).
+pub fn parse(name: &str) -> Result<(&str, usize), String> {
+    let Some((model, count)) = name.rsplit_once('#') else {
+        return Ok((name, 1));
+    };
+    match count.parse::<usize>() {
+        Ok(n) if (1..=MAX_SAMPLES).contains(&n) && !model.is_empty() => Ok((model, n)),
+        _ => Err(format!(
+            "'{name}': sample count must be 1-{MAX_SAMPLES} (e.g. '{model}#3')"
+        )),
+    }
+}
+
+/// Result labels for `n` samples of `model`: "grok#1".."grok#n", or the model
+/// itself for a single sample.
+pub fn labels(model: &str, n: usize) -> Vec<String> {
+    if n <= 1 {
+        return vec![model.to_string()];
+    }
+    (1..=n).map(|i| format!("{model}#{i}")).collect()
+}
+
+/// The model a result label belongs to: "grok#2" → "grok"; other names as they are.
+pub fn base(label: &str) -> &str {
+    sample_of(label).map_or(label, |(model, _)| model)
+}
+
+/// Model and sample number of a sample label. None for plain model names.
+pub fn sample_of(label: &str) -> Option<(&str, usize)> {
+    let (model, n) = label.rsplit_once('#')?;
+    let n = n.parse().ok().filter(|n| *n > 0)?;
+    Some((model, n))
+}
+
+/// How one sampled model's samples voted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleVote {
+    pub model: String,
+    /// Samples dispatched.
+    pub samples: usize,
+    /// Samples that returned a complete response.
+    pub responded: usize,
+    /// Findings reported by more than half of the responding samples.
+    pub majority: Vec<String>,
+    /// The rest of the union: findings only some samples reported.
+    pub minority: Vec<String>,
+}
+
+/// Vote on the findings of every sampled model in `results`, in model order.
+pub fn vote(results: &[ReviewModelResult], severity_map: Option<&SeverityMap>) -> Vec<SampleVote> {
+    let mut groups: BTreeMap<&str, Vec<&ReviewModelResult>> = BTreeMap::new();
+    for result in results {
+        if let Some((model, _)) = sample_of(&result.model) {
+            groups.entry(model).or_default().push(result);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(model, samples)| {
+            let mut found = Vec::new();
+            let mut responded = 0;
+            for result in &samples {
+                let Some(text) = result.response.as_deref() else {
+                    continue;
+                };
+                if result.status != ModelStatus::Success || result.partial {
+                    continue;
+                }
+                responded += 1;
+                let aliases = severity_map
+                    .map(|m| m.for_model(model, &result.provider))
+                    .unwrap_or_default();
+                found.extend(findings::extract_findings_with(
+                    &result.model,
+                    text,
+                    &aliases,
+                ));
+            }
+            let (majority, minority) = findings::cluster_findings(&found)
+                .into_iter()
+                .partition(|cluster| cluster.models.len() * 2 > responded);
+            let describe = |clusters: Vec<findings::FindingCluster>| -> Vec<String> {
+                clusters
+                    .into_iter()
+                    .map(|c| {
+                        let severity = c
+                            .severity
+                            .map(|s| format!("[{}] ", s.as_str()))
+                            .unwrap_or_default();
+                        let votes = format!("{}/{responded}", c.models.len());
+                        match c.file_path {
+                            Some(file) => format!("{severity}{} ({file}; {votes})", c.summary),
+                            None => format!("{severity}{} ({votes})", c.summary),
+                        }
+                    })
+                    .collect()
+            };
+            SampleVote {
+                model: model.to_string(),
+                samples: samples.len(),
+                responded,
+                majority: describe(majority),
+                minority: describe(minority),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_notation_round_trips() {
+        assert_eq!(parse("grok#3").unwrap(), ("grok", 3));
+        assert_eq!(parse("gemini").unwrap(), ("gemini", 1));
+        assert!(parse("grok#0").is_err());
+        assert!(parse("grok#many").is_err());
+        assert!(parse(&format!("grok#{}", MAX_SAMPLES + 1)).is_err());
+
+        assert_eq!(labels("grok", 2), vec!["grok#1", "grok#2"]);
+        assert_eq!(labels("grok", 1), vec!["grok"]);
+        assert_eq!(base("grok#2"), "grok");
+        assert_eq!(base("grok"), "grok");
+        assert_eq!(sample_of("grok#2"), Some(("grok", 2)));
+    }
+
+    #[test]
+    fn findings_most_samples_report_form_the_majority() {
+        let sample = |label: &str, text: &str| ReviewModelResult {
+            model: label.to_string(),
+            provider: "xai".to_string(),
+            status: ModelStatus::Success,
+            response: Some(text.to_string()),
+            error: None,
+            reason: None,
+            latency_ms: 100,
+            partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
+        };
+        let leak = "### [high] Connection pool leaks on timeout\n";
+        let results = vec![
+            sample("grok#1", leak),
+            sample(
+                "grok#2",
+                &format!("{leak}### [low] Retry counter overflow wraps silently\n"),
+            ),
+            sample("grok#3", leak),
+            sample("gemini", leak),
+        ];
+        let votes = vote(&results, None);
+        assert_eq!(votes.len(), 1, "plain models are not voted on");
+        let grok = &votes[0];
+        assert_eq!((grok.samples, grok.responded), (3, 3));
+        assert_eq!(
+            grok.majority,
+            vec!["[high] Connection pool leaks on timeout (3/3)"]
+        );
+        assert_eq!(
+            grok.minority,
+            vec!["[low] Retry counter overflow wraps silently (1/3)"]
+        );
+    }
+}
@@ -1,16 +1,20 @@
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::{Component, Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant, SystemTime};
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
+use crate::documents;
 use crate::error::SquallError;
+use crate::injection;
 
 /// Format for file context injection into model prompts.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ContextFormat {
     /// Standard XML format: full file content wrapped in `<file>` tags.
@@ -23,7 +27,7 @@ pub enum ContextFormat {
 }
 
 /// Git repository context: branch and commit SHA.
-#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitContext {
     /// Short commit SHA (7 chars).
     pub commit_sha: Option<String>,
@@ -75,7 +79,7 @@ impl GitContextCache {
 
 /// Detect git context (branch + short SHA) from a working directory.
 /// Returns None if not a git repo or git is not available.
-async fn detect_git_context(working_directory: &Path) -> Option<GitContext> {
+pub async fn detect_git_context(working_directory: &Path) -> Option<GitContext> {
     // 5s timeout guards against git hangs (NFS mounts, broken hooks, etc.)
     let git_timeout = Duration::from_secs(5);
 
@@ -310,7 +314,7 @@ fn line_hash(line: &str) -> u8 {
 }
 
 /// Validate that a path is safe: relative, no `..` components.
-fn validate_path(path: &str) -> Result<(), SquallError> {
+pub(crate) fn validate_path(path: &str) -> Result<(), SquallError> {
     let p = Path::new(path);
     if p.is_absolute() {
         return Err(SquallError::FileContext(format!(
@@ -345,8 +349,186 @@ async fn validate_no_symlink_escape(
     Ok(canonical)
 }
 
+/// Directories a `working_directory` must resolve inside, after canonicalization.
+/// Built once at startup from `[security] allowed_roots`. The default is unrestricted.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedRoots {
+    /// None = unrestricted. Some(empty) = every configured root was missing → deny all.
+    roots: Option<Vec<PathBuf>>,
+}
+
+impl AllowedRoots {
+    /// Allow any directory.
+    pub fn unrestricted() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to `roots`. `~` and `~/...` expand to `$HOME`. Each root is canonicalized
+    /// so comparisons match `validate_working_directory()` output; roots that don't exist
+    /// are dropped with a warning (fail closed: they can't widen access).
+    pub fn new(roots: &[String]) -> Self {
+        let home = std::env::var("HOME").ok();
+        let resolved = roots
+            .iter()
+            .filter_map(|root| {
+                let expanded = match (root.strip_prefix('~'), home.as_deref()) {
+                    (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+                        PathBuf::from(format!("{home}{rest}"))
+                    }
+                    _ => PathBuf::from(root),
+                };
+                match std::fs::canonicalize(&expanded) {
+                    Ok(canonical) => Some(canonical),
+                    Err(e) => {
+                        tracing::warn!("allowed root {root} ignored: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self {
+            roots: Some(resolved),
+        }
+    }
+
+    /// True if no restriction applies.
+    pub fn is_unrestricted(&self) -> bool {
+        self.roots.is_none()
+    }
+
+    /// True if the canonical path is inside one of the roots.
+    pub fn contains(&self, canonical: &Path) -> bool {
+        match &self.roots {
+            None => true,
+            Some(roots) => roots.iter().any(|root| canonical.starts_with(root)),
+        }
+    }
+}
+
+/// Per-repo ignore file (gitignore syntax), read from the working directory root.
+pub const SQUALLIGNORE_FILE: &str = ".squallignore";
+
+/// Load `.squallignore` from `base_dir`. None if absent; a malformed file is
+/// logged and ignored rather than failing the request.
+pub fn load_squallignore(base_dir: &Path) -> Option<Gitignore> {
+    let path = base_dir.join(SQUALLIGNORE_FILE);
+    if !path.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(base_dir);
+    if let Some(e) = builder.add(&path) {
+        tracing::warn!("{SQUALLIGNORE_FILE}: {e}");
+    }
+    match builder.build() {
+        Ok(ig) if !ig.is_empty() => Some(ig),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("{SQUALLIGNORE_FILE}: {e}");
+            None
+        }
+    }
+}
+
+/// `path` without a leading `./`, if it is a non-empty relative path that stays
+/// inside the tree: not rooted (`/x`, `\x`) and without `..` components.
+/// Paths from diff headers are caller input; gitignore matching panics on
+/// rooted ones.
+pub fn tree_relative_path(path: &str) -> Option<&str> {
+    let path = path.trim_start_matches("./");
+    let rooted = path.starts_with(['/', '\\']) || Path::new(path).has_root();
+    let escapes = path.split(['/', '\\']).any(|c| c == "..");
+    (!path.is_empty() && !rooted && !escapes).then_some(path)
+}
+
+/// True if `rel_path` (or any parent directory) matches the ignore rules.
+/// Paths that are not tree-relative (see `tree_relative_path`) count as
+/// ignored, so they never reach a model.
+pub fn is_squallignored(ignore: &Gitignore, rel_path: &str) -> bool {
+    if rel_path.trim_start_matches("./").is_empty() {
+        return false;
+    }
+    match tree_relative_path(rel_path) {
+        Some(path) => ignore.matched_path_or_any_parents(path, false).is_ignore(),
+        None => true,
+    }
+}
+
+/// Packages (monorepo subtrees) one request can target.
+pub const MAX_PACKAGES: usize = 32;
+
+/// Normalize `packages` to relative directory prefixes without `./` or a
+/// trailing `/`. Rejects absolute paths, `..`, and empty entries.
+pub fn validate_packages(packages: &[String]) -> Result<Vec<String>, String> {
+    if packages.len() > MAX_PACKAGES {
+        return Err(format!(
+            "too many packages: {} (max {MAX_PACKAGES})",
+            packages.len()
+        ));
+    }
+    let mut normalized = Vec::with_capacity(packages.len());
+    for package in packages {
+        let trimmed = package
+            .trim()
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        if trimmed.is_empty()
+            || trimmed.starts_with('/')
+            || trimmed.split('/').any(|c| c == ".." || c.is_empty())
+        {
+            return Err(format!(
+                "invalid package '{package}': expected a relative directory like crates/foo"
+            ));
+        }
+        if !normalized.iter().any(|p: &String| p == trimmed) {
+            normalized.push(trimmed.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Whether `path` lies under one of `packages` (as normalized by `validate_packages`).
+pub fn in_packages(path: &str, packages: &[String]) -> bool {
+    let path = path.trim_start_matches("./");
+    packages.iter().any(|package| {
+        path.strip_prefix(package.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// Drop per-file sections of a git diff whose path is ignored.
+/// Returns the filtered diff and the dropped paths.
+pub fn filter_ignored_diff(diff: &str, ignore: &Gitignore) -> (String, Vec<String>) {
+    filter_diff_sections(diff, |path| !is_squallignored(ignore, path))
+}
+
+/// Keep only the per-file sections of a git diff whose path satisfies `keep`.
+/// Sections start at `diff --git a/... b/...`; text before the first header is kept.
+/// Returns the filtered diff and the dropped paths.
+pub fn filter_diff_sections(diff: &str, keep: impl Fn(&str) -> bool) -> (String, Vec<String>) {
+    let mut kept = String::with_capacity(diff.len());
+    let mut dropped = Vec::new();
+    let mut skipping = false;
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map(|(_, b)| b)
+                .unwrap_or_default();
+            skipping = !keep(path);
+            if skipping {
+                dropped.push(path.to_string());
+            }
+        }
+        if !skipping {
+            kept.push_str(line);
+        }
+    }
+    (kept, dropped)
+}
+
 /// Result of resolving file context, with structured skip/error metadata.
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct FileContextResult {
     /// The XML-formatted file context string (None if no files included).
     pub context: Option<String>,
@@ -354,8 +536,20 @@ pub struct FileContextResult {
     pub skipped: Vec<(String, usize)>,
     /// Files that had read errors (non-fatal).
     pub errors: Vec<String>,
+    /// Files excluded by `.squallignore`.
+    pub ignored: Vec<String>,
+    /// Files included in `context`, in request order (trimmed ones too).
+    pub included: Vec<String>,
+    /// Files cut to fit the budget (only with `resolve_file_context_trimmed`).
+    pub trimmed: Vec<String>,
+    /// Suspected prompt injections in included files, as `path: line N: reason`
+    /// (see `crate::injection`).
+    pub injection_suspected: Vec<String>,
 }
 
+/// Smallest useful slice of a trimmed file. Below this the file is skipped instead.
+const MIN_TRIMMED_BYTES: usize = 1024;
+
 /// Read files and format as context for model prompts. All paths must be relative to `base_dir`.
 /// Path traversal attempts reject the entire request.
 /// Non-existent or unreadable files are noted but non-fatal (unless ALL fail).
@@ -368,12 +562,53 @@ pub async fn resolve_file_context(
     base_dir: &Path,
     budget: usize,
     format: ContextFormat,
+) -> Result<FileContextResult, SquallError> {
+    resolve_file_context_inner(paths, base_dir, budget, format, false, None).await
+}
+
+/// Like `resolve_file_context`, but a file that doesn't fit the remaining budget is cut
+/// at a line boundary (marked `truncated="true"`) instead of skipped. Used for models
+/// with a smaller per-model context budget than `MAX_FILE_CONTEXT_BYTES`.
+pub async fn resolve_file_context_trimmed(
+    paths: &[String],
+    base_dir: &Path,
+    budget: usize,
+    format: ContextFormat,
+) -> Result<FileContextResult, SquallError> {
+    resolve_file_context_inner(paths, base_dir, budget, format, true, None).await
+}
+
+/// `resolve_file_context` (or `_trimmed`) for one repository of a multi-repo
+/// workspace: paths are relative to `base_dir`, but every name in the prompt and
+/// the result is shown as `{repo}/{path}`.
+pub async fn resolve_repo_file_context(
+    paths: &[String],
+    base_dir: &Path,
+    repo: &str,
+    budget: usize,
+    format: ContextFormat,
+    trim: bool,
+) -> Result<FileContextResult, SquallError> {
+    resolve_file_context_inner(paths, base_dir, budget, format, trim, Some(repo)).await
+}
+
+async fn resolve_file_context_inner(
+    paths: &[String],
+    base_dir: &Path,
+    budget: usize,
+    format: ContextFormat,
+    trim: bool,
+    repo: Option<&str>,
 ) -> Result<FileContextResult, SquallError> {
     if paths.is_empty() {
         return Ok(FileContextResult {
             context: None,
             skipped: vec![],
             errors: vec![],
+            ignored: vec![],
+            included: vec![],
+            trimmed: vec![],
+            injection_suspected: vec![],
         });
     }
 
@@ -388,6 +623,7 @@ pub async fn resolve_file_context(
     // Validate all paths first — traversal = reject entire request
     for p in paths {
         validate_path(p)?;
+        documents::split_page_selector(p).map_err(SquallError::FileContext)?;
     }
 
     // Canonicalize base_dir for symlink checks (e.g., /tmp → /private/tmp on macOS).
@@ -399,12 +635,32 @@ pub async fn resolve_file_context(
 
     let mut output = String::new();
     let mut used = 0usize;
-    let mut included = 0usize;
+    let mut included: Vec<String> = Vec::new();
+    let mut trimmed: Vec<String> = Vec::new();
     let mut skipped: Vec<(String, usize)> = Vec::new();
     let mut errors: Vec<String> = Vec::new();
+    let mut ignored: Vec<String> = Vec::new();
+    let mut injection_suspected: Vec<String> = Vec::new();
+    let squallignore = load_squallignore(base_dir);
+
+    for path in paths {
+        // `docs/spec.pdf#pages=3-7` reads docs/spec.pdf; validated above.
+        let (file_path, pages) =
+            documents::split_page_selector(path).map_err(SquallError::FileContext)?;
+        // The name shown to models and in the result.
+        let rel_path = &match repo {
+            Some(repo) => format!("{repo}/{path}"),
+            None => path.clone(),
+        };
+        let document = documents::DocumentKind::from_path(file_path);
+        if let Some(ref ig) = squallignore
+            && is_squallignored(ig, file_path)
+        {
+            ignored.push(rel_path.clone());
+            continue;
+        }
 
-    for rel_path in paths {
-        let full_path = base_dir.join(rel_path);
+        let full_path = base_dir.join(file_path);
 
         // Canonicalize and verify the path stays within base_dir.
         // Prevents symlink traversal attacks. Non-existent files fail
@@ -421,55 +677,105 @@ pub async fn resolve_file_context(
         // Check file size via metadata BEFORE reading — prevents OOM on large files.
         // If raw file size alone exceeds remaining budget, the escaped+wrapped version
         // will certainly exceed it too, so we can skip without reading.
-        let file_size = match tokio::fs::metadata(&canonical).await {
-            Ok(m) => m.len() as usize,
-            Err(e) => {
-                errors.push(format!("{rel_path}: {e}"));
+        let meta = match tokio::fs::metadata(&canonical).await {
+            // Directories, FIFOs, sockets and devices are refused: reading a FIFO blocks
+            // forever and device nodes are never source code.
+            Ok(m) if !m.is_file() => {
+                errors.push(format!("{rel_path}: not a regular file"));
                 continue;
             }
-        };
-
-        if file_size > budget.saturating_sub(used) {
-            skipped.push((rel_path.clone(), file_size));
-            continue;
-        }
-
-        let content = match tokio::fs::read_to_string(&canonical).await {
-            Ok(c) => c,
+            Ok(m) => m,
             Err(e) => {
                 errors.push(format!("{rel_path}: {e}"));
                 continue;
             }
         };
-
-        let formatted = match format {
-            ContextFormat::Xml => escape_xml_content(&content),
-            ContextFormat::Hashline => format_hashline(&content),
-        };
-        let entry = format!(
-            "<file path=\"{}\">\n{}</file>\n",
-            escape_xml_attr(rel_path),
-            // Hashline already ends with \n per line; Xml needs trailing \n
-            if format == ContextFormat::Xml {
-                format!("{formatted}\n")
+        let file_size = meta.len() as usize;
+
+        let remaining = budget.saturating_sub(used);
+        // Documents are budgeted by their extracted text, not their size on disk.
+        let (mut content, mut truncated) = if let Some(kind) = document {
+            if file_size as u64 > documents::MAX_DOCUMENT_BYTES {
+                errors.push(format!(
+                    "{rel_path}: document too large ({file_size}B, max {}B)",
+                    documents::MAX_DOCUMENT_BYTES
+                ));
+                continue;
+            }
+            let text = match documents::extract_text(&canonical, kind, pages.as_ref()).await {
+                Ok(t) => t,
+                Err(e) => {
+                    errors.push(format!("{rel_path}: {e}"));
+                    continue;
+                }
+            };
+            if text.len() <= remaining {
+                (text, false)
+            } else if trim && remaining >= MIN_TRIMMED_BYTES {
+                let cut = floor_char_boundary(&text, remaining);
+                let cut = text[..cut].rfind('\n').map_or(cut, |i| i + 1);
+                (text[..cut].to_string(), true)
             } else {
-                formatted
+                skipped.push((rel_path.clone(), text.len()));
+                continue;
             }
-        );
+        } else {
+            let read_limit = if file_size <= remaining {
+                None
+            } else if trim && remaining >= MIN_TRIMMED_BYTES {
+                Some(remaining)
+            } else {
+                skipped.push((rel_path.clone(), file_size));
+                continue;
+            };
+            match read_file_cached(&canonical, &meta, read_limit).await {
+                Ok(c) => (c, read_limit.is_some()),
+                Err(e) => {
+                    errors.push(format!("{rel_path}: {e}"));
+                    continue;
+                }
+            }
+        };
 
-        // Post-read check: escaped content may be larger than raw (XML entities)
-        if used + entry.len() > budget {
-            skipped.push((rel_path.clone(), content.len()));
+        // Post-read check: escaped content may be larger than raw (XML entities).
+        // In trim mode, shrink by the overshoot until the entry fits.
+        let entry = loop {
+            let entry = render_file_entry(rel_path, &content, format, truncated);
+            if used + entry.len() <= budget {
+                break Some(entry);
+            }
+            if !trim {
+                break None;
+            }
+            let target = content.len().saturating_sub(used + entry.len() - budget);
+            let cut = floor_char_boundary(&content, target);
+            let cut = content[..cut].rfind('\n').map_or(cut, |i| i + 1);
+            if cut < MIN_TRIMMED_BYTES {
+                break None;
+            }
+            content.truncate(cut);
+            truncated = true;
+        };
+        let Some(entry) = entry else {
+            skipped.push((rel_path.clone(), file_size));
             continue;
-        }
+        };
 
         output.push_str(&entry);
         used += entry.len();
-        included += 1;
+        included.push(rel_path.clone());
+        injection_suspected.extend(
+            injection::scan(&content)
+                .into_iter()
+                .map(|hit| format!("{rel_path}: {hit}")),
+        );
+        if truncated {
+            trimmed.push(rel_path.clone());
+        }
     }
 
     // All files had read errors (none skipped for budget) → hard error
-    if included == 0 && skipped.is_empty() && !errors.is_empty() {
+    if included.is_empty() && skipped.is_empty() && !errors.is_empty() {
         return Err(SquallError::FileContext(format!(
             "all files unreadable: {}",
             errors.join("; ")
@@ -478,8 +784,11 @@ pub async fn resolve_file_context(
 
     // Append manifest comment noting skipped/errored files.
     // Escape "--" sequences to prevent XML comment injection from filenames.
-    if !skipped.is_empty() || !errors.is_empty() {
+    if !skipped.is_empty() || !errors.is_empty() || !trimmed.is_empty() {
         let mut comment = String::new();
+        if !trimmed.is_empty() {
+            comment.push_str(&format!("Trimmed to fit: {}. ", trimmed.join(", ")));
+        }
         if !skipped.is_empty() {
             let names: Vec<_> = skipped
                 .iter()
@@ -501,9 +810,164 @@ pub async fn resolve_file_context(
         },
         skipped,
         errors,
+        ignored,
+        included,
+        trimmed,
+        injection_suspected,
+    })
+}
+
+/// Total file content kept by `FILE_CACHE`; least recently used files go first.
+const MAX_FILE_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Contents of files read in full for context, so sequential reviews and
+/// follow-up tools in one session don't re-read the same large files. An entry
+/// is valid while the file keeps the modification time and size it was read
+/// with; budget-trimmed reads are served from it as a prefix.
+static FILE_CACHE: LazyLock<FileCache> = LazyLock::new(FileCache::default);
+
+#[derive(Default)]
+struct FileCache {
+    inner: std::sync::Mutex<FileCacheInner>,
+}
+
+#[derive(Default)]
+struct FileCacheInner {
+    files: HashMap<PathBuf, CachedFile>,
+    bytes: usize,
+}
+
+struct CachedFile {
+    modified: SystemTime,
+    len: u64,
+    content: Arc<str>,
+    last_used: Instant,
+}
+
+impl FileCache {
+    fn get(&self, path: &Path, meta: &std::fs::Metadata) -> Option<Arc<str>> {
+        let modified = meta.modified().ok()?;
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let file = inner.files.get_mut(path)?;
+        if file.modified != modified || file.len != meta.len() {
+            return None;
+        }
+        file.last_used = Instant::now();
+        Some(file.content.clone())
+    }
+
+    fn insert(&self, path: &Path, meta: &std::fs::Metadata, content: Arc<str>) {
+        let Ok(modified) = meta.modified() else {
+            return;
+        };
+        if content.len() > MAX_FILE_CACHE_BYTES {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = CachedFile {
+            modified,
+            len: meta.len(),
+            content,
+            last_used: Instant::now(),
+        };
+        inner.bytes += entry.content.len();
+        if let Some(old) = inner.files.insert(path.to_path_buf(), entry) {
+            inner.bytes -= old.content.len();
+        }
+        while inner.bytes > MAX_FILE_CACHE_BYTES {
+            let Some(oldest) = inner
+                .files
+                .iter()
+                .min_by_key(|(_, file)| file.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            if let Some(old) = inner.files.remove(&oldest) {
+                inner.bytes -= old.content.len();
+            }
+        }
+    }
+}
+
+/// `read_file_prefix` through `FILE_CACHE`. Full reads are cached; a limited read
+/// is cut from a cached copy when there is one, and read from disk otherwise.
+async fn read_file_cached(
+    path: &Path,
+    meta: &std::fs::Metadata,
+    limit: Option<usize>,
+) -> std::io::Result<String> {
+    if let Some(content) = FILE_CACHE.get(path, meta) {
+        return Ok(match limit {
+            None => content.to_string(),
+            Some(limit) => line_prefix(&content, limit).to_string(),
+        });
+    }
+    let content = read_file_prefix(path, limit).await?;
+    if limit.is_none() {
+        FILE_CACHE.insert(path, meta, Arc::from(content.as_str()));
+    }
+    Ok(content)
+}
+
+/// At most `limit` bytes of `text`, cut back to the last complete line (as
+/// `read_file_prefix` does on disk).
+fn line_prefix(text: &str, limit: usize) -> &str {
+    let cut = floor_char_boundary(text, limit);
+    match text[..cut].rfind('\n') {
+        Some(i) => &text[..=i],
+        None => &text[..cut],
+    }
+}
+
+/// Read a file as UTF-8. With `limit`, read at most that many bytes and cut back to
+/// the last complete line, so a huge file is never loaded just to be trimmed.
+async fn read_file_prefix(path: &Path, limit: Option<usize>) -> std::io::Result<String> {
+    let Some(limit) = limit else {
+        return tokio::fs::read_to_string(path).await;
+    };
+    use tokio::io::AsyncReadExt;
+    let mut buf = Vec::with_capacity(limit);
+    tokio::fs::File::open(path)
+        .await?
+        .take(limit as u64)
+        .read_to_end(&mut buf)
+        .await?;
+    // Cut mid-character at the limit: keep the valid prefix.
+    if let Err(e) = std::str::from_utf8(&buf)
+        && e.error_len().is_none()
+    {
+        buf.truncate(e.valid_up_to());
+    }
+    if let Some(i) = buf.iter().rposition(|&b| b == b'\n') {
+        buf.truncate(i + 1);
+    }
+    String::from_utf8(buf).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
     })
 }
 
+/// Render one `<file>` entry. Hashline output already ends each line with `\n`.
+fn render_file_entry(
+    rel_path: &str,
+    content: &str,
+    format: ContextFormat,
+    truncated: bool,
+) -> String {
+    let formatted = match format {
+        ContextFormat::Xml => format!("{}\n", escape_xml_content(content)),
+        ContextFormat::Hashline => format_hashline(content),
+    };
+    let truncated_attr = if truncated { " truncated=\"true\"" } else { "" };
+    format!(
+        "<file path=\"{}\"{truncated_attr}>\n{formatted}</file>\n",
+        escape_xml_attr(rel_path)
+    )
+}
+
 /// Lightweight manifest for CLI backends (paths only, no content).
 /// CLI agents can read files themselves via `working_directory` as cwd.
 pub async fn resolve_file_manifest(
@@ -530,14 +994,28 @@ pub async fn resolve_file_manifest(
         .await
         .map_err(|e| SquallError::FileContext(format!("cannot resolve base directory: {e}")))?;
 
+    let squallignore = load_squallignore(base_dir);
     let mut lines = Vec::new();
     for rel_path in paths {
+        if let Some(ref ig) = squallignore
+            && is_squallignored(ig, rel_path)
+        {
+            continue;
+        }
+
         let full_path = base_dir.join(rel_path);
 
         // Canonicalize to catch symlink escapes
         match validate_no_symlink_escape(&full_path, base_dir, rel_path).await {
-            Ok(_) => {
-                lines.push(format!("- {rel_path} (exists)"));
+            Ok(canonical) => {
+                let regular = tokio::fs::metadata(&canonical)
+                    .await
+                    .is_ok_and(|m| m.is_file());
+                if regular {
+                    lines.push(format!("- {rel_path} (exists)"));
+                } else {
+                    lines.push(format!("- {rel_path} (not a regular file)"));
+                }
             }
             Err(e @ SquallError::SymlinkEscape(_)) => return Err(e),
             Err(_) => {
@@ -546,10 +1024,31 @@ pub async fn resolve_file_manifest(
         }
     }
 
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
     let manifest = format!("Files referenced:\n{}", lines.join("\n"));
     Ok(Some(manifest))
 }
 
+/// Summarize a unified diff as `"+added -removed"` line counts.
+/// File headers (`+++`/`---`) are not counted. Returns None for an empty diff.
+pub fn diff_stat(diff: &str) -> Option<String> {
+    if diff.trim().is_empty() {
+        return None;
+    }
+    let (mut added, mut removed) = (0usize, 0usize);
+    for line in diff.lines() {
+        if line.starts_with('+') && !line.starts_with("+++") {
+            added += 1;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            removed += 1;
+        }
+    }
+    Some(format!("+{added} -{removed}"))
+}
+
 /// Wrap diff text in XML tags for model prompt injection.
 /// XML-escapes content to prevent prompt framing breaks (e.g. diff editing XML files
 /// could contain `</diff>`). Budget is enforced on the **escaped** output to prevent
@@ -624,7 +1123,7 @@ fn floor_entity_boundary(s: &str, index: usize) -> usize {
 
 /// Find the largest byte index ≤ `index` that is a valid UTF-8 char boundary.
 /// Equivalent to `str::floor_char_boundary` (nightly-only as of Rust 1.xx).
-fn floor_char_boundary(s: &str, index: usize) -> usize {
+pub(crate) fn floor_char_boundary(s: &str, index: usize) -> usize {
     if index >= s.len() {
         return s.len();
     }
@@ -645,6 +1144,16 @@ pub fn validate_temperature(temp: Option<f64>) -> Result<(), String> {
     Ok(())
 }
 
+/// Validate `extra_body` is a JSON object (it is merged key by key into the payload).
+pub fn validate_extra_body(extra: Option<&serde_json::Value>) -> Result<(), String> {
+    if let Some(v) = extra
+        && !v.is_object()
+    {
+        return Err("extra_body must be a JSON object".to_string());
+    }
+    Ok(())
+}
+
 /// Validate prompt is non-empty.
 pub fn validate_prompt(prompt: &str) -> Result<(), String> {
     if prompt.trim().is_empty() {
@@ -672,6 +1181,21 @@ pub async fn validate_working_directory(path: &str) -> Result<PathBuf, SquallErr
     Ok(canonical)
 }
 
+/// Validate a working directory and require it to resolve inside `roots`.
+/// The check runs on the canonical path, so symlinks and `..` can't step outside.
+pub async fn validate_working_directory_in(
+    path: &str,
+    roots: &AllowedRoots,
+) -> Result<PathBuf, SquallError> {
+    let canonical = validate_working_directory(path).await?;
+    if !roots.contains(&canonical) {
+        return Err(SquallError::FileContext(format!(
+            "working directory outside allowed roots: {path}"
+        )));
+    }
+    Ok(canonical)
+}
+
 #[cfg(test)]
 #[cfg(feature = "global-memory")]
 mod project_id_tests {
@@ -781,3 +1305,41 @@ mod project_id_tests {
         assert_eq!(id1, id2, "Same directory should produce same project ID");
     }
 }
+
+#[cfg(test)]
+mod file_cache_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cached_contents_follow_file_changes() {
+        let dir = std::env::temp_dir().join("squall-test-file-cache");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt");
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        let canonical = std::fs::canonicalize(&path).unwrap();
+        let paths = vec!["a.txt".to_string()];
+
+        resolve_file_context(&paths, &dir, 4096, ContextFormat::Xml)
+            .await
+            .unwrap();
+        let meta = std::fs::metadata(&canonical).unwrap();
+        assert_eq!(
+            FILE_CACHE.get(&canonical, &meta).as_deref(),
+            Some("one\ntwo\n")
+        );
+        // Trimmed reads are cut from the cached copy at a line boundary.
+        let prefix = read_file_cached(&canonical, &meta, Some(6)).await.unwrap();
+        assert_eq!(prefix, "one\n");
+
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let meta = std::fs::metadata(&canonical).unwrap();
+        assert!(FILE_CACHE.get(&canonical, &meta).is_none());
+        let result = resolve_file_context(&paths, &dir, 4096, ContextFormat::Xml)
+            .await
+            .unwrap();
+        assert!(result.context.unwrap().contains("three"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
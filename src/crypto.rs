@@ -0,0 +1,281 @@
+//! Optional encryption at rest for `.squall/memory/` and persisted review artifacts.
+//!
+//! Prompts and findings can contain proprietary code excerpts. When a 256-bit key is
+//! configured, every file Squall writes under `.squall/` is sealed with AES-256-GCM:
+//!
+//! - `SQUALL_ENCRYPTION_KEY` — the key as 64 hex characters, or
+//! - `SQUALL_ENCRYPTION_KEY_CMD` — a shell command that prints it (e.g. a keychain lookup:
+//!   `security find-generic-password -s squall -w`).
+//!
+//! Sealed files stay text: `squall-enc:v1:` followed by hex(nonce ‖ ciphertext ‖ tag).
+//! Binary files (compressed reviews) are sealed as `squall-enc:v1b:` followed by the
+//! raw nonce ‖ ciphertext ‖ tag. Plaintext files are still readable, so turning
+//! encryption on migrates files as they are rewritten. With no key configured,
+//! `seal`/`open` are pass-through.
+
+use std::io;
+use std::sync::OnceLock;
+
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Env var holding the hex-encoded 256-bit key.
+pub const KEY_ENV: &str = "SQUALL_ENCRYPTION_KEY";
+
+/// Env var holding a shell command that prints the hex-encoded key.
+pub const KEY_CMD_ENV: &str = "SQUALL_ENCRYPTION_KEY_CMD";
+
+/// Marker prefix identifying sealed file contents.
+const SEALED_PREFIX: &str = "squall-enc:v1:";
+
+/// Marker prefix identifying sealed binary contents.
+const SEALED_BYTES_PREFIX: &[u8] = b"squall-enc:v1b:";
+
+/// Key length for AES-256.
+const KEY_LEN: usize = 32;
+
+/// Resolved once per process. `Err` means a key was configured but unusable —
+/// writes fail closed rather than falling back to plaintext.
+static KEY: OnceLock<Result<Option<[u8; KEY_LEN]>, String>> = OnceLock::new();
+
+/// True if encryption at rest is configured with a valid key.
+pub fn is_enabled() -> bool {
+    matches!(key(), Ok(Some(_)))
+}
+
+/// True if `contents` is a sealed payload.
+pub fn is_sealed(contents: &str) -> bool {
+    contents.starts_with(SEALED_PREFIX)
+}
+
+/// Seal `plaintext` for disk if a key is configured; otherwise return it unchanged.
+pub fn seal(plaintext: &str) -> io::Result<String> {
+    match key()? {
+        Some(key) => seal_with(&key, plaintext),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// Open file contents read from disk. Plaintext passes through unchanged.
+pub fn open(contents: String) -> io::Result<String> {
+    if !is_sealed(&contents) {
+        return Ok(contents);
+    }
+    match key()? {
+        Some(key) => open_with(&key, &contents),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("file is encrypted but no key is configured (set {KEY_ENV} or {KEY_CMD_ENV})"),
+        )),
+    }
+}
+
+/// True if `contents` is a sealed binary payload.
+pub fn is_sealed_bytes(contents: &[u8]) -> bool {
+    contents.starts_with(SEALED_BYTES_PREFIX)
+}
+
+/// Seal binary `data` (e.g. compressed bytes) if a key is configured; otherwise
+/// return it unchanged.
+pub fn seal_bytes(data: &[u8]) -> io::Result<Vec<u8>> {
+    match key()? {
+        Some(key) => seal_bytes_with(&key, data),
+        None => Ok(data.to_vec()),
+    }
+}
+
+/// Open binary contents read from disk. Unsealed bytes pass through unchanged.
+pub fn open_bytes(contents: Vec<u8>) -> io::Result<Vec<u8>> {
+    if !is_sealed_bytes(&contents) {
+        return Ok(contents);
+    }
+    match key()? {
+        Some(key) => open_bytes_with(&key, &contents),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("file is encrypted but no key is configured (set {KEY_ENV} or {KEY_CMD_ENV})"),
+        )),
+    }
+}
+
+fn key() -> io::Result<Option<[u8; KEY_LEN]>> {
+    KEY.get_or_init(load_key)
+        .clone()
+        .map_err(|e| io::Error::other(format!("encryption at rest: {e}")))
+}
+
+fn load_key() -> Result<Option<[u8; KEY_LEN]>, String> {
+    let hex_key = if let Ok(k) = std::env::var(KEY_ENV)
+        && !k.trim().is_empty()
+    {
+        k
+    } else if let Ok(cmd) = std::env::var(KEY_CMD_ENV)
+        && !cmd.trim().is_empty()
+    {
+        // Runs once per process, on the first read or write of a memory file, with
+        // the same timeout and closed stdin as API key commands.
+        crate::keys::KeySource::Command(cmd)
+            .fetch()
+            .map_err(|e| format!("{KEY_CMD_ENV}: {e}"))?
+    } else {
+        return Ok(None);
+    };
+    let key = parse_key(&hex_key)?;
+    tracing::info!("encryption at rest: enabled for .squall/ artifacts");
+    Ok(Some(key))
+}
+
+/// Parse a hex-encoded 256-bit key.
+pub fn parse_key(hex_key: &str) -> Result<[u8; KEY_LEN], String> {
+    let bytes = hex::decode(hex_key.trim()).map_err(|e| format!("key is not valid hex: {e}"))?;
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| format!("key must be {KEY_LEN} bytes, got {}", b.len()))
+}
+
+/// Seal `plaintext` with an explicit key. A fresh random nonce is used per call.
+pub fn seal_with(key: &[u8; KEY_LEN], plaintext: &str) -> io::Result<String> {
+    let payload = seal_payload(key, plaintext.as_bytes())?;
+    Ok(format!("{SEALED_PREFIX}{}\n", hex::encode(payload)))
+}
+
+/// Open sealed contents with an explicit key. Fails on tampering or a wrong key.
+pub fn open_with(key: &[u8; KEY_LEN], contents: &str) -> io::Result<String> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    let hex_payload = contents
+        .strip_prefix(SEALED_PREFIX)
+        .ok_or_else(|| invalid("not a sealed payload"))?;
+    let payload =
+        hex::decode(hex_payload.trim()).map_err(|_| invalid("sealed payload is not hex"))?;
+    let plaintext = open_payload(key, payload)?;
+    String::from_utf8(plaintext).map_err(|_| invalid("decrypted payload is not UTF-8"))
+}
+
+/// Seal binary `data` with an explicit key.
+pub fn seal_bytes_with(key: &[u8; KEY_LEN], data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut sealed = SEALED_BYTES_PREFIX.to_vec();
+    sealed.extend(seal_payload(key, data)?);
+    Ok(sealed)
+}
+
+/// Open sealed binary contents with an explicit key.
+pub fn open_bytes_with(key: &[u8; KEY_LEN], contents: &[u8]) -> io::Result<Vec<u8>> {
+    let payload = contents
+        .strip_prefix(SEALED_BYTES_PREFIX)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a sealed binary payload"))?;
+    open_payload(key, payload.to_vec())
+}
+
+/// nonce ‖ ciphertext ‖ tag for `data`, with a fresh random nonce.
+fn seal_payload(key: &[u8; KEY_LEN], data: &[u8]) -> io::Result<Vec<u8>> {
+    let sealing_key = aead_key(key)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| io::Error::other("encryption at rest: no system randomness"))?;
+    let mut in_out = data.to_vec();
+    sealing_key
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| io::Error::other("encryption at rest: seal failed"))?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&in_out);
+    Ok(payload)
+}
+
+/// The data inside a `seal_payload` result.
+fn open_payload(key: &[u8; KEY_LEN], mut payload: Vec<u8>) -> io::Result<Vec<u8>> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    if payload.len() < NONCE_LEN {
+        return Err(invalid("sealed payload is truncated"));
+    }
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&payload[..NONCE_LEN]);
+    let opening_key = aead_key(key)?;
+    let plaintext = opening_key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut payload[NONCE_LEN..],
+        )
+        .map_err(|_| invalid("decryption failed (wrong key or tampered file)"))?;
+    Ok(plaintext.to_vec())
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> io::Result<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| io::Error::other("encryption at rest: invalid key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_HEX: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn seal_open_roundtrip() {
+        let key = parse_key(KEY_HEX).unwrap();
+        let sealed = seal_with(&key, "# Recurring Patterns\n\nsecret code").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("secret"));
+        assert_eq!(
+            open_with(&key, &sealed).unwrap(),
+            "# Recurring Patterns\n\nsecret code"
+        );
+    }
+
+    #[test]
+    fn nonce_is_fresh_per_seal() {
+        let key = parse_key(KEY_HEX).unwrap();
+        assert_ne!(
+            seal_with(&key, "same").unwrap(),
+            seal_with(&key, "same").unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_key_or_tamper_is_rejected() {
+        let key = parse_key(KEY_HEX).unwrap();
+        let sealed = seal_with(&key, "payload").unwrap();
+
+        let mut other = key;
+        other[0] ^= 1;
+        assert!(open_with(&other, &sealed).is_err());
+
+        let mut tampered = sealed.trim_end().to_string();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == '0' { '1' } else { '0' });
+        assert!(open_with(&key, &tampered).is_err());
+
+        assert!(open_with(&key, "squall-enc:v1:abcd").is_err());
+    }
+
+    #[test]
+    fn binary_seal_roundtrip() {
+        let key = parse_key(KEY_HEX).unwrap();
+        let data = [0x28, 0xb5, 0x2f, 0xfd, 0x00, 0xff];
+        let sealed = seal_bytes_with(&key, &data).unwrap();
+        assert!(is_sealed_bytes(&sealed) && !is_sealed(&String::from_utf8_lossy(&sealed)));
+        assert_eq!(open_bytes_with(&key, &sealed).unwrap(), data);
+        assert_eq!(open_bytes(data.to_vec()).unwrap(), data);
+    }
+
+    #[test]
+    fn plaintext_passes_through_open() {
+        assert_eq!(open("plain text".to_string()).unwrap(), "plain text");
+    }
+
+    #[test]
+    fn parse_key_validates_length_and_hex() {
+        assert!(parse_key(KEY_HEX).is_ok());
+        assert!(parse_key(&format!("  {KEY_HEX}\n")).is_ok());
+        assert!(parse_key("abcd").unwrap_err().contains("32 bytes"));
+        assert!(parse_key("zz").unwrap_err().contains("hex"));
+    }
+}
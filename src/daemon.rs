@@ -0,0 +1,432 @@
+//! Daemon mode (`squall daemon`): one resident MCP server that outlives its
+//! clients, so warm connection pools, loaded memory, and the DuckDB writer
+//! survive editor restarts. Editors run `squall attach` as their MCP command; it
+//! bridges stdio to the daemon, starting one in the background if none answers.
+//!
+//! Each connection is one MCP session in the stdio framing (newline-delimited
+//! JSON-RPC), preceded by a line holding the daemon's token. The token is
+//! generated per start and published with the address in `~/.squall/daemon.json`
+//! (mode 0600), so only the owning user can attach. The daemon exits once no
+//! client has been attached for the idle timeout.
+//!
+//! `squall --socket PATH` serves the same sessions on a unix socket instead, for
+//! local orchestration tools. No token there: the socket file is owner-only.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rmcp::ServiceExt;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+use crate::server::SquallServer;
+use crate::worker::tokens_match;
+
+/// Default listen address for `squall daemon`. Fixed, so a second daemon started
+/// by a racing `attach` fails to bind and exits.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7421";
+
+/// Default time with no attached client before the daemon exits.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Time a new connection has to present the token.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Max length of the token line.
+const MAX_HANDSHAKE_BYTES: usize = 256;
+
+/// How long `attach` waits for a daemon it started to come up.
+const STARTUP_WAIT: Duration = Duration::from_secs(15);
+
+/// Where a running daemon can be reached, as published in `daemon.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonInfo {
+    pub addr: SocketAddr,
+    pub token: String,
+    pub pid: u32,
+}
+
+impl DaemonInfo {
+    /// `~/.squall/daemon.json`, or None without `$HOME`.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".squall/daemon.json"))
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        serde_json::from_slice(&std::fs::read(path).ok()?).ok()
+    }
+
+    /// Write atomically with owner-only permissions (the token grants access).
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        std::io::Write::write_all(&mut file, &serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Remove `path` if it still describes this daemon (a successor may have replaced it).
+    pub fn remove(&self, path: &Path) {
+        if Self::load(path).is_some_and(|current| current == *self) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// A fresh random token (256 bits, hex).
+pub fn new_token() -> String {
+    use ring::rand::SecureRandom;
+    let mut bytes = [0u8; 32];
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("system RNG failed");
+    hex::encode(bytes)
+}
+
+/// Serve MCP sessions on `listener` until no client has been attached for
+/// `idle_timeout` (None = forever). Sessions share `server`'s registry and
+/// memory backends, each bound to its own project (`SquallServer::for_session`).
+pub async fn serve(
+    listener: TcpListener,
+    server: SquallServer,
+    token: String,
+    idle_timeout: Option<Duration>,
+) {
+    let token: Arc<str> = token.into();
+    let attached = Arc::new(AtomicUsize::new(0));
+    let detached = Arc::new(Notify::new());
+    loop {
+        // Re-armed on every accept and detach, so it measures time since the last client left.
+        let idle = async {
+            match idle_timeout {
+                Some(timeout) if attached.load(Ordering::SeqCst) == 0 => {
+                    tokio::time::sleep(timeout).await
+                }
+                _ => std::future::pending().await,
+            }
+        };
+        let (socket, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("daemon: accept failed: {e}");
+                    continue;
+                }
+            },
+            () = detached.notified() => continue,
+            () = idle => {
+                tracing::info!("daemon: no client attached for {idle_timeout:?}, exiting");
+                return;
+            }
+        };
+        attached.fetch_add(1, Ordering::SeqCst);
+        let (server, token, attached, detached) = (
+            server.clone(),
+            token.clone(),
+            attached.clone(),
+            detached.clone(),
+        );
+        tokio::spawn(async move {
+            match run_session(socket, server, &token).await {
+                Ok(()) => tracing::info!("daemon: client {peer} detached"),
+                Err(e) => tracing::debug!("daemon: connection from {peer}: {e}"),
+            }
+            attached.fetch_sub(1, Ordering::SeqCst);
+            detached.notify_one();
+        });
+    }
+}
+
+async fn run_session(
+    mut socket: TcpStream,
+    server: SquallServer,
+    token: &str,
+) -> Result<(), String> {
+    let presented = tokio::time::timeout(HANDSHAKE_TIMEOUT, read_line(&mut socket))
+        .await
+        .map_err(|_| "no token presented".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !tokens_match(presented.trim(), token) {
+        return Err("wrong token".to_string());
+    }
+    let service = server
+        .for_session()
+        .serve(socket)
+        .await
+        .map_err(|e| format!("session failed to start: {e}"))?;
+    service.waiting().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Read one `\n`-terminated line byte by byte, so nothing past it is consumed.
+async fn read_line(socket: &mut TcpStream) -> std::io::Result<String> {
+    let mut line = Vec::new();
+    loop {
+        let byte = socket.read_u8().await?;
+        if byte == b'\n' {
+            return Ok(String::from_utf8_lossy(&line).into_owned());
+        }
+        if line.len() >= MAX_HANDSHAKE_BYTES {
+            return Err(std::io::Error::other("token line too long"));
+        }
+        line.push(byte);
+    }
+}
+
+/// Bind a unix socket at `path` with owner-only permissions. A stale socket left
+/// by a crashed server is replaced; one a live server still answers on is not.
+#[cfg(unix)]
+pub async fn bind_unix(path: &Path) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        if tokio::net::UnixStream::connect(path).await.is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("a server is already listening on {}", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Serve MCP sessions on a unix socket until the process ends, each bound to
+/// its own project (`SquallServer::for_session`).
+#[cfg(unix)]
+pub async fn serve_unix(listener: tokio::net::UnixListener, server: SquallServer) {
+    loop {
+        let socket = match listener.accept().await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                tracing::warn!("socket: accept failed: {e}");
+                continue;
+            }
+        };
+        let server = server.clone();
+        tokio::spawn(async move {
+            let result = match server.for_session().serve(socket).await {
+                Ok(service) => service.waiting().await.map(drop).map_err(|e| e.to_string()),
+                Err(e) => Err(format!("session failed to start: {e}")),
+            };
+            match result {
+                Ok(()) => tracing::info!("socket: client detached"),
+                Err(e) => tracing::debug!("socket: {e}"),
+            }
+        });
+    }
+}
+
+/// Connect to the daemon described at `info_path` and present its token.
+async fn connect(info_path: &Path) -> Option<TcpStream> {
+    let info = DaemonInfo::load(info_path)?;
+    let mut stream = TcpStream::connect(info.addr).await.ok()?;
+    stream
+        .write_all(format!("{}\n", info.token).as_bytes())
+        .await
+        .ok()?;
+    Some(stream)
+}
+
+/// Start `squall daemon` detached from this process (its own process group, no
+/// stdio), so it keeps running after the editor ends the attach process.
+fn spawn_daemon() -> std::io::Result<()> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .arg("daemon")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    command.spawn().map(drop)
+}
+
+/// `squall attach`: bridge this process's stdio to the daemon, starting one if
+/// none is reachable. Returns when the daemon closes the session.
+pub async fn attach(info_path: &Path) -> std::io::Result<()> {
+    let stream = match connect(info_path).await {
+        Some(stream) => stream,
+        None => {
+            tracing::info!("attach: no daemon running, starting one");
+            spawn_daemon()?;
+            let deadline = tokio::time::Instant::now() + STARTUP_WAIT;
+            loop {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                if let Some(stream) = connect(info_path).await {
+                    break stream;
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(std::io::Error::other(format!(
+                        "daemon did not start within {STARTUP_WAIT:?} (see its log file)"
+                    )));
+                }
+            }
+        }
+    };
+    let (mut from_daemon, mut to_daemon) = stream.into_split();
+    // Stdin EOF (editor closed the session) half-closes the connection; the
+    // daemon then ends the session and closes its side, which ends the bridge.
+    let forward = tokio::spawn(async move {
+        let _ = tokio::io::copy(&mut tokio::io::stdin(), &mut to_daemon).await;
+        let _ = to_daemon.shutdown().await;
+    });
+    let mut stdout = tokio::io::stdout();
+    let result = tokio::io::copy(&mut from_daemon, &mut stdout).await;
+    let _ = stdout.flush().await;
+    forward.abort();
+    result.map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn info_roundtrip_is_owner_only() {
+        let dir = tempfile_dir();
+        let path = dir.join("daemon.json");
+        let info = DaemonInfo {
+            addr: "127.0.0.1:7421".parse().unwrap(),
+            token: new_token(),
+            pid: 42,
+        };
+        assert_eq!(info.token.len(), 64);
+        info.write(&path).unwrap();
+        assert_eq!(DaemonInfo::load(&path), Some(info.clone()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let successor = DaemonInfo {
+            pid: 43,
+            ..info.clone()
+        };
+        successor.write(&path).unwrap();
+        info.remove(&path);
+        assert!(path.exists(), "a successor's file is left alone");
+        successor.remove(&path);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn tempfile_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "squall-daemon-test-{}-{}",
+            std::process::id(),
+            new_token()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn sessions_need_the_token_and_idle_daemon_exits() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = SquallServer::new(Config::default());
+        let daemon = tokio::spawn(serve(
+            listener,
+            server,
+            "tok".to_string(),
+            Some(Duration::from_millis(300)),
+        ));
+
+        // Wrong token: dropped without a session.
+        let mut rejected = TcpStream::connect(addr).await.unwrap();
+        rejected.write_all(b"nope\n").await.unwrap();
+        let mut buf = [0u8; 1];
+        assert_eq!(rejected.read(&mut buf).await.unwrap(), 0);
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(
+                b"tok\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\",\"params\":\
+                  {\"protocolVersion\":\"2025-03-26\",\"capabilities\":{},\
+                  \"clientInfo\":{\"name\":\"test\",\"version\":\"0\"}}}\n",
+            )
+            .await
+            .unwrap();
+        let (reader, mut writer) = client.split();
+        let mut lines = BufReader::new(reader).lines();
+        let reply = lines.next_line().await.unwrap().unwrap();
+        assert!(
+            reply.contains("\"id\":1") && reply.contains("serverInfo"),
+            "{reply}"
+        );
+
+        // Attached clients keep the daemon alive past the idle timeout.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(!daemon.is_finished());
+
+        writer.shutdown().await.unwrap();
+        drop(lines);
+        tokio::time::timeout(Duration::from_secs(5), daemon)
+            .await
+            .expect("idle daemon exits")
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_serves_sessions_and_replaces_stale_sockets() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile_dir();
+        let path = dir.join("squall.sock");
+        // A socket file nobody listens on, as a crashed server leaves behind.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let listener = bind_unix(&path).await.unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let server = tokio::spawn(serve_unix(listener, SquallServer::new(Config::default())));
+
+        let err = bind_unix(&path).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        let mut client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        client
+            .write_all(
+                b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\",\"params\":\
+                  {\"protocolVersion\":\"2025-03-26\",\"capabilities\":{},\
+                  \"clientInfo\":{\"name\":\"test\",\"version\":\"0\"}}}\n",
+            )
+            .await
+            .unwrap();
+        let reply = BufReader::new(client)
+            .lines()
+            .next_line()
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            reply.contains("\"id\":1") && reply.contains("serverInfo"),
+            "{reply}"
+        );
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
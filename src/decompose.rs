@@ -0,0 +1,226 @@
+//! Question decomposition for the `decompose` tool.
+//!
+//! A planner model splits a complex question into sub-questions and assigns
+//! each to one of the caller's model groups (e.g. "reasoning" for the proof,
+//! "fast" for the lookup). Every model of a group answers its sub-questions in
+//! parallel, and a synthesizer model merges the answers into one, citing the
+//! sub-questions it drew on. The response maps each sub-question to the models
+//! that answered it.
+
+use serde::{Deserialize, Serialize};
+
+/// Sub-questions planned unless the request says otherwise.
+pub const DEFAULT_SUBQUESTIONS: usize = 4;
+
+/// Most sub-questions one request can fan out.
+pub const MAX_SUBQUESTIONS: usize = 8;
+
+/// Group used when the caller names none: the `[review] default_models`.
+pub const DEFAULT_GROUP: &str = "default";
+
+/// A planned sub-question.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubQuestion {
+    pub question: String,
+    /// Model group it was assigned to.
+    pub group: String,
+}
+
+/// One model's answer to a sub-question.
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    pub model: String,
+    pub text: String,
+}
+
+/// A sub-question with the answers its group gave.
+#[derive(Debug, Clone, Serialize)]
+pub struct Answered {
+    #[serde(flatten)]
+    pub sub: SubQuestion,
+    pub answers: Vec<Answer>,
+    /// Models of the group that failed, with the reason.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Outcome of a `decompose` call.
+#[derive(Debug, Serialize)]
+pub struct Decomposition {
+    pub answer: String,
+    pub planned_by: String,
+    pub synthesized_by: String,
+    pub sub_questions: Vec<Answered>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Prompt asking the planner to split `question` into at most `max`
+/// sub-questions over `groups` (name and what its models are good at).
+pub fn plan_prompt(question: &str, groups: &[(String, String)], max: usize) -> String {
+    let groups: Vec<String> = groups
+        .iter()
+        .map(|(name, about)| format!("- {name}: {about}"))
+        .collect();
+    format!(
+        "Split the question below into at most {max} self-contained sub-questions that can be \
+         answered independently and together answer it. Assign each to the model group best \
+         suited to it.\n\nModel groups:\n{}\n\nReply with ONLY a JSON array: \
+         [{{\"question\": \"...\", \"group\": \"<group name>\"}}]. If the question needs no \
+         splitting, reply with a single item.\n\nQuestion:\n{question}",
+        groups.join("\n")
+    )
+}
+
+#[derive(Deserialize)]
+struct Planned {
+    question: String,
+    #[serde(default)]
+    group: String,
+}
+
+/// The planner's sub-questions, at most `max`, tolerating prose or fences
+/// around the JSON array. Unknown groups fall back to the first group. Empty
+/// when the reply holds no usable plan.
+pub fn parse_plan(text: &str, groups: &[String], max: usize) -> Vec<SubQuestion> {
+    let planned: Vec<Planned> = crate::json_repair::parse(text)
+        .and_then(|(doc, _)| serde_json::from_value(doc).ok())
+        .unwrap_or_default();
+    planned
+        .into_iter()
+        .filter(|p| !p.question.trim().is_empty())
+        .take(max)
+        .filter_map(|p| {
+            let group = groups
+                .iter()
+                .find(|g| g.eq_ignore_ascii_case(p.group.trim()))
+                .or_else(|| groups.first())?;
+            Some(SubQuestion {
+                question: p.question.trim().to_string(),
+                group: group.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Prompt asking the synthesizer to merge the sub-answers into one answer
+/// that cites the sub-questions as [Q1], [Q2], ...
+pub fn synthesis_prompt(question: &str, answered: &[Answered]) -> String {
+    let mut out = format!(
+        "Answer the question below using the answers to its sub-questions. Resolve \
+         disagreements explicitly, and cite the sub-questions you rely on as [Q1], [Q2], etc.\
+         \n\nQuestion:\n{question}\n"
+    );
+    for (i, a) in answered.iter().enumerate() {
+        out.push_str(&format!("\n## Q{}: {}\n", i + 1, a.sub.question));
+        if a.answers.is_empty() {
+            out.push_str("(no answer)\n");
+        }
+        for answer in &a.answers {
+            out.push_str(&format!("\n### {}\n{}\n", answer.model, answer.text.trim()));
+        }
+    }
+    out
+}
+
+/// Fallback answer when synthesis fails: each sub-question's first answer.
+pub fn concatenate(answered: &[Answered]) -> String {
+    answered
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let text = a
+                .answers
+                .first()
+                .map_or("(no answer)", |answer| answer.text.trim());
+            format!("**Q{}: {}**\n\n{text}", i + 1, a.sub.question)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+impl Decomposition {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Answer\n\n{}\n\n_Planned by {}, synthesized by {}._\n\n### Sub-questions\n",
+            self.answer.trim(),
+            self.planned_by,
+            self.synthesized_by
+        );
+        for (i, a) in self.sub_questions.iter().enumerate() {
+            let models: Vec<&str> = a.answers.iter().map(|x| x.model.as_str()).collect();
+            out.push_str(&format!(
+                "- **Q{}** ({}): {} — answered by {}\n",
+                i + 1,
+                a.sub.group,
+                a.sub.question,
+                if models.is_empty() {
+                    "no model".to_string()
+                } else {
+                    models.join(", ")
+                }
+            ));
+            for e in &a.errors {
+                out.push_str(&format!("  - failed: {e}\n"));
+            }
+        }
+        if !self.notes.is_empty() {
+            out.push_str("\n### Notes\n");
+            for note in &self.notes {
+                out.push_str(&format!("- {note}\n"));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_keeps_known_groups_and_caps_the_count() {
+        let groups = vec!["reasoning".to_string(), "fast".to_string()];
+        let reply = "Plan:\n```json\n[\
+            {\"question\": \"Is the lock ordering consistent?\", \"group\": \"Reasoning\"},\
+            {\"question\": \"Which crates pin tokio?\", \"group\": \"lookup\"},\
+            {\"question\": \"  \", \"group\": \"fast\"},\
+            {\"question\": \"What does the changelog say?\", \"group\": \"fast\"}]\n```";
+        let plan = parse_plan(reply, &groups, 2);
+        assert_eq!(
+            plan,
+            vec![
+                SubQuestion {
+                    question: "Is the lock ordering consistent?".into(),
+                    group: "reasoning".into()
+                },
+                SubQuestion {
+                    question: "Which crates pin tokio?".into(),
+                    group: "reasoning".into()
+                },
+            ]
+        );
+        assert!(parse_plan("no idea", &groups, 4).is_empty());
+    }
+
+    #[test]
+    fn synthesis_prompt_numbers_sub_questions() {
+        let answered = vec![Answered {
+            sub: SubQuestion {
+                question: "Which crates pin tokio?".into(),
+                group: "fast".into(),
+            },
+            answers: vec![Answer {
+                model: "grok".into(),
+                text: "Only squall-core.\n".into(),
+            }],
+            errors: vec![],
+        }];
+        let prompt = synthesis_prompt("Can we upgrade tokio?", &answered);
+        assert!(prompt.contains("## Q1: Which crates pin tokio?\n\n### grok\nOnly squall-core.\n"));
+        assert_eq!(
+            concatenate(&answered),
+            "**Q1: Which crates pin tokio?**\n\nOnly squall-core."
+        );
+    }
+}
@@ -0,0 +1,872 @@
+//! Dependency-change risk review (`ReviewRequest::dependency_risk`).
+//!
+//! The diff's lockfile and manifest sections are read for the dependency
+//! delta: packages added, updated (old and new version) and removed. Lockfiles
+//! give exact versions, so a package a lockfile reports is dropped from the
+//! manifest changes. Optionally each added or updated exact version is looked
+//! up in the OSV advisory database. The delta leads the review prompt with a
+//! request to assess supply-chain risk and report it under
+//! `### [severity] Supply chain: ...` headings; those findings are pulled out
+//! of the responses, merged across models, and reported as
+//! `ReviewResponse::supply_chain`.
+//!
+//! Supported: `Cargo.lock`/`Cargo.toml`, `package-lock.json`/`yarn.lock`/
+//! `package.json`, `poetry.lock`/`uv.lock`/`pyproject.toml`/`requirements*.txt`,
+//! and `go.mod`.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::escape_xml_content;
+use crate::findings::{Finding, Severity};
+use crate::tools::review::{ModelStatus, ReviewModelResult};
+
+/// Changes listed in the prompt; the rest are counted.
+pub const MAX_CHANGES: usize = 200;
+
+/// Heading prefix that marks a supply-chain finding.
+pub const FINDING_PREFIX: &str = "Supply chain:";
+
+/// OSV batch query endpoint.
+const OSV_QUERYBATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+
+/// Deadline for the advisory lookup.
+const ADVISORY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Manifest keys that are package metadata, not dependencies.
+const METADATA_KEYS: &[&str] = &[
+    "name",
+    "version",
+    "edition",
+    "rust-version",
+    "description",
+    "license",
+    "readme",
+    "repository",
+    "homepage",
+    "documentation",
+    "main",
+    "module",
+    "types",
+    "python",
+    "requires-python",
+    "packageManager",
+];
+
+/// Package registry a dependency comes from, named as OSV names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ecosystem {
+    #[serde(rename = "crates.io")]
+    Cargo,
+    #[serde(rename = "npm")]
+    Npm,
+    #[serde(rename = "PyPI")]
+    PyPi,
+    #[serde(rename = "Go")]
+    Go,
+}
+
+impl Ecosystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cargo => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPi => "PyPI",
+            Self::Go => "Go",
+        }
+    }
+}
+
+/// One added, updated or removed dependency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyChange {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// Lockfile or manifest the change was read from.
+    pub file: String,
+    /// Version before the change; None when the package is new.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Version after the change; None when the package was removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// OSV advisory IDs affecting `to`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<String>,
+}
+
+impl DependencyChange {
+    pub fn kind(&self) -> &'static str {
+        match (&self.from, &self.to) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "updated",
+        }
+    }
+
+    fn line(&self) -> String {
+        let version = |v: &Option<String>| v.clone().unwrap_or_else(|| "unpinned".to_string());
+        let mut line = match (&self.from, &self.to) {
+            (None, to) => format!("added {} {}", self.name, version(to)),
+            (from, None) => format!("removed {} {}", self.name, version(from)),
+            (from, to) => format!("updated {} {} → {}", self.name, version(from), version(to)),
+        };
+        line.push_str(&format!(" ({}, {})", self.ecosystem.as_str(), self.file));
+        if !self.advisories.is_empty() {
+            line.push_str(&format!(" advisories: {}", self.advisories.join(", ")));
+        }
+        line
+    }
+}
+
+/// A review's dependency delta, handed to the executor
+/// (`ReviewExecutor::with_dependencies`).
+#[derive(Debug, Clone, Default)]
+pub struct DependencyDelta {
+    pub changes: Vec<DependencyChange>,
+    /// Whether the advisory database was consulted.
+    pub advisories_checked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// `[[package]]` tables with `name`/`version` (Cargo.lock, poetry.lock, uv.lock).
+    TomlLock,
+    NpmLock,
+    YarnLock,
+    /// `name = "version"` under a dependencies table (Cargo.toml, pyproject.toml).
+    TomlManifest,
+    PackageJson,
+    Requirements,
+    GoMod,
+}
+
+impl Format {
+    fn is_lock(self) -> bool {
+        matches!(self, Self::TomlLock | Self::NpmLock | Self::YarnLock)
+    }
+}
+
+fn format_of(path: &str) -> Option<(Format, Ecosystem)> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    Some(match name {
+        "Cargo.lock" => (Format::TomlLock, Ecosystem::Cargo),
+        "poetry.lock" | "uv.lock" => (Format::TomlLock, Ecosystem::PyPi),
+        "package-lock.json" | "npm-shrinkwrap.json" => (Format::NpmLock, Ecosystem::Npm),
+        "yarn.lock" => (Format::YarnLock, Ecosystem::Npm),
+        "Cargo.toml" => (Format::TomlManifest, Ecosystem::Cargo),
+        "pyproject.toml" => (Format::TomlManifest, Ecosystem::PyPi),
+        "package.json" => (Format::PackageJson, Ecosystem::Npm),
+        "go.mod" => (Format::GoMod, Ecosystem::Go),
+        _ if name.starts_with("requirements") && name.ends_with(".txt") => {
+            (Format::Requirements, Ecosystem::PyPi)
+        }
+        _ => return None,
+    })
+}
+
+/// Whether `diff` touches a lockfile or manifest this module reads.
+pub fn touches_dependencies(diff: &str) -> bool {
+    crate::gentests::diff_paths(diff)
+        .iter()
+        .any(|p| format_of(p).is_some())
+}
+
+/// Per-file parse state.
+struct FileDelta {
+    file: String,
+    format: Format,
+    ecosystem: Ecosystem,
+    /// Lock formats: the package the next version line belongs to.
+    package: Option<String>,
+    /// Manifest formats: whether the current table is a dependencies table
+    /// (None until a header shows up in the hunk).
+    in_dependencies: Option<bool>,
+    /// Dependencies table naming one package (`[dependencies.serde]`).
+    table_package: Option<String>,
+    removed: Vec<(String, Option<String>)>,
+    added: Vec<(String, Option<String>)>,
+}
+
+impl FileDelta {
+    fn line(&mut self, sign: Option<char>, text: &str) {
+        let trimmed = text.trim();
+        let found = match self.format {
+            Format::TomlLock => {
+                if trimmed == "[[package]]" {
+                    self.package = None;
+                } else if let Some(name) = toml_string(trimmed, "name") {
+                    self.package = Some(name);
+                } else if let Some(version) = toml_string(trimmed, "version") {
+                    return self.record(sign, None, Some(version));
+                }
+                None
+            }
+            Format::NpmLock => {
+                if let Some(key) = trimmed.strip_suffix('{').and_then(|k| json_key(k.trim())) {
+                    let name = key.rsplit("node_modules/").next().unwrap_or(&key);
+                    self.package = (!name.is_empty()).then(|| name.to_string());
+                } else if let Some(rest) = trimmed.strip_prefix("\"version\":") {
+                    return self.record(sign, None, Some(unquote(rest)));
+                }
+                None
+            }
+            Format::YarnLock => {
+                if !text.starts_with([' ', '\t'])
+                    && trimmed.ends_with(':')
+                    && !trimmed.starts_with('#')
+                {
+                    let spec = trimmed.trim_end_matches(':');
+                    let first = unquote(spec.split(", ").next().unwrap_or(spec));
+                    self.package = match first.rfind('@') {
+                        Some(at) if at > 0 => Some(first[..at].to_string()),
+                        _ => None,
+                    };
+                } else if let Some(rest) = trimmed
+                    .strip_prefix("version:")
+                    .or_else(|| trimmed.strip_prefix("version "))
+                {
+                    return self.record(sign, None, Some(unquote(rest)));
+                }
+                None
+            }
+            Format::TomlManifest => {
+                if trimmed.starts_with('[') {
+                    let table = trimmed.trim_matches(['[', ']']).trim();
+                    self.in_dependencies = Some(table.contains("dependencies"));
+                    self.table_package = table
+                        .rsplit_once("dependencies.")
+                        .map(|(_, name)| name.trim_matches(['"', '\'']).to_string());
+                    None
+                } else if let Some(version) = self
+                    .table_package
+                    .as_ref()
+                    .and_then(|_| toml_string(trimmed, "version"))
+                {
+                    Some((
+                        self.table_package.clone().unwrap_or_default(),
+                        Some(version),
+                    ))
+                } else if self.ecosystem == Ecosystem::PyPi && trimmed.starts_with('"') {
+                    requirement(unquote(trimmed.trim_end_matches(',')).as_str())
+                        .filter(|(_, version)| version.is_some())
+                } else {
+                    toml_dependency(trimmed).filter(|(name, _)| self.is_dependency(name))
+                }
+            }
+            Format::PackageJson => {
+                if let Some(key) = trimmed.strip_suffix('{').and_then(|k| json_key(k.trim())) {
+                    self.in_dependencies = Some(key.ends_with("ependencies"));
+                    None
+                } else if trimmed.starts_with('}') {
+                    self.in_dependencies = Some(false);
+                    None
+                } else {
+                    trimmed
+                        .split_once(':')
+                        .and_then(|(key, value)| Some((json_key(key.trim())?, unquote(value))))
+                        .filter(|(name, version)| {
+                            self.is_dependency(name) && looks_like_version(version)
+                        })
+                        .map(|(name, version)| (name, Some(version)))
+                }
+            }
+            Format::Requirements => requirement(trimmed),
+            Format::GoMod => {
+                let line = trimmed.strip_prefix("require ").unwrap_or(trimmed);
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some(module), Some(version))
+                        if module.contains('.') && version.starts_with('v') =>
+                    {
+                        Some((module.to_string(), Some(version.to_string())))
+                    }
+                    _ => None,
+                }
+            }
+        };
+        if let Some((name, version)) = found {
+            self.record(sign, Some(name), version);
+        }
+    }
+
+    fn is_dependency(&self, name: &str) -> bool {
+        match self.in_dependencies {
+            Some(known) => known,
+            None => !METADATA_KEYS.contains(&name),
+        }
+    }
+
+    /// Record a `+`/`-` line's package. Lock formats pass the version alone
+    /// and take the name from the preceding `name`/key line.
+    fn record(&mut self, sign: Option<char>, name: Option<String>, version: Option<String>) {
+        let Some(name) = name.or_else(|| self.package.clone()) else {
+            return;
+        };
+        match sign {
+            Some('+') => self.added.push((name, version)),
+            Some('-') => self.removed.push((name, version)),
+            _ => {}
+        }
+    }
+
+    /// Pair removals with additions of the same package into updates.
+    fn finish(mut self) -> Vec<DependencyChange> {
+        let mut changes = Vec::new();
+        for (name, to) in self.added {
+            let from = match self.removed.iter().position(|(n, _)| *n == name) {
+                Some(i) => {
+                    let (_, from) = self.removed.remove(i);
+                    if from == to {
+                        continue;
+                    }
+                    Some(from.unwrap_or_else(|| "unpinned".to_string()))
+                }
+                None => None,
+            };
+            changes.push(DependencyChange {
+                ecosystem: self.ecosystem,
+                name,
+                file: self.file.clone(),
+                from,
+                to: Some(to.unwrap_or_else(|| "unpinned".to_string())),
+                advisories: Vec::new(),
+            });
+        }
+        for (name, from) in self.removed {
+            changes.push(DependencyChange {
+                ecosystem: self.ecosystem,
+                name,
+                file: self.file.clone(),
+                from: Some(from.unwrap_or_else(|| "unpinned".to_string())),
+                to: None,
+                advisories: Vec::new(),
+            });
+        }
+        changes
+    }
+}
+
+/// `key = "value"` → value.
+fn toml_string(line: &str, key: &str) -> Option<String> {
+    let (k, v) = line.split_once('=')?;
+    (k.trim() == key).then(|| unquote(v))
+}
+
+/// A Cargo.toml/poetry dependency line: `serde = "1.0"`,
+/// `serde = { version = "1.0", features = [...] }`, or a git dependency.
+fn toml_dependency(line: &str) -> Option<(String, Option<String>)> {
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim().trim_matches(['"', '\'']);
+    if name.is_empty() || name.contains([' ', '.']) {
+        return None;
+    }
+    let value = value.trim();
+    if let Some(table) = value.strip_prefix('{') {
+        let field = |key: &str| {
+            table
+                .trim_end_matches('}')
+                .split(',')
+                .find_map(|part| toml_string(part.trim(), key))
+        };
+        if let Some(version) = field("version") {
+            return Some((name.to_string(), Some(version)));
+        }
+        if let Some(git) = field("git") {
+            return Some((name.to_string(), Some(format!("git {git}"))));
+        }
+        // Path and workspace dependencies aren't fetched from anywhere.
+        return None;
+    }
+    let version = unquote(value);
+    looks_like_version(&version).then(|| (name.to_string(), Some(version)))
+}
+
+/// A requirements.txt / PEP 508 line: `requests==2.31.0`, `flask>=2`, `numpy`.
+fn requirement(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    let line = line.split(';').next().unwrap_or_default().trim();
+    if line.is_empty() || line.starts_with('-') || line.contains("://") {
+        return None;
+    }
+    let split = line
+        .find(['=', '>', '<', '~', '!', ' '])
+        .unwrap_or(line.len());
+    let name = line[..split].split('[').next().unwrap_or_default().trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return None;
+    }
+    let spec = line[split..].trim();
+    if !spec.is_empty() && !spec.starts_with(['=', '>', '<', '~', '!']) {
+        return None;
+    }
+    let version = match spec.strip_prefix("==") {
+        Some(exact) => Some(exact.trim().to_string()),
+        None => (!spec.is_empty()).then(|| spec.to_string()),
+    };
+    Some((name.to_string(), version))
+}
+
+fn json_key(key: &str) -> Option<String> {
+    let key = key.trim_end_matches(':').trim();
+    (key.len() >= 2 && key.starts_with('"') && key.ends_with('"'))
+        .then(|| key[1..key.len() - 1].to_string())
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_end_matches(',')
+        .trim()
+        .trim_matches(['"', '\''])
+        .to_string()
+}
+
+fn looks_like_version(value: &str) -> bool {
+    value
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit() || "^~<>=*v".contains(c))
+}
+
+/// The dependency changes in `diff`, lockfile versions preferred over manifest
+/// ranges for the same package.
+pub fn extract(diff: &str) -> Vec<DependencyChange> {
+    let mut files: Vec<(bool, Vec<DependencyChange>)> = Vec::new();
+    let mut current: Option<FileDelta> = None;
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            if let Some(done) = current.take() {
+                files.push((done.format.is_lock(), done.finish()));
+            }
+            let path = header
+                .rsplit_once(" b/")
+                .map(|(_, b)| b)
+                .unwrap_or_default();
+            current = format_of(path).map(|(format, ecosystem)| FileDelta {
+                file: path.to_string(),
+                format,
+                ecosystem,
+                package: None,
+                in_dependencies: None,
+                table_package: None,
+                removed: Vec::new(),
+                added: Vec::new(),
+            });
+            continue;
+        }
+        let Some(ref mut delta) = current else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            delta.package = None;
+            delta.in_dependencies = None;
+            delta.table_package = None;
+        } else if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        } else if let Some(text) = line.strip_prefix('+') {
+            delta.line(Some('+'), text);
+        } else if let Some(text) = line.strip_prefix('-') {
+            delta.line(Some('-'), text);
+        } else if let Some(text) = line.strip_prefix(' ') {
+            delta.line(None, text);
+        }
+    }
+    if let Some(done) = current {
+        files.push((done.format.is_lock(), done.finish()));
+    }
+
+    let locked: Vec<(Ecosystem, String)> = files
+        .iter()
+        .filter(|(lock, _)| *lock)
+        .flat_map(|(_, changes)| changes.iter().map(|c| (c.ecosystem, c.name.clone())))
+        .collect();
+    files
+        .into_iter()
+        .flat_map(|(lock, changes)| {
+            changes
+                .into_iter()
+                .filter(|c| lock || !locked.contains(&(c.ecosystem, c.name.clone())))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Exact versions that OSV can be asked about: `(change index, query)`.
+fn osv_queries(changes: &[DependencyChange]) -> Vec<(usize, serde_json::Value)> {
+    changes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            let version = c.to.as_deref()?;
+            let exact = version.starts_with(|ch: char| ch.is_ascii_digit())
+                || (c.ecosystem == Ecosystem::Go && version.starts_with('v'));
+            exact.then(|| {
+                (
+                    i,
+                    serde_json::json!({
+                        "package": {"name": c.name, "ecosystem": c.ecosystem.as_str()},
+                        "version": version,
+                    }),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Advisory IDs from an OSV batch response, one list per query.
+fn osv_ids(response: &serde_json::Value) -> Vec<Vec<String>> {
+    response["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .map(|r| {
+                    r["vulns"]
+                        .as_array()
+                        .map(|vulns| {
+                            vulns
+                                .iter()
+                                .filter_map(|v| v["id"].as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fill in `advisories` for each added or updated exact version from OSV.
+/// Returns how many changes have advisories.
+pub async fn fetch_advisories(changes: &mut [DependencyChange]) -> Result<usize, String> {
+    let queries = osv_queries(changes);
+    if queries.is_empty() {
+        return Ok(0);
+    }
+    let client = reqwest::Client::builder()
+        .timeout(ADVISORY_TIMEOUT)
+        .user_agent(concat!("squall/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("failed to build HTTP client: {e}"))?;
+    let body = serde_json::json!({
+        "queries": queries.iter().map(|(_, q)| q).collect::<Vec<_>>(),
+    });
+    let response = client
+        .post(OSV_QUERYBATCH_URL)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("advisory lookup failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "advisory lookup failed: HTTP {}",
+            response.status()
+        ));
+    }
+    let response: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("advisory lookup failed: {e}"))?;
+    let mut affected = 0;
+    for ((i, _), ids) in queries.iter().zip(osv_ids(&response)) {
+        if !ids.is_empty() {
+            affected += 1;
+        }
+        changes[*i].advisories = ids;
+    }
+    Ok(affected)
+}
+
+/// The prompt block: the delta and how to report supply-chain risk.
+pub fn prompt_block(delta: &DependencyDelta) -> String {
+    let mut out = String::from("<dependency_changes>\n");
+    for change in delta.changes.iter().take(MAX_CHANGES) {
+        out.push_str(&escape_xml_content(&change.line()));
+        out.push('\n');
+    }
+    if delta.changes.len() > MAX_CHANGES {
+        out.push_str(&format!(
+            "... and {} more\n",
+            delta.changes.len() - MAX_CHANGES
+        ));
+    }
+    out.push_str("</dependency_changes>\n");
+    if delta.advisories_checked {
+        out.push_str("Advisories are from the OSV database; none listed means none known.\n");
+    }
+    out.push_str(&format!(
+        "Besides reviewing the code, assess the supply-chain risk of these dependency changes: \
+         known vulnerabilities, typosquatted or unfamiliar packages, major version jumps, \
+         packages that are unmaintained or recently changed hands, install scripts, git or \
+         unpinned sources, and removals that break callers. Report each risk as its own finding \
+         headed `### [severity] {FINDING_PREFIX} <package> — <risk>`, apart from code findings.\n"
+    ));
+    out
+}
+
+/// A supply-chain finding, merged across models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplyChainFinding {
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    pub models: Vec<String>,
+}
+
+/// The dependency delta and the supply-chain findings, as reported in
+/// `ReviewResponse::supply_chain`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplyChainReview {
+    pub changes: Vec<DependencyChange>,
+    #[serde(default)]
+    pub advisories_checked: bool,
+    pub findings: Vec<SupplyChainFinding>,
+}
+
+impl SupplyChainReview {
+    /// Pull the supply-chain findings out of the complete responses in `results`.
+    pub fn new(delta: &DependencyDelta, results: &[ReviewModelResult]) -> Self {
+        let findings: Vec<Finding> = results
+            .iter()
+            .filter(|r| r.status == ModelStatus::Success)
+            .filter_map(|r| Some((r, r.response.as_deref()?)))
+            .flat_map(|(r, text)| crate::findings::extract_findings(&r.model, text))
+            .filter_map(|mut f| {
+                let rest = strip_prefix_ignore_case(&f.summary, FINDING_PREFIX)?;
+                f.summary = rest.trim().to_string();
+                Some(f)
+            })
+            .collect();
+        let mut clusters = crate::findings::cluster_findings(&findings);
+        clusters.sort_by_key(|c| {
+            std::cmp::Reverse(crate::findings::severity_rank(c.severity.as_ref()))
+        });
+        Self {
+            changes: delta.changes.clone(),
+            advisories_checked: delta.advisories_checked,
+            findings: clusters
+                .into_iter()
+                .map(|c| SupplyChainFinding {
+                    summary: c.summary,
+                    severity: c.severity,
+                    models: c.models,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let advised = self
+            .changes
+            .iter()
+            .filter(|c| !c.advisories.is_empty())
+            .count();
+        let mut md = format!(
+            "\n### Supply-chain risk\n{} dependency change(s){}:\n",
+            self.changes.len(),
+            if self.advisories_checked {
+                format!(", {advised} with advisories")
+            } else {
+                String::new()
+            }
+        );
+        for change in self.changes.iter().take(MAX_CHANGES) {
+            md.push_str(&format!("- {}\n", change.line()));
+        }
+        if self.findings.is_empty() {
+            md.push_str("\nNo supply-chain findings.\n");
+        } else {
+            md.push_str("\n**Findings**:\n");
+        }
+        for f in &self.findings {
+            let severity = f.severity.as_ref().map_or("unrated", |s| s.as_str());
+            md.push_str(&format!(
+                "- [{severity}] {} ({})\n",
+                f.summary,
+                f.models.join(", ")
+            ));
+        }
+        md
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/Cargo.toml b/Cargo.toml
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -1,9 +1,10 @@
+ [package]
+ name = \"app\"
+-version = \"0.1.0\"
++version = \"0.2.0\"
+
+ [dependencies]
+-serde = \"1.0\"
++serde = { version = \"1.1\", features = [\"derive\"] }
++left-pad = \"0.1\"
+ local = { path = \"../local\" }
+diff --git a/Cargo.lock b/Cargo.lock
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -10,7 +10,7 @@
+ [[package]]
+ name = \"serde\"
+-version = \"1.0.190\"
++version = \"1.0.195\"
+@@ -40,3 +40,8 @@
+ name = \"old\"
+ version = \"2.0.0\"
++
++[[package]]
++name = \"left-pad\"
++version = \"0.1.3\"
+diff --git a/web/package-lock.json b/web/package-lock.json
+--- a/web/package-lock.json
++++ b/web/package-lock.json
+@@ -5,7 +5,7 @@
+     \"node_modules/lodash\": {
+-      \"version\": \"4.17.20\",
++      \"version\": \"4.17.21\",
+       \"resolved\": \"https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz\",
+diff --git a/requirements.txt b/requirements.txt
+--- a/requirements.txt
++++ b/requirements.txt
+@@ -1,2 +1,2 @@
+-requests==2.30.0
++requests==2.31.0
+-flask>=2
+diff --git a/go.mod b/go.mod
+--- a/go.mod
++++ b/go.mod
+@@ -3,4 +3,5 @@
+ require (
+ \tgithub.com/pkg/errors v0.9.1
++\tgolang.org/x/crypto v0.17.0
+ )
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1 +1 @@
+-name = \"x\"
++name = \"y\"
+";
+
+    fn change<'a>(changes: &'a [DependencyChange], name: &str) -> &'a DependencyChange {
+        changes.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn extracts_the_delta_and_prefers_lockfiles() {
+        assert!(touches_dependencies(DIFF));
+        let changes = extract(DIFF);
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "serde",
+                "left-pad",
+                "lodash",
+                "requests",
+                "flask",
+                "golang.org/x/crypto"
+            ]
+        );
+
+        let serde = change(&changes, "serde");
+        assert_eq!(serde.file, "Cargo.lock");
+        assert_eq!(
+            (serde.from.as_deref(), serde.to.as_deref()),
+            (Some("1.0.190"), Some("1.0.195"))
+        );
+        assert_eq!(serde.kind(), "updated");
+        assert_eq!(change(&changes, "left-pad").kind(), "added");
+        assert_eq!(change(&changes, "left-pad").to.as_deref(), Some("0.1.3"));
+        assert_eq!(change(&changes, "lodash").ecosystem, Ecosystem::Npm);
+        assert_eq!(change(&changes, "requests").to.as_deref(), Some("2.31.0"));
+        assert_eq!(change(&changes, "flask").kind(), "removed");
+        assert_eq!(
+            change(&changes, "golang.org/x/crypto").to.as_deref(),
+            Some("v0.17.0")
+        );
+        assert!(!touches_dependencies("diff --git a/src/a.rs b/src/a.rs\n"));
+    }
+
+    #[test]
+    fn manifests_and_yarn_locks() {
+        let diff = "\
+diff --git a/package.json b/package.json
+--- a/package.json
++++ b/package.json
+@@ -2,6 +2,7 @@
+-  \"version\": \"1.0.0\",
++  \"version\": \"1.1.0\",
+   \"dependencies\": {
++    \"express\": \"^4.18.2\",
+     \"react\": \"18.2.0\"
+diff --git a/yarn.lock b/yarn.lock
+--- a/yarn.lock
++++ b/yarn.lock
+@@ -1,3 +1,3 @@
+ \"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":
+-  version \"7.22.0\"
++  version \"7.23.0\"
+diff --git a/pyproject.toml b/pyproject.toml
+--- a/pyproject.toml
++++ b/pyproject.toml
+@@ -4,3 +4,4 @@
+ dependencies = [
++  \"httpx>=0.25\",
+   \"rich\",
+";
+        let changes = extract(diff);
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["express", "@babel/core", "httpx"]);
+        assert_eq!(change(&changes, "express").to.as_deref(), Some("^4.18.2"));
+        assert_eq!(
+            change(&changes, "@babel/core").from.as_deref(),
+            Some("7.22.0")
+        );
+        assert_eq!(change(&changes, "httpx").to.as_deref(), Some(">=0.25"));
+    }
+
+    #[test]
+    fn osv_round_trip_and_prompt() {
+        let mut changes = extract(DIFF);
+        let queries = osv_queries(&changes);
+        // flask was removed and nothing else is a range: every other change is queried.
+        assert_eq!(queries.len(), 5);
+        assert_eq!(queries[0].1["package"]["ecosystem"], "crates.io");
+        let response = serde_json::json!({"results": [
+            {"vulns": [{"id": "RUSTSEC-2024-0001"}]}, {}, {}, {}, {}
+        ]});
+        for ((i, _), ids) in queries.iter().zip(osv_ids(&response)) {
+            changes[*i].advisories = ids;
+        }
+        let delta = DependencyDelta {
+            changes,
+            advisories_checked: true,
+        };
+        let block = prompt_block(&delta);
+        assert!(block.contains(
+            "updated serde 1.0.190 → 1.0.195 (crates.io, Cargo.lock) advisories: RUSTSEC-2024-0001"
+        ));
+        assert!(block.contains("removed flask &gt;=2 (PyPI, requirements.txt)"));
+        assert!(block.contains("### [severity] Supply chain:"));
+    }
+}
@@ -0,0 +1,318 @@
+//! Commit message and PR description drafting for the `describe_change` tool.
+//!
+//! The diff comes from git: the staged changes, or the branch against a base
+//! ref (`git diff base...HEAD`). Two fast models each draft a commit message
+//! and PR description; the fastest model that answered then merges the drafts
+//! into one. If only one draft comes back it is used as is.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::dispatch::registry::{BackendConfig, ModelEntry};
+use crate::routing::speed_rank;
+
+/// Models drafting when the caller names none.
+pub const DEFAULT_DRAFTERS: usize = 2;
+
+/// Conventional Commits types accepted in a subject line.
+pub const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Commit subject length past which git tooling starts truncating.
+const MAX_SUBJECT_CHARS: usize = 72;
+
+/// Where the diff comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSource {
+    /// `git diff --cached`.
+    Staged,
+    /// `git diff <base>...HEAD`: the branch's changes since it forked from `base`.
+    Branch(String),
+}
+
+impl DiffSource {
+    pub fn label(&self) -> String {
+        match self {
+            DiffSource::Staged => "staged changes".to_string(),
+            DiffSource::Branch(base) => format!("changes since {base}"),
+        }
+    }
+}
+
+/// A ref name git won't read as an option or a range.
+pub fn is_valid_base(base: &str) -> bool {
+    !base.is_empty()
+        && !base.starts_with('-')
+        && !base.contains("..")
+        && !base.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Collect the diff for `source` in `working_directory`.
+pub async fn collect_diff(
+    working_directory: &Path,
+    source: &DiffSource,
+    packages: &[String],
+) -> Result<String, String> {
+    let range;
+    let mut args: Vec<&str> = match source {
+        DiffSource::Staged => vec!["diff", "--cached", "--no-color", "--no-ext-diff"],
+        DiffSource::Branch(base) => {
+            if !is_valid_base(base) {
+                return Err(format!("invalid base ref: {base}"));
+            }
+            range = format!("{base}...HEAD");
+            vec!["diff", "--no-color", "--no-ext-diff", range.as_str()]
+        }
+    };
+    // Packages (monorepo subtrees) limit the diff to those paths.
+    if !packages.is_empty() {
+        args.push("--");
+        args.extend(packages.iter().map(String::as_str));
+    }
+    // Longer than the 5s git context guard: diffing a long-lived branch can be slow.
+    let output = tokio::time::timeout(
+        Duration::from_secs(15),
+        tokio::process::Command::new("git")
+            .args(&args)
+            .current_dir(working_directory)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| "git diff timed out".to_string())?
+    .map_err(|e| format!("git diff failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The `n` fastest HTTP models, by speed tier then name.
+pub fn fast_models(models: &[(&String, &ModelEntry)], n: usize) -> Vec<String> {
+    let mut http: Vec<_> = models
+        .iter()
+        .filter(|(_, e)| matches!(e.backend, BackendConfig::Http { .. }))
+        .collect();
+    http.sort_by(|a, b| {
+        speed_rank(&a.1.speed_tier)
+            .cmp(&speed_rank(&b.1.speed_tier))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    http.into_iter()
+        .take(n)
+        .map(|(k, _)| (*k).clone())
+        .collect()
+}
+
+fn format_rules(conventional: bool) -> String {
+    let subject = if conventional {
+        format!(
+            "The subject line must follow Conventional Commits: `type(scope): summary` or \
+             `type: summary`, where type is one of {}; add `!` after the type for breaking \
+             changes.",
+            CONVENTIONAL_TYPES.join(", ")
+        )
+    } else {
+        "The subject line is an imperative summary (\"Add\", \"Fix\", not \"Added\").".to_string()
+    };
+    format!(
+        "{subject} Keep it under {MAX_SUBJECT_CHARS} characters, then a blank line and a short \
+         body explaining what changed and why. The PR description opens with one or two plain \
+         sentences on what the change does and why, then lists the notable changes and how to \
+         verify them. Describe only what the diff shows.\n\n\
+         Reply in exactly this form:\n\
+         <commit>\ncommit message\n</commit>\n<pr_title>\nPR title\n</pr_title>\n<pr>\nPR \
+         description in markdown\n</pr>"
+    )
+}
+
+/// Prompt asking a model to describe the diff (already wrapped in `<diff>` tags).
+pub fn draft_prompt(diff: &str, conventional: bool, hint: Option<&str>) -> String {
+    let hint = hint
+        .map(|h| format!("\n\nAuthor's note on intent: {h}"))
+        .unwrap_or_default();
+    format!(
+        "{diff}\n\nWrite a commit message and pull request description for the diff above.{hint}\n\n{}",
+        format_rules(conventional)
+    )
+}
+
+/// Prompt asking a model to merge several drafts into one.
+pub fn synthesis_prompt(
+    diff: &str,
+    drafts: &[(String, ChangeDescription)],
+    conventional: bool,
+    hint: Option<&str>,
+) -> String {
+    let mut prompt = format!(
+        "{diff}\n\nSeveral reviewers drafted a commit message and PR description for the diff \
+         above. Merge them into the single best version: keep what is accurate and specific, \
+         drop anything the diff does not support."
+    );
+    if let Some(h) = hint {
+        prompt.push_str(&format!("\n\nAuthor's note on intent: {h}"));
+    }
+    for (i, (_, d)) in drafts.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n\n<draft index=\"{}\">\n<commit>\n{}\n</commit>\n<pr_title>\n{}\n</pr_title>\n<pr>\n{}\n</pr>\n</draft>",
+            i + 1,
+            d.commit_message,
+            d.pr_title,
+            d.pr_description
+        ));
+    }
+    prompt.push_str("\n\n");
+    prompt.push_str(&format_rules(conventional));
+    prompt
+}
+
+/// A commit message and PR description.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ChangeDescription {
+    pub commit_message: String,
+    pub pr_title: String,
+    pub pr_description: String,
+}
+
+fn tagged<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = text.find(&open)? + open.len();
+    let end = text[start..].find(&close).map_or(text.len(), |e| start + e);
+    let body = text[start..end].trim();
+    (!body.is_empty()).then_some(body)
+}
+
+/// Strip a code fence wrapped around a whole section.
+fn unfence(s: &str) -> String {
+    let trimmed = s.trim();
+    if let Some(rest) = trimmed.strip_prefix("```")
+        && let Some(body) = rest.strip_suffix("```")
+    {
+        let body = body.split_once('\n').map_or("", |(_, b)| b);
+        return body.trim().to_string();
+    }
+    trimmed.to_string()
+}
+
+/// Parse a model reply. `None` if it has no commit message.
+pub fn parse(text: &str) -> Option<ChangeDescription> {
+    let commit_message = unfence(tagged(text, "commit")?);
+    let pr_description = tagged(text, "pr").map(unfence).unwrap_or_default();
+    let pr_title = tagged(text, "pr_title")
+        .map(|t| t.lines().next().unwrap_or_default().trim().to_string())
+        .unwrap_or_else(|| subject(&commit_message).to_string());
+    Some(ChangeDescription {
+        commit_message,
+        pr_title,
+        pr_description,
+    })
+}
+
+fn subject(message: &str) -> &str {
+    message.lines().next().unwrap_or_default().trim()
+}
+
+/// Whether `subject` is a Conventional Commits subject line.
+pub fn is_conventional(subject: &str) -> bool {
+    let Some((head, summary)) = subject.split_once(':') else {
+        return false;
+    };
+    if summary.trim().is_empty() || !summary.starts_with(' ') {
+        return false;
+    }
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let ty = match head.split_once('(') {
+        Some((ty, scope)) => match scope.strip_suffix(')') {
+            Some(s) if !s.is_empty() && !s.contains(['(', ')']) => ty,
+            _ => return false,
+        },
+        None => head,
+    };
+    CONVENTIONAL_TYPES.contains(&ty)
+}
+
+/// Problems with a description worth telling the caller about.
+pub fn warnings(desc: &ChangeDescription, conventional: bool) -> Vec<String> {
+    let mut out = Vec::new();
+    let subject = subject(&desc.commit_message);
+    if subject.chars().count() > MAX_SUBJECT_CHARS {
+        out.push(format!(
+            "subject line is {} characters (over {MAX_SUBJECT_CHARS})",
+            subject.chars().count()
+        ));
+    }
+    if conventional && !is_conventional(subject) {
+        out.push("subject line is not a Conventional Commits subject".to_string());
+    }
+    out
+}
+
+/// Render the result as markdown.
+pub fn to_markdown(
+    desc: &ChangeDescription,
+    source: &DiffSource,
+    synthesized_by: &str,
+    drafters: &[String],
+    notes: &[String],
+) -> String {
+    let mut out = format!(
+        "## Commit message\n\n```\n{}\n```\n\n## PR: {}\n\n{}\n\n---\n_Described {} · drafts by {} · final by {}._\n",
+        desc.commit_message,
+        desc.pr_title,
+        desc.pr_description,
+        source.label(),
+        drafters.join(", "),
+        synthesized_by
+    );
+    for note in notes {
+        out.push_str(&format!("\n- {note}"));
+    }
+    if !notes.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_tagged_sections() {
+        let reply = "Here you go.\n<commit>\n```\nfeat(cli): add --dry-run\n\nSkips writes.\n```\n</commit>\n\
+                     <pr_title>Add --dry-run flag</pr_title>\n<pr>\nAdds a dry-run mode.\n</pr>";
+        let desc = parse(reply).unwrap();
+        assert_eq!(
+            desc.commit_message,
+            "feat(cli): add --dry-run\n\nSkips writes."
+        );
+        assert_eq!(desc.pr_title, "Add --dry-run flag");
+        assert_eq!(desc.pr_description, "Adds a dry-run mode.");
+
+        let no_title = parse("<commit>Fix typo in README</commit>").unwrap();
+        assert_eq!(no_title.pr_title, "Fix typo in README");
+        assert!(parse("I could not read the diff.").is_none());
+    }
+
+    #[test]
+    fn conventional_subjects_and_base_refs() {
+        assert!(is_conventional("feat: add thing"));
+        assert!(is_conventional("fix(parser)!: reject empty input"));
+        assert!(!is_conventional("Add thing"));
+        assert!(!is_conventional("feature: add thing"));
+        assert!(!is_conventional("fix(): empty scope"));
+        assert!(!is_conventional("fix:no space"));
+
+        assert!(is_valid_base("origin/main"));
+        assert!(!is_valid_base("--output=/tmp/x"));
+        assert!(!is_valid_base("main..HEAD"));
+        assert!(!is_valid_base("main HEAD"));
+    }
+}
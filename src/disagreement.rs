@@ -0,0 +1,405 @@
+//! Disagreement mining: findings that models directly contradict each other on.
+//!
+//! After a review, findings from different models on the same issue (see
+//! `findings::same_issue`) are compared. Two kinds of contradiction are kept:
+//! one model reports an issue that another explicitly dismisses ("not a bug",
+//! "false positive", "is safe"), or they rate it three or more severity steps
+//! apart (critical vs low). Each lands in `.squall/memory/disagreements.md`
+//! with the topic, the models on each side, and a resolution that `feedback`
+//! on the same review fills in: the side whose models were rated higher wins.
+//! Over time the file says which models to trust on which kinds of finding.
+
+use std::collections::HashMap;
+
+use crate::findings::{Finding, severity_rank};
+
+/// Most entries kept in disagreements.md; older ones are dropped first.
+pub const MAX_ENTRIES: usize = 100;
+
+/// Top-level heading of disagreements.md.
+pub const HEADING: &str = "# Model Disagreements";
+
+/// Severity steps apart at which two ratings of one issue contradict.
+const SEVERITY_GAP: u8 = 3;
+
+/// Longest topic kept in an entry.
+const MAX_TOPIC_LEN: usize = 200;
+
+/// Phrases that mark a finding as dismissing the issue rather than reporting it.
+const DISMISSALS: &[&str] = &[
+    "not an issue",
+    "not a bug",
+    "not a problem",
+    "no issue",
+    "no bug",
+    "false positive",
+    "not vulnerable",
+    "works as intended",
+    "is fine",
+    "is safe",
+    "is correct",
+];
+
+const UNRESOLVED: &str = "unresolved";
+
+/// One contradiction between models on the same issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    /// Summary of the first finding reporting the issue.
+    pub topic: String,
+    pub file_path: Option<String>,
+    /// Models that reported the issue (or rated it severe).
+    pub asserted: Vec<String>,
+    /// Models that dismissed it (or rated it minor).
+    pub disputed: Vec<String>,
+    /// "dismissed" or "severity".
+    pub kind: &'static str,
+}
+
+/// Whether a finding's heading dismisses the issue instead of reporting it.
+pub fn dismisses(finding: &Finding) -> bool {
+    let summary = finding.summary.to_lowercase();
+    DISMISSALS.iter().any(|p| summary.contains(p))
+}
+
+/// Contradictions among `findings`. Samples of one model (`model#2`) count as
+/// that model, so self-consistency runs don't disagree with themselves.
+pub fn find(findings: &[Finding]) -> Vec<Disagreement> {
+    let mut groups: Vec<Vec<&Finding>> = Vec::new();
+    for finding in findings {
+        match groups
+            .iter_mut()
+            .find(|g| g.iter().any(|m| crate::findings::same_issue(m, finding)))
+        {
+            Some(group) => group.push(finding),
+            None => groups.push(vec![finding]),
+        }
+    }
+
+    let mut found = Vec::new();
+    for group in groups {
+        let model = |f: &&Finding| crate::consistency::base(&f.model_key).to_string();
+        let (dismissing, reporting): (Vec<&Finding>, Vec<&Finding>) =
+            group.iter().partition(|f| dismisses(f));
+        let Some(first) = reporting.first() else {
+            continue;
+        };
+        let asserted = distinct(reporting.iter().map(model));
+        let disputed: Vec<String> = distinct(dismissing.iter().map(model))
+            .into_iter()
+            .filter(|m| !asserted.contains(m))
+            .collect();
+        if !disputed.is_empty() {
+            found.push(disagreement(first, asserted, disputed, "dismissed"));
+            continue;
+        }
+
+        let ranked: Vec<(&Finding, u8)> = reporting
+            .iter()
+            .filter(|f| f.severity.is_some())
+            .map(|f| (*f, severity_rank(f.severity.as_ref())))
+            .collect();
+        let (Some(high), Some(low)) = (
+            ranked.iter().map(|(_, r)| *r).max(),
+            ranked.iter().map(|(_, r)| *r).min(),
+        ) else {
+            continue;
+        };
+        if high - low < SEVERITY_GAP {
+            continue;
+        }
+        let side = |rank: u8| {
+            distinct(
+                ranked
+                    .iter()
+                    .filter(|(_, r)| *r == rank)
+                    .map(|(f, _)| model(f)),
+            )
+        };
+        let (asserted, disputed) = (side(high), side(low));
+        if asserted.iter().any(|m| disputed.contains(m)) {
+            continue;
+        }
+        let first = ranked
+            .iter()
+            .find(|(_, r)| *r == high)
+            .map_or(*first, |(f, _)| *f);
+        found.push(disagreement(first, asserted, disputed, "severity"));
+    }
+    found
+}
+
+fn distinct(models: impl Iterator<Item = String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for model in models {
+        if !out.contains(&model) {
+            out.push(model);
+        }
+    }
+    out
+}
+
+fn disagreement(
+    finding: &Finding,
+    asserted: Vec<String>,
+    disputed: Vec<String>,
+    kind: &'static str,
+) -> Disagreement {
+    let mut topic = finding.summary.replace(['\n', '\r'], " ");
+    topic.truncate(crate::context::floor_char_boundary(&topic, MAX_TOPIC_LEN));
+    Disagreement {
+        topic,
+        file_path: finding.file_path.clone(),
+        asserted,
+        disputed,
+        kind,
+    }
+}
+
+/// A disagreements.md entry for `d`, found in review `review` (a results file stem).
+pub fn entry(timestamp: &str, review: &str, d: &Disagreement) -> String {
+    let topic = match d.file_path {
+        Some(ref file) => format!("{} ({file})", d.topic),
+        None => d.topic.clone(),
+    };
+    format!(
+        "## [{timestamp}] {topic}\n\
+         <!-- review:{review} -->\n\
+         - Kind: {}\n\
+         - Asserted: {}\n\
+         - Disputed: {}\n\
+         - Resolution: {UNRESOLVED}\n",
+        d.kind,
+        d.asserted.join(", "),
+        d.disputed.join(", ")
+    )
+}
+
+/// Entries of disagreements.md, oldest first.
+pub fn entries(content: &str) -> Vec<String> {
+    let mut entries: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with("## [") {
+            entries.push(format!("{line}\n"));
+        } else if let Some(current) = entries.last_mut()
+            && !line.trim().is_empty()
+        {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    entries
+}
+
+/// disagreements.md with `entries`, keeping the newest `MAX_ENTRIES`.
+pub fn render(entries: &[String]) -> String {
+    let start = entries.len().saturating_sub(MAX_ENTRIES);
+    format!("{HEADING}\n\n{}", entries[start..].join("\n"))
+}
+
+fn field<'a>(entry: &'a str, name: &str) -> Option<&'a str> {
+    entry
+        .lines()
+        .find_map(|l| l.strip_prefix("- ")?.strip_prefix(name)?.strip_prefix(": "))
+}
+
+fn models(entry: &str, name: &str) -> Vec<String> {
+    field(entry, name)
+        .into_iter()
+        .flat_map(|list| list.split(", "))
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Resolve the unresolved entries of review `review` with feedback `scores`
+/// (0 noise, 1 okay, 2 actionable). The side with the higher mean score wins;
+/// entries missing a score for either side stay unresolved. Returns the new
+/// content and how many entries were resolved.
+pub fn resolve(content: &str, review: &str, scores: &HashMap<String, u8>) -> (String, usize) {
+    let marker = format!("<!-- review:{review} -->");
+    let mut resolved = 0;
+    let entries: Vec<String> = entries(content)
+        .into_iter()
+        .map(|entry| {
+            if !entry.contains(&marker) || field(&entry, "Resolution") != Some(UNRESOLVED) {
+                return entry;
+            }
+            let mean = |side: &[String]| {
+                let rated: Vec<f64> = side
+                    .iter()
+                    .filter_map(|m| scores.get(m))
+                    .map(|s| f64::from(*s))
+                    .collect();
+                (!rated.is_empty()).then(|| rated.iter().sum::<f64>() / rated.len() as f64)
+            };
+            let asserted = models(&entry, "Asserted");
+            let disputed = models(&entry, "Disputed");
+            let (Some(a), Some(d)) = (mean(&asserted), mean(&disputed)) else {
+                return entry;
+            };
+            let resolution = if a > d {
+                format!("asserted ({} rated higher)", asserted.join(", "))
+            } else if d > a {
+                format!("disputed ({} rated higher)", disputed.join(", "))
+            } else {
+                "inconclusive (rated the same)".to_string()
+            };
+            resolved += 1;
+            entry.replace(
+                &format!("- Resolution: {UNRESOLVED}"),
+                &format!("- Resolution: {resolution}"),
+            )
+        })
+        .collect();
+    (render(&entries), resolved)
+}
+
+/// Per model: resolved disagreements it was on the winning side of, and all it
+/// took part in. Best record first.
+pub fn trust(content: &str) -> Vec<(String, usize, usize)> {
+    let mut tally: HashMap<String, (usize, usize)> = HashMap::new();
+    for entry in entries(content) {
+        let Some(resolution) = field(&entry, "Resolution") else {
+            continue;
+        };
+        let winner = if resolution.starts_with("asserted") {
+            "Asserted"
+        } else if resolution.starts_with("disputed") {
+            "Disputed"
+        } else {
+            continue;
+        };
+        for side in ["Asserted", "Disputed"] {
+            for model in models(&entry, side) {
+                let t = tally.entry(model).or_default();
+                t.1 += 1;
+                if side == winner {
+                    t.0 += 1;
+                }
+            }
+        }
+    }
+    let mut trust: Vec<(String, usize, usize)> = tally
+        .into_iter()
+        .map(|(m, (won, of))| (m, won, of))
+        .collect();
+    trust.sort_by(|a, b| {
+        (b.1 * a.2)
+            .cmp(&(a.1 * b.2))
+            .then_with(|| b.2.cmp(&a.2))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    trust
+}
+
+/// disagreements.md for reading: a trust line over resolved entries, then the
+/// entries involving `model` (all if None).
+pub fn summarize(content: &str, model: Option<&str>) -> Option<String> {
+    let entries: Vec<String> = entries(content)
+        .into_iter()
+        .filter(|e| {
+            model.is_none_or(|m| {
+                models(e, "Asserted").iter().any(|x| x == m)
+                    || models(e, "Disputed").iter().any(|x| x == m)
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    let mut out = format!("{HEADING}\n\n");
+    let trust = trust(content);
+    if !trust.is_empty() {
+        let list: Vec<String> = trust
+            .iter()
+            .map(|(m, won, of)| format!("{m} {won}/{of}"))
+            .collect();
+        out.push_str(&format!(
+            "**Sided with the feedback** (resolved disagreements): {}\n\n",
+            list.join(", ")
+        ));
+    }
+    out.push_str(&entries.join("\n"));
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::extract_findings;
+
+    fn review() -> Vec<Finding> {
+        let mut findings = extract_findings(
+            "grok",
+            "### 1. **Token compared with ==** (Confidence: **High**)\n\
+             File: src/auth.rs:42\nTiming leak.\n",
+        );
+        findings.extend(extract_findings(
+            "kimi",
+            "### 1. **Token comparison is safe** (Confidence: **Medium**)\n\
+             File: src/auth.rs:43\nLengths are fixed.\n",
+        ));
+        findings.extend(extract_findings(
+            "grok",
+            "### [critical] Unbounded retry loop\nFile: src/net.rs:10\n",
+        ));
+        findings.extend(extract_findings(
+            "gemini",
+            "### [low] Unbounded retry loop\nFile: src/net.rs:12\n",
+        ));
+        findings.extend(extract_findings(
+            "gemini",
+            "### [medium] Missing docs\nFile: src/lib.rs:1\n",
+        ));
+        findings
+    }
+
+    #[test]
+    fn finds_dismissals_and_severity_splits() {
+        let found = find(&review());
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert_eq!(found[0].kind, "dismissed");
+        assert_eq!(found[0].asserted, vec!["grok"]);
+        assert_eq!(found[0].disputed, vec!["kimi"]);
+        assert_eq!(found[0].file_path.as_deref(), Some("src/auth.rs"));
+        assert_eq!(found[1].kind, "severity");
+        assert_eq!(found[1].disputed, vec!["gemini"]);
+    }
+
+    #[test]
+    fn samples_of_one_model_do_not_disagree() {
+        let mut findings =
+            extract_findings("grok#1", "### [critical] Race in cache\nFile: a.rs:5\n");
+        findings.extend(extract_findings(
+            "grok#2",
+            "### [low] Race in cache\nFile: a.rs:5\n",
+        ));
+        assert!(find(&findings).is_empty());
+    }
+
+    #[test]
+    fn feedback_resolves_and_builds_trust() {
+        let found = find(&review());
+        let content = render(&[
+            entry("2026-10-01T00:00:00Z", "r1", &found[0]),
+            entry("2026-10-01T00:00:00Z", "r1", &found[1]),
+        ]);
+        let scores = HashMap::from([("grok".to_string(), 2), ("kimi".to_string(), 0)]);
+        let (content, resolved) = resolve(&content, "r1", &scores);
+        // gemini unrated: the severity split stays open.
+        assert_eq!(resolved, 1);
+        assert!(content.contains("- Resolution: asserted (grok rated higher)"));
+        assert!(content.contains("- Resolution: unresolved"));
+        assert_eq!(resolve(&content, "r2", &scores).1, 0);
+
+        assert_eq!(
+            trust(&content),
+            vec![("grok".to_string(), 1, 1), ("kimi".to_string(), 0, 1)]
+        );
+        let shown = summarize(&content, Some("kimi")).unwrap();
+        assert!(shown.contains("grok 1/1, kimi 0/1"));
+        assert!(!shown.contains("Unbounded retry loop"));
+        assert!(summarize(&content, Some("codex")).is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Provider model catalog discovery.
+//!
+//! Asks each configured HTTP provider for its catalog (`GET .../models`, derived
+//! from the chat endpoint URL) with the configured key, and reports the model IDs
+//! that no registry entry uses yet. Providers are queried concurrently; one that
+//! fails is reported with its error instead of failing the whole run.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::config::ProviderEndpoint;
+use crate::dispatch::registry::{ApiFormat, Registry};
+
+/// Per-provider request timeout.
+const DISCOVER_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Largest catalog body read. OpenRouter's full catalog is a few MB.
+const MAX_CATALOG_BYTES: usize = 16 * 1024 * 1024;
+
+/// New models listed per provider; the rest are counted.
+pub const MAX_LISTED_PER_PROVIDER: usize = 100;
+
+/// One provider's catalog compared against the registry.
+#[derive(Debug, Serialize)]
+pub struct ProviderCatalog {
+    pub provider: String,
+    /// Models the provider reports for this key (after `filter`).
+    pub total: usize,
+    /// Of those, models a registry entry already uses (by model_id or alias).
+    pub known: usize,
+    /// Model IDs not in the registry, sorted.
+    pub new_models: Vec<String>,
+    /// Why the catalog could not be fetched. Counts are zero when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Catalog URL for a chat endpoint: `.../v1/chat/completions` → `.../v1/models`,
+/// `.../v1/messages` → `.../v1/models`. Other URLs get `/models` appended.
+pub fn models_url(base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let root = base
+        .strip_suffix("/chat/completions")
+        .or_else(|| base.strip_suffix("/messages"))
+        .unwrap_or(base);
+    format!("{root}/models")
+}
+
+/// Model IDs from a catalog body: `{"data": [{"id": ...}]}` (OpenAI, Anthropic,
+/// OpenRouter) or a bare `[{"id": ...}]` array (Together). Sorted and deduplicated.
+pub fn parse_catalog(body: &str) -> Result<Vec<String>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("invalid catalog JSON: {e}"))?;
+    let entries = value
+        .get("data")
+        .unwrap_or(&value)
+        .as_array()
+        .ok_or("catalog has no model list")?;
+    let mut ids: Vec<String> = entries
+        .iter()
+        .filter_map(|m| m.get("id")?.as_str())
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Model IDs (and aliases) the registry already serves from `provider`.
+fn known_ids(registry: &Registry, provider: &str) -> HashSet<String> {
+    registry
+        .list_models()
+        .into_iter()
+        .filter(|(_, entry)| entry.provider == provider)
+        .flat_map(|(_, entry)| {
+            std::iter::once(entry.model_id.clone()).chain(entry.aliases.iter().cloned())
+        })
+        .collect()
+}
+
+async fn fetch_catalog(
+    client: &reqwest::Client,
+    endpoint: &ProviderEndpoint,
+) -> Result<Vec<String>, String> {
+    let url = models_url(&endpoint.base_url);
+    let request = match endpoint.api_format {
+        ApiFormat::OpenAi => client.get(&url).bearer_auth(&endpoint.api_key),
+        // Anthropic pages the catalog (20 per page by default).
+        ApiFormat::Anthropic => client
+            .get(&url)
+            .query(&[("limit", "1000")])
+            .header("x-api-key", &endpoint.api_key)
+            .header("anthropic-version", "2023-06-01"),
+    };
+    let mut response = request
+        .timeout(DISCOVER_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{url} returned HTTP {}", status.as_u16()));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("reading catalog failed: {}", e.without_url()))?
+    {
+        if body.len() + chunk.len() > MAX_CATALOG_BYTES {
+            return Err(format!("catalog exceeds {MAX_CATALOG_BYTES} bytes"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    parse_catalog(&String::from_utf8_lossy(&body))
+}
+
+/// Fetch catalogs for `providers` (all configured providers when None) and
+/// compare them against the registry. `filter` keeps only model IDs containing
+/// it (case-insensitive). Results are in provider name order.
+pub async fn discover(
+    registry: &Registry,
+    providers: Option<&[String]>,
+    filter: Option<&str>,
+) -> Vec<ProviderCatalog> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .expect("failed to build HTTP client");
+    let filter = filter
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty());
+    let selected: Vec<_> = registry
+        .providers()
+        .into_iter()
+        .filter(|(name, _)| providers.is_none_or(|wanted| wanted.iter().any(|w| w == *name)))
+        .collect();
+
+    let fetches = selected.iter().map(|(name, endpoint)| {
+        let client = &client;
+        let filter = filter.as_deref();
+        async move {
+            let ids = match fetch_catalog(client, endpoint).await {
+                Ok(ids) => ids,
+                Err(e) => {
+                    tracing::warn!(provider = %name, "model discovery failed: {e}");
+                    return ProviderCatalog {
+                        provider: (*name).clone(),
+                        total: 0,
+                        known: 0,
+                        new_models: Vec::new(),
+                        error: Some(e),
+                    };
+                }
+            };
+            let known_ids = known_ids(registry, name);
+            let ids: Vec<String> = ids
+                .into_iter()
+                .filter(|id| filter.is_none_or(|f| id.to_lowercase().contains(f)))
+                .collect();
+            let total = ids.len();
+            let new_models: Vec<String> = ids
+                .into_iter()
+                .filter(|id| !known_ids.contains(id))
+                .collect();
+            ProviderCatalog {
+                provider: (*name).clone(),
+                total,
+                known: total - new_models.len(),
+                new_models,
+                error: None,
+            }
+        }
+    });
+    futures_util::future::join_all(fetches).await
+}
+
+/// TOML basic string with quotes, backslashes, and control characters escaped.
+fn toml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A `[models.*]` entry for `model_id` ready to paste into `config.toml`. The
+/// config key is the model ID (quoted when it isn't a bare TOML key).
+pub fn config_snippet(provider: &str, model_id: &str) -> String {
+    let bare = !model_id.is_empty()
+        && model_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let key = if bare {
+        model_id.to_string()
+    } else {
+        toml_string(model_id)
+    };
+    format!(
+        "[models.{key}]\n\
+         model_id = {id}\n\
+         provider = {provider}\n\
+         backend = \"http\"\n\
+         description = \"\"\n\
+         speed_tier = \"medium\"\n\
+         precision_tier = \"medium\"\n",
+        id = toml_string(model_id),
+        provider = toml_string(provider),
+    )
+}
+
+/// Render catalogs as markdown: per provider, the new model IDs (capped at
+/// `MAX_LISTED_PER_PROVIDER`) and, with `snippets`, a TOML block for them.
+pub fn to_markdown(catalogs: &[ProviderCatalog], snippets: bool) -> String {
+    if catalogs.is_empty() {
+        return "No HTTP providers with an API key configured.".to_string();
+    }
+    let mut md = String::from("# Provider model catalogs\n");
+    for catalog in catalogs {
+        md.push_str(&format!("\n## {}\n\n", catalog.provider));
+        if let Some(ref e) = catalog.error {
+            md.push_str(&format!("Could not list models: {e}\n"));
+            continue;
+        }
+        md.push_str(&format!(
+            "{} models available, {} already configured, {} new.\n",
+            catalog.total,
+            catalog.known,
+            catalog.new_models.len()
+        ));
+        if catalog.new_models.is_empty() {
+            continue;
+        }
+        let listed = &catalog.new_models[..catalog.new_models.len().min(MAX_LISTED_PER_PROVIDER)];
+        md.push('\n');
+        for id in listed {
+            md.push_str(&format!("- `{id}`\n"));
+        }
+        let more = catalog.new_models.len() - listed.len();
+        if more > 0 {
+            md.push_str(&format!("- ... and {more} more (narrow with `filter`)\n"));
+        }
+        if snippets {
+            md.push_str("\n```toml\n");
+            let blocks: Vec<String> = listed
+                .iter()
+                .map(|id| config_snippet(&catalog.provider, id))
+                .collect();
+            md.push_str(&blocks.join("\n"));
+            md.push_str("```\n");
+        }
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_url_replaces_chat_path() {
+        assert_eq!(
+            models_url("https://api.x.ai/v1/chat/completions"),
+            "https://api.x.ai/v1/models"
+        );
+        assert_eq!(
+            models_url("https://api.deepseek.com/chat/completions"),
+            "https://api.deepseek.com/models"
+        );
+        assert_eq!(
+            models_url("https://api.anthropic.com/v1/messages"),
+            "https://api.anthropic.com/v1/models"
+        );
+        assert_eq!(
+            models_url("https://example.com/v1/"),
+            "https://example.com/v1/models"
+        );
+    }
+
+    #[test]
+    fn parse_catalog_accepts_data_and_bare_arrays() {
+        let openai =
+            r#"{"object":"list","data":[{"id":"grok-4"},{"id":"grok-3"},{"id":"grok-4"}]}"#;
+        assert_eq!(parse_catalog(openai).unwrap(), vec!["grok-3", "grok-4"]);
+
+        let together = r#"[{"id":"meta/llama","type":"chat"},{"name":"no-id"}]"#;
+        assert_eq!(parse_catalog(together).unwrap(), vec!["meta/llama"]);
+
+        assert!(parse_catalog(r#"{"error":"nope"}"#).is_err());
+        assert!(parse_catalog("<html>").is_err());
+    }
+
+    #[test]
+    fn config_snippet_quotes_keys_and_escapes_values() {
+        let snippet = config_snippet("xai", "grok-5");
+        assert!(snippet.starts_with("[models.grok-5]\n"));
+        assert!(snippet.contains("model_id = \"grok-5\"\nprovider = \"xai\"\n"));
+
+        let snippet = config_snippet("openrouter", "z-ai/glm-6");
+        assert!(snippet.starts_with("[models.\"z-ai/glm-6\"]\n"));
+
+        let hostile = config_snippet("p", "a\"]\n[x");
+        assert!(hostile.starts_with("[models.\"a\\\"]\\u000A[x\"]\n"));
+        assert_eq!(hostile.lines().count(), 7);
+    }
+}
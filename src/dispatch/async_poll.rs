@@ -4,7 +4,7 @@ use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use reqwest::Client;
 
 use crate::dispatch::registry::AsyncPollProviderType;
-use crate::dispatch::{ProviderRequest, ProviderResult};
+use crate::dispatch::{DispatchTiming, ProviderRequest, ProviderResult};
 use crate::error::SquallError;
 
 /// Max response body size for launch responses (64KB — just a JSON with an ID).
@@ -511,6 +511,11 @@ impl AsyncPollDispatch {
                         model: req.model.clone(),
                         provider: provider.to_string(),
                         partial: false,
+                        output_capped: false,
+                        timing: DispatchTiming::default(),
+                        usage: None,
+                        secrets_redacted: Vec::new(),
+                        provider_metadata: Default::default(),
                     });
                 }
                 PollStatus::Failed(msg) => {
@@ -617,6 +622,7 @@ pub async fn persist_research_result(
     });
 
     let json = serde_json::to_string_pretty(&payload).map_err(std::io::Error::other)?;
+    let json = crate::crypto::seal(&json)?;
 
     // Atomic write: temp file + rename prevents partial reads.
     // Clean up temp file on ANY failure (write or rename).
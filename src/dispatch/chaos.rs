@@ -0,0 +1,386 @@
+//! Fault injection for resilience testing (feature = "chaos").
+//!
+//! Wraps dispatches with randomly chosen faults so the review executor's
+//! cutoff, partial-result, and retry paths can be exercised against real
+//! backends. Disabled unless `SQUALL_CHAOS_RATE` is set to a value in (0, 1].
+//!
+//! Environment:
+//! - `SQUALL_CHAOS_RATE` — fraction of dispatches that get a fault (0.0–1.0).
+//! - `SQUALL_CHAOS_FAULTS` — comma-separated subset of `delay,stall,truncate,error`
+//!   (default: all four).
+//! - `SQUALL_CHAOS_MAX_DELAY_MS` — upper bound for injected delays (default 5000).
+//! - `SQUALL_CHAOS_SEED` — fixed RNG seed for reproducible runs.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::dispatch::{ProviderRequest, ProviderResult};
+use crate::error::SquallError;
+
+/// Default upper bound for injected delays.
+const DEFAULT_MAX_DELAY_MS: u64 = 5_000;
+
+/// A single injectable fault.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Sleep before dispatching (bounded by the request deadline).
+    Delay,
+    /// Never respond: wait for cancellation or the deadline.
+    Stall,
+    /// Dispatch normally, then cut the response short and mark it partial.
+    Truncate,
+    /// Fail immediately with a retryable upstream error.
+    Error,
+}
+
+impl Fault {
+    const ALL: [Fault; 4] = [Fault::Delay, Fault::Stall, Fault::Truncate, Fault::Error];
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "delay" => Some(Self::Delay),
+            "stall" => Some(Self::Stall),
+            "truncate" => Some(Self::Truncate),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed chaos settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChaosConfig {
+    pub rate: f64,
+    pub faults: Vec<Fault>,
+    pub max_delay: Duration,
+    pub seed: Option<u64>,
+}
+
+impl ChaosConfig {
+    /// Read settings from `SQUALL_CHAOS_*` env vars. None = chaos disabled.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::parse(
+            var("SQUALL_CHAOS_RATE").as_deref(),
+            var("SQUALL_CHAOS_FAULTS").as_deref(),
+            var("SQUALL_CHAOS_MAX_DELAY_MS").as_deref(),
+            var("SQUALL_CHAOS_SEED").as_deref(),
+        )
+    }
+
+    /// Parse raw setting values. Invalid entries are warned about and ignored;
+    /// a missing, zero, or unparseable rate disables chaos entirely.
+    pub fn parse(
+        rate: Option<&str>,
+        faults: Option<&str>,
+        max_delay_ms: Option<&str>,
+        seed: Option<&str>,
+    ) -> Option<Self> {
+        let rate = match rate?.trim().parse::<f64>() {
+            Ok(r) if r.is_finite() && r > 0.0 => r.min(1.0),
+            Ok(_) => return None,
+            Err(_) => {
+                tracing::warn!("chaos: ignoring unparseable SQUALL_CHAOS_RATE");
+                return None;
+            }
+        };
+
+        let faults = match faults {
+            Some(list) => {
+                let mut parsed = Vec::new();
+                for name in list.split(',').filter(|s| !s.trim().is_empty()) {
+                    match Fault::parse(name) {
+                        Some(f) if !parsed.contains(&f) => parsed.push(f),
+                        Some(_) => {}
+                        None => tracing::warn!("chaos: ignoring unknown fault '{}'", name.trim()),
+                    }
+                }
+                parsed
+            }
+            None => Fault::ALL.to_vec(),
+        };
+        if faults.is_empty() {
+            return None;
+        }
+
+        let max_delay_ms = max_delay_ms
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_MAX_DELAY_MS);
+        let seed = seed.and_then(|s| s.trim().parse::<u64>().ok());
+
+        Some(Self {
+            rate,
+            faults,
+            max_delay: Duration::from_millis(max_delay_ms),
+            seed,
+        })
+    }
+}
+
+/// Randomly injects faults into dispatches according to a `ChaosConfig`.
+pub struct ChaosInjector {
+    config: ChaosConfig,
+    state: AtomicU64,
+}
+
+impl ChaosInjector {
+    pub fn new(config: ChaosConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(|| {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64;
+            nanos ^ (u64::from(std::process::id()) << 32)
+        });
+        Self {
+            config,
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    /// SplitMix64 — tiny, lock-free, good enough for fault sampling.
+    fn next_u64(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0, 1).
+    fn next_f64(&self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Decide whether this dispatch gets a fault, and which one.
+    pub fn roll(&self) -> Option<Fault> {
+        if self.next_f64() >= self.config.rate {
+            return None;
+        }
+        let idx = (self.next_u64() % self.config.faults.len() as u64) as usize;
+        Some(self.config.faults[idx])
+    }
+
+    /// Run `dispatch` with a possibly-injected fault.
+    ///
+    /// `dispatch` is not polled for `Stall` and `Error`, so no upstream request
+    /// is made for those faults.
+    pub async fn apply<F>(
+        &self,
+        req: &ProviderRequest,
+        provider: &str,
+        dispatch: F,
+    ) -> Result<ProviderResult, SquallError>
+    where
+        F: Future<Output = Result<ProviderResult, SquallError>>,
+    {
+        let Some(fault) = self.roll() else {
+            return dispatch.await;
+        };
+        tracing::warn!(model = %req.model, provider, ?fault, "chaos: injecting fault");
+        let start = Instant::now();
+
+        match fault {
+            Fault::Delay => {
+                let max_ms = self.config.max_delay.as_millis() as u64;
+                let delay = Duration::from_millis(self.next_u64() % (max_ms + 1));
+                let remaining = req.deadline.saturating_duration_since(Instant::now());
+                tokio::time::sleep(delay.min(remaining)).await;
+                dispatch.await
+            }
+            Fault::Stall => {
+                let cancel = req.cancellation_token.clone();
+                let cancel_fut = async {
+                    match &cancel {
+                        Some(t) => t.cancelled().await,
+                        None => std::future::pending().await,
+                    }
+                };
+                tokio::select! {
+                    _ = cancel_fut => {
+                        Err(SquallError::Cancelled(start.elapsed().as_millis() as u64))
+                    }
+                    _ = tokio::time::sleep_until(req.deadline.into()) => {
+                        Err(SquallError::Timeout(start.elapsed().as_millis() as u64))
+                    }
+                }
+            }
+            Fault::Truncate => {
+                let mut result = dispatch.await?;
+                let keep = truncation_point(&result.text, self.next_f64());
+                result.text.truncate(keep);
+                result.partial = true;
+                Ok(result)
+            }
+            Fault::Error => Err(SquallError::Upstream {
+                provider: provider.to_string(),
+                message: "chaos: injected fault".to_string(),
+                status: Some(503),
+            }),
+        }
+    }
+}
+
+/// Byte offset at `fraction` of `text`, floored to a char boundary.
+fn truncation_point(text: &str, fraction: f64) -> usize {
+    let mut idx = ((text.len() as f64) * fraction.clamp(0.0, 1.0)) as usize;
+    idx = idx.min(text.len());
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    fn request(deadline: Duration) -> ProviderRequest {
+        ProviderRequest {
+            prompt: Arc::from("hello"),
+            model: "test-model".to_string(),
+            deadline: Instant::now() + deadline,
+            working_directory: None,
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: None,
+        }
+    }
+
+    fn ok_result() -> Result<ProviderResult, SquallError> {
+        Ok(ProviderResult {
+            text: "the quick brown fox".to_string(),
+            model: "test-model".to_string(),
+            provider: "test".to_string(),
+            partial: false,
+            output_capped: false,
+            timing: Default::default(),
+            usage: None,
+            secrets_redacted: Vec::new(),
+            provider_metadata: Default::default(),
+        })
+    }
+
+    async fn must_not_dispatch() -> Result<ProviderResult, SquallError> {
+        panic!("dispatch must not be polled")
+    }
+
+    fn injector(faults: &str) -> ChaosInjector {
+        let cfg = ChaosConfig::parse(Some("1.0"), Some(faults), Some("10"), Some("7")).unwrap();
+        ChaosInjector::new(cfg)
+    }
+
+    #[test]
+    fn parse_disabled_without_rate() {
+        assert_eq!(ChaosConfig::parse(None, None, None, None), None);
+        assert_eq!(ChaosConfig::parse(Some("0"), None, None, None), None);
+        assert_eq!(ChaosConfig::parse(Some("nope"), None, None, None), None);
+    }
+
+    #[test]
+    fn parse_defaults_and_clamps() {
+        let cfg = ChaosConfig::parse(Some("2.5"), None, None, None).unwrap();
+        assert_eq!(cfg.rate, 1.0);
+        assert_eq!(cfg.faults, Fault::ALL.to_vec());
+        assert_eq!(cfg.max_delay, Duration::from_millis(DEFAULT_MAX_DELAY_MS));
+    }
+
+    #[test]
+    fn parse_fault_list_skips_unknown_and_duplicates() {
+        let cfg =
+            ChaosConfig::parse(Some("0.5"), Some("stall, bogus,stall,error"), None, None).unwrap();
+        assert_eq!(cfg.faults, vec![Fault::Stall, Fault::Error]);
+        assert_eq!(
+            ChaosConfig::parse(Some("0.5"), Some("bogus"), None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn seeded_rolls_are_reproducible() {
+        let cfg = ChaosConfig::parse(Some("0.5"), None, None, Some("42")).unwrap();
+        let a = ChaosInjector::new(cfg.clone());
+        let b = ChaosInjector::new(cfg);
+        let rolls_a: Vec<_> = (0..32).map(|_| a.roll()).collect();
+        let rolls_b: Vec<_> = (0..32).map(|_| b.roll()).collect();
+        assert_eq!(rolls_a, rolls_b);
+        assert!(rolls_a.iter().any(Option::is_some));
+        assert!(rolls_a.iter().any(Option::is_none));
+    }
+
+    #[test]
+    fn truncation_point_respects_char_boundaries() {
+        assert_eq!(truncation_point("", 0.5), 0);
+        assert_eq!(truncation_point("abcd", 0.5), 2);
+        // "é" is two bytes; 0.5 of "é" lands mid-char
+        assert_eq!(truncation_point("é", 0.5), 0);
+    }
+
+    #[tokio::test]
+    async fn error_fault_is_retryable_and_skips_dispatch() {
+        let chaos = injector("error");
+        let err = chaos
+            .apply(
+                &request(Duration::from_secs(5)),
+                "test",
+                must_not_dispatch(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(err.provider(), Some("test"));
+    }
+
+    #[tokio::test]
+    async fn truncate_fault_marks_partial() {
+        let chaos = injector("truncate");
+        let result = chaos
+            .apply(&request(Duration::from_secs(5)), "test", async {
+                ok_result()
+            })
+            .await
+            .unwrap();
+        assert!(result.partial);
+        assert!(result.text.len() < "the quick brown fox".len());
+    }
+
+    #[tokio::test]
+    async fn stall_fault_honors_cancellation() {
+        let chaos = injector("stall");
+        let token = CancellationToken::new();
+        let mut req = request(Duration::from_secs(30));
+        req.cancellation_token = Some(token.clone());
+        token.cancel();
+        let err = chaos
+            .apply(&req, "test", must_not_dispatch())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SquallError::Cancelled(_)));
+    }
+
+    #[tokio::test]
+    async fn stall_fault_times_out_at_deadline() {
+        let chaos = injector("stall");
+        let err = chaos
+            .apply(
+                &request(Duration::from_millis(20)),
+                "test",
+                must_not_dispatch(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SquallError::Timeout(_)));
+    }
+}
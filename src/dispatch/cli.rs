@@ -6,7 +6,7 @@ use tokio::process::Command;
 
 use crate::config::PersistRawOutput;
 use crate::dispatch::async_poll::sanitize_model_name;
-use crate::dispatch::{ProviderRequest, ProviderResult};
+use crate::dispatch::{DispatchTiming, ProviderRequest, ProviderResult};
 use crate::error::SquallError;
 use crate::parsers::OutputParser;
 
@@ -447,6 +447,11 @@ impl CliDispatch {
             model: req.model.clone(),
             provider: provider.to_string(),
             partial: false,
+            output_capped: false,
+            timing: DispatchTiming::default(),
+            usage: None,
+            secrets_redacted: Vec::new(),
+            provider_metadata: Default::default(),
         })
     }
 }
@@ -546,6 +551,7 @@ pub async fn persist_cli_output(
     });
 
     let json = serde_json::to_string_pretty(&payload).map_err(std::io::Error::other)?;
+    let json = crate::crypto::seal(&json)?;
 
     // Atomic write: temp file + rename prevents partial reads.
     // Clean up temp file on ANY failure (write or rename).
@@ -0,0 +1,237 @@
+//! Name resolution overrides for HTTP providers on split-horizon networks.
+//!
+//! Two options per provider (see `HttpClientSettings`): pin the provider's host
+//! to fixed addresses (`resolve_addrs`), or resolve every host the client
+//! contacts through a specific DNS server (`dns_server`) instead of the system
+//! resolver. The latter is a minimal UDP stub resolver: one A query, then AAAA
+//! if there were no A records, with no caching (the connection pool keeps
+//! resolved connections warm) and no TCP fallback for truncated answers.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::net::UdpSocket;
+
+/// Time to wait for the DNS server's answer.
+const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+
+/// Resolves hosts by querying one DNS server directly.
+#[derive(Debug, Clone)]
+pub struct DnsServerResolver {
+    server: SocketAddr,
+}
+
+impl DnsServerResolver {
+    pub fn new(server: SocketAddr) -> Self {
+        Self { server }
+    }
+
+    /// Addresses for `host` (port 0; the client fills in the URL's port).
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, String> {
+        let ips = self.query(host, TYPE_A).await?;
+        if !ips.is_empty() {
+            return Ok(ips);
+        }
+        let ips = self.query(host, TYPE_AAAA).await?;
+        if ips.is_empty() {
+            return Err(format!("{host}: no A or AAAA records from {}", self.server));
+        }
+        Ok(ips)
+    }
+
+    async fn query(&self, host: &str, qtype: u16) -> Result<Vec<IpAddr>, String> {
+        let id = query_id(host, qtype);
+        let packet = encode_query(id, host, qtype)?;
+        let bind: SocketAddr = if self.server.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(bind).await.map_err(|e| e.to_string())?;
+        socket
+            .connect(self.server)
+            .await
+            .map_err(|e| e.to_string())?;
+        socket.send(&packet).await.map_err(|e| e.to_string())?;
+        let mut buf = [0u8; 1500];
+        loop {
+            let n = tokio::time::timeout(DNS_TIMEOUT, socket.recv(&mut buf))
+                .await
+                .map_err(|_| format!("{host}: DNS server {} did not answer", self.server))?
+                .map_err(|e| e.to_string())?;
+            // Ignore stray datagrams that don't answer this query.
+            if n >= 2 && u16::from_be_bytes([buf[0], buf[1]]) == id {
+                return parse_response(&buf[..n], qtype).map_err(|e| format!("{host}: {e}"));
+            }
+        }
+    }
+}
+
+impl Resolve for DnsServerResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let ips = resolver.lookup(name.as_str()).await?;
+            let addrs: Addrs = Box::new(ips.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+/// Query ID that differs per query without needing a random source.
+fn query_id(host: &str, qtype: u16) -> u16 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    host.bytes()
+        .fold(nanos ^ u32::from(qtype), |h, b| {
+            h.rotate_left(5) ^ u32::from(b)
+        })
+        .to_be_bytes()
+        .chunks(2)
+        .fold(0u16, |acc, c| acc ^ u16::from_be_bytes([c[0], c[1]]))
+}
+
+/// A recursive query for `host` with one question of type `qtype`.
+fn encode_query(id: u16, host: &str, qtype: u16) -> Result<Vec<u8>, String> {
+    let host = host.trim_end_matches('.');
+    let mut packet = Vec::with_capacity(18 + host.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&[0x01, 0x00]); // standard query, recursion desired
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]); // 1 question
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("invalid host name '{host}'"));
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&qtype.to_be_bytes());
+    packet.extend_from_slice(&[0, 1]); // class IN
+    Ok(packet)
+}
+
+/// Skip a (possibly compressed) name starting at `pos`; returns the offset after it.
+fn skip_name(msg: &[u8], mut pos: usize) -> Result<usize, String> {
+    loop {
+        let len = *msg.get(pos).ok_or("truncated name")?;
+        match len {
+            0 => return Ok(pos + 1),
+            // Compression pointer: two bytes, and the name ends here.
+            l if l & 0xC0 == 0xC0 => return Ok(pos + 2),
+            l => pos += 1 + l as usize,
+        }
+    }
+}
+
+/// Addresses of type `qtype` in the answer section of a DNS response.
+fn parse_response(msg: &[u8], qtype: u16) -> Result<Vec<IpAddr>, String> {
+    if msg.len() < 12 {
+        return Err("short DNS response".to_string());
+    }
+    let flags = u16::from_be_bytes([msg[2], msg[3]]);
+    if flags & 0x0200 != 0 {
+        return Err("truncated DNS response (TCP fallback unsupported)".to_string());
+    }
+    match flags & 0x000F {
+        0 => {}
+        3 => return Err("no such host (NXDOMAIN)".to_string()),
+        rcode => return Err(format!("DNS error rcode {rcode}")),
+    }
+    let questions = u16::from_be_bytes([msg[4], msg[5]]);
+    let answers = u16::from_be_bytes([msg[6], msg[7]]);
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos)? + 4;
+    }
+    let mut ips = Vec::new();
+    for _ in 0..answers {
+        pos = skip_name(msg, pos)?;
+        let header = msg.get(pos..pos + 10).ok_or("truncated answer")?;
+        let rtype = u16::from_be_bytes([header[0], header[1]]);
+        let rdlen = u16::from_be_bytes([header[8], header[9]]) as usize;
+        pos += 10;
+        let rdata = msg.get(pos..pos + rdlen).ok_or("truncated answer")?;
+        pos += rdlen;
+        if rtype != qtype {
+            continue; // CNAMEs and other records along the way
+        }
+        match (rtype, rdata.len()) {
+            (TYPE_A, 4) => ips.push(IpAddr::from([rdata[0], rdata[1], rdata[2], rdata[3]])),
+            (TYPE_AAAA, 16) => {
+                let bytes: [u8; 16] = rdata.try_into().map_err(|_| "bad AAAA record")?;
+                ips.push(IpAddr::from(bytes));
+            }
+            _ => {}
+        }
+    }
+    Ok(ips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answer `query` with a CNAME followed by the given A records.
+    fn answer(query: &[u8], addrs: &[[u8; 4]]) -> Vec<u8> {
+        let mut msg = query.to_vec();
+        msg[2] = 0x81; // response, recursion desired
+        msg[3] = 0x80; // recursion available, rcode 0
+        msg[7] = (addrs.len() + 1) as u8;
+        // CNAME pointing at the question name (offset 12).
+        msg.extend_from_slice(&[0xC0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xC0, 12]);
+        for a in addrs {
+            msg.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4]);
+            msg.extend_from_slice(a);
+        }
+        msg
+    }
+
+    #[test]
+    fn parses_a_records_past_cnames() {
+        let query = encode_query(7, "gateway.corp.internal", TYPE_A).unwrap();
+        assert_eq!(&query[12..20], b"\x07gateway");
+        let ips = parse_response(&answer(&query, &[[10, 0, 0, 5], [10, 0, 0, 6]]), TYPE_A);
+        assert_eq!(
+            ips.unwrap(),
+            vec![IpAddr::from([10, 0, 0, 5]), IpAddr::from([10, 0, 0, 6])]
+        );
+
+        let mut nxdomain = answer(&query, &[]);
+        nxdomain[3] = 0x83;
+        assert!(
+            parse_response(&nxdomain, TYPE_A)
+                .unwrap_err()
+                .contains("NXDOMAIN")
+        );
+        assert!(parse_response(&query[..8], TYPE_A).is_err());
+        assert!(encode_query(1, "bad..host", TYPE_A).is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_through_configured_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            while let Ok((n, peer)) = server.recv_from(&mut buf).await {
+                let qtype = u16::from_be_bytes([buf[n - 4], buf[n - 3]]);
+                let records: &[[u8; 4]] = if qtype == TYPE_A {
+                    &[[10, 1, 2, 3]]
+                } else {
+                    &[]
+                };
+                let _ = server.send_to(&answer(&buf[..n], records), peer).await;
+            }
+        });
+
+        let resolver = DnsServerResolver::new(addr);
+        let ips = resolver.lookup("llm-gateway.internal").await.unwrap();
+        assert_eq!(ips, vec![IpAddr::from([10, 1, 2, 3])]);
+    }
+}
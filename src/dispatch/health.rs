@@ -0,0 +1,276 @@
+//! Per-backend health, learned from dispatch outcomes and probes.
+//!
+//! A backend (provider) is healthy until calls to it start failing in ways that
+//! say the backend itself is broken: connection errors, timeouts, 5xx, rejected
+//! keys. `DEGRADED_AFTER` consecutive failures mark it degraded; `DOWN_AFTER`
+//! mark it down for `DOWN_COOLDOWN`, during which reviews skip its models
+//! instantly (`reason: "backend_down"`) instead of waiting out timeouts. Once
+//! the cooldown passes it is degraded again and the next call is the probe: a
+//! success makes it healthy, a failure puts it back down.
+//!
+//! Rate limits, cutoffs, and bad prompts say nothing about the backend and
+//! leave its state alone.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::dispatch::ratelimit::now_ms;
+use crate::error::SquallError;
+
+/// Consecutive failures before a backend counts as degraded.
+pub const DEGRADED_AFTER: u32 = 2;
+
+/// Consecutive failures before a backend counts as down.
+pub const DOWN_AFTER: u32 = 5;
+
+/// How long a down backend is skipped before the next call probes it.
+pub const DOWN_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Healthy,
+    Degraded,
+    Down,
+}
+
+impl HealthState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Down => "down",
+        }
+    }
+}
+
+/// Whether an outcome says anything about the backend. None = neutral.
+fn failure_of<T>(result: &Result<T, SquallError>) -> Option<Option<String>> {
+    match result {
+        Ok(_) => Some(None),
+        Err(
+            e @ (SquallError::Request(_)
+            | SquallError::Timeout(_)
+            | SquallError::AuthFailed { .. }
+            | SquallError::PollFailed { .. }),
+        ) => Some(Some(e.user_message())),
+        Err(e @ SquallError::Upstream { status, .. }) if status.is_some_and(|s| s >= 500) => {
+            Some(Some(e.user_message()))
+        }
+        Err(_) => None,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Record {
+    consecutive_failures: u32,
+    /// Unix ms until which the backend is down.
+    down_until_ms: Option<u64>,
+    last_error: Option<String>,
+}
+
+impl Record {
+    fn state(&self, now: u64) -> HealthState {
+        if self.down_until_ms.is_some_and(|until| until > now) {
+            HealthState::Down
+        } else if self.consecutive_failures >= DEGRADED_AFTER {
+            HealthState::Degraded
+        } else {
+            HealthState::Healthy
+        }
+    }
+}
+
+/// A backend's health as reported by `listmodels`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackendHealth {
+    pub provider: String,
+    pub state: HealthState,
+    pub consecutive_failures: u32,
+    /// Seconds until a down backend is probed again.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl BackendHealth {
+    /// One line for markdown, e.g. "xai: down, probed again in 42s (5
+    /// consecutive failures; last: request timed out after 30000ms)".
+    pub fn describe(&self) -> String {
+        let mut out = format!("{}: {}", self.provider, self.state.as_str());
+        if let Some(secs) = self.retry_in_secs {
+            out.push_str(&format!(", probed again in {secs}s"));
+        }
+        out.push_str(&format!(
+            " ({} consecutive failure{}",
+            self.consecutive_failures,
+            if self.consecutive_failures == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ));
+        if let Some(e) = &self.last_error {
+            out.push_str(&format!("; last: {e}"));
+        }
+        out.push(')');
+        out
+    }
+}
+
+/// Health of every backend that has been called or probed.
+#[derive(Debug, Default)]
+pub struct HealthTracker(Mutex<HashMap<String, Record>>);
+
+impl HealthTracker {
+    /// Update `provider` from a call's (or probe's) outcome.
+    pub fn record<T>(&self, provider: &str, result: &Result<T, SquallError>) {
+        self.record_at(provider, failure_of(result), now_ms());
+    }
+
+    /// Update `provider` from a probe that only reports success or an error.
+    pub fn record_probe(&self, provider: &str, result: &Result<(), String>) {
+        let failure = result.as_ref().err().cloned();
+        self.record_at(provider, Some(failure), now_ms());
+    }
+
+    fn record_at(&self, provider: &str, outcome: Option<Option<String>>, now: u64) {
+        let Some(failure) = outcome else {
+            return;
+        };
+        let mut records = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let record = records.entry(provider.to_string()).or_default();
+        let before = record.state(now);
+        match failure {
+            None => *record = Record::default(),
+            Some(error) => {
+                record.consecutive_failures += 1;
+                record.last_error = Some(error);
+                if record.consecutive_failures >= DOWN_AFTER {
+                    record.down_until_ms = Some(now + DOWN_COOLDOWN.as_millis() as u64);
+                }
+            }
+        }
+        let after = record.state(now);
+        if after != before {
+            match after {
+                HealthState::Healthy => tracing::info!("backend {provider} is healthy again"),
+                state => tracing::warn!(
+                    "backend {provider} is {} after {} consecutive failures",
+                    state.as_str(),
+                    record.consecutive_failures
+                ),
+            }
+        }
+    }
+
+    /// Current state of `provider`; healthy if it has never been called.
+    pub fn state(&self, provider: &str) -> HealthState {
+        let records = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        records
+            .get(provider)
+            .map_or(HealthState::Healthy, |r| r.state(now_ms()))
+    }
+
+    /// Health of each of `providers`, sorted and deduplicated.
+    pub fn report<'a>(&self, providers: impl IntoIterator<Item = &'a str>) -> Vec<BackendHealth> {
+        self.report_at(providers, now_ms())
+    }
+
+    fn report_at<'a>(
+        &self,
+        providers: impl IntoIterator<Item = &'a str>,
+        now: u64,
+    ) -> Vec<BackendHealth> {
+        let records = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<&str> = providers.into_iter().collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|provider| {
+                let record = records.get(provider).cloned().unwrap_or_default();
+                let state = record.state(now);
+                BackendHealth {
+                    provider: provider.to_string(),
+                    state,
+                    consecutive_failures: record.consecutive_failures,
+                    retry_in_secs: record
+                        .down_until_ms
+                        .filter(|_| state == HealthState::Down)
+                        .map(|until| (until - now).div_ceil(1000)),
+                    last_error: record.last_error,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed() -> Option<Option<String>> {
+        Some(Some("request timed out after 30000ms".to_string()))
+    }
+
+    #[test]
+    fn failures_walk_healthy_degraded_down_and_back() {
+        let health = HealthTracker::default();
+        let at = |now| health.report_at(["xai"], now)[0].state;
+
+        health.record_at("xai", failed(), 1_000);
+        assert_eq!(at(1_000), HealthState::Healthy);
+        health.record_at("xai", failed(), 2_000);
+        assert_eq!(at(2_000), HealthState::Degraded);
+        // Neutral outcomes (rate limits, cutoffs) leave the count alone.
+        health.record_at("xai", None, 2_500);
+        for now in [3_000, 4_000, 5_000] {
+            health.record_at("xai", failed(), now);
+        }
+        let report = health.report_at(["xai"], 6_000);
+        assert_eq!(report[0].state, HealthState::Down);
+        assert_eq!(report[0].consecutive_failures, 5);
+        assert_eq!(report[0].retry_in_secs, Some(59));
+        assert_eq!(
+            report[0].describe(),
+            "xai: down, probed again in 59s (5 consecutive failures; \
+             last: request timed out after 30000ms)"
+        );
+
+        // After the cooldown the next call probes it.
+        let probe_at = 5_000 + DOWN_COOLDOWN.as_millis() as u64;
+        assert_eq!(at(probe_at), HealthState::Degraded);
+        health.record_at("xai", failed(), probe_at);
+        assert_eq!(at(probe_at + 1), HealthState::Down);
+        health.record_at("xai", Some(None), probe_at + 2);
+        assert_eq!(at(probe_at + 2), HealthState::Healthy);
+        assert_eq!(health.report_at(["xai"], probe_at + 2)[0].last_error, None);
+    }
+
+    #[test]
+    fn only_backend_failures_count() {
+        let rate_limited: Result<(), _> = Err(SquallError::RateLimited {
+            provider: "xai".into(),
+        });
+        let bad_request: Result<(), _> = Err(SquallError::Upstream {
+            provider: "xai".into(),
+            message: "context too long".into(),
+            status: Some(400),
+        });
+        let unavailable: Result<(), _> = Err(SquallError::Upstream {
+            provider: "xai".into(),
+            message: "overloaded".into(),
+            status: Some(503),
+        });
+        assert_eq!(failure_of(&rate_limited), None);
+        assert_eq!(failure_of(&bad_request), None);
+        assert_eq!(failure_of(&Err::<(), _>(SquallError::Cancelled(5))), None);
+        assert!(matches!(failure_of(&unavailable), Some(Some(_))));
+        assert_eq!(failure_of(&Ok::<(), SquallError>(())), Some(None));
+    }
+}
@@ -1,16 +1,68 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use eventsource_stream::Eventsource;
 use futures_util::StreamExt;
 use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::Deserialize;
 
+use crate::dispatch::dns::DnsServerResolver;
+use crate::dispatch::ratelimit::{MAX_RATE_LIMIT_WAIT, RateLimitStore, RateLimitWindow, now_ms};
 use crate::dispatch::registry::ApiFormat;
-use crate::dispatch::{ProviderRequest, ProviderResult};
+use crate::dispatch::signing::RequestSigner;
+use crate::dispatch::{
+    DispatchTiming, ProviderMetadata, ProviderRequest, ProviderResult, TokenUsage, output_cut,
+};
 use crate::error::SquallError;
 
 pub const MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024; // 2MB
 
+/// Payload keys `extra_body` cannot override: the prompt and the streaming
+/// contract the SSE parser depends on.
+const RESERVED_BODY_KEYS: &[&str] = &["model", "messages", "stream"];
+
+/// Deep-merge `patch` into `target`: objects merge key by key, a `null` value
+/// removes the key, and anything else replaces the target value.
+pub fn merge_json(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(key);
+                } else {
+                    merge_json(
+                        target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                        value,
+                    );
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+/// Merge a request's `extra_body` into the outgoing payload. Reserved keys are
+/// skipped with a warning; everything else (including `temperature` or
+/// `max_tokens`) is the caller's to override.
+pub fn merge_extra_body(body: &mut serde_json::Value, extra: &serde_json::Value, provider: &str) {
+    let Some(extra) = extra.as_object() else {
+        tracing::warn!(provider, "extra_body is not a JSON object; ignored");
+        return;
+    };
+    let mut allowed = serde_json::Map::new();
+    for (key, value) in extra {
+        if RESERVED_BODY_KEYS.contains(&key.as_str()) {
+            tracing::warn!(provider, "extra_body key '{key}' is reserved; ignored");
+        } else {
+            allowed.insert(key.clone(), value.clone());
+        }
+    }
+    merge_json(body, &serde_json::Value::Object(allowed));
+}
+
 /// Default duration without any SSE chunk before returning partial result.
 const STALL_TIMEOUT: Duration = Duration::from_secs(60);
 
@@ -47,8 +99,104 @@ pub fn first_byte_timeout_for(reasoning_effort: Option<&str>) -> Duration {
 /// Maximum time to wait for response headers after sending the request.
 pub const HEADERS_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Connection tuning for one provider's HTTP client (`[providers.*]` config).
+/// Unset fields keep the shared client's defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpClientSettings {
+    /// Idle connections kept open per host.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle pooled connection is kept before closing.
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Interval of HTTP/2 keep-alive pings, which also run while the connection
+    /// is idle so the pool stays warm between fan-outs.
+    pub http2_keep_alive_secs: Option<u64>,
+    /// TCP keep-alive probe interval.
+    pub tcp_keepalive_secs: Option<u64>,
+    /// Disable Nagle's algorithm (default true).
+    pub tcp_nodelay: Option<bool>,
+    /// Fixed addresses for the provider's host, bypassing DNS.
+    pub resolve: Option<ResolveOverride>,
+    /// DNS server to resolve hosts with instead of the system resolver.
+    pub dns_server: Option<SocketAddr>,
+    /// Headers sent with every request (e.g. `OpenAI-Organization`, gateway
+    /// config or tenant IDs). Headers a request sets itself take precedence.
+    pub extra_headers: BTreeMap<String, String>,
+}
+
+/// Pins `host` to `addrs` (e.g. an internal gateway whose public name doesn't
+/// resolve from inside the network). Connections use the URL's port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolveOverride {
+    pub host: String,
+    pub addrs: Vec<IpAddr>,
+}
+
+/// Shared client defaults.
+const POOL_MAX_IDLE_PER_HOST: usize = 4;
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Catalog bytes a warm-up ping reads. A larger catalog is abandoned with
+/// its connection; the ping has still done the DNS, TLS and auth work.
+const PING_BODY_BYTES: usize = 256 * 1024;
+
+impl HttpClientSettings {
+    /// Build a client with these settings over the shared defaults. Err names
+    /// the setting that can't be applied, such as an invalid extra header.
+    pub fn build_client(&self) -> Result<Client, String> {
+        let mut builder = Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .pool_idle_timeout(
+                self.pool_idle_timeout_secs
+                    .map_or(POOL_IDLE_TIMEOUT, Duration::from_secs),
+            )
+            .pool_max_idle_per_host(
+                self.pool_max_idle_per_host
+                    .unwrap_or(POOL_MAX_IDLE_PER_HOST),
+            )
+            .tcp_keepalive(
+                self.tcp_keepalive_secs
+                    .map_or(TCP_KEEPALIVE, Duration::from_secs),
+            )
+            .tcp_nodelay(self.tcp_nodelay.unwrap_or(true));
+        if let Some(secs) = self.http2_keep_alive_secs {
+            builder = builder
+                .http2_keep_alive_interval(Duration::from_secs(secs))
+                .http2_keep_alive_timeout(Duration::from_secs(secs.clamp(5, 20)))
+                .http2_keep_alive_while_idle(true);
+        }
+        if let Some(ref pin) = self.resolve {
+            let addrs: Vec<SocketAddr> =
+                pin.addrs.iter().map(|ip| SocketAddr::new(*ip, 0)).collect();
+            builder = builder.resolve_to_addrs(&pin.host, &addrs);
+        }
+        if !self.extra_headers.is_empty() {
+            let mut headers = HeaderMap::new();
+            for (name, value) in &self.extra_headers {
+                let header = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| format!("extra_headers: invalid header name '{name}'"))?;
+                let value = HeaderValue::from_str(value)
+                    .map_err(|_| format!("extra_headers: invalid value for '{name}'"))?;
+                headers.insert(header, value);
+            }
+            builder = builder.default_headers(headers);
+        }
+        if let Some(server) = self.dns_server {
+            builder = builder.dns_resolver(Arc::new(DnsServerResolver::new(server)));
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
 pub struct HttpDispatch {
     client: Client,
+    /// Long-lived clients for providers with their own `HttpClientSettings`;
+    /// other providers share `client`.
+    provider_clients: HashMap<String, Client>,
+    /// Per-provider request signers (see `crate::dispatch::signing`).
+    signers: HashMap<String, Arc<dyn RequestSigner>>,
+    /// Shared per-provider windows. None = no cross-process coordination.
+    rate_limits: Option<RateLimitStore>,
 }
 
 /// SSE streaming chunk from OpenAI chat completions API.
@@ -142,14 +290,154 @@ impl ParsedChunk {
 #[allow(clippy::new_without_default)]
 impl HttpDispatch {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .connect_timeout(Duration::from_secs(10))
-            .pool_idle_timeout(Duration::from_secs(90))
-            .pool_max_idle_per_host(4)
-            .build()
+        let client = HttpClientSettings::default()
+            .build_client()
             .expect("failed to build HTTP client");
 
-        Self { client }
+        Self {
+            client,
+            provider_clients: HashMap::new(),
+            signers: HashMap::new(),
+            rate_limits: None,
+        }
+    }
+
+    /// Give each provider in `settings` its own client. `Config::load` builds each
+    /// one first and skips the models of a provider whose client can't be built
+    /// (reported by `doctor`); settings from elsewhere that fail here log an error
+    /// and leave the provider on the shared client.
+    pub fn with_client_settings(mut self, settings: &HashMap<String, HttpClientSettings>) -> Self {
+        for (provider, s) in settings {
+            match s.build_client() {
+                Ok(client) => {
+                    self.provider_clients.insert(provider.clone(), client);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "provider {provider}: HTTP client settings not applied, \
+                         using the shared client: {e}"
+                    );
+                }
+            }
+        }
+        self
+    }
+
+    /// Sign every request to `provider` with `signer`.
+    pub fn with_signer(mut self, provider: &str, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signers.insert(provider.to_string(), signer);
+        self
+    }
+
+    fn client_for(&self, provider: &str) -> &Client {
+        self.provider_clients.get(provider).unwrap_or(&self.client)
+    }
+
+    /// Warm `provider`'s pooled connection (DNS, TCP, TLS, and a signer's token)
+    /// with a `GET` of its model catalog, which costs no tokens. Only outcomes
+    /// that say the backend is broken are errors: no connection, a rejected key,
+    /// or a 5xx. Any other status (a provider without a catalog answers 404)
+    /// still warmed the connection.
+    pub async fn ping(
+        &self,
+        provider: &str,
+        base_url: &str,
+        api_key: &str,
+        api_format: &ApiFormat,
+        timeout: Duration,
+    ) -> Result<(), SquallError> {
+        let start = Instant::now();
+        let url = crate::discover::models_url(base_url);
+        let builder = match api_format {
+            ApiFormat::OpenAi => self.client_for(provider).get(&url).bearer_auth(api_key),
+            ApiFormat::Anthropic => self
+                .client_for(provider)
+                .get(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", "2023-06-01"),
+        };
+        let mut request = builder.build()?;
+        let ping = async {
+            if let Some(signer) = self.signers.get(provider) {
+                let headers =
+                    signer
+                        .sign(&request)
+                        .await
+                        .map_err(|message| SquallError::AuthFailed {
+                            provider: provider.to_string(),
+                            message,
+                        })?;
+                for (name, value) in headers {
+                    request.headers_mut().insert(name, value);
+                }
+            }
+            let mut response = self.client_for(provider).execute(request).await?;
+            // Read a small body to the end so the connection returns to the pool.
+            let _ = Self::stream_body_capped(&mut response, PING_BODY_BYTES).await;
+            Ok::<_, SquallError>(response.status())
+        };
+        let status = tokio::time::timeout(timeout, ping)
+            .await
+            .map_err(|_| SquallError::Timeout(start.elapsed().as_millis() as u64))??;
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(SquallError::AuthFailed {
+                provider: provider.to_string(),
+                message: format!("{status}"),
+            });
+        }
+        if status.is_server_error() {
+            return Err(SquallError::Upstream {
+                provider: provider.to_string(),
+                message: format!("{status}"),
+                status: Some(status.as_u16()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Coordinate provider rate limits with other processes through `store`.
+    pub fn with_rate_limits(mut self, store: RateLimitStore) -> Self {
+        self.rate_limits = Some(store);
+        self
+    }
+
+    /// Wait out a provider window another request found exhausted, or fail fast
+    /// if it won't reset within `MAX_RATE_LIMIT_WAIT` and the deadline.
+    async fn wait_for_rate_limit(
+        &self,
+        req: &ProviderRequest,
+        provider: &str,
+        start: Instant,
+    ) -> Result<(), SquallError> {
+        let Some(store) = &self.rate_limits else {
+            return Ok(());
+        };
+        let Some(wait) = store.wait_for(provider).await else {
+            return Ok(());
+        };
+        let remaining = req.deadline.saturating_duration_since(Instant::now());
+        if wait > MAX_RATE_LIMIT_WAIT || wait >= remaining {
+            return Err(SquallError::RateLimited {
+                provider: provider.to_string(),
+            });
+        }
+        tracing::info!(
+            provider,
+            wait_ms = wait.as_millis() as u64,
+            "rate limits: waiting for shared window reset"
+        );
+        match &req.cancellation_token {
+            Some(token) => tokio::select! {
+                _ = tokio::time::sleep(wait) => Ok(()),
+                _ = token.cancelled() => {
+                    Err(SquallError::Cancelled(start.elapsed().as_millis() as u64))
+                }
+            },
+            None => {
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+        }
     }
 
     /// Read response body in chunks, stopping at `max_bytes`.
@@ -185,6 +473,7 @@ impl HttpDispatch {
         api_format: &ApiFormat,
     ) -> Result<ProviderResult, SquallError> {
         let start = Instant::now();
+        self.wait_for_rate_limit(req, provider, start).await?;
 
         // Check for expired deadline before making the request
         let remaining = req
@@ -193,7 +482,7 @@ impl HttpDispatch {
             .filter(|d| *d > Duration::from_millis(100))
             .ok_or(SquallError::Timeout(0))?;
 
-        let (body, request_builder) = match api_format {
+        let (mut body, request_builder) = match api_format {
             ApiFormat::OpenAi => {
                 let mut messages = Vec::new();
                 if let Some(ref system) = req.system_prompt {
@@ -223,9 +512,14 @@ impl HttpDispatch {
                 if let Some(ref effort) = req.reasoning_effort {
                     body["reasoning"] = serde_json::json!({"effort": effort});
                 }
+                // OpenAI only streams token usage on request; most compatible
+                // providers send it in the last chunk unasked.
+                if provider == "openai" {
+                    body["stream_options"] = serde_json::json!({"include_usage": true});
+                }
 
                 let builder = self
-                    .client
+                    .client_for(provider)
                     .post(base_url)
                     .header("Authorization", format!("Bearer {}", api_key))
                     .header("Content-Type", "application/json");
@@ -248,7 +542,7 @@ impl HttpDispatch {
                 }
 
                 let builder = self
-                    .client
+                    .client_for(provider)
                     .post(base_url)
                     .header("x-api-key", api_key)
                     .header("anthropic-version", "2023-06-01")
@@ -256,20 +550,43 @@ impl HttpDispatch {
                 (body, builder)
             }
         };
+        if let Some(ref extra) = req.extra_body {
+            merge_extra_body(&mut body, extra, provider);
+        }
 
         // [FIX #2] Scoped timeout around send() only — prevents hanging on headers.
         // Client-level connect_timeout(10s) handles TCP/TLS; this covers the gap
         // between connection and first response header.
+        let mut request = request_builder.json(&body).build()?;
+        if let Some(signer) = self.signers.get(provider) {
+            let headers = tokio::time::timeout(remaining, signer.sign(&request))
+                .await
+                .map_err(|_| SquallError::Timeout(start.elapsed().as_millis() as u64))?
+                .map_err(|message| SquallError::AuthFailed {
+                    provider: provider.to_string(),
+                    message,
+                })?;
+            for (name, value) in headers {
+                request.headers_mut().insert(name, value);
+            }
+        }
         let headers_timeout = remaining.min(HEADERS_TIMEOUT);
-        let send_future = request_builder.json(&body).send();
+        let send_future = self.client_for(provider).execute(request);
 
         let mut response = tokio::time::timeout(headers_timeout, send_future)
             .await
             .map_err(|_| SquallError::Timeout(start.elapsed().as_millis() as u64))?
             .map_err(SquallError::from)?;
 
+        let headers_at = Instant::now();
         let status = response.status();
 
+        if let Some(store) = &self.rate_limits
+            && let Some(window) = RateLimitWindow::from_headers(response.headers(), now_ms())
+        {
+            store.record(provider, &window).await;
+        }
+
         if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
             return Err(SquallError::RateLimited {
                 provider: provider.to_string(),
@@ -336,7 +653,7 @@ impl HttpDispatch {
         }
 
         // Success — read SSE stream
-        self.read_sse_stream(response, req, provider, start, api_format)
+        self.read_sse_stream(response, req, provider, start, headers_at, api_format)
             .await
     }
 
@@ -355,9 +672,18 @@ impl HttpDispatch {
         req: &ProviderRequest,
         provider: &str,
         start: Instant,
+        headers_at: Instant,
         api_format: &ApiFormat,
     ) -> Result<ProviderResult, SquallError> {
+        let mut metadata = ProviderMetadata {
+            request_id: request_id(response.headers()),
+            ..Default::default()
+        };
         let mut stream = response.bytes_stream().eventsource();
+        // Per-model cap, never above the global memory guard.
+        let output_cap = req
+            .max_output_bytes
+            .map_or(MAX_RESPONSE_BYTES, |cap| cap.min(MAX_RESPONSE_BYTES));
         let mut accumulated = String::new();
 
         // [FIX #3] Safe Instant conversion: compute remaining duration from std::time::Instant,
@@ -394,6 +720,13 @@ impl HttpDispatch {
         tokio::pin!(cancel_fut);
 
         let mut received_first = false;
+        // Token counts, from whichever events carry them (see `merge_usage`).
+        let mut usage: Option<TokenUsage> = None;
+        // Wall-clock mark of the first data event, for the TTFB/streaming split.
+        let mut first_byte_at: Option<Instant> = None;
+        let timing = |first_byte_at: Option<Instant>| {
+            DispatchTiming::from_marks(start, Some(headers_at), first_byte_at, Instant::now())
+        };
         let mut last_chunk_at = tokio::time::Instant::now();
 
         // Pin the deadline sleep outside the loop — reset() reuses the timer
@@ -422,8 +755,13 @@ impl HttpDispatch {
                     return Ok(ProviderResult {
                         text: accumulated,
                         partial: true,
+                        output_capped: false,
                         model: req.model.clone(),
                         provider: provider.to_string(),
+                        timing: timing(first_byte_at),
+                        usage,
+                        secrets_redacted: Vec::new(),
+                        provider_metadata: metadata,
                     });
                 }
                 _ = &mut deadline_sleep => {
@@ -433,30 +771,50 @@ impl HttpDispatch {
                     return Ok(ProviderResult {
                         text: accumulated,
                         partial: true,
+                        output_capped: false,
                         model: req.model.clone(),
                         provider: provider.to_string(),
+                        timing: timing(first_byte_at),
+                        usage,
+                        secrets_redacted: Vec::new(),
+                        provider_metadata: metadata,
                     });
                 }
                 event = stream.next() => match event {
                     Some(Ok(ev)) => {
+                        if ev.data.contains("\"usage\"") {
+                            merge_usage(&mut usage, &ev.data);
+                        }
+                        // IDs and the served model come with the first events.
+                        if accumulated.is_empty() {
+                            merge_metadata(&mut metadata, &ev.data);
+                        }
                         match parse_sse_event(&ev.data, api_format) {
                             ParsedChunk::Done => break,
                             ParsedChunk::Text(text) => {
                                 received_first = true;
+                                first_byte_at.get_or_insert_with(Instant::now);
                                 last_chunk_at = tokio::time::Instant::now();
-                                if accumulated.len() + text.len() > MAX_RESPONSE_BYTES {
-                                    // Return accumulated text as partial instead of
-                                    // discarding it — consistent with stream error path.
+                                if accumulated.len() + text.len() > output_cap {
+                                    // Stop reading and return what fits as partial instead
+                                    // of discarding it — consistent with stream error path.
+                                    accumulated.push_str(&text);
+                                    accumulated.truncate(output_cut(&accumulated, output_cap));
                                     tracing::warn!(
                                         provider,
                                         bytes = accumulated.len(),
-                                        "SSE response exceeded {MAX_RESPONSE_BYTES}B, returning partial"
+                                        "SSE response exceeded {output_cap}B output cap, returning partial"
                                     );
                                     return Ok(ProviderResult {
                                         text: accumulated,
                                         partial: true,
+                                        output_capped: true,
                                         model: req.model.clone(),
                                         provider: provider.to_string(),
+                                        timing: timing(first_byte_at),
+                                        usage,
+                                        secrets_redacted: Vec::new(),
+                                        provider_metadata: metadata,
                                     });
                                 }
                                 accumulated.push_str(&text);
@@ -480,8 +838,13 @@ impl HttpDispatch {
                                 return Ok(ProviderResult {
                                     text: accumulated,
                                     partial: true,
+                                    output_capped: false,
                                     model: req.model.clone(),
                                     provider: provider.to_string(),
+                                    timing: timing(first_byte_at),
+                                    usage,
+                                    secrets_redacted: Vec::new(),
+                                    provider_metadata: metadata,
                                 });
                             }
                             ParsedChunk::Skip => {
@@ -489,6 +852,7 @@ impl HttpDispatch {
                                 // is alive: switch from first-byte to stall timer, and
                                 // reset the stall timer to prevent false timeouts.
                                 received_first = true;
+                                first_byte_at.get_or_insert_with(Instant::now);
                                 last_chunk_at = tokio::time::Instant::now();
                             }
                         }
@@ -509,8 +873,13 @@ impl HttpDispatch {
                         return Ok(ProviderResult {
                             text: accumulated,
                             partial: true,
+                            output_capped: false,
                             model: req.model.clone(),
                             provider: provider.to_string(),
+                            timing: timing(first_byte_at),
+                            usage,
+                            secrets_redacted: Vec::new(),
+                            provider_metadata: metadata,
                         });
                     }
                     None => {
@@ -530,8 +899,13 @@ impl HttpDispatch {
                         return Ok(ProviderResult {
                             text: accumulated,
                             partial: true,
+                            output_capped: false,
                             model: req.model.clone(),
                             provider: provider.to_string(),
+                            timing: timing(first_byte_at),
+                            usage,
+                            secrets_redacted: Vec::new(),
+                            provider_metadata: metadata,
                         });
                     }
                 },
@@ -549,8 +923,13 @@ impl HttpDispatch {
         Ok(ProviderResult {
             text: accumulated,
             partial: false,
+            output_capped: false,
             model: req.model.clone(),
             provider: provider.to_string(),
+            timing: timing(first_byte_at),
+            usage,
+            secrets_redacted: Vec::new(),
+            provider_metadata: metadata,
         })
     }
 }
@@ -563,6 +942,92 @@ fn parse_sse_event(data: &str, api_format: &ApiFormat) -> ParsedChunk {
     }
 }
 
+/// Token counts in an SSE event, under either provider's field names.
+#[derive(Deserialize)]
+struct UsageEvent {
+    usage: Option<UsageCounts>,
+    /// Anthropic `message_start` nests usage in the message.
+    message: Option<UsageMessage>,
+}
+
+#[derive(Deserialize)]
+struct UsageMessage {
+    usage: Option<UsageCounts>,
+}
+
+#[derive(Deserialize)]
+struct UsageCounts {
+    /// OpenAI-compatible.
+    prompt_tokens: Option<u64>,
+    completion_tokens: Option<u64>,
+    /// Anthropic.
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+}
+
+/// Response headers that carry the provider's request ID, in preference order.
+const REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "request-id", "x-amzn-requestid"];
+
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    REQUEST_ID_HEADERS.iter().find_map(|name| {
+        let value = headers.get(*name)?.to_str().ok()?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Response identity fields in an SSE event. OpenAI-compatible chunks carry them
+/// at the top level; Anthropic's `message_start` nests them in the message.
+#[derive(Deserialize)]
+struct MetadataEvent {
+    id: Option<String>,
+    model: Option<String>,
+    system_fingerprint: Option<String>,
+    message: Option<MetadataMessage>,
+}
+
+#[derive(Deserialize)]
+struct MetadataMessage {
+    id: Option<String>,
+    model: Option<String>,
+}
+
+/// Fill unset `metadata` fields from an SSE event. First value seen wins.
+fn merge_metadata(metadata: &mut ProviderMetadata, data: &str) {
+    let Ok(event) = serde_json::from_str::<MetadataEvent>(data) else {
+        return;
+    };
+    let (message_id, message_model) = event.message.map_or((None, None), |m| (m.id, m.model));
+    let non_empty = |v: Option<String>| v.filter(|s| !s.trim().is_empty());
+    if metadata.response_id.is_none() {
+        metadata.response_id = non_empty(event.id.or(message_id));
+    }
+    if metadata.served_model.is_none() {
+        metadata.served_model = non_empty(event.model.or(message_model));
+    }
+    if metadata.system_fingerprint.is_none() {
+        metadata.system_fingerprint = non_empty(event.system_fingerprint);
+    }
+}
+
+/// Fold the usage in an SSE event into `usage`. OpenAI-compatible APIs report it
+/// once, in the last chunk; Anthropic reports input tokens in `message_start` and
+/// the running output count in `message_delta`.
+fn merge_usage(usage: &mut Option<TokenUsage>, data: &str) {
+    let Ok(event) = serde_json::from_str::<UsageEvent>(data) else {
+        return;
+    };
+    let Some(counts) = event.usage.or_else(|| event.message.and_then(|m| m.usage)) else {
+        return;
+    };
+    let total = usage.get_or_insert_with(TokenUsage::default);
+    if let Some(input) = counts.prompt_tokens.or(counts.input_tokens) {
+        total.input_tokens = input;
+    }
+    if let Some(output) = counts.completion_tokens.or(counts.output_tokens) {
+        total.output_tokens = output;
+    }
+}
+
 /// Parse an OpenAI chat completions SSE event.
 fn parse_openai_event(data: &str) -> ParsedChunk {
     if data.trim() == "[DONE]" {
@@ -0,0 +1,169 @@
+//! Per-provider API key validity, learned from dispatch outcomes.
+//!
+//! Each successful call records when the provider's key last authenticated.
+//! A 401/403 marks the provider degraded until a call succeeds again, so a
+//! review can lead with one warning ("the xai key stopped working at ...")
+//! instead of leaving callers to decode an auth error per model.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::dispatch::ratelimit::now_ms;
+use crate::error::SquallError;
+use crate::memory::iso_from_epoch_ms;
+
+/// What the registry knows about one provider's key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct KeyState {
+    /// Unix ms of the last call that authenticated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_ok_ms: Option<u64>,
+    /// Unix ms of the first auth failure since the last success. Set = degraded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failing_since_ms: Option<u64>,
+    /// Auth failures since the last success.
+    pub failures: u32,
+    /// The most recent auth error, as the provider worded it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl KeyState {
+    pub fn is_degraded(&self) -> bool {
+        self.failing_since_ms.is_some()
+    }
+
+    /// Warning for a degraded key, e.g. "API key for xai is being rejected
+    /// (3 auth failures since 2026-10-16T09:12:00Z, last worked
+    /// 2026-10-15T18:40:02Z): 401 Unauthorized. ...".
+    pub fn warning(&self, provider: &str) -> String {
+        let mut msg = format!(
+            "API key for {provider} is being rejected ({} auth failure{}",
+            self.failures,
+            if self.failures == 1 { "" } else { "s" }
+        );
+        if let Some(since) = self.failing_since_ms {
+            msg.push_str(&format!(" since {}", iso_from_epoch_ms(since as i64)));
+        }
+        match self.last_ok_ms {
+            Some(ok) => msg.push_str(&format!(", last worked {}", iso_from_epoch_ms(ok as i64))),
+            None => msg.push_str(", never worked this session"),
+        }
+        msg.push(')');
+        if let Some(e) = &self.last_error {
+            msg.push_str(&format!(": {e}"));
+        }
+        msg.push_str(
+            ". Its models will keep failing until the key is replaced or rotated \
+             and Squall restarted",
+        );
+        msg
+    }
+}
+
+/// Key state for every provider that has been called.
+#[derive(Debug, Default)]
+pub struct KeyHealth(Mutex<HashMap<String, KeyState>>);
+
+impl KeyHealth {
+    /// Update `provider` from a call's outcome. Only successes and auth
+    /// failures count; a timeout says nothing about the key.
+    pub fn record<T>(&self, provider: &str, result: &Result<T, SquallError>) {
+        self.record_at(provider, result, now_ms());
+    }
+
+    fn record_at<T>(&self, provider: &str, result: &Result<T, SquallError>, now: u64) {
+        let mut states = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(_) => {
+                let state = states.entry(provider.to_string()).or_default();
+                if state.is_degraded() {
+                    tracing::info!("API key for {provider} authenticates again");
+                }
+                *state = KeyState {
+                    last_ok_ms: Some(now),
+                    ..KeyState::default()
+                };
+            }
+            Err(SquallError::AuthFailed { message, .. }) => {
+                let state = states.entry(provider.to_string()).or_default();
+                if !state.is_degraded() {
+                    tracing::warn!("API key for {provider} rejected, marking it degraded");
+                }
+                state.failing_since_ms.get_or_insert(now);
+                state.failures += 1;
+                state.last_error = Some(message.clone());
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// State of `provider`'s key, if it has been called.
+    pub fn get(&self, provider: &str) -> Option<KeyState> {
+        let states = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        states.get(provider).cloned()
+    }
+
+    /// Degraded providers among `providers`, sorted and deduplicated.
+    pub fn degraded<'a>(
+        &self,
+        providers: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<(String, KeyState)> {
+        let states = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<(String, KeyState)> = providers
+            .into_iter()
+            .filter_map(|p| {
+                let state = states.get(p).filter(|s| s.is_degraded())?;
+                Some((p.to_string(), state.clone()))
+            })
+            .collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out.dedup_by(|a, b| a.0 == b.0);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_failed(message: &str) -> Result<(), SquallError> {
+        Err(SquallError::AuthFailed {
+            provider: "xai".into(),
+            message: message.into(),
+        })
+    }
+
+    #[test]
+    fn auth_failures_degrade_until_a_success() {
+        let health = KeyHealth::default();
+        health.record_at("xai", &Ok::<(), SquallError>(()), 1_000);
+        health.record_at("xai", &auth_failed("401 Unauthorized"), 2_000);
+        health.record_at("xai", &Err::<(), _>(SquallError::Timeout(5)), 2_500);
+        health.record_at("xai", &auth_failed("401 Unauthorized: key revoked"), 3_000);
+
+        let state = health.get("xai").unwrap();
+        assert_eq!(state.failures, 2);
+        assert_eq!(state.failing_since_ms, Some(2_000));
+        assert_eq!(state.last_ok_ms, Some(1_000));
+        assert_eq!(
+            health.degraded(["xai", "together", "xai"]),
+            vec![("xai".to_string(), state.clone())]
+        );
+        let warning = state.warning("xai");
+        assert!(
+            warning.starts_with(
+                "API key for xai is being rejected (2 auth failures since \
+                 1970-01-01T00:00:02Z, last worked 1970-01-01T00:00:01Z): \
+                 401 Unauthorized: key revoked."
+            ),
+            "{warning}"
+        );
+
+        health.record_at("xai", &Ok::<(), SquallError>(()), 4_000);
+        assert!(health.degraded(["xai"]).is_empty());
+        assert_eq!(health.get("xai").unwrap().last_ok_ms, Some(4_000));
+    }
+}
@@ -1,13 +1,26 @@
 pub mod async_poll;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod cli;
+pub mod dns;
+pub mod health;
 pub mod http;
+pub mod keyhealth;
+pub mod pricing;
+pub mod priority;
+pub mod ratelimit;
 pub mod registry;
+pub mod remote;
+pub mod signing;
 
 use std::sync::Arc;
 use std::time::Instant;
 
+use serde::{Deserialize, Serialize};
 use tokio_util::sync::CancellationToken;
 
+use crate::tools::enums::Priority;
+
 /// Internal request type — both HTTP and CLI backends accept this.
 #[derive(Clone)]
 pub struct ProviderRequest {
@@ -29,9 +42,20 @@ pub struct ProviderRequest {
     /// Cooperative cancellation signal from review executor. When cancelled,
     /// streaming backends return accumulated partial text instead of aborting.
     pub cancellation_token: Option<CancellationToken>,
-    /// Override stall timeout for non-reasoning slow models (Kimi, GLM).
+    /// Override stall timeout (deep mode, or a model's configured `stall_timeout_secs`).
     /// Clamped to min(stall_timeout, remaining deadline) at read time.
     pub stall_timeout: Option<std::time::Duration>,
+    /// Output cap in bytes (a model's configured `max_output_bytes`). Streaming
+    /// backends stop reading once it is reached; others are cut after the fact.
+    pub max_output_bytes: Option<usize>,
+    /// Priority for a backend permit when concurrency is saturated (None = normal).
+    pub priority: Option<Priority>,
+    /// JSON object merged into the HTTP request body for provider-specific
+    /// parameters (see `http::merge_extra_body`). Other backends ignore it.
+    pub extra_body: Option<serde_json::Value>,
+    /// Client and tool the call is made for, recorded in the usage ledger
+    /// (`crate::usage`). None = not recorded.
+    pub usage: Option<crate::usage::UsageTag>,
 }
 
 /// Internal result type — all backends return this.
@@ -42,4 +66,157 @@ pub struct ProviderResult {
     pub provider: String,
     /// True if the result was truncated due to cancellation, deadline, or stall.
     pub partial: bool,
+    /// True if the text was cut at the output cap (implies `partial`).
+    pub output_capped: bool,
+    /// Phase breakdown of the dispatch latency. Empty for backends that can't observe it.
+    pub timing: DispatchTiming,
+    /// Tokens the provider reported. None for backends that don't report usage (CLI).
+    pub usage: Option<TokenUsage>,
+    /// Identifiers the provider returned, for support tickets. Empty for non-HTTP backends.
+    pub provider_metadata: ProviderMetadata,
+    /// Kinds of secrets replaced in `text`, one per match (see `crate::secrets`).
+    pub secrets_redacted: Vec<&'static str>,
+}
+
+impl ProviderResult {
+    /// Enforce an output cap on a finished result. No-op when already within it.
+    pub fn cap_output(&mut self, cap: usize) {
+        if self.text.len() > cap {
+            self.text.truncate(output_cut(&self.text, cap));
+            self.partial = true;
+            self.output_capped = true;
+        }
+    }
+
+    /// Replace secrets the model echoed or invented with `[REDACTED:<kind>]`.
+    pub fn redact_secrets(&mut self) {
+        let (text, kinds) = crate::secrets::redact(&self.text);
+        if !kinds.is_empty() {
+            tracing::warn!(
+                "redacted {} from {} output",
+                crate::secrets::summarize(&kinds),
+                self.model
+            );
+            self.text = text;
+            self.secrets_redacted.extend(kinds);
+        }
+    }
+}
+
+/// Byte index at which to cut `text` to fit `cap`: the last line break within the
+/// cap when that keeps at least half of it, so the kept text ends on a whole line;
+/// otherwise the last char boundary.
+pub(crate) fn output_cut(text: &str, cap: usize) -> usize {
+    if text.len() <= cap {
+        return text.len();
+    }
+    let cut = crate::context::floor_char_boundary(text, cap);
+    match text[..cut].rfind('\n') {
+        Some(i) if i + 1 >= cap / 2 => i + 1,
+        _ => cut,
+    }
+}
+
+/// Where a dispatch spent its time, so "slow" can be told apart as queueing
+/// (long TTFB) vs generation (long streaming phase).
+///
+/// All values are milliseconds measured from the start of the dispatch, except
+/// `stream_ms` which is the span from first byte to end of stream and `queue_ms`
+/// which precedes the dispatch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DispatchTiming {
+    /// Time spent waiting for a backend permit under a concurrency limit, not part
+    /// of the other phases or of the model's execution time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_ms: Option<u64>,
+    /// Time until response headers arrived: any TCP/TLS connect (pooled
+    /// connections skip it), sending the request, and upstream accepting it.
+    /// Reviews persisted before the rename call it `connect_ms`.
+    #[serde(default, alias = "connect_ms", skip_serializing_if = "Option::is_none")]
+    pub headers_ms: Option<u64>,
+    /// Time until the first streamed data event (connect + queueing + prefill).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<u64>,
+    /// Time from first data event to end of stream (generation).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_ms: Option<u64>,
+}
+
+impl DispatchTiming {
+    /// Build a breakdown from phase marks taken during a streaming dispatch.
+    pub fn from_marks(
+        start: Instant,
+        headers_at: Option<Instant>,
+        first_byte_at: Option<Instant>,
+        end: Instant,
+    ) -> Self {
+        let ms = |from: Instant, to: Instant| to.saturating_duration_since(from).as_millis() as u64;
+        Self {
+            queue_ms: None,
+            headers_ms: headers_at.map(|t| ms(start, t)),
+            ttfb_ms: first_byte_at.map(|t| ms(start, t)),
+            stream_ms: first_byte_at.map(|t| ms(t, end)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue_ms.is_none()
+            && self.headers_ms.is_none()
+            && self.ttfb_ms.is_none()
+            && self.stream_ms.is_none()
+    }
+}
+
+/// Token counts reported by a provider for one call, or summed over several.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// USD, when the model's prices are known. A sum is only estimated when every
+    /// call in it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<f64>,
+}
+
+impl TokenUsage {
+    /// Sum usage over calls. None when no call reported usage.
+    pub fn sum<'a>(usages: impl IntoIterator<Item = &'a TokenUsage>) -> Option<TokenUsage> {
+        usages.into_iter().fold(None, |total, u| {
+            Some(match total {
+                None => *u,
+                Some(t) => TokenUsage {
+                    input_tokens: t.input_tokens + u.input_tokens,
+                    output_tokens: t.output_tokens + u.output_tokens,
+                    estimated_cost: t.estimated_cost.zip(u.estimated_cost).map(|(a, b)| a + b),
+                },
+            })
+        })
+    }
+}
+
+/// What the provider said about the response it served: the IDs its support
+/// team asks for and the exact model version behind the name requested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderMetadata {
+    /// Request ID from the response headers (`x-request-id`, `request-id`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Response ID from the body (`chatcmpl-...`, `msg_...`, `gen-...`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_id: Option<String>,
+    /// Model the provider reports having served, e.g. a dated snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_model: Option<String>,
+    /// OpenAI-compatible backend configuration fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+}
+
+impl ProviderMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.request_id.is_none()
+            && self.response_id.is_none()
+            && self.served_model.is_none()
+            && self.system_fingerprint.is_none()
+    }
 }
@@ -0,0 +1,62 @@
+//! Per-model token prices for cost estimates.
+//!
+//! Prices are USD per million tokens, input and output. A model's
+//! `price_per_mtok_in`/`price_per_mtok_out` config wins; otherwise the built-in
+//! table below supplies list prices for well-known provider model IDs. Models
+//! with no price (CLI subscriptions, unknown IDs) get no cost estimate.
+
+/// List prices (provider model_id, input $/Mtok, output $/Mtok). Providers
+/// change these; override in config when they drift.
+const DEFAULT_PRICES: &[(&str, f64, f64)] = &[
+    ("grok-4-1-fast-reasoning", 0.20, 0.50),
+    ("grok-4-1-fast-non-reasoning", 0.20, 0.50),
+    ("grok-4", 3.00, 15.00),
+    ("grok-code-fast-1", 0.20, 1.50),
+    ("deepseek-ai/DeepSeek-R1", 3.00, 7.00),
+    ("deepseek-ai/DeepSeek-V3.1", 0.60, 1.70),
+    ("deepseek-chat", 0.28, 0.42),
+    ("deepseek-reasoner", 0.28, 0.42),
+    ("moonshotai/Kimi-K2.5", 0.50, 2.80),
+    ("Qwen/Qwen3-Coder-480B-A35B-Instruct-FP8", 2.00, 2.00),
+    (
+        "meta-llama/Llama-4-Maverick-17B-128E-Instruct-FP8",
+        0.27,
+        0.85,
+    ),
+    ("mistral-large-latest", 0.50, 1.50),
+    ("o3-deep-research", 10.00, 40.00),
+    ("o4-mini-deep-research", 2.00, 8.00),
+];
+
+/// Built-in (input, output) price for a provider model_id.
+pub fn default_price(model_id: &str) -> Option<(f64, f64)> {
+    DEFAULT_PRICES
+        .iter()
+        .find(|(id, _, _)| *id == model_id)
+        .map(|&(_, input, output)| (input, output))
+}
+
+/// USD cost of a call at the given per-million-token prices.
+pub fn cost_usd(input_tokens: u64, output_tokens: u64, price_in: f64, price_out: f64) -> f64 {
+    (input_tokens as f64 * price_in + output_tokens as f64 * price_out) / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_prices_and_cost() {
+        assert_eq!(default_price("o3-deep-research"), Some((10.0, 40.0)));
+        assert!(default_price("gemini").is_none());
+        assert!(
+            DEFAULT_PRICES
+                .iter()
+                .all(|&(_, i, o)| i.is_finite() && i >= 0.0 && o.is_finite() && o >= 0.0)
+        );
+
+        let cost = cost_usd(200_000, 10_000, 3.0, 15.0);
+        assert!((cost - 0.75).abs() < 1e-9, "{cost}");
+        assert_eq!(cost_usd(0, 0, 3.0, 15.0), 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+//! Priority-aware backend permits. Each backend's concurrency limit is a
+//! semaphore; when it is saturated, a dispatch waits for a permit. A waiting
+//! dispatch of higher priority holds back every lower-priority dispatch that has
+//! not started yet — including ones already queued, which step out of the queue
+//! until the higher-priority work has its permits. In-flight calls are never
+//! interrupted, so a background deep review is paused between model calls, not
+//! cut off mid-response.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
+
+use crate::error::SquallError;
+use crate::tools::enums::Priority;
+
+/// A semaphore whose waiters are served by priority, then arrival order.
+pub struct PrioritySemaphore {
+    semaphore: Semaphore,
+    /// Dispatches waiting for a permit, per priority level.
+    waiting: [AtomicUsize; Priority::LEVELS],
+    /// Woken whenever a waiting count changes.
+    changed: Notify,
+}
+
+/// Counts a dispatch as waiting at its level until dropped.
+struct Waiting<'a> {
+    gate: &'a PrioritySemaphore,
+    level: usize,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.gate.waiting[self.level].fetch_sub(1, Ordering::SeqCst);
+        self.gate.changed.notify_waiters();
+    }
+}
+
+impl PrioritySemaphore {
+    pub fn new(permits: usize) -> Self {
+        Self {
+            semaphore: Semaphore::new(permits),
+            waiting: Default::default(),
+            changed: Notify::new(),
+        }
+    }
+
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Dispatches waiting at a priority above `priority`.
+    pub fn waiting_above(&self, priority: Priority) -> usize {
+        self.waiting[priority.level() + 1..]
+            .iter()
+            .map(|n| n.load(Ordering::SeqCst))
+            .sum()
+    }
+
+    /// Acquire a permit by `deadline`, yielding to higher-priority waiters. A
+    /// deadline that passes first is a `QueuedTimeout` carrying the time waited.
+    pub async fn acquire(
+        &self,
+        priority: Priority,
+        deadline: Instant,
+    ) -> Result<SemaphorePermit<'_>, SquallError> {
+        let queued_at = Instant::now();
+        let queued_timeout = || SquallError::QueuedTimeout(queued_at.elapsed().as_millis() as u64);
+        let deadline = tokio::time::Instant::from_std(deadline);
+        let mut yielded = false;
+        loop {
+            // Hold off while anything more urgent is waiting.
+            loop {
+                let changed = self.changed.notified();
+                if self.waiting_above(priority) == 0 {
+                    break;
+                }
+                if !yielded {
+                    tracing::debug!(?priority, "dispatch deferred to higher-priority work");
+                    yielded = true;
+                }
+                tokio::time::timeout_at(deadline, changed)
+                    .await
+                    .map_err(|_| queued_timeout())?;
+            }
+
+            self.waiting[priority.level()].fetch_add(1, Ordering::SeqCst);
+            let _waiting = Waiting {
+                gate: self,
+                level: priority.level(),
+            };
+            self.changed.notify_waiters();
+
+            tokio::select! {
+                permit = self.semaphore.acquire() => {
+                    return permit.map_err(|_| SquallError::Other("semaphore closed".to_string()));
+                }
+                // Step out of the queue when higher-priority work arrives.
+                () = self.outranked(priority) => continue,
+                () = tokio::time::sleep_until(deadline) => return Err(queued_timeout()),
+            }
+        }
+    }
+
+    /// Resolves once a higher-priority dispatch is waiting.
+    async fn outranked(&self, priority: Priority) {
+        loop {
+            let changed = self.changed.notified();
+            if self.waiting_above(priority) > 0 {
+                return;
+            }
+            changed.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn queued_low_priority_yields_to_high() {
+        let sem = Arc::new(PrioritySemaphore::new(1));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let held = sem.acquire(Priority::Normal, deadline).await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let low = {
+            let (sem, tx) = (sem.clone(), tx.clone());
+            tokio::spawn(async move {
+                let _p = sem.acquire(Priority::Low, deadline).await.unwrap();
+                tx.send("low").unwrap();
+            })
+        };
+        // Let the low-priority dispatch queue first.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let high = {
+            let sem = sem.clone();
+            tokio::spawn(async move {
+                let _p = sem.acquire(Priority::High, deadline).await.unwrap();
+                tx.send("high").unwrap();
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(sem.waiting_above(Priority::Low), 1);
+
+        drop(held);
+        high.await.unwrap();
+        low.await.unwrap();
+        assert_eq!(rx.recv().await, Some("high"));
+        assert_eq!(rx.recv().await, Some("low"));
+    }
+
+    #[tokio::test]
+    async fn waiting_dispatch_honors_deadline() {
+        let sem = PrioritySemaphore::new(1);
+        let far = Instant::now() + Duration::from_secs(5);
+        let _held = sem.acquire(Priority::High, far).await.unwrap();
+        let soon = Instant::now() + Duration::from_millis(30);
+        assert!(matches!(
+            sem.acquire(Priority::Low, soon).await,
+            Err(SquallError::QueuedTimeout(ms)) if ms >= 25
+        ));
+    }
+}
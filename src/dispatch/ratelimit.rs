@@ -0,0 +1,316 @@
+//! Shared rate-limit state: per-provider windows parsed from response headers,
+//! persisted under `~/.squall/ratelimits/` so every Squall process on the machine
+//! backs off together instead of each spending the same quota independently.
+//!
+//! One small JSON file per provider, replaced atomically. The latest observation
+//! wins — providers report the current window on every response, so there is
+//! nothing to merge.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+/// Longest wait for a window to reset before failing fast with `RateLimited`.
+/// Longer waits are better spent by the caller on a fallback model.
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+
+/// A provider's rate-limit window as last reported. Times are unix milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitWindow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_reset_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_reset_ms: Option<u64>,
+    /// Backoff demanded by a 429 `retry-after`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl RateLimitWindow {
+    /// Parse rate-limit headers (OpenAI-style `x-ratelimit-*`, Anthropic-style
+    /// `anthropic-ratelimit-*`, and `retry-after`). None if no header is present.
+    pub fn from_headers(headers: &HeaderMap, now_ms: u64) -> Option<Self> {
+        let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let count = |name: &str| get(name).and_then(|v| v.trim().parse::<u64>().ok());
+        // OpenAI reports resets as durations ("6m0s"), Anthropic as RFC 3339 times.
+        let reset = |openai: &str, anthropic: &str| {
+            get(openai)
+                .and_then(parse_go_duration)
+                .map(|d| now_ms + d.as_millis() as u64)
+                .or_else(|| get(anthropic).and_then(parse_rfc3339_ms))
+        };
+
+        let window = Self {
+            requests_remaining: count("x-ratelimit-remaining-requests")
+                .or_else(|| count("anthropic-ratelimit-requests-remaining")),
+            requests_reset_ms: reset(
+                "x-ratelimit-reset-requests",
+                "anthropic-ratelimit-requests-reset",
+            ),
+            tokens_remaining: count("x-ratelimit-remaining-tokens")
+                .or_else(|| count("anthropic-ratelimit-tokens-remaining")),
+            tokens_reset_ms: reset(
+                "x-ratelimit-reset-tokens",
+                "anthropic-ratelimit-tokens-reset",
+            ),
+            retry_after_ms: count("retry-after").map(|secs| now_ms + secs * 1000),
+        };
+        (window != Self::default()).then_some(window)
+    }
+
+    /// When the provider accepts requests again, if it is exhausted as of `now_ms`.
+    pub fn blocked_until(&self, now_ms: u64) -> Option<u64> {
+        let exhausted =
+            |remaining: Option<u64>, reset: Option<u64>| reset.filter(|_| remaining == Some(0));
+        [
+            exhausted(self.requests_remaining, self.requests_reset_ms),
+            exhausted(self.tokens_remaining, self.tokens_reset_ms),
+            self.retry_after_ms,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .filter(|until| *until > now_ms)
+    }
+}
+
+/// On-disk store of per-provider windows.
+#[derive(Debug, Clone)]
+pub struct RateLimitStore {
+    dir: PathBuf,
+}
+
+impl RateLimitStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `~/.squall/ratelimits`, or None without `$HOME`.
+    pub fn default_dir() -> Option<PathBuf> {
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".squall/ratelimits"))
+    }
+
+    fn path(&self, provider: &str) -> PathBuf {
+        let name: String = provider
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Last recorded window for `provider`. Missing or unreadable files read as none.
+    pub async fn load(&self, provider: &str) -> Option<RateLimitWindow> {
+        let raw = tokio::fs::read_to_string(self.path(provider)).await.ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    /// Replace the window for `provider`. Write failures are logged, never fatal.
+    pub async fn record(&self, provider: &str, window: &RateLimitWindow) {
+        if let Err(e) = self.write(&self.path(provider), window).await {
+            tracing::warn!("rate limits: failed to record {provider} window: {e}");
+        }
+    }
+
+    async fn write(&self, path: &Path, window: &RateLimitWindow) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let json = serde_json::to_string(window).map_err(std::io::Error::other)?;
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        tokio::fs::write(&tmp, json).await?;
+        if let Err(e) = tokio::fs::rename(&tmp, path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Time to wait before dispatching to `provider`, if another process (or this
+    /// one) observed the provider's quota exhausted.
+    pub async fn wait_for(&self, provider: &str) -> Option<Duration> {
+        let now = now_ms();
+        let until = self.load(provider).await?.blocked_until(now)?;
+        Some(Duration::from_millis(until - now))
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Parse a Go-style duration as used by OpenAI: "1s", "6m0s", "20ms", "1h2m3.5s".
+fn parse_go_duration(s: &str) -> Option<Duration> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total_ms = 0f64;
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3_600_000.0,
+            "m" => 60_000.0,
+            "s" => 1_000.0,
+            "ms" => 1.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total_ms += value * scale;
+    }
+    Some(Duration::from_millis(total_ms.round() as u64))
+}
+
+/// Parse an RFC 3339 timestamp ("2025-01-01T12:00:30Z", optional fraction and
+/// numeric offset) into unix milliseconds.
+fn parse_rfc3339_ms(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let mut ymd = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (ymd.next()??, ymd.next()??, ymd.next()??);
+
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let split = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..].split_once(':')?;
+        (
+            clock,
+            sign * (oh.parse::<i64>().ok()? * 3600 + om.parse::<i64>().ok()? * 60),
+        )
+    };
+    let mut hms = clock.splitn(3, ':');
+    let hour: i64 = hms.next()?.parse().ok()?;
+    let minute: i64 = hms.next()?.parse().ok()?;
+    let seconds: f64 = hms.next()?.parse().ok()?;
+
+    // Days since the epoch for a proleptic Gregorian date (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 - offset_secs;
+    let ms = secs * 1000 + (seconds * 1000.0) as i64;
+    u64::try_from(ms).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn parses_openai_style_headers() {
+        let window = RateLimitWindow::from_headers(
+            &headers(&[
+                ("x-ratelimit-remaining-requests", "0"),
+                ("x-ratelimit-reset-requests", "1m30s"),
+                ("x-ratelimit-remaining-tokens", "5000"),
+                ("x-ratelimit-reset-tokens", "250ms"),
+            ]),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(window.requests_remaining, Some(0));
+        assert_eq!(window.requests_reset_ms, Some(91_000));
+        assert_eq!(window.tokens_reset_ms, Some(1_250));
+        // Requests exhausted; tokens are not, so their reset doesn't matter.
+        assert_eq!(window.blocked_until(1_000), Some(91_000));
+        assert_eq!(window.blocked_until(91_000), None);
+    }
+
+    #[test]
+    fn parses_anthropic_style_and_retry_after() {
+        let window = RateLimitWindow::from_headers(
+            &headers(&[
+                ("anthropic-ratelimit-tokens-remaining", "0"),
+                ("anthropic-ratelimit-tokens-reset", "2024-01-01T00:01:00Z"),
+                ("retry-after", "5"),
+            ]),
+            1_704_067_200_000,
+        )
+        .unwrap();
+        assert_eq!(window.tokens_reset_ms, Some(1_704_067_260_000));
+        assert_eq!(window.retry_after_ms, Some(1_704_067_205_000));
+        assert_eq!(
+            window.blocked_until(1_704_067_200_000),
+            Some(1_704_067_260_000)
+        );
+    }
+
+    #[test]
+    fn no_headers_no_window() {
+        assert!(RateLimitWindow::from_headers(&HeaderMap::new(), 0).is_none());
+    }
+
+    #[test]
+    fn duration_and_timestamp_parsing() {
+        assert_eq!(parse_go_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_go_duration("1h2m3.5s"),
+            Some(Duration::from_millis(3_723_500))
+        );
+        assert_eq!(parse_go_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_go_duration("soon"), None);
+        assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:01.5Z"), Some(1_500));
+        assert_eq!(parse_rfc3339_ms("1970-01-01T01:00:00+01:00"), Some(0));
+        assert_eq!(parse_rfc3339_ms("not a time"), None);
+    }
+
+    #[tokio::test]
+    async fn store_shares_windows_between_instances() {
+        let dir =
+            std::env::temp_dir().join(format!("squall-test-ratelimits-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let writer = RateLimitStore::new(&dir);
+        let reader = RateLimitStore::new(&dir);
+
+        assert!(reader.wait_for("xai").await.is_none());
+        let window = RateLimitWindow {
+            requests_remaining: Some(0),
+            requests_reset_ms: Some(now_ms() + 10_000),
+            ..Default::default()
+        };
+        writer.record("xai", &window).await;
+        assert_eq!(reader.load("xai").await, Some(window));
+        let wait = reader.wait_for("xai").await.unwrap();
+        assert!(wait > Duration::from_secs(5) && wait <= Duration::from_secs(10));
+        assert!(reader.wait_for("openai").await.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,26 @@
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
-use tokio::sync::Semaphore;
+use futures_util::StreamExt;
+use tokio::sync::SemaphorePermit;
 
-use crate::config::{Config, PersistRawOutput};
+use crate::config::{Config, PersistRawOutput, ProviderEndpoint};
 use crate::dispatch::async_poll::AsyncPollDispatch;
+#[cfg(feature = "chaos")]
+use crate::dispatch::chaos::{ChaosConfig, ChaosInjector};
 use crate::dispatch::cli::CliDispatch;
+use crate::dispatch::health::{BackendHealth, HealthState, HealthTracker};
 use crate::dispatch::http::HttpDispatch;
-use crate::dispatch::{ProviderRequest, ProviderResult};
+use crate::dispatch::keyhealth::{KeyHealth, KeyState};
+use crate::dispatch::priority::PrioritySemaphore;
+use crate::dispatch::ratelimit::RateLimitStore;
+use crate::dispatch::remote::RemoteDispatch;
+use crate::dispatch::{ProviderRequest, ProviderResult, TokenUsage};
 use crate::error::SquallError;
 use crate::parsers::OutputParser;
 use crate::parsers::codex::CodexParser;
 use crate::parsers::gemini::GeminiParser;
+use crate::usage::{UsageLedger, UsageRecord};
 
 /// Max concurrent CLI subprocesses per Squall instance.
 const CLI_MAX_CONCURRENT: usize = 4;
@@ -23,6 +32,16 @@ const HTTP_MAX_CONCURRENT: usize = 8;
 /// Low limit since these are long-running (minutes to an hour).
 const ASYNC_POLL_MAX_CONCURRENT: usize = 4;
 
+/// Warm-up pings in flight at once.
+const WARMUP_CONCURRENCY: usize = 4;
+
+/// Per-provider warm-up ping timeout.
+const WARMUP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Max concurrent requests forwarded to remote workers per Squall instance.
+/// Higher than HTTP: each worker applies its own backend limits.
+const REMOTE_MAX_CONCURRENT: usize = 16;
+
 /// Discriminant for async-poll API providers.
 #[derive(Clone, Debug)]
 pub enum AsyncPollProviderType {
@@ -57,6 +76,8 @@ pub enum BackendConfig {
         provider_type: AsyncPollProviderType,
         api_key: String,
     },
+    /// Forwarded to a remote `squall worker`, which holds the real backend config.
+    Remote { url: String, token: String },
 }
 
 #[derive(Clone)]
@@ -74,6 +95,31 @@ pub struct ModelEntry {
     pub speed_tier: String,
     /// Precision tier: "high", "medium", "low".
     pub precision_tier: String,
+    /// Stall timeout override in seconds for slow-but-reliable models (e.g. OpenRouter-routed
+    /// models that queue before streaming). None = `stall_timeout_for` heuristic.
+    pub stall_timeout_secs: Option<u64>,
+    /// File-context budget in bytes for models whose context window differs from the
+    /// default. Below `MAX_FILE_CONTEXT_BYTES`, review prompts for this model get files
+    /// trimmed to fit. None = `MAX_FILE_CONTEXT_BYTES`.
+    pub context_budget_bytes: Option<usize>,
+    /// Output cap in bytes. A response past it is cut and marked partial with
+    /// `reason: "output_cap"`. None = only the global `MAX_RESPONSE_BYTES`.
+    pub max_output_bytes: Option<usize>,
+    /// Former names of this model (old config keys or provider model_ids). Requests
+    /// using them resolve to this entry, and memory events logged under them count
+    /// toward it.
+    pub aliases: Vec<String>,
+    /// Last day (YYYY-MM-DD) the provider serves this model; `listmodels` warns
+    /// as it approaches.
+    pub deprecated_after: Option<String>,
+    /// USD per million input tokens. Defaults from `pricing::default_price`;
+    /// None = unknown, no cost estimate.
+    pub price_per_mtok_in: Option<f64>,
+    /// USD per million output tokens.
+    pub price_per_mtok_out: Option<f64>,
+    /// JSON object merged into this model's HTTP request body for provider-specific
+    /// parameters. A request's own `extra_body` is merged over it.
+    pub extra_body: Option<serde_json::Value>,
 }
 
 impl ModelEntry {
@@ -82,12 +128,42 @@ impl ModelEntry {
         matches!(self.backend, BackendConfig::AsyncPoll { .. })
     }
 
+    /// Configured stall timeout, if any.
+    pub fn stall_timeout(&self) -> Option<Duration> {
+        self.stall_timeout_secs.map(Duration::from_secs)
+    }
+
+    /// File-context byte budget for this model. Default: `MAX_FILE_CONTEXT_BYTES`.
+    pub fn context_budget(&self) -> usize {
+        self.context_budget_bytes
+            .unwrap_or(crate::context::MAX_FILE_CONTEXT_BYTES)
+    }
+
+    /// Days from `today` (YYYY-MM-DD) until `deprecated_after`; negative once past.
+    /// None if no deprecation date is set.
+    pub fn days_until_deprecation(&self, today: &str) -> Option<i64> {
+        let removal = crate::memory::date_to_days(self.deprecated_after.as_deref()?)?;
+        let today = crate::memory::date_to_days(today)?;
+        Some(removal as i64 - today as i64)
+    }
+
+    /// Estimated USD cost of a call with this usage. None unless both prices are known.
+    pub fn estimate_cost(&self, usage: &TokenUsage) -> Option<f64> {
+        Some(crate::dispatch::pricing::cost_usd(
+            usage.input_tokens,
+            usage.output_tokens,
+            self.price_per_mtok_in?,
+            self.price_per_mtok_out?,
+        ))
+    }
+
     /// Returns the backend type as a string for display purposes.
     pub fn backend_name(&self) -> &'static str {
         match &self.backend {
             BackendConfig::Http { .. } => "http",
             BackendConfig::Cli { .. } => "cli",
             BackendConfig::AsyncPoll { .. } => "async_poll",
+            BackendConfig::Remote { .. } => "remote",
         }
     }
 }
@@ -122,41 +198,157 @@ impl std::fmt::Debug for ModelEntry {
                     .field("provider_type", provider_type)
                     .field("api_key", &"[REDACTED]");
             }
+            BackendConfig::Remote { url, .. } => {
+                s.field("backend", &"remote")
+                    .field("url", url)
+                    .field("token", &"[REDACTED]");
+            }
         }
 
         s.field("description", &self.description)
             .field("speed_tier", &self.speed_tier)
-            .field("precision_tier", &self.precision_tier);
+            .field("precision_tier", &self.precision_tier)
+            .field("stall_timeout_secs", &self.stall_timeout_secs)
+            .field("context_budget_bytes", &self.context_budget_bytes)
+            .field("max_output_bytes", &self.max_output_bytes)
+            .field("aliases", &self.aliases)
+            .field("deprecated_after", &self.deprecated_after)
+            .field("price_per_mtok_in", &self.price_per_mtok_in)
+            .field("price_per_mtok_out", &self.price_per_mtok_out)
+            .field("extra_body", &self.extra_body);
 
         s.finish()
     }
 }
 
+/// An HTTP entry with no endpoint or key, tiers at config's "medium" and no
+/// overrides. Set what matters and fill the rest with `..Default::default()`.
+impl Default for ModelEntry {
+    fn default() -> Self {
+        Self {
+            model_id: String::new(),
+            provider: String::new(),
+            backend: BackendConfig::Http {
+                base_url: String::new(),
+                api_key: String::new(),
+                api_format: ApiFormat::default(),
+            },
+            description: String::new(),
+            strengths: Vec::new(),
+            weaknesses: Vec::new(),
+            speed_tier: "medium".to_string(),
+            precision_tier: "medium".to_string(),
+            stall_timeout_secs: None,
+            context_budget_bytes: None,
+            max_output_bytes: None,
+            aliases: Vec::new(),
+            deprecated_after: None,
+            price_per_mtok_in: None,
+            price_per_mtok_out: None,
+            extra_body: None,
+        }
+    }
+}
+
 pub struct Registry {
     models: HashMap<String, ModelEntry>,
+    /// Alias → config key, from each entry's `aliases`.
+    aliases: HashMap<String, String>,
+    /// HTTP providers with a key set, for `discover_models`.
+    providers: HashMap<String, ProviderEndpoint>,
     http: HttpDispatch,
     cli: CliDispatch,
     async_poll: AsyncPollDispatch,
-    cli_semaphore: Semaphore,
-    http_semaphore: Semaphore,
-    async_poll_semaphore: Semaphore,
+    remote: RemoteDispatch,
+    cli_semaphore: PrioritySemaphore,
+    http_semaphore: PrioritySemaphore,
+    async_poll_semaphore: PrioritySemaphore,
+    remote_semaphore: PrioritySemaphore,
     persist_raw_output: PersistRawOutput,
+    /// Per-provider key validity, updated from every call's outcome.
+    key_health: KeyHealth,
+    /// Per-provider backend health, updated from every call's outcome and from probes.
+    health: HealthTracker,
+    /// Ledger every tagged call is recorded in. None = usage not recorded.
+    usage: Option<UsageLedger>,
+    /// Fault injector for resilience testing. None unless `SQUALL_CHAOS_RATE` is set.
+    #[cfg(feature = "chaos")]
+    chaos: Option<ChaosInjector>,
+}
+
+/// Alias → config key. Aliases that shadow a configured model, or that two
+/// models both claim, are dropped with a warning (keys are visited in sorted order).
+fn alias_map(models: &HashMap<String, ModelEntry>) -> HashMap<String, String> {
+    let mut keys: Vec<&String> = models.keys().collect();
+    keys.sort();
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    for key in keys {
+        for alias in &models[key].aliases {
+            if models.contains_key(alias) {
+                tracing::warn!(
+                    "model {key}: alias '{alias}' is itself a configured model, ignored"
+                );
+            } else if let Some(owner) = aliases.get(alias) {
+                tracing::warn!("model {key}: alias '{alias}' already belongs to {owner}, ignored");
+            } else {
+                aliases.insert(alias.clone(), key.clone());
+            }
+        }
+    }
+    aliases
 }
 
 impl Registry {
     pub fn from_config(config: Config) -> Self {
+        let aliases = alias_map(&config.models);
         Self {
             models: config.models,
-            http: HttpDispatch::new(),
+            aliases,
+            providers: config.providers,
+            http: {
+                let mut http = HttpDispatch::new().with_client_settings(&config.http_clients);
+                for (provider, signer) in &config.signers {
+                    match signer.build() {
+                        Ok(signer) => http = http.with_signer(provider, signer),
+                        Err(e) => {
+                            tracing::warn!("provider {provider}: request signing disabled: {e}")
+                        }
+                    }
+                }
+                match config.rate_limit_dir {
+                    Some(dir) => http.with_rate_limits(RateLimitStore::new(dir)),
+                    None => http,
+                }
+            },
             cli: CliDispatch::new(),
             async_poll: AsyncPollDispatch::new(),
-            cli_semaphore: Semaphore::new(CLI_MAX_CONCURRENT),
-            http_semaphore: Semaphore::new(HTTP_MAX_CONCURRENT),
-            async_poll_semaphore: Semaphore::new(ASYNC_POLL_MAX_CONCURRENT),
+            remote: RemoteDispatch::new(),
+            cli_semaphore: PrioritySemaphore::new(CLI_MAX_CONCURRENT),
+            http_semaphore: PrioritySemaphore::new(HTTP_MAX_CONCURRENT),
+            async_poll_semaphore: PrioritySemaphore::new(ASYNC_POLL_MAX_CONCURRENT),
+            remote_semaphore: PrioritySemaphore::new(REMOTE_MAX_CONCURRENT),
             persist_raw_output: config.persist_raw_output,
+            key_health: KeyHealth::default(),
+            health: HealthTracker::default(),
+            usage: None,
+            #[cfg(feature = "chaos")]
+            chaos: ChaosConfig::from_env().map(|cfg| {
+                tracing::warn!(
+                    rate = cfg.rate,
+                    faults = ?cfg.faults,
+                    "chaos: fault injection enabled"
+                );
+                ChaosInjector::new(cfg)
+            }),
         }
     }
 
+    /// Record calls that carry a `ProviderRequest::usage` tag in `ledger`.
+    pub fn with_usage_ledger(mut self, ledger: UsageLedger) -> Self {
+        self.usage = Some(ledger);
+        self
+    }
+
     /// Returns the number of CLI semaphore permits (for testing).
     pub fn cli_semaphore_permits(&self) -> usize {
         self.cli_semaphore.available_permits()
@@ -167,8 +359,102 @@ impl Registry {
         self.http_semaphore.available_permits()
     }
 
+    /// Look up a model by config key or alias.
     pub fn get(&self, model: &str) -> Option<&ModelEntry> {
-        self.models.get(model)
+        self.models
+            .get(model)
+            .or_else(|| self.models.get(self.aliases.get(model)?))
+    }
+
+    /// The config key `model` refers to, following aliases. None if unknown.
+    pub fn canonical_name<'a>(&'a self, model: &'a str) -> Option<&'a str> {
+        if self.models.contains_key(model) {
+            return Some(model);
+        }
+        self.aliases.get(model).map(String::as_str)
+    }
+
+    /// Configured HTTP providers with a key set, sorted by name.
+    pub fn providers(&self) -> Vec<(&String, &ProviderEndpoint)> {
+        let mut providers: Vec<_> = self.providers.iter().collect();
+        providers.sort_by(|a, b| a.0.cmp(b.0));
+        providers
+    }
+
+    /// Providers among `providers` whose key is being rejected, with what is known
+    /// about it. Sorted by provider.
+    pub fn degraded_keys<'a>(
+        &self,
+        providers: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<(String, KeyState)> {
+        self.key_health.degraded(providers)
+    }
+
+    /// Current health of `provider`'s backend.
+    pub fn backend_state(&self, provider: &str) -> HealthState {
+        self.health.state(provider)
+    }
+
+    /// Health of every provider some model uses, sorted by provider.
+    pub fn backend_health(&self) -> Vec<BackendHealth> {
+        self.health
+            .report(self.models.values().map(|m| m.provider.as_str()))
+    }
+
+    /// Record the outcome of a health probe against `provider`.
+    pub fn record_probe(&self, provider: &str, result: &Result<(), String>) {
+        self.health.record_probe(provider, result);
+    }
+
+    /// Ping each HTTP provider once (see `HttpDispatch::ping`), a few at a time,
+    /// so the first real call skips connection and auth setup and backend health
+    /// is known up front. Outcomes feed key validity and backend health like any
+    /// call; failures are only logged. Returns each provider's outcome, sorted.
+    pub async fn warm_up(&self) -> Vec<(String, Result<Duration, String>)> {
+        // One model per provider: the first by name, for a stable choice.
+        let mut targets: Vec<(&String, &ModelEntry)> = self.models.iter().collect();
+        targets.sort_by(|a, b| a.0.cmp(b.0));
+        let mut seen = HashSet::new();
+        let pings = targets
+            .into_iter()
+            .filter_map(|(_, entry)| match &entry.backend {
+                BackendConfig::Http {
+                    base_url,
+                    api_key,
+                    api_format,
+                } if seen.insert(entry.provider.as_str()) => Some((
+                    entry.provider.clone(),
+                    base_url.clone(),
+                    api_key.clone(),
+                    api_format.clone(),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|(provider, base_url, api_key, api_format)| async move {
+                let start = Instant::now();
+                let result = self
+                    .http
+                    .ping(&provider, &base_url, &api_key, &api_format, WARMUP_TIMEOUT)
+                    .await;
+                self.key_health.record(&provider, &result);
+                self.health.record(&provider, &result);
+                let outcome = match result {
+                    Ok(()) => Ok(start.elapsed()),
+                    Err(e) => {
+                        tracing::warn!(provider = %provider, "warm-up ping failed: {e}");
+                        Err(e.user_message())
+                    }
+                };
+                (provider, outcome)
+            });
+        let mut outcomes: Vec<_> = futures_util::stream::iter(pings)
+            .buffer_unordered(WARMUP_CONCURRENCY)
+            .collect()
+            .await;
+        outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+        outcomes
     }
 
     pub fn list_models(&self) -> Vec<(&String, &ModelEntry)> {
@@ -178,11 +464,19 @@ impl Registry {
     /// Returns a map of model_id → config_key for model identity normalization.
     /// Used by memory subsystem to normalize event log entries that may use
     /// provider model_ids instead of config keys.
+    /// Aliases are included, so history logged under a model's former name carries over.
     pub fn model_id_to_key(&self) -> HashMap<String, String> {
-        self.models
+        let mut map: HashMap<String, String> = self
+            .aliases
             .iter()
-            .map(|(key, entry)| (entry.model_id.clone(), key.clone()))
-            .collect()
+            .map(|(alias, key)| (alias.clone(), key.clone()))
+            .collect();
+        map.extend(
+            self.models
+                .iter()
+                .map(|(key, entry)| (entry.model_id.clone(), key.clone())),
+        );
+        map
     }
 
     /// Suggest similar model names for a failed lookup (substring match).
@@ -219,24 +513,8 @@ impl Registry {
         }
     }
 
-    /// Acquire a semaphore permit with a deadline-aware timeout.
-    /// Returns Timeout if the deadline expires before a permit is available.
-    async fn acquire_with_deadline(
-        semaphore: &Semaphore,
-        deadline: Instant,
-    ) -> Result<tokio::sync::SemaphorePermit<'_>, SquallError> {
-        let timeout = deadline
-            .checked_duration_since(Instant::now())
-            .ok_or(SquallError::Timeout(0))?;
-
-        tokio::time::timeout(timeout, semaphore.acquire())
-            .await
-            .map_err(|_| SquallError::Timeout(0))?
-            .map_err(|_| SquallError::Other("semaphore closed".to_string()))
-    }
-
     pub async fn query(&self, req: &ProviderRequest) -> Result<ProviderResult, SquallError> {
-        let entry = self.models.get(&req.model).ok_or_else(|| {
+        let entry = self.get(&req.model).ok_or_else(|| {
             let suggestions = self.suggest_models(&req.model);
             SquallError::ModelNotFound {
                 model: req.model.clone(),
@@ -246,20 +524,132 @@ impl Registry {
 
         // Substitute the provider's model_id for the Squall model name.
         // e.g. "kimi-k2.5" → "moonshotai/Kimi-K2.5" for the API request body.
+        // Per-model stall timeout: when both the entry and the caller (e.g. deep mode)
+        // set one, the longer wins. Neither set → backend falls back to stall_timeout_for.
+        let stall_timeout = match (req.stall_timeout, entry.stall_timeout()) {
+            (Some(caller), Some(configured)) => Some(caller.max(configured)),
+            (caller, configured) => caller.or(configured),
+        };
+        // Output cap: the tighter of the caller's and the model's.
+        let max_output_bytes = match (req.max_output_bytes, entry.max_output_bytes) {
+            (Some(caller), Some(configured)) => Some(caller.min(configured)),
+            (caller, configured) => caller.or(configured),
+        };
+        // Provider-specific body parameters: the request's over the model's.
+        let extra_body = match (&entry.extra_body, &req.extra_body) {
+            (Some(configured), Some(caller)) => {
+                let mut merged = configured.clone();
+                crate::dispatch::http::merge_json(&mut merged, caller);
+                Some(merged)
+            }
+            (configured, caller) => caller.clone().or_else(|| configured.clone()),
+        };
+        let name = self
+            .canonical_name(&req.model)
+            .unwrap_or(&req.model)
+            .to_string();
         let resolved = ProviderRequest {
             model: entry.model_id.clone(),
+            stall_timeout,
+            max_output_bytes,
+            extra_body,
             ..(*req).clone()
         };
         let req = &resolved;
 
+        #[cfg(feature = "chaos")]
+        let result = match &self.chaos {
+            Some(chaos) => {
+                chaos
+                    .apply(req, &entry.provider, self.dispatch(entry, req))
+                    .await
+            }
+            None => self.dispatch(entry, req).await,
+        };
+        #[cfg(not(feature = "chaos"))]
+        let result = self.dispatch(entry, req).await;
+        self.key_health.record(&entry.provider, &result);
+        self.health.record(&entry.provider, &result);
+
+        // HTTP enforces the cap while streaming; CLI, async-poll and remote
+        // results arrive whole and are cut here. Secrets are redacted before any
+        // caller returns or persists the text. Remote results keep the worker's
+        // cost estimate.
+        let result = result.map(|mut r| {
+            if let Some(cap) = max_output_bytes {
+                r.cap_output(cap);
+            }
+            r.redact_secrets();
+            if let Some(usage) = r.usage.as_mut()
+                && usage.estimated_cost.is_none()
+            {
+                usage.estimated_cost = entry.estimate_cost(usage);
+            }
+            r
+        });
+        if let (Some(ledger), Some(tag)) = (&self.usage, &req.usage) {
+            ledger
+                .append(&UsageRecord::new(tag, &name, &entry.provider, &result))
+                .await;
+        }
+        result
+    }
+
+    /// Route a resolved request to its backend, holding the backend's permit.
+    /// Time spent waiting for the permit is reported as `timing.queue_ms`.
+    async fn dispatch(
+        &self,
+        entry: &ModelEntry,
+        req: &ProviderRequest,
+    ) -> Result<ProviderResult, SquallError> {
+        let gate = match &entry.backend {
+            BackendConfig::Http { .. } => &self.http_semaphore,
+            BackendConfig::Cli { .. } => &self.cli_semaphore,
+            BackendConfig::AsyncPoll { .. } => &self.async_poll_semaphore,
+            BackendConfig::Remote { .. } => &self.remote_semaphore,
+        };
+        let queued_at = Instant::now();
+        let _permit = Self::acquire_permit(gate, req).await?;
+        let queue_ms = queued_at.elapsed().as_millis() as u64;
+        self.dispatch_backend(entry, req).await.map(|mut r| {
+            if queue_ms > 0 {
+                // Remote results may already carry the worker's own queue time.
+                *r.timing.queue_ms.get_or_insert(0) += queue_ms;
+            }
+            r
+        })
+    }
+
+    /// Wait for a permit from `gate`. Cancellation while still queued (a review's
+    /// straggler cutoff) is a `QueuedTimeout` like the deadline: the model never ran.
+    async fn acquire_permit<'a>(
+        gate: &'a PrioritySemaphore,
+        req: &ProviderRequest,
+    ) -> Result<SemaphorePermit<'a>, SquallError> {
+        let acquire = gate.acquire(req.priority.unwrap_or_default(), req.deadline);
+        let Some(token) = &req.cancellation_token else {
+            return acquire.await;
+        };
+        let queued_at = Instant::now();
+        tokio::select! {
+            permit = acquire => permit,
+            () = token.cancelled() => Err(SquallError::QueuedTimeout(
+                queued_at.elapsed().as_millis() as u64,
+            )),
+        }
+    }
+
+    async fn dispatch_backend(
+        &self,
+        entry: &ModelEntry,
+        req: &ProviderRequest,
+    ) -> Result<ProviderResult, SquallError> {
         match &entry.backend {
             BackendConfig::Http {
                 base_url,
                 api_key,
                 api_format,
             } => {
-                let _permit =
-                    Self::acquire_with_deadline(&self.http_semaphore, req.deadline).await?;
                 self.http
                     .query_model(req, &entry.provider, base_url, api_key, api_format)
                     .await
@@ -269,8 +659,6 @@ impl Registry {
                 args_template,
             } => {
                 let parser = Self::parser_for(&entry.provider)?;
-                let _permit =
-                    Self::acquire_with_deadline(&self.cli_semaphore, req.deadline).await?;
                 self.cli
                     .query_model(
                         req,
@@ -286,12 +674,11 @@ impl Registry {
                 provider_type,
                 api_key,
             } => {
-                let _permit =
-                    Self::acquire_with_deadline(&self.async_poll_semaphore, req.deadline).await?;
                 self.async_poll
                     .query_model(req, &entry.provider, provider_type, api_key)
                     .await
             }
+            BackendConfig::Remote { url, token } => self.remote.query_model(req, url, token).await,
         }
     }
 }
@@ -0,0 +1,306 @@
+//! Distributed dispatch: forward a `ProviderRequest` to a remote Squall worker
+//! (`squall worker`) that holds the provider credentials, and map its answer back
+//! into a `ProviderResult`. The worker side lives in `crate::worker`.
+
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::http::MAX_RESPONSE_BYTES;
+use crate::dispatch::{
+    DispatchTiming, ProviderMetadata, ProviderRequest, ProviderResult, TokenUsage,
+};
+use crate::error::SquallError;
+
+/// Worker endpoint that runs one `ProviderRequest`.
+pub const DISPATCH_PATH: &str = "/v1/dispatch";
+
+/// Env var holding the shared bearer token (worker side, and the coordinator default).
+pub const WORKER_TOKEN_ENV: &str = "SQUALL_WORKER_TOKEN";
+
+/// Transit allowance on top of the request deadline. The worker enforces the
+/// deadline itself; this only covers the round trip.
+const TRANSIT_GRACE: Duration = Duration::from_secs(5);
+
+/// Wire format of a forwarded request. The worker resolves `model` by its Squall
+/// name, so the coordinator never needs the provider's model_id or credentials.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteRequest {
+    pub model: String,
+    pub prompt: String,
+    /// Time left before the coordinator's deadline.
+    pub timeout_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_body: Option<serde_json::Value>,
+}
+
+/// Wire format of a worker's answer.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RemoteResponse {
+    Success {
+        text: String,
+        provider: String,
+        partial: bool,
+        #[serde(default)]
+        output_capped: bool,
+        #[serde(default)]
+        timing: DispatchTiming,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<TokenUsage>,
+        #[serde(default, skip_serializing_if = "ProviderMetadata::is_empty")]
+        provider_metadata: ProviderMetadata,
+    },
+    Error {
+        /// Error class, so the coordinator keeps retry/gating semantics.
+        kind: String,
+        /// Sanitized message; never carries worker-side URLs or credentials.
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provider: Option<String>,
+        /// Upstream HTTP status. Not `status`: that key is the variant tag.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        http_status: Option<u16>,
+    },
+}
+
+impl RemoteResponse {
+    /// Encode a worker-side dispatch error.
+    pub fn from_error(e: &SquallError) -> Self {
+        let kind = match e {
+            SquallError::ModelNotFound { .. } => "model_not_found",
+            SquallError::Timeout(_) => "timeout",
+            SquallError::QueuedTimeout(_) => "queued_timeout",
+            SquallError::Cancelled(_) => "cancelled",
+            SquallError::RateLimited { .. } => "rate_limited",
+            SquallError::AuthFailed { .. } => "auth_failed",
+            SquallError::Upstream { .. } => "upstream",
+            _ => "other",
+        };
+        let status = match e {
+            SquallError::Upstream { status, .. } => *status,
+            _ => None,
+        };
+        // Keep the bare message for variants the coordinator re-wraps with a provider.
+        let message = match e {
+            SquallError::Upstream { message, .. } | SquallError::AuthFailed { message, .. } => {
+                message.clone()
+            }
+            _ => e.user_message(),
+        };
+        Self::Error {
+            kind: kind.to_string(),
+            message,
+            provider: e.provider().map(String::from),
+            http_status: status,
+        }
+    }
+
+    /// Decode into a dispatch result. `worker` names the worker in fallback errors.
+    pub fn into_result(
+        self,
+        model: &str,
+        worker: &str,
+        elapsed_ms: u64,
+    ) -> Result<ProviderResult, SquallError> {
+        match self {
+            Self::Success {
+                text,
+                provider,
+                partial,
+                output_capped,
+                timing,
+                usage,
+                provider_metadata,
+            } => Ok(ProviderResult {
+                text,
+                model: model.to_string(),
+                provider,
+                partial,
+                output_capped,
+                timing,
+                usage,
+                secrets_redacted: Vec::new(),
+                provider_metadata,
+            }),
+            Self::Error {
+                kind,
+                message,
+                provider,
+                http_status,
+            } => {
+                let provider = provider.unwrap_or_else(|| worker.to_string());
+                Err(match kind.as_str() {
+                    "model_not_found" => SquallError::ModelNotFound {
+                        model: model.to_string(),
+                        suggestions: vec![],
+                    },
+                    "timeout" => SquallError::Timeout(elapsed_ms),
+                    "queued_timeout" => SquallError::QueuedTimeout(elapsed_ms),
+                    "cancelled" => SquallError::Cancelled(elapsed_ms),
+                    "rate_limited" => SquallError::RateLimited { provider },
+                    "auth_failed" => SquallError::AuthFailed { provider, message },
+                    _ => SquallError::Upstream {
+                        provider,
+                        message,
+                        status: http_status,
+                    },
+                })
+            }
+        }
+    }
+}
+
+pub struct RemoteDispatch {
+    client: Client,
+}
+
+#[allow(clippy::new_without_default)]
+impl RemoteDispatch {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .expect("failed to build HTTP client");
+        Self { client }
+    }
+
+    /// Forward `req` to the worker at `url`. `req.model` is the model's name in the
+    /// worker's own registry.
+    pub async fn query_model(
+        &self,
+        req: &ProviderRequest,
+        url: &str,
+        token: &str,
+    ) -> Result<ProviderResult, SquallError> {
+        let start = Instant::now();
+        let remaining = req
+            .deadline
+            .checked_duration_since(start)
+            .ok_or(SquallError::Timeout(0))?;
+        let body = RemoteRequest {
+            model: req.model.clone(),
+            prompt: req.prompt.to_string(),
+            timeout_ms: remaining.as_millis() as u64,
+            system_prompt: req.system_prompt.clone(),
+            temperature: req.temperature,
+            max_tokens: req.max_tokens,
+            reasoning_effort: req.reasoning_effort.clone(),
+            stall_timeout_ms: req.stall_timeout.map(|d| d.as_millis() as u64),
+            max_output_bytes: req.max_output_bytes,
+            extra_body: req.extra_body.clone(),
+        };
+        let worker = format!("worker {}", worker_host(url));
+        let elapsed_ms = || start.elapsed().as_millis() as u64;
+
+        let exchange = async {
+            let mut response = self
+                .client
+                .post(format!("{}{DISPATCH_PATH}", url.trim_end_matches('/')))
+                .bearer_auth(token)
+                .json(&body)
+                .timeout(remaining + TRANSIT_GRACE)
+                .send()
+                .await?;
+            let status = response.status();
+            // JSON escaping can grow the text; leave headroom over the text cap.
+            let cap = MAX_RESPONSE_BYTES * 2;
+            let mut bytes = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                bytes.extend_from_slice(&chunk);
+                if bytes.len() > cap {
+                    break;
+                }
+            }
+            Ok::<_, reqwest::Error>((status, bytes))
+        };
+
+        // Dropping the exchange closes the connection; the worker treats that as
+        // cancellation and stops the upstream call.
+        let (status, bytes) = match &req.cancellation_token {
+            Some(token) => tokio::select! {
+                r = exchange => r?,
+                _ = token.cancelled() => return Err(SquallError::Cancelled(elapsed_ms())),
+            },
+            None => exchange.await?,
+        };
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(SquallError::AuthFailed {
+                provider: worker,
+                message: "worker rejected the token".to_string(),
+            });
+        }
+        match serde_json::from_slice::<RemoteResponse>(&bytes) {
+            Ok(remote) => remote.into_result(&req.model, &worker, elapsed_ms()),
+            Err(_) => Err(SquallError::Upstream {
+                provider: worker,
+                message: format!("unreadable worker response (HTTP {})", status.as_u16()),
+                status: Some(status.as_u16()),
+            }),
+        }
+    }
+}
+
+/// Host part of a worker URL, for error messages that must not leak full URLs.
+fn worker_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?']).next().unwrap_or(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_kinds_roundtrip() {
+        for err in [
+            SquallError::RateLimited {
+                provider: "xai".to_string(),
+            },
+            SquallError::AuthFailed {
+                provider: "xai".to_string(),
+                message: "bad key".to_string(),
+            },
+            SquallError::Timeout(5),
+            SquallError::QueuedTimeout(5),
+            SquallError::Upstream {
+                provider: "xai".to_string(),
+                message: "boom".to_string(),
+                status: Some(503),
+            },
+        ] {
+            let wire = serde_json::to_string(&RemoteResponse::from_error(&err)).unwrap();
+            let back = serde_json::from_str::<RemoteResponse>(&wire)
+                .unwrap()
+                .into_result("grok", "worker w", 7)
+                .unwrap_err();
+            assert_eq!(
+                std::mem::discriminant(&back),
+                std::mem::discriminant(&err),
+                "{wire}"
+            );
+            assert_eq!(back.is_retryable(), err.is_retryable());
+        }
+    }
+
+    #[test]
+    fn worker_host_strips_scheme_and_path() {
+        assert_eq!(worker_host("https://bastion:7420/x?y"), "bastion:7420");
+        assert_eq!(worker_host("bastion"), "bastion");
+    }
+}
@@ -0,0 +1,351 @@
+//! Text extraction for PDF and DOCX files in `file_paths`, so design docs and specs
+//! can be reviewed alongside code. Extraction needs the `documents` feature (pure-Rust
+//! `pdf-extract` and `zip`); without it these files are reported in `files_errors`
+//! rather than inlined as binary noise.
+//!
+//! A path can select pages with a `#pages=` suffix: `docs/spec.pdf#pages=3-7` or
+//! `design.docx#pages=1,4-5`. DOCX pages are the page breaks Word stored in the file
+//! (explicit breaks and where it last rendered one).
+//!
+//! Jupyter notebooks (`.ipynb`) need no feature: their JSON is converted to a
+//! percent-format script (`# %%` cell markers, markdown as comments) with outputs
+//! and embedded base64 images dropped, instead of megabytes of JSON noise.
+
+use std::path::Path;
+
+/// Largest document read for extraction. Extracted text still counts against the
+/// file context budget; this bounds the parse itself.
+pub const MAX_DOCUMENT_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Suffix that selects pages of a document path.
+pub const PAGES_SELECTOR: &str = "#pages=";
+
+/// A document format whose text is extracted rather than read verbatim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Notebook,
+}
+
+impl DocumentKind {
+    /// The document kind for `path`'s extension, if it is one.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
+            "ipynb" => Some(Self::Notebook),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pdf => "PDF",
+            Self::Docx => "DOCX",
+            Self::Notebook => "notebook",
+        }
+    }
+
+    /// Whether `#pages=` applies.
+    fn is_paged(self) -> bool {
+        !matches!(self, Self::Notebook)
+    }
+}
+
+/// Pages to keep: 1-based inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRange(Vec<(usize, usize)>);
+
+impl PageRange {
+    /// Parse `3`, `3-7`, or a comma-separated list of them.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut ranges = Vec::new();
+        for part in spec.split(',').map(str::trim) {
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let parse = |n: &str| n.trim().parse::<usize>().ok().filter(|n| *n > 0);
+            match (parse(start), parse(end)) {
+                (Some(start), Some(end)) if start <= end => ranges.push((start, end)),
+                _ => return Err(format!("invalid page range '{part}' in '{spec}'")),
+            }
+        }
+        Ok(Self(ranges))
+    }
+
+    pub fn contains(&self, page: usize) -> bool {
+        self.0
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&page))
+    }
+}
+
+/// Split `docs/spec.pdf#pages=3-7` into the path and its page selection.
+/// Paths without the suffix, and non-document paths, come back unchanged.
+pub fn split_page_selector(rel_path: &str) -> Result<(&str, Option<PageRange>), String> {
+    match rel_path.rsplit_once(PAGES_SELECTOR) {
+        Some((path, spec)) if DocumentKind::from_path(path).is_some_and(DocumentKind::is_paged) => {
+            Ok((path, Some(PageRange::parse(spec)?)))
+        }
+        _ => Ok((rel_path, None)),
+    }
+}
+
+/// Extract `path`'s text, keeping only `pages` if given. Each page is headed by a
+/// `--- page N ---` line so models can cite pages.
+pub async fn extract_text(
+    path: &Path,
+    kind: DocumentKind,
+    pages: Option<&PageRange>,
+) -> Result<String, String> {
+    let bytes = tokio::fs::read(path).await.map_err(|e| e.to_string())?;
+    if kind == DocumentKind::Notebook {
+        let json = String::from_utf8(bytes).map_err(|_| "notebook is not valid UTF-8")?;
+        return notebook_to_script(&json);
+    }
+    let all = tokio::task::spawn_blocking(move || extract_pages(kind, &bytes))
+        .await
+        .map_err(|_| format!("{} extraction failed (malformed file?)", kind.name()))??;
+
+    let mut text = String::new();
+    for (i, page) in all.iter().enumerate() {
+        let number = i + 1;
+        if pages.is_some_and(|p| !p.contains(number)) {
+            continue;
+        }
+        text.push_str(&format!("--- page {number} ---\n{}\n", page.trim()));
+    }
+    if text.is_empty() {
+        return Err(format!(
+            "no pages selected (document has {} pages)",
+            all.len()
+        ));
+    }
+    Ok(text)
+}
+
+/// Convert notebook JSON to a percent-format script: one `# %%` block per cell,
+/// markdown and raw cells commented out, outputs replaced by a count.
+pub fn notebook_to_script(json: &str) -> Result<String, String> {
+    let notebook: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("invalid notebook JSON: {e}"))?;
+    let cells = notebook
+        .get("cells")
+        .and_then(|c| c.as_array())
+        .ok_or("notebook has no cells array")?;
+    let metadata = &notebook["metadata"];
+    let language = metadata["language_info"]["name"]
+        .as_str()
+        .or_else(|| metadata["kernelspec"]["language"].as_str())
+        .unwrap_or("python")
+        .to_ascii_lowercase();
+    let comment = match language.as_str() {
+        "c++" | "csharp" | "c#" | "go" | "java" | "javascript" | "kotlin" | "rust" | "scala"
+        | "swift" | "typescript" => "//",
+        _ => "#",
+    };
+
+    let mut script = format!("{comment} notebook language: {language}\n");
+    for (i, cell) in cells.iter().enumerate() {
+        let kind = cell["cell_type"].as_str().unwrap_or("code");
+        // nbformat stores source as a string or a list of lines (with their own newlines).
+        let source = match &cell["source"] {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Array(lines) => lines.iter().filter_map(|l| l.as_str()).collect(),
+            _ => String::new(),
+        };
+        let source = strip_base64_data_uris(&source);
+        script.push_str(&format!("\n{comment} %% [{kind}] cell {}\n", i + 1));
+        if kind == "code" {
+            script.push_str(source.trim_end());
+            script.push('\n');
+            let outputs = cell["outputs"].as_array().map_or(0, Vec::len);
+            if outputs > 0 {
+                script.push_str(&format!("{comment} [{outputs} output(s) omitted]\n"));
+            }
+        } else {
+            for line in source.trim_end().lines() {
+                if line.is_empty() {
+                    script.push_str(comment);
+                } else {
+                    script.push_str(&format!("{comment} {line}"));
+                }
+                script.push('\n');
+            }
+        }
+    }
+    Ok(script)
+}
+
+/// Replace `data:...;base64,...` URIs (images pasted into markdown) with a placeholder.
+fn strip_base64_data_uris(text: &str) -> String {
+    const MARKER: &str = ";base64,";
+    let mut out = String::with_capacity(text.len().min(64 * 1024));
+    let mut rest = text;
+    while let Some(data) = rest.find("data:") {
+        let Some(marker) = rest[data..].find(MARKER).map(|m| data + m) else {
+            break;
+        };
+        // The media type between `data:` and `;base64,` never contains spaces or quotes.
+        if rest[data..marker].contains(|c: char| c.is_whitespace() || "\"')".contains(c)) {
+            out.push_str(&rest[..data + 5]);
+            rest = &rest[data + 5..];
+            continue;
+        }
+        let payload = marker + MARKER.len();
+        let end = rest[payload..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "+/=".contains(c)))
+            .map_or(rest.len(), |e| payload + e);
+        out.push_str(&rest[..data]);
+        out.push_str(&format!("data:{}(base64 omitted)", &rest[data + 5..marker]));
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(feature = "documents")]
+fn extract_pages(kind: DocumentKind, bytes: &[u8]) -> Result<Vec<String>, String> {
+    match kind {
+        DocumentKind::Notebook => unreachable!("notebooks are converted, not paged"),
+        DocumentKind::Pdf => pdf_extract::extract_text_from_mem_by_pages(bytes)
+            .map_err(|e| format!("PDF extraction failed: {e}")),
+        DocumentKind::Docx => {
+            use std::io::Read;
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+                .map_err(|e| format!("not a DOCX archive: {e}"))?;
+            let mut xml = String::new();
+            archive
+                .by_name("word/document.xml")
+                .map_err(|e| format!("DOCX has no word/document.xml: {e}"))?
+                // Compressed size is capped; cap the inflated XML too.
+                .take(4 * MAX_DOCUMENT_BYTES)
+                .read_to_string(&mut xml)
+                .map_err(|e| format!("DOCX read failed: {e}"))?;
+            Ok(docx_xml_pages(&xml))
+        }
+    }
+}
+
+#[cfg(not(feature = "documents"))]
+fn extract_pages(kind: DocumentKind, _bytes: &[u8]) -> Result<Vec<String>, String> {
+    Err(format!(
+        "{} extraction requires squall built with the `documents` feature",
+        kind.name()
+    ))
+}
+
+/// Pages of text from a DOCX `word/document.xml`: `<w:t>` runs joined, one line
+/// per paragraph, split at page breaks. Empty pages are dropped, which also
+/// collapses an explicit break followed by Word's rendered-break marker.
+#[cfg(feature = "documents")]
+fn docx_xml_pages(xml: &str) -> Vec<String> {
+    fn end_page(pages: &mut Vec<String>, page: &mut String) {
+        if page.trim().is_empty() {
+            page.clear();
+        } else {
+            pages.push(std::mem::take(page));
+        }
+    }
+
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    let mut in_text = false;
+    let mut i = 0;
+    while let Some(offset) = xml[i..].find('<') {
+        if in_text {
+            page.push_str(&crate::urls::decode_entities(&xml[i..i + offset]));
+        }
+        let start = i + offset;
+        let Some(end) = xml[start..].find('>') else {
+            break;
+        };
+        let tag = &xml[start + 1..start + end];
+        i = start + end + 1;
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "w:t" => in_text = !closing && !tag.ends_with('/'),
+            "w:tab" if !closing => page.push('\t'),
+            "w:br" | "w:cr" if !closing => {
+                if tag.contains("w:type=\"page\"") {
+                    end_page(&mut pages, &mut page);
+                } else {
+                    page.push('\n');
+                }
+            }
+            "w:lastRenderedPageBreak" => end_page(&mut pages, &mut page),
+            "w:p" if closing => page.push('\n'),
+            _ => {}
+        }
+    }
+    end_page(&mut pages, &mut page);
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_selector_parses_ranges_on_documents_only() {
+        let (path, pages) = split_page_selector("docs/spec.pdf#pages=2,5-6").unwrap();
+        assert_eq!(path, "docs/spec.pdf");
+        let pages = pages.unwrap();
+        assert!(pages.contains(2) && pages.contains(5) && pages.contains(6));
+        assert!(!pages.contains(1) && !pages.contains(3) && !pages.contains(7));
+
+        assert_eq!(
+            split_page_selector("notes.md#pages=1").unwrap(),
+            ("notes.md#pages=1", None)
+        );
+        assert!(split_page_selector("a.docx#pages=0").is_err());
+        assert!(split_page_selector("a.docx#pages=5-3").is_err());
+        assert!(split_page_selector("a.PDF#pages=x").is_err());
+        assert_eq!(
+            split_page_selector("nb.ipynb#pages=1").unwrap(),
+            ("nb.ipynb#pages=1", None)
+        );
+    }
+
+    #[test]
+    fn notebook_becomes_script_without_outputs_or_blobs() {
+        let notebook = r##"{
+            "metadata": {"language_info": {"name": "python"}},
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Load\n", "\n", "![plot](data:image/png;base64,iVBORw0KGgo=)"]},
+                {"cell_type": "code", "source": "import pandas as pd\ndf = pd.read_csv('x.csv')\n",
+                 "outputs": [{"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo="}}]}
+            ],
+            "nbformat": 4
+        }"##;
+        let script = notebook_to_script(notebook).unwrap();
+        assert_eq!(
+            script,
+            "# notebook language: python\n\
+             \n# %% [markdown] cell 1\n# # Load\n#\n# ![plot](data:image/png(base64 omitted))\n\
+             \n# %% [code] cell 2\nimport pandas as pd\ndf = pd.read_csv('x.csv')\n\
+             # [1 output(s) omitted]\n"
+        );
+        assert!(notebook_to_script("{\"cells\": 3}").is_err());
+    }
+
+    #[cfg(feature = "documents")]
+    #[test]
+    fn docx_xml_splits_paragraphs_and_pages() {
+        let xml = r#"<w:document><w:body>
+            <w:p><w:r><w:t>Design</w:t></w:r><w:r><w:t xml:space="preserve"> &amp; scope</w:t></w:r></w:p>
+            <w:p><w:r><w:t>a</w:t><w:tab/><w:t>b</w:t></w:r></w:p>
+            <w:p><w:r><w:br w:type="page"/></w:r></w:p>
+            <w:p><w:r><w:lastRenderedPageBreak/><w:t>Appendix</w:t></w:r></w:p>
+        </w:body></w:document>"#;
+        let pages = docx_xml_pages(xml);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].trim(), "Design & scope\na\tb");
+        assert_eq!(pages[1].trim(), "Appendix");
+    }
+}
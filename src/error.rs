@@ -11,6 +11,10 @@ pub enum SquallError {
     #[error("timeout after {0}ms")]
     Timeout(u64),
 
+    /// Out of time while waiting for a backend permit; the model never started.
+    #[error("deadline passed after {0}ms queued for a backend slot")]
+    QueuedTimeout(u64),
+
     #[error("cancelled after {0}ms")]
     Cancelled(u64),
 
@@ -101,6 +105,9 @@ impl SquallError {
                 }
             }
             Self::Timeout(ms) => format!("request timed out after {ms}ms"),
+            Self::QueuedTimeout(ms) => {
+                format!("not started: waited {ms}ms for a free slot (concurrency limit reached)")
+            }
             Self::Cancelled(ms) => format!("cancelled after {ms}ms"),
             Self::RateLimited { provider } => {
                 format!("rate limited by {provider} — try again shortly")
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
@@ -16,8 +17,23 @@ pub enum Severity {
 }
 
 impl Severity {
-    fn parse(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+            Self::Info => "info",
+        }
+    }
+
+    /// Parse a severity word: a configured alias first, then the built-in words.
+    fn parse(s: &str, aliases: &SeverityAliases) -> Option<Self> {
+        let word = normalize_phrase(s);
+        if let Some(severity) = aliases.get(&word) {
+            return Some(severity.clone());
+        }
+        match word.as_str() {
             "critical" | "fatal" => Some(Self::Critical),
             "high" | "severe" | "major" => Some(Self::High),
             "medium" | "moderate" | "med" => Some(Self::Medium),
@@ -28,6 +44,61 @@ impl Severity {
     }
 }
 
+/// Configured severity phrasings, keyed by normalized phrase (see `normalize_phrase`).
+pub type SeverityAliases = HashMap<String, Severity>;
+
+/// Severity phrasings mapped onto the canonical scale (`[review.severity]` in
+/// config), so consensus counting compares like with like across models. Some
+/// models say "blocker" or "P1", others "should fix".
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeverityMap {
+    /// Phrasings of every model.
+    aliases: SeverityAliases,
+    /// Phrasings of one model or provider, keyed by model config key or provider name.
+    scoped: HashMap<String, SeverityAliases>,
+}
+
+impl SeverityMap {
+    pub fn new(
+        aliases: HashMap<String, Severity>,
+        scoped: HashMap<String, HashMap<String, Severity>>,
+    ) -> Self {
+        let normalize = |map: HashMap<String, Severity>| -> SeverityAliases {
+            map.into_iter()
+                .map(|(phrase, severity)| (normalize_phrase(&phrase), severity))
+                .collect()
+        };
+        Self {
+            aliases: normalize(aliases),
+            scoped: scoped
+                .into_iter()
+                .map(|(key, map)| (key, normalize(map)))
+                .collect(),
+        }
+    }
+
+    /// Phrasings that apply to a model: its own override its provider's, which
+    /// override the global ones.
+    pub fn for_model(&self, model_key: &str, provider: &str) -> SeverityAliases {
+        let mut aliases = self.aliases.clone();
+        for key in [provider, model_key] {
+            if let Some(scoped) = self.scoped.get(key) {
+                aliases.extend(scoped.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        aliases
+    }
+}
+
+/// Lowercase, drop bold markers, and collapse whitespace: "**Must  Fix**" → "must fix".
+fn normalize_phrase(s: &str) -> String {
+    s.replace("**", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 /// A discrete finding extracted from a model's unstructured response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
@@ -70,6 +141,16 @@ fn finding_id(model_key: &str, summary: &str) -> String {
 ///
 /// Body is all text between the heading and the next heading of same or higher level.
 pub fn extract_findings(model_key: &str, response: &str) -> Vec<Finding> {
+    extract_findings_with(model_key, response, &SeverityAliases::new())
+}
+
+/// `extract_findings`, also reading the configured severity phrasings in `aliases`
+/// (see `SeverityMap::for_model`).
+pub fn extract_findings_with(
+    model_key: &str,
+    response: &str,
+    aliases: &SeverityAliases,
+) -> Vec<Finding> {
     let lines: Vec<&str> = response.lines().collect();
     let mut findings: Vec<Finding> = Vec::new();
     let mut i = 0;
@@ -92,7 +173,7 @@ pub fn extract_findings(model_key: &str, response: &str) -> Vec<Finding> {
             let heading_level = if line.starts_with("####") { 4 } else { 3 };
 
             // Try to parse this heading as a finding
-            if let Some((severity, summary, confidence)) = parse_heading(rest) {
+            if let Some((severity, summary, confidence)) = parse_heading(rest, aliases) {
                 // Collect body: everything until next heading of same or higher level
                 let body_start = i + 1;
                 let mut body_end = body_start;
@@ -149,7 +230,10 @@ fn is_heading_at_level(line: &str, level: usize) -> bool {
 /// - `Title (Confidence: High)` → severity None, confidence parsed
 /// - `The ML Algorithm Mismatch: GRPO vs. DPO (Fatal)` → severity from trailing paren
 /// - `**Title** (Confidence: **99%**)` → confidence as number
-fn parse_heading(heading: &str) -> Option<(Option<Severity>, String, Option<f64>)> {
+fn parse_heading(
+    heading: &str,
+    aliases: &SeverityAliases,
+) -> Option<(Option<Severity>, String, Option<f64>)> {
     let heading = heading.trim();
     if heading.is_empty() {
         return None;
@@ -163,7 +247,7 @@ fn parse_heading(heading: &str) -> Option<(Option<Severity>, String, Option<f64>
         && let Some(bracket_end) = heading.find(']')
     {
         let sev_str = &heading[1..bracket_end];
-        let severity = Severity::parse(sev_str);
+        let severity = Severity::parse(sev_str, aliases);
         let rest = heading[bracket_end + 1..].trim().to_string();
         if !rest.is_empty() {
             let (summary, confidence) = extract_heading_confidence(&rest);
@@ -172,7 +256,7 @@ fn parse_heading(heading: &str) -> Option<(Option<Severity>, String, Option<f64>
     }
 
     // Try trailing parenthetical: "Title (Fatal)" or "Title (Confidence: High)"
-    let (summary, confidence, trailing_severity) = parse_trailing_paren(heading);
+    let (summary, confidence, trailing_severity) = parse_trailing_paren(heading, aliases);
     let severity = trailing_severity;
 
     let summary = clean_summary(&summary);
@@ -210,7 +294,10 @@ fn clean_summary(s: &str) -> String {
 
 /// Extract confidence from a trailing parenthetical.
 /// Returns (summary_without_paren, confidence, severity_from_paren).
-fn parse_trailing_paren(heading: &str) -> (String, Option<f64>, Option<Severity>) {
+fn parse_trailing_paren(
+    heading: &str,
+    aliases: &SeverityAliases,
+) -> (String, Option<f64>, Option<Severity>) {
     // Find the last parenthesized group
     if let Some(paren_start) = heading.rfind('(')
         && heading.ends_with(')')
@@ -231,7 +318,7 @@ fn parse_trailing_paren(heading: &str) -> (String, Option<f64>, Option<Severity>
 
         // Single severity word: "(Fatal)", "(High)", "(Critical)"
         let paren_clean_trimmed = paren_clean.trim();
-        if let Some(sev) = Severity::parse(paren_clean_trimmed) {
+        if let Some(sev) = Severity::parse(paren_clean_trimmed, aliases) {
             return (summary, None, Some(sev));
         }
 
@@ -388,6 +475,109 @@ fn extract_confidence(body: &str) -> Option<f64> {
     None
 }
 
+/// Findings from different models that describe the same issue.
+#[derive(Debug, Clone)]
+pub struct FindingCluster {
+    /// Summary of the first finding in the cluster.
+    pub summary: String,
+    /// Most severe severity reported by any member.
+    pub severity: Option<Severity>,
+    /// File path cited by any member.
+    pub file_path: Option<String>,
+    /// Distinct model keys that reported this issue, in first-seen order.
+    pub models: Vec<String>,
+}
+
+/// Line distance under which two findings on the same file count as the same issue.
+const CLUSTER_LINE_SLACK: u32 = 5;
+
+/// Minimum word overlap (Jaccard) for two summaries to count as the same issue.
+const CLUSTER_SUMMARY_SIMILARITY: f64 = 0.5;
+
+/// Group findings from different models that describe the same issue.
+///
+/// Two findings match if they cite nearby lines in the same file, or if their
+/// summaries share enough significant words. Clusters are returned in order of
+/// descending model count (ties keep first-seen order).
+pub fn cluster_findings(findings: &[Finding]) -> Vec<FindingCluster> {
+    let mut clusters: Vec<(FindingCluster, Vec<&Finding>)> = Vec::new();
+
+    for finding in findings {
+        let existing = clusters
+            .iter_mut()
+            .find(|(_, members)| members.iter().any(|m| same_issue(m, finding)));
+        match existing {
+            Some((cluster, members)) => {
+                if !cluster.models.contains(&finding.model_key) {
+                    cluster.models.push(finding.model_key.clone());
+                }
+                if severity_rank(finding.severity.as_ref())
+                    > severity_rank(cluster.severity.as_ref())
+                {
+                    cluster.severity = finding.severity.clone();
+                }
+                if cluster.file_path.is_none() {
+                    cluster.file_path = finding.file_path.clone();
+                }
+                members.push(finding);
+            }
+            None => clusters.push((
+                FindingCluster {
+                    summary: finding.summary.clone(),
+                    severity: finding.severity.clone(),
+                    file_path: finding.file_path.clone(),
+                    models: vec![finding.model_key.clone()],
+                },
+                vec![finding],
+            )),
+        }
+    }
+
+    let mut clusters: Vec<FindingCluster> = clusters.into_iter().map(|(c, _)| c).collect();
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.models.len()));
+    clusters
+}
+
+pub(crate) fn severity_rank(severity: Option<&Severity>) -> u8 {
+    match severity {
+        Some(Severity::Critical) => 5,
+        Some(Severity::High) => 4,
+        Some(Severity::Medium) => 3,
+        Some(Severity::Low) => 2,
+        Some(Severity::Info) => 1,
+        None => 0,
+    }
+}
+
+/// Whether two findings describe the same issue: nearby lines in the same
+/// file, or summaries that share enough significant words.
+pub fn same_issue(a: &Finding, b: &Finding) -> bool {
+    if let (Some(pa), Some(pb)) = (&a.file_path, &b.file_path)
+        && pa == pb
+        && let (Some((a_start, a_end)), Some((b_start, b_end))) = (a.line_range, b.line_range)
+    {
+        return a_start <= b_end.saturating_add(CLUSTER_LINE_SLACK)
+            && b_start <= a_end.saturating_add(CLUSTER_LINE_SLACK);
+    }
+    summary_similarity(&a.summary, &b.summary) >= CLUSTER_SUMMARY_SIMILARITY
+}
+
+/// Jaccard similarity over lowercase words of 4+ characters.
+pub(crate) fn summary_similarity(a: &str, b: &str) -> f64 {
+    let words = |s: &str| -> std::collections::HashSet<String> {
+        s.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|w| w.len() >= 4)
+            .map(|w| w.to_lowercase())
+            .collect()
+    };
+    let (wa, wb) = (words(a), words(b));
+    if wa.is_empty() || wb.is_empty() {
+        return 0.0;
+    }
+    let shared = wa.intersection(&wb).count();
+    shared as f64 / wa.union(&wb).count() as f64
+}
+
 /// Persist extracted findings alongside the review results file.
 ///
 /// Writes to `.squall/reviews/{review_stem}_findings.json`.
@@ -401,16 +591,18 @@ pub async fn persist_findings(
 
     let results_path = PathBuf::from(results_file);
     let stem = results_path
-        .file_stem()
+        .file_name()
         .and_then(|s| s.to_str())
+        .and_then(crate::artifact::review_stem)
         .unwrap_or("unknown");
     let findings_filename = format!("{stem}_findings.json");
     let findings_path = results_path
         .parent()
-        .unwrap_or(&PathBuf::from(".squall/reviews"))
+        .unwrap_or(std::path::Path::new(crate::review::REVIEWS_DIR))
         .join(&findings_filename);
 
     let json = serde_json::to_string_pretty(findings).map_err(std::io::Error::other)?;
+    let json = crate::crypto::seal(&json)?;
 
     // Atomic write
     let tmp_path = findings_path.with_extension("tmp");
@@ -455,6 +647,60 @@ Tabs vs spaces inconsistency.
         assert_eq!(findings[1].summary, "Minor style issue");
     }
 
+    #[test]
+    fn configured_severity_phrasings_map_per_model() {
+        let phrases = |pairs: &[(&str, Severity)]| -> HashMap<String, Severity> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect()
+        };
+        let map = SeverityMap::new(
+            phrases(&[("Blocker", Severity::Critical), ("major", Severity::Medium)]),
+            HashMap::from([
+                ("xai".to_string(), phrases(&[("P1", Severity::High)])),
+                ("grok".to_string(), phrases(&[("p1", Severity::Critical)])),
+            ]),
+        );
+        let response = "\
+### [Blocker] Pool never releases connections
+### Retry loop has no backoff (**Should  Fix**)
+### [P1] Token logged at debug level
+### [major] Config reload drops overrides
+";
+        let severities = |aliases: &SeverityAliases| -> Vec<Option<Severity>> {
+            extract_findings_with("grok", response, aliases)
+                .into_iter()
+                .map(|f| f.severity)
+                .collect()
+        };
+
+        assert_eq!(
+            severities(&map.for_model("grok", "xai")),
+            vec![
+                Some(Severity::Critical),
+                None,
+                Some(Severity::Critical),
+                Some(Severity::Medium),
+            ]
+        );
+        assert_eq!(
+            severities(&map.for_model("grok-fast", "xai"))[2],
+            Some(Severity::High)
+        );
+        // Without configuration only the built-in words are known.
+        assert_eq!(
+            severities(&SeverityAliases::new()),
+            vec![None, None, None, Some(Severity::High)]
+        );
+
+        let map = SeverityMap::new(phrases(&[("should fix", Severity::Medium)]), HashMap::new());
+        assert_eq!(
+            severities(&map.for_model("grok", "xai"))[1],
+            Some(Severity::Medium)
+        );
+    }
+
     #[test]
     fn numbered_heading_with_bold_and_confidence() {
         let response = "\
@@ -702,6 +948,47 @@ More details.
         assert!(!findings.iter().any(|f| f.summary.contains("Deep nested")));
     }
 
+    #[test]
+    fn cluster_groups_same_issue_across_models() {
+        let grok = "\
+### [high] Race condition in cache refresh
+- File: src/cache.rs:40
+
+### [low] Unused import
+Nothing serious.
+";
+        let gemini = "\
+### [critical] Cache refresh is not atomic
+- File: src/cache.rs:43
+";
+        let codex = "\
+### [medium] Race condition during cache refresh
+Two writers can interleave.
+";
+        let mut findings = extract_findings("grok", grok);
+        findings.extend(extract_findings("gemini", gemini));
+        findings.extend(extract_findings("codex", codex));
+
+        let clusters = cluster_findings(&findings);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].summary, "Race condition in cache refresh");
+        assert_eq!(clusters[0].models, vec!["grok", "gemini", "codex"]);
+        assert_eq!(clusters[0].severity, Some(Severity::Critical));
+        assert_eq!(clusters[0].file_path.as_deref(), Some("src/cache.rs"));
+        assert_eq!(clusters[1].models, vec!["grok"]);
+    }
+
+    #[test]
+    fn cluster_counts_each_model_once() {
+        let response = "\
+### [high] Missing timeout on upstream call
+### [high] Missing timeout on upstream request
+";
+        let clusters = cluster_findings(&extract_findings("grok", response));
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].models, vec!["grok"]);
+    }
+
     #[test]
     fn url_not_matched_as_file_ref() {
         let response = "\
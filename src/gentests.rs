@@ -0,0 +1,518 @@
+//! Prompt assembly and output parsing for the `gentests` tool.
+//!
+//! The test framework is detected from the target files' extensions and the
+//! project manifests in the working directory (`Cargo.toml`, `package.json`,
+//! `pyproject.toml`, `go.mod`, `pom.xml`/`build.gradle`). Models are asked to
+//! label each test file with a `### FILE: <path>` line before its code block;
+//! responses are parsed into per-file blocks, and the blocks from all models are
+//! merged into one file-write plan: per target path, the candidate with the most
+//! test cases wins. Paths that are absolute or leave the project are rejected.
+
+use std::path::{Component, Path};
+
+use serde::Serialize;
+
+/// Detected test framework and the conventions models are told to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Framework {
+    pub name: &'static str,
+    pub conventions: &'static str,
+    /// Line prefixes (after indentation) that start one test case, used to rank
+    /// candidates.
+    test_markers: &'static [&'static str],
+}
+
+const RUST: Framework = Framework {
+    name: "Rust's built-in test harness (cargo test)",
+    conventions: "Put unit tests in a `#[cfg(test)] mod tests` block at the end of the source \
+        file they test, and integration tests in `tests/*.rs` using only the crate's public \
+        API. Use `#[tokio::test]` for async code when tokio is a dependency.",
+    test_markers: &["#[test]", "#[tokio::test"],
+};
+
+const PYTEST: Framework = Framework {
+    name: "pytest",
+    conventions: "Put tests in `tests/test_<module>.py` as plain `test_*` functions, using \
+        fixtures and `pytest.raises` rather than unittest classes.",
+    test_markers: &["def test_", "async def test_"],
+};
+
+const GO: Framework = Framework {
+    name: "Go's testing package (go test)",
+    conventions: "Put tests in `<file>_test.go` next to the source, in the same package, as \
+        `func TestXxx(t *testing.T)` with table-driven cases where it fits.",
+    test_markers: &["func Test"],
+};
+
+const JUNIT: Framework = Framework {
+    name: "JUnit 5",
+    conventions: "Mirror the source path under `src/test/java` with a `<Class>Test` class of \
+        `@Test` methods.",
+    test_markers: &["@Test"],
+};
+
+const JS_TEST_MARKERS: &[&str] = &["it(", "test(", "it.each", "test.each"];
+
+const JEST: Framework = Framework {
+    name: "Jest",
+    conventions: "Put tests in `<file>.test.<ext>` next to the source, using `describe`/`it` \
+        and `expect`.",
+    test_markers: JS_TEST_MARKERS,
+};
+
+const VITEST: Framework = Framework {
+    name: "Vitest",
+    conventions: "Put tests in `<file>.test.<ext>` next to the source, importing `describe`, \
+        `it`, and `expect` from 'vitest'.",
+    test_markers: JS_TEST_MARKERS,
+};
+
+const MOCHA: Framework = Framework {
+    name: "Mocha",
+    conventions: "Put tests in `test/<file>.test.<ext>`, using `describe`/`it` and the \
+        assertion library the project already uses.",
+    test_markers: JS_TEST_MARKERS,
+};
+
+const NODE_TEST: Framework = Framework {
+    name: "node:test",
+    conventions: "Put tests in `<file>.test.<ext>` next to the source, using `test` from \
+        'node:test' and `node:assert/strict`.",
+    test_markers: JS_TEST_MARKERS,
+};
+
+const UNKNOWN: Framework = Framework {
+    name: "the idiomatic test framework for this language",
+    conventions: "Follow the language's standard test file layout.",
+    test_markers: &["test"],
+};
+
+/// Largest manifest read for detection.
+const MAX_MANIFEST_BYTES: u64 = 256 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Python,
+    Go,
+    Java,
+    JavaScript,
+}
+
+fn language_of(path: &str) -> Option<Language> {
+    let ext = Path::new(path).extension()?.to_str()?;
+    Some(match ext {
+        "rs" => Language::Rust,
+        "py" => Language::Python,
+        "go" => Language::Go,
+        "java" | "kt" => Language::Java,
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => Language::JavaScript,
+        _ => return None,
+    })
+}
+
+/// File paths named in a diff's `+++ b/...` headers.
+pub fn diff_paths(diff: &str) -> Vec<String> {
+    diff.lines()
+        .filter_map(|l| l.strip_prefix("+++ "))
+        .map(|p| p.trim().strip_prefix("b/").unwrap_or(p.trim()))
+        .filter(|p| *p != "/dev/null")
+        .map(str::to_string)
+        .collect()
+}
+
+async fn read_manifest(path: &Path) -> Option<String> {
+    let meta = tokio::fs::metadata(path).await.ok()?;
+    if !meta.is_file() || meta.len() > MAX_MANIFEST_BYTES {
+        return None;
+    }
+    tokio::fs::read_to_string(path).await.ok()
+}
+
+/// Detect the test framework for `paths` (the target files), using the
+/// project manifests in `base_dir` when given.
+pub async fn detect_framework(base_dir: Option<&Path>, paths: &[String]) -> Framework {
+    // Majority language of the target files; manifests break ties or fill in.
+    let mut counts: Vec<(Language, usize)> = Vec::new();
+    for lang in paths.iter().filter_map(|p| language_of(p)) {
+        match counts.iter_mut().find(|(l, _)| *l == lang) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((lang, 1)),
+        }
+    }
+    let mut language = counts.iter().max_by_key(|(_, n)| *n).map(|(l, _)| *l);
+
+    let exists = |name: &str| base_dir.is_some_and(|d| d.join(name).is_file());
+    if language.is_none() {
+        language = if exists("Cargo.toml") {
+            Some(Language::Rust)
+        } else if exists("go.mod") {
+            Some(Language::Go)
+        } else if exists("pyproject.toml") || exists("pytest.ini") || exists("setup.py") {
+            Some(Language::Python)
+        } else if exists("pom.xml") || exists("build.gradle") || exists("build.gradle.kts") {
+            Some(Language::Java)
+        } else if exists("package.json") {
+            Some(Language::JavaScript)
+        } else {
+            None
+        };
+    }
+
+    match language {
+        Some(Language::Rust) => RUST,
+        Some(Language::Python) => PYTEST,
+        Some(Language::Go) => GO,
+        Some(Language::Java) => JUNIT,
+        Some(Language::JavaScript) => {
+            let manifest = match base_dir {
+                Some(dir) => read_manifest(&dir.join("package.json")).await,
+                None => None,
+            };
+            let deps = manifest.unwrap_or_default();
+            if deps.contains("\"vitest\"") {
+                VITEST
+            } else if deps.contains("\"jest\"") || deps.contains("\"ts-jest\"") {
+                JEST
+            } else if deps.contains("\"mocha\"") {
+                MOCHA
+            } else {
+                NODE_TEST
+            }
+        }
+        None => UNKNOWN,
+    }
+}
+
+/// Instructions prepended to the user's prompt and code context.
+pub fn instructions(framework: &Framework, has_diff: bool) -> String {
+    let scope = if has_diff {
+        "Focus on the behavior the diff changes or adds."
+    } else {
+        "Cover the public behavior of the files provided."
+    };
+    format!(
+        "Write unit tests using {}. {}\n{scope} Include edge cases and error paths; avoid \
+         testing private details likely to change. Use only APIs that appear in the code \
+         provided.\n\n\
+         For each test file, output a line `### FILE: <path relative to the project root>` \
+         followed by exactly one fenced code block with the content to add to that file. If \
+         the tests belong inside an existing source file, use that file's path.",
+        framework.name, framework.conventions
+    )
+}
+
+/// One labeled test file from a model response.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TestFile {
+    pub path: String,
+    pub code: String,
+}
+
+/// Parsed test files, plus labels that were rejected as unsafe paths.
+#[derive(Debug, Default, Serialize)]
+pub struct ParsedTests {
+    pub files: Vec<TestFile>,
+    pub rejected_paths: Vec<String>,
+    /// Code blocks with no `FILE:` label before them.
+    pub unlabeled_blocks: usize,
+}
+
+/// Path named by a `### FILE: path` (or bare `FILE: path`) label line.
+fn file_label(line: &str) -> Option<&str> {
+    let rest = line.trim().trim_start_matches('#').trim_start();
+    let rest = rest
+        .strip_prefix("**")
+        .map(|r| r.trim_end_matches("**"))
+        .unwrap_or(rest);
+    let path = rest
+        .strip_prefix("FILE:")
+        .or_else(|| rest.strip_prefix("File:"))?
+        .trim()
+        .trim_matches(['`', '*'])
+        .trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Relative paths that stay inside the project.
+fn is_safe_path(path: &str) -> bool {
+    let p = Path::new(path);
+    !path.contains('\\')
+        && p.components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Split a response into labeled test files.
+pub fn parse_response(response: &str) -> ParsedTests {
+    let mut parsed = ParsedTests::default();
+    let mut label: Option<&str> = None;
+    let mut lines = response.lines();
+    while let Some(line) = lines.next() {
+        if let Some(path) = file_label(line) {
+            label = Some(path);
+            continue;
+        }
+        if !line.trim_start().starts_with("```") {
+            continue;
+        }
+        let mut code = String::new();
+        for body in lines.by_ref() {
+            if body.trim_start().starts_with("```") {
+                break;
+            }
+            code.push_str(body);
+            code.push('\n');
+        }
+        match label.take() {
+            Some(path) if is_safe_path(path) => {
+                let path = path.trim_start_matches("./").to_string();
+                parsed.files.push(TestFile { path, code });
+            }
+            Some(path) => parsed.rejected_paths.push(path.to_string()),
+            None => parsed.unlabeled_blocks += 1,
+        }
+    }
+    parsed
+}
+
+/// Whether the plan creates a file or appends to an existing one.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteAction {
+    Create,
+    Append,
+}
+
+/// One file to write.
+#[derive(Debug, Serialize)]
+pub struct PlanEntry {
+    pub path: String,
+    pub action: WriteAction,
+    /// Model whose code block is chosen.
+    pub model: String,
+    pub tests: usize,
+    /// Other models that proposed the same file.
+    pub alternatives: Vec<String>,
+    pub code: String,
+}
+
+/// Test cases in a code block: lines that start with a test marker.
+pub fn count_tests(framework: &Framework, code: &str) -> usize {
+    code.lines()
+        .map(str::trim_start)
+        .filter(|l| framework.test_markers.iter().any(|m| l.starts_with(m)))
+        .count()
+}
+
+/// Merge each model's files into one plan: per path, the candidate with the
+/// most test cases (first model on ties). `exists` reports whether a path is
+/// already in the project.
+pub fn plan(
+    framework: &Framework,
+    per_model: &[(String, ParsedTests)],
+    exists: impl Fn(&str) -> bool,
+) -> Vec<PlanEntry> {
+    let mut entries: Vec<PlanEntry> = Vec::new();
+    for (model, parsed) in per_model {
+        for file in &parsed.files {
+            let tests = count_tests(framework, &file.code);
+            match entries.iter_mut().find(|e| e.path == file.path) {
+                Some(entry) if tests > entry.tests => {
+                    let previous = std::mem::replace(&mut entry.model, model.clone());
+                    entry.alternatives.push(previous);
+                    entry.tests = tests;
+                    entry.code = file.code.clone();
+                }
+                Some(entry) => {
+                    if entry.model != *model && !entry.alternatives.contains(model) {
+                        entry.alternatives.push(model.clone());
+                    }
+                }
+                None => entries.push(PlanEntry {
+                    path: file.path.clone(),
+                    action: if exists(&file.path) {
+                        WriteAction::Append
+                    } else {
+                        WriteAction::Create
+                    },
+                    model: model.clone(),
+                    tests,
+                    alternatives: Vec::new(),
+                    code: file.code.clone(),
+                }),
+            }
+        }
+    }
+    entries.retain(|e| !e.code.trim().is_empty());
+    entries
+}
+
+/// Fence language for a test file path.
+fn fence_lang(path: &str) -> &str {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map_or("", |ext| match ext {
+            "rs" => "rust",
+            "py" => "python",
+            "ts" | "tsx" => "typescript",
+            "js" | "jsx" | "mjs" | "cjs" => "javascript",
+            other => other,
+        })
+}
+
+/// Render the plan and per-model notes as markdown.
+pub fn to_markdown(
+    framework: &Framework,
+    plan: &[PlanEntry],
+    per_model: &[(String, ParsedTests)],
+    errors: &[String],
+) -> String {
+    let mut out = format!("## Generated tests ({})\n\n", framework.name);
+    if plan.is_empty() {
+        out.push_str("No labeled test files were returned.\n");
+    } else {
+        out.push_str(
+            "### File-write plan\n\n| File | Action | Model | Tests | Also proposed by |\n",
+        );
+        out.push_str("|------|--------|-------|-------|------------------|\n");
+        for e in plan {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                e.path,
+                match e.action {
+                    WriteAction::Create => "create",
+                    WriteAction::Append => "append",
+                },
+                e.model,
+                e.tests,
+                if e.alternatives.is_empty() {
+                    "—".to_string()
+                } else {
+                    e.alternatives.join(", ")
+                }
+            ));
+        }
+        for e in plan {
+            out.push_str(&format!(
+                "\n### {}\n\n```{}\n{}```\n",
+                e.path,
+                fence_lang(&e.path),
+                e.code
+            ));
+        }
+    }
+    let mut notes = Vec::new();
+    for (model, parsed) in per_model {
+        if !parsed.rejected_paths.is_empty() {
+            notes.push(format!(
+                "{model}: rejected unsafe paths {}",
+                parsed.rejected_paths.join(", ")
+            ));
+        }
+        if parsed.unlabeled_blocks > 0 {
+            notes.push(format!(
+                "{model}: {} code block(s) without a FILE label ignored",
+                parsed.unlabeled_blocks
+            ));
+        }
+    }
+    notes.extend(errors.iter().cloned());
+    if !notes.is_empty() {
+        out.push_str("\n### Notes\n");
+        for n in notes {
+            out.push_str(&format!("- {n}\n"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_labeled_blocks_and_rejects_escaping_paths() {
+        let response = "Here are the tests.\n\n\
+            ### FILE: `tests/cache.rs`\n```rust\n#[test]\nfn hit() {}\n```\n\n\
+            **FILE: ../etc/passwd**\n```\nroot\n```\n\
+            ```rust\nfn stray() {}\n```\n\
+            FILE: ./src/lib.rs\n```rust\n#[cfg(test)]\nmod tests {}\n```\n";
+        let parsed = parse_response(response);
+        assert_eq!(
+            parsed.files,
+            vec![
+                TestFile {
+                    path: "tests/cache.rs".into(),
+                    code: "#[test]\nfn hit() {}\n".into()
+                },
+                TestFile {
+                    path: "src/lib.rs".into(),
+                    code: "#[cfg(test)]\nmod tests {}\n".into()
+                },
+            ]
+        );
+        assert_eq!(parsed.rejected_paths, vec!["../etc/passwd"]);
+        assert_eq!(parsed.unlabeled_blocks, 1);
+    }
+
+    #[test]
+    fn plan_prefers_the_candidate_with_more_tests() {
+        let file = |path: &str, code: &str| TestFile {
+            path: path.into(),
+            code: code.into(),
+        };
+        let per_model = vec![
+            (
+                "grok".to_string(),
+                ParsedTests {
+                    files: vec![file("tests/a.rs", "#[test]\nfn one() {}\n")],
+                    ..Default::default()
+                },
+            ),
+            (
+                "kimi".to_string(),
+                ParsedTests {
+                    files: vec![
+                        file(
+                            "tests/a.rs",
+                            "#[test]\nfn one() {}\n#[tokio::test]\nasync fn two() {}\n",
+                        ),
+                        file("src/lib.rs", "#[test]\nfn inline() {}\n"),
+                    ],
+                    ..Default::default()
+                },
+            ),
+        ];
+        let plan = plan(&RUST, &per_model, |p| p == "src/lib.rs");
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].model, "kimi");
+        assert_eq!(plan[0].alternatives, vec!["grok"]);
+        assert_eq!(plan[0].action, WriteAction::Create);
+        assert_eq!(plan[1].action, WriteAction::Append);
+    }
+
+    #[tokio::test]
+    async fn detects_framework_from_extensions_and_manifests() {
+        let paths = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(detect_framework(None, &paths(&["src/a.rs"])).await, RUST);
+        assert_eq!(
+            detect_framework(None, &paths(&["app/x.py", "app/y.py", "web/z.ts"])).await,
+            PYTEST
+        );
+
+        let dir = std::env::temp_dir().join(format!("squall-test-gentests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"devDependencies": {"vitest": "^1.0.0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            detect_framework(Some(&dir), &paths(&["src/app.ts"])).await,
+            VITEST
+        );
+        assert_eq!(detect_framework(Some(&dir), &[]).await, VITEST);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
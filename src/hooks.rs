@@ -0,0 +1,324 @@
+//! Git hooks that flush branch-scoped memory once a branch is merged, so the
+//! cleanup the `flush` tool does isn't left to whoever remembers to call it.
+//!
+//! `squall install-hooks` adds `post-merge` and `post-rewrite` hooks (git's hook
+//! for rebases) that run `squall flush-on-merge`. When HEAD is on a base branch
+//! (see `base_branches`), that finds the branches with `branch:<name>` memory
+//! that this merge brought into HEAD and flushes each one. Merges into feature
+//! branches, such as pulling the base into one, flush nothing. Branches merged by
+//! squash or rebase on a forge don't show up as merged locally; flush those by
+//! hand.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::memory::MemoryStore;
+
+/// Hooks `install` writes. `post-rewrite` only acts on rebases, not amends.
+pub const HOOKS: &[&str] = &["post-merge", "post-rewrite"];
+
+/// Marks the lines `install` adds, so installing twice is a no-op.
+const MARKER: &str = "# squall: flush memory of merged branches";
+
+/// Lines added to `hook`, calling the squall binary at `exe`.
+pub fn hook_lines(hook: &str, exe: &Path) -> String {
+    let exe = shell_quote(&exe.to_string_lossy());
+    // stdin stays with the rest of the hook (post-rewrite reads the rewritten commits).
+    let command = format!("{exe} flush-on-merge </dev/null >/dev/null 2>&1 || true");
+    let command = if hook == "post-rewrite" {
+        format!("[ \"$1\" = rebase ] && {command}")
+    } else {
+        command
+    };
+    format!("{MARKER}\n{command}\n")
+}
+
+/// Install the hooks in the repository at `repo`, adding to hooks that already
+/// exist (see `add_lines`). Returns the hook files written; hooks already
+/// carrying the squall lines are left alone. If any hook is refused, none is written.
+pub async fn install(repo: &Path, exe: &Path) -> Result<Vec<PathBuf>, String> {
+    let dir = repo.join(git(repo, &["rev-parse", "--git-path", "hooks"]).await?);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+
+    // Every hook is checked before any is written, so a refused one leaves none half-installed.
+    let mut pending = Vec::new();
+    for hook in HOOKS {
+        let path = dir.join(hook);
+        let existing = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("cannot read {}: {e}", path.display())),
+        };
+        if existing.contains(MARKER) {
+            continue;
+        }
+        let content = add_lines(&existing, &hook_lines(hook, exe))
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        pending.push((path, content));
+    }
+    let mut written = Vec::new();
+    for (path, content) in pending {
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .await
+                .map_err(|e| format!("cannot make {} executable: {e}", path.display()))?;
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// `hook` (a new sh script if empty) with `lines` added: before a final `exit` or
+/// `exec`, which would skip them, else at the end. Hooks in other languages than
+/// sh or bash are refused, since the lines are shell.
+fn add_lines(hook: &str, lines: &str) -> Result<String, String> {
+    if hook.is_empty() {
+        return Ok(format!("#!/bin/sh\n{lines}"));
+    }
+    // Without a shebang git runs the hook with sh.
+    if let Some(shebang) = hook.lines().next().and_then(|l| l.strip_prefix("#!")) {
+        let mut words = shebang.split_whitespace();
+        let mut interpreter = words.next().unwrap_or_default();
+        if interpreter.ends_with("/env") {
+            interpreter = words.find(|w| !w.starts_with('-')).unwrap_or_default();
+        }
+        let name = interpreter.rsplit('/').next().unwrap_or_default();
+        if name != "sh" && name != "bash" {
+            return Err(format!(
+                "hook runs `{}`, not sh or bash; add these lines to it by hand:\n{lines}",
+                shebang.trim()
+            ));
+        }
+    }
+    let mut content: Vec<&str> = hook.lines().collect();
+    let last = content
+        .iter()
+        .rposition(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let at = match last {
+        Some(i)
+            if ["exit", "exec"]
+                .contains(&content[i].split_whitespace().next().unwrap_or_default()) =>
+        {
+            i
+        }
+        _ => content.len(),
+    };
+    content.insert(at, lines.trim_end());
+    Ok(content.join("\n") + "\n")
+}
+
+/// Branches the merge (or rebase) that just ran brought into HEAD: local branches
+/// whose tips are reachable from HEAD but were not from ORIG_HEAD, HEAD before
+/// it. Empty unless HEAD is on a base branch, or when there is no ORIG_HEAD.
+pub async fn merged_branches(repo: &Path) -> Result<Vec<String>, String> {
+    let current = git(repo, &["branch", "--show-current"]).await?;
+    let bases = base_branches(repo).await;
+    if !bases.contains(&current) {
+        return Ok(Vec::new());
+    }
+    if git(repo, &["rev-parse", "--verify", "-q", "ORIG_HEAD"])
+        .await
+        .is_err()
+    {
+        return Ok(Vec::new());
+    }
+    let merged_into = async |rev: &str| {
+        git(
+            repo,
+            &["branch", "--merged", rev, "--format=%(refname:short)"],
+        )
+        .await
+    };
+    let before = merged_into("ORIG_HEAD").await?;
+    let before: Vec<&str> = before.lines().map(str::trim).collect();
+    Ok(merged_into("HEAD")
+        .await?
+        .lines()
+        .map(str::trim)
+        // A base branch merged into another base (e.g. main into master) stays.
+        .filter(|b| !b.is_empty() && !before.contains(b) && !bases.iter().any(|x| x == b))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Long-lived branches that feature branches merge into: `git config
+/// squall.baseBranch` if set, else origin's default branch, `init.defaultBranch`,
+/// `main` and `master`.
+pub async fn base_branches(repo: &Path) -> Vec<String> {
+    if let Ok(base) = git(repo, &["config", "squall.baseBranch"]).await
+        && !base.is_empty()
+    {
+        return vec![base];
+    }
+    let mut bases = Vec::new();
+    if let Ok(head) = git(
+        repo,
+        &["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"],
+    )
+    .await
+        && let Some(branch) = head.strip_prefix("origin/")
+    {
+        bases.push(branch.to_string());
+    }
+    if let Ok(default) = git(repo, &["config", "init.defaultBranch"]).await {
+        bases.push(default);
+    }
+    bases.extend(["main".to_string(), "master".to_string()]);
+    bases.retain(|b| !b.is_empty());
+    bases.dedup();
+    bases
+}
+
+/// Flush every branch that has branch-scoped memory and was just merged into
+/// HEAD (see `merged_branches`). Returns each flush report.
+pub async fn flush_on_merge(repo: &Path, memory: &MemoryStore) -> Result<Vec<String>, String> {
+    let scoped = memory.scoped_branches().await;
+    if scoped.is_empty() {
+        return Ok(Vec::new());
+    }
+    let merged = merged_branches(repo).await?;
+    let mut reports = Vec::new();
+    for branch in scoped.iter().filter(|b| merged.contains(b)) {
+        reports.push(memory.flush_branch(branch).await?);
+    }
+    Ok(reports)
+}
+
+/// Run git in `repo` and return its trimmed stdout. 5s timeout, as for
+/// `context::detect_git_context`.
+async fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(5),
+        tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("git {} timed out", args.join(" ")))?
+    .map_err(|e| format!("cannot run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Single-quote `s` for /bin/sh.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrite_hook_only_flushes_after_rebase() {
+        let exe = Path::new("/opt/it's/squall");
+        let merge = hook_lines("post-merge", exe);
+        assert!(merge.starts_with(MARKER));
+        assert!(merge.contains(r"'/opt/it'\''s/squall' flush-on-merge"));
+        assert!(hook_lines("post-rewrite", exe).contains("[ \"$1\" = rebase ] && "));
+    }
+
+    #[test]
+    fn lines_go_before_a_final_exit_or_exec() {
+        let lines = hook_lines("post-merge", Path::new("/usr/local/bin/squall"));
+        let hook = add_lines("#!/bin/bash\nlint\nexit 0\n# done\n", &lines).unwrap();
+        assert_eq!(hook, format!("#!/bin/bash\nlint\n{lines}exit 0\n# done\n"));
+        let hook = add_lines("#!/usr/bin/env sh\nexec other-hook \"$@\"", &lines).unwrap();
+        assert_eq!(
+            hook,
+            format!("#!/usr/bin/env sh\n{lines}exec other-hook \"$@\"\n")
+        );
+        let hook = add_lines("#!/bin/sh\nlint || exit 1\n", &lines).unwrap();
+        assert_eq!(hook, format!("#!/bin/sh\nlint || exit 1\n{lines}"));
+        assert_eq!(
+            add_lines("", &lines).unwrap(),
+            format!("#!/bin/sh\n{lines}")
+        );
+    }
+
+    #[test]
+    fn refuses_hooks_not_written_in_sh_or_bash() {
+        let lines = hook_lines("post-merge", Path::new("/usr/local/bin/squall"));
+        for shebang in ["#!/usr/bin/env python3", "#!/usr/bin/perl -w", "#!/bin/zsh"] {
+            let err = add_lines(&format!("{shebang}\nprint(1)\n"), &lines).unwrap_err();
+            assert!(
+                err.contains("not sh or bash") && err.contains(&lines),
+                "{err}"
+            );
+        }
+        // No shebang: git runs it with sh.
+        assert!(add_lines("lint\n", &lines).is_ok());
+    }
+
+    #[tokio::test]
+    async fn flushes_only_merged_branches_and_installs_once() {
+        let dir = std::env::temp_dir().join(format!("squall_hooks_git_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+        };
+        if !git(&["init", "-q"]).status.success() {
+            return; // git unavailable
+        }
+        git(&["commit", "-q", "--allow-empty", "-m", "base"]);
+        git(&["branch", "-M", "trunk"]);
+        git(&["config", "squall.baseBranch", "trunk"]);
+        for branch in ["feat", "other"] {
+            git(&["checkout", "-q", "-b", branch, "trunk"]);
+            git(&["commit", "-q", "--allow-empty", "-m", branch]);
+        }
+        git(&["checkout", "-q", "trunk"]);
+        // Created at trunk's tip: reachable, but not merged by this merge.
+        git(&["branch", "fresh"]);
+        git(&["merge", "-q", "--no-edit", "feat"]);
+
+        let memory = MemoryStore::new().for_project(&dir);
+        for branch in ["feat", "other", "fresh"] {
+            let scope = format!("branch:{branch}");
+            memory
+                .memorize("pattern", "retry loop", None, None, Some(&scope), None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(merged_branches(&dir).await.unwrap(), vec!["feat"]);
+        let reports = flush_on_merge(&dir, &memory).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].contains("'feat'"), "{reports:?}");
+        assert_eq!(memory.scoped_branches().await, vec!["fresh", "other"]);
+
+        // Pulling the base into a feature branch flushes nothing, base included.
+        git(&["checkout", "-q", "other"]);
+        git(&["merge", "-q", "--no-edit", "trunk"]);
+        assert!(merged_branches(&dir).await.unwrap().is_empty());
+        assert!(flush_on_merge(&dir, &memory).await.unwrap().is_empty());
+
+        let exe = Path::new("/usr/local/bin/squall");
+        assert_eq!(install(&dir, exe).await.unwrap().len(), HOOKS.len());
+        assert!(install(&dir, exe).await.unwrap().is_empty());
+        let hook = std::fs::read_to_string(dir.join(".git/hooks/post-merge")).unwrap();
+        assert!(hook.starts_with("#!/bin/sh\n") && hook.contains("flush-on-merge"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,224 @@
+//! Hot spots: the files that keep accumulating confirmed findings.
+//!
+//! Every review adds its findings to `.squall/memory/hotspots.md`, per cited
+//! file. A finding is confirmed when two or more models reported it, or, for a
+//! finding only one model reported, when `feedback` later rates that model
+//! actionable. Files are ranked by confirmed findings, then by all findings.
+//!
+//! With `[review] hotspot_boost = true`, a review's `file_paths` are reordered
+//! so hot files come first: file context is budgeted in order, so they are the
+//! last to be skipped or trimmed.
+
+use std::collections::BTreeMap;
+
+use crate::findings::Finding;
+
+/// Most files kept in hotspots.md; the coldest are dropped first.
+pub const MAX_FILES: usize = 200;
+
+/// Header of hotspots.md.
+pub const HEADER: &str = "# Hot Spots\n\n\
+    | File | Findings | Confirmed | Last seen |\n\
+    |------|----------|-----------|-----------|";
+
+/// Finding counts of one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotspot {
+    pub file: String,
+    pub findings: usize,
+    pub confirmed: usize,
+    /// Timestamp of the last review or feedback that counted it.
+    pub last_seen: String,
+}
+
+/// Per-file counts to add: (findings, confirmed).
+pub type Counts = BTreeMap<String, (usize, usize)>;
+
+fn normalize(path: &str) -> String {
+    path.trim().trim_start_matches("./").to_string()
+}
+
+/// Counts from one review's findings: one finding per issue (see
+/// `findings::cluster_findings`), confirmed when several models reported it.
+pub fn tally(findings: &[Finding]) -> Counts {
+    let mut counts = Counts::new();
+    for cluster in crate::findings::cluster_findings(findings) {
+        let Some(ref file) = cluster.file_path else {
+            continue;
+        };
+        let entry = counts.entry(normalize(file)).or_default();
+        entry.0 += 1;
+        if distinct_models(&cluster.models) >= 2 {
+            entry.1 += 1;
+        }
+    }
+    counts
+}
+
+/// Confirmations from feedback on a review: issues only one model reported,
+/// where `rated_actionable` says yes for that model.
+pub fn confirmed_by_feedback(
+    findings: &[Finding],
+    rated_actionable: impl Fn(&str) -> bool,
+) -> Counts {
+    let mut counts = Counts::new();
+    for cluster in crate::findings::cluster_findings(findings) {
+        if let Some(ref file) = cluster.file_path
+            && distinct_models(&cluster.models) == 1
+            && rated_actionable(&cluster.models[0])
+        {
+            counts.entry(normalize(file)).or_default().1 += 1;
+        }
+    }
+    counts
+}
+
+/// Samples of one model (`model#2`) agreeing don't make a consensus.
+fn distinct_models(models: &[String]) -> usize {
+    let mut bases: Vec<&str> = models.iter().map(|m| crate::consistency::base(m)).collect();
+    bases.sort_unstable();
+    bases.dedup();
+    bases.len()
+}
+
+/// Hot spots from hotspots.md, hottest first.
+pub fn parse(content: &str) -> Vec<Hotspot> {
+    content
+        .lines()
+        .filter(|l| l.starts_with("| ") && !l.starts_with("| File"))
+        .filter_map(|line| {
+            let cols: Vec<&str> = line
+                .split(" | ")
+                .map(|s| s.trim_matches(['|', ' ']))
+                .collect();
+            let [file, findings, confirmed, last_seen] = cols[..] else {
+                return None;
+            };
+            Some(Hotspot {
+                file: file.replace("\\|", "|"),
+                findings: findings.parse().ok()?,
+                confirmed: confirmed.parse().ok()?,
+                last_seen: last_seen.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Add `counts` to `hotspots`, re-rank, and keep the hottest `MAX_FILES`.
+pub fn merge(mut hotspots: Vec<Hotspot>, counts: &Counts, timestamp: &str) -> Vec<Hotspot> {
+    for (file, (findings, confirmed)) in counts {
+        match hotspots.iter_mut().find(|h| &h.file == file) {
+            Some(h) => {
+                h.findings += findings;
+                h.confirmed += confirmed;
+                h.last_seen = timestamp.to_string();
+            }
+            None => hotspots.push(Hotspot {
+                file: file.clone(),
+                findings: *findings,
+                confirmed: *confirmed,
+                last_seen: timestamp.to_string(),
+            }),
+        }
+    }
+    hotspots.sort_by(|a, b| {
+        b.confirmed
+            .cmp(&a.confirmed)
+            .then(b.findings.cmp(&a.findings))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    hotspots.truncate(MAX_FILES);
+    hotspots
+}
+
+/// hotspots.md for `hotspots`.
+pub fn render(hotspots: &[Hotspot]) -> String {
+    let mut out = format!("{HEADER}\n");
+    for h in hotspots {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            h.file.replace('|', "\\|"),
+            h.findings,
+            h.confirmed,
+            h.last_seen
+        ));
+    }
+    out
+}
+
+/// Stable-sort `paths` so files with confirmed findings come first, hottest
+/// first. Returns the paths that moved ahead.
+pub fn prioritize(paths: &mut [String], hotspots: &[Hotspot]) -> Vec<String> {
+    let heat = |path: &String| {
+        let path = normalize(path);
+        hotspots
+            .iter()
+            .find(|h| h.file == path)
+            .map_or(0, |h| h.confirmed)
+    };
+    let before = paths.to_vec();
+    paths.sort_by_key(|p| std::cmp::Reverse(heat(p)));
+    paths
+        .iter()
+        .zip(&before)
+        .take_while(|(p, _)| heat(p) > 0)
+        .filter(|(p, b)| p != b)
+        .map(|(p, _)| p.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::extract_findings;
+
+    fn findings() -> Vec<Finding> {
+        let mut findings = extract_findings(
+            "grok",
+            "### [high] Token compared with ==\nFile: src/auth.rs:42\n\n\
+             ### [low] Magic number\nFile: ./src/net.rs:7\n",
+        );
+        findings.extend(extract_findings(
+            "kimi",
+            "### [high] Non-constant-time compare\nFile: src/auth.rs:43\n",
+        ));
+        findings
+    }
+
+    #[test]
+    fn consensus_confirms_and_feedback_confirms_the_rest() {
+        let counts = tally(&findings());
+        assert_eq!(counts["src/auth.rs"], (1, 1));
+        assert_eq!(counts["src/net.rs"], (1, 0));
+
+        let from_feedback = confirmed_by_feedback(&findings(), |m| m == "grok");
+        assert_eq!(from_feedback.len(), 1);
+        assert_eq!(from_feedback["src/net.rs"], (0, 1));
+        assert!(confirmed_by_feedback(&findings(), |m| m == "kimi").is_empty());
+    }
+
+    #[test]
+    fn merge_ranks_and_round_trips() {
+        let first = merge(Vec::new(), &tally(&findings()), "2026-10-01T00:00:00Z");
+        assert_eq!(first[0].file, "src/auth.rs");
+        let both = Counts::from([("src/net.rs".to_string(), (2, 2))]);
+        let hot = merge(first, &both, "2026-10-02T00:00:00Z");
+        assert_eq!(hot[0].file, "src/net.rs");
+        assert_eq!(hot[0].findings, 3);
+        assert_eq!(parse(&render(&hot)), hot);
+    }
+
+    #[test]
+    fn prioritize_moves_hot_files_first() {
+        let hot = merge(Vec::new(), &tally(&findings()), "2026-10-01T00:00:00Z");
+        let mut paths = vec![
+            "README.md".to_string(),
+            "src/net.rs".to_string(),
+            "./src/auth.rs".to_string(),
+        ];
+        let moved = prioritize(&mut paths, &hot);
+        assert_eq!(paths, ["./src/auth.rs", "README.md", "src/net.rs"]);
+        assert_eq!(moved, ["./src/auth.rs"]);
+        assert!(prioritize(&mut paths, &hot).is_empty());
+    }
+}
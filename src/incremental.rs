@@ -0,0 +1,271 @@
+//! Incremental review: re-review only the files changed since the last persisted
+//! review of the current branch, carrying forward that review's findings for
+//! files that haven't changed.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::findings::{Finding, extract_findings};
+
+/// Incremental-review metadata attached to a `ReviewResponse`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalReview {
+    /// Results file of the review this one builds on.
+    pub base_review: String,
+    /// Commit the base review was taken at.
+    pub base_commit: String,
+    /// Files changed since `base_commit` (the only files re-reviewed).
+    pub changed_files: Vec<String>,
+    /// Findings from the base review (and its own carry-forwards) on unchanged files.
+    #[serde(default)]
+    pub carried_forward: Vec<Finding>,
+}
+
+/// The newest persisted review for a branch.
+#[derive(Debug)]
+pub struct PreviousReview {
+    /// Path of the review JSON, as reported in `results_file`.
+    pub results_file: String,
+    pub commit_sha: String,
+    /// Findings from successful model responses plus earlier carry-forwards.
+    pub findings: Vec<Finding>,
+}
+
+/// Find the newest review in `reviews_dir` that recorded `branch` and a commit.
+/// Review files are named `{ts_ms}_{pid}_{seq}.json`, so newest = largest timestamp.
+pub async fn find_last_review(reviews_dir: &Path, branch: &str) -> Option<PreviousReview> {
+    let mut entries = tokio::fs::read_dir(reviews_dir).await.ok()?;
+    let mut candidates: Vec<(u128, std::path::PathBuf)> = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let Some(stem) = name.to_str().and_then(crate::artifact::review_stem) else {
+            continue;
+        };
+        if let Some(ts) = stem.split('_').next().and_then(|ts| ts.parse().ok()) {
+            candidates.push((ts, entry.path()));
+        }
+    }
+    candidates.sort_unstable_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+
+    for (_, path) in candidates {
+        let Ok(raw) = crate::artifact::read_review(&path).await else {
+            continue;
+        };
+        let Ok(review) = serde_json::from_str::<serde_json::Value>(&raw) else {
+            continue;
+        };
+        if review["git"]["branch"].as_str() != Some(branch) {
+            continue;
+        }
+        let Some(commit_sha) = review["git"]["commit_sha"].as_str() else {
+            continue;
+        };
+        return Some(PreviousReview {
+            results_file: path.to_string_lossy().to_string(),
+            commit_sha: commit_sha.to_string(),
+            findings: review_findings(&review),
+        });
+    }
+    None
+}
+
+/// Findings of a persisted review: extracted from successful responses, plus
+/// whatever that review itself carried forward.
+pub(crate) fn review_findings(review: &serde_json::Value) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for result in review["results"].as_array().into_iter().flatten() {
+        if result["status"] != "success" {
+            continue;
+        }
+        if let (Some(model), Some(text)) = (result["model"].as_str(), result["response"].as_str()) {
+            findings.extend(extract_findings(model, text));
+        }
+    }
+    if let Some(carried) = review["incremental"]["carried_forward"].as_array() {
+        findings.extend(
+            carried
+                .iter()
+                .filter_map(|f| serde_json::from_value::<Finding>(f.clone()).ok()),
+        );
+    }
+    findings
+}
+
+/// Files changed in the working tree since `commit`: tracked modifications
+/// (committed or not) plus untracked, non-ignored files. Paths are relative to
+/// `working_directory`, matching `file_paths`. Sorted and deduplicated.
+pub async fn changed_files_since(
+    working_directory: &Path,
+    commit: &str,
+) -> Result<Vec<String>, String> {
+    if commit.starts_with('-') || commit.is_empty() {
+        return Err(format!("invalid commit: {commit}"));
+    }
+    let mut files = git_lines(
+        working_directory,
+        &["diff", "--name-only", "--relative", commit, "--"],
+    )
+    .await?;
+    files.extend(
+        git_lines(
+            working_directory,
+            &["ls-files", "--others", "--exclude-standard"],
+        )
+        .await?,
+    );
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+async fn git_lines(working_directory: &Path, args: &[&str]) -> Result<Vec<String>, String> {
+    // Same 5s guard as git context detection (NFS mounts, broken hooks, etc.)
+    let output = tokio::time::timeout(
+        Duration::from_secs(5),
+        tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(working_directory)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("git {} timed out", args[0]))?
+    .map_err(|e| format!("git {} failed: {e}", args[0]))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Keep findings whose cited file is unchanged. Findings without a file, or on a
+/// changed file, are dropped — the re-review covers them.
+pub fn carry_forward(findings: &[Finding], changed: &[String]) -> Vec<Finding> {
+    let changed: HashSet<&str> = changed.iter().map(String::as_str).collect();
+    let mut seen = HashSet::new();
+    findings
+        .iter()
+        .filter(|f| {
+            f.file_path
+                .as_deref()
+                .is_some_and(|p| !changed.contains(p.trim_start_matches("./")))
+        })
+        .filter(|f| seen.insert(f.finding_id.clone()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(id: &str, file: Option<&str>) -> Finding {
+        Finding {
+            finding_id: id.to_string(),
+            model_key: "grok".to_string(),
+            severity: None,
+            summary: format!("finding {id}"),
+            body: String::new(),
+            file_path: file.map(String::from),
+            line_range: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn carry_forward_keeps_unchanged_files_only() {
+        let findings = vec![
+            finding("a", Some("src/a.rs")),
+            finding("b", Some("./src/b.rs")),
+            finding("c", None),
+            finding("a", Some("src/a.rs")),
+        ];
+        let kept = carry_forward(&findings, &["src/b.rs".to_string()]);
+        let ids: Vec<_> = kept.iter().map(|f| f.finding_id.as_str()).collect();
+        assert_eq!(ids, vec!["a"]);
+    }
+
+    #[test]
+    fn review_findings_include_prior_carry_forwards() {
+        let review = serde_json::json!({
+            "results": [
+                {"model": "grok", "status": "success",
+                 "response": "### [high] Race in cache\n- File: src/cache.rs:10\n"},
+                {"model": "gemini", "status": "error", "response": null}
+            ],
+            "incremental": {
+                "base_review": "x", "base_commit": "abc1234", "changed_files": [],
+                "carried_forward": [serde_json::to_value(finding("old", Some("src/old.rs"))).unwrap()]
+            }
+        });
+        let findings = review_findings(&review);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].file_path.as_deref(), Some("src/cache.rs"));
+        assert_eq!(findings[1].finding_id, "old");
+    }
+
+    #[tokio::test]
+    async fn find_last_review_matches_branch_newest_first() {
+        let dir = std::env::temp_dir().join(format!("squall_incr_find_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let review = |branch: &str, sha: &str| {
+            serde_json::json!({"results": [], "git": {"branch": branch, "commit_sha": sha}})
+                .to_string()
+        };
+        std::fs::write(dir.join("100_1_0.json"), review("feat", "aaaaaaa")).unwrap();
+        std::fs::write(dir.join("200_1_1.json"), review("feat", "bbbbbbb")).unwrap();
+        std::fs::write(dir.join("300_1_2.json"), review("main", "ccccccc")).unwrap();
+        std::fs::write(dir.join("400_1_3_findings.json"), "[]").unwrap();
+
+        let found = find_last_review(&dir, "feat").await.unwrap();
+        assert_eq!(found.commit_sha, "bbbbbbb");
+        assert!(found.results_file.ends_with("200_1_1.json"));
+        assert!(find_last_review(&dir, "other").await.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn changed_files_since_lists_modified_and_untracked() {
+        let dir = std::env::temp_dir().join(format!("squall_incr_git_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+        };
+        if !git(&["init", "-q"]).status.success() {
+            return; // git unavailable
+        }
+        std::fs::write(dir.join("a.rs"), "a").unwrap();
+        std::fs::write(dir.join("b.rs"), "b").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "base"]);
+        let sha = String::from_utf8(git(&["rev-parse", "--short", "HEAD"]).stdout).unwrap();
+
+        std::fs::write(dir.join("b.rs"), "b2").unwrap();
+        std::fs::write(dir.join("c.rs"), "c").unwrap();
+        let changed = changed_files_since(&dir, sha.trim()).await.unwrap();
+        assert_eq!(changed, vec!["b.rs", "c.rs"]);
+
+        assert!(changed_files_since(&dir, "--output=x").await.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
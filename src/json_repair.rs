@@ -0,0 +1,181 @@
+//! Tolerant parsing for almost-valid JSON. Models asked for a JSON document
+//! often wrap it in a code fence next to prose, leave a trailing comma, or get
+//! cut off before the closing brackets. `repair` salvages those before the
+//! response is declared unparseable:
+//!
+//! - strip a markdown fence (closed or not) and any prose around the document,
+//! - drop commas directly before `}` or `]`,
+//! - close an unterminated string and every open array and object, dropping the
+//!   trailing element if it was cut mid-way.
+
+use serde_json::Value;
+
+/// Cut points tried, from the end, when closing a truncated JSON document.
+const MAX_JSON_CUTS: usize = 32;
+
+/// Parse `text` as JSON, repairing it if it doesn't parse as-is. Returns the
+/// document and whether a repair was needed. Only objects and arrays count as
+/// salvaged; a bare number or string found in prose is not a document.
+pub fn parse(text: &str) -> Option<(Value, bool)> {
+    let trimmed = text.trim();
+    if let Ok(v) = serde_json::from_str(trimmed) {
+        return Some((v, false));
+    }
+    repair(trimmed).map(|v| (v, true))
+}
+
+/// Repair pass for text that doesn't parse: try the fenced body, the span from
+/// the first opening bracket to its last matching close, then everything from
+/// the opening bracket on (for documents cut off before their close).
+pub fn repair(text: &str) -> Option<Value> {
+    let mut candidates = Vec::new();
+    if let Some(body) = fenced_body(text) {
+        candidates.push(body);
+    }
+    if let Some(start) = text.find(['{', '[']) {
+        let close = if text[start..].starts_with('{') {
+            '}'
+        } else {
+            ']'
+        };
+        if let Some(end) = text.rfind(close).filter(|&end| end > start) {
+            candidates.push(&text[start..=end]);
+        }
+        candidates.push(&text[start..]);
+    }
+    candidates.into_iter().find_map(|candidate| {
+        let fixed = strip_trailing_commas(candidate);
+        close_truncated(&fixed).filter(|v| v.is_object() || v.is_array())
+    })
+}
+
+/// Body of the first fenced code block, up to its closing fence or, if the
+/// response was cut off, the end of the text.
+fn fenced_body(text: &str) -> Option<&str> {
+    let start = text.find("```")?;
+    let (_info, body) = text[start + 3..].split_once('\n')?;
+    Some(body.split("```").next().unwrap_or(body))
+}
+
+/// Remove commas that are followed (past whitespace) by `}` or `]`.
+fn strip_trailing_commas(text: &str) -> String {
+    let commas = commas_outside_strings(text);
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for i in commas {
+        if text[i + 1..].trim_start().starts_with(['}', ']']) {
+            out.push_str(&text[last..i]);
+            last = i + 1;
+        }
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Parse a JSON document that may have been cut off: close an unterminated
+/// string and every open array and object. If that doesn't parse, retry from
+/// each earlier top-level-or-nested comma, dropping the half-written element.
+pub(crate) fn close_truncated(text: &str) -> Option<Value> {
+    let text = text.trim_end();
+    let mut cuts = vec![text.len()];
+    cuts.extend(
+        commas_outside_strings(text)
+            .into_iter()
+            .rev()
+            .take(MAX_JSON_CUTS),
+    );
+    cuts.into_iter()
+        .find_map(|end| serde_json::from_str(&close(&text[..end])).ok())
+}
+
+/// Byte offsets of commas that aren't inside string literals.
+fn commas_outside_strings(text: &str) -> Vec<usize> {
+    let mut commas = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            commas.push(i);
+        }
+    }
+    commas
+}
+
+/// Append whatever closes the strings, arrays and objects `text` leaves open.
+fn close(text: &str) -> String {
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+            }
+            _ => {}
+        }
+    }
+    let mut out = text.to_string();
+    if in_string {
+        if escaped {
+            out.pop();
+        }
+        out.push('"');
+    }
+    out.extend(closers.into_iter().rev());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_json_needs_no_repair() {
+        let (v, repaired) = parse(" {\"findings\": []}\n").unwrap();
+        assert_eq!(v["findings"], serde_json::json!([]));
+        assert!(!repaired);
+        assert!(parse("No issues found.").is_none());
+        assert!(parse("Severity: 3 of 5").is_none());
+    }
+
+    #[test]
+    fn repairs_fences_trailing_commas_and_open_brackets() {
+        let fenced =
+            "Here are the findings:\n```json\n{\"findings\": [\"leak\", \"race\",],}\n```\nThanks!";
+        let (v, repaired) = parse(fenced).unwrap();
+        assert!(repaired);
+        assert_eq!(v["findings"][1], "race");
+
+        // A comma inside a string is content, not a trailing comma.
+        assert_eq!(repair(r#"{"note": "a, ]",}"#).unwrap()["note"], "a, ]");
+
+        let cut = r#"{"findings": [{"severity": "high", "file": "src/a.rs"}, {"severity": "lo"#;
+        assert_eq!(
+            close_truncated(cut).unwrap()["findings"][0]["file"],
+            "src/a.rs"
+        );
+        let (v, _) = parse(&format!("```json\n{cut}")).unwrap();
+        assert_eq!(v["findings"][0]["severity"], "high");
+    }
+}
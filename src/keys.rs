@@ -0,0 +1,236 @@
+//! API keys from outside the environment, so plaintext keys need not live in
+//! `.env` next to the binary. A provider (or model) can name a shell command
+//! that prints its key (`api_key_cmd = "op read op://dev/xai/key"`) or an OS
+//! keychain entry (`api_key_keychain = "squall"`):
+//!
+//! - macOS: Keychain, via `security find-generic-password -s <service> -a <account> -w`
+//! - Windows: Credential Manager (web credentials), via PowerShell's `PasswordVault`
+//! - Linux and other Unix: Secret Service, via `secret-tool lookup service <service> account <account>`
+//!
+//! The key's env var still wins when set, so one run can override the stored key.
+//! Lookups run while config loads, once per source. Each gets `LOOKUP_TIMEOUT`
+//! and no stdin, so a command waiting for an unlock prompt or interactive sign-in
+//! skips the key instead of hanging startup.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long one command or keychain lookup may run.
+pub const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a key comes from when its env var is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeySource {
+    /// A shell command that prints the key.
+    Command(String),
+    /// A generic password in the OS keychain.
+    Keychain { service: String, account: String },
+}
+
+impl KeySource {
+    /// Short description for logs and skip reasons; never the key itself.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Command(cmd) => format!("api_key_cmd `{cmd}`"),
+            Self::Keychain { service, account } => {
+                format!("keychain entry {service}/{account}")
+            }
+        }
+    }
+
+    /// Run the command or keychain lookup and return the trimmed key.
+    pub fn fetch(&self) -> Result<String, String> {
+        self.fetch_within(LOOKUP_TIMEOUT)
+    }
+
+    fn fetch_within(&self, timeout: Duration) -> Result<String, String> {
+        let mut cmd = match self {
+            Self::Command(cmd) => {
+                let mut sh = Command::new("sh");
+                sh.arg("-c").arg(cmd);
+                sh
+            }
+            Self::Keychain { service, account } => keychain_command(service, account),
+        };
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("{} failed to start: {e}", self.describe()))?;
+        // Read on a thread so a full pipe can't stall the child while we wait.
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut out = Vec::new();
+            let _ = stdout.read_to_end(&mut out);
+            let _ = tx.send(out);
+        });
+        let start = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if start.elapsed() < timeout => {
+                    std::thread::sleep(Duration::from_millis(20))
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "{} timed out after {}s (waiting for an unlock or sign-in?); key skipped",
+                        self.describe(),
+                        timeout.as_secs_f32()
+                    ));
+                }
+                Err(e) => return Err(format!("{} failed: {e}", self.describe())),
+            }
+        };
+        if !status.success() {
+            return Err(format!("{} exited with {status}", self.describe()));
+        }
+        // A grandchild may still hold the pipe open; don't wait past the deadline for it.
+        let out = rx
+            .recv_timeout(
+                timeout
+                    .saturating_sub(start.elapsed())
+                    .max(Duration::from_millis(100)),
+            )
+            .unwrap_or_default();
+        let key = String::from_utf8_lossy(&out).trim().to_string();
+        if key.is_empty() {
+            return Err(format!("{} printed no key", self.describe()));
+        }
+        Ok(key)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_command(service: &str, account: &str) -> Command {
+    let mut cmd = Command::new("security");
+    cmd.args(["find-generic-password", "-s", service, "-a", account, "-w"]);
+    cmd
+}
+
+#[cfg(windows)]
+fn keychain_command(service: &str, account: &str) -> Command {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        &format!(
+            "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,\
+             ContentType=WindowsRuntime]; $c = (New-Object Windows.Security.Credentials.PasswordVault)\
+             .Retrieve({}, {}); $c.RetrievePassword(); $c.Password",
+            quote(service),
+            quote(account)
+        ),
+    ]);
+    cmd
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn keychain_command(service: &str, account: &str) -> Command {
+    let mut cmd = Command::new("secret-tool");
+    cmd.args(["lookup", "service", service, "account", account]);
+    cmd
+}
+
+/// Keys fetched during one config load, so providers sharing a source run
+/// its command once.
+#[derive(Debug, Default)]
+pub struct KeyCache(HashMap<KeySource, Result<String, String>>);
+
+impl KeyCache {
+    /// The key for `env_name` if set and non-empty, else from `source`.
+    /// Ok(None) when the env var is unset and there is no source.
+    pub fn resolve(
+        &mut self,
+        env_name: &str,
+        source: Option<&KeySource>,
+    ) -> Result<Option<String>, String> {
+        if let Ok(key) = std::env::var(env_name)
+            && !key.trim().is_empty()
+        {
+            return Ok(Some(key));
+        }
+        let Some(source) = source else {
+            return Ok(None);
+        };
+        self.0
+            .entry(source.clone())
+            .or_insert_with(|| {
+                let key = source.fetch();
+                match key {
+                    Ok(_) => tracing::info!("loaded API key from {}", source.describe()),
+                    Err(ref e) => tracing::warn!("{e}"),
+                }
+                key
+            })
+            .clone()
+            .map(Some)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_var_wins_over_the_command_source() {
+        let env_name = "SQUALL_TEST_KEYS_ENV_WINS";
+        let source = KeySource::Command("printf ' sk-from-cmd \\n'".to_string());
+        let mut cache = KeyCache::default();
+        assert_eq!(
+            cache.resolve(env_name, Some(&source)),
+            Ok(Some("sk-from-cmd".to_string()))
+        );
+        assert_eq!(cache.resolve(env_name, None), Ok(None));
+
+        unsafe {
+            std::env::set_var(env_name, "sk-from-env");
+        }
+        assert_eq!(
+            cache.resolve(env_name, Some(&source)),
+            Ok(Some("sk-from-env".to_string()))
+        );
+        unsafe {
+            std::env::remove_var(env_name);
+        }
+    }
+
+    #[test]
+    fn failing_or_silent_commands_are_errors() {
+        let mut cache = KeyCache::default();
+        let failing = KeySource::Command("exit 3".to_string());
+        let err = cache
+            .resolve("SQUALL_TEST_KEYS_UNSET", Some(&failing))
+            .unwrap_err();
+        assert!(err.contains("api_key_cmd `exit 3` exited"), "{err}");
+
+        let silent = KeySource::Command("true".to_string());
+        let err = cache
+            .resolve("SQUALL_TEST_KEYS_UNSET", Some(&silent))
+            .unwrap_err();
+        assert!(err.ends_with("printed no key"), "{err}");
+    }
+
+    #[test]
+    fn hung_lookups_time_out_without_stdin() {
+        // Would wait forever for input if stdin were inherited.
+        let reads_stdin = KeySource::Command("read key; echo \"$key\"".to_string());
+        let err = reads_stdin
+            .fetch_within(Duration::from_secs(5))
+            .unwrap_err();
+        assert!(err.ends_with("printed no key"), "{err}");
+
+        let start = Instant::now();
+        let hangs = KeySource::Command("sleep 30".to_string());
+        let err = hangs.fetch_within(Duration::from_millis(200)).unwrap_err();
+        assert!(err.contains("timed out after 0.2s"), "{err}");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,11 +1,60 @@
+pub mod adr;
+pub mod artifact;
+pub mod blame;
+pub mod brainstorm;
+pub mod calibration;
+pub mod compare;
+pub mod compliance;
 pub mod config;
+pub mod consistency;
 pub mod context;
+pub mod crypto;
+#[cfg(feature = "server")]
+pub mod daemon;
+pub mod decompose;
+pub mod dependencies;
+pub mod describe;
+pub mod disagreement;
+pub mod discover;
 pub mod dispatch;
+pub mod documents;
 pub mod error;
 pub mod findings;
+pub mod gentests;
+pub mod hooks;
+pub mod hotspots;
+pub mod incremental;
+pub mod injection;
+pub mod json_repair;
+pub mod keys;
+pub mod license;
+pub mod logging;
 pub mod memory;
 pub mod parsers;
+pub mod partial;
+pub mod personas;
+pub mod plan;
+pub mod policy;
+pub mod profiles;
+pub mod questions;
+pub mod quotes;
+pub mod redteam;
+pub mod refine;
+pub mod regress;
+#[cfg(feature = "server")]
 pub mod response;
 pub mod review;
+pub mod routing;
+pub mod rubric;
+pub mod search;
+pub mod secrets;
+#[cfg(feature = "server")]
 pub mod server;
+pub mod suites;
+pub mod summarize;
 pub mod tools;
+pub mod triage;
+pub mod urls;
+pub mod usage;
+pub mod worker;
+pub mod workspace;
@@ -0,0 +1,563 @@
+//! Dependency-license and file-header checks for the `license_scan` tool.
+//!
+//! The scan collects the project's license file, its dependency manifests
+//! (`Cargo.toml`, `package.json`, `go.mod`, ...), and the first lines of every
+//! file added since `base` (or not yet committed), and asks models to flag
+//! dependencies whose license is incompatible with the project's, licenses
+//! they can't determine, and new files missing the expected header. Each model
+//! replies with JSON findings; findings on the same path, package and kind are
+//! merged. With a required `header`, new files are also checked for it without
+//! a model. The scan fails when any finding is an error, for CI gating.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::{escape_xml_attr, escape_xml_content, floor_char_boundary};
+
+/// Dependency manifests read from the project root, or wherever a new one is added.
+pub const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "requirements.txt",
+    "setup.cfg",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+];
+
+/// The project's own license, first match wins.
+pub const LICENSE_FILES: &[&str] = &["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"];
+
+/// Lines of each new file shown to the models and searched for `header`.
+pub const HEADER_LINES: usize = 20;
+
+/// New files checked per scan; the rest are listed as not checked.
+pub const MAX_NEW_FILES: usize = 200;
+
+/// Cap per manifest; larger ones are cut.
+const MAX_MANIFEST_BYTES: usize = 64 * 1024;
+
+/// Cap on the license file excerpt: enough to name the license.
+const MAX_LICENSE_BYTES: usize = 2048;
+
+/// Default cutoff for the models.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 180;
+
+/// What a finding is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// A dependency's license conflicts with the project's.
+    IncompatibleLicense,
+    /// A dependency's license couldn't be determined.
+    UnknownLicense,
+    /// A new file lacks the expected license header.
+    MissingHeader,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IncompatibleLicense => "incompatible license",
+            Self::UnknownLicense => "unknown license",
+            Self::MissingHeader => "missing header",
+        }
+    }
+}
+
+/// Errors fail the scan; warnings are reported only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+/// One license finding, as returned in `LicenseReport::findings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseFinding {
+    pub kind: FindingKind,
+    pub severity: Level,
+    /// Manifest or new file the finding is about.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// The dependency's license, as the model read it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    pub detail: String,
+    /// Models that reported it; empty for the built-in header check.
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+/// The `license_scan` result.
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseReport {
+    /// False when any finding is an error.
+    pub passed: bool,
+    /// License file the project's license was read from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_file: Option<String>,
+    pub manifests: Vec<String>,
+    pub new_files: Vec<String>,
+    pub findings: Vec<LicenseFinding>,
+    /// Models that failed or replied without findings JSON, and files not checked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl LicenseReport {
+    pub fn new(scan: &Scan, findings: Vec<LicenseFinding>, errors: Vec<String>) -> Self {
+        Self {
+            passed: !findings.iter().any(|f| f.severity == Level::Error),
+            license_file: scan.license.as_ref().map(|(path, _)| path.clone()),
+            manifests: scan
+                .manifests
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect(),
+            new_files: scan.headers.iter().map(|(path, _)| path.clone()).collect(),
+            findings,
+            errors,
+        }
+    }
+}
+
+/// What the scan sends to the models.
+#[derive(Debug, Default)]
+pub struct Scan {
+    /// License file name and its opening text.
+    pub license: Option<(String, String)>,
+    /// Manifest path and content.
+    pub manifests: Vec<(String, String)>,
+    /// New file path and its first `HEADER_LINES` lines.
+    pub headers: Vec<(String, String)>,
+}
+
+impl Scan {
+    /// Read the license file, the root and newly added manifests, and the
+    /// headers of `new_files` (relative to `dir`). Binary and unreadable files,
+    /// and symlinks out of `dir`, are skipped.
+    pub async fn collect(dir: &Path, new_files: &[String]) -> Self {
+        let mut scan = Scan::default();
+        for name in LICENSE_FILES {
+            if let Ok(text) = tokio::fs::read_to_string(dir.join(name)).await {
+                let text = &text[..floor_char_boundary(&text, MAX_LICENSE_BYTES)];
+                scan.license = Some((name.to_string(), text.trim().to_string()));
+                break;
+            }
+        }
+        let mut manifests: Vec<String> = MANIFESTS.iter().map(|m| m.to_string()).collect();
+        for path in new_files {
+            let is_manifest = Path::new(path)
+                .file_name()
+                .is_some_and(|name| MANIFESTS.iter().any(|m| name == *m));
+            if is_manifest && !manifests.contains(path) {
+                manifests.push(path.clone());
+            }
+        }
+        for path in manifests {
+            if let Some(full) = inside(dir, &path).await
+                && let Ok(text) = tokio::fs::read_to_string(full).await
+            {
+                let text = text[..floor_char_boundary(&text, MAX_MANIFEST_BYTES)].to_string();
+                scan.manifests.push((path, text));
+            }
+        }
+        for path in new_files.iter().take(MAX_NEW_FILES) {
+            if let Some(full) = inside(dir, path).await
+                && let Ok(bytes) = tokio::fs::read(full).await
+                && !bytes.contains(&0)
+            {
+                let text = String::from_utf8_lossy(&bytes);
+                let head: Vec<&str> = text.lines().take(HEADER_LINES).collect();
+                scan.headers.push((path.clone(), head.join("\n")));
+            }
+        }
+        scan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.manifests.is_empty() && self.headers.is_empty()
+    }
+}
+
+async fn inside(dir: &Path, path: &str) -> Option<std::path::PathBuf> {
+    let dir = tokio::fs::canonicalize(dir).await.ok()?;
+    let full = tokio::fs::canonicalize(dir.join(path)).await.ok()?;
+    full.starts_with(&dir).then_some(full)
+}
+
+/// Files added since `base` (`git diff base...HEAD`), or else those added but
+/// not yet committed, staged or untracked.
+pub async fn new_files(dir: &Path, base: Option<&str>) -> Result<Vec<String>, String> {
+    let mut files = match base {
+        Some(base) => {
+            git(
+                dir,
+                &[
+                    "diff",
+                    "--name-only",
+                    "--no-renames",
+                    "--diff-filter=A",
+                    &format!("{base}...HEAD"),
+                ],
+            )
+            .await?
+        }
+        None => {
+            let mut files = git(
+                dir,
+                &[
+                    "diff",
+                    "--name-only",
+                    "--no-renames",
+                    "--diff-filter=A",
+                    "HEAD",
+                ],
+            )
+            .await?;
+            files.extend(git(dir, &["ls-files", "--others", "--exclude-standard"]).await?);
+            files
+        }
+    };
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<Vec<String>, String> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(15),
+        tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("git {} timed out", args[0]))?
+    .map_err(|e| format!("git {} failed: {e}", args[0]))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// The models' prompt: the project license, manifests and new-file headers,
+/// then the reply format.
+pub fn prompt(scan: &Scan, header: Option<&str>, extra: Option<&str>) -> String {
+    let mut out = String::new();
+    match scan.license {
+        Some((ref path, ref text)) => out.push_str(&format!(
+            "<project_license path=\"{}\">\n{}\n</project_license>\n\n",
+            escape_xml_attr(path),
+            escape_xml_content(text)
+        )),
+        None => out.push_str("The project has no license file.\n\n"),
+    }
+    for (path, text) in &scan.manifests {
+        out.push_str(&format!(
+            "<manifest path=\"{}\">\n{}\n</manifest>\n\n",
+            escape_xml_attr(path),
+            escape_xml_content(text)
+        ));
+    }
+    for (path, head) in &scan.headers {
+        out.push_str(&format!(
+            "<new_file path=\"{}\" lines=\"1-{HEADER_LINES}\">\n{}\n</new_file>\n\n",
+            escape_xml_attr(path),
+            escape_xml_content(head)
+        ));
+    }
+    out.push_str(
+        "Audit these dependencies and new files for license compliance with the project's \
+         license. Flag:\n\
+         - incompatible_license: a dependency whose license conflicts with the project's \
+         (e.g. GPL code in a permissively licensed project). Severity error.\n\
+         - unknown_license: a dependency whose license you can't determine with confidence. \
+         Severity warning.\n\
+         - missing_header: a new source file without the license header the project's other \
+         files are expected to carry. Severity error.\n",
+    );
+    match header {
+        Some(header) => out.push_str(&format!(
+            "Every new source file must carry this header: {header}\n"
+        )),
+        None => out.push_str(
+            "Only flag missing headers if the project evidently requires them (e.g. an SPDX \
+             line in its files, or a license that asks for notices).\n",
+        ),
+    }
+    if let Some(extra) = extra {
+        out.push_str(&format!("\n{extra}\n"));
+    }
+    out.push_str(
+        "\nReport only real problems; permissive licenses that are compatible are fine. Reply \
+         with ONLY a JSON object:\n\
+         {\"findings\": [{\"kind\": \"incompatible_license\", \"severity\": \"error\", \
+         \"path\": \"<manifest or file>\", \"package\": \"<name or null>\", \
+         \"license\": \"<SPDX id or null>\", \"detail\": \"<one sentence>\"}]}\n\
+         Use {\"findings\": []} if everything is compliant.",
+    );
+    out
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    findings: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ReplyFinding {
+    kind: FindingKind,
+    #[serde(default)]
+    severity: Option<Level>,
+    path: String,
+    #[serde(default)]
+    package: Option<String>,
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    detail: String,
+}
+
+/// A model's findings, tolerating prose or fences around the JSON. Entries
+/// that don't fit the schema are dropped. None when there is no findings list.
+pub fn parse(model: &str, text: &str) -> Option<Vec<LicenseFinding>> {
+    let reply: Reply =
+        crate::json_repair::parse(text).and_then(|(doc, _)| serde_json::from_value(doc).ok())?;
+    Some(
+        reply
+            .findings
+            .into_iter()
+            .filter_map(|v| serde_json::from_value::<ReplyFinding>(v).ok())
+            .filter(|f| !f.path.trim().is_empty())
+            .map(|f| LicenseFinding {
+                kind: f.kind,
+                severity: f.severity.unwrap_or(match f.kind {
+                    FindingKind::UnknownLicense => Level::Warning,
+                    _ => Level::Error,
+                }),
+                path: f.path.trim().trim_start_matches("./").to_string(),
+                package: f
+                    .package
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty()),
+                license: f
+                    .license
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty()),
+                detail: f.detail.trim().to_string(),
+                models: vec![model.to_string()],
+            })
+            .collect(),
+    )
+}
+
+/// New files whose first `HEADER_LINES` lines don't contain `header`.
+pub fn missing_headers(scan: &Scan, header: &str) -> Vec<LicenseFinding> {
+    scan.headers
+        .iter()
+        .filter(|(_, head)| !head.contains(header))
+        .map(|(path, _)| LicenseFinding {
+            kind: FindingKind::MissingHeader,
+            severity: Level::Error,
+            path: path.clone(),
+            package: None,
+            license: None,
+            detail: format!("\"{header}\" not found in the first {HEADER_LINES} lines"),
+            models: Vec::new(),
+        })
+        .collect()
+}
+
+/// Merge findings on the same kind, path and package: the models are joined
+/// and the stricter severity kept. Errors first, then by path.
+pub fn merge(findings: Vec<LicenseFinding>) -> Vec<LicenseFinding> {
+    let key = |f: &LicenseFinding| {
+        (
+            f.kind,
+            f.path.clone(),
+            f.package.as_deref().map(str::to_lowercase),
+        )
+    };
+    let mut merged: Vec<LicenseFinding> = Vec::new();
+    for finding in findings {
+        match merged.iter_mut().find(|m| key(m) == key(&finding)) {
+            Some(m) => {
+                m.severity = m.severity.max(finding.severity);
+                for model in finding.models {
+                    if !m.models.contains(&model) {
+                        m.models.push(model);
+                    }
+                }
+                if m.license.is_none() {
+                    m.license = finding.license;
+                }
+            }
+            None => merged.push(finding),
+        }
+    }
+    merged.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.kind.cmp(&b.kind))
+    });
+    merged
+}
+
+pub fn to_markdown(report: &LicenseReport) -> String {
+    let mut md = format!(
+        "## License scan: {}\n{} manifest(s), {} new file(s); license: {}\n",
+        if report.passed { "passed" } else { "FAILED" },
+        report.manifests.len(),
+        report.new_files.len(),
+        report.license_file.as_deref().unwrap_or("none found"),
+    );
+    if report.findings.is_empty() {
+        md.push_str("\nNo findings.\n");
+    } else {
+        md.push_str("\n### Findings\n");
+    }
+    for f in &report.findings {
+        let level = match f.severity {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        };
+        md.push_str(&format!("- [{level}] {} — `{}`", f.kind.as_str(), f.path));
+        if let Some(ref package) = f.package {
+            md.push_str(&format!(" {package}"));
+        }
+        if let Some(ref license) = f.license {
+            md.push_str(&format!(" ({license})"));
+        }
+        if !f.detail.is_empty() {
+            md.push_str(&format!(": {}", f.detail));
+        }
+        if f.models.is_empty() {
+            md.push_str(" [header check]");
+        } else {
+            md.push_str(&format!(" [{}]", f.models.join(", ")));
+        }
+        md.push('\n');
+    }
+    if !report.errors.is_empty() {
+        md.push_str("\n### Errors\n");
+        for e in &report.errors {
+            md.push_str(&format!("- {e}\n"));
+        }
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan() -> Scan {
+        Scan {
+            license: Some(("LICENSE".to_string(), "MIT License".to_string())),
+            manifests: vec![(
+                "Cargo.toml".to_string(),
+                "[dependencies]\nfoo = \"1\"".to_string(),
+            )],
+            headers: vec![
+                (
+                    "src/new.rs".to_string(),
+                    "// SPDX-License-Identifier: MIT\nfn main() {}".to_string(),
+                ),
+                ("src/bare.rs".to_string(), "fn bare() {}".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn replies_are_parsed_and_merged() {
+        let grok = parse(
+            "grok",
+            "Found one:\n```json\n{\"findings\": [\
+             {\"kind\": \"incompatible_license\", \"path\": \"./Cargo.toml\", \"package\": \"Foo\", \"license\": \"GPL-3.0\", \"detail\": \"Copyleft.\"},\
+             {\"kind\": \"bogus\", \"path\": \"x\"}]}\n```",
+        )
+        .unwrap();
+        assert_eq!(grok.len(), 1);
+        assert_eq!(grok[0].severity, Level::Error);
+        assert_eq!(grok[0].path, "Cargo.toml");
+        let kimi = parse(
+            "kimi",
+            r#"{"findings": [
+                {"kind": "incompatible_license", "severity": "warning", "path": "Cargo.toml", "package": "foo", "detail": "GPL"},
+                {"kind": "unknown_license", "path": "Cargo.toml", "package": "bar", "detail": "No license field"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(kimi[1].severity, Level::Warning);
+        assert!(parse("kimi", "Everything looks compliant.").is_none());
+        assert_eq!(parse("kimi", r#"{"findings": []}"#), Some(Vec::new()));
+
+        let merged = merge(grok.into_iter().chain(kimi).collect());
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].models, ["grok", "kimi"]);
+        assert_eq!(merged[0].severity, Level::Error);
+        assert_eq!(merged[0].license.as_deref(), Some("GPL-3.0"));
+        assert_eq!(merged[1].kind, FindingKind::UnknownLicense);
+    }
+
+    #[test]
+    fn header_check_flags_files_without_it() {
+        let scan = scan();
+        let missing = missing_headers(&scan, "SPDX-License-Identifier: MIT");
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].path, "src/bare.rs");
+        assert!(missing[0].models.is_empty());
+
+        let report = LicenseReport::new(&scan, merge(missing), Vec::new());
+        assert!(!report.passed);
+        assert_eq!(report.new_files, ["src/new.rs", "src/bare.rs"]);
+        let md = to_markdown(&report);
+        assert!(md.starts_with("## License scan: FAILED\n"));
+        assert!(md.contains("- [error] missing header — `src/bare.rs`"));
+        assert!(md.contains("[header check]"));
+
+        let prompt = prompt(&scan, Some("SPDX-License-Identifier: MIT"), None);
+        assert!(prompt.contains("<manifest path=\"Cargo.toml\">"));
+        assert!(prompt.contains("<new_file path=\"src/bare.rs\" lines=\"1-20\">"));
+        assert!(prompt.contains("must carry this header: SPDX-License-Identifier: MIT"));
+    }
+
+    #[tokio::test]
+    async fn collect_reads_license_manifests_and_new_files() {
+        let dir = std::env::temp_dir().join(format!("squall_license_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("web")).unwrap();
+        std::fs::write(dir.join("LICENSE"), "Apache License 2.0").unwrap();
+        std::fs::write(dir.join("package.json"), "{\"license\": \"MIT\"}").unwrap();
+        std::fs::write(dir.join("web/package.json"), "{}").unwrap();
+        std::fs::write(dir.join("logo.png"), [0x89, b'P', 0, 1]).unwrap();
+
+        let new = ["web/package.json".to_string(), "logo.png".to_string()];
+        let scan = Scan::collect(&dir, &new).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(scan.license.as_ref().unwrap().0, "LICENSE");
+        let manifests: Vec<&str> = scan.manifests.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(manifests, ["package.json", "web/package.json"]);
+        assert_eq!(scan.headers.len(), 1);
+    }
+}
@@ -0,0 +1,271 @@
+//! Process-wide tracing setup with a reloadable filter, so a running server's
+//! log level can change without a restart (`set_log_level` tool, MCP
+//! `logging/setLevel`).
+//!
+//! `SQUALL_LOG_FORMAT=json` switches stderr to one JSON object per line, with
+//! the enclosing spans' fields attached: review dispatch runs inside a `review`
+//! span carrying `review_id`, and each model call inside a `model` span.
+//!
+//! `[logging] file = true` also writes logs to `.squall/logs/squall.log`,
+//! rotated by size, since an MCP client often swallows its server's stderr.
+//! The file layer is installed up front and stays dormant until config turns
+//! it on, because config loads after the subscriber (and logs while loading).
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing_subscriber::filter::FilterFn;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
+
+use crate::config::LoggingConfig;
+
+/// Env var selecting the log format: `text` (default) or `json`.
+pub const LOG_FORMAT_ENV: &str = "SQUALL_LOG_FORMAT";
+
+/// Directory for log files, relative to the working directory like `.squall/reviews`.
+pub const LOG_DIR: &str = ".squall/logs";
+
+/// The active log file, once `enable_file_log` has opened it.
+static FILE_LOG: Mutex<Option<RotatingFile>> = Mutex::new(None);
+/// Mirrors `FILE_LOG.is_some()` so the file layer skips formatting while dormant.
+static FILE_LOG_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Handle for swapping the active filter at runtime.
+#[derive(Clone)]
+pub struct LogLevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogLevelHandle {
+    /// The active filter, in `RUST_LOG` syntax.
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|f| f.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Replace the filter. `filter` uses `RUST_LOG` syntax: a level ("debug") or
+    /// directives ("info,squall::dispatch=trace"). Returns the previous filter.
+    pub fn set(&self, filter: &str) -> Result<String, String> {
+        let new = EnvFilter::try_new(filter.trim())
+            .map_err(|e| format!("invalid log filter '{filter}': {e}"))?;
+        let previous = self.current();
+        self.handle
+            .reload(new)
+            .map_err(|e| format!("failed to apply log filter: {e}"))?;
+        tracing::info!("log filter changed: {previous} -> {}", self.current());
+        Ok(previous)
+    }
+}
+
+/// A log file that rolls over to `<name>.1`, `<name>.2`, ... once a write would
+/// take it past `max_bytes`, keeping at most `max_files` rolled-over files.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    /// Open `path` for appending, creating it and its directory if needed.
+    pub fn open(path: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes: max_bytes.max(1),
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn rolled(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.rolled(self.max_files));
+            for n in (1..self.max_files).rev() {
+                match std::fs::rename(self.rolled(n), self.rolled(n + 1)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            }
+            std::fs::rename(&self.path, self.rolled(1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Writer handed to the file layer; discards output until a file is open.
+struct FileLogWriter;
+
+impl Write for FileLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut file = FILE_LOG.lock().unwrap_or_else(|e| e.into_inner());
+        match file.as_mut() {
+            Some(f) => f.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut file = FILE_LOG.lock().unwrap_or_else(|e| e.into_inner());
+        file.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+/// Start writing logs to `.squall/logs/squall.log` if `[logging] file` is on.
+/// Returns the log file's path, or None when file logging is off.
+pub fn enable_file_log(config: &LoggingConfig) -> io::Result<Option<PathBuf>> {
+    if !config.file {
+        return Ok(None);
+    }
+    let path = Path::new(LOG_DIR).join("squall.log");
+    let file = RotatingFile::open(&path, config.max_file_bytes, config.max_files)?;
+    *FILE_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    FILE_LOG_ENABLED.store(true, Ordering::Relaxed);
+    Ok(Some(path))
+}
+
+fn fmt_layer<S, W>(json: bool, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    if json {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .boxed()
+    }
+}
+
+/// Install the global subscriber: logs on stderr (stdout carries the MCP stream)
+/// in the `SQUALL_LOG_FORMAT` format, filtered by `RUST_LOG` plus a default of INFO.
+/// The same lines go to the log file once `enable_file_log` opens one.
+pub fn init() -> LogLevelHandle {
+    let filter = EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into());
+    let (filter, handle) = reload::Layer::new(filter);
+    let json = match std::env::var(LOG_FORMAT_ENV) {
+        Ok(format) if format.eq_ignore_ascii_case("json") => true,
+        Ok(format) if !format.is_empty() && !format.eq_ignore_ascii_case("text") => {
+            eprintln!("unknown {LOG_FORMAT_ENV} '{format}', using text");
+            false
+        }
+        _ => false,
+    };
+    let file_layer = fmt_layer(json, || FileLogWriter)
+        .with_filter(FilterFn::new(|_| FILE_LOG_ENABLED.load(Ordering::Relaxed)));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(json, std::io::stderr))
+        .with(file_layer)
+        .init();
+    LogLevelHandle { handle }
+}
+
+/// Map an MCP logging level (RFC 5424 names) to a tracing filter.
+pub fn filter_for_mcp_level(level: &str) -> Option<&'static str> {
+    Some(match level {
+        "debug" => "debug",
+        "info" | "notice" => "info",
+        "warning" => "warn",
+        "error" | "critical" | "alert" | "emergency" => "error",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mcp_levels_map_to_tracing_filters() {
+        assert_eq!(filter_for_mcp_level("notice"), Some("info"));
+        assert_eq!(filter_for_mcp_level("warning"), Some("warn"));
+        assert_eq!(filter_for_mcp_level("emergency"), Some("error"));
+        assert_eq!(filter_for_mcp_level("verbose"), None);
+    }
+
+    #[test]
+    fn set_rejects_invalid_filters_and_reports_previous() {
+        // The handle only works while its layer is alive.
+        let (_layer, handle) = reload::Layer::<_, Registry>::new(EnvFilter::new("info"));
+        let handle = LogLevelHandle { handle };
+        assert!(handle.set("squall=notalevel").is_err());
+        assert_eq!(handle.current(), "info");
+        assert_eq!(handle.set("debug").unwrap(), "info");
+        assert_eq!(handle.current(), "debug");
+    }
+
+    #[test]
+    fn rotating_file_rolls_over_and_caps_kept_files() {
+        let dir = std::env::temp_dir().join(format!("squall-test-logs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("squall.log");
+        let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        assert_eq!(read(&path), "dddddddd\n");
+        assert_eq!(read(&dir.join("squall.log.1")), "cccccccc\n");
+        assert_eq!(read(&dir.join("squall.log.2")), "bbbbbbbb\n");
+        assert!(!dir.join("squall.log.3").exists());
+
+        // Reopening appends and counts the existing size toward the limit.
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+        file.write_all(b"e\n").unwrap();
+        assert_eq!(read(&path), "e\n");
+        assert_eq!(read(&dir.join("squall.log.1")), "dddddddd\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,46 +1,41 @@
 use rmcp::{ServiceExt, transport::stdio};
 
-use squall::config::Config;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use squall::config::{Config, PROFILE_ENV, parse_profile};
+use squall::daemon::DaemonInfo;
+use squall::dispatch::registry::Registry;
+use squall::dispatch::remote::WORKER_TOKEN_ENV;
 use squall::server::SquallServer;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .with_writer(std::io::stderr)
-        .with_ansi(false)
-        .init();
+    let log_level = squall::logging::init();
 
     // Load .env from the binary's directory (MCP servers may start with any CWD).
     // Falls back to dotenvy's default CWD search if the binary path can't be resolved.
-    if let Ok(exe) = std::env::current_exe() {
-        if let Some(dir) = exe.parent() {
-            let env_path = dir.join(".env");
-            if env_path.exists() {
-                dotenvy::from_path(&env_path).ok();
-            } else {
-                // Try the cargo project root (development builds: target/release/../..)
-                let project_root = dir.join("../../.env");
-                if project_root.exists() {
-                    dotenvy::from_path(&project_root).ok();
-                } else {
-                    dotenvy::dotenv().ok();
-                }
-            }
-        } else {
-            dotenvy::dotenv().ok();
-        }
-    } else {
-        dotenvy::dotenv().ok();
+    load_env_files();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("worker") => return run_worker(&args[1..]).await,
+        Some("daemon") => return run_daemon(&args[1..], log_level).await,
+        Some("attach") => return run_attach(&args[1..]).await,
+        Some("--socket") => return run_socket(&args[1..], log_level).await,
+        Some("install-hooks") => return run_install_hooks(&args[1..]).await,
+        Some("flush-on-merge") => return run_flush_on_merge(&args[1..]).await,
+        _ => {}
     }
 
     tracing::info!("squall starting");
 
     let config = Config::load();
-    let server = SquallServer::new(config);
+    enable_file_log(&config);
+    report_config_errors(&config);
+    let server = SquallServer::new(config).with_log_level(log_level);
+    server.spawn_warmup();
 
     let service = server
         .serve(stdio())
@@ -52,3 +47,244 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("squall shutting down");
     Ok(())
 }
+
+/// Load `.env` from the binary's directory, the cargo project root (development
+/// builds: target/release/../..), or else the CWD search. With `SQUALL_PROFILE=work`,
+/// `.env.work` beside it is loaded first; dotenvy never overwrites a variable, so
+/// the profile's keys win and `.env` fills in the rest.
+fn load_env_files() {
+    // An invalid name is reported by `Config::load`.
+    let profile = std::env::var(PROFILE_ENV)
+        .ok()
+        .and_then(|v| parse_profile(&v).ok().flatten());
+    let profile_file = profile.as_ref().map(|p| format!(".env.{p}"));
+    let has_env = |dir: &Path| {
+        dir.join(".env").exists() || profile_file.as_ref().is_some_and(|f| dir.join(f).exists())
+    };
+    let env_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .and_then(|dir| {
+            [dir.clone(), dir.join("../..")]
+                .into_iter()
+                .find(|d| has_env(d))
+        });
+
+    let mut profile_loaded = false;
+    match env_dir {
+        Some(dir) => {
+            if let Some(ref file) = profile_file {
+                profile_loaded = dotenvy::from_path(dir.join(file)).is_ok();
+            }
+            dotenvy::from_path(dir.join(".env")).ok();
+        }
+        None => {
+            if let Some(ref file) = profile_file {
+                profile_loaded = dotenvy::from_filename(file).is_ok();
+            }
+            dotenvy::dotenv().ok();
+        }
+    }
+    if let Some(name) = profile {
+        let config_exists = squall::config::profile_config_path(&name).is_some_and(|p| p.exists());
+        if profile_loaded || config_exists {
+            tracing::info!("using profile '{name}'");
+        } else {
+            tracing::warn!(
+                "{PROFILE_ENV}={name}: found neither .env.{name} nor profiles/{name}.toml"
+            );
+        }
+    }
+}
+
+/// Log config errors at startup; `doctor` lists them (and warnings) on demand.
+fn report_config_errors(config: &Config) {
+    let errors: Vec<String> = config
+        .validation
+        .errors()
+        .map(ToString::to_string)
+        .collect();
+    if !errors.is_empty() {
+        tracing::error!(
+            "config has {} error(s), run the doctor tool for details: {}",
+            errors.len(),
+            errors.join("; ")
+        );
+    }
+}
+
+/// `squall worker [--listen ADDR]`: serve this host's models to remote coordinators.
+async fn run_worker(args: &[String]) -> anyhow::Result<()> {
+    let listen = match args {
+        [] => squall::worker::DEFAULT_LISTEN.to_string(),
+        [flag, addr] if flag == "--listen" => addr.clone(),
+        _ => anyhow::bail!("usage: squall worker [--listen ADDR]"),
+    };
+    let token = std::env::var(WORKER_TOKEN_ENV).unwrap_or_default();
+    if token.trim().is_empty() {
+        anyhow::bail!("{WORKER_TOKEN_ENV} must be set to the token coordinators present");
+    }
+
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        tracing::warn!(
+            "worker listening on {addr} over plain HTTP — put it behind a TLS proxy \
+             or reach it through an SSH tunnel"
+        );
+    }
+
+    let config = Config::load();
+    enable_file_log(&config);
+    report_config_errors(&config);
+    tracing::info!(
+        "squall worker serving {} model(s) on {addr}",
+        config.models.len()
+    );
+    squall::worker::serve(listener, Arc::new(Registry::from_config(config)), token).await;
+    Ok(())
+}
+
+/// `squall daemon [--listen ADDR] [--idle-timeout SECS]`: a resident MCP server
+/// that `squall attach` clients share. `--idle-timeout 0` never exits.
+async fn run_daemon(
+    args: &[String],
+    log_level: squall::logging::LogLevelHandle,
+) -> anyhow::Result<()> {
+    const USAGE: &str = "usage: squall daemon [--listen ADDR] [--idle-timeout SECS]";
+    let mut listen = squall::daemon::DEFAULT_LISTEN.to_string();
+    let mut idle_timeout = Some(squall::daemon::DEFAULT_IDLE_TIMEOUT);
+    let mut rest = args.iter();
+    while let Some(flag) = rest.next() {
+        let Some(value) = rest.next() else {
+            anyhow::bail!(USAGE);
+        };
+        match flag.as_str() {
+            "--listen" => listen = value.clone(),
+            "--idle-timeout" => {
+                let secs: u64 = value.parse().map_err(|_| anyhow::anyhow!(USAGE))?;
+                idle_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            _ => anyhow::bail!(USAGE),
+        }
+    }
+    let Some(info_path) = DaemonInfo::default_path() else {
+        anyhow::bail!("HOME must be set so clients can find the daemon");
+    };
+
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        anyhow::bail!("daemon must listen on a loopback address, not {addr}");
+    }
+
+    let config = Config::load();
+    enable_file_log(&config);
+    report_config_errors(&config);
+    let server = SquallServer::new(config).with_log_level(log_level);
+    server.spawn_warmup();
+    let info = DaemonInfo {
+        addr,
+        token: squall::daemon::new_token(),
+        pid: std::process::id(),
+    };
+    info.write(&info_path)?;
+    tracing::info!("squall daemon listening on {addr}");
+
+    squall::daemon::serve(listener, server, info.token.clone(), idle_timeout).await;
+    info.remove(&info_path);
+    tracing::info!("squall daemon shutting down");
+    Ok(())
+}
+
+/// `squall --socket PATH`: serve MCP sessions on a unix socket instead of stdio.
+#[cfg(unix)]
+async fn run_socket(
+    args: &[String],
+    log_level: squall::logging::LogLevelHandle,
+) -> anyhow::Result<()> {
+    let [path] = args else {
+        anyhow::bail!("usage: squall --socket PATH");
+    };
+    let path = std::path::Path::new(path);
+    let listener = squall::daemon::bind_unix(path).await?;
+
+    let config = Config::load();
+    enable_file_log(&config);
+    report_config_errors(&config);
+    let server = SquallServer::new(config).with_log_level(log_level);
+    server.spawn_warmup();
+    tracing::info!("squall serving on unix socket {}", path.display());
+    tokio::select! {
+        () = squall::daemon::serve_unix(listener, server) => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    let _ = std::fs::remove_file(path);
+    tracing::info!("squall shutting down");
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn run_socket(
+    _args: &[String],
+    _log_level: squall::logging::LogLevelHandle,
+) -> anyhow::Result<()> {
+    anyhow::bail!("--socket needs unix domain sockets, which this platform lacks")
+}
+
+/// `squall attach`: the MCP command for editors using the daemon.
+async fn run_attach(args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: squall attach");
+    }
+    let Some(info_path) = DaemonInfo::default_path() else {
+        anyhow::bail!("HOME must be set to find the daemon");
+    };
+    squall::daemon::attach(&info_path).await?;
+    Ok(())
+}
+
+/// `squall install-hooks [DIR]`: git hooks that flush merged branches' memory.
+async fn run_install_hooks(args: &[String]) -> anyhow::Result<()> {
+    let repo = match args {
+        [] => std::env::current_dir()?,
+        [dir] => dir.into(),
+        _ => anyhow::bail!("usage: squall install-hooks [DIR]"),
+    };
+    let exe = std::env::current_exe()?;
+    let written = squall::hooks::install(&repo, &exe)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    if written.is_empty() {
+        println!("squall hooks already installed");
+    }
+    for path in written {
+        println!("installed {}", path.display());
+    }
+    Ok(())
+}
+
+/// `squall flush-on-merge`: run by the hooks from the repository root.
+async fn run_flush_on_merge(args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: squall flush-on-merge");
+    }
+    let repo = std::env::current_dir()?;
+    let memory = squall::memory::MemoryStore::new().for_project(&repo);
+    for report in squall::hooks::flush_on_merge(&repo, &memory)
+        .await
+        .map_err(anyhow::Error::msg)?
+    {
+        println!("{report}");
+    }
+    Ok(())
+}
+
+/// Start the `[logging]` log file, if configured. Failure only costs the file.
+fn enable_file_log(config: &Config) {
+    match squall::logging::enable_file_log(&config.logging) {
+        Ok(Some(path)) => tracing::info!("logging to {}", path.display()),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("failed to open log file: {e}"),
+    }
+}
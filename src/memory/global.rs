@@ -9,13 +9,17 @@
 //!
 //! All code is gated with `#[cfg(feature = "global-memory")]` at the module level.
 
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
+use std::sync::{Arc, Mutex, mpsc};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use duckdb::params;
+use serde::Serialize;
 
 use super::schema::{self, ModelEvent, ProjectInfo};
+use super::sync::GlobalSync;
+use crate::tools::enums::PromptSize;
 use crate::tools::review::ReviewModelResult;
 
 // ---------------------------------------------------------------------------
@@ -29,14 +33,79 @@ use crate::tools::review::ReviewModelResult;
 /// guaranteed non-blocking).
 pub struct GlobalWriter {
     tx: mpsc::SyncSender<DbCommand>,
+    db_path: PathBuf,
     events_dir: PathBuf,
     worker_handle: Option<std::thread::JoinHandle<()>>,
+    status: SharedStatus,
+    sync_remote: Option<String>,
 }
 
 /// Recommendations returned from a global DuckDB query.
 #[derive(Debug, Default)]
 pub struct GlobalRecommendations {
     pub models: Vec<GlobalModelStats>,
+    /// Per-model latency by time-of-day window (see `schema::LOAD_WINDOWS`).
+    pub load: Vec<LoadWindowStats>,
+}
+
+/// Average latency of one model's successful calls within one load window.
+#[derive(Debug)]
+pub struct LoadWindowStats {
+    pub model_key: String,
+    /// Window name from `schema::LOAD_WINDOWS`.
+    pub window: String,
+    pub avg_latency_ms: f64,
+    pub sample_count: u64,
+}
+
+/// A model at least this many times slower in its worst window than its best
+/// one is called out in recommendations.
+const LOAD_SKEW_RATIO: f64 = 1.5;
+
+/// Successful calls a window needs before its average is trusted.
+const MIN_LOAD_WINDOW_SAMPLES: usize = 5;
+
+impl GlobalRecommendations {
+    /// Models whose latency depends on when they are called, as sentences like
+    /// `grok is 3.1x slower during US business hours (...) than on weekends (95s vs 31s avg)`.
+    /// Slowest skew first.
+    pub fn load_skew(&self) -> Vec<String> {
+        let mut skews: Vec<(f64, String)> = Vec::new();
+        let mut models: Vec<&str> = self.load.iter().map(|l| l.model_key.as_str()).collect();
+        models.dedup();
+        for model in models {
+            let windows = self.load.iter().filter(|l| l.model_key == model);
+            let by_latency = |a: &&LoadWindowStats, b: &&LoadWindowStats| {
+                a.avg_latency_ms
+                    .partial_cmp(&b.avg_latency_ms)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            };
+            let (Some(fast), Some(slow)) = (
+                windows.clone().min_by(by_latency),
+                windows.max_by(by_latency),
+            ) else {
+                continue;
+            };
+            if fast.avg_latency_ms <= 0.0 {
+                continue;
+            }
+            let ratio = slow.avg_latency_ms / fast.avg_latency_ms;
+            if ratio >= LOAD_SKEW_RATIO {
+                skews.push((
+                    ratio,
+                    format!(
+                        "{model} is {ratio:.1}x slower {} than {} ({:.0}s vs {:.0}s avg)",
+                        schema::load_window_label(&slow.window),
+                        schema::load_window_label(&fast.window),
+                        slow.avg_latency_ms / 1000.0,
+                        fast.avg_latency_ms / 1000.0,
+                    ),
+                ));
+            }
+        }
+        skews.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        skews.into_iter().map(|(_, line)| line).collect()
+    }
 }
 
 /// Per-model stats from global aggregation.
@@ -49,6 +118,168 @@ pub struct GlobalModelStats {
     pub p95_latency_ms: f64,
 }
 
+/// Snapshot of the global memory worker's health (`global_memory_status` tool).
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalMemoryStatus {
+    pub db_path: String,
+    /// DuckDB file plus its WAL. None if the DB hasn't been created yet.
+    pub db_size_bytes: Option<u64>,
+    pub worker_running: bool,
+    pub bootstrap: BootstrapState,
+    /// Commands sent to the worker but not yet processed.
+    pub queued_commands: usize,
+    /// Parquet event files waiting for the next merge.
+    pub pending_event_files: usize,
+    /// Parquet event files written by this process.
+    pub event_files_written: u64,
+    pub last_merge_ms: Option<u64>,
+    pub last_merge_files: usize,
+    /// Commands dropped because the channel was full or the worker had exited.
+    pub dropped_commands: u64,
+    /// Team sync remote (query string stripped). None = local only.
+    pub sync_remote: Option<String>,
+    pub last_sync_ms: Option<u64>,
+    /// Most recent worker errors, oldest first.
+    pub recent_errors: Vec<WorkerError>,
+}
+
+/// Progress of the one-time ingest of local models.md into DuckDB.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BootstrapState {
+    #[default]
+    NotStarted,
+    Queued,
+    Running,
+    Done {
+        inserted: u32,
+        skipped: u32,
+    },
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerError {
+    pub ts_ms: u64,
+    pub message: String,
+}
+
+/// Max worker errors kept for status reports.
+const MAX_RECENT_ERRORS: usize = 10;
+
+/// Status shared between `GlobalWriter` and its `DbWorker`.
+#[derive(Default)]
+struct StatusInner {
+    bootstrap: BootstrapState,
+    queued: usize,
+    event_files_written: u64,
+    last_merge_ms: Option<u64>,
+    last_merge_files: usize,
+    last_sync_ms: Option<u64>,
+    dropped: u64,
+    errors: VecDeque<WorkerError>,
+}
+
+type SharedStatus = Arc<Mutex<StatusInner>>;
+
+/// Update shared status. A poisoned lock only loses diagnostics, never work.
+fn update_status(status: &SharedStatus, f: impl FnOnce(&mut StatusInner)) {
+    if let Ok(mut inner) = status.lock() {
+        f(&mut inner);
+    }
+}
+
+/// Log a warning and keep it for `global_memory_status`.
+fn record_error(status: &SharedStatus, message: String) {
+    tracing::warn!("global memory: {message}");
+    update_status(status, |s| {
+        if s.errors.len() == MAX_RECENT_ERRORS {
+            s.errors.pop_front();
+        }
+        s.errors.push_back(WorkerError {
+            ts_ms: epoch_ms(),
+            message,
+        });
+    });
+}
+
+impl GlobalMemoryStatus {
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Global Memory Status\n\n");
+        out.push_str(&format!("- **Database:** `{}`", self.db_path));
+        match self.db_size_bytes {
+            Some(bytes) => out.push_str(&format!(" ({:.1} KB)\n", bytes as f64 / 1024.0)),
+            None => out.push_str(" (not created yet)\n"),
+        }
+        out.push_str(&format!(
+            "- **Worker:** {}\n",
+            if self.worker_running {
+                "running"
+            } else {
+                "stopped"
+            }
+        ));
+        let bootstrap = match &self.bootstrap {
+            BootstrapState::NotStarted => "not started (runs after the first review)".to_string(),
+            BootstrapState::Queued => "queued".to_string(),
+            BootstrapState::Running => "running".to_string(),
+            BootstrapState::Done { inserted, skipped } => {
+                format!("done ({inserted} events ingested, {skipped} skipped)")
+            }
+            BootstrapState::Skipped { reason } => format!("skipped ({reason})"),
+            BootstrapState::Failed { error } => format!("failed: {error}"),
+        };
+        out.push_str(&format!("- **Bootstrap:** {bootstrap}\n"));
+        out.push_str(&format!(
+            "- **Queued:** {} command(s), {} event file(s) awaiting merge ({} written this session)\n",
+            self.queued_commands, self.pending_event_files, self.event_files_written
+        ));
+        match self.last_merge_ms {
+            Some(ms) => out.push_str(&format!(
+                "- **Last merge:** {} ({} file(s))\n",
+                super::local::iso_from_epoch_ms(ms as i64),
+                self.last_merge_files
+            )),
+            None => out.push_str("- **Last merge:** none this session\n"),
+        }
+        if let Some(remote) = &self.sync_remote {
+            match self.last_sync_ms {
+                Some(ms) => out.push_str(&format!(
+                    "- **Team sync:** `{remote}`, last synced {}\n",
+                    super::local::iso_from_epoch_ms(ms as i64)
+                )),
+                None => out.push_str(&format!(
+                    "- **Team sync:** `{remote}`, not synced this session\n"
+                )),
+            }
+        }
+        if self.dropped_commands > 0 {
+            out.push_str(&format!(
+                "- **Dropped commands:** {} (worker busy or stopped)\n",
+                self.dropped_commands
+            ));
+        }
+        if self.recent_errors.is_empty() {
+            out.push_str("\nNo worker errors.\n");
+        } else {
+            out.push_str("\n### Recent errors\n");
+            for e in &self.recent_errors {
+                out.push_str(&format!(
+                    "- {} — {}\n",
+                    super::local::iso_from_epoch_ms(e.ts_ms as i64),
+                    e.message
+                ));
+            }
+        }
+        out
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Actor commands
 // ---------------------------------------------------------------------------
@@ -62,10 +293,14 @@ enum DbCommand {
     /// Query global recommendations (excluding a specific project).
     QueryRecommendations {
         exclude_project_id: Option<String>,
+        /// Count only events in this prompt-size bucket.
+        prompt_size: Option<PromptSize>,
         reply: tokio::sync::oneshot::Sender<Result<GlobalRecommendations, String>>,
     },
     /// Merge pending Parquet files into the compacted DuckDB table.
     MergeParquet,
+    /// Pull, merge, and push the team-shared snapshot (no-op without a remote).
+    Sync,
     /// Bootstrap: ingest local models.md events into DuckDB.
     Bootstrap {
         models_md_path: PathBuf,
@@ -88,6 +323,12 @@ impl GlobalWriter {
     /// - DuckDB fails to open
     /// - Worker thread fails to spawn
     pub fn new(db_path: PathBuf) -> Option<Self> {
+        Self::with_sync(db_path, None)
+    }
+
+    /// Like `new`, also syncing with a team-shared snapshot: on startup, every
+    /// `sync.interval()` (checked at merge time), on `trigger_sync`, and on shutdown.
+    pub fn with_sync(db_path: PathBuf, sync: Option<GlobalSync>) -> Option<Self> {
         let events_dir = db_path.parent().unwrap_or(Path::new(".")).join("events");
 
         // Ensure directories exist
@@ -107,10 +348,13 @@ impl GlobalWriter {
         let (tx, rx) = mpsc::sync_channel(128);
         let worker_db_path = db_path.clone();
         let worker_events_dir = events_dir.clone();
+        let status = SharedStatus::default();
+        let worker_status = status.clone();
+        let sync_remote = sync.as_ref().map(|s| s.display());
 
         let builder = std::thread::Builder::new().name("squall-global-db".into());
         let handle = match builder.spawn(move || {
-            DbWorker::run(rx, worker_db_path, worker_events_dir);
+            DbWorker::run(rx, worker_db_path, worker_events_dir, worker_status, sync);
         }) {
             Ok(h) => h,
             Err(e) => {
@@ -121,11 +365,30 @@ impl GlobalWriter {
 
         Some(Self {
             tx,
+            db_path,
             events_dir,
             worker_handle: Some(handle),
+            status,
+            sync_remote,
         })
     }
 
+    /// Non-blocking send that keeps the queued/dropped counters current.
+    fn try_send(&self, command: DbCommand, name: &str) -> bool {
+        update_status(&self.status, |s| s.queued += 1);
+        match self.tx.try_send(command) {
+            Ok(()) => true,
+            Err(e) => {
+                update_status(&self.status, |s| {
+                    s.queued -= 1;
+                    s.dropped += 1;
+                });
+                record_error(&self.status, format!("failed to send {name} command: {e}"));
+                false
+            }
+        }
+    }
+
     /// Log model events from a completed review.
     ///
     /// Converts `ReviewModelResult` entries into `ModelEvent` structs and sends
@@ -181,38 +444,44 @@ impl GlobalWriter {
             last_seen_ts: now_ms as i64,
         };
 
-        if let Err(e) = self
-            .tx
-            .try_send(DbCommand::WriteParquet { events, project })
-        {
-            tracing::warn!("global memory: failed to send WriteParquet command: {e}");
-        }
+        self.try_send(DbCommand::WriteParquet { events, project }, "WriteParquet");
     }
 
-    /// Query global model recommendations, excluding the current project.
+    /// Query global model recommendations, excluding the current project and, with
+    /// `prompt_size`, counting only events in that bucket.
     ///
     /// Returns a `oneshot::Receiver` that the caller should `.await` in async context.
     /// The worker sends the result on the oneshot channel after processing the query.
     pub fn query_recommendations(
         &self,
         exclude_project_id: Option<&str>,
+        prompt_size: Option<PromptSize>,
     ) -> Result<tokio::sync::oneshot::Receiver<Result<GlobalRecommendations, String>>, String> {
         let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
-        self.tx
-            .send(DbCommand::QueryRecommendations {
-                exclude_project_id: exclude_project_id.map(|s| s.to_string()),
-                reply: reply_tx,
-            })
-            .map_err(|e| format!("global memory: worker dead: {e}"))?;
+        update_status(&self.status, |s| s.queued += 1);
+        if let Err(e) = self.tx.send(DbCommand::QueryRecommendations {
+            exclude_project_id: exclude_project_id.map(|s| s.to_string()),
+            prompt_size,
+            reply: reply_tx,
+        }) {
+            update_status(&self.status, |s| {
+                s.queued -= 1;
+                s.dropped += 1;
+            });
+            return Err(format!("global memory: worker dead: {e}"));
+        }
 
         Ok(reply_rx)
     }
 
     /// Trigger a merge of pending Parquet files into the compacted DuckDB table.
     pub fn trigger_merge(&self) {
-        if let Err(e) = self.tx.try_send(DbCommand::MergeParquet) {
-            tracing::warn!("global memory: failed to send MergeParquet command: {e}");
-        }
+        self.try_send(DbCommand::MergeParquet, "MergeParquet");
+    }
+
+    /// Sync with the team remote now (no-op if none is configured).
+    pub fn trigger_sync(&self) {
+        self.try_send(DbCommand::Sync, "Sync");
     }
 
     /// Send a bootstrap command to ingest local models.md into DuckDB.
@@ -224,12 +493,17 @@ impl GlobalWriter {
         project_id: String,
         id_to_key: std::collections::HashMap<String, String>,
     ) {
-        if let Err(e) = self.tx.try_send(DbCommand::Bootstrap {
+        let command = DbCommand::Bootstrap {
             models_md_path,
             project_id,
             id_to_key,
-        }) {
-            tracing::warn!("global memory: failed to send Bootstrap command: {e}");
+        };
+        if self.try_send(command, "Bootstrap") {
+            update_status(&self.status, |s| {
+                if s.bootstrap == BootstrapState::NotStarted {
+                    s.bootstrap = BootstrapState::Queued;
+                }
+            });
         }
     }
 
@@ -237,6 +511,37 @@ impl GlobalWriter {
     pub fn events_dir(&self) -> &Path {
         &self.events_dir
     }
+
+    /// Current worker health. Cheap: a lock, two `stat`s, and a directory listing.
+    pub fn status(&self) -> GlobalMemoryStatus {
+        let wal_path = PathBuf::from(format!("{}.wal", self.db_path.display()));
+        let db_size_bytes = std::fs::metadata(&self.db_path)
+            .ok()
+            .map(|m| m.len() + std::fs::metadata(&wal_path).map(|w| w.len()).unwrap_or(0));
+        let pending_event_files = list_parquet_files(&self.events_dir)
+            .map(|f| f.len())
+            .unwrap_or(0);
+        let worker_running = self
+            .worker_handle
+            .as_ref()
+            .is_some_and(|h| !h.is_finished());
+        let inner = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        GlobalMemoryStatus {
+            db_path: self.db_path.display().to_string(),
+            db_size_bytes,
+            worker_running,
+            bootstrap: inner.bootstrap.clone(),
+            queued_commands: inner.queued,
+            pending_event_files,
+            event_files_written: inner.event_files_written,
+            last_merge_ms: inner.last_merge_ms,
+            last_merge_files: inner.last_merge_files,
+            dropped_commands: inner.dropped,
+            sync_remote: self.sync_remote.clone(),
+            last_sync_ms: inner.last_sync_ms,
+            recent_errors: inner.errors.iter().cloned().collect(),
+        }
+    }
 }
 
 impl Drop for GlobalWriter {
@@ -259,18 +564,33 @@ struct DbWorker {
     events_dir: PathBuf,
     /// Counter for merge frequency: merge every N writes.
     write_count: u64,
+    status: SharedStatus,
+    sync: Option<GlobalSync>,
+    last_sync_attempt: Option<std::time::Instant>,
 }
 
 /// How many WriteParquet commands between automatic merge attempts.
 const MERGE_INTERVAL: u64 = 10;
 
+/// Recommendation lookback; also bounds what a team sync pushes.
+const LOOKBACK_MS: i64 = 90 * 86400 * 1000;
+
 impl DbWorker {
-    fn run(rx: mpsc::Receiver<DbCommand>, db_path: PathBuf, events_dir: PathBuf) {
+    fn run(
+        rx: mpsc::Receiver<DbCommand>,
+        db_path: PathBuf,
+        events_dir: PathBuf,
+        status: SharedStatus,
+        sync: Option<GlobalSync>,
+    ) {
         let mut worker = DbWorker {
             rx,
             db_path,
             events_dir,
             write_count: 0,
+            status,
+            sync,
+            last_sync_attempt: None,
         };
 
         // On startup, apply migrations (idempotent) and merge pending parquet files.
@@ -279,22 +599,33 @@ impl DbWorker {
             drop(conn);
         }
         worker.do_merge();
+        worker.do_sync();
 
         loop {
-            match worker.rx.recv() {
+            let command = worker.rx.recv();
+            if matches!(&command, Ok(c) if !matches!(c, DbCommand::Shutdown)) {
+                update_status(&worker.status, |s| s.queued = s.queued.saturating_sub(1));
+            }
+            match command {
                 Ok(DbCommand::WriteParquet { events, project }) => {
                     worker.handle_write_parquet(events, project);
                 }
                 Ok(DbCommand::QueryRecommendations {
                     exclude_project_id,
+                    prompt_size,
                     reply,
                 }) => {
-                    let result = worker.handle_query_recommendations(exclude_project_id.as_deref());
+                    let result = worker
+                        .handle_query_recommendations(exclude_project_id.as_deref(), prompt_size);
                     let _ = reply.send(result);
                 }
                 Ok(DbCommand::MergeParquet) => {
                     worker.do_merge();
                 }
+                Ok(DbCommand::Sync) => {
+                    worker.do_merge();
+                    worker.do_sync();
+                }
                 Ok(DbCommand::Bootstrap {
                     models_md_path,
                     project_id,
@@ -304,8 +635,9 @@ impl DbWorker {
                 }
                 Ok(DbCommand::Shutdown) => {
                     tracing::debug!("global memory: worker shutting down");
-                    // Final merge before exit
+                    // Final merge (and push) before exit
                     worker.do_merge();
+                    worker.do_sync();
                     break;
                 }
                 Err(_) => {
@@ -327,13 +659,13 @@ impl DbWorker {
         match duckdb::Connection::open(&self.db_path) {
             Ok(conn) => {
                 if let Err(e) = schema::apply_migrations(&conn) {
-                    tracing::warn!("global memory: migration failed: {e}");
+                    record_error(&self.status, format!("migration failed: {e}"));
                     return None;
                 }
                 Some(conn)
             }
             Err(e) => {
-                tracing::warn!("global memory: failed to open DuckDB: {e}");
+                record_error(&self.status, format!("failed to open DuckDB: {e}"));
                 None
             }
         }
@@ -354,9 +686,10 @@ impl DbWorker {
         let parquet_path = self.events_dir.join(&filename);
 
         if let Err(e) = write_parquet_file(&parquet_path, &events) {
-            tracing::warn!("global memory: parquet write failed: {e}");
+            record_error(&self.status, format!("parquet write failed: {e}"));
             return;
         }
+        update_status(&self.status, |s| s.event_files_written += 1);
 
         // Also persist project info for the merge step
         // (We'll upsert it during merge when we have a real connection)
@@ -365,6 +698,13 @@ impl DbWorker {
         // Auto-merge every MERGE_INTERVAL writes
         if self.write_count.is_multiple_of(MERGE_INTERVAL) {
             self.do_merge();
+            if self
+                .last_sync_attempt
+                .zip(self.sync.as_ref())
+                .is_some_and(|(at, sync)| at.elapsed() >= sync.interval())
+            {
+                self.do_sync();
+            }
         }
     }
 
@@ -372,19 +712,32 @@ impl DbWorker {
     fn handle_query_recommendations(
         &self,
         exclude_project_id: Option<&str>,
+        prompt_size: Option<PromptSize>,
     ) -> Result<GlobalRecommendations, String> {
         let conn = self
             .open_connection()
             .ok_or_else(|| "global memory: cannot open DuckDB for query".to_string())?;
 
-        // 90-day lookback window
-        let cutoff_ms = epoch_ms() as i64 - 90 * 86400 * 1000;
+        let cutoff_ms = epoch_ms() as i64 - LOOKBACK_MS;
 
         let exclude_clause = if exclude_project_id.is_some() {
             "AND project_id != ?2"
         } else {
             ""
         };
+        // Bucket literals come from `PromptSize::as_str`, never from user input.
+        let bucket_clause = prompt_size
+            .map(|size| format!("AND prompt_bucket = '{}'", size.as_str()))
+            .unwrap_or_default();
+        let parquet_bucket_clause = prompt_size
+            .map(|size| {
+                format!(
+                    "AND {} = '{}'",
+                    schema::prompt_bucket_sql("prompt_tokens"),
+                    size.as_str()
+                )
+            })
+            .unwrap_or_default();
 
         // Check if there are pending parquet files.
         // DuckDB's read_parquet errors on empty globs, so we only UNION ALL
@@ -393,64 +746,72 @@ impl DbWorker {
             .map(|f| !f.is_empty())
             .unwrap_or(false);
 
-        let sql = if has_parquet {
+        let parquet_events = if has_parquet {
             let events_glob = self.events_dir.join("*.parquet");
             let events_glob_str = events_glob.to_string_lossy().replace('\'', "''");
             format!(
                 r#"
-                SELECT model_key,
-                       COUNT(*) FILTER (WHERE status = 'success' AND reason IS NULL) AS successes,
-                       COUNT(*) FILTER (WHERE reason IS NULL
-                                        OR reason NOT IN ('auth_failed', 'rate_limited')) AS quality_n,
-                       AVG(latency_ms) AS avg_latency,
-                       APPROX_QUANTILE(latency_ms, 0.95) AS p95
-                FROM (
-                    SELECT event_uid, project_id, ts, model_key, status, partial,
-                           reason, latency_ms, prompt_tokens
-                    FROM model_events
-                    WHERE ts > ?1
-                      {exclude_clause}
                     UNION ALL
                     SELECT event_uid, project_id, ts, model_key, status, partial,
                            reason, latency_ms, prompt_tokens
-                    FROM read_parquet('{events_glob}', union_by_name=true)
+                    FROM read_parquet('{events_glob_str}', union_by_name=true)
                     WHERE ts > ?1
                       {exclude_clause}
-                ) combined
-                GROUP BY model_key
-                HAVING quality_n >= 5
-                ORDER BY successes DESC, avg_latency ASC
+                      {parquet_bucket_clause}
                 "#,
-                events_glob = events_glob_str,
-                exclude_clause = exclude_clause,
             )
         } else {
-            format!(
-                r#"
-                SELECT model_key,
-                       COUNT(*) FILTER (WHERE status = 'success' AND reason IS NULL) AS successes,
-                       COUNT(*) FILTER (WHERE reason IS NULL
-                                        OR reason NOT IN ('auth_failed', 'rate_limited')) AS quality_n,
-                       AVG(latency_ms) AS avg_latency,
-                       APPROX_QUANTILE(latency_ms, 0.95) AS p95
-                FROM model_events
-                WHERE ts > ?1
-                  {exclude_clause}
-                GROUP BY model_key
-                HAVING quality_n >= 5
-                ORDER BY successes DESC, avg_latency ASC
-                "#,
-                exclude_clause = exclude_clause,
-            )
+            String::new()
         };
+        let events = format!(
+            r#"(
+                    SELECT event_uid, project_id, ts, model_key, status, partial,
+                           reason, latency_ms, prompt_tokens
+                    FROM model_events_bucketed
+                    WHERE ts > ?1
+                      {exclude_clause}
+                      {bucket_clause}
+                    {parquet_events}
+                ) combined"#,
+        );
 
-        let result = if let Some(exclude_id) = exclude_project_id {
-            query_recommendations_impl(&conn, &sql, cutoff_ms, Some(exclude_id))
-        } else {
-            query_recommendations_impl(&conn, &sql, cutoff_ms, None)
-        };
+        let sql = format!(
+            r#"
+            SELECT model_key,
+                   COUNT(*) FILTER (WHERE status = 'success' AND reason IS NULL) AS successes,
+                   COUNT(*) FILTER (WHERE reason IS NULL
+                                    OR reason NOT IN ('auth_failed', 'rate_limited', 'queued_timeout', 'backend_down')) AS quality_n,
+                   AVG(latency_ms) AS avg_latency,
+                   APPROX_QUANTILE(latency_ms, 0.95) AS p95
+            FROM {events}
+            GROUP BY model_key
+            HAVING quality_n >= 5
+            ORDER BY successes DESC, avg_latency ASC
+            "#,
+        );
+        // Successful calls only: timeouts would measure the cutoff, not the provider.
+        let load_sql = format!(
+            r#"
+            SELECT model_key,
+                   {window} AS load_window,
+                   AVG(latency_ms) AS avg_latency,
+                   COUNT(*) AS n
+            FROM {events}
+            WHERE status = 'success' AND reason IS NULL
+            GROUP BY model_key, load_window
+            HAVING n >= {MIN_LOAD_WINDOW_SAMPLES}
+            ORDER BY model_key, load_window
+            "#,
+            window = schema::load_window_sql("ts"),
+        );
 
-        result.map_err(|e| format!("global memory: query failed: {e}"))
+        query_recommendations_impl(&conn, &sql, cutoff_ms, exclude_project_id)
+            .and_then(|mut recs| {
+                recs.load =
+                    query_load_windows_impl(&conn, &load_sql, cutoff_ms, exclude_project_id)?;
+                Ok(recs)
+            })
+            .map_err(|e| format!("global memory: query failed: {e}"))
     }
 
     /// Merge pending Parquet files into the compacted DuckDB table.
@@ -459,7 +820,7 @@ impl DbWorker {
         let parquet_files = match list_parquet_files(&self.events_dir) {
             Ok(files) => files,
             Err(e) => {
-                tracing::warn!("global memory: failed to list parquet files: {e}");
+                record_error(&self.status, format!("failed to list parquet files: {e}"));
                 return;
             }
         };
@@ -474,39 +835,7 @@ impl DbWorker {
         };
 
         let events_glob = self.events_dir.join("*.parquet");
-        let events_glob_str = events_glob.to_string_lossy().replace('\'', "''");
-
-        // Upsert projects referenced by the parquet events
-        // (required to satisfy the foreign key on model_events.project_id)
-        let upsert_projects_sql = format!(
-            r#"
-            INSERT INTO projects (project_id, first_seen_ts, last_seen_ts)
-            SELECT DISTINCT project_id, MIN(ts), MAX(ts)
-            FROM read_parquet('{events_glob}', union_by_name=true)
-            GROUP BY project_id
-            ON CONFLICT (project_id) DO UPDATE SET
-                last_seen_ts = GREATEST(projects.last_seen_ts, EXCLUDED.last_seen_ts)
-            "#,
-            events_glob = events_glob_str,
-        );
-        if let Err(e) = conn.execute_batch(&upsert_projects_sql) {
-            tracing::warn!("global memory: project upsert during merge failed: {e}");
-            return;
-        }
-
-        // Ingest pending Parquet files into main table
-        let merge_sql = format!(
-            r#"
-            INSERT INTO model_events
-            SELECT event_uid, project_id, ts, model_key, status, partial,
-                   reason, latency_ms, prompt_tokens
-            FROM read_parquet('{events_glob}', union_by_name=true)
-            ON CONFLICT (event_uid) DO NOTHING
-            "#,
-            events_glob = events_glob_str,
-        );
-
-        match conn.execute_batch(&merge_sql) {
+        match ingest_parquet(&conn, &events_glob) {
             Ok(_) => {
                 tracing::debug!(
                     "global memory: merged {} parquet file(s)",
@@ -515,17 +844,96 @@ impl DbWorker {
                 // Delete successfully merged parquet files
                 for path in &parquet_files {
                     if let Err(e) = std::fs::remove_file(path) {
-                        tracing::warn!(
-                            "global memory: failed to remove merged parquet file {}: {e}",
-                            path.display()
+                        record_error(
+                            &self.status,
+                            format!(
+                                "failed to remove merged parquet file {}: {e}",
+                                path.display()
+                            ),
                         );
                     }
                 }
+                update_status(&self.status, |s| {
+                    s.last_merge_ms = Some(epoch_ms());
+                    s.last_merge_files = parquet_files.len();
+                });
             }
             Err(e) => {
-                tracing::warn!("global memory: merge failed: {e}");
+                record_error(&self.status, format!("merge failed: {e}"));
+            }
+        }
+    }
+
+    /// Pull the team snapshot, merge it, and push the merged result back.
+    ///
+    /// A failed pull or merge skips the push: replacing the remote with a
+    /// snapshot that lacks its contents would erase teammates' history.
+    fn do_sync(&mut self) {
+        if self.sync.is_none() {
+            return;
+        }
+        self.last_sync_attempt = Some(std::time::Instant::now());
+        let Some(sync) = &self.sync else {
+            return;
+        };
+
+        let sync_dir = self.db_path.parent().unwrap_or(Path::new(".")).join("sync");
+        if let Err(e) = std::fs::create_dir_all(&sync_dir) {
+            record_error(&self.status, format!("cannot create sync dir: {e}"));
+            return;
+        }
+        let incoming = sync_dir.join("incoming.parquet");
+        let outgoing = sync_dir.join("outgoing.parquet");
+
+        let pulled = match sync.pull(&incoming) {
+            Ok(pulled) => pulled,
+            Err(e) => {
+                record_error(
+                    &self.status,
+                    format!("sync pull from {} failed: {e}", sync.display()),
+                );
+                return;
+            }
+        };
+
+        let Some(conn) = self.open_connection() else {
+            return;
+        };
+        if pulled && let Err(e) = ingest_parquet(&conn, &incoming) {
+            record_error(&self.status, format!("sync merge failed: {e}"));
+            return;
+        }
+        let cutoff_ms = epoch_ms() as i64 - LOOKBACK_MS;
+        let export_sql = format!(
+            r#"
+            COPY (
+                SELECT event_uid, project_id, ts, model_key, status, partial,
+                       reason, latency_ms, prompt_tokens
+                FROM model_events
+                WHERE ts > {cutoff_ms}
+            ) TO '{path}' (FORMAT PARQUET, COMPRESSION ZSTD)
+            "#,
+            path = outgoing.to_string_lossy().replace('\'', "''"),
+        );
+        if let Err(e) = conn.execute_batch(&export_sql) {
+            record_error(&self.status, format!("sync export failed: {e}"));
+            return;
+        }
+        // Release the DuckDB file lock before touching the network.
+        drop(conn);
+
+        match sync.push(&outgoing) {
+            Ok(()) => {
+                tracing::debug!("global memory: synced with {}", sync.display());
+                update_status(&self.status, |s| s.last_sync_ms = Some(epoch_ms()));
             }
+            Err(e) => record_error(
+                &self.status,
+                format!("sync push to {} failed: {e}", sync.display()),
+            ),
         }
+        let _ = std::fs::remove_file(&incoming);
+        let _ = std::fs::remove_file(&outgoing);
     }
 
     /// Ingest events from a local models.md file into DuckDB.
@@ -540,11 +948,18 @@ impl DbWorker {
     ) {
         use super::local::{parse_iso_to_epoch_ms, parse_models_file};
 
+        let set_bootstrap =
+            |state: BootstrapState| update_status(&self.status, |s| s.bootstrap = state);
+        set_bootstrap(BootstrapState::Running);
+
         // Read the file (sync I/O is fine on the worker thread)
-        let content = match std::fs::read_to_string(models_md_path) {
+        let content = match std::fs::read_to_string(models_md_path).and_then(crate::crypto::open) {
             Ok(c) => c,
             Err(e) => {
                 tracing::debug!("global memory: bootstrap skipped (can't read models.md): {e}");
+                set_bootstrap(BootstrapState::Skipped {
+                    reason: format!("can't read models.md: {e}"),
+                });
                 return;
             }
         };
@@ -552,12 +967,20 @@ impl DbWorker {
         let (_summary, event_lines) = parse_models_file(&content);
         if event_lines.is_empty() {
             tracing::debug!("global memory: bootstrap skipped (no events in models.md)");
+            set_bootstrap(BootstrapState::Skipped {
+                reason: "no events in models.md".to_string(),
+            });
             return;
         }
 
         let conn = match self.open_connection() {
             Some(c) => c,
-            None => return,
+            None => {
+                set_bootstrap(BootstrapState::Failed {
+                    error: "cannot open DuckDB".to_string(),
+                });
+                return;
+            }
         };
         let em_dash = "\u{2014}";
 
@@ -570,7 +993,13 @@ impl DbWorker {
              last_seen_ts = GREATEST(projects.last_seen_ts, EXCLUDED.last_seen_ts)",
             duckdb::params![project_id, now_ms, now_ms],
         ) {
-            tracing::warn!("global memory: bootstrap project upsert failed: {e}");
+            record_error(
+                &self.status,
+                format!("bootstrap project upsert failed: {e}"),
+            );
+            set_bootstrap(BootstrapState::Failed {
+                error: format!("project upsert failed: {e}"),
+            });
             return;
         }
 
@@ -662,7 +1091,10 @@ impl DbWorker {
                     event.prompt_tokens,
                 ],
             ) {
-                tracing::warn!("global memory: bootstrap insert failed for line {i}: {e}");
+                record_error(
+                    &self.status,
+                    format!("bootstrap insert failed for line {i}: {e}"),
+                );
                 skipped += 1;
                 continue;
             }
@@ -673,6 +1105,7 @@ impl DbWorker {
         tracing::info!(
             "global memory: bootstrapped {inserted} events from local models.md ({skipped} skipped)"
         );
+        set_bootstrap(BootstrapState::Done { inserted, skipped });
     }
 }
 
@@ -736,6 +1169,39 @@ fn write_parquet_file(path: &Path, events: &[ModelEvent]) -> Result<(), String>
     Ok(())
 }
 
+/// Merge events from Parquet file(s) matching `source` (a path or glob) into
+/// `model_events`, upserting the projects they reference.
+fn ingest_parquet(conn: &duckdb::Connection, source: &Path) -> Result<(), String> {
+    let source = source.to_string_lossy().replace('\'', "''");
+
+    // Upsert projects referenced by the parquet events
+    // (required to satisfy the foreign key on model_events.project_id)
+    let upsert_projects_sql = format!(
+        r#"
+        INSERT INTO projects (project_id, first_seen_ts, last_seen_ts)
+        SELECT DISTINCT project_id, MIN(ts), MAX(ts)
+        FROM read_parquet('{source}', union_by_name=true)
+        GROUP BY project_id
+        ON CONFLICT (project_id) DO UPDATE SET
+            last_seen_ts = GREATEST(projects.last_seen_ts, EXCLUDED.last_seen_ts)
+        "#,
+    );
+    conn.execute_batch(&upsert_projects_sql)
+        .map_err(|e| format!("project upsert failed: {e}"))?;
+
+    // Events are immutable: an event_uid already present is the same event.
+    let merge_sql = format!(
+        r#"
+        INSERT INTO model_events
+        SELECT event_uid, project_id, ts, model_key, status, partial,
+               reason, latency_ms, prompt_tokens
+        FROM read_parquet('{source}', union_by_name=true)
+        ON CONFLICT (event_uid) DO NOTHING
+        "#,
+    );
+    conn.execute_batch(&merge_sql).map_err(|e| e.to_string())
+}
+
 // ---------------------------------------------------------------------------
 // Query helpers
 // ---------------------------------------------------------------------------
@@ -774,7 +1240,36 @@ fn query_recommendations_impl(
         });
     }
 
-    Ok(GlobalRecommendations { models })
+    Ok(GlobalRecommendations {
+        models,
+        load: Vec::new(),
+    })
+}
+
+fn query_load_windows_impl(
+    conn: &duckdb::Connection,
+    sql: &str,
+    cutoff_ms: i64,
+    exclude_id: Option<&str>,
+) -> Result<Vec<LoadWindowStats>, duckdb::Error> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = if let Some(eid) = exclude_id {
+        stmt.query(params![cutoff_ms, eid])?
+    } else {
+        stmt.query(params![cutoff_ms])?
+    };
+
+    let mut load = Vec::new();
+    while let Some(row) = rows.next()? {
+        let n: i64 = row.get(3)?;
+        load.push(LoadWindowStats {
+            model_key: row.get(0)?,
+            window: row.get(1)?,
+            avg_latency_ms: row.get(2)?,
+            sample_count: n as u64,
+        });
+    }
+    Ok(load)
 }
 
 // ---------------------------------------------------------------------------
@@ -917,6 +1412,17 @@ mod tests {
             reason: None,
             latency_ms: 25000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }];
 
         writer.log_events(&results, 1000, "test:project", Some("/tmp/test"), None);
@@ -955,6 +1461,17 @@ mod tests {
                 reason: None,
                 latency_ms: 25000,
                 partial: false,
+                timing: Default::default(),
+                quotes_checked: 0,
+                quality_flags: Vec::new(),
+                format_compliance: None,
+                rubric: None,
+                usage: None,
+                provider_metadata: Default::default(),
+                late: false,
+                usable: None,
+                repaired: false,
+                duplicate_of: None,
             },
             ReviewModelResult {
                 model: "gemini".to_string(),
@@ -965,6 +1482,17 @@ mod tests {
                 reason: None,
                 latency_ms: 50000,
                 partial: false,
+                timing: Default::default(),
+                quotes_checked: 0,
+                quality_flags: Vec::new(),
+                format_compliance: None,
+                rubric: None,
+                usage: None,
+                provider_metadata: Default::default(),
+                late: false,
+                usable: None,
+                repaired: false,
+                duplicate_of: None,
             },
         ];
 
@@ -988,7 +1516,7 @@ mod tests {
 
         // Query recommendations
         let rx = writer
-            .query_recommendations(None)
+            .query_recommendations(None, None)
             .expect("send should succeed");
         let rt = tokio::runtime::Runtime::new().unwrap();
         let recs = rt.block_on(async {
@@ -1031,4 +1559,31 @@ mod tests {
         // Should be before 2030-01-01 (1893456000000)
         assert!(ms < 1893456000000, "epoch_ms too large: {ms}");
     }
+
+    #[test]
+    fn test_load_skew_reports_slow_windows() {
+        let window = |model: &str, window: &str, secs: f64| LoadWindowStats {
+            model_key: model.to_string(),
+            window: window.to_string(),
+            avg_latency_ms: secs * 1000.0,
+            sample_count: 10,
+        };
+        let recs = GlobalRecommendations {
+            models: Vec::new(),
+            load: vec![
+                window("gemini", "us_business", 33.0),
+                window("gemini", "weekend", 30.0),
+                window("grok", "us_business", 93.0),
+                window("grok", "weekday_night", 40.0),
+                window("grok", "weekend", 31.0),
+            ],
+        };
+        assert_eq!(
+            recs.load_skew(),
+            vec![
+                "grok is 3.0x slower during US business hours (14-22 UTC, Mon-Fri) \
+                 than on weekends (93s vs 31s avg)"
+            ]
+        );
+    }
 }
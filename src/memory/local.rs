@@ -4,9 +4,8 @@ use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use tokio::sync::Mutex;
-
-use crate::tools::review::ReviewModelResult;
+use crate::tools::enums::PromptSize;
+use crate::tools::review::{QualityFlag, ReviewModelResult};
 
 /// Per-model performance stats for hard gate decisions and diagnostics.
 #[derive(Debug, Clone)]
@@ -24,9 +23,206 @@ pub struct ModelGateStats {
     pub partial_count: usize,
     /// Average prompt_len for timeout/cutoff events only. 0 if no such events.
     pub avg_failed_prompt_len: usize,
+    /// Average time-to-first-byte over events that recorded one. None if no event did
+    /// (CLI/async-poll backends, or logs written before the TTFB column existed).
+    pub avg_ttfb_secs: Option<f64>,
+    /// Average time to response headers, likewise.
+    pub avg_headers_secs: Option<f64>,
+    /// Average time from first byte to end of stream (generation), likewise.
+    pub avg_stream_secs: Option<f64>,
+    /// Average wait for a backend permit, over events that waited.
+    pub avg_queue_secs: Option<f64>,
+    /// Share of checked verbatim quotes that were not in the supplied context. None if
+    /// no event checked any (no files/diff sent, or logs from before the Quotes column).
+    pub fabricated_quote_rate: Option<f64>,
+    /// Average output-format compliance (0.0-1.0) over reviews that requested a
+    /// format. None if none did.
+    pub avg_format_compliance: Option<f64>,
+    /// Average rubric grade (0.0-1.0) a judge model gave this model's responses.
+    /// None if no review was graded (see `crate::rubric`).
+    pub avg_rubric_score: Option<f64>,
+    /// Bucketed latency distribution over quality events. Averages hide tail
+    /// outliers; use `p95()`/`p99()` to spot models that occasionally hang.
+    pub latency_histogram: LatencyHistogram,
     pub last_seen: String,
 }
 
+impl ModelGateStats {
+    pub fn p50_latency_secs(&self) -> Option<f64> {
+        self.latency_histogram.percentile(0.50)
+    }
+
+    pub fn p95_latency_secs(&self) -> Option<f64> {
+        self.latency_histogram.percentile(0.95)
+    }
+
+    pub fn p99_latency_secs(&self) -> Option<f64> {
+        self.latency_histogram.percentile(0.99)
+    }
+}
+
+/// Structured filter for the memory read path.
+///
+/// Pattern filters (`scope`, `scope_prefix`, `tags`, `since`, `min_evidence`) are
+/// ANDed together and apply to `patterns.md` entries only; `model` applies to tactics.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryQuery {
+    /// Tactics: keep only lines mentioning `[model]`.
+    pub model: Option<String>,
+    /// Patterns: exact scope match (e.g. "branch:feature/x").
+    pub scope: Option<String>,
+    /// Patterns: scope prefix match (e.g. "branch:" for all branch-scoped entries).
+    pub scope_prefix: Option<String>,
+    /// Patterns: entry must carry every one of these tags (case-insensitive).
+    pub tags: Vec<String>,
+    /// Patterns: entry last seen on or after this date (YYYY-MM-DD).
+    pub since: Option<String>,
+    /// Patterns: minimum evidence count (`[xN]`).
+    pub min_evidence: Option<usize>,
+    /// Recommendations: count only events whose prompt fell in this size bucket.
+    pub prompt_size: Option<PromptSize>,
+}
+
+impl MemoryQuery {
+    /// Reject malformed filter values before touching disk.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(since) = &self.since
+            && (since.len() != 10 || date_to_days(since).is_none())
+        {
+            return Err(format!("since must be a YYYY-MM-DD date, got '{since}'"));
+        }
+        Ok(())
+    }
+
+    fn has_pattern_filters(&self) -> bool {
+        self.scope.is_some()
+            || self.scope_prefix.is_some()
+            || !self.tags.is_empty()
+            || self.since.is_some()
+            || self.min_evidence.is_some()
+    }
+
+    fn matches_pattern(&self, entry: &str) -> bool {
+        let entry_scope = extract_entry_scope(entry);
+        if let Some(scope) = &self.scope
+            && entry_scope != Some(scope.trim())
+        {
+            return false;
+        }
+        if let Some(prefix) = &self.scope_prefix
+            && !entry_scope.is_some_and(|s| s.starts_with(prefix.trim()))
+        {
+            return false;
+        }
+        if !self.tags.is_empty() {
+            let entry_tags: Vec<String> = extract_entry_tags(entry)
+                .unwrap_or("")
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if !self
+                .tags
+                .iter()
+                .all(|t| entry_tags.contains(&t.trim().to_lowercase()))
+            {
+                return false;
+            }
+        }
+        if let Some(since) = &self.since {
+            // Header date is bumped on every merge, so it is the last-seen date.
+            let last_seen = entry.get(4..14).unwrap_or("");
+            if date_to_days(last_seen) < date_to_days(since) {
+                return false;
+            }
+        }
+        if let Some(min) = self.min_evidence
+            && extract_evidence_count(entry) < min
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Upper bounds (seconds, inclusive) of latency histogram buckets.
+/// Samples above the last bound land in an open-ended overflow bucket.
+pub const LATENCY_BUCKETS_SECS: [f64; 11] = [
+    5.0, 10.0, 20.0, 30.0, 45.0, 60.0, 90.0, 120.0, 180.0, 300.0, 600.0,
+];
+
+/// Fixed-bucket latency histogram (see `LATENCY_BUCKETS_SECS`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    /// `counts[i]` = samples in bucket i; the last slot is the overflow bucket.
+    pub counts: [usize; LATENCY_BUCKETS_SECS.len() + 1],
+    /// Largest sample seen. Caps percentile estimates so sparse data isn't
+    /// rounded up to a bucket bound no sample reached.
+    pub max_secs: f64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, secs: f64) {
+        if !secs.is_finite() || secs < 0.0 {
+            return;
+        }
+        let idx = LATENCY_BUCKETS_SECS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_SECS.len());
+        self.counts[idx] += 1;
+        self.max_secs = self.max_secs.max(secs);
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Estimate the `q`-quantile (0.0-1.0) as the upper bound of the bucket holding
+    /// the nearest-rank sample, capped at `max_secs`. None when empty.
+    pub fn percentile(&self, q: f64) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let rank = ((total as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as usize;
+        let mut cumulative = 0;
+        for (i, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= rank {
+                let bound = LATENCY_BUCKETS_SECS
+                    .get(i)
+                    .copied()
+                    .unwrap_or(self.max_secs);
+                return Some(bound.min(self.max_secs));
+            }
+        }
+        Some(self.max_secs)
+    }
+
+    /// Compact rendering of non-empty buckets, e.g. `≤10s:3 ≤30s:5 >600s:1`.
+    pub fn to_compact_string(&self) -> String {
+        let parts: Vec<String> = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| match LATENCY_BUCKETS_SECS.get(i) {
+                Some(bound) => format!("\u{2264}{bound:.0}s:{count}"),
+                None => format!(
+                    ">{:.0}s:{count}",
+                    LATENCY_BUCKETS_SECS[LATENCY_BUCKETS_SECS.len() - 1]
+                ),
+            })
+            .collect();
+        if parts.is_empty() {
+            "\u{2014}".to_string()
+        } else {
+            parts.join(" ")
+        }
+    }
+}
+
 /// Maximum entries in the models.md event log before compaction.
 const MAX_EVENT_LOG_ENTRIES: usize = 100;
 
@@ -49,16 +245,16 @@ pub const VALID_CATEGORIES: &[&str] = &["pattern", "tactic", "recommend"];
 pub const CONFIRMED_THRESHOLD: usize = 5;
 
 /// Default base directory for memory files.
-const DEFAULT_MEMORY_DIR: &str = ".squall/memory";
+pub(super) const DEFAULT_MEMORY_DIR: &str = ".squall/memory";
 
 /// Manages Squall's persistent memory files.
 ///
-/// Thread-safe: all file writes go through an internal Mutex to prevent
-/// concurrent writes from interleaving. Reads are lock-free (atomic file
-/// reads via temp+rename ensure no partial reads).
+/// Process-safe: every read-modify-write holds an exclusive advisory lock on
+/// `<base_dir>/.lock`, so concurrent tasks and other Squall servers on the same
+/// repo can't interleave and drop each other's writes. Reads are lock-free
+/// (atomic file reads via temp+rename ensure no partial reads).
 pub struct MemoryStore {
     base_dir: PathBuf,
-    write_lock: Mutex<()>,
     write_counter: AtomicU64,
     /// Maps provider model_ids to config keys for display normalization.
     id_to_key: HashMap<String, String>,
@@ -74,7 +270,6 @@ impl MemoryStore {
     pub fn new() -> Self {
         Self {
             base_dir: PathBuf::from(DEFAULT_MEMORY_DIR),
-            write_lock: Mutex::new(()),
             write_counter: AtomicU64::new(0),
             id_to_key: HashMap::new(),
         }
@@ -84,7 +279,6 @@ impl MemoryStore {
     pub fn with_base_dir(base_dir: PathBuf) -> Self {
         Self {
             base_dir,
-            write_lock: Mutex::new(()),
             write_counter: AtomicU64::new(0),
             id_to_key: HashMap::new(),
         }
@@ -98,6 +292,11 @@ impl MemoryStore {
         self
     }
 
+    /// The same store (model-key map included) rooted at `base_dir`.
+    pub(super) fn relocated(&self, base_dir: PathBuf) -> Self {
+        Self::with_base_dir(base_dir).with_id_to_key(self.id_to_key.clone())
+    }
+
     pub(crate) fn models_path(&self) -> PathBuf {
         self.base_dir.join("models.md")
     }
@@ -114,10 +313,46 @@ impl MemoryStore {
         self.base_dir.join("archive.md")
     }
 
+    fn calibration_path(&self) -> PathBuf {
+        self.base_dir.join("calibration.md")
+    }
+
+    fn hotspots_path(&self) -> PathBuf {
+        self.base_dir.join("hotspots.md")
+    }
+
+    fn disagreements_path(&self) -> PathBuf {
+        self.base_dir.join("disagreements.md")
+    }
+
     fn index_path(&self) -> PathBuf {
         self.base_dir.join("index.md")
     }
 
+    fn lock_path(&self) -> PathBuf {
+        self.base_dir.join(".lock")
+    }
+
+    /// Take the exclusive write lock for one read-modify-write cycle.
+    ///
+    /// `flock` locks belong to the open file description, so a fresh handle per
+    /// call serializes tasks in this process as well as other processes.
+    /// Blocking acquisition runs off the async runtime. Released on drop.
+    async fn lock_for_write(&self) -> Result<WriteLock, std::io::Error> {
+        tokio::fs::create_dir_all(&self.base_dir).await?;
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_path())?;
+        tokio::task::spawn_blocking(move || {
+            file.lock()?;
+            Ok(WriteLock { _file: file })
+        })
+        .await
+        .map_err(std::io::Error::other)?
+    }
+
     /// The display path for this store's directory (for returning in tool responses).
     fn display_dir(&self) -> String {
         self.base_dir.display().to_string()
@@ -139,13 +374,33 @@ impl MemoryStore {
     /// Extracts latency, status, and error info from each model result and
     /// appends to the event log in models.md. Every COMPACTION_INTERVAL writes,
     /// recomputes the summary table and truncates to MAX_EVENT_LOG_ENTRIES.
+    #[cfg(test)]
     pub async fn log_model_metrics(
         &self,
         results: &[ReviewModelResult],
         prompt_len: usize,
         id_to_key: Option<&HashMap<String, String>>,
     ) {
-        let _lock = self.write_lock.lock().await;
+        self.log_model_metrics_scoped(results, prompt_len, id_to_key, None)
+            .await
+    }
+
+    /// `log_model_metrics`, recording each event's `scope` (e.g. `branch:<name>`)
+    /// so `flush_branch` can graduate it with the branch's patterns.
+    pub async fn log_model_metrics_scoped(
+        &self,
+        results: &[ReviewModelResult],
+        prompt_len: usize,
+        id_to_key: Option<&HashMap<String, String>>,
+        scope: Option<&str>,
+    ) {
+        let _lock = match self.lock_for_write().await {
+            Ok(lock) => lock,
+            Err(e) => {
+                tracing::warn!("memory: failed to lock memory directory: {e}");
+                return;
+            }
+        };
 
         if let Err(e) = self.ensure_dir().await {
             tracing::warn!("memory: failed to create directory: {e}");
@@ -162,6 +417,7 @@ impl MemoryStore {
         };
 
         let timestamp = iso_timestamp();
+        let scope = escape_pipes(scope.unwrap_or("\u{2014}"));
         let mut new_events = Vec::new();
         for r in results {
             let latency_s = format!("{:.1}s", r.latency_ms as f64 / 1000.0);
@@ -176,8 +432,36 @@ impl MemoryStore {
                 .map(|s| s.as_str())
                 .unwrap_or(raw_model.as_str());
             let model = escape_pipes(normalized);
+            let secs = |ms: Option<u64>| {
+                ms.map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+                    .unwrap_or_else(|| "\u{2014}".to_string())
+            };
+            let ttfb = secs(r.timing.ttfb_ms);
+            let headers = secs(r.timing.headers_ms);
+            let stream = secs(r.timing.stream_ms);
+            let queue = secs(r.timing.queue_ms);
+            // Fabricated/checked verbatim quotes (see `crate::quotes`).
+            let quotes = if r.quotes_checked > 0 {
+                let fabricated = r
+                    .quality_flags
+                    .iter()
+                    .filter(|f| matches!(f, QualityFlag::FabricatedQuote { .. }))
+                    .count();
+                format!("{fabricated}/{}", r.quotes_checked)
+            } else {
+                "\u{2014}".to_string()
+            };
+            let format_score = r
+                .format_compliance
+                .map(|s| format!("{s:.2}"))
+                .unwrap_or_else(|| "\u{2014}".to_string());
+            let rubric_score = r
+                .rubric
+                .as_ref()
+                .map(|g| format!("{:.2}", g.score))
+                .unwrap_or_else(|| "\u{2014}".to_string());
             new_events.push(format!(
-                "| {timestamp} | {model} | {latency_s} | {status} | {partial} | {reason} | {error} | {prompt_len} |",
+                "| {timestamp} | {model} | {latency_s} | {status} | {partial} | {reason} | {error} | {prompt_len} | {ttfb} | {quotes} | {format_score} | {rubric_score} | {headers} | {stream} | {queue} | {scope} |",
             ));
         }
 
@@ -229,7 +513,10 @@ impl MemoryStore {
             }
         }
 
-        let _lock = self.write_lock.lock().await;
+        let _lock = self
+            .lock_for_write()
+            .await
+            .map_err(|e| format!("failed to lock memory directory: {e}"))?;
 
         if let Err(e) = self.ensure_dir().await {
             return Err(format!("failed to create memory directory: {e}"));
@@ -249,7 +536,7 @@ impl MemoryStore {
             };
             let sanitized = escape_pipes(model);
             new_events.push(format!(
-                "| {timestamp} | {sanitized} | — | feedback | no | {label} | — | 0 |",
+                "| {timestamp} | {sanitized} | — | feedback | no | {label} | — | 0 | — |",
             ));
         }
 
@@ -275,6 +562,103 @@ impl MemoryStore {
             return Err(format!("failed to write models.md: {e}"));
         }
 
+        let reviews_dir = self
+            .base_dir
+            .parent()
+            .unwrap_or(&self.base_dir)
+            .join("reviews");
+        let review_path = crate::artifact::review_path(&reviews_dir, review_file).ok();
+        let stem = review_path
+            .as_deref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .and_then(crate::artifact::review_stem)
+            .unwrap_or(review_file);
+
+        // Calibration: how many high-confidence findings each rated model made
+        // in that review. A review that can't be read just isn't counted.
+        if let Some(ref path) = review_path
+            && let Ok(raw) = crate::artifact::read_review(path).await
+            && let Ok(review) = serde_json::from_str::<serde_json::Value>(&raw)
+        {
+            let mut rows = Vec::new();
+            for (model, score) in scores {
+                let high: usize = review["results"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|r| {
+                        r["model"]
+                            .as_str()
+                            .is_some_and(|m| m == model || crate::consistency::base(m) == model)
+                    })
+                    .filter_map(|r| Some((r["model"].as_str()?, r["response"].as_str()?)))
+                    .map(|(m, text)| crate::calibration::high_confidence_count(m, text))
+                    .sum();
+                if high > 0 {
+                    let verdict = match score {
+                        0 => "noise",
+                        1 => "okay",
+                        _ => "actionable",
+                    };
+                    rows.push(crate::calibration::row(
+                        &timestamp, model, stem, high, verdict,
+                    ));
+                }
+            }
+            if !rows.is_empty() {
+                let path = self.calibration_path();
+                let existing = read_to_string_lossy(&path).await.unwrap_or_default();
+                let mut all: Vec<String> = crate::calibration::rows(&existing)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect();
+                all.extend(rows);
+                let start = all.len().saturating_sub(crate::calibration::MAX_ROWS);
+                let content = format!(
+                    "{}\n{}\n",
+                    crate::calibration::HEADER,
+                    all[start..].join("\n")
+                );
+                if let Err(e) = atomic_write(&path, &content).await {
+                    tracing::warn!("feedback: failed to write calibration.md: {e}");
+                }
+            }
+
+            // Hot spots: issues only one model reported are confirmed once that
+            // model is rated actionable.
+            let findings: Vec<crate::findings::Finding> = review["results"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|r| r["status"] == "success")
+                .filter_map(|r| Some((r["model"].as_str()?, r["response"].as_str()?)))
+                .flat_map(|(m, text)| crate::findings::extract_findings(m, text))
+                .collect();
+            let confirmed = crate::hotspots::confirmed_by_feedback(&findings, |m| {
+                scores
+                    .get(m)
+                    .or_else(|| scores.get(crate::consistency::base(m)))
+                    .is_some_and(|s| *s == 2)
+            });
+            if let Err(e) = self.add_hotspots(&confirmed, &timestamp).await {
+                tracing::warn!("feedback: {e}");
+            }
+        }
+
+        // Disagreements mined from that review are settled by the same scores.
+        let path = self.disagreements_path();
+        if let Ok(existing) = read_to_string_lossy(&path).await
+            && !existing.is_empty()
+        {
+            let (content, resolved) = crate::disagreement::resolve(&existing, stem, scores);
+            if resolved > 0
+                && let Err(e) = atomic_write(&path, &content).await
+            {
+                tracing::warn!("feedback: failed to write disagreements.md: {e}");
+            }
+        }
+
         // Write standalone feedback file for ACT training
         let feedback_dir = self
             .base_dir
@@ -306,6 +690,206 @@ impl MemoryStore {
         ))
     }
 
+    /// Per-model confidence calibration from calibration.md (see `crate::calibration`),
+    /// keyed by config key. Empty when no feedback has been calibrated yet.
+    pub async fn get_calibration(&self) -> HashMap<String, crate::calibration::Calibration> {
+        let Ok(content) = read_decrypted(&self.calibration_path()).await else {
+            return HashMap::new();
+        };
+        let mut by_key: HashMap<String, crate::calibration::Calibration> = HashMap::new();
+        for (model, c) in crate::calibration::parse(&content) {
+            let key = self.id_to_key.get(&model).cloned().unwrap_or(model);
+            let entry = by_key.entry(key).or_default();
+            entry.high_confidence += c.high_confidence;
+            entry.accepted += c.accepted;
+        }
+        by_key
+    }
+
+    /// Add a review's findings to hotspots.md (see `crate::hotspots`).
+    pub async fn record_hotspots(
+        &self,
+        findings: &[crate::findings::Finding],
+    ) -> Result<(), String> {
+        let counts = crate::hotspots::tally(findings);
+        if counts.is_empty() {
+            return Ok(());
+        }
+        let _lock = self
+            .lock_for_write()
+            .await
+            .map_err(|e| format!("failed to lock memory directory: {e}"))?;
+        self.ensure_dir()
+            .await
+            .map_err(|e| format!("failed to create memory directory: {e}"))?;
+        self.add_hotspots(&counts, &iso_timestamp()).await
+    }
+
+    /// Merge `counts` into hotspots.md. The caller holds the write lock.
+    async fn add_hotspots(
+        &self,
+        counts: &crate::hotspots::Counts,
+        timestamp: &str,
+    ) -> Result<(), String> {
+        if counts.is_empty() {
+            return Ok(());
+        }
+        let path = self.hotspots_path();
+        let existing = read_to_string_lossy(&path)
+            .await
+            .map_err(|e| format!("failed to read hotspots.md: {e}"))?;
+        let hotspots = crate::hotspots::merge(crate::hotspots::parse(&existing), counts, timestamp);
+        atomic_write(&path, &crate::hotspots::render(&hotspots))
+            .await
+            .map_err(|e| format!("failed to write hotspots.md: {e}"))
+    }
+
+    /// Files ranked by confirmed findings, hottest first. Empty without hotspots.md.
+    pub async fn get_hotspots(&self) -> Vec<crate::hotspots::Hotspot> {
+        match read_decrypted(&self.hotspots_path()).await {
+            Ok(content) => crate::hotspots::parse(&content),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Append `found` to disagreements.md as unresolved entries of review `review`
+    /// (a results file stem). Returns how many were recorded.
+    pub async fn record_disagreements(
+        &self,
+        review: &str,
+        found: &[crate::disagreement::Disagreement],
+    ) -> Result<usize, String> {
+        if found.is_empty() {
+            return Ok(0);
+        }
+        let _lock = self
+            .lock_for_write()
+            .await
+            .map_err(|e| format!("failed to lock memory directory: {e}"))?;
+        self.ensure_dir()
+            .await
+            .map_err(|e| format!("failed to create memory directory: {e}"))?;
+
+        let path = self.disagreements_path();
+        let existing = read_to_string_lossy(&path)
+            .await
+            .map_err(|e| format!("failed to read disagreements.md: {e}"))?;
+        let timestamp = iso_timestamp();
+        let mut entries = crate::disagreement::entries(&existing);
+        entries.extend(
+            found
+                .iter()
+                .map(|d| crate::disagreement::entry(&timestamp, review, d)),
+        );
+        atomic_write(&path, &crate::disagreement::render(&entries))
+            .await
+            .map_err(|e| format!("failed to write disagreements.md: {e}"))?;
+        Ok(found.len())
+    }
+
+    /// Memorize the consensus findings of a persisted review as patterns.
+    ///
+    /// `review_id` is the results file stem (e.g. `1773157800594_76050_0`) or its
+    /// path under `.squall/reviews/`. Findings are extracted from each successful
+    /// model response, clustered across models, and every cluster reported by at
+    /// least `min_consensus` models is written as a pattern with `models`,
+    /// `consensus`, `diff_size`, and `review` metadata.
+    pub async fn memorize_from_review(
+        &self,
+        review_id: &str,
+        min_consensus: usize,
+        scope: Option<&str>,
+    ) -> Result<String, String> {
+        if min_consensus == 0 {
+            return Err("min_consensus must be at least 1".to_string());
+        }
+        let reviews_dir = self
+            .base_dir
+            .parent()
+            .unwrap_or(&self.base_dir)
+            .join("reviews");
+        let path = crate::artifact::review_path(&reviews_dir, review_id)?;
+        let stem = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(crate::artifact::review_stem)
+            .unwrap_or(review_id);
+        let raw = crate::artifact::read_review(&path)
+            .await
+            .map_err(|e| format!("failed to read review {}: {e}", path.display()))?;
+        let review: serde_json::Value =
+            serde_json::from_str(&raw).map_err(|e| format!("invalid review file: {e}"))?;
+
+        let mut findings = Vec::new();
+        let mut responded = 0usize;
+        for result in review["results"].as_array().into_iter().flatten() {
+            if result["status"] != "success" {
+                continue;
+            }
+            let (Some(model), Some(text)) = (result["model"].as_str(), result["response"].as_str())
+            else {
+                continue;
+            };
+            responded += 1;
+            findings.extend(crate::findings::extract_findings(model, text));
+        }
+        let diff_size = review["diff_size"].as_str();
+
+        let clusters = crate::findings::cluster_findings(&findings);
+        let mut saved = Vec::new();
+        for cluster in clusters.iter().filter(|c| c.models.len() >= min_consensus) {
+            let mut content = match &cluster.file_path {
+                Some(file) => format!("{} ({file})", cluster.summary),
+                None => cluster.summary.clone(),
+            };
+            content.truncate(floor_char_boundary(&content, MAX_MEMORIZE_CONTENT_LEN));
+
+            let mut tags = vec!["review".to_string()];
+            if let Some(severity) = &cluster.severity {
+                tags.push(severity.as_str().to_string());
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("models".to_string(), cluster.models.join(", "));
+            metadata.insert(
+                "consensus".to_string(),
+                format!("{}/{responded}", cluster.models.len()),
+            );
+            metadata.insert("review".to_string(), stem.to_string());
+            if let Some(diff_size) = diff_size {
+                metadata.insert("diff_size".to_string(), diff_size.to_string());
+            }
+
+            self.memorize(
+                "pattern",
+                &content,
+                None,
+                Some(&tags),
+                scope,
+                Some(&metadata),
+            )
+            .await?;
+            saved.push(format!(
+                "- {content} [{}/{responded}]",
+                cluster.models.len()
+            ));
+        }
+
+        if saved.is_empty() {
+            return Ok(format!(
+                "No findings reached consensus {min_consensus} in review {stem} \
+                 ({} findings from {responded} model(s)).",
+                findings.len()
+            ));
+        }
+        Ok(format!(
+            "Memorized {} pattern(s) from review {stem} → {}\n{}",
+            saved.len(),
+            self.display_dir(),
+            saved.join("\n")
+        ))
+    }
+
     /// Write an explicit memorize entry to patterns.md or tactics.md.
     pub async fn memorize(
         &self,
@@ -332,7 +916,10 @@ impl MemoryStore {
             return Err("content must not be empty".to_string());
         }
 
-        let _lock = self.write_lock.lock().await;
+        let _lock = self
+            .lock_for_write()
+            .await
+            .map_err(|e| format!("failed to lock memory directory: {e}"))?;
 
         if let Err(e) = self.ensure_dir().await {
             return Err(format!("failed to create memory directory: {e}"));
@@ -557,16 +1144,38 @@ impl MemoryStore {
         model: Option<&str>,
         max_chars: usize,
         scope: Option<&str>,
+    ) -> Result<String, String> {
+        let query = MemoryQuery {
+            model: model.map(str::to_string),
+            scope: scope.map(str::to_string),
+            ..Default::default()
+        };
+        self.read_memory_query(category, max_chars, &query).await
+    }
+
+    /// Read memory files filtered by a structured query (see `MemoryQuery`).
+    pub async fn read_memory_query(
+        &self,
+        category: Option<&str>,
+        max_chars: usize,
+        query: &MemoryQuery,
     ) -> Result<String, String> {
         let category = category.unwrap_or("all");
         let mut sections = Vec::new();
 
         if category == "recommend" {
             let path = self.models_path();
-            match tokio::fs::read_to_string(&path).await {
+            match read_decrypted(&path).await {
                 Ok(content) => {
-                    let recommendation = generate_recommendations(&content, &self.id_to_key);
+                    let mut recommendation =
+                        generate_recommendations(&content, &self.id_to_key, query.prompt_size);
                     if !recommendation.is_empty() {
+                        let calibration = self.get_calibration().await;
+                        if let Some(line) = crate::calibration::summary_line(
+                            &crate::calibration::view(&calibration, None),
+                        ) {
+                            recommendation.push_str(&format!("\n\n{line}"));
+                        }
                         return Ok(recommendation);
                     }
                     return Ok(
@@ -586,7 +1195,7 @@ impl MemoryStore {
 
         if category == "all" || category == "models" {
             let path = self.models_path();
-            match tokio::fs::read_to_string(&path).await {
+            match read_decrypted(&path).await {
                 Ok(content) => {
                     // Return only the summary section, not the full event log
                     let (summary, _) = parse_models_file(&content);
@@ -601,20 +1210,13 @@ impl MemoryStore {
 
         if category == "all" || category == "patterns" {
             let path = self.patterns_path();
-            match tokio::fs::read_to_string(&path).await {
+            match read_decrypted(&path).await {
                 Ok(content) => {
-                    if let Some(filter_scope) = scope {
-                        // Filter entries by exact scope match
+                    if query.has_pattern_filters() {
                         let entries = parse_pattern_entries(&content);
                         let filtered: Vec<&str> = entries
                             .iter()
-                            .filter(|entry| {
-                                entry.lines().any(|line| {
-                                    line.starts_with("- Scope: ")
-                                        && line.trim_start_matches("- Scope: ").trim()
-                                            == filter_scope.trim()
-                                })
-                            })
+                            .filter(|entry| query.matches_pattern(entry))
                             .map(|s| s.as_str())
                             .collect();
                         if !filtered.is_empty() {
@@ -632,9 +1234,9 @@ impl MemoryStore {
 
         if category == "all" || category == "tactics" {
             let path = self.tactics_path();
-            match tokio::fs::read_to_string(&path).await {
+            match read_decrypted(&path).await {
                 Ok(content) => {
-                    if let Some(m) = model.filter(|m| !m.is_empty()) {
+                    if let Some(m) = query.model.as_deref().filter(|m| !m.is_empty()) {
                         // Filter to only the lines mentioning this model
                         let filtered: Vec<&str> = content
                             .lines()
@@ -656,6 +1258,29 @@ impl MemoryStore {
             }
         }
 
+        if category == "all" || category == "hotspots" {
+            let path = self.hotspots_path();
+            match read_decrypted(&path).await {
+                Ok(content) => sections.push(content),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("failed to read hotspots.md: {e}")),
+            }
+        }
+
+        if category == "all" || category == "disagreements" {
+            let path = self.disagreements_path();
+            match read_decrypted(&path).await {
+                Ok(content) => {
+                    let model = query.model.as_deref().filter(|m| !m.is_empty());
+                    if let Some(section) = crate::disagreement::summarize(&content, model) {
+                        sections.push(section);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("failed to read disagreements.md: {e}")),
+            }
+        }
+
         if sections.is_empty() {
             return Ok("No memory found. Use the `memorize` tool to save learnings, or run a `review` to auto-populate model metrics.".to_string());
         }
@@ -681,7 +1306,17 @@ impl MemoryStore {
         &self,
         id_to_key: Option<&HashMap<String, String>>,
     ) -> Option<HashMap<String, ModelGateStats>> {
-        let content = tokio::fs::read_to_string(self.models_path()).await.ok()?;
+        self.get_model_stats_for_size(id_to_key, None).await
+    }
+
+    /// Like `get_model_stats`, counting only events in `prompt_size` when set.
+    /// Returns None if no event falls in the bucket.
+    pub async fn get_model_stats_for_size(
+        &self,
+        id_to_key: Option<&HashMap<String, String>>,
+        prompt_size: Option<PromptSize>,
+    ) -> Option<HashMap<String, ModelGateStats>> {
+        let content = read_decrypted(&self.models_path()).await.ok()?;
         let (_, events) = parse_models_file(&content);
         if events.is_empty() {
             return None;
@@ -699,6 +1334,17 @@ impl MemoryStore {
             partials: usize,
             failed_prompt_total: usize,
             failed_prompt_count: usize,
+            ttfb_total: f64,
+            ttfb_count: usize,
+            // (total seconds, events) for headers, stream and queue.
+            phases: [(f64, usize); 3],
+            quotes_fabricated: usize,
+            quotes_checked: usize,
+            format_total: f64,
+            format_count: usize,
+            rubric_total: f64,
+            rubric_count: usize,
+            histogram: LatencyHistogram,
             last_seen: String,
         }
 
@@ -708,6 +1354,9 @@ impl MemoryStore {
             if cols.len() < 8 {
                 continue;
             }
+            if prompt_size.is_some_and(|size| event_prompt_size(&cols) != Some(size)) {
+                continue;
+            }
             let raw_model = cols[2];
             // Normalize model_id → config key if map provided
             let model = id_to_key
@@ -725,12 +1374,16 @@ impl MemoryStore {
             let entry = stats.entry(model).or_default();
 
             // Exclude infrastructure failures from quality stats
-            let is_infra = matches!(reason, "auth_failed" | "rate_limited");
+            let is_infra = matches!(
+                reason,
+                "auth_failed" | "rate_limited" | "queued_timeout" | "backend_down"
+            );
             if is_infra {
                 entry.infra_failures += 1;
             } else {
                 entry.total_latency += latency;
                 entry.quality_count += 1;
+                entry.histogram.record(latency);
                 if status == "success" && partial != "yes" {
                     entry.successes += 1;
                 }
@@ -749,29 +1402,69 @@ impl MemoryStore {
 
             // Track prompt_len for timeout/cutoff events only (diagnostic: what sizes cause failures)
             if matches!(reason, "timeout" | "cutoff") {
-                // prompt_len column: new format (>=10 cols) = cols[8], old format = cols[7]
-                let prompt_col = if is_new_format { 8 } else { 7 };
-                let prompt_len: usize = cols
-                    .get(prompt_col)
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-                entry.failed_prompt_total += prompt_len;
+                entry.failed_prompt_total += event_prompt_len(&cols).unwrap_or(0);
                 entry.failed_prompt_count += 1;
             }
 
+            // TTFB column (cols[9]) only exists in logs written with timing breakdown
+            if is_new_format
+                && let Some(ttfb) = cols
+                    .get(9)
+                    .and_then(|s| s.trim_end_matches('s').parse::<f64>().ok())
+            {
+                entry.ttfb_total += ttfb;
+                entry.ttfb_count += 1;
+            }
+
+            // Quotes column (cols[10]): "fabricated/checked"
+            if let Some((fabricated, checked)) = cols.get(10).and_then(|s| s.split_once('/'))
+                && let (Ok(fabricated), Ok(checked)) =
+                    (fabricated.parse::<usize>(), checked.parse::<usize>())
+            {
+                entry.quotes_fabricated += fabricated;
+                entry.quotes_checked += checked;
+            }
+
+            // Format column (cols[11]): compliance score when a format was requested
+            if let Some(score) = cols.get(11).and_then(|s| s.parse::<f64>().ok()) {
+                entry.format_total += score;
+                entry.format_count += 1;
+            }
+
+            // Rubric column (cols[12]): judge's grade when a rubric was given. Older
+            // rows have Scope here, which never parses as a number.
+            if let Some(score) = cols.get(12).and_then(|s| s.parse::<f64>().ok()) {
+                entry.rubric_total += score;
+                entry.rubric_count += 1;
+            }
+
+            // Headers, Stream and Queue columns (cols[13..16]) sit before Scope in
+            // rows with 16 columns; older rows have Scope at 12 or 13.
+            if cols.len() >= 18 {
+                for (phase, col) in entry.phases.iter_mut().zip(&cols[13..16]) {
+                    if let Ok(secs) = col.trim_end_matches('s').parse::<f64>() {
+                        phase.0 += secs;
+                        phase.1 += 1;
+                    }
+                }
+            }
+
             if event_date > entry.last_seen {
                 entry.last_seen = event_date;
             }
         }
 
+        if prompt_size.is_some() && stats.is_empty() {
+            return None;
+        }
+        let phase_avg = |(total, count): (f64, usize)| (count > 0).then(|| total / count as f64);
         let result: HashMap<String, ModelGateStats> = stats
             .into_iter()
             .map(|(model, a)| {
-                let avg_failed = if a.failed_prompt_count > 0 {
-                    a.failed_prompt_total / a.failed_prompt_count
-                } else {
-                    0
-                };
+                let avg_failed = a
+                    .failed_prompt_total
+                    .checked_div(a.failed_prompt_count)
+                    .unwrap_or(0);
                 (
                     model,
                     ModelGateStats {
@@ -791,6 +1484,18 @@ impl MemoryStore {
                         cutoff_count: a.cutoffs,
                         partial_count: a.partials,
                         avg_failed_prompt_len: avg_failed,
+                        avg_ttfb_secs: (a.ttfb_count > 0)
+                            .then(|| a.ttfb_total / a.ttfb_count as f64),
+                        avg_headers_secs: phase_avg(a.phases[0]),
+                        avg_stream_secs: phase_avg(a.phases[1]),
+                        avg_queue_secs: phase_avg(a.phases[2]),
+                        fabricated_quote_rate: (a.quotes_checked > 0)
+                            .then(|| a.quotes_fabricated as f64 / a.quotes_checked as f64),
+                        avg_format_compliance: (a.format_count > 0)
+                            .then(|| a.format_total / a.format_count as f64),
+                        avg_rubric_score: (a.rubric_count > 0)
+                            .then(|| a.rubric_total / a.rubric_count as f64),
+                        latency_histogram: a.histogram,
                         last_seen: a.last_seen,
                     },
                 )
@@ -807,7 +1512,10 @@ impl MemoryStore {
     ///
     /// Returns a graduation report string.
     pub async fn flush_branch(&self, branch: &str) -> Result<String, String> {
-        let _lock = self.write_lock.lock().await;
+        let _lock = self
+            .lock_for_write()
+            .await
+            .map_err(|e| format!("failed to lock memory directory: {e}"))?;
 
         let branch_scope = format!("branch:{branch}");
         let mut graduated = 0usize;
@@ -871,6 +1579,7 @@ impl MemoryStore {
                 .map_err(|e| e.to_string())?;
         }
 
+        let graduated_events = self.graduate_model_events(&branch_scope).await;
         // Prune model events older than 30 days
         let pruned_events = self.prune_old_model_events(30).await;
 
@@ -878,15 +1587,64 @@ impl MemoryStore {
             "Flush complete for branch '{branch}': \
              {graduated} patterns graduated to codebase, \
              {archived} patterns archived, \
+             {graduated_events} model events graduated, \
              {pruned_events} old model events pruned"
         ))
     }
 
+    /// Branches that have branch-scoped patterns or model events in memory.
+    pub async fn scoped_branches(&self) -> Vec<String> {
+        let mut branches = std::collections::BTreeSet::new();
+        if let Ok(patterns) = read_to_string_lossy(&self.patterns_path()).await {
+            for entry in parse_pattern_entries(&patterns) {
+                if let Some(branch) =
+                    extract_entry_scope(&entry).and_then(|s| s.strip_prefix("branch:"))
+                {
+                    branches.insert(branch.to_string());
+                }
+            }
+        }
+        if let Ok(models) = read_decrypted(&self.models_path()).await {
+            let (_, events) = parse_models_file(&models);
+            for event in &events {
+                // Scope is the last column: `... | branch:<name> |`.
+                if let Some((_, last)) = event.trim_end_matches(" |").rsplit_once("| ")
+                    && let Some(branch) = last.strip_prefix("branch:")
+                {
+                    branches.insert(branch.to_string());
+                }
+            }
+        }
+        branches.into_iter().collect()
+    }
+
+    /// Re-scope model events logged under `branch_scope` to codebase.
+    /// Returns the number of events changed.
+    async fn graduate_model_events(&self, branch_scope: &str) -> usize {
+        let path = self.models_path();
+        let Ok(content) = read_decrypted(&path).await else {
+            return 0;
+        };
+        let (summary, mut events) = parse_models_file(&content);
+        let scoped = format!("| {} |", escape_pipes(branch_scope));
+        let mut graduated = 0;
+        for event in &mut events {
+            if let Some(rest) = event.strip_suffix(&scoped) {
+                *event = format!("{rest}| codebase |");
+                graduated += 1;
+            }
+        }
+        if graduated > 0 {
+            let _ = atomic_write(&path, &format_models_file(&summary, &events)).await;
+        }
+        graduated
+    }
+
     /// Prune model events older than `max_age_days` from models.md.
     /// Returns the number of events pruned.
     async fn prune_old_model_events(&self, max_age_days: u64) -> usize {
         let path = self.models_path();
-        let content = match tokio::fs::read_to_string(&path).await {
+        let content = match read_decrypted(&path).await {
             Ok(c) => c,
             Err(_) => return 0,
         };
@@ -931,9 +1689,17 @@ impl MemoryStore {
     }
 }
 
+/// Exclusive advisory lock held for the lifetime of the guard (see `lock_for_write`).
+struct WriteLock {
+    /// Closing the handle releases the lock.
+    _file: std::fs::File,
+}
+
 /// Atomic write: write to temp file, then rename.
 /// Temp filename includes PID to avoid cross-process collisions.
+/// Contents are sealed first when encryption at rest is configured.
 async fn atomic_write(path: &PathBuf, content: &str) -> Result<(), std::io::Error> {
+    let content = crate::crypto::seal(content)?;
     let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
     tokio::fs::write(&tmp_path, content.as_bytes()).await?;
     if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
@@ -997,6 +1763,9 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
         timeouts: usize,
         cutoffs: usize,
         latencies: Vec<f64>,
+        histogram: LatencyHistogram,
+        /// (successes, quality events) per prompt-size bucket.
+        by_size: [(usize, usize); 4],
         common_errors: HashMap<String, usize>,
     }
 
@@ -1032,18 +1801,30 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
             timeouts: 0,
             cutoffs: 0,
             latencies: Vec::new(),
+            histogram: LatencyHistogram::default(),
+            by_size: [(0, 0); 4],
             common_errors: HashMap::new(),
         });
 
         // Exclude infrastructure failures from quality stats
-        let is_infra = matches!(reason, "auth_failed" | "rate_limited");
+        let is_infra = matches!(
+            reason,
+            "auth_failed" | "rate_limited" | "queued_timeout" | "backend_down"
+        );
         if !is_infra {
             entry.total_latency += latency;
             entry.count += 1;
             entry.latencies.push(latency);
-            if status == "success" && partial != "yes" {
+            entry.histogram.record(latency);
+            let success = status == "success" && partial != "yes";
+            if success {
                 entry.successes += 1;
             }
+            if let Some(size) = event_prompt_size(&cols) {
+                let bucket = &mut entry.by_size[size as usize];
+                bucket.0 += usize::from(success);
+                bucket.1 += 1;
+            }
         }
 
         // Diagnostic counters (independent of is_infra)
@@ -1073,13 +1854,19 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
 
         let mut sorted_latencies = s.latencies.clone();
         sorted_latencies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let p95 = if !sorted_latencies.is_empty() {
-            let idx = ((sorted_latencies.len() as f64) * 0.95).ceil() as usize;
-            let idx = idx.min(sorted_latencies.len()) - 1;
+        // Exact nearest-rank percentiles from the raw event window
+        let percentile = |q: f64| {
+            if sorted_latencies.is_empty() {
+                return "\u{2014}".to_string();
+            }
+            let idx = ((sorted_latencies.len() as f64) * q).ceil() as usize;
+            let idx = idx.clamp(1, sorted_latencies.len()) - 1;
             format!("{:.0}s", sorted_latencies[idx])
-        } else {
-            "\u{2014}".to_string()
         };
+        let p50 = percentile(0.50);
+        let p95 = percentile(0.95);
+        let p99 = percentile(0.99);
+        let histogram = s.histogram.to_compact_string();
 
         let rate = if s.count > 0 {
             let pct = (s.successes as f64 / s.count as f64 * 100.0).round() as u64;
@@ -1095,6 +1882,21 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
             "\u{2014}".to_string()
         };
 
+        let by_size: Vec<String> = PromptSize::ALL
+            .iter()
+            .zip(&s.by_size)
+            .filter(|(_, (_, n))| *n > 0)
+            .map(|(size, (ok, n))| {
+                let pct = (*ok as f64 / *n as f64 * 100.0).round() as u64;
+                format!("{} {pct}% ({n})", size.label())
+            })
+            .collect();
+        let by_size = if by_size.is_empty() {
+            "\u{2014}".to_string()
+        } else {
+            by_size.join(", ")
+        };
+
         let top_error = s
             .common_errors
             .iter()
@@ -1105,15 +1907,17 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
         let today = iso_date();
         rows.push((
             model.clone(),
-            format!("| {model} | {avg} | {p95} | {rate} | {timing_col} | {top_error} | {today} |"),
+            format!(
+                "| {model} | {avg} | {p50} | {p95} | {p99} | {histogram} | {rate} | {timing_col} | {by_size} | {top_error} | {today} |"
+            ),
         ));
     }
 
     let mut table = String::from(
-        "| Model | Avg Latency | P95 Latency | Success Rate | Timeouts | Common Failures | Last Updated |\n",
+        "| Model | Avg Latency | P50 | P95 Latency | P99 | Histogram | Success Rate | Timeouts | By Prompt Size | Common Failures | Last Updated |\n",
     );
     table.push_str(
-        "|-------|-------------|-------------|--------------|----------|-----------------|--------------|",
+        "|-------|-------------|-----|-------------|-----|-----------|--------------|----------|----------------|-----------------|--------------|",
     );
     for (_, row) in &rows {
         table.push('\n');
@@ -1122,13 +1926,30 @@ fn compute_summary(events: &[String], id_to_key: &HashMap<String, String>) -> St
     table
 }
 
+/// Prompt length of an event row, if logged (new format: cols[8], old format: cols[7]).
+fn event_prompt_len(cols: &[&str]) -> Option<usize> {
+    let prompt_col = if cols.len() >= 10 { 8 } else { 7 };
+    cols.get(prompt_col).and_then(|s| s.parse().ok())
+}
+
+/// Prompt-size bucket of an event row, if its prompt length was logged.
+fn event_prompt_size(cols: &[&str]) -> Option<PromptSize> {
+    event_prompt_len(cols).map(PromptSize::from_len)
+}
+
 /// Generate model recommendations with recency-weighted confidence.
 ///
 /// Parses the event log to compute per-model stats, applies a 90-day
 /// decay to confidence, and generates actionable recommendations for
 /// model selection.
 /// If `id_to_key` is non-empty, normalizes model names from provider model_ids to config keys.
-fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, String>) -> String {
+/// With `prompt_size`, only events in that bucket count; if there are none, all events
+/// are used and the output says so.
+fn generate_recommendations(
+    models_content: &str,
+    id_to_key: &HashMap<String, String>,
+    prompt_size: Option<PromptSize>,
+) -> String {
     let (_, events) = parse_models_file(models_content);
     if events.is_empty() {
         return String::new();
@@ -1147,6 +1968,12 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
 
     // (total_lat, count, successes, last_seen)
     let mut stats: HashMap<String, (f64, usize, usize, String)> = HashMap::new();
+    // (total format compliance, scored reviews)
+    let mut format_stats: HashMap<String, (f64, usize)> = HashMap::new();
+    // (total rubric grade, graded reviews)
+    let mut rubric_stats: HashMap<String, (f64, usize)> = HashMap::new();
+    // (successes, quality events) per (prompt size, model), across all sizes
+    let mut size_stats: HashMap<(PromptSize, String), (usize, usize)> = HashMap::new();
 
     for line in &events {
         let cols: Vec<&str> = line.split('|').map(|s| s.trim()).collect();
@@ -1168,10 +1995,39 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
         };
         let event_date = cols[1].get(..10).unwrap_or("").to_string();
 
+        let size = event_prompt_size(&cols);
+        if let Some(size) = size
+            && !matches!(
+                reason,
+                "auth_failed" | "rate_limited" | "queued_timeout" | "backend_down"
+            )
+        {
+            let s = size_stats.entry((size, model.clone())).or_insert((0, 0));
+            s.0 += usize::from(status == "success" && partial != "yes");
+            s.1 += 1;
+        }
+        if prompt_size.is_some() && size != prompt_size {
+            continue;
+        }
+
+        if let Some(score) = cols.get(11).and_then(|s| s.parse::<f64>().ok()) {
+            let f = format_stats.entry(model.clone()).or_insert((0.0, 0));
+            f.0 += score;
+            f.1 += 1;
+        }
+        if let Some(score) = cols.get(12).and_then(|s| s.parse::<f64>().ok()) {
+            let g = rubric_stats.entry(model.clone()).or_insert((0.0, 0));
+            g.0 += score;
+            g.1 += 1;
+        }
+
         let entry = stats.entry(model).or_insert((0.0, 0, 0, String::new()));
 
         // Exclude infrastructure failures from quality stats
-        let is_infra = matches!(reason, "auth_failed" | "rate_limited");
+        let is_infra = matches!(
+            reason,
+            "auth_failed" | "rate_limited" | "queued_timeout" | "backend_down"
+        );
         if !is_infra {
             entry.0 += latency;
             entry.1 += 1;
@@ -1184,6 +2040,20 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
         }
     }
 
+    if let Some(size) = prompt_size
+        && stats.is_empty()
+    {
+        return generate_recommendations(models_content, id_to_key, None).replacen(
+            "# Model Recommendations\n\n",
+            &format!(
+                "# Model Recommendations\n\n*No events logged for {} prompts ({}) yet; showing all sizes.*\n\n",
+                size.label(),
+                size.range()
+            ),
+            1,
+        );
+    }
+
     let mut recs: Vec<(String, ModelRec)> = stats
         .into_iter()
         .map(|(model, (total_lat, count, successes, last_seen))| {
@@ -1232,7 +2102,14 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    let mut output = String::from("# Model Recommendations\n\n");
+    let mut output = match prompt_size {
+        Some(size) => format!(
+            "# Model Recommendations ({} prompts, {})\n\n",
+            size.label(),
+            size.range()
+        ),
+        None => String::from("# Model Recommendations\n\n"),
+    };
 
     // Quick triage: fastest model with >80% success
     if let Some((name, r)) = recs
@@ -1267,6 +2144,72 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
         ));
     }
 
+    // By prompt size: the best model per bucket, when more than one bucket has data.
+    // A model that is reliable on small prompts can time out on large ones.
+    if prompt_size.is_none() {
+        let best: Vec<String> = PromptSize::ALL
+            .iter()
+            .filter_map(|size| {
+                size_stats
+                    .iter()
+                    .filter(|((s, _), (_, n))| s == size && *n >= 3)
+                    .map(|((_, model), (ok, n))| {
+                        let smoothed =
+                            (*ok as f64 + PRIOR_RATE * PRIOR_COUNT) / (*n as f64 + PRIOR_COUNT);
+                        (model, *ok as f64 / *n as f64, *n, smoothed)
+                    })
+                    .max_by(|a, b| {
+                        a.3.partial_cmp(&b.3)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                            .then_with(|| b.0.cmp(a.0))
+                    })
+                    .map(|(model, rate, n, _)| {
+                        format!("{} {model} {:.0}% ({n})", size.label(), rate * 100.0)
+                    })
+            })
+            .collect();
+        if best.len() > 1 {
+            output.push_str(&format!(
+                "**By prompt size** (pass `prompt_size` for bucket-specific stats): {}\n\n",
+                best.join(", ")
+            ));
+        }
+    }
+
+    // Instruction following: how well each model kept to requested output formats
+    let mut following: Vec<(&String, f64, usize)> = format_stats
+        .iter()
+        .map(|(model, (total, n))| (model, total / *n as f64, *n))
+        .collect();
+    following.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    if !following.is_empty() {
+        let list: Vec<String> = following
+            .iter()
+            .map(|(model, avg, n)| format!("{model} {:.0}% ({n})", avg * 100.0))
+            .collect();
+        output.push_str(&format!(
+            "**Format compliance** (requested output structure followed): {}\n\n",
+            list.join(", ")
+        ));
+    }
+
+    // Answer quality: average rubric grade from judge models
+    let mut quality: Vec<(&String, f64, usize)> = rubric_stats
+        .iter()
+        .map(|(model, (total, n))| (model, total / *n as f64, *n))
+        .collect();
+    quality.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    if !quality.is_empty() {
+        let list: Vec<String> = quality
+            .iter()
+            .map(|(model, avg, n)| format!("{model} {:.0}% ({n})", avg * 100.0))
+            .collect();
+        output.push_str(&format!(
+            "**Rubric score** (judged answer quality): {}\n\n",
+            list.join(", ")
+        ));
+    }
+
     // Full table
     output.push_str("| Model | Avg Latency | Success Rate | Confidence | Last Seen | Samples |\n");
     output.push_str("|-------|-------------|--------------|------------|-----------|---------|");
@@ -1287,7 +2230,7 @@ fn generate_recommendations(models_content: &str, id_to_key: &HashMap<String, St
 
 /// Parse a YYYY-MM-DD date string to days since Unix epoch.
 /// Returns None for malformed or out-of-range dates (year < 1970, month/day = 0).
-fn date_to_days(date: &str) -> Option<u64> {
+pub(crate) fn date_to_days(date: &str) -> Option<u64> {
     if date.len() < 10 {
         return None;
     }
@@ -1360,10 +2303,10 @@ fn format_models_file(summary: &str, events: &[String]) -> String {
     output.push_str(summary);
     output.push_str("\n\n## Recent Events (last 100)\n");
     output.push_str(
-        "| Timestamp | Model | Latency | Status | Partial | Reason | Error | Prompt Len |\n",
+        "| Timestamp | Model | Latency | Status | Partial | Reason | Error | Prompt Len | TTFB | Quotes | Format | Rubric | Headers | Stream | Queue | Scope |\n",
     );
     output.push_str(
-        "|-----------|-------|---------|--------|---------|--------|-------|------------|",
+        "|-----------|-------|---------|--------|---------|--------|-------|------------|------|--------|--------|--------|---------|--------|-------|-------|",
     );
     for event in events {
         output.push('\n');
@@ -1509,7 +2452,7 @@ fn extract_entry_metadata_lines(entry: &str) -> Vec<&str> {
 }
 
 /// ISO date string (YYYY-MM-DD).
-fn iso_date() -> String {
+pub(crate) fn iso_date() -> String {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -1524,7 +2467,13 @@ fn iso_timestamp() -> String {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
-        .as_secs();
+        .as_millis();
+    iso_from_epoch_ms(now as i64)
+}
+
+/// ISO timestamp string (YYYY-MM-DDTHH:MM:SSZ) for a unix time in milliseconds.
+pub(crate) fn iso_from_epoch_ms(ms: i64) -> String {
+    let now = (ms.max(0) / 1000) as u64;
     let days = now / 86400;
     let day_secs = now % 86400;
     let (year, month, day) = days_to_ymd(days);
@@ -1573,12 +2522,18 @@ pub(crate) fn floor_char_boundary(s: &str, max: usize) -> usize {
 /// in read-modify-write callers.
 async fn read_to_string_lossy(path: &std::path::Path) -> Result<String, std::io::Error> {
     match tokio::fs::read(path).await {
-        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        Ok(bytes) => crate::crypto::open(String::from_utf8_lossy(&bytes).into_owned()),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
         Err(e) => Err(e),
     }
 }
 
+/// Read a memory or review file, decrypting it if it was sealed at rest.
+/// Unlike `read_to_string_lossy`, a missing file is an error.
+async fn read_decrypted(path: &std::path::Path) -> Result<String, std::io::Error> {
+    crate::crypto::open(tokio::fs::read_to_string(path).await?)
+}
+
 /// Index file content.
 const INDEX_CONTENT: &str = "# Squall Memory
 
@@ -1595,6 +2550,9 @@ This directory contains Squall's learning from past interactions.
 - `models.md` \u{2014} Auto-updated model performance stats
 - `patterns.md` \u{2014} Human/AI-curated recurring findings
 - `tactics.md` \u{2014} What works for each model
+- `calibration.md` \u{2014} How often each model's high-confidence findings were rated actionable
+- `hotspots.md` \u{2014} Files ranked by the confirmed findings reviews left on them
+- `disagreements.md` \u{2014} Findings models contradicted each other on, and which side feedback backed
 ";
 
 // --- Public wrappers for integration testing (phase4_defects) ---
@@ -1606,7 +2564,7 @@ pub fn content_hash_pub(content: &str, scope: Option<&str>) -> String {
 
 /// Public wrapper for `generate_recommendations` (testing sample-count weighting).
 pub fn generate_recommendations_pub(models_content: &str) -> String {
-    generate_recommendations(models_content, &HashMap::new())
+    generate_recommendations(models_content, &HashMap::new(), None)
 }
 
 /// Public wrapper for `iso_date` (needed by tests to build model events).
@@ -1671,6 +2629,57 @@ mod tests {
         assert!(!parsed_summary.is_empty());
     }
 
+    #[test]
+    fn latency_histogram_percentiles() {
+        let mut h = LatencyHistogram::default();
+        assert_eq!(h.percentile(0.5), None);
+        // 18 fast samples and 2 that hang: the average looks tolerable, the tail doesn't
+        for _ in 0..18 {
+            h.record(8.0);
+        }
+        h.record(310.0);
+        h.record(700.0);
+        assert_eq!(h.total(), 20);
+        assert_eq!(h.percentile(0.50), Some(10.0));
+        assert_eq!(h.percentile(0.95), Some(600.0));
+        assert_eq!(h.percentile(0.99), Some(700.0));
+        assert_eq!(
+            h.to_compact_string(),
+            "\u{2264}10s:18 \u{2264}600s:1 >600s:1"
+        );
+    }
+
+    #[test]
+    fn latency_histogram_caps_at_max_sample() {
+        let mut h = LatencyHistogram::default();
+        h.record(3.0);
+        h.record(f64::NAN);
+        h.record(-1.0);
+        assert_eq!(h.total(), 1);
+        assert_eq!(h.percentile(0.99), Some(3.0));
+    }
+
+    #[test]
+    fn compute_summary_reports_percentiles_and_histogram() {
+        let mut events: Vec<String> = (0..19)
+            .map(|i| {
+                format!(
+                    "| 2026-02-25T10:{i:02}:00Z | grok | 8.0s | success | no | \u{2014} | \u{2014} | 2000 |"
+                )
+            })
+            .collect();
+        events.push(
+            "| 2026-02-25T11:00:00Z | grok | 400.0s | success | no | \u{2014} | \u{2014} | 2000 |"
+                .to_string(),
+        );
+        let summary = compute_summary(&events, &HashMap::new());
+        assert!(summary.contains("| P50 | P95 Latency | P99 | Histogram |"));
+        assert!(
+            summary.contains("| 8s | 8s | 400s | \u{2264}10s:19 \u{2264}600s:1 |"),
+            "summary: {summary}"
+        );
+    }
+
     #[test]
     fn compute_summary_basic() {
         let events = vec![
@@ -1684,6 +2693,64 @@ mod tests {
         assert!(summary.contains("67%"), "summary: {summary}");
     }
 
+    /// grok is reliable on small prompts and times out on huge ones; gemini the reverse.
+    fn sized_events() -> Vec<String> {
+        let mut events = Vec::new();
+        for i in 0..6 {
+            events.push(format!(
+                "| 2026-02-25T10:{i:02}:00Z | grok | 9.0s | success | no | \u{2014} | \u{2014} | 2000 |"
+            ));
+            events.push(format!(
+                "| 2026-02-25T11:{i:02}:00Z | grok | 300.0s | error | no | timeout | timed out | 200000 |"
+            ));
+            events.push(format!(
+                "| 2026-02-25T12:{i:02}:00Z | gemini | 40.0s | {} | no | \u{2014} | \u{2014} | 2000 |",
+                if i < 3 { "success" } else { "error" }
+            ));
+            events.push(format!(
+                "| 2026-02-25T13:{i:02}:00Z | gemini | 60.0s | success | no | \u{2014} | \u{2014} | 200000 |"
+            ));
+        }
+        events
+    }
+
+    #[test]
+    fn compute_summary_breaks_down_by_prompt_size() {
+        let summary = compute_summary(&sized_events(), &HashMap::new());
+        assert!(summary.contains("| By Prompt Size |"), "summary: {summary}");
+        assert!(
+            summary.contains("| S 100% (6), XL 0% (6) |"),
+            "summary: {summary}"
+        );
+        assert!(
+            summary.contains("| S 50% (6), XL 100% (6) |"),
+            "summary: {summary}"
+        );
+    }
+
+    #[test]
+    fn recommendations_follow_prompt_size() {
+        let content = format_models_file("", &sized_events());
+        let id_to_key = HashMap::new();
+
+        let all = generate_recommendations(&content, &id_to_key, None);
+        assert!(
+            all.contains("**By prompt size** (pass `prompt_size` for bucket-specific stats): S grok 100% (6), XL gemini 100% (6)"),
+            "all: {all}"
+        );
+
+        let xl = generate_recommendations(&content, &id_to_key, Some(PromptSize::Xl));
+        assert!(xl.starts_with("# Model Recommendations (XL prompts, >=128KB)"));
+        assert!(xl.contains("\n| gemini | 60s | 100% |"), "xl: {xl}");
+        assert!(xl.contains("\n| grok | 300s | 0% |"), "xl: {xl}");
+
+        let m = generate_recommendations(&content, &id_to_key, Some(PromptSize::M));
+        assert!(
+            m.contains("*No events logged for M prompts (8-32KB) yet; showing all sizes.*"),
+            "m: {m}"
+        );
+    }
+
     #[test]
     fn compute_summary_timeouts_column_denominator() {
         // New format: 5 successes + 3 timeouts + 2 cutoffs = 10 quality events
@@ -1755,6 +2822,17 @@ mod tests {
             reason: None,
             latency_ms: 5000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }];
 
         store.log_model_metrics(&results, 1000, None).await;
@@ -1854,6 +2932,45 @@ mod tests {
         assert!(result.unwrap_err().contains("too long"));
     }
 
+    #[tokio::test]
+    async fn writes_wait_for_lock_held_by_another_process() {
+        let (store, tmp) = test_store("cross-process-lock").await;
+        tokio::fs::create_dir_all(&tmp).await.unwrap();
+
+        // A separate handle stands in for another Squall server holding the lock.
+        let other = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(tmp.join(".lock"))
+            .unwrap();
+        other.lock().unwrap();
+
+        let store = std::sync::Arc::new(store);
+        let writer = {
+            let store = store.clone();
+            tokio::spawn(async move {
+                store
+                    .memorize("pattern", "Written after unlock", None, None, None, None)
+                    .await
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(
+            !writer.is_finished(),
+            "write must block while the lock is held"
+        );
+
+        drop(other);
+        writer.await.unwrap().unwrap();
+        let content = tokio::fs::read_to_string(tmp.join("patterns.md"))
+            .await
+            .unwrap();
+        assert!(content.contains("Written after unlock"));
+
+        let _ = tokio::fs::remove_dir_all(tmp.parent().unwrap()).await;
+    }
+
     #[tokio::test]
     async fn memory_store_read_empty() {
         let (store, tmp) = test_store("read-empty").await;
@@ -1878,6 +2995,17 @@ mod tests {
             reason: None,
             latency_ms: 25000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }];
         store.log_model_metrics(&results, 1000, None).await;
         store
@@ -2014,6 +3142,22 @@ mod tests {
         assert!(date_to_days("1969-12-31").is_none());
     }
 
+    #[test]
+    fn memory_query_validates_since() {
+        let ok = MemoryQuery {
+            since: Some("2026-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(ok.validate().is_ok());
+        for bad in ["2026-1-1", "yesterday", "2026-13-01"] {
+            let q = MemoryQuery {
+                since: Some(bad.to_string()),
+                ..Default::default()
+            };
+            assert!(q.validate().is_err(), "{bad} should be rejected");
+        }
+    }
+
     #[test]
     fn date_to_days_accepts_epoch() {
         // 1970-01-01 should be 0 days since epoch
@@ -2404,6 +3548,45 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(tmp.parent().unwrap()).await;
     }
 
+    #[tokio::test]
+    async fn feedback_calibrates_high_confidence_findings() {
+        let (store, tmp) = test_store("feedback_calibration").await;
+        let reviews = tmp.parent().unwrap().join("reviews");
+        tokio::fs::create_dir_all(&reviews).await.unwrap();
+        let high = "### 1. **Token compared with ==** (Confidence: **High**)\nTiming leak.\n";
+        let review = serde_json::json!({"results": [
+            {"model": "grok", "status": "success", "response": high},
+            {"model": "kimi", "status": "success", "response": high},
+        ]});
+        tokio::fs::write(reviews.join("1_2_3.json"), review.to_string())
+            .await
+            .unwrap();
+
+        for _ in 0..crate::calibration::MIN_SAMPLES {
+            let scores: HashMap<String, u8> =
+                [("grok".to_string(), 2u8), ("kimi".to_string(), 0u8)]
+                    .into_iter()
+                    .collect();
+            store.record_feedback("1_2_3", &scores, None).await.unwrap();
+        }
+
+        let calibration = store.get_calibration().await;
+        assert_eq!(
+            calibration["grok"].accepted,
+            crate::calibration::MIN_SAMPLES
+        );
+        assert_eq!(calibration["kimi"].shift(), -1);
+        let content = read_to_string_lossy(&store.calibration_path())
+            .await
+            .unwrap();
+        assert!(
+            content.contains("| grok | 1_2_3 | 1 | actionable |"),
+            "{content}"
+        );
+
+        let _ = tokio::fs::remove_dir_all(tmp.parent().unwrap()).await;
+    }
+
     #[tokio::test]
     async fn feedback_rejects_invalid_score() {
         let (store, tmp) = test_store("feedback_invalid").await;
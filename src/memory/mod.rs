@@ -4,16 +4,19 @@ mod local;
 pub mod global;
 #[cfg(feature = "global-memory")]
 pub mod schema;
+#[cfg(feature = "global-memory")]
+pub mod sync;
 
 // Re-export public items from local (excluding MemoryStore, which is aliased below).
 pub use local::{
-    CONFIRMED_THRESHOLD, MAX_MEMORIZE_CONTENT_LEN, MAX_PATTERN_ENTRIES, MAX_TACTICS_BYTES,
-    ModelGateStats, VALID_CATEGORIES, content_hash_pub, extract_evidence_count_pub,
-    generate_recommendations_pub, iso_date_pub,
+    CONFIRMED_THRESHOLD, LATENCY_BUCKETS_SECS, LatencyHistogram, MAX_MEMORIZE_CONTENT_LEN,
+    MAX_PATTERN_ENTRIES, MAX_TACTICS_BYTES, MemoryQuery, ModelGateStats, VALID_CATEGORIES,
+    content_hash_pub, extract_evidence_count_pub, generate_recommendations_pub, iso_date_pub,
 };
+pub(crate) use local::{date_to_days, iso_date, iso_from_epoch_ms};
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::tools::review::ReviewModelResult;
 
@@ -24,14 +27,18 @@ use crate::tools::review::ReviewModelResult;
 /// read operations compose results from both sources.
 pub struct CompositeMemoryStore {
     local: local::MemoryStore,
+    /// Shared by every store `for_project` derives from this one.
+    #[cfg(feature = "global-memory")]
+    global: Option<std::sync::Arc<global::GlobalWriter>>,
+    /// Why `global` is None, for `global_memory_status`.
     #[cfg(feature = "global-memory")]
-    global: Option<global::GlobalWriter>,
+    global_unavailable: Option<String>,
     /// Cached (working_directory, project_id) from `log_model_metrics`.
     /// Avoids spawning `git remote get-url origin` on every review call.
     /// Also used by `compose_recommendations` to exclude the current project from global stats.
     #[cfg(feature = "global-memory")]
     cached_project: std::sync::Mutex<Option<(String, String)>>,
-    /// Guard: only attempt bootstrap once per process lifetime.
+    /// Guard: only attempt bootstrap once per store (once per project in a daemon).
     #[cfg(feature = "global-memory")]
     bootstrapped: std::sync::atomic::AtomicBool,
 }
@@ -52,6 +59,8 @@ impl CompositeMemoryStore {
             #[cfg(feature = "global-memory")]
             global: None,
             #[cfg(feature = "global-memory")]
+            global_unavailable: None,
+            #[cfg(feature = "global-memory")]
             cached_project: std::sync::Mutex::new(None),
             #[cfg(feature = "global-memory")]
             bootstrapped: std::sync::atomic::AtomicBool::new(false),
@@ -65,6 +74,8 @@ impl CompositeMemoryStore {
             #[cfg(feature = "global-memory")]
             global: None,
             #[cfg(feature = "global-memory")]
+            global_unavailable: None,
+            #[cfg(feature = "global-memory")]
             cached_project: std::sync::Mutex::new(None),
             #[cfg(feature = "global-memory")]
             bootstrapped: std::sync::atomic::AtomicBool::new(false),
@@ -80,10 +91,56 @@ impl CompositeMemoryStore {
     /// Set the global writer for cross-project memory.
     #[cfg(feature = "global-memory")]
     pub fn with_global(mut self, writer: global::GlobalWriter) -> Self {
-        self.global = Some(writer);
+        self.global = Some(std::sync::Arc::new(writer));
         self
     }
 
+    /// Record why global memory is not running (disabled, or failed to start).
+    #[cfg(feature = "global-memory")]
+    pub fn with_global_unavailable(mut self, reason: impl Into<String>) -> Self {
+        self.global_unavailable = Some(reason.into());
+        self
+    }
+
+    /// A store for the project at `root` (`<root>/.squall/memory`) that shares
+    /// this store's model-key map and global writer, but has its own project-ID
+    /// cache. Used to keep daemon sessions in different projects apart.
+    pub fn for_project(&self, root: &Path) -> Self {
+        Self {
+            local: self.local.relocated(root.join(local::DEFAULT_MEMORY_DIR)),
+            #[cfg(feature = "global-memory")]
+            global: self.global.clone(),
+            #[cfg(feature = "global-memory")]
+            global_unavailable: self.global_unavailable.clone(),
+            #[cfg(feature = "global-memory")]
+            cached_project: std::sync::Mutex::new(None),
+            #[cfg(feature = "global-memory")]
+            bootstrapped: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Markdown health report for the global (cross-project) store.
+    pub fn global_status_report(&self) -> String {
+        #[cfg(feature = "global-memory")]
+        {
+            match (&self.global, &self.global_unavailable) {
+                (Some(writer), _) => writer.status().to_markdown(),
+                (None, Some(reason)) => {
+                    format!("## Global Memory Status\n\nGlobal memory is not running: {reason}.\n")
+                }
+                (None, None) => {
+                    "## Global Memory Status\n\nGlobal memory is not configured.\n".to_string()
+                }
+            }
+        }
+        #[cfg(not(feature = "global-memory"))]
+        {
+            "## Global Memory Status\n\nGlobal memory is not compiled into this build \
+             (feature `global-memory`).\n"
+                .to_string()
+        }
+    }
+
     /// Set the cached project ID (for testing compose_recommendations exclusion).
     #[cfg(feature = "global-memory")]
     pub fn set_project_id(&self, id: String) {
@@ -97,15 +154,41 @@ impl CompositeMemoryStore {
     /// When `working_directory` is `Some` and a global writer is configured,
     /// events are also forwarded to the global cross-project store.
     pub async fn log_model_metrics(
+        &self,
+        results: &[ReviewModelResult],
+        prompt_len: usize,
+        id_to_key: Option<&HashMap<String, String>>,
+        working_directory: Option<&str>,
+    ) {
+        self.log_model_metrics_scoped(results, prompt_len, id_to_key, working_directory, None)
+            .await
+    }
+
+    /// `log_model_metrics` with a scope (e.g. `branch:<name>`) recorded on each
+    /// local event, for `flush_branch` to graduate after the merge.
+    pub async fn log_model_metrics_scoped(
         &self,
         results: &[ReviewModelResult],
         prompt_len: usize,
         id_to_key: Option<&HashMap<String, String>>,
         #[cfg_attr(not(feature = "global-memory"), allow(unused_variables))]
         working_directory: Option<&str>,
+        scope: Option<&str>,
     ) {
+        // Models cancelled because a quorum already answered, or still finishing in
+        // the background, say nothing about their reliability yet; logging them
+        // would count against the hard gate.
+        let undecided =
+            |r: &ReviewModelResult| matches!(r.reason.as_deref(), Some("quorum" | "background"));
+        let kept: Vec<ReviewModelResult>;
+        let results = if results.iter().any(undecided) {
+            kept = results.iter().filter(|r| !undecided(r)).cloned().collect();
+            &kept[..]
+        } else {
+            results
+        };
         self.local
-            .log_model_metrics(results, prompt_len, id_to_key)
+            .log_model_metrics_scoped(results, prompt_len, id_to_key, scope)
             .await;
 
         #[cfg(feature = "global-memory")]
@@ -130,7 +213,7 @@ impl CompositeMemoryStore {
             writer.log_events(results, prompt_len, &project_id, Some(wd), id_to_key);
 
             // Lazy bootstrap: on first call with a real project_id, ingest local
-            // models.md history into DuckDB. Runs at most once per process.
+            // models.md history into DuckDB. Runs at most once per store.
             if !self.bootstrapped.load(std::sync::atomic::Ordering::Relaxed) {
                 let models_path = self.local.models_path();
                 if models_path.exists() {
@@ -153,6 +236,18 @@ impl CompositeMemoryStore {
         self.local.record_feedback(review_file, scores, note).await
     }
 
+    /// Memorize consensus findings from a persisted review as patterns.
+    pub async fn memorize_from_review(
+        &self,
+        review_id: &str,
+        min_consensus: usize,
+        scope: Option<&str>,
+    ) -> Result<String, String> {
+        self.local
+            .memorize_from_review(review_id, min_consensus, scope)
+            .await
+    }
+
     /// Write an explicit memorize entry to patterns.md or tactics.md.
     pub async fn memorize(
         &self,
@@ -184,7 +279,7 @@ impl CompositeMemoryStore {
         if category == Some("recommend")
             && let Some(writer) = &self.global
         {
-            return self.compose_recommendations(writer, max_chars).await;
+            return self.compose_recommendations(writer, max_chars, None).await;
         }
 
         self.local
@@ -192,6 +287,28 @@ impl CompositeMemoryStore {
             .await
     }
 
+    /// Read memory filtered by a structured query. Recommendations are composed
+    /// the same way as `read_memory`; the query narrows patterns and tactics.
+    pub async fn read_memory_query(
+        &self,
+        category: Option<&str>,
+        max_chars: usize,
+        query: &MemoryQuery,
+    ) -> Result<String, String> {
+        #[cfg(feature = "global-memory")]
+        if category == Some("recommend")
+            && let Some(writer) = &self.global
+        {
+            return self
+                .compose_recommendations(writer, max_chars, query.prompt_size)
+                .await;
+        }
+
+        self.local
+            .read_memory_query(category, max_chars, query)
+            .await
+    }
+
     /// Returns per-model stats parsed from models.md event log.
     pub async fn get_model_stats(
         &self,
@@ -200,11 +317,43 @@ impl CompositeMemoryStore {
         self.local.get_model_stats(id_to_key).await
     }
 
+    /// Add a review's findings to the hot-spot map (see `crate::hotspots`).
+    pub async fn record_hotspots(
+        &self,
+        findings: &[crate::findings::Finding],
+    ) -> Result<(), String> {
+        self.local.record_hotspots(findings).await
+    }
+
+    /// Files ranked by confirmed findings, hottest first.
+    pub async fn get_hotspots(&self) -> Vec<crate::hotspots::Hotspot> {
+        self.local.get_hotspots().await
+    }
+
+    /// Record contradictions between models in review `review` (see `crate::disagreement`).
+    pub async fn record_disagreements(
+        &self,
+        review: &str,
+        found: &[crate::disagreement::Disagreement],
+    ) -> Result<usize, String> {
+        self.local.record_disagreements(review, found).await
+    }
+
+    /// Per-model confidence calibration from feedback (see `crate::calibration`).
+    pub async fn get_calibration(&self) -> HashMap<String, crate::calibration::Calibration> {
+        self.local.get_calibration().await
+    }
+
     /// Flush branch-scoped memory after PR merge.
     pub async fn flush_branch(&self, branch: &str) -> Result<String, String> {
         self.local.flush_branch(branch).await
     }
 
+    /// Branches with branch-scoped patterns or model events still to flush.
+    pub async fn scoped_branches(&self) -> Vec<String> {
+        self.local.scoped_branches().await
+    }
+
     /// Compose local + global recommendations into a unified view.
     ///
     /// Confidence levels:
@@ -212,15 +361,20 @@ impl CompositeMemoryStore {
     /// - **M** (Medium): only one source has data
     /// - **L** (Low): local and global disagree (>20% success rate delta)
     ///
+    /// A model's confidence calibration (`crate::calibration`) then moves its level
+    /// one step down or up.
+    ///
     /// Includes an exploration slot for models with <5 global samples.
+    /// With `prompt_size`, both sources count only events in that bucket.
     #[cfg(feature = "global-memory")]
     async fn compose_recommendations(
         &self,
         writer: &global::GlobalWriter,
         max_chars: usize,
+        prompt_size: Option<crate::tools::enums::PromptSize>,
     ) -> Result<String, String> {
         // 1. Get local stats (structured)
-        let local_stats = self.local.get_model_stats(None).await;
+        let local_stats = self.local.get_model_stats_for_size(None, prompt_size).await;
 
         // 2. Query global stats, excluding the current project to avoid double-counting
         let exclude_id = self
@@ -228,7 +382,7 @@ impl CompositeMemoryStore {
             .lock()
             .ok()
             .and_then(|g| g.as_ref().map(|(_, id)| id.clone()));
-        let global_recs = match writer.query_recommendations(exclude_id.as_deref()) {
+        let global_recs = match writer.query_recommendations(exclude_id.as_deref(), prompt_size) {
             Ok(reply_rx) => {
                 // 15s allows for a queued merge (~2s) + the query itself (~1s) with margin.
                 match tokio::time::timeout(std::time::Duration::from_secs(15), reply_rx).await {
@@ -255,6 +409,7 @@ impl CompositeMemoryStore {
 
         // 3. Compose
         let local_map = local_stats.unwrap_or_default();
+        let calibration = self.local.get_calibration().await;
 
         // Build unified model set
         let mut all_models: Vec<String> = local_map.keys().cloned().collect();
@@ -266,6 +421,13 @@ impl CompositeMemoryStore {
         all_models.sort();
 
         if all_models.is_empty() {
+            if let Some(size) = prompt_size {
+                return Ok(format!(
+                    "No model data for {} prompts ({}) yet. Omit `prompt_size` for stats across all sizes.",
+                    size.label(),
+                    size.range()
+                ));
+            }
             return Ok(
                 "No model data yet. Run a `review` first to populate model metrics.".to_string(),
             );
@@ -336,6 +498,10 @@ impl CompositeMemoryStore {
                 }
                 _ => 'M', // only one source
             };
+            // Models whose high-confidence findings rarely survive feedback drop a
+            // level; consistently accepted ones gain one.
+            let confidence =
+                crate::calibration::adjust_level(confidence, calibration.get(model.as_str()));
 
             // Sort score: prefer high confidence, high success rate, low latency
             let conf_weight = match confidence {
@@ -383,7 +549,14 @@ impl CompositeMemoryStore {
         });
 
         // Format output
-        let mut output = String::from("# Model Recommendations\n\n");
+        let mut output = match prompt_size {
+            Some(size) => format!(
+                "# Model Recommendations ({} prompts, {})\n\n",
+                size.label(),
+                size.range()
+            ),
+            None => String::from("# Model Recommendations\n\n"),
+        };
 
         // Top pick
         if let Some(top) = rows.first() {
@@ -418,6 +591,23 @@ impl CompositeMemoryStore {
             ));
         }
 
+        if let Some(line) =
+            crate::calibration::summary_line(&crate::calibration::view(&calibration, None))
+        {
+            output.push_str(&format!("\n{line}\n"));
+        }
+
+        // Time of day: models whose latency swings with provider load (global data only)
+        let load_skew = global_recs.load_skew();
+        if !load_skew.is_empty() {
+            output.push_str(
+                "\n**Time of day** (global, successful calls; schedule deep reviews accordingly):\n",
+            );
+            for line in &load_skew {
+                output.push_str(&format!("- {line}\n"));
+            }
+        }
+
         // Progressive truncation: trim to max_chars
         if output.len() > max_chars {
             let suffix = "\n\n[truncated]";
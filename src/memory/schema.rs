@@ -4,6 +4,8 @@
 
 use std::fmt;
 
+use crate::tools::enums::PromptSize;
+
 // ---------------------------------------------------------------------------
 // DDL constants
 // ---------------------------------------------------------------------------
@@ -53,8 +55,79 @@ pub const SCHEMA_V1: &[&str] = &[
     DDL_INDEX_EVENTS_MODEL,
 ];
 
+/// SQL expression bucketing a prompt length column into `PromptSize` values
+/// (`'s'`, `'m'`, `'l'`, `'xl'`); NULL when the length was not logged.
+pub fn prompt_bucket_sql(column: &str) -> String {
+    let [s, m, l] = PromptSize::BOUNDS;
+    format!(
+        "CASE WHEN {column} IS NULL THEN NULL \
+         WHEN {column} < {s} THEN '{}' \
+         WHEN {column} < {m} THEN '{}' \
+         WHEN {column} < {l} THEN '{}' \
+         ELSE '{}' END",
+        PromptSize::S.as_str(),
+        PromptSize::M.as_str(),
+        PromptSize::L.as_str(),
+        PromptSize::Xl.as_str(),
+    )
+}
+
+/// Time-of-day load windows (UTC) as `(name, label)`, in `load_window_sql` order.
+/// Labels read as "slower {label}".
+pub const LOAD_WINDOWS: &[(&str, &str)] = &[
+    ("weekend", "on weekends"),
+    (
+        "us_business",
+        "during US business hours (14-22 UTC, Mon-Fri)",
+    ),
+    (
+        "eu_business",
+        "during EU business hours (07-14 UTC, Mon-Fri)",
+    ),
+    ("weekday_night", "on weekday nights (22-07 UTC)"),
+];
+
+/// SQL expression naming the `LOAD_WINDOWS` entry of an epoch-ms timestamp column.
+pub fn load_window_sql(ts_column: &str) -> String {
+    let at = format!("epoch_ms({ts_column})");
+    format!(
+        "CASE WHEN isodow({at}) >= 6 THEN 'weekend' \
+         WHEN hour({at}) BETWEEN 14 AND 21 THEN 'us_business' \
+         WHEN hour({at}) BETWEEN 7 AND 13 THEN 'eu_business' \
+         ELSE 'weekday_night' END"
+    )
+}
+
+/// Human-readable label of a load window name (the name itself if unknown).
+pub fn load_window_label(window: &str) -> &str {
+    LOAD_WINDOWS
+        .iter()
+        .find(|(name, _)| *name == window)
+        .map_or(window, |(_, label)| label)
+}
+
+/// `model_events` plus derived analytics columns. A view rather than stored
+/// columns, so existing databases and older Parquet files need no rewrite.
+///
+/// - `prompt_bucket` (version 2): `PromptSize` of the prompt; `prompt_tokens` holds
+///   the logged prompt length in bytes.
+/// - `hour_utc`, `weekday` (ISO, 1 = Monday), `load_window` (version 3): when the
+///   call was made, for time-of-day latency analysis.
+pub fn ddl_model_events_bucketed() -> String {
+    format!(
+        "CREATE OR REPLACE VIEW model_events_bucketed AS \
+         SELECT *, {} AS prompt_bucket, \
+                hour(epoch_ms(ts)) AS hour_utc, \
+                isodow(epoch_ms(ts)) AS weekday, \
+                {} AS load_window \
+         FROM model_events;",
+        prompt_bucket_sql("prompt_tokens"),
+        load_window_sql("ts"),
+    )
+}
+
 /// Current schema version.
-pub const CURRENT_VERSION: i32 = 1;
+pub const CURRENT_VERSION: i32 = 3;
 
 // ---------------------------------------------------------------------------
 // Migration support
@@ -83,8 +156,22 @@ pub fn apply_migrations(conn: &duckdb::Connection) -> Result<i32, MigrationError
         record_version(conn, 1)?;
     }
 
+    // Apply version 2
+    if current < 2 {
+        conn.execute_batch(&ddl_model_events_bucketed())
+            .map_err(MigrationError::Duckdb)?;
+        record_version(conn, 2)?;
+    }
+
+    // Apply version 3 (time-of-day columns on the same view)
+    if current < 3 {
+        conn.execute_batch(&ddl_model_events_bucketed())
+            .map_err(MigrationError::Duckdb)?;
+        record_version(conn, 3)?;
+    }
+
     // Future migrations go here:
-    // if current < 2 { ... record_version(conn, 2)?; }
+    // if current < 4 { ... record_version(conn, 4)?; }
 
     Ok(CURRENT_VERSION)
 }
@@ -150,7 +237,7 @@ pub struct ModelEvent {
     pub status: String,
     /// Whether the response was partial/truncated.
     pub partial: bool,
-    /// Infrastructure failure reason (e.g. "auth_failed", "rate_limited"), or None.
+    /// Infrastructure failure reason (e.g. "auth_failed", "rate_limited", "queued_timeout"), or None.
     pub reason: Option<String>,
     /// Wall-clock latency in milliseconds.
     pub latency_ms: i32,
@@ -431,10 +518,10 @@ mod tests {
         let v2 = apply_migrations(&conn).unwrap();
         assert_eq!(v1, v2);
 
-        // Only one version record
+        // One record per version
         let mut stmt = conn.prepare("SELECT COUNT(*) FROM schema_version").unwrap();
         let count: i32 = stmt.query_row([], |row| row.get(0)).unwrap();
-        assert_eq!(count, 1);
+        assert_eq!(count, CURRENT_VERSION);
     }
 
     #[test]
@@ -488,6 +575,84 @@ mod tests {
         assert_eq!(latency, 25000);
     }
 
+    #[test]
+    fn bucketed_view_matches_prompt_size() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        apply_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO projects (project_id, first_seen_ts, last_seen_ts) VALUES (?, ?, ?)",
+            duckdb::params!["git:test", 1708000000000_i64, 1708000000000_i64],
+        )
+        .unwrap();
+
+        let lens = [0, 8191, 8192, 32767, 32768, 131071, 131072, 900_000];
+        for (i, len) in lens.iter().enumerate() {
+            let ts = 1708000000000_i64 + i as i64;
+            let uid = ModelEvent::compute_uid(ts, "grok", 1000, "success");
+            conn.execute(
+                "INSERT INTO model_events (event_uid, project_id, ts, model_key, status, partial, latency_ms, prompt_tokens) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                duckdb::params![uid, "git:test", ts, "grok", "success", false, 1000, *len],
+            )
+            .unwrap();
+        }
+
+        let mut stmt = conn
+            .prepare("SELECT prompt_tokens, prompt_bucket FROM model_events_bucketed ORDER BY ts")
+            .unwrap();
+        let rows: Vec<(i32, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(rows.len(), lens.len());
+        for (len, bucket) in rows {
+            assert_eq!(
+                bucket,
+                PromptSize::from_len(len as usize).as_str(),
+                "len {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn bucketed_view_derives_load_window() {
+        let conn = duckdb::Connection::open_in_memory().unwrap();
+        apply_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO projects (project_id, first_seen_ts, last_seen_ts) VALUES (?, ?, ?)",
+            duckdb::params!["git:test", 0_i64, 0_i64],
+        )
+        .unwrap();
+
+        // 2024-03-04 is a Monday; 2024-03-09 a Saturday.
+        let cases = [
+            (1_709_560_800_000_i64, 14, "us_business"), // Mon 14:00 UTC
+            (1_709_539_200_000, 8, "eu_business"),      // Mon 08:00 UTC
+            (1_709_593_200_000, 23, "weekday_night"),   // Mon 23:00 UTC
+            (1_709_992_800_000, 14, "weekend"),         // Sat 14:00 UTC
+        ];
+        for (ts, ..) in cases {
+            let uid = ModelEvent::compute_uid(ts, "grok", 1000, "success");
+            conn.execute(
+                "INSERT INTO model_events (event_uid, project_id, ts, model_key, status, partial, latency_ms) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                duckdb::params![uid, "git:test", ts, "grok", "success", false, 1000],
+            )
+            .unwrap();
+        }
+
+        for (ts, hour, window) in cases {
+            let (got_hour, got_window): (i64, String) = conn
+                .prepare("SELECT hour_utc, load_window FROM model_events_bucketed WHERE ts = ?")
+                .unwrap()
+                .query_row(duckdb::params![ts], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap();
+            assert_eq!((got_hour, got_window.as_str()), (hour, window), "ts {ts}");
+        }
+        assert_eq!(load_window_label("weekend"), "on weekends");
+    }
+
     #[test]
     fn schema_enforces_unique_event_uid() {
         let conn = duckdb::Connection::open_in_memory().unwrap();
@@ -0,0 +1,233 @@
+//! Team sync for global memory: push/pull a Parquet snapshot of `model_events`
+//! to a shared location so model performance knowledge accumulates across
+//! machines, not just per laptop.
+//!
+//! The remote holds a single snapshot object. Each sync pulls it, merges it into
+//! the local DuckDB (events are immutable and keyed by `event_uid`), then replaces
+//! it with the merged snapshot. Last writer wins on the object, but every writer
+//! merges before pushing, so events lost to a concurrent push come back with
+//! their author's next sync.
+//!
+//! Runs on the `DbWorker` thread (sync I/O); HTTP transfers use a throwaway
+//! current-thread runtime.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::GlobalSyncConfig;
+
+/// Default env var for the sync endpoint's bearer token.
+pub const SYNC_TOKEN_ENV: &str = "SQUALL_SYNC_TOKEN";
+
+/// Per-transfer timeout for HTTP remotes.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest snapshot accepted from a remote (~90 days of team events is far smaller).
+const MAX_SNAPSHOT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Where the shared snapshot lives.
+enum SyncTarget {
+    /// A file on a shared or mounted filesystem (NFS, SMB, gcsfuse, mountpoint-s3).
+    Path(PathBuf),
+    /// An object URL that serves GET and accepts PUT (GCS with an OAuth token,
+    /// an S3-compatible gateway, a WebDAV share).
+    Http { url: String, token: Option<String> },
+}
+
+/// A configured sync remote plus how often the worker syncs with it.
+pub struct GlobalSync {
+    target: SyncTarget,
+    interval: Duration,
+}
+
+impl std::fmt::Debug for GlobalSync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobalSync")
+            .field("remote", &self.display())
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl GlobalSync {
+    /// `remote` is an `http(s)://` URL, a `file://` URL, or a plain path.
+    pub fn new(remote: &str, token: Option<String>, interval: Duration) -> Self {
+        let target = if remote.starts_with("http://") || remote.starts_with("https://") {
+            SyncTarget::Http {
+                url: remote.to_string(),
+                token,
+            }
+        } else {
+            let path = remote.strip_prefix("file://").unwrap_or(remote);
+            SyncTarget::Path(PathBuf::from(expand_home(path)))
+        };
+        Self { target, interval }
+    }
+
+    /// Build from config, reading the token env var. Returns None (with a warning)
+    /// when an explicitly configured token env var is unset — syncing a team's
+    /// history unauthenticated by accident is worse than not syncing.
+    pub fn from_config(config: &GlobalSyncConfig) -> Option<Self> {
+        let token_env = config.token_env.as_deref().unwrap_or(SYNC_TOKEN_ENV);
+        let token = std::env::var(token_env).ok().filter(|t| !t.is_empty());
+        if token.is_none() && config.token_env.is_some() {
+            tracing::warn!("global memory sync disabled: {token_env} not set");
+            return None;
+        }
+        Some(Self::new(
+            &config.remote,
+            token,
+            Duration::from_secs(config.interval_secs),
+        ))
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Remote location for logs and status, without URL query strings (which
+    /// may carry signatures).
+    pub fn display(&self) -> String {
+        match &self.target {
+            SyncTarget::Path(p) => p.display().to_string(),
+            SyncTarget::Http { url, .. } => url.split('?').next().unwrap_or(url).to_string(),
+        }
+    }
+
+    /// Download the remote snapshot to `dest`. Ok(false) if none exists yet.
+    pub fn pull(&self, dest: &Path) -> Result<bool, String> {
+        match &self.target {
+            SyncTarget::Path(src) => {
+                match std::fs::metadata(src) {
+                    Ok(m) if m.len() > MAX_SNAPSHOT_BYTES => {
+                        return Err(format!("snapshot is {} bytes, over the limit", m.len()));
+                    }
+                    Ok(_) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                    Err(e) => return Err(e.to_string()),
+                }
+                std::fs::copy(src, dest)
+                    .map(|_| true)
+                    .map_err(|e| e.to_string())
+            }
+            SyncTarget::Http { url, token } => block_on(async {
+                let mut req = reqwest::Client::new().get(url).timeout(SYNC_TIMEOUT);
+                if let Some(token) = token {
+                    req = req.bearer_auth(token);
+                }
+                let resp = req.send().await.map_err(|e| e.without_url().to_string())?;
+                let status = resp.status();
+                if status == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(false);
+                }
+                if !status.is_success() {
+                    return Err(format!("GET returned HTTP {status}"));
+                }
+                if resp
+                    .content_length()
+                    .is_some_and(|len| len > MAX_SNAPSHOT_BYTES)
+                {
+                    return Err("snapshot over the size limit".to_string());
+                }
+                let bytes = resp
+                    .bytes()
+                    .await
+                    .map_err(|e| e.without_url().to_string())?;
+                if bytes.len() as u64 > MAX_SNAPSHOT_BYTES {
+                    return Err("snapshot over the size limit".to_string());
+                }
+                std::fs::write(dest, &bytes).map_err(|e| e.to_string())?;
+                Ok::<_, String>(true)
+            })?,
+        }
+    }
+
+    /// Replace the remote snapshot with `src`.
+    pub fn push(&self, src: &Path) -> Result<(), String> {
+        match &self.target {
+            SyncTarget::Path(dest) => {
+                // Copy beside the target, then rename: readers never see a torn file.
+                // The parent is not created — an unmounted share should fail, not
+                // silently sync to a local directory.
+                let tmp = dest.with_extension(format!("tmp-{}", std::process::id()));
+                std::fs::copy(src, &tmp).map_err(|e| e.to_string())?;
+                std::fs::rename(&tmp, dest).map_err(|e| {
+                    let _ = std::fs::remove_file(&tmp);
+                    e.to_string()
+                })
+            }
+            SyncTarget::Http { url, token } => {
+                let body = std::fs::read(src).map_err(|e| e.to_string())?;
+                block_on(async {
+                    let mut req = reqwest::Client::new()
+                        .put(url)
+                        .timeout(SYNC_TIMEOUT)
+                        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                        .body(body);
+                    if let Some(token) = token {
+                        req = req.bearer_auth(token);
+                    }
+                    let resp = req.send().await.map_err(|e| e.without_url().to_string())?;
+                    if !resp.status().is_success() {
+                        return Err(format!("PUT returned HTTP {}", resp.status()));
+                    }
+                    Ok::<_, String>(())
+                })?
+            }
+        }
+    }
+}
+
+/// Run a future to completion on a fresh current-thread runtime.
+/// Only called from the `DbWorker` thread, which is never inside a runtime.
+fn block_on<F: std::future::Future>(fut: F) -> Result<F::Output, String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map(|rt| rt.block_on(fut))
+        .map_err(|e| format!("failed to start sync runtime: {e}"))
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_kinds_and_redacted_display() {
+        let http = GlobalSync::new(
+            "https://storage.example.com/team/global.parquet?X-Sig=abc",
+            None,
+            Duration::from_secs(60),
+        );
+        assert!(matches!(http.target, SyncTarget::Http { .. }));
+        assert_eq!(
+            http.display(),
+            "https://storage.example.com/team/global.parquet"
+        );
+
+        let file = GlobalSync::new("file:///mnt/team/global.parquet", None, Duration::ZERO);
+        assert!(
+            matches!(&file.target, SyncTarget::Path(p) if p == Path::new("/mnt/team/global.parquet"))
+        );
+    }
+
+    #[test]
+    fn missing_path_remote_pulls_nothing() {
+        let dir = std::env::temp_dir().join(format!("squall-test-sync-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sync = GlobalSync::new(
+            dir.join("absent.parquet").to_str().unwrap(),
+            None,
+            Duration::ZERO,
+        );
+        assert_eq!(sync.pull(&dir.join("incoming.parquet")), Ok(false));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
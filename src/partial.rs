@@ -0,0 +1,98 @@
+//! Usability of partial results. A straggler cancelled at the cutoff returns
+//! whatever it had streamed: sometimes a nearly finished review, sometimes one
+//! dangling sentence. `usable` gives synthesis a per-model hint instead of
+//! treating every partial alike.
+//!
+//! - Structured output (a JSON document, bare or in a code fence) is usable when
+//!   it parses, and is non-empty, once its open strings, arrays and objects are
+//!   closed, dropping the trailing element if it was cut mid-way.
+//! - Prose is usable when at least `MIN_USABLE_CHARS` of it come before the last
+//!   line or sentence break, i.e. what remains after dropping the cut-off tail.
+
+/// Complete text a partial prose response needs to be worth reading.
+pub const MIN_USABLE_CHARS: usize = 200;
+
+/// Whether a partial response is worth including in synthesis.
+pub fn usable(text: &str) -> bool {
+    let trimmed = text.trim();
+    if let Some(json) = json_body(trimmed) {
+        return crate::json_repair::close_truncated(json).is_some_and(|v| match v {
+            serde_json::Value::Object(map) => !map.is_empty(),
+            serde_json::Value::Array(items) => !items.is_empty(),
+            _ => false,
+        });
+    }
+    complete_prefix(trimmed).chars().count() >= MIN_USABLE_CHARS
+}
+
+/// True when `text` stops mid-sentence: its last character doesn't end a
+/// sentence, a list item's markup, or a code fence.
+pub fn ends_mid_sentence(text: &str) -> bool {
+    let trimmed = text.trim_end();
+    if trimmed.ends_with("```") {
+        return false;
+    }
+    !trimmed.ends_with(['.', '!', '?', ':', ')', ']', '}', '"', '\'', '`', '*', '|'])
+}
+
+/// The JSON document of a response that is one (bare, or opening a code fence
+/// whose closing fence may have been cut off). None for prose.
+fn json_body(text: &str) -> Option<&str> {
+    let body = match text.strip_prefix("```") {
+        Some(fenced) => {
+            let (_info, body) = fenced.split_once('\n')?;
+            body.trim_end().trim_end_matches("```")
+        }
+        None => text,
+    };
+    body.trim_start().starts_with(['{', '[']).then_some(body)
+}
+
+/// `text` up to its last line break or sentence end: the part the cutoff didn't
+/// leave dangling.
+fn complete_prefix(text: &str) -> &str {
+    if !ends_mid_sentence(text) {
+        return text;
+    }
+    let sentence_end = text
+        .match_indices([' ', '\n'])
+        .filter(|(i, _)| text[..*i].ends_with(['.', '!', '?']))
+        .map(|(i, _)| i)
+        .next_back();
+    let line_end = text.rfind('\n');
+    let end = sentence_end.max(line_end).unwrap_or(0);
+    text[..end].trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_repair::close_truncated;
+
+    #[test]
+    fn prose_needs_enough_complete_text() {
+        let finding = "- **High**: the retry loop in `dispatch` never resets its backoff, \
+                       so a single slow provider stalls every later request.\n";
+        let long = format!("{}- **Medium**: the cache key omits the", finding.repeat(3));
+        assert!(ends_mid_sentence(&long));
+        assert!(usable(&long));
+
+        assert!(!usable("## Findings\n\nThe handler leaks a file desc"));
+        assert!(!ends_mid_sentence("Looks fine."));
+    }
+
+    #[test]
+    fn truncated_json_is_usable_once_closed() {
+        let cut = r#"{"findings": [{"severity": "high", "file": "src/a.rs"}, {"severity": "lo"#;
+        let closed = close_truncated(cut).unwrap();
+        assert_eq!(closed["findings"][0]["file"], "src/a.rs");
+        assert!(usable(cut));
+
+        let fenced = "```json\n[{\"line\": 4, \"issue\": \"unchecked unwrap\"}, {\"line\":";
+        assert_eq!(close_truncated(&fenced[8..]).unwrap()[0]["line"], 4);
+        assert!(usable(fenced));
+
+        // Nothing complete yet.
+        assert!(!usable(r#"{"findings": [{"sev"#));
+    }
+}
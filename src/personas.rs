@@ -0,0 +1,101 @@
+//! Reviewer personas: with `personas: true`, each model in a review's fan-out
+//! reviews as a different specialist from the built-in library, so five models
+//! cover five angles instead of making the same generic pass.
+//!
+//! Assignment rotates with the review ID, so over several reviews every model
+//! takes every persona. Models with their own `per_model_system_prompts` entry
+//! keep it and get no persona.
+
+use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// A reviewer persona.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Persona {
+    /// Short name shown in the review response.
+    pub name: &'static str,
+    /// System prompt the persona reviews under.
+    pub prompt: &'static str,
+}
+
+/// The built-in library, most broadly useful first.
+pub const PERSONAS: &[Persona] = &[
+    Persona {
+        name: "adversarial security researcher",
+        prompt: "You are an adversarial security researcher. Attempt to PROVE the change can be exploited: injection, broken authorization, unsafe deserialization, secrets exposure, or trust boundaries crossed without validation. Show the input that breaks it. Report confidence for each finding.",
+    },
+    Persona {
+        name: "correctness auditor",
+        prompt: "You are a correctness auditor. Attempt to PROVE the logic is wrong: trace edge cases, error paths, off-by-one bounds, and states the author did not consider. If the logic holds, say why. Report confidence for each finding.",
+    },
+    Persona {
+        name: "performance engineer",
+        prompt: "You are a performance engineer. Look for avoidable allocations and copies, blocking calls on async paths, super-linear work on hot paths, and unbounded growth. Estimate the cost where you can. Report confidence for each finding.",
+    },
+    Persona {
+        name: "API ergonomics reviewer",
+        prompt: "You are an API ergonomics reviewer. Judge the change from its callers' side: naming, misuse-resistant signatures, error types and messages, defaults, and breaking changes to public interfaces. Propose concrete alternatives. Report confidence for each finding.",
+    },
+    Persona {
+        name: "reliability engineer",
+        prompt: "You are a reliability engineer who gets paged when this fails. Look for failure modes in production: timeouts and retries, partial failures, resource leaks, races, missing logging or metrics, and unsafe rollouts. Report confidence for each finding.",
+    },
+    Persona {
+        name: "maintainability reviewer",
+        prompt: "You are the engineer who will maintain this code next year. Look for unclear structure, duplicated logic, leaky abstractions, missing or weak tests, and comments that mislead. Report confidence for each finding.",
+    },
+];
+
+/// A persona for each of `models`, in order, starting at a library position
+/// that rotates with `review_id`. Personas repeat only when there are more
+/// models than personas.
+pub fn assign(models: &[String], review_id: &str) -> BTreeMap<String, &'static Persona> {
+    let mut hasher = DefaultHasher::new();
+    review_id.hash(&mut hasher);
+    let offset = hasher.finish() as usize % PERSONAS.len();
+    models
+        .iter()
+        .enumerate()
+        .map(|(i, model)| (model.clone(), &PERSONAS[(offset + i) % PERSONAS.len()]))
+        .collect()
+}
+
+/// `persona`'s prompt followed by the shared `system_prompt`, if any.
+pub fn merge(persona: &Persona, system_prompt: Option<&str>) -> String {
+    match system_prompt.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(prompt) => format!("{}\n\n{prompt}", persona.prompt),
+        None => persona.prompt.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_get_distinct_personas_that_rotate_across_reviews() {
+        let models: Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let first = assign(&models, "1773157800594_1_0");
+        let names: std::collections::HashSet<_> = first.values().map(|p| p.name).collect();
+        assert_eq!(names.len(), 4, "{first:?}");
+
+        // Across reviews, a model sees more than one persona.
+        let seen: std::collections::HashSet<_> = (0..20)
+            .map(|i| assign(&models, &format!("review_{i}"))["a"].name)
+            .collect();
+        assert!(seen.len() > 1, "{seen:?}");
+
+        let many: Vec<String> = (0..PERSONAS.len() + 1).map(|i| i.to_string()).collect();
+        assert_eq!(assign(&many, "r").len(), PERSONAS.len() + 1);
+    }
+
+    #[test]
+    fn persona_prompt_leads_the_shared_system_prompt() {
+        let persona = &PERSONAS[2];
+        assert_eq!(merge(persona, None), persona.prompt);
+        assert_eq!(
+            merge(persona, Some("Focus on src/pool.rs.")),
+            format!("{}\n\nFocus on src/pool.rs.", persona.prompt)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Meeting-of-experts planning for the `plan` tool.
+//!
+//! Every model proposes an implementation plan for the task on its own. A
+//! synthesizer merges the proposals into one ordered step list as JSON: each
+//! step names the files it touches, the steps it depends on, and the models
+//! whose plans contained it. Where the proposals disagree (order, approach,
+//! whether a step is needed at all) the step carries the disagreement, and
+//! disagreements that aren't about one step are listed on their own. The
+//! result is meant to be handed to a coding agent step by step.
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::escape_xml_attr;
+
+/// Seconds for proposals and synthesis together, unless the request says.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Most steps kept from the synthesized plan.
+pub const MAX_STEPS: usize = 40;
+
+/// One step of the merged plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    /// 1-based position in the plan.
+    #[serde(default)]
+    pub id: usize,
+    pub title: String,
+    /// What to do, concretely enough to act on.
+    #[serde(default)]
+    pub detail: String,
+    /// Files the step creates or changes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Earlier steps that must be done first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<usize>,
+    /// Models whose plans contained this step.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// How the proposals disagreed about this step, if they did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disagreement: Option<String>,
+}
+
+/// A disagreement that isn't about a single step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Disagreement {
+    pub topic: String,
+    /// Each model's position, as `model: position`.
+    #[serde(default)]
+    pub positions: Vec<String>,
+}
+
+/// The synthesizer's JSON reply.
+#[derive(Debug, Deserialize)]
+struct Merged {
+    #[serde(default)]
+    summary: String,
+    steps: Vec<Step>,
+    #[serde(default)]
+    disagreements: Vec<Disagreement>,
+    #[serde(default)]
+    open_questions: Vec<String>,
+}
+
+/// Outcome of a `plan` call.
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub summary: String,
+    pub steps: Vec<Step>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disagreements: Vec<Disagreement>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub open_questions: Vec<String>,
+    pub proposed_by: Vec<String>,
+    pub synthesized_by: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+fn with_context(task: &str, context: Option<&str>) -> String {
+    match context {
+        Some(ctx) => format!("{ctx}\n\n<task>\n{task}\n</task>"),
+        None => format!("<task>\n{task}\n</task>"),
+    }
+}
+
+/// Prompt asking one model for its own implementation plan.
+pub fn proposal_prompt(task: &str, context: Option<&str>) -> String {
+    format!(
+        "{}\n\nWrite an implementation plan for this task as a numbered list of steps, in \
+         the order you would do them. For each step give a short title, what to change and \
+         how, and the files it touches. Include tests and migrations where needed. Note the \
+         risks and the assumptions you made.",
+        with_context(task, context)
+    )
+}
+
+/// Prompt asking the synthesizer to merge the proposals into one JSON plan.
+pub fn synthesis_prompt(
+    task: &str,
+    context: Option<&str>,
+    proposals: &[(String, String)],
+) -> String {
+    let mut prompt = with_context(task, context);
+    prompt.push_str("\n\n");
+    for (model, text) in proposals {
+        prompt.push_str(&format!(
+            "<plan model=\"{}\">\n{}\n</plan>\n",
+            escape_xml_attr(model),
+            text.trim()
+        ));
+    }
+    prompt.push_str(&format!(
+        "\nThese plans were written independently by the models named. Merge them into one \
+         ordered plan of at most {MAX_STEPS} steps: keep every step that is needed, merge steps \
+         that are the same work, and order them so each step only depends on earlier ones. \
+         For each step list the models whose plans contained it. Where the plans disagree \
+         about a step (approach, order, or whether it is needed), say so in that step's \
+         `disagreement`, naming who proposed what. Disagreements about the plan as a whole go \
+         in `disagreements`.\n\nReply with ONLY a JSON object:\n\
+         {{\"summary\": \"one paragraph\", \"steps\": [{{\"id\": 1, \"title\": \"...\", \
+         \"detail\": \"...\", \"files\": [\"...\"], \"depends_on\": [], \"models\": [\"...\"], \
+         \"disagreement\": null}}], \"disagreements\": [{{\"topic\": \"...\", \"positions\": \
+         [\"model: position\"]}}], \"open_questions\": [\"...\"]}}"
+    ));
+    prompt
+}
+
+/// The merged plan from a synthesizer reply, tolerating prose or fences around
+/// the JSON. Steps are renumbered 1..n in order; dependencies on unknown or
+/// later steps and models that proposed nothing are dropped. None when the
+/// reply has no steps.
+pub fn parse(text: &str, proposed_by: &[String], synthesized_by: &str) -> Option<Plan> {
+    let merged: Merged =
+        crate::json_repair::parse(text).and_then(|(doc, _)| serde_json::from_value(doc).ok())?;
+    let mut steps: Vec<Step> = merged
+        .steps
+        .into_iter()
+        .filter(|s| !s.title.trim().is_empty())
+        .take(MAX_STEPS)
+        .collect();
+    if steps.is_empty() {
+        return None;
+    }
+    // Old id → new id, so dependencies survive renumbering.
+    let renumber: Vec<(usize, usize)> = steps
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id, i + 1))
+        .collect();
+    for (i, step) in steps.iter_mut().enumerate() {
+        step.id = i + 1;
+        step.title = step.title.trim().to_string();
+        step.depends_on = step
+            .depends_on
+            .iter()
+            .filter_map(|old| renumber.iter().find(|(o, _)| o == old).map(|(_, new)| *new))
+            .filter(|new| *new < step.id)
+            .collect();
+        step.depends_on.dedup();
+        step.models.retain(|m| proposed_by.contains(m));
+        step.disagreement = step
+            .disagreement
+            .take()
+            .map(|d| d.trim().to_string())
+            .filter(|d| {
+                !d.is_empty() && !d.eq_ignore_ascii_case("null") && !d.eq_ignore_ascii_case("none")
+            });
+    }
+    let disagreements = merged
+        .disagreements
+        .into_iter()
+        .filter(|d| !d.topic.trim().is_empty())
+        .collect();
+    let open_questions = merged
+        .open_questions
+        .into_iter()
+        .filter(|q| !q.trim().is_empty())
+        .collect();
+    Some(Plan {
+        summary: merged.summary.trim().to_string(),
+        steps,
+        disagreements,
+        open_questions,
+        proposed_by: proposed_by.to_vec(),
+        synthesized_by: synthesized_by.to_string(),
+        notes: Vec::new(),
+    })
+}
+
+impl Plan {
+    /// Steps flagged with a disagreement.
+    pub fn disputed(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| s.disagreement.is_some())
+            .count()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Plan\n\n");
+        if !self.summary.is_empty() {
+            out.push_str(&format!("{}\n\n", self.summary));
+        }
+        out.push_str(&format!(
+            "_{} step(s), {} disputed. Proposed by {}; synthesized by {}._\n\n",
+            self.steps.len(),
+            self.disputed(),
+            self.proposed_by.join(", "),
+            self.synthesized_by
+        ));
+        for step in &self.steps {
+            out.push_str(&format!("{}. **{}**", step.id, step.title));
+            if step.disagreement.is_some() {
+                out.push_str(" ⚠ disputed");
+            }
+            out.push('\n');
+            if !step.detail.trim().is_empty() {
+                out.push_str(&format!("   {}\n", step.detail.trim()));
+            }
+            if !step.files.is_empty() {
+                out.push_str(&format!("   - Files: {}\n", step.files.join(", ")));
+            }
+            if !step.depends_on.is_empty() {
+                let deps: Vec<String> = step.depends_on.iter().map(|d| d.to_string()).collect();
+                out.push_str(&format!("   - After: {}\n", deps.join(", ")));
+            }
+            if !step.models.is_empty() {
+                out.push_str(&format!("   - Proposed by: {}\n", step.models.join(", ")));
+            }
+            if let Some(ref d) = step.disagreement {
+                out.push_str(&format!("   - Disagreement: {d}\n"));
+            }
+        }
+        if !self.disagreements.is_empty() {
+            out.push_str("\n### Disagreements\n");
+            for d in &self.disagreements {
+                out.push_str(&format!("- **{}**\n", d.topic.trim()));
+                for p in &d.positions {
+                    out.push_str(&format!("  - {p}\n"));
+                }
+            }
+        }
+        if !self.open_questions.is_empty() {
+            out.push_str("\n### Open questions\n");
+            for q in &self.open_questions {
+                out.push_str(&format!("- {q}\n"));
+            }
+        }
+        if !self.notes.is_empty() {
+            out.push_str("\n### Notes\n");
+            for note in &self.notes {
+                out.push_str(&format!("- {note}\n"));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_renumbers_steps_and_keeps_dependencies() {
+        let proposed_by = vec!["grok".to_string(), "gemini".to_string()];
+        let reply = "Here is the merged plan:\n```json\n{\"summary\": \"Add the cache.\", \"steps\": [\
+            {\"id\": 3, \"title\": \"Add the cache module\", \"files\": [\"src/cache.rs\"], \"models\": [\"grok\", \"gemini\", \"gpt\"]},\
+            {\"id\": 4, \"title\": \"  \"},\
+            {\"id\": 5, \"title\": \"Wire it into the server\", \"depends_on\": [3, 9, 6], \"models\": [\"gemini\"], \"disagreement\": \"grok wanted a feature flag\"},\
+            {\"id\": 6, \"title\": \"Test eviction\", \"depends_on\": [5, 3], \"disagreement\": \"none\"}],\
+            \"disagreements\": [{\"topic\": \"LRU or TTL\", \"positions\": [\"grok: LRU\", \"gemini: TTL\"]}]}\n```";
+        let plan = parse(reply, &proposed_by, "grok").unwrap();
+        let steps = &plan.steps;
+        assert_eq!(plan.summary, "Add the cache.");
+        let ids: Vec<usize> = steps.iter().map(|s| s.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(steps[0].models, ["grok", "gemini"]);
+        // 9 doesn't exist and 6 comes later.
+        assert_eq!(steps[1].depends_on, [1]);
+        assert_eq!(
+            steps[1].disagreement.as_deref(),
+            Some("grok wanted a feature flag")
+        );
+        assert_eq!(steps[2].depends_on, [2, 1]);
+        assert_eq!(steps[2].disagreement, None);
+        assert_eq!(
+            plan.disagreements[0].positions,
+            ["grok: LRU", "gemini: TTL"]
+        );
+        assert!(plan.open_questions.is_empty());
+
+        assert!(parse("I could not merge these.", &proposed_by, "grok").is_none());
+        assert!(parse("{\"steps\": []}", &proposed_by, "grok").is_none());
+    }
+
+    #[test]
+    fn markdown_flags_disputed_steps() {
+        let plan = Plan {
+            summary: "Add the cache.".into(),
+            steps: vec![Step {
+                id: 1,
+                title: "Wire it in".into(),
+                detail: "Call it from the handler.".into(),
+                files: vec!["src/server.rs".into()],
+                depends_on: vec![],
+                models: vec!["gemini".into()],
+                disagreement: Some("grok wanted a feature flag".into()),
+            }],
+            disagreements: vec![],
+            open_questions: vec!["Which TTL?".into()],
+            proposed_by: vec!["grok".into(), "gemini".into()],
+            synthesized_by: "grok".into(),
+            notes: vec![],
+        };
+        let md = plan.to_markdown();
+        assert!(
+            md.contains("_1 step(s), 1 disputed. Proposed by grok, gemini; synthesized by grok._")
+        );
+        assert!(md.contains("1. **Wire it in** ⚠ disputed\n   Call it from the handler.\n   - Files: src/server.rs\n"));
+        assert!(md.contains("   - Disagreement: grok wanted a feature flag\n"));
+        assert!(md.contains("### Open questions\n- Which TTL?\n"));
+    }
+}
@@ -0,0 +1,539 @@
+//! Review policy: mandatory review rules for parts of a repository, declared in
+//! `.squall/policy.toml` next to the code they guard:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "auth"
+//! paths = ["src/auth/"]
+//! min_models = 2
+//! precision = "high"
+//! lens = "security"
+//! ```
+//!
+//! A rule applies to a review when one of its gitignore-style `paths` matches a
+//! file under review (`file_paths` or the diff). The review is then augmented
+//! to meet it: `models` it names and enough models of the required precision
+//! tier are added, and the qualifying models get the lens prompt. Compliance is
+//! judged on the results — only models that answered in full count — and
+//! reported in `ReviewResponse::policy`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::tools::enums::Lens;
+use crate::tools::review::{ModelStatus, ReviewModelResult, ReviewRequest};
+
+/// Policy file, relative to the reviewed repository's root.
+pub const POLICY_FILE: &str = ".squall/policy.toml";
+
+/// Most rules one policy file can hold.
+pub const MAX_RULES: usize = 50;
+
+const PRECISION_TIERS: [&str; 3] = ["low", "medium", "high"];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+/// One `[[rule]]` of the policy file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    /// Gitignore-style patterns, relative to the repository root.
+    pub paths: Vec<String>,
+    /// Models that must review matching changes.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Models of the required precision that must answer. Default: 1.
+    pub min_models: Option<usize>,
+    /// Lowest `precision_tier` that counts: "low", "medium" or "high".
+    pub precision: Option<String>,
+    /// Review focus given to the qualifying models.
+    pub lens: Option<Lens>,
+}
+
+impl Rule {
+    fn min_models(&self) -> usize {
+        self.min_models.unwrap_or(1)
+    }
+
+    /// Whether a model of precision `tier` counts toward `min_models`.
+    fn accepts(&self, tier: Option<&str>) -> bool {
+        match (self.precision.as_deref(), tier) {
+            (None, _) => true,
+            (Some(min), Some(tier)) => precision_rank(tier) >= precision_rank(min),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// What the rule asks for, e.g. "2 high-precision models, security lens".
+    fn requirement(&self) -> String {
+        let n = self.min_models();
+        let mut text = format!(
+            "{n} {}model{}",
+            self.precision
+                .as_deref()
+                .map(|p| format!("{p}-precision "))
+                .unwrap_or_default(),
+            if n == 1 { "" } else { "s" }
+        );
+        if let Some(lens) = self.lens {
+            text.push_str(&format!(", {} lens", lens.as_str()));
+        }
+        if !self.models.is_empty() {
+            text.push_str(&format!(", including {}", self.models.join(", ")));
+        }
+        text
+    }
+}
+
+fn precision_rank(tier: &str) -> usize {
+    PRECISION_TIERS.iter().position(|t| *t == tier).unwrap_or(1)
+}
+
+/// A parsed policy file.
+#[derive(Debug)]
+pub struct Policy {
+    rules: Vec<(Rule, Gitignore)>,
+}
+
+impl Policy {
+    /// Parse and check `raw`; `root` anchors the rules' patterns.
+    pub fn parse(root: &Path, raw: &str) -> Result<Self, String> {
+        let file: PolicyFile = toml::from_str(raw).map_err(|e| e.to_string())?;
+        if file.rules.len() > MAX_RULES {
+            return Err(format!("at most {MAX_RULES} rules"));
+        }
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for (i, rule) in file.rules.into_iter().enumerate() {
+            let name = rule.name.trim();
+            if name.is_empty() {
+                return Err(format!("rule[{i}] has no name"));
+            }
+            if rules.iter().any(|(r, _): &(Rule, _)| r.name == name) {
+                return Err(format!("rule '{name}' is defined twice"));
+            }
+            if rule.paths.is_empty() {
+                return Err(format!("rule '{name}' has no paths"));
+            }
+            if rule.min_models == Some(0) || rule.min_models() > crate::review::MAX_MODELS {
+                return Err(format!(
+                    "rule '{name}': min_models must be 1-{}",
+                    crate::review::MAX_MODELS
+                ));
+            }
+            if let Some(ref precision) = rule.precision
+                && !PRECISION_TIERS.contains(&precision.as_str())
+            {
+                return Err(format!(
+                    "rule '{name}': precision must be one of {}",
+                    PRECISION_TIERS.join(", ")
+                ));
+            }
+            let mut builder = GitignoreBuilder::new(root);
+            for pattern in &rule.paths {
+                builder
+                    .add_line(None, pattern)
+                    .map_err(|e| format!("rule '{name}': {e}"))?;
+            }
+            let matcher = builder.build().map_err(|e| format!("rule '{name}': {e}"))?;
+            let name = name.to_string();
+            rules.push((Rule { name, ..rule }, matcher));
+        }
+        Ok(Self { rules })
+    }
+
+    /// The rules of `root`'s policy file. None when it has none.
+    pub async fn load(root: &Path) -> Result<Option<Self>, String> {
+        let raw = match tokio::fs::read_to_string(root.join(POLICY_FILE)).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("cannot read {POLICY_FILE}: {e}")),
+        };
+        let policy = Self::parse(root, &raw).map_err(|e| format!("invalid {POLICY_FILE}: {e}"))?;
+        Ok((!policy.rules.is_empty()).then_some(policy))
+    }
+
+    /// What `req` needs to meet every rule matching `changed`. Models start
+    /// from `req.models` (or `default_models`); `available` lists the registry's
+    /// models with their precision tier, sorted by name, to pick extras from.
+    pub fn apply(
+        &self,
+        req: &ReviewRequest,
+        changed: &[String],
+        default_models: &[String],
+        available: &[(String, String)],
+    ) -> PolicyPlan {
+        let tier = |model: &str| {
+            available
+                .iter()
+                .find(|(name, _)| name == crate::consistency::base(model))
+                .map(|(_, tier)| tier.as_str())
+        };
+        let mut models = req
+            .models
+            .clone()
+            .unwrap_or_else(|| default_models.to_vec());
+        let has = |models: &[String], model: &str| {
+            models.iter().any(|m| crate::consistency::base(m) == model)
+        };
+
+        let mut planned = Vec::new();
+        for (rule, matcher) in &self.rules {
+            let files: Vec<String> = changed
+                .iter()
+                .filter(|path| {
+                    // Rooted and `..` paths from the diff match no rule.
+                    crate::context::tree_relative_path(path).is_some_and(|path| {
+                        matcher.matched_path_or_any_parents(path, false).is_ignore()
+                    })
+                })
+                .cloned()
+                .collect();
+            if files.is_empty() {
+                continue;
+            }
+            let mut added = Vec::new();
+            for model in &rule.models {
+                if !has(&models, model) {
+                    models.push(model.clone());
+                    added.push(model.clone());
+                }
+            }
+            let qualifying =
+                |models: &[String]| models.iter().filter(|m| rule.accepts(tier(m))).count();
+            while qualifying(&models) < rule.min_models() {
+                let Some((name, _)) = available
+                    .iter()
+                    .find(|(name, tier)| rule.accepts(Some(tier)) && !has(&models, name))
+                else {
+                    break;
+                };
+                models.push(name.clone());
+                added.push(name.clone());
+            }
+            planned.push(PlannedRule {
+                rule: rule.clone(),
+                files,
+                added,
+            });
+        }
+
+        // The lens leads whatever prompt the model would have had.
+        let mut system_prompts: HashMap<String, String> = HashMap::new();
+        for p in &planned {
+            let Some(lens) = p.rule.lens else {
+                continue;
+            };
+            for model in models.iter().filter(|m| p.rule.accepts(tier(m))) {
+                let model = crate::consistency::base(model);
+                let prompt = system_prompts
+                    .get(model)
+                    .or(req
+                        .per_model_system_prompts
+                        .as_ref()
+                        .and_then(|prompts| prompts.get(model)))
+                    .or(req.system_prompt.as_ref())
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                if !prompt.contains(lens.system_prompt()) {
+                    let prompt = format!("{}\n\n{prompt}", lens.system_prompt());
+                    system_prompts.insert(model.to_string(), prompt.trim_end().to_string());
+                }
+            }
+        }
+
+        PolicyPlan {
+            models: planned
+                .iter()
+                .any(|p| !p.added.is_empty())
+                .then_some(models),
+            system_prompts,
+            rules: planned,
+            tiers: available.iter().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PlannedRule {
+    rule: Rule,
+    files: Vec<String>,
+    added: Vec<String>,
+}
+
+/// The rules a review fell under: what the request needs to meet them, and,
+/// once the executor has the results (`ReviewExecutor::with_policy`), whether
+/// it did.
+#[derive(Debug, Clone)]
+pub struct PolicyPlan {
+    /// The request's models with the ones the rules added; None if none were.
+    pub models: Option<Vec<String>>,
+    /// Lens-led `per_model_system_prompts` entries, replacing the request's.
+    pub system_prompts: HashMap<String, String>,
+    rules: Vec<PlannedRule>,
+    tiers: HashMap<String, String>,
+}
+
+impl PolicyPlan {
+    /// Apply the plan to a request's `models` and `per_model_system_prompts`.
+    pub fn augment(
+        &mut self,
+        models: &mut Option<Vec<String>>,
+        per_model_system_prompts: &mut Option<HashMap<String, String>>,
+    ) {
+        if let Some(added) = self.models.take() {
+            *models = Some(added);
+        }
+        if !self.system_prompts.is_empty() {
+            per_model_system_prompts
+                .get_or_insert_with(HashMap::new)
+                .extend(std::mem::take(&mut self.system_prompts));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Each rule's compliance. A model counts once, however many samples it
+    /// sent, and only if it answered in full.
+    pub fn check(&self, results: &[ReviewModelResult]) -> Vec<PolicyCheck> {
+        let mut answered: Vec<&str> = results
+            .iter()
+            .filter(|r| r.status == ModelStatus::Success && !r.partial)
+            .map(|r| crate::consistency::base(&r.model))
+            .collect();
+        answered.sort_unstable();
+        answered.dedup();
+
+        self.rules
+            .iter()
+            .map(|p| {
+                let satisfied_by: Vec<String> = answered
+                    .iter()
+                    .filter(|m| p.rule.accepts(self.tiers.get(**m).map(String::as_str)))
+                    .map(|m| m.to_string())
+                    .collect();
+                let compliant = satisfied_by.len() >= p.rule.min_models()
+                    && p.rule.models.iter().all(|m| answered.contains(&m.as_str()));
+                PolicyCheck {
+                    rule: p.rule.name.clone(),
+                    requirement: p.rule.requirement(),
+                    files: p.files.clone(),
+                    satisfied_by,
+                    added: p.added.clone(),
+                    compliant,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A policy rule's outcome, as reported in `ReviewResponse::policy`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyCheck {
+    pub rule: String,
+    pub requirement: String,
+    /// Reviewed files the rule matched.
+    pub files: Vec<String>,
+    /// Qualifying models that answered in full.
+    pub satisfied_by: Vec<String>,
+    /// Models the policy added to the request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    pub compliant: bool,
+}
+
+/// "Policy: 1/2 rules met (auth not met)", or None when no rule applied.
+pub fn summary_line(checks: &[PolicyCheck]) -> Option<String> {
+    if checks.is_empty() {
+        return None;
+    }
+    let unmet: Vec<&str> = checks
+        .iter()
+        .filter(|c| !c.compliant)
+        .map(|c| c.rule.as_str())
+        .collect();
+    let mut line = format!(
+        "Policy: {}/{} rules met",
+        checks.len() - unmet.len(),
+        checks.len()
+    );
+    if !unmet.is_empty() {
+        line.push_str(&format!(" ({} not met)", unmet.join(", ")));
+    }
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+[[rule]]
+name = "auth"
+paths = ["src/auth/"]
+min_models = 2
+precision = "high"
+lens = "security"
+
+[[rule]]
+name = "migrations"
+paths = ["*.sql"]
+models = ["kimi"]
+"#;
+
+    fn available() -> Vec<(String, String)> {
+        [
+            ("deepseek", "medium"),
+            ("grok", "high"),
+            ("kimi", "low"),
+            ("opus", "high"),
+        ]
+        .iter()
+        .map(|(m, t)| (m.to_string(), t.to_string()))
+        .collect()
+    }
+
+    fn request(models: &[&str]) -> ReviewRequest {
+        let mut options = crate::review::ReviewOptions::new("Review");
+        options.models = models.iter().map(|m| m.to_string()).collect();
+        options.system_prompt = Some("Be terse.".to_string());
+        options.into_request()
+    }
+
+    fn result(model: &str, status: ModelStatus) -> ReviewModelResult {
+        ReviewModelResult {
+            model: model.to_string(),
+            provider: "test".to_string(),
+            status,
+            response: Some("No findings.".to_string()),
+            error: None,
+            reason: None,
+            latency_ms: 100,
+            partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn matching_rules_add_models_and_lens() {
+        let policy = Policy::parse(Path::new("/repo"), POLICY).unwrap();
+        let mut req = request(&["grok", "deepseek"]);
+        let changed = vec!["./src/auth/login.rs".to_string(), "README.md".to_string()];
+        let mut plan = policy.apply(&req, &changed, &[], &available());
+        plan.augment(&mut req.models, &mut req.per_model_system_prompts);
+
+        // Only the auth rule matched; opus is the second high-precision model.
+        assert_eq!(req.models.as_deref().unwrap(), ["grok", "deepseek", "opus"]);
+        let prompts = req.per_model_system_prompts.as_ref().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts["grok"].starts_with(Lens::Security.system_prompt()));
+        assert!(prompts["opus"].ends_with("Be terse."));
+
+        let checks = plan.check(&[
+            result("grok", ModelStatus::Success),
+            result("deepseek", ModelStatus::Success),
+            result("opus", ModelStatus::Error),
+        ]);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].files, ["./src/auth/login.rs"]);
+        assert_eq!(checks[0].added, ["opus"]);
+        assert_eq!(checks[0].satisfied_by, ["grok"]);
+        assert!(!checks[0].compliant);
+        assert_eq!(
+            summary_line(&checks).unwrap(),
+            "Policy: 0/1 rules met (auth not met)"
+        );
+    }
+
+    #[test]
+    fn rooted_and_escaping_paths_match_no_rule() {
+        let policy = Policy::parse(Path::new("/repo"), POLICY).unwrap();
+        let req = request(&["grok"]);
+        let changed = vec![
+            "/src/auth/login.rs".to_string(),
+            "src/auth/../../x.sql".to_string(),
+        ];
+        let plan = policy.apply(&req, &changed, &[], &available());
+        assert!(plan.check(&[]).is_empty());
+    }
+
+    #[test]
+    fn required_models_must_answer() {
+        let policy = Policy::parse(Path::new("/repo"), POLICY).unwrap();
+        let mut req = request(&["grok"]);
+        let changed = vec!["db/001_init.sql".to_string()];
+        let mut plan = policy.apply(&req, &changed, &[], &available());
+        plan.augment(&mut req.models, &mut req.per_model_system_prompts);
+        assert_eq!(req.models.as_deref().unwrap(), ["grok", "kimi"]);
+        assert!(req.per_model_system_prompts.is_none());
+
+        let met = plan.check(&[
+            result("grok", ModelStatus::Success),
+            result("kimi#1", ModelStatus::Success),
+            result("kimi#2", ModelStatus::Success),
+        ]);
+        assert!(met[0].compliant);
+        assert_eq!(met[0].requirement, "1 model, including kimi");
+        assert!(!plan.check(&[result("grok", ModelStatus::Success)])[0].compliant);
+    }
+
+    #[test]
+    fn parse_rejects_bad_rules() {
+        let root = Path::new("/repo");
+        assert!(
+            Policy::parse(root, "[[rule]]\nname = \"a\"\npaths = []\n")
+                .unwrap_err()
+                .contains("no paths")
+        );
+        assert!(
+            Policy::parse(
+                root,
+                "[[rule]]\nname = \"a\"\npaths = [\"x\"]\nprecision = \"max\"\n"
+            )
+            .unwrap_err()
+            .contains("precision")
+        );
+        assert!(
+            Policy::parse(
+                root,
+                "[[rule]]\nname = \"a\"\npaths = [\"x\"]\nmin_models = 0\n"
+            )
+            .is_err()
+        );
+        assert!(
+            Policy::parse(
+                root,
+                "[[rule]]\nname = \"a\"\npaths = [\"x\"]\nlens = \"vibes\"\n"
+            )
+            .is_err()
+        );
+        assert!(
+            Policy::parse(
+                root,
+                "[[rule]]\nname = \"a\"\npaths = [\"x\"]\n[[rule]]\nname = \"a\"\npaths = [\"y\"]\n"
+            )
+            .unwrap_err()
+            .contains("twice")
+        );
+    }
+}
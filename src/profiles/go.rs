@@ -0,0 +1,16 @@
+use super::Profile;
+
+pub const PROFILE: Profile = Profile {
+    language: "Go",
+    extensions: &["go"],
+    guidance: "\
+- Errors ignored (`_ =`), shadowed by `:=` in an inner scope, or wrapped without `%w`.
+- Goroutine leaks: goroutines blocked forever on channels, missing `context` cancellation, \
+`time.After` in loops.
+- Data races: maps and slices shared across goroutines without a lock, loop variables \
+captured by closures (before Go 1.22).
+- `defer` inside loops, `defer resp.Body.Close()` before the error check, unclosed bodies.
+- nil pointers from unchecked type assertions and nil interfaces holding typed nil values.
+- Slices aliasing a shared backing array after `append`.
+- Assume `go vet` and staticcheck run; don't repeat what they would flag.",
+};
@@ -0,0 +1,96 @@
+//! Language review profiles: idioms to check, common footguns, and what the
+//! usual linters already catch, per language. The predominant language of the
+//! files under review (`file_paths` plus the files a `diff` touches) selects a
+//! profile, which is merged into every model's system prompt.
+//!
+//! Each profile lives in its own file; add a language by adding a module and
+//! listing its `PROFILE` in `PROFILES`.
+
+mod go;
+mod python;
+mod rust;
+mod typescript;
+
+use std::path::Path;
+
+/// Review guidance for one language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Display name, e.g. "Rust".
+    pub language: &'static str,
+    /// File extensions (without the dot) this profile covers.
+    pub extensions: &'static [&'static str],
+    /// Bulleted checklist merged into system prompts.
+    pub guidance: &'static str,
+}
+
+/// Every known profile.
+pub const PROFILES: &[Profile] = &[
+    rust::PROFILE,
+    python::PROFILE,
+    typescript::PROFILE,
+    go::PROFILE,
+];
+
+/// The profile for a file path, by extension.
+pub fn for_path(path: &str) -> Option<&'static Profile> {
+    let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    PROFILES
+        .iter()
+        .find(|p| p.extensions.contains(&ext.as_str()))
+}
+
+/// The profile covering most of `paths`. None when no path has a profile or
+/// two languages tie for most files.
+pub fn detect<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<&'static Profile> {
+    let mut counts = [0usize; PROFILES.len()];
+    for path in paths {
+        if let Some(i) = for_path(path).and_then(|p| PROFILES.iter().position(|q| q == p)) {
+            counts[i] += 1;
+        }
+    }
+    let top = *counts.iter().max()?;
+    let mut leaders = counts.iter().enumerate().filter(|(_, n)| **n == top);
+    match (leaders.next(), leaders.next()) {
+        (Some((i, _)), None) if top > 0 => Some(&PROFILES[i]),
+        _ => None,
+    }
+}
+
+/// `system_prompt` with `profile`'s guidance appended (or the guidance alone).
+pub fn merge(system_prompt: Option<&str>, profile: &Profile) -> String {
+    let section = format!(
+        "## {} review checklist\n\nThe code under review is mostly {}. Also check for:\n{}",
+        profile.language, profile.language, profile.guidance
+    );
+    match system_prompt.map(str::trim_end).filter(|s| !s.is_empty()) {
+        Some(prompt) => format!("{prompt}\n\n{section}"),
+        None => section,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_predominant_language() {
+        let paths = ["src/lib.rs", "src/main.rs", "scripts/gen.py", "README.md"];
+        assert_eq!(detect(paths).unwrap().language, "Rust");
+        assert_eq!(
+            detect(["web/App.TSX", "web/util.js"]).unwrap().language,
+            "TypeScript/JavaScript"
+        );
+        assert!(detect(["a.rs", "b.go"]).is_none(), "tie");
+        assert!(detect(["notes.md", "Makefile"]).is_none());
+    }
+
+    #[test]
+    fn merge_appends_to_the_system_prompt() {
+        let go = for_path("cmd/main.go").unwrap();
+        let merged = merge(Some("You are a security auditor.\n"), go);
+        assert!(merged.starts_with("You are a security auditor.\n\n## Go review checklist"));
+        assert!(merged.ends_with(go.guidance));
+        assert!(merge(None, go).starts_with("## Go review checklist"));
+    }
+}
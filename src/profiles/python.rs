@@ -0,0 +1,16 @@
+use super::Profile;
+
+pub const PROFILE: Profile = Profile {
+    language: "Python",
+    extensions: &["py", "pyi"],
+    guidance: "\
+- Mutable default arguments, and module-level state shared across requests or threads.
+- Bare `except:` or `except Exception` that hides bugs; exceptions lost in background tasks.
+- `async` code calling blocking I/O (`requests`, `time.sleep`, file reads) or forgetting `await`.
+- Resources opened without `with` (files, sockets, DB connections, locks).
+- Injection: string-built SQL or shell commands, `subprocess(..., shell=True)`, `eval`, \
+`pickle`/`yaml.load` on untrusted data.
+- Type hints that disagree with the code, `Optional` values used without a `None` check.
+- Late-binding closures in loops, `is` used for value comparison, float equality.
+- Assume a linter (ruff/flake8) and formatter run; don't repeat style nits.",
+};
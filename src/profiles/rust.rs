@@ -0,0 +1,17 @@
+use super::Profile;
+
+pub const PROFILE: Profile = Profile {
+    language: "Rust",
+    extensions: &["rs"],
+    guidance: "\
+- Panics on reachable paths: `unwrap`/`expect` on fallible input, slice indexing, integer \
+overflow in release builds, `str` slicing off a char boundary.
+- `unsafe` blocks: the invariant each one relies on, and whether safe callers can break it.
+- Async: locks (`std::sync::Mutex`, `RefCell` borrows) held across `.await`, blocking I/O \
+or heavy CPU on the runtime, futures dropped mid-way that leave state half-updated.
+- Errors swallowed by `let _ =`, `.ok()`, or `unwrap_or_default()` where the caller needs \
+to know.
+- Needless `clone()`/`to_string()` on hot paths, and `Arc<Mutex<_>>` where ownership would do.
+- Public API: missing `#[must_use]`, leaking internal types, breaking changes to `pub` items.
+- Assume `cargo clippy -D warnings` runs; don't repeat what it would flag.",
+};
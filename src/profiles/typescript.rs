@@ -0,0 +1,17 @@
+use super::Profile;
+
+pub const PROFILE: Profile = Profile {
+    language: "TypeScript/JavaScript",
+    extensions: &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"],
+    guidance: "\
+- Floating promises: async calls without `await`, `.catch`, or `void`; `forEach` with an \
+async callback; unhandled rejections.
+- Type escapes: `any`, `as` casts and non-null `!` assertions hiding real `undefined`/`null` \
+cases; unchecked `JSON.parse` results trusted as typed.
+- `==` vs `===`, truthiness checks that treat `0` or `\"\"` as missing, `||` where `??` was meant.
+- React: missing or stale hook dependencies, state mutated in place, effects without cleanup, \
+keys derived from array indexes.
+- Security: `innerHTML`/`dangerouslySetInnerHTML` with user data, prototype pollution via \
+object merges, secrets bundled into client code.
+- Assume eslint and `tsc --strict` run; don't repeat what they would flag.",
+};
@@ -0,0 +1,376 @@
+//! Follow-ups for numbered questions a model skipped.
+//!
+//! When the review prompt asks explicit numbered questions ("1. Is the lock
+//! held across the await?"), each complete response is checked for an answer to
+//! every one: a line that starts with the question's number (`2.`, `**2)**`,
+//! `### Q2:`), or most of the question's key words. Models that skipped some are
+//! asked just those, with their first answer for reference, if the review's
+//! cutoff leaves time. The follow-up answer is appended to the response under a
+//! marked heading, and `ReviewResponse::question_coverage` says which answers
+//! came from it.
+//!
+//! The check is deliberately simple. Numbered lists inside an answer can pass
+//! for answers to later questions, so it errs toward not re-asking.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::registry::Registry;
+use crate::dispatch::{ProviderRequest, TokenUsage};
+use crate::tools::review::{ModelStatus, ReviewModelResult};
+
+/// Questions a prompt needs before coverage is checked.
+pub const MIN_QUESTIONS: usize = 2;
+
+/// Questions checked; later ones are ignored.
+pub const MAX_QUESTIONS: usize = 20;
+
+/// Least time before the cutoff worth starting a follow-up in.
+pub const MIN_FOLLOW_UP: Duration = Duration::from_secs(15);
+
+/// Heading the follow-up answer is appended under.
+pub const FOLLOW_UP_HEADING: &str = "Follow-up answers";
+
+/// Share of a question's key words an answer must mention to count without a number.
+const KEYWORD_COVERAGE: f64 = 0.7;
+
+/// Words too common to say what a question is about.
+const STOPWORDS: &[&str] = &[
+    "about", "could", "does", "doing", "every", "there", "these", "their", "those", "should",
+    "which", "while", "would", "where", "other", "being", "after", "before", "under", "whether",
+];
+
+/// A numbered question from the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub number: usize,
+    pub text: String,
+}
+
+/// How one model covered the prompt's questions, by question number.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionCoverage {
+    /// Answered in the first response.
+    pub answered: Vec<usize>,
+    /// Answered only in the follow-up.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub followed_up: Vec<usize>,
+    /// Still unanswered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unanswered: Vec<usize>,
+}
+
+/// The number a line starts with, as a list item or heading (`2.`, `2)`, `(2)`,
+/// `**2.**`, `### Q2:`, `Question 2`), and the rest of the line.
+fn numbered(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim_start_matches(|c: char| c.is_whitespace() || "#*_>(".contains(c));
+    let (named, line) = match line
+        .strip_prefix("Question ")
+        .or_else(|| line.strip_prefix("question "))
+        .or_else(|| line.strip_prefix('Q'))
+        .or_else(|| line.strip_prefix('q'))
+    {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, line),
+    };
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 3 {
+        return None;
+    }
+    let number = line[..digits].parse().ok()?;
+    let rest = &line[digits..];
+    let mut chars = rest.chars();
+    let ok = match chars.next() {
+        None => named,
+        // "1.5" is a number, not an item.
+        Some('.') => !chars.next().is_some_and(|c| c.is_ascii_digit()),
+        Some(')' | ':' | '*' | '—' | '-') => true,
+        Some(c) => named && c.is_whitespace(),
+    };
+    ok.then(|| {
+        let text = rest.trim_start_matches(|c: char| c.is_whitespace() || ".):*—-".contains(c));
+        (number, text)
+    })
+}
+
+/// The prompt's numbered questions: the first run numbered 1, 2, 3, ... whose
+/// lines ask something (contain `?`). Empty with fewer than `MIN_QUESTIONS`.
+pub fn extract(prompt: &str) -> Vec<Question> {
+    let mut questions: Vec<Question> = Vec::new();
+    for line in prompt.lines() {
+        let Some((number, text)) = numbered(line) else {
+            continue;
+        };
+        if !text.contains('?') {
+            continue;
+        }
+        if number == questions.len() + 1 {
+            questions.push(Question {
+                number,
+                text: text.trim().to_string(),
+            });
+        } else if !questions.is_empty() {
+            break;
+        }
+        if questions.len() == MAX_QUESTIONS {
+            break;
+        }
+    }
+    if questions.len() < MIN_QUESTIONS {
+        questions.clear();
+    }
+    questions
+}
+
+fn key_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.chars().count() >= 5)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Question numbers `response` leaves unanswered.
+pub fn unanswered(questions: &[Question], response: &str) -> Vec<usize> {
+    let numbers: HashSet<usize> = response
+        .lines()
+        .filter_map(numbered)
+        .map(|(n, _)| n)
+        .collect();
+    let words: HashSet<String> = key_words(response).into_iter().collect();
+    questions
+        .iter()
+        .filter(|q| {
+            if numbers.contains(&q.number) {
+                return false;
+            }
+            let keys = key_words(&q.text);
+            let hits = keys.iter().filter(|k| words.contains(*k)).count();
+            keys.len() < 3 || (hits as f64) < KEYWORD_COVERAGE * keys.len() as f64
+        })
+        .map(|q| q.number)
+        .collect()
+}
+
+/// Follow-up prompt: the original request, the model's first answer, and the
+/// questions it skipped.
+pub fn follow_up_prompt(original: &str, answer: &str, skipped: &[&Question]) -> String {
+    let mut prompt = format!(
+        "{original}\n\n<previous_answer>\n{}\n</previous_answer>\n\nYour answer above did not \
+         address the following question(s). Answer each one now, under its number, without \
+         repeating the rest of your answer:\n",
+        answer.trim()
+    );
+    for q in skipped {
+        prompt.push_str(&format!("{}. {}\n", q.number, q.text));
+    }
+    prompt
+}
+
+/// Check every complete response in `results` against `questions` and re-ask
+/// each model the ones it skipped, using its original request from `requests`,
+/// if at least `MIN_FOLLOW_UP` remains before `deadline`. Follow-ups run in
+/// parallel; their answers are appended to the responses. Returns each checked
+/// model's coverage and warnings for follow-ups that failed.
+pub async fn follow_up(
+    registry: &Registry,
+    questions: &[Question],
+    results: &mut [ReviewModelResult],
+    requests: &HashMap<String, ProviderRequest>,
+    deadline: Instant,
+) -> (BTreeMap<String, QuestionCoverage>, Vec<String>) {
+    let mut coverage = BTreeMap::new();
+    let mut pending = Vec::new();
+    let time_left = deadline.saturating_duration_since(Instant::now()) >= MIN_FOLLOW_UP;
+    for (i, result) in results.iter().enumerate() {
+        if result.status != ModelStatus::Success || result.partial {
+            continue;
+        }
+        let Some(ref text) = result.response else {
+            continue;
+        };
+        let missing = unanswered(questions, text);
+        let answered = questions
+            .iter()
+            .map(|q| q.number)
+            .filter(|n| !missing.contains(n))
+            .collect();
+        coverage.insert(
+            result.model.clone(),
+            QuestionCoverage {
+                answered,
+                followed_up: Vec::new(),
+                unanswered: missing.clone(),
+            },
+        );
+        if missing.is_empty() || !time_left {
+            continue;
+        }
+        if let Some(original) = requests.get(&result.model) {
+            let skipped: Vec<&Question> = questions
+                .iter()
+                .filter(|q| missing.contains(&q.number))
+                .collect();
+            let req = ProviderRequest {
+                prompt: follow_up_prompt(&original.prompt, text, &skipped).into(),
+                deadline: deadline.min(original.deadline),
+                cancellation_token: None,
+                ..original.clone()
+            };
+            pending.push((i, missing, req));
+        }
+    }
+
+    let answers =
+        futures_util::future::join_all(pending.iter().map(|(_, _, req)| registry.query(req))).await;
+    let mut warnings = Vec::new();
+    for ((i, missing, _), answer) in pending.into_iter().zip(answers) {
+        let result = &mut results[i];
+        match answer {
+            Ok(answer) if !answer.text.trim().is_empty() => {
+                let still = unanswered(
+                    &questions
+                        .iter()
+                        .filter(|q| missing.contains(&q.number))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    &answer.text,
+                );
+                let numbers: Vec<String> = missing.iter().map(|n| n.to_string()).collect();
+                if let Some(ref mut text) = result.response {
+                    text.push_str(&format!(
+                        "\n\n---\n\n### {FOLLOW_UP_HEADING} (question {})\n\n{}\n",
+                        numbers.join(", "),
+                        answer.text.trim()
+                    ));
+                }
+                result.usage = TokenUsage::sum(result.usage.iter().chain(answer.usage.iter()));
+                if let Some(c) = coverage.get_mut(&result.model) {
+                    c.followed_up = missing
+                        .iter()
+                        .copied()
+                        .filter(|n| !still.contains(n))
+                        .collect();
+                    c.unanswered = still;
+                }
+            }
+            Ok(_) => warnings.push(format!("{}: follow-up answer was empty", result.model)),
+            Err(e) => {
+                tracing::warn!("follow-up for {} failed: {e}", result.model);
+                warnings.push(format!(
+                    "{}: follow-up for skipped questions failed: {}",
+                    result.model,
+                    e.user_message()
+                ));
+            }
+        }
+    }
+    (coverage, warnings)
+}
+
+/// One-line summary for the review markdown. None when nothing was checked.
+pub fn summary_line(coverage: &BTreeMap<String, QuestionCoverage>) -> Option<String> {
+    if coverage.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = coverage
+        .iter()
+        .filter(|(_, c)| !c.followed_up.is_empty() || !c.unanswered.is_empty())
+        .map(|(model, c)| {
+            let list = |v: &[usize]| {
+                v.iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            match (c.followed_up.is_empty(), c.unanswered.is_empty()) {
+                (false, true) => format!("{model} followed up {}", list(&c.followed_up)),
+                (true, false) => format!("{model} skipped {}", list(&c.unanswered)),
+                _ => format!(
+                    "{model} followed up {}, skipped {}",
+                    list(&c.followed_up),
+                    list(&c.unanswered)
+                ),
+            }
+        })
+        .collect();
+    Some(if parts.is_empty() {
+        format!("Questions: all answered by {} model(s)", coverage.len())
+    } else {
+        format!("Questions: {}", parts.join("; "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROMPT: &str = "Review the pool changes.\n\n\
+        1. Is the lock held across the await in acquire()?\n\
+        2) Can release() double-free a connection under cancellation?\n\
+        3. Does the eviction timer leak tasks on shutdown?\n\
+        4. Check the naming.\n\n\
+        Steps we took:\n1. Ran the tests?\n";
+
+    #[test]
+    fn extracts_the_first_numbered_run_of_questions() {
+        let questions = extract(PROMPT);
+        let numbers: Vec<usize> = questions.iter().map(|q| q.number).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        assert_eq!(
+            questions[1].text,
+            "Can release() double-free a connection under cancellation?"
+        );
+        assert!(extract("1. Only one question?").is_empty());
+        assert!(extract("Version 1.5 is out. Is it faster?\n2. And?").is_empty());
+    }
+
+    #[test]
+    fn coverage_by_number_or_key_words() {
+        let questions = extract(PROMPT);
+        let response = "**1.** Yes, the guard lives across `.await`.\n\n\
+            ### Q3: Eviction\nThe timer task is aborted in Drop.";
+        assert_eq!(unanswered(&questions, response), [2]);
+
+        // No numbers, but it plainly discusses release, double-free and cancellation.
+        let prose = "release() can double-free: a cancellation between the two awaits \
+            returns the connection twice. The lock in acquire() is held across the await. \
+            The eviction timer leaks tasks on shutdown.";
+        assert!(unanswered(&questions, prose).is_empty());
+        assert_eq!(unanswered(&questions, "Looks fine."), [1, 2, 3]);
+    }
+
+    #[test]
+    fn follow_up_prompt_and_summary() {
+        let questions = extract(PROMPT);
+        let prompt = follow_up_prompt("ORIGINAL", "first answer", &[&questions[1]]);
+        assert!(
+            prompt.starts_with("ORIGINAL\n\n<previous_answer>\nfirst answer\n</previous_answer>")
+        );
+        assert!(
+            prompt.ends_with("\n2. Can release() double-free a connection under cancellation?\n")
+        );
+
+        let mut coverage = BTreeMap::new();
+        coverage.insert(
+            "grok".to_string(),
+            QuestionCoverage {
+                answered: vec![1, 3],
+                followed_up: vec![2],
+                unanswered: vec![],
+            },
+        );
+        assert_eq!(
+            summary_line(&coverage).as_deref(),
+            Some("Questions: grok followed up 2")
+        );
+        coverage.get_mut("grok").unwrap().followed_up.clear();
+        coverage.get_mut("grok").unwrap().answered = vec![1, 2, 3];
+        assert_eq!(
+            summary_line(&coverage).as_deref(),
+            Some("Questions: all answered by 1 model(s)")
+        );
+    }
+}
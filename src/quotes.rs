@@ -0,0 +1,257 @@
+//! Verbatim quote validation: checks that code a model quotes in its response
+//! actually appears in the files and diff it was sent. A quote found nowhere in
+//! the prompt is a fabrication — a direct, per-model measure of hallucination.
+//!
+//! Quotes are inline code spans long enough to be a statement rather than an
+//! identifier, and fenced code blocks. Suggested fixes are not quotes: blocks
+//! introduced by fix/suggestion wording are skipped, and in `diff` blocks only the
+//! removed and context lines are checked. Matching ignores whitespace, XML
+//! escaping, hashline prefixes, and diff markers in the supplied context.
+
+/// Shortest inline code span treated as a quote. Shorter spans are identifiers.
+const MIN_INLINE_QUOTE_CHARS: usize = 20;
+
+/// Shortest fenced-block line worth checking (skips `}`, `else {`, blank-ish lines).
+const MIN_BLOCK_LINE_CHARS: usize = 12;
+
+/// Quotes checked per response; the rest are ignored.
+const MAX_QUOTES: usize = 50;
+
+/// Longest quote text kept in a flag.
+const MAX_FLAG_QUOTE_CHARS: usize = 120;
+
+/// Wording in the lines before a fenced block that marks it as new code, not a quote.
+const SUGGESTION_MARKERS: &[&str] = &[
+    "fix",
+    "suggest",
+    "instead",
+    "should be",
+    "should look",
+    "replace",
+    "change to",
+    "change it",
+    "corrected",
+    "recommend",
+    "could be",
+    "consider",
+    "e.g.",
+    "example",
+    "proposed",
+    "rewrite",
+    "refactor",
+];
+
+/// Outcome of checking one response.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct QuoteCheck {
+    /// Quotes looked up in the context.
+    pub checked: usize,
+    /// Quotes not found, truncated for display.
+    pub fabricated: Vec<String>,
+}
+
+/// Supplied context normalized once for repeated lookups.
+pub struct QuoteContext {
+    text: String,
+}
+
+impl QuoteContext {
+    pub fn new(prompt: &str) -> Self {
+        let mut text = String::with_capacity(prompt.len() + prompt.len() / 4);
+        for line in prompt.lines() {
+            let line = strip_hashline_prefix(line);
+            push_normalized(&mut text, &unescape_xml(line));
+            // Diff lines: also index the code without its +/- marker.
+            if let Some(code) = line
+                .strip_prefix('+')
+                .or_else(|| line.strip_prefix('-'))
+                .filter(|_| !line.starts_with("+++") && !line.starts_with("---"))
+            {
+                push_normalized(&mut text, &unescape_xml(code));
+            }
+        }
+        Self { text }
+    }
+
+    fn contains(&self, quote: &str) -> bool {
+        let quote = normalize(quote);
+        quote.is_empty() || self.text.contains(&quote)
+    }
+
+    /// Check every quote in `response` against this context.
+    pub fn check(&self, response: &str) -> QuoteCheck {
+        let mut result = QuoteCheck::default();
+        for quote in extract_quotes(response).into_iter().take(MAX_QUOTES) {
+            result.checked += 1;
+            let missing: Vec<&str> = quote
+                .lines
+                .iter()
+                .copied()
+                .filter(|l| !self.contains(l))
+                .collect();
+            // A block survives a line or two being edited; an inline span must match.
+            if missing.len() * 2 > quote.lines.len() {
+                result.fabricated.push(truncate(missing[0].trim()));
+            }
+        }
+        result
+    }
+}
+
+/// A quote: one inline span, or the checkable lines of one fenced block.
+struct Quote<'a> {
+    lines: Vec<&'a str>,
+}
+
+fn extract_quotes(response: &str) -> Vec<Quote<'_>> {
+    let mut quotes = Vec::new();
+    let mut lines = response.lines().enumerate();
+    let all: Vec<&str> = response.lines().collect();
+    while let Some((i, line)) = lines.next() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            let is_diff = matches!(info.trim(), "diff" | "patch");
+            let suggestion = all[i.saturating_sub(2)..i]
+                .iter()
+                .any(|l| is_suggestion_intro(l));
+            let mut block = Vec::new();
+            for (_, body) in lines.by_ref() {
+                if body.trim_start().starts_with("```") {
+                    break;
+                }
+                let code = if is_diff {
+                    match body.chars().next() {
+                        Some('+') => continue,
+                        Some('-' | ' ') => &body[1..],
+                        _ => body,
+                    }
+                } else {
+                    body
+                };
+                if code.trim().len() >= MIN_BLOCK_LINE_CHARS {
+                    block.push(code);
+                }
+            }
+            if !suggestion && !block.is_empty() {
+                quotes.push(Quote { lines: block });
+            }
+            continue;
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find('`') {
+            let after = &rest[start + 1..];
+            let Some(end) = after.find('`') else { break };
+            let span = after[..end].trim();
+            if span.len() >= MIN_INLINE_QUOTE_CHARS && looks_like_code(span) {
+                quotes.push(Quote { lines: vec![span] });
+            }
+            rest = &after[end + 1..];
+        }
+    }
+    quotes
+}
+
+fn is_suggestion_intro(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    SUGGESTION_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Statements have spaces or operators; identifiers and paths don't.
+fn looks_like_code(span: &str) -> bool {
+    span.contains(char::is_whitespace) || span.contains(['(', '=', ';', '{'])
+}
+
+/// Strip a `line_num:hash|` hashline prefix.
+fn strip_hashline_prefix(line: &str) -> &str {
+    let Some((head, rest)) = line.split_once('|') else {
+        return line;
+    };
+    match head.split_once(':') {
+        Some((num, hash))
+            if !num.is_empty()
+                && num.bytes().all(|b| b.is_ascii_digit())
+                && hash.len() == 2
+                && hash.bytes().all(|b| b.is_ascii_hexdigit()) =>
+        {
+            rest
+        }
+        _ => line,
+    }
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Append a normalized line, newline-separated so quotes can't span lines.
+fn push_normalized(text: &mut String, line: &str) {
+    text.push_str(&normalize(line));
+    text.push('\n');
+}
+
+fn truncate(quote: &str) -> String {
+    match quote.char_indices().nth(MAX_FLAG_QUOTE_CHARS) {
+        Some((i, _)) => format!("{}…", &quote[..i]),
+        None => quote.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROMPT: &str = "Review this.\n\
+        <file path=\"src/lib.rs\">\n\
+        fn load(path: &amp;Path) -&gt; Result&lt;String&gt; {\n    \
+            let raw = std::fs::read_to_string(path)?;\n    \
+            Ok(raw)\n\
+        }\n\
+        </file>\n\
+        --- a/src/main.rs\n\
+        +++ b/src/main.rs\n\
+        +    let config = load(&path).unwrap();\n";
+
+    #[test]
+    fn real_quotes_pass_and_invented_ones_are_flagged() {
+        let ctx = QuoteContext::new(PROMPT);
+        let response = "`fn load(path: &Path) -> Result<String>` reads the file, and \
+            `let config = load(&path).unwrap();` panics on error. It also calls \
+            `validate_checksum(&raw, expected)` which does not exist.\n\
+            Short names like `read_to_string` are not quotes.";
+        let check = ctx.check(response);
+        assert_eq!(check.checked, 3);
+        assert_eq!(check.fabricated, vec!["validate_checksum(&raw, expected)"]);
+    }
+
+    #[test]
+    fn fenced_blocks_check_quotes_but_skip_suggestions() {
+        let ctx = QuoteContext::new(PROMPT);
+        let response = "The loader:\n```rust\n\
+            let raw   =   std::fs::read_to_string(path)?;\n\
+            Ok(raw)\n```\n\
+            Invented:\n```rust\nlet cache = GLOBAL_CACHE.lock().unwrap();\n```\n\
+            Suggested fix:\n```rust\nlet config = load(&path).context(\"load\")?;\n```\n\
+            ```diff\n-    let config = load(&path).unwrap();\n+    let config = load(&path)?;\n```\n";
+        let check = ctx.check(response);
+        assert_eq!(check.checked, 3);
+        assert_eq!(
+            check.fabricated,
+            vec!["let cache = GLOBAL_CACHE.lock().unwrap();"]
+        );
+    }
+
+    #[test]
+    fn hashline_context_is_matched_without_prefixes() {
+        let ctx = QuoteContext::new("1:a3|let retries = config.max_retries;\n");
+        let check = ctx.check("`let retries = config.max_retries;` is never bounded");
+        assert_eq!(check.checked, 1);
+        assert!(check.fabricated.is_empty());
+    }
+}
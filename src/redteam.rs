@@ -0,0 +1,157 @@
+//! Red-team review (`ReviewRequest::red_team`).
+//!
+//! A subset of the fan-out stops reviewing and attacks instead: under their own
+//! system prompt those models construct concrete exploit scenarios and abuse
+//! cases for the code under review. The rest review as usual. The red team's
+//! findings are kept out of the conventional ones (the findings file, memory
+//! and hot spots) and reported on their own as `ReviewResponse::red_team`.
+//!
+//! Unless the request names the red team, about a third of the models join it
+//! (at least one), chosen by a rotation seeded with the review ID as personas
+//! are, so over several reviews every model takes a turn.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use crate::findings::Severity;
+use crate::tools::review::{ModelStatus, ReviewModelResult};
+
+/// Heading prefix the red team is asked to put on each scenario.
+pub const SCENARIO_PREFIX: &str = "Attack:";
+
+/// System prompt the red team reviews under.
+pub const PROMPT: &str = "You are a red team attacking the code under review, not reviewing it. Construct concrete exploit scenarios and abuse cases: who the attacker is, what they control, the exact input or sequence of calls, and what they gain (data exposure, privilege escalation, denial of service, fraud, corrupted state). Chain weaknesses where one step enables the next. Abuse legitimate features too: rate limits, retries, quotas, error messages, defaults. Report only scenarios you can walk through against this code, each under its own heading `### [severity] Attack: <scenario>`, with preconditions, steps, impact, and the fix that breaks the chain. Skip style and ordinary bugs; other reviewers cover those.";
+
+/// Red-team members from `models`: a third of them (at least one), starting at
+/// a position that rotates with `review_id`. Kept in `models` order.
+pub fn pick(models: &[String], review_id: &str) -> Vec<String> {
+    if models.is_empty() {
+        return Vec::new();
+    }
+    let count = (models.len() / 3).max(1);
+    let mut hasher = DefaultHasher::new();
+    review_id.hash(&mut hasher);
+    let offset = hasher.finish() as usize % models.len();
+    let chosen: Vec<usize> = (0..count).map(|i| (offset + i) % models.len()).collect();
+    models
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| chosen.contains(i))
+        .map(|(_, model)| model.clone())
+        .collect()
+}
+
+/// The red-team prompt followed by the model's own system prompt, if any.
+pub fn merge(system_prompt: Option<&str>) -> String {
+    match system_prompt.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(prompt) => format!("{PROMPT}\n\n{prompt}"),
+        None => PROMPT.to_string(),
+    }
+}
+
+/// One exploit scenario, merged across red-team models.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    pub models: Vec<String>,
+}
+
+/// The red team and its scenarios, as reported in `ReviewResponse::red_team`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedTeamReview {
+    pub models: Vec<String>,
+    pub scenarios: Vec<Scenario>,
+}
+
+impl RedTeamReview {
+    /// Scenarios from the red team's complete responses in `results`.
+    pub fn new(models: Vec<String>, results: &[ReviewModelResult]) -> Self {
+        let findings: Vec<_> = results
+            .iter()
+            .filter(|r| r.status == ModelStatus::Success && models.contains(&r.model))
+            .filter_map(|r| Some((r, r.response.as_deref()?)))
+            .flat_map(|(r, text)| crate::findings::extract_findings(&r.model, text))
+            .map(|mut f| {
+                if let Some(rest) = f.summary.get(..SCENARIO_PREFIX.len())
+                    && rest.eq_ignore_ascii_case(SCENARIO_PREFIX)
+                {
+                    f.summary = f.summary[SCENARIO_PREFIX.len()..].trim().to_string();
+                }
+                f
+            })
+            .collect();
+        let mut clusters = crate::findings::cluster_findings(&findings);
+        clusters.sort_by_key(|c| {
+            std::cmp::Reverse(crate::findings::severity_rank(c.severity.as_ref()))
+        });
+        Self {
+            models,
+            scenarios: clusters
+                .into_iter()
+                .map(|c| Scenario {
+                    summary: c.summary,
+                    severity: c.severity,
+                    file_path: c.file_path,
+                    models: c.models,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "\n### Red team\n{} ({} scenario(s)):\n",
+            self.models.join(", "),
+            self.scenarios.len()
+        );
+        if self.scenarios.is_empty() {
+            md.push_str("No exploit scenarios.\n");
+        }
+        for s in &self.scenarios {
+            let severity = s.severity.as_ref().map_or("unrated", |s| s.as_str());
+            md.push_str(&format!("- [{severity}] {}", s.summary));
+            if let Some(ref path) = s.file_path {
+                md.push_str(&format!(" ({path})"));
+            }
+            md.push_str(&format!(" — {}\n", s.models.join(", ")));
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_third_of_the_models_rotating_across_reviews() {
+        let models: Vec<String> = ["a", "b", "c", "d", "e", "f", "g"]
+            .map(String::from)
+            .to_vec();
+        let team = pick(&models, "1773157800594_1_0");
+        assert_eq!(team.len(), 2, "{team:?}");
+        assert!(team.iter().all(|m| models.contains(m)));
+
+        let seen: std::collections::HashSet<_> = (0..20)
+            .flat_map(|i| pick(&models, &format!("review_{i}")))
+            .collect();
+        assert!(seen.len() > 2, "{seen:?}");
+
+        assert_eq!(pick(&models[..1], "r"), ["a"]);
+        assert!(pick(&[], "r").is_empty());
+    }
+
+    #[test]
+    fn prompt_leads_the_models_own() {
+        assert_eq!(merge(None), PROMPT);
+        assert_eq!(
+            merge(Some("Focus on auth.")),
+            format!("{PROMPT}\n\nFocus on auth.")
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Critique-and-revise chain for the `refine` tool.
+//!
+//! The author model writes a draft; each round, the critic model reviews the
+//! latest draft against a rubric and the author revises it. A critique that
+//! starts with `APPROVED` ends the loop early. If a step fails after the first
+//! draft, the chain stops and the latest draft is returned with the reason.
+//! Every draft and critique is persisted to `.squall/refine/` (sealed and
+//! compressed like review results).
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::dispatch::registry::Registry;
+use crate::dispatch::{ProviderRequest, TokenUsage};
+use crate::error::SquallError;
+use crate::usage::UsageTag;
+
+/// Rubric used when the caller gives none.
+pub const DEFAULT_RUBRIC: &str = "Correctness (no factual or logical errors), completeness \
+    (every part of the task addressed), clarity (well organized, no filler), and \
+    specificity (concrete details instead of generalities).";
+
+/// Default critique-and-revise rounds.
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// Most rounds per request; each round is two model calls.
+pub const MAX_ROUNDS: usize = 3;
+
+/// Marker a critic puts first when the draft needs no changes.
+const APPROVED_MARKER: &str = "APPROVED";
+
+static PERSIST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Author,
+    Critic,
+}
+
+/// One model call in the chain.
+#[derive(Debug, Serialize)]
+pub struct Step {
+    /// 0 for the first draft, then 1..=rounds.
+    pub round: usize,
+    pub role: Role,
+    pub model: String,
+    pub text: String,
+    pub duration_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Settings for one refine run.
+pub struct RefineOptions {
+    pub author_model: String,
+    pub critic_model: String,
+    pub rubric: String,
+    pub rounds: usize,
+    pub author_system_prompt: Option<String>,
+    pub critic_system_prompt: Option<String>,
+    pub temperature: Option<f64>,
+    pub deadline: Instant,
+    /// Tag for the usage ledger, applied to every draft and critique.
+    pub usage: Option<UsageTag>,
+}
+
+/// Result of a refine run.
+#[derive(Debug, Serialize)]
+pub struct RefineOutcome {
+    /// Latest draft.
+    pub final_text: String,
+    /// The critic approved the final draft.
+    pub approved: bool,
+    /// Critique rounds completed.
+    pub rounds_run: usize,
+    /// Why the chain stopped early after a failure, if it did.
+    pub stopped: Option<String>,
+    pub steps: Vec<Step>,
+}
+
+fn with_context(context: Option<&str>, body: String) -> String {
+    match context {
+        Some(ctx) => format!("{ctx}\n{body}"),
+        None => body,
+    }
+}
+
+/// Prompt for the critic reviewing `draft`.
+pub fn critique_prompt(task: &str, draft: &str, rubric: &str) -> String {
+    format!(
+        "Critique the draft below against the rubric. List each concrete problem and how to \
+         fix it, most important first. If the draft fully meets the rubric and needs no \
+         changes, start your reply with {APPROVED_MARKER}.\n\n\
+         <rubric>\n{rubric}\n</rubric>\n\n<task>\n{task}\n</task>\n\n<draft>\n{draft}\n</draft>"
+    )
+}
+
+/// Prompt for the author revising `draft` after `critique`.
+pub fn revise_prompt(task: &str, draft: &str, critique: &str) -> String {
+    format!(
+        "Revise your draft to address the critique. Keep what works, fix what the critique \
+         identifies, and reply with the complete revised version only.\n\n\
+         <task>\n{task}\n</task>\n\n<draft>\n{draft}\n</draft>\n\n<critique>\n{critique}\n</critique>"
+    )
+}
+
+/// Whether a critique approves the draft as is.
+pub fn is_approved(critique: &str) -> bool {
+    critique
+        .trim_start()
+        .trim_start_matches(['*', '#', ' '])
+        .starts_with(APPROVED_MARKER)
+}
+
+async fn call(
+    registry: &Registry,
+    prompt: String,
+    model: &str,
+    system_prompt: Option<&String>,
+    opts: &RefineOptions,
+) -> Result<(String, f64, Option<TokenUsage>), SquallError> {
+    let start = Instant::now();
+    let req = ProviderRequest {
+        prompt: prompt.into(),
+        model: model.to_string(),
+        deadline: opts.deadline,
+        working_directory: None,
+        system_prompt: system_prompt.cloned(),
+        temperature: opts.temperature,
+        max_tokens: None,
+        reasoning_effort: None,
+        cancellation_token: None,
+        stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: opts.usage.clone(),
+    };
+    let result = registry.query(&req).await?;
+    Ok((result.text, start.elapsed().as_secs_f64(), result.usage))
+}
+
+/// Run the chain. Fails only if the first draft fails.
+pub async fn run(
+    registry: &Registry,
+    task: &str,
+    context: Option<&str>,
+    opts: &RefineOptions,
+) -> Result<RefineOutcome, SquallError> {
+    let author = opts.author_model.as_str();
+    let critic = opts.critic_model.as_str();
+    let (mut draft, secs, usage) = call(
+        registry,
+        with_context(context, task.to_string()),
+        author,
+        opts.author_system_prompt.as_ref(),
+        opts,
+    )
+    .await?;
+    let mut steps = vec![Step {
+        round: 0,
+        role: Role::Author,
+        model: author.to_string(),
+        text: draft.clone(),
+        duration_secs: secs,
+        usage,
+    }];
+
+    let mut approved = false;
+    let mut rounds_run = 0;
+    let mut stopped = None;
+    for round in 1..=opts.rounds {
+        let critique = match call(
+            registry,
+            with_context(context, critique_prompt(task, &draft, &opts.rubric)),
+            critic,
+            opts.critic_system_prompt.as_ref(),
+            opts,
+        )
+        .await
+        {
+            Ok((text, secs, usage)) => {
+                steps.push(Step {
+                    round,
+                    role: Role::Critic,
+                    model: critic.to_string(),
+                    text: text.clone(),
+                    duration_secs: secs,
+                    usage,
+                });
+                text
+            }
+            Err(e) => {
+                tracing::warn!("refine: critique round {round} failed: {e}");
+                stopped = Some(format!(
+                    "critique round {round} failed: {}",
+                    e.user_message()
+                ));
+                break;
+            }
+        };
+        rounds_run = round;
+        if is_approved(&critique) {
+            approved = true;
+            break;
+        }
+        match call(
+            registry,
+            with_context(context, revise_prompt(task, &draft, &critique)),
+            author,
+            opts.author_system_prompt.as_ref(),
+            opts,
+        )
+        .await
+        {
+            Ok((text, secs, usage)) => {
+                steps.push(Step {
+                    round,
+                    role: Role::Author,
+                    model: author.to_string(),
+                    text: text.clone(),
+                    duration_secs: secs,
+                    usage,
+                });
+                draft = text;
+            }
+            Err(e) => {
+                tracing::warn!("refine: revision round {round} failed: {e}");
+                stopped = Some(format!(
+                    "revision round {round} failed: {}",
+                    e.user_message()
+                ));
+                break;
+            }
+        }
+    }
+
+    Ok(RefineOutcome {
+        final_text: draft,
+        approved,
+        rounds_run,
+        stopped,
+        steps,
+    })
+}
+
+/// Persist a run to `.squall/refine/{ts}_{pid}_{seq}.json[.zst]`. Returns the
+/// relative path.
+pub async fn persist(
+    task: &str,
+    opts: &RefineOptions,
+    outcome: &RefineOutcome,
+) -> Result<String, std::io::Error> {
+    let dir = PathBuf::from(".squall/refine");
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let pid = std::process::id();
+    let seq = PERSIST_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut payload = serde_json::to_value(outcome).map_err(std::io::Error::other)?;
+    payload["task"] = serde_json::Value::String(task.to_string());
+    payload["author_model"] = serde_json::Value::String(opts.author_model.clone());
+    payload["critic_model"] = serde_json::Value::String(opts.critic_model.clone());
+    payload["rubric"] = serde_json::Value::String(opts.rubric.clone());
+
+    let json = serde_json::to_string_pretty(&payload).map_err(std::io::Error::other)?;
+    let (bytes, ext) = crate::artifact::encode(&json)?;
+    let filename = format!("{ts}_{pid}_{seq}{ext}");
+    let path = dir.join(&filename);
+
+    // Atomic write: temp file + rename, cleaning up the temp file on failure.
+    let tmp_path = dir.join(format!("{ts}_{pid}_{seq}.tmp"));
+    if let Err(e) = tokio::fs::write(&tmp_path, &bytes).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    if let Err(e) = tokio::fs::rename(&tmp_path, &path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+
+    Ok(format!(".squall/refine/{filename}"))
+}
+
+impl RefineOutcome {
+    /// Tokens summed over every step that reported usage.
+    pub fn usage(&self) -> Option<TokenUsage> {
+        TokenUsage::sum(self.steps.iter().filter_map(|s| s.usage.as_ref()))
+    }
+
+    pub fn to_markdown(&self, results_file: Option<&str>) -> String {
+        let status = if self.approved {
+            format!("approved after {} round(s)", self.rounds_run)
+        } else {
+            format!("{} round(s), not approved", self.rounds_run)
+        };
+        let mut out = format!("## Refined ({status})\n\n{}\n", self.final_text.trim());
+        if let Some(reason) = &self.stopped {
+            out.push_str(&format!("\n_Stopped early: {reason}._\n"));
+        }
+        if let Some(last) = self.steps.iter().rev().find(|s| s.role == Role::Critic) {
+            out.push_str(&format!(
+                "\n### Last critique ({})\n\n{}\n",
+                last.model,
+                last.text.trim()
+            ));
+        }
+        if let Some(file) = results_file {
+            out.push_str(&format!("\nAll drafts and critiques: `{file}`\n"));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approval_marker_tolerates_markdown_emphasis() {
+        assert!(is_approved("APPROVED. The draft is complete."));
+        assert!(is_approved("  **APPROVED** — no changes needed"));
+        assert!(is_approved("## APPROVED"));
+        assert!(!is_approved(
+            "1. The draft is not APPROVED: missing error handling"
+        ));
+        assert!(!is_approved("Not approved."));
+    }
+
+    #[test]
+    fn prompts_carry_task_draft_and_feedback() {
+        let critique = critique_prompt("Explain the cache", "It caches.", "Be specific.");
+        assert!(critique.contains("<rubric>\nBe specific.\n</rubric>"));
+        assert!(critique.contains("<draft>\nIt caches.\n</draft>"));
+        assert!(critique.contains(APPROVED_MARKER));
+
+        let revise = revise_prompt("Explain the cache", "It caches.", "Too vague.");
+        assert!(revise.contains("<task>\nExplain the cache\n</task>"));
+        assert!(revise.contains("<critique>\nToo vague.\n</critique>"));
+    }
+}
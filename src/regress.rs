@@ -0,0 +1,453 @@
+//! Regression watch for the `model_regress` tool.
+//!
+//! A suite (see `crate::suites`) is replayed against one model at temperature 0
+//! and each answer is compared with the baseline recorded for that model:
+//! word Jaccard similarity (the measure used to group review findings), the
+//! length ratio, and the model version the provider reports serving. A prompt
+//! whose answer drifts below the similarity threshold, or a changed served
+//! model, flags the run: the provider may have swapped or degraded the model
+//! behind the same name. The first run, or one with `update_baseline`, records
+//! the baseline instead.
+//!
+//! Baselines live in `.squall/suites/baselines/{suite}/{model}.json`, sealed
+//! when encryption at rest is configured.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::registry::Registry;
+use crate::dispatch::{ProviderRequest, TokenUsage};
+use crate::findings::summary_similarity;
+use crate::suites::Suite;
+use crate::usage::UsageTag;
+
+/// Baselines directory, under the suites directory.
+const BASELINES_DIR: &str = "baselines";
+
+/// Default similarity below which an answer counts as changed.
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.5;
+
+/// Length ratio (either way) past which an answer is reported as resized.
+const LENGTH_RATIO_ALERT: f64 = 2.0;
+
+/// Answers recorded for one model on one suite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub suite: String,
+    pub model: String,
+    /// ISO timestamp of the run that recorded it.
+    pub recorded_at: String,
+    pub responses: Vec<RecordedResponse>,
+}
+
+/// One model answer, as replayed or as recorded in a baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// Suite prompt id.
+    pub id: String,
+    pub text: String,
+    /// Model version the provider reported serving, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_model: Option<String>,
+}
+
+/// Outcome of replaying one suite prompt.
+#[derive(Debug)]
+pub struct Replayed {
+    pub id: String,
+    pub result: Result<RecordedResponse, String>,
+    pub usage: Option<TokenUsage>,
+}
+
+/// Replay every prompt in `suite` against `model` at temperature 0.
+pub async fn replay(
+    registry: &Registry,
+    suite: &Suite,
+    model: &str,
+    deadline: Instant,
+    usage: Option<UsageTag>,
+) -> Vec<Replayed> {
+    let requests: Vec<ProviderRequest> = suite
+        .prompts
+        .iter()
+        .map(|p| ProviderRequest {
+            prompt: p.prompt.as_str().into(),
+            model: model.to_string(),
+            deadline,
+            working_directory: None,
+            system_prompt: p.system_prompt.clone(),
+            temperature: Some(0.0),
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: usage.clone(),
+        })
+        .collect();
+    let results = futures_util::future::join_all(requests.iter().map(|r| registry.query(r))).await;
+    suite
+        .prompts
+        .iter()
+        .zip(results)
+        .map(|(p, result)| match result {
+            Ok(r) => Replayed {
+                id: p.id.clone(),
+                usage: r.usage,
+                result: Ok(RecordedResponse {
+                    id: p.id.clone(),
+                    text: r.text,
+                    served_model: r.provider_metadata.served_model,
+                }),
+            },
+            Err(e) => Replayed {
+                id: p.id.clone(),
+                result: Err(e.user_message()),
+                usage: None,
+            },
+        })
+        .collect()
+}
+
+/// Comparison of one replayed answer with its baseline.
+#[derive(Debug, Serialize)]
+pub struct PromptDiff {
+    pub id: String,
+    /// Word similarity to the baseline answer (0-1). None if either is missing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// Current length / baseline length, in characters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_ratio: Option<f64>,
+    /// Served model in the baseline and now, when they differ.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub served_model_change: Option<(String, String)>,
+    /// The replay failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Prompt added to the suite after the baseline was recorded.
+    pub new: bool,
+    pub regressed: bool,
+}
+
+/// Result of a `model_regress` run.
+#[derive(Debug, Serialize)]
+pub struct RegressionReport {
+    pub suite: String,
+    pub model: String,
+    /// When the baseline compared against was recorded. None on the first run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_recorded_at: Option<String>,
+    /// This run was saved as the new baseline.
+    pub baseline_written: bool,
+    pub min_similarity: f64,
+    /// Mean similarity over prompts compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_similarity: Option<f64>,
+    pub prompts: Vec<PromptDiff>,
+    /// Human-readable reasons the run is flagged.
+    pub alerts: Vec<String>,
+    pub regressed: bool,
+}
+
+/// Compare `replayed` with `baseline`.
+pub fn compare(
+    suite: &str,
+    model: &str,
+    baseline: &Baseline,
+    replayed: &[Replayed],
+    min_similarity: f64,
+) -> RegressionReport {
+    let mut prompts = Vec::new();
+    let mut alerts = Vec::new();
+    for r in replayed {
+        let before = baseline.responses.iter().find(|b| b.id == r.id);
+        let mut diff = PromptDiff {
+            id: r.id.clone(),
+            similarity: None,
+            length_ratio: None,
+            served_model_change: None,
+            error: None,
+            new: before.is_none(),
+            regressed: false,
+        };
+        match (&r.result, before) {
+            (Err(e), _) => {
+                diff.error = Some(e.clone());
+                if before.is_some() {
+                    alerts.push(format!("{}: failed ({e})", r.id));
+                }
+            }
+            (Ok(_), None) => {}
+            (Ok(now), Some(before)) => {
+                let similarity = summary_similarity(&before.text, &now.text);
+                let ratio =
+                    now.text.chars().count() as f64 / before.text.chars().count().max(1) as f64;
+                diff.similarity = Some(similarity);
+                diff.length_ratio = Some(ratio);
+                if similarity < min_similarity {
+                    diff.regressed = true;
+                    alerts.push(format!(
+                        "{}: answer similarity {similarity:.2} is below {min_similarity:.2}",
+                        r.id
+                    ));
+                }
+                if !(1.0 / LENGTH_RATIO_ALERT..=LENGTH_RATIO_ALERT).contains(&ratio) {
+                    alerts.push(format!("{}: answer length changed {ratio:.1}x", r.id));
+                }
+                if let (Some(was), Some(is)) = (&before.served_model, &now.served_model)
+                    && was != is
+                {
+                    diff.regressed = true;
+                    alerts.push(format!(
+                        "{}: provider now serves `{is}` (was `{was}`)",
+                        r.id
+                    ));
+                    diff.served_model_change = Some((was.clone(), is.clone()));
+                }
+            }
+        }
+        prompts.push(diff);
+    }
+    let similarities: Vec<f64> = prompts.iter().filter_map(|p| p.similarity).collect();
+    let mean_similarity = (!similarities.is_empty())
+        .then(|| similarities.iter().sum::<f64>() / similarities.len() as f64);
+    let regressed = prompts.iter().any(|p| p.regressed);
+    RegressionReport {
+        suite: suite.to_string(),
+        model: model.to_string(),
+        baseline_recorded_at: Some(baseline.recorded_at.clone()),
+        baseline_written: false,
+        min_similarity,
+        mean_similarity,
+        prompts,
+        alerts,
+        regressed,
+    }
+}
+
+/// Report for a run with no baseline to compare against.
+pub fn first_run(
+    suite: &str,
+    model: &str,
+    replayed: &[Replayed],
+    min_similarity: f64,
+) -> RegressionReport {
+    RegressionReport {
+        suite: suite.to_string(),
+        model: model.to_string(),
+        baseline_recorded_at: None,
+        baseline_written: false,
+        min_similarity,
+        mean_similarity: None,
+        prompts: replayed
+            .iter()
+            .map(|r| PromptDiff {
+                id: r.id.clone(),
+                similarity: None,
+                length_ratio: None,
+                served_model_change: None,
+                error: r.result.as_ref().err().cloned(),
+                new: true,
+                regressed: false,
+            })
+            .collect(),
+        alerts: Vec::new(),
+        regressed: false,
+    }
+}
+
+/// Baseline from a replay, or None if any prompt failed (a partial baseline
+/// would hide those prompts from later comparisons).
+pub fn baseline_from(
+    suite: &str,
+    model: &str,
+    replayed: &[Replayed],
+    recorded_at: String,
+) -> Option<Baseline> {
+    let responses = replayed
+        .iter()
+        .map(|r| r.result.clone().ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some(Baseline {
+        suite: suite.to_string(),
+        model: model.to_string(),
+        recorded_at,
+        responses,
+    })
+}
+
+/// Path of the baseline for `model` on `suite`, under the suites directory.
+pub fn baseline_path(suites_dir: &Path, suite: &str, model: &str) -> PathBuf {
+    suites_dir
+        .join(BASELINES_DIR)
+        .join(suite)
+        .join(format!("{model}.json"))
+}
+
+/// Load the baseline for `model` on `suite`. Ok(None) if there is none yet.
+pub async fn load_baseline(
+    suites_dir: &Path,
+    suite: &str,
+    model: &str,
+) -> Result<Option<Baseline>, String> {
+    let path = baseline_path(suites_dir, suite, model);
+    let text = match tokio::fs::read_to_string(&path).await {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("baseline {}: {e}", path.display())),
+    };
+    let json =
+        crate::crypto::open(text).map_err(|e| format!("baseline {}: {e}", path.display()))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("baseline {}: {e}", path.display()))
+}
+
+/// Write `baseline`, replacing any previous one. Returns the path written.
+pub async fn save_baseline(suites_dir: &Path, baseline: &Baseline) -> std::io::Result<PathBuf> {
+    let path = baseline_path(suites_dir, &baseline.suite, &baseline.model);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let json = serde_json::to_string_pretty(baseline).map_err(std::io::Error::other)?;
+    let text = crate::crypto::seal(&json)?;
+    // Atomic write: temp file + rename, cleaning up the temp file on failure.
+    let tmp_path = path.with_extension("json.tmp");
+    if let Err(e) = tokio::fs::write(&tmp_path, text).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    if let Err(e) = tokio::fs::rename(&tmp_path, &path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    Ok(path)
+}
+
+impl RegressionReport {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Regression watch: `{}` on suite `{}`\n\n",
+            self.model, self.suite
+        );
+        match &self.baseline_recorded_at {
+            Some(at) => out.push_str(&format!("Compared with the baseline from {at}. ")),
+            None => out.push_str("No baseline yet. "),
+        }
+        if self.baseline_written {
+            out.push_str("This run is now the baseline.");
+        }
+        out.push('\n');
+        if let Some(mean) = self.mean_similarity {
+            out.push_str(&format!(
+                "\nMean similarity {mean:.2} (threshold {:.2}).\n",
+                self.min_similarity
+            ));
+        }
+        if self.regressed {
+            out.push_str("\n**Regression suspected:**\n");
+        } else if !self.alerts.is_empty() {
+            out.push_str("\nNotes:\n");
+        }
+        for alert in &self.alerts {
+            out.push_str(&format!("- {alert}\n"));
+        }
+        out.push_str("\n| Prompt | Similarity | Length | Status |\n|---|---|---|---|\n");
+        for p in &self.prompts {
+            let similarity = p
+                .similarity
+                .map_or("\u{2014}".to_string(), |s| format!("{s:.2}"));
+            let length = p
+                .length_ratio
+                .map_or("\u{2014}".to_string(), |r| format!("{r:.1}x"));
+            let status = if let Some(e) = &p.error {
+                format!("error: {e}")
+            } else if p.regressed {
+                "changed".to_string()
+            } else if p.new {
+                "new".to_string()
+            } else {
+                "ok".to_string()
+            };
+            out.push_str(&format!(
+                "| {} | {similarity} | {length} | {status} |\n",
+                p.id
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replayed(id: &str, text: &str, served: Option<&str>) -> Replayed {
+        Replayed {
+            id: id.to_string(),
+            result: Ok(RecordedResponse {
+                id: id.to_string(),
+                text: text.to_string(),
+                served_model: served.map(str::to_string),
+            }),
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn compare_flags_drift_and_swapped_models() {
+        let first = vec![
+            replayed(
+                "sort",
+                "Use a stable merge sort over the records",
+                Some("m-0401"),
+            ),
+            replayed(
+                "auth",
+                "Validate the token signature before trusting claims",
+                Some("m-0401"),
+            ),
+        ];
+        let baseline =
+            baseline_from("smoke", "grok", &first, "2026-10-01T00:00:00Z".into()).unwrap();
+
+        let same = compare("smoke", "grok", &baseline, &first, DEFAULT_MIN_SIMILARITY);
+        assert!(!same.regressed);
+        assert_eq!(same.mean_similarity, Some(1.0));
+
+        let now = vec![
+            replayed(
+                "sort",
+                "Use a stable merge sort over the records",
+                Some("m-0901"),
+            ),
+            replayed("auth", "Bananas are yellow", Some("m-0401")),
+            replayed("new", "Anything", None),
+        ];
+        let report = compare("smoke", "grok", &baseline, &now, DEFAULT_MIN_SIMILARITY);
+        assert!(report.regressed);
+        assert_eq!(
+            report.prompts[0].served_model_change,
+            Some(("m-0401".to_string(), "m-0901".to_string()))
+        );
+        assert!(report.prompts[1].regressed);
+        assert!(report.prompts[2].new && !report.prompts[2].regressed);
+        assert!(report.to_markdown().contains("Regression suspected"));
+    }
+
+    #[test]
+    fn baseline_needs_every_prompt() {
+        let mut runs = vec![replayed("a", "fine", None)];
+        runs.push(Replayed {
+            id: "b".into(),
+            result: Err("timeout".into()),
+            usage: None,
+        });
+        assert!(baseline_from("smoke", "grok", &runs, String::new()).is_none());
+    }
+}
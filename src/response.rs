@@ -1,6 +1,8 @@
 use rmcp::model::{CallToolResult, Content};
 use serde::Serialize;
 
+use crate::dispatch::TokenUsage;
+
 /// PAL-compatible tool response format.
 /// The `/consensus` slash command and ori-v2 parse this JSON shape.
 /// All tools return Content::text(json_string) — double-encoded JSON matching PAL's format.
@@ -19,6 +21,11 @@ pub struct PalMetadata {
     pub provider_used: String,
     #[serde(serialize_with = "serialize_finite_f64")]
     pub duration_seconds: f64,
+    /// Tokens and cost summed over every model call the tool made. Flattened into
+    /// `input_tokens`, `output_tokens`, `estimated_cost`; omitted when no provider
+    /// reported usage.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
 }
 
 /// Serialize f64, clamping non-finite values (NaN, Inf) to 0.0.
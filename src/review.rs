@@ -1,18 +1,28 @@
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 static PERSIST_COUNTER: AtomicU64 = AtomicU64::new(0);
 
-use tokio::task::{Id as TaskId, JoinSet};
+use futures_util::Stream;
+use serde::Serialize;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::task::{AbortHandle, Id as TaskId, JoinSet};
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 /// Maximum number of models per review request (prevents DoS).
 pub const MAX_MODELS: usize = 20;
 
-use crate::dispatch::ProviderRequest;
+/// Where reviews are persisted, relative to the project root.
+pub const REVIEWS_DIR: &str = ".squall/reviews";
+
+use crate::dispatch::{DispatchTiming, ProviderRequest};
 
 /// Resolve a per-model key using fuzzy matching against target model names.
 ///
@@ -42,13 +52,59 @@ fn resolve_per_model_key<'a>(
     }
     None
 }
-use crate::dispatch::registry::Registry;
+
+/// Resolve every key of a per-model override map via `resolve_per_model_key`.
+///
+/// Returns a map keyed by exact config keys. Fuzzy matches and unknown keys are
+/// surfaced as warnings (prefixed with `field` so callers can tell maps apart).
+fn resolve_per_model_map<V: Clone>(
+    field: &str,
+    per_model: &HashMap<String, V>,
+    target_set: &HashSet<&String>,
+    id_to_key: &HashMap<String, String>,
+    warnings: &mut Vec<String>,
+) -> HashMap<String, V> {
+    let mut resolved = HashMap::new();
+    let mut unresolved: Vec<&String> = Vec::new();
+    for (key, value) in per_model {
+        if let Some(matched) = resolve_per_model_key(key, target_set, id_to_key) {
+            if key != matched.as_str() {
+                warnings.push(format!("{field} key '{key}' resolved to '{matched}'"));
+            }
+            resolved.insert(matched.clone(), value.clone());
+        } else {
+            unresolved.push(key);
+        }
+    }
+    if !unresolved.is_empty() {
+        let msg = format!(
+            "{field} contains unknown models: {unresolved:?}. Check listmodels for valid names."
+        );
+        tracing::warn!("{msg}");
+        warnings.push(msg);
+    }
+    resolved
+}
+
+/// A resolved per-model override for `model`, or else for the model a sample
+/// label ("grok#2") belongs to.
+fn per_model<'m, V>(map: Option<&'m HashMap<String, V>>, model: &str) -> Option<&'m V> {
+    let map = map?;
+    map.get(model)
+        .or_else(|| map.get(crate::consistency::base(model)))
+}
+use crate::context::{self, ContextFormat, FileContextResult};
+use crate::dispatch::health::HealthState;
+use crate::dispatch::registry::{BackendConfig, Registry};
 use crate::error::SquallError;
-use crate::memory::MemoryStore;
+use crate::memory::{MemoryStore, ModelGateStats};
+use crate::quotes::QuoteContext;
+use crate::tools::enums::{ExpectedFormat, GatingMode, Priority, ReasoningEffort, StragglerPolicy};
 use crate::tools::review::{
-    MAX_INVESTIGATION_CONTEXT_BYTES, ModelStatus, ReviewModelResult, ReviewRequest, ReviewResponse,
-    ReviewSummary,
+    MAX_INVESTIGATION_CONTEXT_BYTES, ModelStatus, QualityFlag, ReviewModelResult, ReviewRequest,
+    ReviewResponse, ReviewSummary, WorkingDirectory,
 };
+use crate::workspace::Workspace;
 
 /// Minimum success rate for a model to pass the hard gate (70%).
 pub const MIN_SUCCESS_RATE: f64 = 0.70;
@@ -57,10 +113,74 @@ pub const MIN_SUCCESS_RATE: f64 = 0.70;
 /// Models with fewer samples are allowed through (insufficient data to judge).
 pub const MIN_GATE_SAMPLES: usize = 5;
 
+/// Hard-gate thresholds (`[review.gate]`). Defaults are `MIN_SUCCESS_RATE` and
+/// `MIN_GATE_SAMPLES`, with no failure cap.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GateThresholds {
+    /// Models below this success rate are dropped.
+    pub min_success_rate: f64,
+    /// Samples a model needs before the gate judges it.
+    pub min_samples: usize,
+    /// Models with more recorded failures than this are dropped whatever their
+    /// success rate. None = no cap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_failures: Option<usize>,
+}
+
+impl Default for GateThresholds {
+    fn default() -> Self {
+        Self {
+            min_success_rate: MIN_SUCCESS_RATE,
+            min_samples: MIN_GATE_SAMPLES,
+            max_failures: None,
+        }
+    }
+}
+
+impl GateThresholds {
+    /// Whether a model with these stats is dropped.
+    pub fn excludes(&self, s: &ModelGateStats) -> bool {
+        s.sample_count >= self.min_samples
+            && (s.success_rate < self.min_success_rate
+                || self.max_failures.is_some_and(|max| failures(s) > max))
+    }
+
+    /// The thresholds as shown in gate warnings, e.g. "<70.0% success, >=5 samples".
+    pub fn describe(&self) -> String {
+        let mut out = format!(
+            "<{:.1}% success, >={} samples",
+            self.min_success_rate * 100.0,
+            self.min_samples
+        );
+        if let Some(max) = self.max_failures {
+            out.push_str(&format!(", or >{max} failures"));
+        }
+        out
+    }
+}
+
+/// Recorded failures behind a model's success rate (infrastructure failures excluded).
+fn failures(s: &ModelGateStats) -> usize {
+    let successes = (s.success_rate * s.sample_count as f64).round() as usize;
+    s.sample_count.saturating_sub(successes)
+}
+
 /// Maximum allowed timeout to prevent Instant overflow from untrusted input.
 /// 600s matches Claude Code's MCP tool timeout ceiling.
 pub const MAX_TIMEOUT_SECS: u64 = 600;
 
+/// Longest grace a straggler can be given to flush partial output after the cutoff.
+pub const MAX_STRAGGLER_GRACE_SECS: u64 = 60;
+
+/// Tags kept per review; extras are dropped.
+pub const MAX_REVIEW_TAGS: usize = 20;
+
+/// Longest tag kept, in characters; longer ones are cut.
+pub const MAX_REVIEW_TAG_CHARS: usize = 64;
+
+/// Request prompt bytes kept in the persisted review (diff and file context excluded).
+const MAX_PERSISTED_PROMPT_BYTES: usize = 32 * 1024;
+
 /// Orchestrates parallel model dispatch with straggler cutoff.
 ///
 /// Unlike `Registry::query` (single model, single response), ReviewExecutor:
@@ -70,23 +190,630 @@ pub const MAX_TIMEOUT_SECS: u64 = 600;
 /// - Persists full results to disk for compaction resilience
 pub struct ReviewExecutor {
     registry: Arc<Registry>,
+    /// Prompts keyed by per-model file-context budget (`ModelEntry::context_budget`).
+    budgeted_prompts: HashMap<usize, BudgetedPrompt>,
+    /// Files in the shared prompt, reported for models without a budgeted prompt.
+    files_sent: Option<Vec<String>>,
+    /// Branch/commit recorded in the response for later incremental reviews.
+    git: Option<crate::context::GitContext>,
+    /// Per-repository git context for multi-repo reviews.
+    repos: Vec<crate::workspace::RepoContext>,
+    incremental: Option<crate::incremental::IncrementalReview>,
+    /// First stage of a `triage_then_deep` review, reported in the response.
+    triage: Option<crate::triage::TriageOutcome>,
+    /// Policy rules the review fell under, checked against its results.
+    policy: Option<crate::policy::PolicyPlan>,
+    /// Dependency delta for a `dependency_risk` review.
+    dependencies: Option<crate::dependencies::DependencyDelta>,
+    /// Caller-side warnings (e.g. incremental fallback) surfaced with the executor's own.
+    warnings: Vec<String>,
+    /// Memory used by `run` for the hard gate, auto-memorize, and metrics logging.
+    memory: Option<Arc<MemoryStore>>,
+    /// Stop waiting once this many models have succeeded; the rest are cancelled.
+    quorum: Option<usize>,
+    /// Defaults used by `run` (`default_models`, `auto_memorize_model`).
+    review_config: Option<crate::config::ReviewConfig>,
+    /// Receives each background straggler's result once it is in the results file.
+    late_results: Option<UnboundedSender<ReviewModelResult>>,
+    /// Tag for the usage ledger, applied to every model call.
+    usage: Option<crate::usage::UsageTag>,
+    /// Directory the response is persisted to. Default: `REVIEWS_DIR` under the CWD.
+    reviews_dir: Option<PathBuf>,
+}
+
+/// Review prompt assembled for one file-context budget.
+#[derive(Debug, Clone)]
+pub struct BudgetedPrompt {
+    pub prompt: Arc<str>,
+    /// Files included in `prompt`; trimmed files carry a ` (trimmed)` suffix.
+    pub files_sent: Vec<String>,
+}
+
+/// File-context budgets other than the default among the models a review will query:
+/// `models`, or the configured default models when it names none (every
+/// registry model without a config), as dispatch resolves them.
+pub fn context_budgets(
+    registry: &Registry,
+    models: Option<&[String]>,
+    review_config: Option<&crate::config::ReviewConfig>,
+) -> BTreeSet<usize> {
+    let budget_of = |model: &str| {
+        registry
+            .get(crate::consistency::base(model))
+            .map(|entry| entry.context_budget())
+    };
+    let budgets: Vec<usize> = match (models, review_config) {
+        (Some(models), _) => models.iter().filter_map(|m| budget_of(m)).collect(),
+        (None, Some(cfg)) => cfg
+            .default_models
+            .iter()
+            .filter_map(|m| budget_of(m))
+            .collect(),
+        (None, None) => registry
+            .list_models()
+            .iter()
+            .map(|(_, entry)| entry.context_budget())
+            .collect(),
+    };
+    budgets
+        .into_iter()
+        .filter(|budget| *budget != context::MAX_FILE_CONTEXT_BYTES)
+        .collect()
+}
+
+/// A prompt per `context_budgets` budget, for `ReviewExecutor::with_budgeted_prompts`.
+/// `assemble` builds the complete prompt at one budget, so budgeted prompts carry
+/// the same extra context as the shared one.
+pub async fn budgeted_prompts<F, Fut, E>(
+    registry: &Registry,
+    models: Option<&[String]>,
+    review_config: Option<&crate::config::ReviewConfig>,
+    mut assemble: F,
+) -> Result<HashMap<usize, BudgetedPrompt>, E>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<(Arc<str>, Option<FileContextResult>), E>>,
+{
+    let mut budgeted = HashMap::new();
+    for budget in context_budgets(registry, models, review_config) {
+        let (prompt, file_result) = assemble(budget).await?;
+        budgeted.insert(
+            budget,
+            BudgetedPrompt {
+                prompt,
+                files_sent: file_result.as_ref().map(files_sent).unwrap_or_default(),
+            },
+        );
+    }
+    Ok(budgeted)
+}
+
+/// Per-model results of a review as they land (see `ReviewExecutor::execute_streaming`).
+/// Polling the stream drives the review; it ends once every model is accounted for
+/// and the response is persisted.
+pub struct ReviewStream<'a> {
+    review: Pin<Box<dyn Future<Output = ReviewResponse> + Send + 'a>>,
+    response: Option<ReviewResponse>,
+    results: UnboundedReceiver<ReviewModelResult>,
+}
+
+impl ReviewStream<'_> {
+    /// The full response. `None` until the stream has ended.
+    pub fn into_response(self) -> Option<ReviewResponse> {
+        self.response
+    }
+}
+
+impl Stream for ReviewStream<'_> {
+    type Item = ReviewModelResult;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.response.is_none()
+            && let Poll::Ready(response) = this.review.as_mut().poll(cx)
+        {
+            this.response = Some(response);
+        }
+        // The sender lives in the review future, so the channel closes when it finishes.
+        this.results.poll_recv(cx)
+    }
+}
+
+/// Builder for `ReviewExecutor` (see `ReviewExecutor::builder`).
+#[derive(Default)]
+pub struct ReviewExecutorBuilder {
+    registry: Option<Arc<Registry>>,
+    memory: Option<Arc<MemoryStore>>,
+    quorum: Option<usize>,
+    review_config: Option<crate::config::ReviewConfig>,
+}
+
+impl ReviewExecutorBuilder {
+    /// Models to dispatch to. Required.
+    pub fn registry(mut self, registry: Arc<Registry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Memory for the hard gate, auto-memorize, and per-model metrics. Without it,
+    /// `run` dispatches to every requested model and records nothing.
+    pub fn memory(mut self, memory: Arc<MemoryStore>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Return as soon as `n` models have succeeded, cancelling the rest.
+    pub fn quorum(mut self, n: usize) -> Self {
+        self.quorum = Some(n);
+        self
+    }
+
+    /// Default models and auto-memorize settings. Without it, omitted `models`
+    /// means every model in the registry.
+    pub fn review_config(mut self, config: crate::config::ReviewConfig) -> Self {
+        self.review_config = Some(config);
+        self
+    }
+
+    pub fn build(self) -> Result<ReviewExecutor, SquallError> {
+        let registry = self
+            .registry
+            .ok_or_else(|| SquallError::Other("ReviewExecutor requires a registry".to_string()))?;
+        if self.quorum == Some(0) {
+            return Err(SquallError::Other("quorum must be at least 1".to_string()));
+        }
+        let mut executor = ReviewExecutor::new(registry);
+        executor.memory = self.memory;
+        executor.quorum = self.quorum;
+        executor.review_config = self.review_config;
+        Ok(executor)
+    }
+}
+
+/// Typed review settings for library callers; the `review` tool's
+/// `ReviewRequest` minus the MCP-only knobs. Empty collections mean "not set".
+#[derive(Debug, Clone, Default)]
+pub struct ReviewOptions {
+    pub prompt: String,
+    /// Empty: `review_config` default models, or every registry model.
+    pub models: Vec<String>,
+    /// Straggler cutoff (default 180s, 600s in deep mode, max `MAX_TIMEOUT_SECS`).
+    pub timeout: Option<Duration>,
+    pub system_prompt: Option<String>,
+    pub per_model_system_prompts: HashMap<String, String>,
+    pub per_model_timeouts: HashMap<String, Duration>,
+    pub temperature: Option<f64>,
+    pub per_model_temperature: HashMap<String, f64>,
+    pub max_tokens: Option<u64>,
+    pub per_model_max_tokens: HashMap<String, u64>,
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Deep mode: 600s timeout, high reasoning effort, 16384 max tokens.
+    pub deep: bool,
+    /// Files to inline, relative to `working_directory`.
+    pub file_paths: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    pub diff: Option<String>,
+    /// http(s) URLs fetched and included as context (see `crate::urls`).
+    pub urls: Vec<String>,
+    pub context_format: ContextFormat,
+    /// Permit priority when backends are saturated (default: low in deep mode, else normal).
+    pub priority: Option<Priority>,
+    /// Merged into each HTTP model's request body (see `ReviewRequest::extra_body`).
+    pub extra_body: Option<serde_json::Value>,
+    pub expected_format: Option<ExpectedFormat>,
+    pub expected_sections: Vec<String>,
+    /// Time stragglers get after the cutoff to flush partial output
+    /// (default: `review_config`, 3s).
+    pub straggler_grace: Option<Duration>,
+    pub per_model_straggler_grace: HashMap<String, Duration>,
+    /// Kill stragglers at the cutoff, or finish them in the background
+    /// (default: `review_config`, kill).
+    pub straggler_policy: Option<StragglerPolicy>,
+    /// Labels persisted with the review (ticket ID, PR number, "pre-merge").
+    pub tags: Vec<String>,
+}
+
+impl ReviewOptions {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn into_request(self) -> ReviewRequest {
+        let per_model_timeout_secs: HashMap<String, u64> = self
+            .per_model_timeouts
+            .into_iter()
+            .map(|(model, timeout)| (model, timeout.as_secs()))
+            .collect();
+        let per_model_straggler_grace_secs: HashMap<String, u64> = self
+            .per_model_straggler_grace
+            .into_iter()
+            .map(|(model, grace)| (model, grace.as_secs()))
+            .collect();
+        ReviewRequest {
+            prompt: self.prompt,
+            models: (!self.models.is_empty()).then_some(self.models),
+            timeout_secs: self.timeout.map(|t| t.as_secs()),
+            system_prompt: self.system_prompt,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            reasoning_effort: self.reasoning_effort,
+            file_paths: (!self.file_paths.is_empty()).then_some(self.file_paths),
+            working_directory: self
+                .working_directory
+                .map(|d| WorkingDirectory::One(d.to_string_lossy().to_string())),
+            diff: self.diff.filter(|d| !d.trim().is_empty()),
+            per_model_system_prompts: (!self.per_model_system_prompts.is_empty())
+                .then_some(self.per_model_system_prompts),
+            per_model_timeout_secs: (!per_model_timeout_secs.is_empty())
+                .then_some(per_model_timeout_secs),
+            per_model_temperature: (!self.per_model_temperature.is_empty())
+                .then_some(self.per_model_temperature),
+            per_model_max_tokens: (!self.per_model_max_tokens.is_empty())
+                .then_some(self.per_model_max_tokens),
+            deep: self.deep.then_some(true),
+            context_format: Some(self.context_format),
+            response_format: None,
+            investigation_context: None,
+            incremental: None,
+            expected_format: self.expected_format,
+            expected_sections: (!self.expected_sections.is_empty())
+                .then_some(self.expected_sections),
+            urls: (!self.urls.is_empty()).then_some(self.urls),
+            priority: self.priority,
+            extra_body: self.extra_body,
+            straggler_grace_secs: self.straggler_grace.map(|g| g.as_secs()),
+            per_model_straggler_grace_secs: (!per_model_straggler_grace_secs.is_empty())
+                .then_some(per_model_straggler_grace_secs),
+            straggler_policy: self.straggler_policy,
+            tags: (!self.tags.is_empty()).then_some(self.tags),
+            blame: None,
+            language_profile: None,
+            packages: None,
+            personas: None,
+            samples: None,
+            triage_then_deep: None,
+            gating: None,
+            rubric: None,
+            dependency_risk: None,
+            dependency_advisories: None,
+            red_team: None,
+            red_team_models: None,
+            reprompt_unanswered: None,
+        }
+    }
 }
 
 impl ReviewExecutor {
     pub fn new(registry: Arc<Registry>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            budgeted_prompts: HashMap::new(),
+            files_sent: None,
+            git: None,
+            repos: Vec::new(),
+            incremental: None,
+            triage: None,
+            policy: None,
+            dependencies: None,
+            warnings: Vec::new(),
+            memory: None,
+            quorum: None,
+            review_config: None,
+            late_results: None,
+            usage: None,
+            reviews_dir: None,
+        }
+    }
+
+    /// Builder for embedding the fan-out engine outside the MCP server.
+    ///
+    /// ```no_run
+    /// # async fn example(registry: std::sync::Arc<squall::dispatch::registry::Registry>) {
+    /// use squall::review::{ReviewExecutor, ReviewOptions};
+    ///
+    /// let executor = ReviewExecutor::builder()
+    ///     .registry(registry)
+    ///     .quorum(2)
+    ///     .build()
+    ///     .unwrap();
+    /// let mut options = ReviewOptions::new("Review this function for race conditions.");
+    /// options.models = vec!["grok".into(), "deepseek-v3.1".into(), "kimi-k2.5".into()];
+    /// let response = executor.run(options).await.unwrap();
+    /// # }
+    /// ```
+    pub fn builder() -> ReviewExecutorBuilder {
+        ReviewExecutorBuilder::default()
+    }
+
+    /// Surface a warning from request preparation in the response.
+    pub fn with_warning(mut self, warning: String) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+
+    /// Record the branch/commit under review in the response.
+    pub fn with_git_context(mut self, git: Option<crate::context::GitContext>) -> Self {
+        self.git = git;
+        self
+    }
+
+    /// Record each repository's git context for a multi-repo review.
+    pub fn with_repos(mut self, repos: Vec<crate::workspace::RepoContext>) -> Self {
+        self.repos = repos;
+        self
+    }
+
+    /// Attach incremental-review metadata (base review, carried-forward findings).
+    pub fn with_incremental(mut self, incremental: crate::incremental::IncrementalReview) -> Self {
+        self.incremental = Some(incremental);
+        self
+    }
+
+    /// Attach the triage pass that picked this review's files.
+    pub fn with_triage(mut self, triage: crate::triage::TriageOutcome) -> Self {
+        self.triage = Some(triage);
+        self
+    }
+
+    /// Check the results against the policy rules this review fell under.
+    pub fn with_policy(mut self, policy: crate::policy::PolicyPlan) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Report the supply-chain findings on this dependency delta separately.
+    pub fn with_dependencies(mut self, delta: crate::dependencies::DependencyDelta) -> Self {
+        self.dependencies = Some(delta);
+        self
+    }
+
+    /// Report background stragglers' results on `tx` as they are appended to the
+    /// results file, after the review itself has returned.
+    pub fn with_late_results(mut self, tx: UnboundedSender<ReviewModelResult>) -> Self {
+        self.late_results = Some(tx);
+        self
+    }
+
+    /// Record every model call in the usage ledger under `tag`.
+    pub fn with_usage(mut self, tag: crate::usage::UsageTag) -> Self {
+        self.usage = Some(tag);
+        self
+    }
+
+    /// Persist the response (and stragglers' late results) in `dir` instead of
+    /// `REVIEWS_DIR` under the CWD.
+    pub fn with_reviews_dir(mut self, dir: PathBuf) -> Self {
+        self.reviews_dir = Some(dir);
+        self
+    }
+
+    /// Record which files the shared prompt carries, for `ReviewResponse::files_sent`.
+    pub fn with_files_sent(mut self, files: Vec<String>) -> Self {
+        self.files_sent = Some(files);
+        self
+    }
+
+    /// Send each model the prompt built for its context budget instead of the shared
+    /// prompt. Models whose budget has no entry get the shared prompt.
+    pub fn with_budgeted_prompts(mut self, prompts: HashMap<usize, BudgetedPrompt>) -> Self {
+        self.budgeted_prompts = prompts;
+        self
     }
 
     #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         &self,
         req: &ReviewRequest,
-        prompt: String,
+        prompt: impl Into<Arc<str>>,
         memory: &MemoryStore,
         working_directory: Option<String>,
         files_skipped: Option<Vec<String>>,
         files_errors: Option<Vec<String>>,
         review_config: Option<&crate::config::ReviewConfig>,
+    ) -> ReviewResponse {
+        self.dispatch(
+            req,
+            prompt.into(),
+            Some(memory),
+            working_directory,
+            files_skipped,
+            files_errors,
+            review_config,
+            None,
+        )
+        .await
+    }
+
+    /// Like `execute`, but yields each model's result as it lands: completions first,
+    /// then straggler cutoffs and quorum cancellations. Streamed results carry no quote
+    /// checks, format scores or rubric grades; those, the summary, and the persisted `results_file` are
+    /// in the full response from `ReviewStream::into_response` once the stream ends.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_streaming<'a>(
+        &'a self,
+        req: &'a ReviewRequest,
+        prompt: impl Into<Arc<str>>,
+        memory: &'a MemoryStore,
+        working_directory: Option<String>,
+        files_skipped: Option<Vec<String>>,
+        files_errors: Option<Vec<String>>,
+        review_config: Option<&'a crate::config::ReviewConfig>,
+    ) -> ReviewStream<'a> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        ReviewStream {
+            review: Box::pin(self.dispatch(
+                req,
+                prompt.into(),
+                Some(memory),
+                working_directory,
+                files_skipped,
+                files_errors,
+                review_config,
+                Some(tx),
+            )),
+            response: None,
+            results: rx,
+        }
+    }
+
+    /// Run a review with the builder's memory, quorum, and review config. Files and
+    /// the diff are inlined as the `review` tool does it (`.squallignore`, per-model
+    /// context budgets). With memory set, per-model metrics are logged before returning.
+    /// The response is persisted under `working_directory` when set (see `with_reviews_dir`).
+    pub async fn run(&self, options: ReviewOptions) -> Result<ReviewResponse, SquallError> {
+        context::validate_temperature(options.temperature).map_err(SquallError::Other)?;
+        context::validate_extra_body(options.extra_body.as_ref()).map_err(SquallError::Other)?;
+        let base_dir = match options.working_directory {
+            Some(ref dir) => {
+                Some(context::validate_working_directory(&dir.to_string_lossy()).await?)
+            }
+            None if !options.file_paths.is_empty() => {
+                return Err(SquallError::FileContext(
+                    "working_directory is required when file_paths is set".to_string(),
+                ));
+            }
+            None => None,
+        };
+        let req = options.into_request();
+        let url_result = match req.urls {
+            Some(ref urls) => Some(crate::urls::resolve_url_context(urls).await?),
+            None => None,
+        };
+
+        let mut files_skipped: Option<Vec<String>> = None;
+        let mut diff = req.diff.clone();
+        if let Some(ref diff_text) = diff
+            && let Some(ref dir) = base_dir
+            && let Some(ig) = context::load_squallignore(dir)
+        {
+            let (filtered, dropped) = context::filter_ignored_diff(diff_text, &ig);
+            if !dropped.is_empty() {
+                files_skipped = Some(
+                    dropped
+                        .into_iter()
+                        .map(|name| format!("{name} (diff, {})", context::SQUALLIGNORE_FILE))
+                        .collect(),
+                );
+                diff = Some(filtered);
+            }
+        }
+
+        let workspace = base_dir.clone().map(Workspace::single);
+        let (mut prompt, file_result) = assemble_prompt(
+            &req,
+            workspace.as_ref(),
+            diff.as_deref(),
+            context::MAX_FILE_CONTEXT_BYTES,
+        )
+        .await?;
+        let mut executor = ReviewExecutor::new(self.registry.clone());
+        executor.quorum = self.quorum;
+        // Reviews persist with the project they review, as in a server session.
+        executor.reviews_dir = self
+            .reviews_dir
+            .clone()
+            .or_else(|| base_dir.as_ref().map(|dir| dir.join(REVIEWS_DIR)));
+        if let Some(ref dir) = base_dir {
+            executor.git = context::detect_git_context(dir).await;
+        }
+        executor.warnings = self.warnings.clone();
+        if let Some(ref url_result) = url_result {
+            prompt = url_result.prepend_to(prompt);
+            executor.warnings.extend(url_result.warnings());
+        }
+        executor
+            .warnings
+            .extend(injection_warnings(file_result.as_ref(), diff.as_deref()));
+        let mut files_errors = None;
+        if let Some(ref file_result) = file_result {
+            let skipped = files_skipped.get_or_insert_with(Vec::new);
+            skipped.extend(
+                file_result
+                    .skipped
+                    .iter()
+                    .map(|(name, sz)| format!("{name} ({sz}B)")),
+            );
+            skipped.extend(
+                file_result
+                    .ignored
+                    .iter()
+                    .map(|name| format!("{name} ({})", context::SQUALLIGNORE_FILE)),
+            );
+            if skipped.is_empty() {
+                files_skipped = None;
+            }
+            if !file_result.errors.is_empty() {
+                files_errors = Some(file_result.errors.clone());
+            }
+            executor = executor.with_files_sent(files_sent(file_result));
+        }
+        if req.file_paths.is_some() || diff.is_some() {
+            let budgeted = budgeted_prompts(
+                &self.registry,
+                req.models.as_deref(),
+                self.review_config.as_ref(),
+                |budget| {
+                    let (req, workspace, diff) = (&req, workspace.as_ref(), diff.as_deref());
+                    let url_result = url_result.as_ref();
+                    async move {
+                        let (mut prompt, file_result) =
+                            assemble_prompt(req, workspace, diff, budget).await?;
+                        if let Some(url_result) = url_result {
+                            prompt = url_result.prepend_to(prompt);
+                        }
+                        Ok::<_, SquallError>((Arc::from(prompt), file_result))
+                    }
+                },
+            )
+            .await?;
+            executor = executor.with_budgeted_prompts(budgeted);
+        }
+
+        let prompt_len = prompt.len();
+        let working_directory = base_dir.map(|d| d.to_string_lossy().to_string());
+        let response = executor
+            .dispatch(
+                &req,
+                Arc::from(prompt),
+                self.memory.as_deref(),
+                working_directory.clone(),
+                files_skipped,
+                files_errors,
+                self.review_config.as_ref(),
+                None,
+            )
+            .await;
+        if let Some(ref memory) = self.memory {
+            memory
+                .log_model_metrics_scoped(
+                    &response.results,
+                    prompt_len,
+                    Some(&self.registry.model_id_to_key()),
+                    working_directory.as_deref(),
+                    memory_scope(response.git.as_ref()).as_deref(),
+                )
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// `execute` with optional memory: without it, the hard gate and auto-memorize
+    /// are skipped.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(name = "review", skip_all, fields(review_id = tracing::field::Empty))]
+    async fn dispatch(
+        &self,
+        req: &ReviewRequest,
+        prompt: Arc<str>,
+        memory: Option<&MemoryStore>,
+        working_directory: Option<String>,
+        files_skipped: Option<Vec<String>>,
+        files_errors: Option<Vec<String>>,
+        review_config: Option<&crate::config::ReviewConfig>,
+        progress: Option<UnboundedSender<ReviewModelResult>>,
     ) -> ReviewResponse {
         // Fix #3: Clamp timeout to prevent Instant overflow from untrusted input.
         // Use effective_timeout_secs() to account for deep mode (600s default).
@@ -94,18 +821,56 @@ impl ReviewExecutor {
         let effective_cutoff_secs = req.effective_timeout_secs().min(MAX_TIMEOUT_SECS);
         let cutoff = Duration::from_secs(effective_cutoff_secs);
         let start = Instant::now();
+        let review_id = new_review_id();
+        tracing::Span::current().record("review_id", review_id.as_str());
 
         // Collect warnings for quality gates (augments tracing — both logged and surfaced to caller).
-        let mut warnings: Vec<String> = Vec::new();
+        let mut warnings: Vec<String> = self.warnings.clone();
+
+        // Self-consistency: `samples` for every model, unless a name says `model#n`.
+        let default_samples = match req.samples {
+            Some(n) if n > crate::consistency::MAX_SAMPLES => {
+                warnings.push(format!(
+                    "samples: {n} exceeds the max of {}; using {}.",
+                    crate::consistency::MAX_SAMPLES,
+                    crate::consistency::MAX_SAMPLES
+                ));
+                crate::consistency::MAX_SAMPLES
+            }
+            Some(n) => n.max(1),
+            None => 1,
+        };
 
         // Determine which models to query (deduplicate, cap at MAX_MODELS)
         let target_models: Vec<String> = if let Some(ref specific) = req.models {
+            // Renamed models are requested under their current config key so
+            // hard-gate stats and results line up with the registry.
             let mut seen = HashSet::new();
-            let deduped: Vec<String> = specific
-                .iter()
-                .filter(|m| seen.insert((*m).clone()))
-                .cloned()
-                .collect();
+            let mut deduped: Vec<String> = Vec::new();
+            for requested in specific {
+                let (m, samples) = match crate::consistency::parse(requested) {
+                    Ok((m, 1)) => (m, default_samples),
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        warnings.push(format!("Skipped model {e}"));
+                        continue;
+                    }
+                };
+                let m = match self.registry.canonical_name(m) {
+                    Some(canonical) if canonical != m => {
+                        let msg = format!("Model '{m}' is now '{canonical}'; update the name.");
+                        tracing::info!("{msg}");
+                        warnings.push(msg);
+                        canonical.to_string()
+                    }
+                    _ => m.to_string(),
+                };
+                deduped.extend(
+                    crate::consistency::labels(&m, samples)
+                        .into_iter()
+                        .filter(|label| seen.insert(label.clone())),
+                );
+            }
             if deduped.len() > MAX_MODELS {
                 let dropped: Vec<&str> = deduped[MAX_MODELS..].iter().map(|s| s.as_str()).collect();
                 let msg = format!(
@@ -132,6 +897,10 @@ impl ReviewExecutor {
                     .collect()
             };
             all.sort();
+            let mut all: Vec<String> = all
+                .iter()
+                .flat_map(|m| crate::consistency::labels(m, default_samples))
+                .collect();
             if all.len() > MAX_MODELS {
                 let dropped: Vec<&str> = all[MAX_MODELS..].iter().map(|s| s.as_str()).collect();
                 let msg = format!(
@@ -157,15 +926,23 @@ impl ReviewExecutor {
         // Models with insufficient samples pass through (can't judge on tiny data).
         // If ALL models would be gated, restore the original list (never dispatch to zero).
         // Diagnostic: gate warnings include timeout/cutoff breakdown + avg failed prompt size.
+        // `gating` can turn the gate off, or leave it reporting without dropping.
         let mut gated_count = 0usize;
+        let gating = req.gating.unwrap_or_default();
+        let gate = review_config.map(|c| c.gate).unwrap_or_default();
         let id_to_key = self.registry.model_id_to_key();
-        if let Some(stats) = memory.get_model_stats(Some(&id_to_key)).await {
+        let stats = match memory {
+            Some(memory) if gating != GatingMode::Off => {
+                memory.get_model_stats(Some(&id_to_key)).await
+            }
+            _ => None,
+        };
+        if let Some(stats) = stats {
             let original = target_models.clone();
             let mut gated = Vec::new();
             target_models.retain(|model| {
-                if let Some(s) = stats.get(model)
-                    && s.sample_count >= MIN_GATE_SAMPLES
-                    && s.success_rate < MIN_SUCCESS_RATE
+                if let Some(s) = stats.get(crate::consistency::base(model))
+                    && gate.excludes(s)
                 {
                     // Diagnostic gate warning: break down WHY the model is failing
                     let timing = s.timeout_count + s.cutoff_count;
@@ -186,18 +963,40 @@ impl ReviewExecutor {
                     if s.partial_count > 0 {
                         detail.push_str(&format!(", {} partial", s.partial_count));
                     }
+                    if s.infrastructure_failures > 0 {
+                        detail.push_str(&format!(
+                            ", {} infrastructure failures not counted",
+                            s.infrastructure_failures
+                        ));
+                    }
+                    if !s.last_seen.is_empty() {
+                        detail.push_str(&format!(", last seen {}", s.last_seen));
+                    }
                     detail.push(')');
                     gated.push(detail);
                     return false;
                 }
                 true
             });
+            if gating == GatingMode::ReportOnly {
+                if !gated.is_empty() {
+                    let msg = format!(
+                        "Hard gate (report only) would exclude ({}): {}",
+                        gate.describe(),
+                        gated.join("; ")
+                    );
+                    tracing::info!("{msg}");
+                    warnings.push(msg);
+                }
+                target_models = original.clone();
+                gated.clear();
+            }
             gated_count = gated.len();
             if !gated.is_empty() {
                 let msg = format!(
-                    "Models excluded by hard gate (<{:.1}% success, >={} samples): {}",
-                    MIN_SUCCESS_RATE * 100.0,
-                    MIN_GATE_SAMPLES,
+                    "Models excluded by hard gate ({}): {}. \
+                     Pass gating: \"off\" to dispatch them anyway",
+                    gate.describe(),
                     gated.join("; ")
                 );
                 tracing::warn!("{msg}");
@@ -221,11 +1020,10 @@ impl ReviewExecutor {
                 let best_timeout_gated = original
                     .iter()
                     .filter(|m| !target_models.contains(m))
-                    .filter_map(|m| stats.get(m).map(|s| (m, s)))
+                    .filter_map(|m| stats.get(crate::consistency::base(m)).map(|s| (m, s)))
                     .filter(|(_, s)| {
                         let timing = s.timeout_count + s.cutoff_count;
-                        let successes = (s.success_rate * s.sample_count as f64).round() as usize;
-                        let failures = s.sample_count.saturating_sub(successes);
+                        let failures = failures(s);
                         // >50% of failures are timing-related
                         failures > 0 && timing * 2 > failures
                     })
@@ -255,83 +1053,174 @@ impl ReviewExecutor {
         let mut model_providers: Vec<(String, String)> = Vec::new();
 
         for model_id in &target_models {
-            if let Some(entry) = self.registry.get(model_id) {
+            if let Some(entry) = self.registry.get(crate::consistency::base(model_id)) {
                 model_providers.push((model_id.clone(), entry.provider.clone()));
             } else {
                 not_started.push(model_id.clone());
             }
         }
 
+        // Models on a backend that is down fail instantly instead of waiting out
+        // their timeouts; the backend is probed again once its cooldown passes.
+        let mut backend_down = Vec::new();
+        model_providers.retain(|(model_id, provider)| {
+            if self.registry.backend_state(provider) != HealthState::Down {
+                return true;
+            }
+            backend_down.push((model_id.clone(), provider.clone()));
+            false
+        });
+        if !backend_down.is_empty() {
+            let health = self.registry.backend_health();
+            let down: Vec<String> = health
+                .iter()
+                .filter(|h| backend_down.iter().any(|(_, p)| *p == h.provider))
+                .map(|h| h.describe())
+                .collect();
+            let msg = format!(
+                "Skipped {} model(s) on backends that are down: {}",
+                backend_down.len(),
+                down.join("; ")
+            );
+            tracing::warn!("{msg}");
+            warnings.push(msg);
+        }
+
         // Spawn all model queries as independent tokio tasks.
         let mut set = JoinSet::new();
 
         // Fix #1: Track task ID → model mapping for panic attribution
         let mut task_model_map: HashMap<TaskId, (String, String)> = HashMap::new();
-
-        // Fix #4: Compute internal deadline once before loop (not per-iteration).
-        // Buffer covers cooperative grace (3s) + abort drain (5s) + margin (7s).
-        const CUTOFF_BUFFER_SECS: u64 = 15;
-        let internal_deadline = Instant::now() + cutoff + Duration::from_secs(CUTOFF_BUFFER_SECS);
+        // Per-model abort handles, so each straggler is aborted when its own grace ends.
+        let mut abort_handles: HashMap<String, AbortHandle> = HashMap::new();
 
         // Cooperative cancellation: cancel_token signals streaming tasks to return
         // partial results instead of being hard-aborted.
         let cancel_token = CancellationToken::new();
 
+        // Language profile for the files under review, merged into every system prompt.
+        let diff_paths = req
+            .diff
+            .as_deref()
+            .map(crate::gentests::diff_paths)
+            .unwrap_or_default();
+        let profile = if req.language_profile == Some(false) {
+            None
+        } else {
+            crate::profiles::detect(
+                req.file_paths
+                    .iter()
+                    .flatten()
+                    .chain(&diff_paths)
+                    .map(String::as_str),
+            )
+        };
+
         // Resolve per_model_system_prompts keys with fuzzy matching.
-        // Builds a normalized map keyed by exact config keys.
-        let target_set: HashSet<&String> = model_providers.iter().map(|(m, _)| m).collect();
+        // Builds a normalized map keyed by exact config keys. Keys may also name a
+        // sampled model ("grok" for "grok#1".."grok#3"); see `per_model`.
+        let sampled: Vec<String> = model_providers
+            .iter()
+            .filter_map(|(m, _)| crate::consistency::sample_of(m))
+            .map(|(model, _)| model.to_string())
+            .collect();
+        let target_set: HashSet<&String> = model_providers
+            .iter()
+            .map(|(m, _)| m)
+            .chain(&sampled)
+            .collect();
         let id_to_key = self.registry.model_id_to_key();
 
-        let resolved_per_model_prompts: Option<HashMap<String, String>> =
-            req.per_model_system_prompts.as_ref().map(|per_model| {
-                let mut resolved = HashMap::new();
-                let mut unresolved: Vec<&String> = Vec::new();
-                for (key, prompt) in per_model {
-                    if let Some(matched) = resolve_per_model_key(key, &target_set, &id_to_key) {
-                        if key != matched.as_str() {
-                            warnings.push(format!(
-                                "per_model_system_prompts key '{key}' resolved to '{matched}'"
-                            ));
-                        }
-                        resolved.insert(matched.clone(), prompt.clone());
-                    } else {
-                        unresolved.push(key);
+        let resolved_per_model_prompts = req.per_model_system_prompts.as_ref().map(|per_model| {
+            resolve_per_model_map(
+                "per_model_system_prompts",
+                per_model,
+                &target_set,
+                &id_to_key,
+                &mut warnings,
+            )
+        });
+
+        // Red team: the named models (a sampled model's samples all join), or a
+        // rotating third of the fan-out.
+        let red_team: Vec<String> = if req.red_team == Some(true) {
+            let models: Vec<String> = model_providers.iter().map(|(m, _)| m.clone()).collect();
+            match req.red_team_models.as_deref().filter(|m| !m.is_empty()) {
+                Some(named) => {
+                    let unknown: Vec<&str> = named
+                        .iter()
+                        .filter(|n| {
+                            !models
+                                .iter()
+                                .any(|m| m == *n || crate::consistency::base(m) == n.as_str())
+                        })
+                        .map(String::as_str)
+                        .collect();
+                    if !unknown.is_empty() {
+                        warnings.push(format!(
+                            "red_team_models: {} not in this review",
+                            unknown.join(", ")
+                        ));
                     }
+                    models
+                        .into_iter()
+                        .filter(|m| {
+                            named
+                                .iter()
+                                .any(|n| n == m || n == crate::consistency::base(m))
+                        })
+                        .collect()
                 }
-                if !unresolved.is_empty() {
-                    let msg = format!(
-                        "per_model_system_prompts contains unknown models: {unresolved:?}. Check listmodels for valid names."
-                    );
-                    tracing::warn!("{msg}");
-                    warnings.push(msg);
-                }
-                resolved
-            });
+                None => crate::redteam::pick(&models, &review_id),
+            }
+        } else {
+            Vec::new()
+        };
+
+        // Reviewer personas for the models without a prompt of their own.
+        let personas = if req.personas == Some(true) {
+            let unprompted: Vec<String> = model_providers
+                .iter()
+                .map(|(model, _)| model)
+                .filter(|model| per_model(resolved_per_model_prompts.as_ref(), model).is_none())
+                .filter(|model| !red_team.contains(model))
+                .cloned()
+                .collect();
+            crate::personas::assign(&unprompted, &review_id)
+        } else {
+            BTreeMap::new()
+        };
+
+        // Resolve per_model_temperature / per_model_max_tokens the same way.
+        let resolved_per_model_temperature = req.per_model_temperature.as_ref().map(|per_model| {
+            resolve_per_model_map(
+                "per_model_temperature",
+                per_model,
+                &target_set,
+                &id_to_key,
+                &mut warnings,
+            )
+        });
+        let resolved_per_model_max_tokens = req.per_model_max_tokens.as_ref().map(|per_model| {
+            resolve_per_model_map(
+                "per_model_max_tokens",
+                per_model,
+                &target_set,
+                &id_to_key,
+                &mut warnings,
+            )
+        });
 
         // Resolve per_model_timeout_secs keys with fuzzy matching.
         let resolved_per_model_timeouts: Option<HashMap<String, u64>> =
             req.per_model_timeout_secs.as_ref().map(|per_model| {
-                let mut resolved = HashMap::new();
-                let mut unresolved: Vec<&String> = Vec::new();
-                for (key, timeout) in per_model {
-                    if let Some(matched) = resolve_per_model_key(key, &target_set, &id_to_key) {
-                        if key != matched.as_str() {
-                            warnings.push(format!(
-                                "per_model_timeout_secs key '{key}' resolved to '{matched}'"
-                            ));
-                        }
-                        resolved.insert(matched.clone(), *timeout);
-                    } else {
-                        unresolved.push(key);
-                    }
-                }
-                if !unresolved.is_empty() {
-                    let msg = format!(
-                        "per_model_timeout_secs contains unknown models: {unresolved:?}. Check listmodels for valid names."
-                    );
-                    tracing::warn!("{msg}");
-                    warnings.push(msg);
-                }
+                let resolved = resolve_per_model_map(
+                    "per_model_timeout_secs",
+                    per_model,
+                    &target_set,
+                    &id_to_key,
+                    &mut warnings,
+                );
                 // Warn on zero-value timeouts
                 let zeros: Vec<&String> = resolved
                     .iter()
@@ -348,24 +1237,122 @@ impl ReviewExecutor {
                 resolved
             });
 
+        // Straggler handling: request, then [review] config, then built-in defaults.
+        let straggler_policy = req
+            .straggler_policy
+            .or(review_config.map(|c| c.straggler_policy))
+            .unwrap_or_default();
+        let background = straggler_policy == StragglerPolicy::Background;
+        let default_grace_secs = req
+            .straggler_grace_secs
+            .or(review_config.map(|c| c.straggler_grace_secs))
+            .unwrap_or(crate::config::ReviewConfig::DEFAULT_STRAGGLER_GRACE_SECS);
+        let resolved_per_model_grace = req
+            .per_model_straggler_grace_secs
+            .as_ref()
+            .map(|per_model| {
+                resolve_per_model_map(
+                    "per_model_straggler_grace_secs",
+                    per_model,
+                    &target_set,
+                    &id_to_key,
+                    &mut warnings,
+                )
+            })
+            .unwrap_or_default();
+        let grace_for = |model: &str| {
+            let secs = per_model(Some(&resolved_per_model_grace), model)
+                .copied()
+                .unwrap_or(default_grace_secs);
+            Duration::from_secs(secs.min(MAX_STRAGGLER_GRACE_SECS))
+        };
+        let max_grace = model_providers
+            .iter()
+            .map(|(model, _)| grace_for(model))
+            .max()
+            .unwrap_or_default();
+
+        // Fix #4: Compute internal deadline once before loop (not per-iteration).
+        // Buffer covers the longest cooperative grace + abort drain (5s) + margin (7s).
+        // Background stragglers may run on to MAX_TIMEOUT_SECS.
+        const CUTOFF_BUFFER_SECS: u64 = 12;
+        let run_limit = if background {
+            cutoff.max(Duration::from_secs(MAX_TIMEOUT_SECS))
+        } else {
+            cutoff
+        };
+        let internal_deadline =
+            Instant::now() + run_limit + max_grace + Duration::from_secs(CUTOFF_BUFFER_SECS);
+
         // Pin base timestamp before spawn loop to avoid per-model time skew.
         let base_now = Instant::now();
 
-        // Share prompt across models via Arc — avoids cloning MB-scale buffers per model.
-        let prompt: Arc<str> = Arc::from(prompt);
+        // The prompt is shared across models via Arc — avoids cloning MB-scale buffers
+        // per model.
+        let mut files_sent: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        // What each model was sent, for checking its quotes afterwards.
+        let mut prompts_sent: HashMap<String, Arc<str>> = HashMap::new();
+        // Numbered questions in the prompt, and each model's request for re-asking
+        // the ones it skips.
+        let questions = if req.reprompt_unanswered == Some(false) {
+            Vec::new()
+        } else {
+            crate::questions::extract(&req.prompt)
+        };
+        let mut follow_up_requests: HashMap<String, ProviderRequest> = HashMap::new();
 
         for (model_id, provider) in &model_providers {
             let registry = self.registry.clone();
             let model_id = model_id.clone();
             let provider = provider.clone();
-            let prompt = prompt.clone(); // Arc refcount bump, not a buffer copy
-            // Per-model system prompt: use fuzzy-resolved map, fall back to shared
-            let system_prompt = resolved_per_model_prompts
-                .as_ref()
-                .and_then(|map| map.get(&model_id).cloned())
+            // Arc refcount bump, not a buffer copy
+            let prompt = match self
+                .registry
+                .get(crate::consistency::base(&model_id))
+                .and_then(|entry| self.budgeted_prompts.get(&entry.context_budget()))
+            {
+                Some(budgeted) => {
+                    files_sent.insert(model_id.clone(), budgeted.files_sent.clone());
+                    budgeted.prompt.clone()
+                }
+                None => {
+                    if let Some(ref files) = self.files_sent {
+                        files_sent.insert(model_id.clone(), files.clone());
+                    }
+                    prompt.clone()
+                }
+            };
+            prompts_sent.insert(model_id.clone(), prompt.clone());
+            // Per-model system prompt: use fuzzy-resolved map, then the model's
+            // persona, then the shared prompt. The red-team prompt leads whichever applies.
+            let system_prompt = per_model(resolved_per_model_prompts.as_ref(), &model_id)
+                .cloned()
+                .or_else(|| {
+                    personas.get(&model_id).map(|persona| {
+                        crate::personas::merge(persona, req.system_prompt.as_deref())
+                    })
+                })
                 .or_else(|| req.system_prompt.clone());
-            let temperature = req.temperature;
-            let max_tokens = req.effective_max_tokens();
+            let system_prompt = if red_team.contains(&model_id) {
+                Some(crate::redteam::merge(system_prompt.as_deref()))
+            } else {
+                system_prompt
+            };
+            let system_prompt = match profile {
+                Some(profile) => Some(crate::profiles::merge(system_prompt.as_deref(), profile)),
+                None => system_prompt,
+            };
+            // Samples need some randomness to be worth voting on.
+            let temperature = per_model(resolved_per_model_temperature.as_ref(), &model_id)
+                .copied()
+                .or(req.temperature)
+                .or_else(|| {
+                    crate::consistency::sample_of(&model_id)
+                        .map(|_| crate::consistency::SAMPLE_TEMPERATURE)
+                });
+            let max_tokens = per_model(resolved_per_model_max_tokens.as_ref(), &model_id)
+                .copied()
+                .or_else(|| req.effective_max_tokens());
             let reasoning_effort = req
                 .effective_reasoning_effort()
                 .map(|e| e.as_str().to_string());
@@ -374,54 +1361,80 @@ impl ReviewExecutor {
 
             // Per-model deadline: min(per_model_timeout, internal_deadline).
             // Per-model timeouts are clamped to MAX_TIMEOUT_SECS.
-            let per_model_deadline = resolved_per_model_timeouts
-                .as_ref()
-                .and_then(|map| map.get(&model_id))
+            let per_model_deadline = per_model(resolved_per_model_timeouts.as_ref(), &model_id)
                 .map(|secs| {
                     let clamped = (*secs).min(MAX_TIMEOUT_SECS);
                     (base_now + Duration::from_secs(clamped)).min(internal_deadline)
                 })
                 .unwrap_or(internal_deadline);
 
-            // Stall timeout: extend for deep mode or known slow models (non-reasoning)
+            // Stall timeout: extend for deep mode. Known slow models carry their own
+            // `stall_timeout_secs` in config, merged in Registry::query.
             let stall_timeout = if req.deep == Some(true) {
                 Some(Duration::from_secs(300))
             } else {
                 None
             };
 
+            let priority = Some(req.effective_priority());
+            let extra_body = req.extra_body.clone();
+            let usage = self.usage.clone();
             // Clone before moving into async block — needed for task_model_map below
             let model_id_for_map = model_id.clone();
             let provider_for_map = provider.clone();
             let token = cancel_token.clone();
+            let model_span = tracing::info_span!("model", model = %model_id);
 
-            let abort_handle = set.spawn(async move {
-                let model_start = Instant::now();
-                let provider_req = ProviderRequest {
-                    prompt,
-                    model: model_id.clone(),
-                    deadline: per_model_deadline,
-                    working_directory: wd,
-                    system_prompt,
-                    temperature,
-                    max_tokens,
-                    reasoning_effort,
-                    cancellation_token: Some(token),
-                    stall_timeout,
-                };
-                let result = registry.query(&provider_req).await;
-                let latency_ms = model_start.elapsed().as_millis() as u64;
-                (model_id, provider, result, latency_ms)
-            });
-            task_model_map.insert(abort_handle.id(), (model_id_for_map, provider_for_map));
+            let provider_req = ProviderRequest {
+                prompt,
+                model: crate::consistency::base(&model_id).to_string(),
+                deadline: per_model_deadline,
+                working_directory: wd,
+                system_prompt,
+                temperature,
+                max_tokens,
+                reasoning_effort,
+                cancellation_token: Some(token),
+                stall_timeout,
+                max_output_bytes: None,
+                priority,
+                extra_body,
+                usage,
+            };
+            if !questions.is_empty() {
+                follow_up_requests.insert(model_id.clone(), provider_req.clone());
+            }
+
+            let abort_handle = set.spawn(
+                async move {
+                    let model_start = Instant::now();
+                    let result = registry.query(&provider_req).await;
+                    let latency_ms = model_start.elapsed().as_millis() as u64;
+                    (model_id, provider, result, latency_ms)
+                }
+                .instrument(model_span),
+            );
+            task_model_map.insert(
+                abort_handle.id(),
+                (model_id_for_map.clone(), provider_for_map),
+            );
+            abort_handles.insert(model_id_for_map, abort_handle);
         }
 
         // Collect results as they complete, racing against the cutoff timer.
         let mut results = Vec::new();
         let mut completed_models = HashSet::new();
 
+        for (model_id, provider) in backend_down {
+            let error = format!("skipped: backend {provider} is down");
+            let mut result = collect_result(Err(SquallError::Other(error)), model_id, provider, 0);
+            result.reason = Some("backend_down".to_string());
+            record(&mut results, result, progress.as_ref());
+        }
+
         let deadline = tokio::time::sleep(cutoff);
         tokio::pin!(deadline);
+        let mut quorum_met = false;
 
         loop {
             tokio::select! {
@@ -430,14 +1443,34 @@ impl ReviewExecutor {
                     match join_result {
                         Some(Ok((model_id, provider, query_result, latency_ms))) => {
                             completed_models.insert(model_id.clone());
-                            results.push(collect_result(query_result, model_id, provider, latency_ms));
+                            record(&mut results, collect_result(query_result, model_id, provider, latency_ms), progress.as_ref());
                             if set.is_empty() { break; }
+                            if self.quorum.is_some_and(|q| {
+                                results.iter().filter(|r| r.status == ModelStatus::Success && !r.partial).count() >= q
+                            }) {
+                                // Quorum reached: drop the rest, keeping any that finished meanwhile.
+                                quorum_met = true;
+                                cancel_token.cancel();
+                                set.abort_all();
+                                let drain_until = tokio::time::Instant::now() + Duration::from_secs(5);
+                                while let Ok(Some(join_result)) = tokio::time::timeout_at(drain_until, set.join_next()).await {
+                                    if let Ok((model_id, provider, query_result, latency_ms)) = join_result {
+                                        // Still queued when quorum landed: reported as "quorum" below.
+                                        if matches!(query_result, Err(SquallError::QueuedTimeout(_))) {
+                                            continue;
+                                        }
+                                        completed_models.insert(model_id.clone());
+                                        record(&mut results, collect_result(query_result, model_id, provider, latency_ms), progress.as_ref());
+                                    }
+                                }
+                                break;
+                            }
                         }
                         // Fix #1: Attribute panics to the correct model via task ID.
                         // Guard with is_panic() — cancelled tasks should not be
                         // reported as panics (defensive; cancellation is unexpected here).
                         Some(Err(join_err)) if join_err.is_panic() => {
-                            collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start);
+                            collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start, progress.as_ref());
                             if set.is_empty() { break; }
                         }
                         Some(Err(_)) => {
@@ -448,37 +1481,56 @@ impl ReviewExecutor {
                     }
                 }
                 _ = &mut deadline => {
+                    // Background policy: leave stragglers running; they are handed
+                    // off once the response is persisted.
+                    if background {
+                        break;
+                    }
+
                     // Straggler cutoff: cooperative cancel first, then hard-abort.
                     // cancel_token signals streaming tasks to return partial results.
                     cancel_token.cancel();
 
                     // Grace period: collect partial results from tasks that respond
                     // to cancellation quickly (streaming tasks flush accumulated text).
-                    let grace = tokio::time::sleep(Duration::from_secs(3));
-                    tokio::pin!(grace);
-                    loop {
+                    // Each straggler is hard-aborted when its own grace runs out.
+                    let now = tokio::time::Instant::now();
+                    let mut graces: Vec<(tokio::time::Instant, &AbortHandle)> = abort_handles
+                        .iter()
+                        .filter(|(model, _)| !completed_models.contains(*model))
+                        .map(|(model, handle)| (now + grace_for(model), handle))
+                        .collect();
+                    while let Some(next_abort) = graces.iter().map(|(at, _)| *at).min() {
                         tokio::select! {
                             biased;
-                            join_result = set.join_next() => {
+                            join_result = set.join_next_with_id() => {
                                 match join_result {
-                                    Some(Ok((model_id, provider, query_result, latency_ms))) => {
+                                    Some(Ok((id, (model_id, provider, query_result, latency_ms)))) => {
+                                        graces.retain(|(_, handle)| handle.id() != id);
                                         completed_models.insert(model_id.clone());
-                                        results.push(collect_result(query_result, model_id, provider, latency_ms));
+                                        record(&mut results, collect_result(query_result, model_id, provider, latency_ms), progress.as_ref());
                                     }
                                     Some(Err(join_err)) if join_err.is_panic() => {
-                                        collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start);
+                                        graces.retain(|(_, handle)| handle.id() != join_err.id());
+                                        collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start, progress.as_ref());
                                     }
                                     Some(Err(_)) => {} // Cancelled — unexpected before abort
                                     None => break,
                                 }
                             }
-                            _ = &mut grace => {
+                            _ = tokio::time::sleep_until(next_abort) => {
                                 // Hard-abort stragglers that didn't respond to cancellation
-                                set.abort_all();
-                                break;
+                                graces.retain(|(at, handle)| {
+                                    let expired = *at <= next_abort;
+                                    if expired {
+                                        handle.abort();
+                                    }
+                                    !expired
+                                });
                             }
                         }
                     }
+                    set.abort_all();
 
                     // Drain tasks that completed during abort_all()
                     let drain_grace = tokio::time::sleep(Duration::from_secs(5));
@@ -490,10 +1542,10 @@ impl ReviewExecutor {
                                 match join_result {
                                     Some(Ok((model_id, provider, query_result, latency_ms))) => {
                                         completed_models.insert(model_id.clone());
-                                        results.push(collect_result(query_result, model_id, provider, latency_ms));
+                                        record(&mut results, collect_result(query_result, model_id, provider, latency_ms), progress.as_ref());
                                     }
                                     Some(Err(join_err)) if join_err.is_panic() => {
-                                        collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start);
+                                        collect_panic(&join_err, &task_model_map, &mut completed_models, &mut results, &start, progress.as_ref());
                                     }
                                     Some(Err(_)) => {} // Cancelled — expected after abort_all()
                                     None => break,
@@ -511,23 +1563,173 @@ impl ReviewExecutor {
             }
         }
 
-        // Mark cutoff models (spawned but didn't complete before deadline)
+        // Mark cutoff models (spawned but didn't complete before the deadline or quorum)
         let elapsed_ms = start.elapsed().as_millis() as u64;
         for (model_id, provider) in &model_providers {
             if !completed_models.contains(model_id) {
-                results.push(ReviewModelResult {
-                    model: model_id.clone(),
-                    provider: provider.clone(),
-                    status: ModelStatus::Error,
-                    response: None,
-                    error: Some("straggler cutoff".to_string()),
-                    reason: Some("cutoff".to_string()),
-                    latency_ms: elapsed_ms,
-                    partial: false,
-                });
+                let (error, reason) = if quorum_met {
+                    ("cancelled: quorum reached", "quorum")
+                } else if background {
+                    (
+                        "still running after the cutoff; its result is appended to results_file",
+                        "background",
+                    )
+                } else {
+                    ("straggler cutoff", "cutoff")
+                };
+                record(
+                    &mut results,
+                    ReviewModelResult {
+                        model: model_id.clone(),
+                        provider: provider.clone(),
+                        status: ModelStatus::Error,
+                        response: None,
+                        error: Some(error.to_string()),
+                        reason: Some(reason.to_string()),
+                        latency_ms: elapsed_ms,
+                        partial: false,
+                        timing: DispatchTiming::default(),
+                        quotes_checked: 0,
+                        quality_flags: Vec::new(),
+                        format_compliance: None,
+                        rubric: None,
+                        usage: None,
+                        provider_metadata: Default::default(),
+                        late: false,
+                        usable: None,
+                        repaired: false,
+                        duplicate_of: None,
+                    },
+                    progress.as_ref(),
+                );
+            }
+        }
+
+        // Re-ask models the prompt's numbered questions they skipped, before the cutoff.
+        let mut question_coverage = BTreeMap::new();
+        if !questions.is_empty() {
+            let (coverage, follow_up_warnings) = crate::questions::follow_up(
+                &self.registry,
+                &questions,
+                &mut results,
+                &follow_up_requests,
+                start + cutoff,
+            )
+            .await;
+            question_coverage = coverage;
+            warnings.extend(follow_up_warnings);
+        }
+
+        // Check verbatim quotes against the context each model was sent. Only
+        // meaningful when files or a diff were supplied; CLI models read files
+        // themselves and get a manifest, so their quotes can't be checked here.
+        let has_context =
+            req.file_paths.as_ref().is_some_and(|p| !p.is_empty()) || req.diff.is_some();
+        if has_context {
+            let mut contexts: HashMap<*const u8, QuoteContext> = HashMap::new();
+            for result in &mut results {
+                let (Some(text), Some(sent)) = (&result.response, prompts_sent.get(&result.model))
+                else {
+                    continue;
+                };
+                if self
+                    .registry
+                    .get(&result.model)
+                    .is_some_and(|e| matches!(e.backend, BackendConfig::Cli { .. }))
+                {
+                    continue;
+                }
+                let ctx = contexts
+                    .entry(Arc::as_ptr(sent) as *const u8)
+                    .or_insert_with(|| QuoteContext::new(sent));
+                let check = ctx.check(text);
+                result.quotes_checked = check.checked;
+                result.quality_flags.extend(
+                    check
+                        .fabricated
+                        .into_iter()
+                        .map(|quote| QualityFlag::FabricatedQuote { quote }),
+                );
+            }
+        }
+
+        // Score compliance with the requested output structure. Truncated responses
+        // are skipped: a cut-off JSON document says nothing about the model.
+        if let Some(format) = req.expected_format {
+            let sections = req.expected_sections.as_deref().unwrap_or_default();
+            for result in &mut results {
+                if let Some(text) = &result.response
+                    && !result.partial
+                {
+                    result.format_compliance =
+                        Some(crate::compliance::score(text, format, sections));
+                }
+                // Salvage almost-valid JSON so callers get a parseable document; the
+                // score above still reflects what the model actually sent.
+                if format == ExpectedFormat::Json
+                    && let Some(text) = &result.response
+                    && let Some((doc, true)) = crate::json_repair::parse(text)
+                    && let Ok(json) = serde_json::to_string_pretty(&doc)
+                {
+                    result.response = Some(json);
+                    result.repaired = true;
+                }
             }
         }
 
+        // Grade complete responses against the rubric. Runs after the cutoff with
+        // its own deadline, so slow judging never costs a reviewer its slot.
+        if let Some(ref rubric) = req.rubric {
+            warnings.extend(
+                crate::rubric::grade(
+                    &self.registry,
+                    rubric,
+                    &req.prompt,
+                    &mut results,
+                    self.usage.clone(),
+                )
+                .await,
+            );
+        }
+
+        for result in &results {
+            let secrets = result.redacted_secrets();
+            if !secrets.is_empty() {
+                warnings.push(format!(
+                    "{}: redacted {} from the response",
+                    result.model,
+                    crate::secrets::summarize(&secrets)
+                ));
+            }
+        }
+
+        // A rejected key fails every model of its provider; lead with one clear
+        // warning instead of leaving callers to decode each auth error.
+        let degraded = self
+            .registry
+            .degraded_keys(results.iter().map(|r| r.provider.as_str()));
+        for (i, (provider, state)) in degraded.iter().enumerate() {
+            let msg = state.warning(provider);
+            tracing::warn!("{msg}");
+            warnings.insert(i, msg);
+        }
+
+        mark_duplicate_responses(&mut results);
+        let consistency =
+            crate::consistency::vote(&results, review_config.map(|c| &c.severity_map));
+        // How far each reviewed model's "High" has held up under feedback, so the
+        // synthesis can weigh its findings accordingly.
+        let calibration = match memory {
+            Some(memory) => {
+                let reviewed: Vec<&str> = results
+                    .iter()
+                    .map(|r| crate::consistency::base(&r.model))
+                    .collect();
+                crate::calibration::view(&memory.get_calibration().await, Some(&reviewed))
+            }
+            None => Vec::new(),
+        };
+
         // Build summary from collected results.
         let selection_reasoning = if auto_selected {
             Some(format!(
@@ -546,11 +1748,22 @@ impl ReviewExecutor {
                 .count(),
             models_failed: results
                 .iter()
-                .filter(|r| r.status == ModelStatus::Error && r.reason.as_deref() != Some("cutoff"))
+                .filter(|r| {
+                    r.status == ModelStatus::Error
+                        && !matches!(
+                            r.reason.as_deref(),
+                            Some("cutoff" | "quorum" | "background")
+                        )
+                })
                 .count(),
             models_cutoff: results
                 .iter()
-                .filter(|r| r.reason.as_deref() == Some("cutoff"))
+                .filter(|r| {
+                    matches!(
+                        r.reason.as_deref(),
+                        Some("cutoff" | "quorum" | "background")
+                    )
+                })
                 .count(),
             models_partial: results
                 .iter()
@@ -561,8 +1774,21 @@ impl ReviewExecutor {
             selection_reasoning,
         };
 
+        let policy = self
+            .policy
+            .as_ref()
+            .map(|plan| plan.check(&results))
+            .unwrap_or_default();
+        let supply_chain = self
+            .dependencies
+            .as_ref()
+            .map(|delta| crate::dependencies::SupplyChainReview::new(delta, &results));
+        let red_team =
+            (!red_team.is_empty()).then(|| crate::redteam::RedTeamReview::new(red_team, &results));
+
         // Construct response first (results_file: None), then persist.
         let mut response = ReviewResponse {
+            review_id,
             results,
             not_started,
             cutoff_seconds: effective_cutoff_secs,
@@ -571,8 +1797,27 @@ impl ReviewExecutor {
             persist_error: None,
             files_skipped,
             files_errors,
+            files_sent,
             warnings,
             summary,
+            diff_size: req.diff.as_deref().and_then(crate::context::diff_stat),
+            auto_memorized: Vec::new(),
+            git: self.git.clone(),
+            tags: review_tags(req.tags.as_deref().unwrap_or_default()),
+            language_profile: profile.map(|p| p.language.to_string()),
+            incremental: self.incremental.clone(),
+            repos: self.repos.clone(),
+            consistency,
+            calibration,
+            personas: personas
+                .iter()
+                .map(|(model, persona)| (model.clone(), persona.name.to_string()))
+                .collect(),
+            triage: self.triage.clone(),
+            policy,
+            supply_chain,
+            red_team,
+            question_coverage,
         };
 
         // Clamp investigation_context for persistence (prevent oversized payloads).
@@ -606,7 +1851,14 @@ impl ReviewExecutor {
         }
 
         // Persist to disk — failure must never lose in-memory results
-        match persist_response(&response, investigation_context).await {
+        // The request prompt is persisted (clamped) so `reviews_search` can find it.
+        let prompt = &req.prompt
+            [..crate::context::floor_char_boundary(&req.prompt, MAX_PERSISTED_PROMPT_BYTES)];
+        let reviews_dir = self
+            .reviews_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(REVIEWS_DIR));
+        match persist_response(&reviews_dir, &response, prompt, investigation_context).await {
             Ok(path) => response.results_file = Some(path),
             Err(e) => {
                 tracing::warn!("failed to persist review results: {e}");
@@ -614,35 +1866,464 @@ impl ReviewExecutor {
             }
         }
 
+        // Background stragglers finish on their own task; without a results file
+        // there is nowhere to put them, so they are dropped (aborted) instead.
+        if background
+            && !quorum_met
+            && !set.is_empty()
+            && let Some(ref results_file) = response.results_file
+        {
+            tokio::spawn(
+                finish_stragglers(
+                    set,
+                    task_model_map,
+                    PathBuf::from(results_file),
+                    start,
+                    self.late_results.clone(),
+                )
+                .instrument(tracing::Span::current()),
+            );
+        }
+
         // Extract structured findings from successful responses and persist alongside.
-        if let Some(ref results_file) = response.results_file {
-            let mut all_findings = Vec::new();
-            for result in &response.results {
-                if result.status == ModelStatus::Success
-                    && let Some(ref text) = result.response
-                {
-                    // result.model is already the config key (from target_models iteration)
-                    let model_key = result.model.clone();
-                    let findings = crate::findings::extract_findings(&model_key, text);
-                    all_findings.extend(findings);
+        // Configured severity phrasings are normalized here, so the findings file and
+        // auto-memorize consensus use one scale.
+        let severity_map = review_config.map(|c| &c.severity_map);
+        // The red team's scenarios are reported apart and kept out of these.
+        let red_team = response
+            .red_team
+            .as_ref()
+            .map(|r| r.models.as_slice())
+            .unwrap_or_default();
+        let mut all_findings = Vec::new();
+        for result in &response.results {
+            if result.status == ModelStatus::Success
+                && !red_team.contains(&result.model)
+                && let Some(ref text) = result.response
+            {
+                // result.model is already the config key (from target_models iteration)
+                let model_key = result.model.clone();
+                let aliases = severity_map
+                    .map(|m| m.for_model(&model_key, &result.provider))
+                    .unwrap_or_default();
+                let findings = crate::findings::extract_findings_with(&model_key, text, &aliases);
+                all_findings.extend(findings);
+            }
+        }
+        if let Some(ref results_file) = response.results_file
+            && !all_findings.is_empty()
+        {
+            match crate::findings::persist_findings(results_file, &all_findings).await {
+                Ok(path) => {
+                    tracing::info!("persisted {} findings to {path}", all_findings.len())
                 }
+                Err(e) => tracing::warn!("failed to persist findings: {e}"),
             }
-            if !all_findings.is_empty() {
-                match crate::findings::persist_findings(results_file, &all_findings).await {
-                    Ok(path) => {
-                        tracing::info!("persisted {} findings to {path}", all_findings.len())
-                    }
-                    Err(e) => tracing::warn!("failed to persist findings: {e}"),
+        }
+
+        if let Some(memory) = memory
+            && let Err(e) = memory.record_hotspots(&all_findings).await
+        {
+            tracing::warn!("failed to record hot spots: {e}");
+        }
+
+        // Findings models contradict each other on go to memory, for feedback to settle.
+        if let Some(memory) = memory
+            && let Some(stem) = response
+                .results_file
+                .as_deref()
+                .and_then(|f| Path::new(f).file_name()?.to_str())
+                .and_then(crate::artifact::review_stem)
+        {
+            let found = crate::disagreement::find(&all_findings);
+            match memory.record_disagreements(stem, &found).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("recorded {n} model disagreement(s)"),
+                Err(e) => tracing::warn!("failed to record disagreements: {e}"),
+            }
+        }
+
+        // Opt-in: let a cheap model turn the findings into memory patterns.
+        if let Some(model) = review_config.and_then(|c| c.auto_memorize_model.as_deref())
+            && let Some(memory) = memory
+            && !all_findings.is_empty()
+        {
+            let responded = response
+                .results
+                .iter()
+                .filter(|r| r.status == ModelStatus::Success)
+                .count();
+            let scope = memory_scope(response.git.as_ref());
+            match self
+                .auto_memorize(model, &all_findings, responded, scope.as_deref(), memory)
+                .await
+            {
+                Ok(saved) => response.auto_memorized = saved,
+                Err(e) => {
+                    let msg = format!("auto-memorize via {model} failed: {e}");
+                    tracing::warn!("{msg}");
+                    response.warnings.push(msg);
                 }
             }
         }
 
         response
     }
+
+    /// Ask `model` for 0-3 durable lessons from the review findings and memorize
+    /// them as patterns tagged `source: auto`. Returns the saved entries.
+    async fn auto_memorize(
+        &self,
+        model: &str,
+        findings: &[crate::findings::Finding],
+        responded: usize,
+        scope: Option<&str>,
+        memory: &MemoryStore,
+    ) -> Result<Vec<String>, String> {
+        let clusters = crate::findings::cluster_findings(findings);
+        let mut prompt = String::from("Findings from a multi-model code review:\n");
+        for cluster in clusters.iter().take(AUTO_MEMORIZE_MAX_FINDINGS) {
+            let severity = cluster.severity.as_ref().map_or("unrated", |s| s.as_str());
+            prompt.push_str(&format!("- [{severity}] {}", cluster.summary));
+            if let Some(ref file) = cluster.file_path {
+                prompt.push_str(&format!(" ({file})"));
+            }
+            prompt.push_str(&format!(
+                " — reported by {}/{responded} models\n",
+                cluster.models.len()
+            ));
+        }
+
+        let provider_req = ProviderRequest {
+            prompt: prompt.into(),
+            model: model.to_string(),
+            deadline: Instant::now() + Duration::from_secs(AUTO_MEMORIZE_TIMEOUT_SECS),
+            working_directory: None,
+            system_prompt: Some(AUTO_MEMORIZE_SYSTEM_PROMPT.to_string()),
+            temperature: Some(0.0),
+            max_tokens: Some(1024),
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: self.usage.clone(),
+        };
+        let result = self
+            .registry
+            .query(&provider_req)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut saved = Vec::new();
+        let metadata = HashMap::from([("source".to_string(), "auto".to_string())]);
+        for entry in parse_auto_memorize_entries(&result.text) {
+            match memory
+                .memorize(
+                    "pattern",
+                    &entry.content,
+                    None,
+                    Some(&entry.tags),
+                    scope,
+                    Some(&metadata),
+                )
+                .await
+            {
+                Ok(_) => saved.push(entry.content),
+                Err(e) => tracing::warn!("auto-memorize: skipped entry: {e}"),
+            }
+        }
+        Ok(saved)
+    }
+}
+
+/// Build a review prompt (file context + diff + request prompt) within `budget` bytes.
+/// Below `MAX_FILE_CONTEXT_BYTES`, files that don't fit are trimmed instead of skipped.
+pub async fn assemble_prompt(
+    req: &ReviewRequest,
+    workspace: Option<&Workspace>,
+    diff: Option<&str>,
+    budget: usize,
+) -> Result<(String, Option<FileContextResult>), SquallError> {
+    let mut prompt = req.prompt.clone();
+    let mut file_result = None;
+    if let Some(ref file_paths) = req.file_paths
+        && let Some(workspace) = workspace
+    {
+        let fmt = req.context_format.unwrap_or_default();
+        // When both file_paths and diff are provided, reserve MIN_DIFF_BUDGET
+        // for the diff so it's never starved by large file context.
+        let file_budget = if diff.is_some() {
+            budget.saturating_sub(context::MIN_DIFF_BUDGET.min(budget / 2))
+        } else {
+            budget
+        };
+        let trim = budget < context::MAX_FILE_CONTEXT_BYTES;
+        let result = workspace
+            .resolve_file_context(file_paths, file_budget, fmt, trim)
+            .await?;
+        if let Some(ref ctx) = result.context {
+            prompt = format!("{ctx}\n{prompt}");
+        }
+        file_result = Some(result);
+    }
+
+    // Inject diff context (shared budget with file context)
+    if let Some(diff_text) = diff {
+        let file_context_used = prompt.len() - req.prompt.len();
+        let diff_budget = budget.saturating_sub(file_context_used);
+        if let Some(wrapped) = context::wrap_diff_context(diff_text, diff_budget) {
+            prompt = format!("{wrapped}\n{prompt}");
+        }
+    }
+    Ok((prompt, file_result))
+}
+
+/// How long an assembled prompt is reused for the same review inputs.
+const PROMPT_CACHE_TTL: Duration = Duration::from_secs(120);
+
+/// Assembled prompts kept at once; the oldest is evicted past this.
+const MAX_CACHED_PROMPTS: usize = 16;
+
+/// Cache of assembled review prompts, so back-to-back reviews of the same files
+/// (a re-run, another model set, a follow-up with a different system prompt)
+/// don't re-read and re-render them. Keyed by everything `assemble_prompt` reads;
+/// a hit is served within `PROMPT_CACHE_TTL` and only while every requested file
+/// (and `.squallignore`) still has the size and mtime it was read with.
+#[derive(Default)]
+pub struct PromptCache {
+    inner: tokio::sync::Mutex<HashMap<u64, CachedPrompt>>,
+}
+
+struct CachedPrompt {
+    cached_at: Instant,
+    stamps: Vec<Option<(SystemTime, u64)>>,
+    prompt: Arc<str>,
+    file_result: Option<FileContextResult>,
+}
+
+impl PromptCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `assemble_prompt`, served from the cache when the inputs and files are
+    /// unchanged. Requests without `file_paths` are assembled directly: there is
+    /// nothing to re-read.
+    pub async fn assemble(
+        &self,
+        req: &ReviewRequest,
+        workspace: Option<&Workspace>,
+        diff: Option<&str>,
+        budget: usize,
+    ) -> Result<(Arc<str>, Option<FileContextResult>), SquallError> {
+        let (Some(file_paths), Some(ws)) = (req.file_paths.as_deref(), workspace) else {
+            let (prompt, file_result) = assemble_prompt(req, workspace, diff, budget).await?;
+            return Ok((Arc::from(prompt), file_result));
+        };
+        let key = prompt_cache_key(req, ws, diff, budget);
+        let stamps = file_stamps(file_paths, ws).await;
+        {
+            let guard = self.inner.lock().await;
+            if let Some(hit) = guard.get(&key)
+                && hit.cached_at.elapsed() < PROMPT_CACHE_TTL
+                && hit.stamps == stamps
+            {
+                return Ok((hit.prompt.clone(), hit.file_result.clone()));
+            }
+        }
+        // Lock released while reading files.
+        let (prompt, file_result) = assemble_prompt(req, workspace, diff, budget).await?;
+        let prompt: Arc<str> = Arc::from(prompt);
+
+        let mut guard = self.inner.lock().await;
+        guard.retain(|_, entry| entry.cached_at.elapsed() < PROMPT_CACHE_TTL);
+        if guard.len() >= MAX_CACHED_PROMPTS
+            && let Some(oldest) = guard
+                .iter()
+                .min_by_key(|(_, entry)| entry.cached_at)
+                .map(|(key, _)| *key)
+        {
+            guard.remove(&oldest);
+        }
+        guard.insert(
+            key,
+            CachedPrompt {
+                cached_at: Instant::now(),
+                stamps,
+                prompt: prompt.clone(),
+                file_result: file_result.clone(),
+            },
+        );
+        Ok((prompt, file_result))
+    }
+}
+
+/// Hash of every input `assemble_prompt` renders from.
+fn prompt_cache_key(
+    req: &ReviewRequest,
+    workspace: &Workspace,
+    diff: Option<&str>,
+    budget: usize,
+) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::hash::DefaultHasher::new();
+    req.prompt.hash(&mut hasher);
+    req.file_paths.hash(&mut hasher);
+    req.context_format.unwrap_or_default().hash(&mut hasher);
+    for repo in workspace.repos() {
+        repo.root.hash(&mut hasher);
+    }
+    diff.hash(&mut hasher);
+    budget.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Modification time and size of each requested file and of each repository's
+/// `.squallignore` (None where unreadable), for telling whether a cached prompt
+/// is stale.
+async fn file_stamps(
+    file_paths: &[String],
+    workspace: &Workspace,
+) -> Vec<Option<(SystemTime, u64)>> {
+    let files = file_paths.iter().map(|p| {
+        let p = crate::documents::split_page_selector(p).map_or(p.as_str(), |(path, _)| path);
+        workspace.locate(p).map(|(repo, rel)| repo.root.join(rel))
+    });
+    let ignores = workspace
+        .repos()
+        .iter()
+        .map(|repo| Some(repo.root.join(context::SQUALLIGNORE_FILE)));
+    let mut stamps = Vec::with_capacity(file_paths.len() + workspace.repos().len());
+    for path in files.chain(ignores) {
+        let meta = match path {
+            Some(path) => tokio::fs::metadata(path).await.ok(),
+            None => None,
+        };
+        stamps.push(meta.and_then(|m| Some((m.modified().ok()?, m.len()))));
+    }
+    stamps
+}
+
+/// `injection_suspected` warnings for a review's file context and diff.
+pub fn injection_warnings(
+    file_result: Option<&FileContextResult>,
+    diff: Option<&str>,
+) -> Vec<String> {
+    let files = file_result.map_or(&[][..], |r| &r.injection_suspected[..]);
+    let diff_hits = diff.map(crate::injection::scan).unwrap_or_default();
+    files
+        .iter()
+        .cloned()
+        .chain(diff_hits.into_iter().map(|hit| format!("diff: {hit}")))
+        .map(|hit| format!("injection_suspected: {hit}"))
+        .collect()
+}
+
+/// Files included in a prompt, for `ReviewResponse::files_sent`.
+pub fn files_sent(result: &FileContextResult) -> Vec<String> {
+    result
+        .included
+        .iter()
+        .map(|name| {
+            if result.trimmed.contains(name) {
+                format!("{name} (trimmed)")
+            } else {
+                name.clone()
+            }
+        })
+        .collect()
+}
+
+/// Word overlap (see `findings::summary_similarity`) at which two responses
+/// count as the same review.
+pub const DUPLICATE_RESPONSE_SIMILARITY: f64 = 0.9;
+
+/// Point near-identical responses at the first model that gave them, so the
+/// markdown shows each distinct review once. Only complete successful
+/// responses are compared; a duplicate is only ever matched to a response that
+/// is not itself a duplicate.
+pub fn mark_duplicate_responses(results: &mut [ReviewModelResult]) {
+    for i in 1..results.len() {
+        let (earlier, rest) = results.split_at_mut(i);
+        let result = &mut rest[0];
+        let Some(text) = result
+            .response
+            .as_deref()
+            .filter(|_| result.status == ModelStatus::Success && !result.partial)
+        else {
+            continue;
+        };
+        let primary = earlier.iter().find(|other| {
+            other.status == ModelStatus::Success
+                && !other.partial
+                && other.duplicate_of.is_none()
+                && other.response.as_deref().is_some_and(|o| {
+                    crate::findings::summary_similarity(o, text) >= DUPLICATE_RESPONSE_SIMILARITY
+                })
+        });
+        if let Some(primary) = primary {
+            result.duplicate_of = Some(primary.model.clone());
+        }
+    }
+}
+
+/// Deadline for the auto-memorize model call (it runs after the review, before returning).
+const AUTO_MEMORIZE_TIMEOUT_SECS: u64 = 60;
+
+/// Finding clusters sent to the auto-memorize model (highest consensus first).
+const AUTO_MEMORIZE_MAX_FINDINGS: usize = 20;
+
+/// Upper bound on patterns saved per review by auto-memorize.
+pub const AUTO_MEMORIZE_MAX_ENTRIES: usize = 3;
+
+const AUTO_MEMORIZE_SYSTEM_PROMPT: &str = "You curate a code review memory. From the findings, \
+    propose at most 3 durable, reusable lessons about this codebase (recurring bug classes, \
+    risky modules, conventions that were violated) — not one-off typos. Reply with ONLY a JSON \
+    array of objects {\"content\": string (one sentence, under 300 characters), \"tags\": \
+    [string]}. Reply [] if nothing is worth remembering.";
+
+/// A pattern proposed by the auto-memorize model.
+#[derive(Debug, serde::Deserialize)]
+pub struct AutoMemoryEntry {
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Parse the auto-memorize model's reply into at most `AUTO_MEMORIZE_MAX_ENTRIES`
+/// entries. Tolerates prose or code fences around the JSON array; anything
+/// unparseable yields no entries. Each entry gets an `auto` tag.
+pub fn parse_auto_memorize_entries(text: &str) -> Vec<AutoMemoryEntry> {
+    let (Some(start), Some(end)) = (text.find('['), text.rfind(']')) else {
+        return Vec::new();
+    };
+    if end <= start {
+        return Vec::new();
+    }
+    let Ok(entries) = serde_json::from_str::<Vec<AutoMemoryEntry>>(&text[start..=end]) else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter(|e| {
+            !e.content.trim().is_empty()
+                && e.content.len() <= crate::memory::MAX_MEMORIZE_CONTENT_LEN
+        })
+        .take(AUTO_MEMORIZE_MAX_ENTRIES)
+        .map(|mut e| {
+            if !e.tags.iter().any(|t| t == "auto") {
+                e.tags.push("auto".to_string());
+            }
+            e
+        })
+        .collect()
 }
 
 /// Build a `ReviewModelResult` from a query outcome.
-/// Partial results (from cooperative cancellation) are still Success with `reason: "partial"`.
+/// Partial results (from cooperative cancellation) are still Success with `reason: "partial"`;
+/// responses cut at the model's output cap get `reason: "output_cap"`.
 pub fn collect_result(
     query_result: Result<crate::dispatch::ProviderResult, SquallError>,
     model_id: String,
@@ -651,18 +2332,38 @@ pub fn collect_result(
 ) -> ReviewModelResult {
     match query_result {
         Ok(pr) => ReviewModelResult {
+            // Before `pr.text` moves into `response`.
+            usable: pr.partial.then(|| crate::partial::usable(&pr.text)),
             model: model_id,
             provider: pr.provider,
             status: ModelStatus::Success,
             response: Some(pr.text),
             error: None,
-            reason: if pr.partial {
+            reason: if pr.output_capped {
+                Some("output_cap".to_string())
+            } else if pr.partial {
                 Some("partial".to_string())
             } else {
                 None
             },
             latency_ms,
             partial: pr.partial,
+            timing: pr.timing,
+            quotes_checked: 0,
+            quality_flags: pr
+                .secrets_redacted
+                .iter()
+                .map(|kind| QualityFlag::SecretRedacted {
+                    secret: kind.to_string(),
+                })
+                .collect(),
+            format_compliance: None,
+            rubric: None,
+            usage: pr.usage,
+            provider_metadata: pr.provider_metadata,
+            late: false,
+            repaired: false,
+            duplicate_of: None,
         },
         Err(e) => ReviewModelResult {
             model: model_id,
@@ -673,6 +2374,23 @@ pub fn collect_result(
             reason: Some(error_reason(&e)),
             latency_ms,
             partial: false,
+            timing: DispatchTiming {
+                queue_ms: match e {
+                    SquallError::QueuedTimeout(ms) => Some(ms),
+                    _ => None,
+                },
+                ..Default::default()
+            },
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         },
     }
 }
@@ -684,27 +2402,148 @@ fn collect_panic(
     completed_models: &mut HashSet<String>,
     results: &mut Vec<ReviewModelResult>,
     start: &Instant,
+    progress: Option<&UnboundedSender<ReviewModelResult>>,
 ) {
     tracing::error!("review task panicked: {join_err}");
     if let Some((model_id, provider)) = task_model_map.get(&join_err.id()) {
         completed_models.insert(model_id.clone());
-        results.push(ReviewModelResult {
-            model: model_id.clone(),
-            provider: provider.clone(),
-            status: ModelStatus::Error,
-            response: None,
-            error: Some(format!("task panicked: {join_err}")),
-            reason: Some("panic".to_string()),
-            latency_ms: start.elapsed().as_millis() as u64,
-            partial: false,
-        });
+        record(
+            results,
+            ReviewModelResult {
+                model: model_id.clone(),
+                provider: provider.clone(),
+                status: ModelStatus::Error,
+                response: None,
+                error: Some(format!("task panicked: {join_err}")),
+                reason: Some("panic".to_string()),
+                latency_ms: start.elapsed().as_millis() as u64,
+                partial: false,
+                timing: DispatchTiming::default(),
+                quotes_checked: 0,
+                quality_flags: Vec::new(),
+                format_compliance: None,
+                rubric: None,
+                usage: None,
+                provider_metadata: Default::default(),
+                late: false,
+                usable: None,
+                repaired: false,
+                duplicate_of: None,
+            },
+            progress,
+        );
+    }
+}
+
+/// Collect background stragglers as they finish and write each result into the
+/// persisted review at `results_file`, marked `late`, then report it on `notify`.
+async fn finish_stragglers(
+    mut set: JoinSet<(
+        String,
+        String,
+        Result<crate::dispatch::ProviderResult, SquallError>,
+        u64,
+    )>,
+    task_model_map: HashMap<TaskId, (String, String)>,
+    results_file: PathBuf,
+    start: Instant,
+    notify: Option<UnboundedSender<ReviewModelResult>>,
+) {
+    while let Some(join_result) = set.join_next().await {
+        let mut late = Vec::new();
+        match join_result {
+            Ok((model_id, provider, query_result, latency_ms)) => {
+                late.push(collect_result(query_result, model_id, provider, latency_ms));
+            }
+            Err(join_err) if join_err.is_panic() => {
+                let mut completed = HashSet::new();
+                collect_panic(
+                    &join_err,
+                    &task_model_map,
+                    &mut completed,
+                    &mut late,
+                    &start,
+                    None,
+                );
+            }
+            Err(_) => continue,
+        }
+        for mut result in late {
+            result.late = true;
+            match append_late_result(&results_file, &result).await {
+                Ok(()) => {
+                    tracing::info!(
+                        model = %result.model,
+                        "late result appended to {}",
+                        results_file.display()
+                    );
+                    if let Some(ref tx) = notify {
+                        let _ = tx.send(result);
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    model = %result.model,
+                    "failed to append late result to {}: {e}",
+                    results_file.display()
+                ),
+            }
+        }
+    }
+}
+
+/// Replace `result.model`'s `background` placeholder in the persisted review at
+/// `path` with its late result. The file keeps its encoding, so its path stays valid.
+async fn append_late_result(path: &Path, result: &ReviewModelResult) -> std::io::Result<()> {
+    let json = crate::artifact::read_review(path).await?;
+    let mut payload: serde_json::Value =
+        serde_json::from_str(&json).map_err(std::io::Error::other)?;
+    let late = serde_json::to_value(result).map_err(std::io::Error::other)?;
+    let Some(results) = payload
+        .get_mut("results")
+        .and_then(serde_json::Value::as_array_mut)
+    else {
+        return Err(std::io::Error::other("review file has no results"));
+    };
+    match results
+        .iter_mut()
+        .find(|r| r["model"] == result.model.as_str() && r["reason"] == "background")
+    {
+        Some(slot) => *slot = late,
+        None => results.push(late),
+    }
+
+    let json = serde_json::to_string_pretty(&payload).map_err(std::io::Error::other)?;
+    let bytes = crate::artifact::encode_like(path, &json)?;
+    let tmp_path = path.with_extension("late.tmp");
+    if let Err(e) = tokio::fs::write(&tmp_path, &bytes).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Keep a model's result, forwarding a copy to a streaming consumer if there is one.
+fn record(
+    results: &mut Vec<ReviewModelResult>,
+    result: ReviewModelResult,
+    progress: Option<&UnboundedSender<ReviewModelResult>>,
+) {
+    if let Some(tx) = progress {
+        // A dropped receiver just means nobody is watching any more.
+        let _ = tx.send(result.clone());
     }
+    results.push(result);
 }
 
 /// Classify a SquallError into a reason string for the review response.
 fn error_reason(e: &SquallError) -> String {
     match e {
         SquallError::Timeout(_) => "timeout".to_string(),
+        SquallError::QueuedTimeout(_) => "queued_timeout".to_string(),
         SquallError::Cancelled(_) => "cutoff".to_string(),
         SquallError::RateLimited { .. } => "rate_limited".to_string(),
         SquallError::AuthFailed { .. } => "auth_failed".to_string(),
@@ -715,40 +2554,70 @@ fn error_reason(e: &SquallError) -> String {
     }
 }
 
-/// Write review response to `.squall/reviews/{timestamp}_{pid}_{seq}.json`.
-/// Uses epoch millis + PID + atomic counter for filename uniqueness across
-/// concurrent invocations and concurrent processes.
-///
-/// Persists the full ReviewResponse plus optional investigation_context
-/// (which lives on the request, not the response).
-async fn persist_response(
-    response: &ReviewResponse,
-    investigation_context: Option<&str>,
-) -> Result<String, std::io::Error> {
-    let reviews_dir = PathBuf::from(".squall/reviews");
-    tokio::fs::create_dir_all(&reviews_dir).await?;
+/// `{epoch_ms}_{pid}_{seq}`: sorts by start time and never repeats across processes.
+/// Memory scope for what a review logs and learns: `branch:<name>` when it ran on
+/// a branch (`commit:<sha>` when detached), so `flush` can graduate it after the
+/// merge. None outside git, leaving the store's default.
+pub fn memory_scope(git: Option<&context::GitContext>) -> Option<String> {
+    git.map(|git| context::default_scope_from_git(Some(git)))
+}
 
+/// Trimmed, non-empty, deduplicated (case-insensitively, first spelling wins) and
+/// capped at `MAX_REVIEW_TAGS` of `MAX_REVIEW_TAG_CHARS` each.
+pub fn review_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.iter()
+        .map(|t| {
+            t.trim()
+                .chars()
+                .take(MAX_REVIEW_TAG_CHARS)
+                .collect::<String>()
+        })
+        .filter(|t| !t.is_empty() && seen.insert(t.to_lowercase()))
+        .take(MAX_REVIEW_TAGS)
+        .collect()
+}
+
+fn new_review_id() -> String {
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis();
-    let pid = std::process::id();
     let seq = PERSIST_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let filename = format!("{ts}_{pid}_{seq}.json");
-    let path = reviews_dir.join(&filename);
+    format!("{ts}_{}_{seq}", std::process::id())
+}
 
-    // Serialize the response, then merge in investigation_context if present.
+/// Write review response to `{reviews_dir}/{review_id}.json`
+/// (`.json.zst` when large — see `crate::artifact`) and return that path. The
+/// review ID is unique across concurrent invocations and concurrent processes.
+///
+/// Persists the full ReviewResponse plus the prompt and optional
+/// investigation_context (which live on the request, not the response).
+async fn persist_response(
+    reviews_dir: &Path,
+    response: &ReviewResponse,
+    prompt: &str,
+    investigation_context: Option<&str>,
+) -> Result<String, std::io::Error> {
+    tokio::fs::create_dir_all(reviews_dir).await?;
+    let id = &response.review_id;
+
+    // Serialize the response, then merge in the request-side fields.
     let mut payload = serde_json::to_value(response).map_err(std::io::Error::other)?;
+    payload["prompt"] = serde_json::Value::String(prompt.to_string());
     if let Some(ctx) = investigation_context {
         payload["investigation_context"] = serde_json::Value::String(ctx.to_string());
     }
 
     let json = serde_json::to_string_pretty(&payload).map_err(std::io::Error::other)?;
+    let (bytes, ext) = crate::artifact::encode(&json)?;
+    let filename = format!("{id}{ext}");
+    let path = reviews_dir.join(&filename);
 
     // Atomic write: temp file + rename prevents partial reads.
     // Clean up temp file on ANY failure (write or rename).
-    let tmp_path = path.with_extension("tmp");
-    if let Err(e) = tokio::fs::write(&tmp_path, json.as_bytes()).await {
+    let tmp_path = reviews_dir.join(format!("{id}.tmp"));
+    if let Err(e) = tokio::fs::write(&tmp_path, &bytes).await {
         let _ = tokio::fs::remove_file(&tmp_path).await;
         return Err(e);
     }
@@ -757,5 +2626,5 @@ async fn persist_response(
         return Err(e);
     }
 
-    Ok(format!(".squall/reviews/{filename}"))
+    Ok(path.display().to_string())
 }
@@ -0,0 +1,489 @@
+//! Model routing for the `classify` tool: turns a short task description into a
+//! recommended review setup (depth, models, lenses, timeout), so agent skills can
+//! delegate model selection to Squall instead of hard-coding lists.
+//!
+//! A fast model classifies the task into a depth and up to `MAX_LENSES` focus
+//! lenses. If it fails or answers with something unparseable, a keyword heuristic
+//! classifies instead. The policy then picks models:
+//!
+//! - quick: the fastest of the core ensemble (`[review] default_models`).
+//! - standard/deep: the core ensemble plus up to `EXTRA_MODELS` others, ranked by
+//!   how well their strengths match the lenses, then by memory success rate.
+//!
+//! Models the review hard gate would drop are excluded, and the timeout is
+//! stretched to cover the slowest selected model's p95 latency from memory.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::registry::ModelEntry;
+use crate::memory::ModelGateStats;
+use crate::review::{GateThresholds, MAX_TIMEOUT_SECS};
+use crate::tools::enums::{Depth, Lens};
+use crate::tools::review::ReviewRequest;
+
+/// Lenses kept from a classification.
+pub const MAX_LENSES: usize = 3;
+
+/// Models added to the core ensemble for standard and deep tasks.
+pub const EXTRA_MODELS: usize = 2;
+
+/// Deadline for the classifier call; the heuristic takes over after it.
+pub const CLASSIFY_TIMEOUT_SECS: u64 = 60;
+
+/// Headroom over the slowest selected model's p95 latency.
+const P95_TIMEOUT_FACTOR: f64 = 1.2;
+
+/// Task wording that marks a small, low-risk change.
+const QUICK_MARKERS: &[&str] = &["quick", "typo", "trivial", "rename", "small", "docs only"];
+
+/// Task wording that marks a high-stakes change.
+const DEEP_MARKERS: &[&str] = &[
+    "deep",
+    "audit",
+    "critical",
+    "thorough",
+    "production",
+    "exploit",
+];
+
+impl Lens {
+    /// Falsification-framed system prompt for this lens.
+    pub fn system_prompt(&self) -> &'static str {
+        match self {
+            Self::Security => {
+                "You are an adversarial tester. Attempt to PROVE the code fails under unusual inputs, injection attacks, or trust boundary violations. If it's robust, explain the defenses. Report confidence for each finding."
+            }
+            Self::Correctness => {
+                "You are a correctness enforcer. Attempt to PROVE logic errors, edge-case failures, or off-by-one bugs exist. Trace each path step by step. If the logic is sound, explain why. Report confidence for each finding."
+            }
+            Self::Concurrency => {
+                "You are a concurrency skeptic. Attempt to PROVE race conditions, resource leaks, or deadlocks exist. If you cannot find evidence, explain why the synchronization is correct. Report confidence (high/medium/low) for each finding."
+            }
+            Self::Defects => {
+                "You are a defect hunter. Attempt to PROVE null dereferences, missing validation, or common pitfalls exist. If the code handles these correctly, explain the safeguards. Report confidence for each finding."
+            }
+            Self::Architecture => {
+                "You are an architecture reviewer. Attempt to PROVE the change breaks module boundaries, leaks abstractions, or makes future changes harder. If the design holds up, explain why. Report confidence for each finding."
+            }
+            Self::Performance => {
+                "You are a performance skeptic. Attempt to PROVE the change adds avoidable allocations, blocking calls, or super-linear work on hot paths. If it is efficient, explain why. Report confidence for each finding."
+            }
+        }
+    }
+
+    /// Lowercase keywords matched against task text and model strengths.
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Self::Security => &[
+                "security",
+                "auth",
+                "crypto",
+                "session",
+                "permission",
+                "token",
+                "jwt",
+                "injection",
+                "secret",
+                "vulnerab",
+                "adversarial",
+                "edge cases",
+            ],
+            Self::Correctness => &[
+                "correct",
+                "logic",
+                "bug",
+                "off-by-one",
+                "invariant",
+                "algorithm",
+                "reasoning",
+            ],
+            Self::Concurrency => &[
+                "concurren",
+                "race",
+                "deadlock",
+                "async",
+                "thread",
+                "mutex",
+                "rwlock",
+                "atomic",
+                "parallel",
+                "systems",
+            ],
+            Self::Defects => &[
+                "null",
+                "validation",
+                "error handling",
+                "panic",
+                "unwrap",
+                "pitfall",
+                "defect",
+                "broad",
+            ],
+            Self::Architecture => &[
+                "architect",
+                "design",
+                "refactor",
+                "module",
+                "structure",
+                "coupling",
+            ],
+            Self::Performance => &[
+                "perf",
+                "latency",
+                "throughput",
+                "allocation",
+                "optimi",
+                "efficient",
+                "pattern",
+            ],
+        }
+    }
+
+    fn matches(&self, text: &str) -> usize {
+        self.keywords().iter().filter(|k| text.contains(*k)).count()
+    }
+}
+
+/// Depth and lenses for a task, and who decided them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub depth: Depth,
+    pub lenses: Vec<Lens>,
+    /// Classifier model name, or "heuristic".
+    pub classified_by: String,
+}
+
+/// Prompt asking the classifier model for a JSON classification of `task`.
+pub fn classify_prompt(task: &str) -> String {
+    let lenses: Vec<&str> = Lens::ALL.iter().map(Lens::as_str).collect();
+    format!(
+        "Classify this code review or analysis task for model routing. Reply with only a \
+         JSON object: {{\"depth\": \"quick\" | \"standard\" | \"deep\", \"lenses\": [...]}}.\n\
+         - quick: small, low-risk change (docs, renames, trivial fixes).\n\
+         - standard: a normal change.\n\
+         - deep: security-sensitive, concurrency-heavy, or critical infrastructure.\n\
+         lenses: 1-{MAX_LENSES} of {}, most important first.\n\n\
+         <task>\n{task}\n</task>",
+        lenses.join(", ")
+    )
+}
+
+#[derive(Deserialize)]
+struct RawClassification {
+    depth: Depth,
+    #[serde(default)]
+    lenses: Vec<String>,
+}
+
+/// Parse the classifier's answer: the first `{...}` span, with unknown lenses
+/// dropped. None if no valid depth can be read. Like the heuristic, a security
+/// lens lifts quick to standard.
+pub fn parse_classification(text: &str, model: &str) -> Option<Classification> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    let raw: RawClassification = serde_json::from_str(&text[start..=end]).ok()?;
+    let mut lenses = Vec::new();
+    for name in &raw.lenses {
+        let name = name.trim().to_lowercase();
+        if let Some(lens) = Lens::ALL.into_iter().find(|l| l.as_str() == name)
+            && !lenses.contains(&lens)
+        {
+            lenses.push(lens);
+        }
+    }
+    lenses.truncate(MAX_LENSES);
+    if lenses.is_empty() {
+        lenses.push(Lens::Correctness);
+    }
+    let depth = if raw.depth == Depth::Quick && lenses.contains(&Lens::Security) {
+        Depth::Standard
+    } else {
+        raw.depth
+    };
+    Some(Classification {
+        depth,
+        lenses,
+        classified_by: model.to_string(),
+    })
+}
+
+/// Keyword classification, used when the classifier model is unavailable.
+/// Security-sensitive tasks are never quick.
+pub fn heuristic(task: &str) -> Classification {
+    let text = task.to_lowercase();
+    let mut ranked: Vec<(usize, Lens)> = Lens::ALL
+        .into_iter()
+        .map(|l| (l.matches(&text), l))
+        .filter(|(n, _)| *n > 0)
+        .collect();
+    // Stable sort keeps Lens::ALL order on ties.
+    ranked.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+    let mut lenses: Vec<Lens> = ranked
+        .into_iter()
+        .map(|(_, l)| l)
+        .take(MAX_LENSES)
+        .collect();
+    if lenses.is_empty() {
+        lenses.push(Lens::Correctness);
+    }
+    let depth = if DEEP_MARKERS.iter().any(|m| text.contains(m)) {
+        Depth::Deep
+    } else if QUICK_MARKERS.iter().any(|m| text.contains(m)) && !lenses.contains(&Lens::Security) {
+        Depth::Quick
+    } else {
+        Depth::Standard
+    };
+    Classification {
+        depth,
+        lenses,
+        classified_by: "heuristic".to_string(),
+    }
+}
+
+/// Recommended review setup, ready to pass to `review`.
+#[derive(Debug, Serialize)]
+pub struct Recommendation {
+    pub depth: Depth,
+    pub lenses: Vec<Lens>,
+    pub models: Vec<String>,
+    /// One lens prompt per model, for `review`'s `per_model_system_prompts`.
+    pub per_model_system_prompts: BTreeMap<String, String>,
+    pub timeout_secs: u64,
+    pub deep: bool,
+    pub classified_by: String,
+    /// One line per selection decision.
+    pub rationale: Vec<String>,
+}
+
+pub(crate) fn speed_rank(tier: &str) -> u8 {
+    match tier {
+        "fast" => 0,
+        "medium" => 1,
+        "slow" => 2,
+        "very_slow" => 3,
+        _ => 1,
+    }
+}
+
+/// Lens matches against a model's strengths and description.
+fn strength_matches(entry: &ModelEntry, lens: Lens) -> usize {
+    let mut text = entry.description.to_lowercase();
+    for s in &entry.strengths {
+        text.push(' ');
+        text.push_str(&s.to_lowercase());
+    }
+    lens.matches(&text)
+}
+
+/// Apply the routing policy to a classification.
+///
+/// `models` is the registry's model list, `core` the configured default ensemble,
+/// `stats` per-model memory stats keyed by config name, and `gate` the hard-gate
+/// thresholds models must pass.
+pub fn recommend(
+    classification: Classification,
+    models: &[(&String, &ModelEntry)],
+    core: &[String],
+    stats: Option<&HashMap<String, ModelGateStats>>,
+    gate: &GateThresholds,
+) -> Recommendation {
+    let Classification {
+        depth,
+        lenses,
+        classified_by,
+    } = classification;
+    let entries: HashMap<&str, &ModelEntry> =
+        models.iter().map(|(k, e)| (k.as_str(), *e)).collect();
+    let stat = |name: &str| stats.and_then(|s| s.get(name));
+    let is_gated = |s: Option<&ModelGateStats>| s.is_some_and(|s| gate.excludes(s));
+    let mut rationale = Vec::new();
+
+    let mut core_ok = Vec::new();
+    for name in core {
+        if !entries.contains_key(name.as_str()) {
+            rationale.push(format!("{name}: skipped (not configured)"));
+        } else if let Some(s) = stat(name).filter(|s| is_gated(Some(s))) {
+            rationale.push(format!(
+                "{name}: skipped (gated, {:.0}% success over {} samples)",
+                s.success_rate * 100.0,
+                s.sample_count
+            ));
+        } else {
+            core_ok.push(name.as_str());
+        }
+    }
+
+    let mut selected: Vec<String> = Vec::new();
+    if depth == Depth::Quick {
+        // Fastest core model; fall back to the fastest configured model.
+        let pool: Vec<&str> = if core_ok.is_empty() {
+            let mut all: Vec<&str> = entries
+                .iter()
+                .filter(|(n, e)| !e.is_async_poll() && !is_gated(stat(n)))
+                .map(|(n, _)| *n)
+                .collect();
+            all.sort_unstable();
+            all
+        } else {
+            core_ok.clone()
+        };
+        if let Some(pick) = pool.into_iter().min_by(|a, b| {
+            let key = |n: &str| {
+                (
+                    speed_rank(&entries[n].speed_tier),
+                    stat(n)
+                        .and_then(ModelGateStats::p50_latency_secs)
+                        .unwrap_or(f64::MAX),
+                )
+            };
+            let (ra, la) = key(a);
+            let (rb, lb) = key(b);
+            ra.cmp(&rb).then(la.total_cmp(&lb))
+        }) {
+            rationale.push(format!("{pick}: fastest available model (quick)"));
+            selected.push(pick.to_string());
+        }
+    } else {
+        for name in &core_ok {
+            rationale.push(format!("{name}: core ensemble"));
+            selected.push(name.to_string());
+        }
+        let mut extras: Vec<(&str, usize, f64)> = entries
+            .iter()
+            .filter(|(n, e)| {
+                !core.iter().any(|c| c == *n) && !e.is_async_poll() && !is_gated(stat(n))
+            })
+            .map(|(n, e)| {
+                let fit: usize = lenses.iter().map(|l| strength_matches(e, *l)).sum();
+                // Untried models rank as if at the gate threshold.
+                let success = stat(n)
+                    .filter(|s| s.sample_count >= gate.min_samples)
+                    .map_or(gate.min_success_rate, |s| s.success_rate);
+                (*n, fit, success)
+            })
+            .collect();
+        extras.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.total_cmp(&a.2)).then(a.0.cmp(b.0)));
+        for (name, fit, success) in extras.into_iter().take(EXTRA_MODELS) {
+            rationale.push(format!(
+                "{name}: {} lens match{}, {:.0}% success",
+                fit,
+                if fit == 1 { "" } else { "es" },
+                success * 100.0
+            ));
+            selected.push(name.to_string());
+        }
+    }
+
+    // Each model gets the lens its strengths fit best, else lenses in rotation.
+    let mut per_model_system_prompts = BTreeMap::new();
+    for (i, name) in selected.iter().enumerate() {
+        let fallback = lenses[i % lenses.len()];
+        let lens = lenses
+            .iter()
+            .copied()
+            .max_by_key(|l| strength_matches(entries[name.as_str()], *l))
+            .filter(|l| strength_matches(entries[name.as_str()], *l) > 0)
+            .unwrap_or(fallback);
+        per_model_system_prompts.insert(name.clone(), lens.system_prompt().to_string());
+    }
+
+    let deep = depth == Depth::Deep;
+    let base = if deep {
+        ReviewRequest::DEEP_TIMEOUT_SECS
+    } else {
+        ReviewRequest::DEFAULT_TIMEOUT_SECS
+    };
+    let slowest_p95 = selected
+        .iter()
+        .filter_map(|n| stat(n).and_then(ModelGateStats::p95_latency_secs))
+        .fold(0.0_f64, f64::max);
+    let timeout_secs = base
+        .max((slowest_p95 * P95_TIMEOUT_FACTOR).ceil() as u64)
+        .min(MAX_TIMEOUT_SECS);
+    if timeout_secs > base {
+        rationale.push(format!(
+            "timeout {timeout_secs}s: covers slowest p95 latency ({slowest_p95:.0}s)"
+        ));
+    }
+
+    Recommendation {
+        depth,
+        lenses,
+        models: selected,
+        per_model_system_prompts,
+        timeout_secs,
+        deep,
+        classified_by,
+        rationale,
+    }
+}
+
+impl Recommendation {
+    pub fn to_markdown(&self) -> String {
+        let lenses: Vec<&str> = self.lenses.iter().map(Lens::as_str).collect();
+        let mut out = format!(
+            "## Routing: {} (classified by {})\n\n\
+             - **models**: {}\n\
+             - **lenses**: {}\n\
+             - **timeout_secs**: {}\n\
+             - **deep**: {}\n",
+            self.depth.as_str(),
+            self.classified_by,
+            self.models.join(", "),
+            lenses.join(", "),
+            self.timeout_secs,
+            self.deep,
+        );
+        if !self.rationale.is_empty() {
+            out.push_str("\n### Rationale\n");
+            for line in &self.rationale {
+                out.push_str(&format!("- {line}\n"));
+            }
+        }
+        out.push_str("\n### per_model_system_prompts\n");
+        for (model, prompt) in &self.per_model_system_prompts {
+            out.push_str(&format!("- **{model}**: {prompt}\n"));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_classification_and_drops_unknown_lenses() {
+        let text = "```json\n{\"depth\": \"deep\", \"lenses\": [\"Security\", \"vibes\", \
+                    \"security\", \"concurrency\"]}\n```";
+        let c = parse_classification(text, "grok").unwrap();
+        assert_eq!(c.depth, Depth::Deep);
+        assert_eq!(c.lenses, vec![Lens::Security, Lens::Concurrency]);
+        assert_eq!(c.classified_by, "grok");
+
+        let c = parse_classification("{\"depth\": \"quick\"}", "grok").unwrap();
+        assert_eq!(c.lenses, vec![Lens::Correctness]);
+        assert!(parse_classification("{\"depth\": \"extreme\"}", "grok").is_none());
+        assert!(parse_classification("standard, I think", "grok").is_none());
+    }
+
+    #[test]
+    fn heuristic_never_rates_security_work_quick() {
+        let c = heuristic("quick fix to the JWT session refresh");
+        assert_eq!(c.depth, Depth::Standard);
+        assert_eq!(c.lenses[0], Lens::Security);
+
+        let c = heuristic("fix a typo in the README");
+        assert_eq!(c.depth, Depth::Quick);
+        assert_eq!(c.lenses, vec![Lens::Correctness]);
+
+        let c = heuristic("audit the mutex handling in the async worker pool");
+        assert_eq!(c.depth, Depth::Deep);
+        assert_eq!(c.lenses, vec![Lens::Concurrency]);
+    }
+}
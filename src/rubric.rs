@@ -0,0 +1,294 @@
+//! Scoring rubrics: a judge model grades every fan-out response against
+//! weighted criteria. The weighted score (0.0-1.0) is returned per model and
+//! logged to memory, so a model's record says whether its answers were good,
+//! not only whether it answered.
+//!
+//! The judge sees the caller's question and one response at a time, and must
+//! reply with a JSON object scoring each criterion 0-10. Responses it fails to
+//! grade (error, timeout, a criterion left unscored) keep no score and are
+//! reported as warnings.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::ProviderRequest;
+use crate::dispatch::registry::Registry;
+use crate::tools::review::{ModelStatus, ReviewModelResult, Rubric};
+use crate::usage::UsageTag;
+
+/// Most criteria one rubric can have.
+pub const MAX_CRITERIA: usize = 10;
+
+/// Cutoff for the grading pass unless the rubric says otherwise.
+pub const DEFAULT_JUDGE_TIMEOUT_SECS: u64 = 120;
+
+/// Highest score the judge gives a criterion.
+pub const MAX_CRITERION_SCORE: f64 = 10.0;
+
+/// Response text beyond this is cut before grading.
+const MAX_GRADED_RESPONSE_BYTES: usize = 48 * 1024;
+
+const JUDGE_SYSTEM_PROMPT: &str = "You grade answers against a rubric. Score each \
+    criterion on its own, from 0 (absent or wrong) to 10 (excellent), judging only \
+    the answer you are given. Be strict: a confident answer is not a correct one.";
+
+/// A response's grade, as reported in `ReviewModelResult::rubric`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubricScore {
+    /// Weighted mean of the criteria scores, scaled to 0.0-1.0.
+    pub score: f64,
+    /// Per-criterion score, 0-10.
+    pub criteria: BTreeMap<String, f64>,
+    /// Model that graded the response.
+    pub judge: String,
+    /// The judge's one-line justification, if it gave one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Check names and weights: at least one criterion, names unique and non-empty,
+/// weights positive and finite.
+pub fn validate(rubric: &Rubric) -> Result<(), String> {
+    if rubric.judge.trim().is_empty() {
+        return Err("judge must name a model".to_string());
+    }
+    if rubric.criteria.is_empty() || rubric.criteria.len() > MAX_CRITERIA {
+        return Err(format!("needs 1-{MAX_CRITERIA} criteria"));
+    }
+    for (i, criterion) in rubric.criteria.iter().enumerate() {
+        let name = criterion.name.trim();
+        if name.is_empty() {
+            return Err(format!("criteria[{i}] has no name"));
+        }
+        if rubric.criteria[..i]
+            .iter()
+            .any(|c| c.name.trim().eq_ignore_ascii_case(name))
+        {
+            return Err(format!("criterion '{name}' is listed twice"));
+        }
+        if let Some(weight) = criterion.weight
+            && !(weight.is_finite() && weight > 0.0)
+        {
+            return Err(format!("criterion '{name}': weight must be positive"));
+        }
+    }
+    Ok(())
+}
+
+/// The judge's prompt for one response.
+pub fn judge_prompt(rubric: &Rubric, question: &str, response: &str) -> String {
+    let response =
+        &response[..crate::context::floor_char_boundary(response, MAX_GRADED_RESPONSE_BYTES)];
+    let mut prompt = format!(
+        "<question>\n{question}\n</question>\n\n<answer>\n{response}\n</answer>\n\nCriteria:\n"
+    );
+    for criterion in &rubric.criteria {
+        match criterion.description.as_deref().map(str::trim) {
+            Some(description) if !description.is_empty() => {
+                prompt.push_str(&format!("- {}: {description}\n", criterion.name.trim()))
+            }
+            _ => prompt.push_str(&format!("- {}\n", criterion.name.trim())),
+        }
+    }
+    let example: Vec<String> = rubric
+        .criteria
+        .iter()
+        .map(|c| format!("\"{}\": <0-10>", c.name.trim()))
+        .collect();
+    prompt.push_str(&format!(
+        "\nReply with ONLY a JSON object: {{\"scores\": {{{}}}, \"comment\": \"<one sentence>\"}}",
+        example.join(", ")
+    ));
+    prompt
+}
+
+#[derive(Deserialize)]
+struct Verdict {
+    scores: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    comment: Option<String>,
+}
+
+/// Read the judge's answer, tolerating prose or fences around the JSON. Every
+/// criterion must be scored; names match case-insensitively and scores are
+/// clamped to 0-10.
+pub fn parse_verdict(rubric: &Rubric, judge: &str, text: &str) -> Result<RubricScore, String> {
+    let verdict: Verdict = crate::json_repair::parse(text)
+        .and_then(|(doc, _)| serde_json::from_value(doc).ok())
+        .ok_or("judge did not reply with a scores object")?;
+
+    let mut criteria = BTreeMap::new();
+    let mut weighted = 0.0;
+    let mut total_weight = 0.0;
+    for criterion in &rubric.criteria {
+        let name = criterion.name.trim();
+        let score = verdict
+            .scores
+            .iter()
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| match v {
+                serde_json::Value::Number(n) => n.as_f64(),
+                serde_json::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            })
+            .filter(|s| s.is_finite())
+            .ok_or_else(|| format!("judge left '{name}' unscored"))?
+            .clamp(0.0, MAX_CRITERION_SCORE);
+        let weight = criterion.weight.unwrap_or(1.0);
+        weighted += score * weight;
+        total_weight += weight;
+        criteria.insert(name.to_string(), score);
+    }
+    let score = weighted / total_weight / MAX_CRITERION_SCORE;
+    Ok(RubricScore {
+        score: (score * 100.0).round() / 100.0,
+        criteria,
+        judge: judge.to_string(),
+        comment: verdict
+            .comment
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty()),
+    })
+}
+
+/// Grade every complete response in `results` concurrently, setting
+/// `ReviewModelResult::rubric`. Returns a warning per response left ungraded.
+pub async fn grade(
+    registry: &Registry,
+    rubric: &Rubric,
+    question: &str,
+    results: &mut [ReviewModelResult],
+    usage: Option<UsageTag>,
+) -> Vec<String> {
+    let deadline = Instant::now()
+        + Duration::from_secs(
+            rubric
+                .timeout_secs
+                .unwrap_or(DEFAULT_JUDGE_TIMEOUT_SECS)
+                .min(crate::review::MAX_TIMEOUT_SECS),
+        );
+    let graded: Vec<usize> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.status == ModelStatus::Success && !r.partial && r.response.is_some())
+        .map(|(i, _)| i)
+        .collect();
+    let requests: Vec<ProviderRequest> = graded
+        .iter()
+        .map(|&i| ProviderRequest {
+            prompt: judge_prompt(
+                rubric,
+                question,
+                results[i].response.as_deref().unwrap_or_default(),
+            )
+            .into(),
+            model: rubric.judge.clone(),
+            deadline,
+            working_directory: None,
+            system_prompt: Some(JUDGE_SYSTEM_PROMPT.to_string()),
+            temperature: Some(0.0),
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: usage.clone(),
+        })
+        .collect();
+    let verdicts = futures_util::future::join_all(requests.iter().map(|r| registry.query(r))).await;
+
+    let mut warnings = Vec::new();
+    for (i, verdict) in graded.into_iter().zip(verdicts) {
+        let result = &mut results[i];
+        match verdict
+            .map_err(|e| e.user_message())
+            .and_then(|r| parse_verdict(rubric, &rubric.judge, &r.text))
+        {
+            Ok(score) => result.rubric = Some(score),
+            Err(e) => warnings.push(format!("rubric: {} was not graded: {e}", result.model)),
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::review::RubricCriterion;
+
+    fn rubric() -> Rubric {
+        Rubric {
+            criteria: vec![
+                RubricCriterion {
+                    name: "correctness".to_string(),
+                    description: Some("Claims about the code are true".to_string()),
+                    weight: Some(3.0),
+                },
+                RubricCriterion {
+                    name: "actionability".to_string(),
+                    description: None,
+                    weight: None,
+                },
+            ],
+            judge: "judge".to_string(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn verdicts_are_weighted_and_tolerant() {
+        let rubric = rubric();
+        let score = parse_verdict(
+            &rubric,
+            "judge",
+            "Sure:\n```json\n{\"scores\": {\"Correctness\": 8, \"actionability\": \"4\"}, \"comment\": \" Mostly right. \"}\n```",
+        )
+        .unwrap();
+        // (8*3 + 4*1) / 4 / 10
+        assert_eq!(score.score, 0.7);
+        assert_eq!(score.criteria["correctness"], 8.0);
+        assert_eq!(score.comment.as_deref(), Some("Mostly right."));
+
+        let clamped = parse_verdict(
+            &rubric,
+            "judge",
+            r#"{"scores": {"correctness": 14, "actionability": -2}}"#,
+        )
+        .unwrap();
+        assert_eq!(clamped.score, 0.75);
+
+        let err = parse_verdict(&rubric, "judge", r#"{"scores": {"correctness": 9}}"#).unwrap_err();
+        assert!(err.contains("actionability"), "{err}");
+        assert!(parse_verdict(&rubric, "judge", "Looks good to me.").is_err());
+    }
+
+    #[test]
+    fn validate_rejects_bad_rubrics() {
+        assert!(validate(&rubric()).is_ok());
+
+        let mut dup = rubric();
+        dup.criteria[1].name = "CORRECTNESS".to_string();
+        assert!(validate(&dup).unwrap_err().contains("twice"));
+
+        let mut weight = rubric();
+        weight.criteria[0].weight = Some(0.0);
+        assert!(validate(&weight).is_err());
+
+        let mut empty = rubric();
+        empty.criteria.clear();
+        assert!(validate(&empty).is_err());
+    }
+
+    #[test]
+    fn judge_prompt_lists_every_criterion() {
+        let prompt = judge_prompt(&rubric(), "Is this safe?", "No: the lock is dropped early.");
+        assert!(prompt.contains("- correctness: Claims about the code are true\n"));
+        assert!(prompt.contains("- actionability\n"));
+        assert!(prompt.contains("\"actionability\": <0-10>"));
+        assert!(prompt.contains("<answer>\nNo: the lock is dropped early.\n</answer>"));
+    }
+}
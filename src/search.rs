@@ -0,0 +1,777 @@
+//! Keyword search over persisted reviews (`.squall/reviews/*.json`) for the
+//! `reviews_search` tool, and the filtered listing behind `reviews_list`.
+//!
+//! Each review is split into documents — the request prompt, investigation
+//! notes, every extracted finding, and every model response — and indexed in
+//! memory on first search. Later searches re-read only review files that are
+//! new or changed, so the index stays current without a file watcher.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use tokio::sync::Mutex;
+
+use crate::findings::extract_findings;
+use crate::memory::{date_to_days, iso_from_epoch_ms};
+
+/// Default number of hits returned.
+pub const DEFAULT_MAX_RESULTS: usize = 10;
+
+/// Upper bound on `max_results`.
+pub const MAX_RESULTS_LIMIT: usize = 50;
+
+/// Characters of context shown on each side of the first match.
+const SNIPPET_RADIUS: usize = 160;
+
+/// Characters of the request prompt shown per review in a listing.
+const PROMPT_HEAD_CHARS: usize = 120;
+
+/// Filters for a search. Dates are inclusive `YYYY-MM-DD` (UTC).
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub query: String,
+    /// Only hits from these models (config keys, case-insensitive). Empty = all.
+    /// Prompts and investigation notes have no model and are excluded when set.
+    pub models: Vec<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Only reviews taken on this branch.
+    pub branch: Option<String>,
+    /// Only reviews carrying every one of these tags (case-insensitive).
+    pub tags: Vec<String>,
+    pub max_results: usize,
+}
+
+impl SearchQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if parse_terms(&self.query).is_empty() {
+            return Err("query must contain at least one search term".to_string());
+        }
+        validate_dates(&self.since, &self.until)
+    }
+
+    fn filter(&self) -> ReviewFilter {
+        ReviewFilter::new(&self.since, &self.until, &self.branch, &self.tags)
+    }
+}
+
+/// Filters for a listing; same semantics as in `SearchQuery`.
+#[derive(Debug, Default)]
+pub struct ListQuery {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub branch: Option<String>,
+    pub tags: Vec<String>,
+    pub max_results: usize,
+}
+
+impl ListQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_dates(&self.since, &self.until)
+    }
+
+    fn filter(&self) -> ReviewFilter {
+        ReviewFilter::new(&self.since, &self.until, &self.branch, &self.tags)
+    }
+}
+
+fn validate_dates(since: &Option<String>, until: &Option<String>) -> Result<(), String> {
+    for (name, date) in [("since", since), ("until", until)] {
+        if let Some(date) = date
+            && (date.len() != 10 || date_to_days(date).is_none())
+        {
+            return Err(format!("{name} must be a YYYY-MM-DD date, got '{date}'"));
+        }
+    }
+    Ok(())
+}
+
+/// Review-level filters shared by search and listing.
+struct ReviewFilter {
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+    branch: Option<String>,
+    tags: Vec<String>,
+}
+
+impl ReviewFilter {
+    fn new(
+        since: &Option<String>,
+        until: &Option<String>,
+        branch: &Option<String>,
+        tags: &[String],
+    ) -> Self {
+        Self {
+            since_ms: since
+                .as_deref()
+                .and_then(date_to_days)
+                .map(|d| d * 86_400_000),
+            until_ms: until
+                .as_deref()
+                .and_then(date_to_days)
+                .map(|d| (d + 1) * 86_400_000),
+            branch: branch.clone(),
+            tags: tags.iter().map(|t| t.trim().to_lowercase()).collect(),
+        }
+    }
+
+    fn matches(&self, review: &IndexedReview) -> bool {
+        let meta = &review.meta;
+        self.since_ms.is_none_or(|s| review.ts_ms >= s)
+            && self.until_ms.is_none_or(|u| review.ts_ms < u)
+            && self
+                .branch
+                .as_ref()
+                .is_none_or(|b| meta.branch.as_ref() == Some(b))
+            && self
+                .tags
+                .iter()
+                .all(|t| meta.tags.iter().any(|have| have.to_lowercase() == *t))
+    }
+}
+
+/// Where in a review a hit was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    Prompt,
+    Investigation,
+    Finding,
+    Response,
+}
+
+impl DocKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::Investigation => "investigation notes",
+            Self::Finding => "finding",
+            Self::Response => "response",
+        }
+    }
+}
+
+/// One matching document.
+#[derive(Debug)]
+pub struct SearchHit {
+    /// Review file as reported in `results_file`.
+    pub review_file: String,
+    pub ts_ms: u64,
+    pub branch: Option<String>,
+    pub tags: Vec<String>,
+    pub kind: DocKind,
+    pub model: Option<String>,
+    /// Finding heading (with severity) for `DocKind::Finding`.
+    pub title: Option<String>,
+    pub snippet: String,
+    pub score: usize,
+}
+
+/// Result of a search: ranked hits plus how many matched in total.
+#[derive(Debug)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub total_matches: usize,
+    pub reviews_indexed: usize,
+}
+
+/// One review in a listing.
+#[derive(Debug)]
+pub struct ReviewEntry {
+    /// Review file as reported in `results_file`.
+    pub review_file: String,
+    pub ts_ms: u64,
+    pub branch: Option<String>,
+    pub tags: Vec<String>,
+    /// Start of the request prompt, on one line.
+    pub prompt_head: Option<String>,
+    pub models_succeeded: usize,
+    pub models_total: usize,
+}
+
+/// Result of a listing: newest reviews first, plus how many matched in total.
+#[derive(Debug)]
+pub struct ReviewList {
+    pub reviews: Vec<ReviewEntry>,
+    pub total_matches: usize,
+    pub reviews_indexed: usize,
+}
+
+struct Doc {
+    kind: DocKind,
+    model: Option<String>,
+    title: Option<String>,
+    text: String,
+    lower: String,
+}
+
+struct IndexedReview {
+    modified: Option<SystemTime>,
+    len: u64,
+    ts_ms: u64,
+    meta: ReviewMeta,
+    docs: Vec<Doc>,
+}
+
+/// Review-level fields read from the review file.
+struct ReviewMeta {
+    branch: Option<String>,
+    tags: Vec<String>,
+    prompt_head: Option<String>,
+    models_succeeded: usize,
+    models_total: usize,
+}
+
+/// In-memory index of persisted reviews, refreshed incrementally per search.
+#[derive(Default)]
+pub struct ReviewIndex {
+    reviews: Mutex<HashMap<PathBuf, IndexedReview>>,
+}
+
+impl ReviewIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refresh the index from `reviews_dir`, then run `query` against it.
+    pub async fn search(&self, reviews_dir: &Path, query: &SearchQuery) -> SearchResults {
+        let mut reviews = self.reviews.lock().await;
+        refresh(&mut reviews, reviews_dir).await;
+
+        let terms = parse_terms(&query.query);
+        let models: HashSet<String> = query.models.iter().map(|m| m.to_lowercase()).collect();
+        let filter = query.filter();
+
+        let mut hits = Vec::new();
+        for (path, review) in reviews.iter() {
+            if !filter.matches(review) {
+                continue;
+            }
+            let mut review_hits: Vec<SearchHit> = Vec::new();
+            for doc in &review.docs {
+                if !models.is_empty()
+                    && !doc
+                        .model
+                        .as_ref()
+                        .is_some_and(|m| models.contains(&m.to_lowercase()))
+                {
+                    continue;
+                }
+                let Some(score) = score(&doc.lower, &terms) else {
+                    continue;
+                };
+                review_hits.push(SearchHit {
+                    review_file: path.to_string_lossy().to_string(),
+                    ts_ms: review.ts_ms,
+                    branch: review.meta.branch.clone(),
+                    tags: review.meta.tags.clone(),
+                    kind: doc.kind,
+                    model: doc.model.clone(),
+                    title: doc.title.clone(),
+                    snippet: snippet(&doc.text, &doc.lower, &terms[0]),
+                    score,
+                });
+            }
+            // A matching finding is the precise hit; drop the same model's response.
+            let finding_models: HashSet<Option<String>> = review_hits
+                .iter()
+                .filter(|h| h.kind == DocKind::Finding)
+                .map(|h| h.model.clone())
+                .collect();
+            review_hits
+                .retain(|h| h.kind != DocKind::Response || !finding_models.contains(&h.model));
+            hits.extend(review_hits);
+        }
+
+        let total_matches = hits.len();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.ts_ms.cmp(&a.ts_ms))
+                .then(a.review_file.cmp(&b.review_file))
+        });
+        hits.truncate(query.max_results.clamp(1, MAX_RESULTS_LIMIT));
+        SearchResults {
+            hits,
+            total_matches,
+            reviews_indexed: reviews.len(),
+        }
+    }
+
+    /// Refresh the index from `reviews_dir`, then list the reviews matching
+    /// `query`, newest first.
+    pub async fn list(&self, reviews_dir: &Path, query: &ListQuery) -> ReviewList {
+        let mut reviews = self.reviews.lock().await;
+        refresh(&mut reviews, reviews_dir).await;
+
+        let filter = query.filter();
+        let mut entries: Vec<ReviewEntry> = reviews
+            .iter()
+            .filter(|(_, review)| filter.matches(review))
+            .map(|(path, review)| ReviewEntry {
+                review_file: path.to_string_lossy().to_string(),
+                ts_ms: review.ts_ms,
+                branch: review.meta.branch.clone(),
+                tags: review.meta.tags.clone(),
+                prompt_head: review.meta.prompt_head.clone(),
+                models_succeeded: review.meta.models_succeeded,
+                models_total: review.meta.models_total,
+            })
+            .collect();
+        let total_matches = entries.len();
+        entries.sort_by(|a, b| {
+            b.ts_ms
+                .cmp(&a.ts_ms)
+                .then(a.review_file.cmp(&b.review_file))
+        });
+        entries.truncate(query.max_results.clamp(1, MAX_RESULTS_LIMIT));
+        ReviewList {
+            reviews: entries,
+            total_matches,
+            reviews_indexed: reviews.len(),
+        }
+    }
+}
+
+impl ReviewList {
+    pub fn to_markdown(&self) -> String {
+        let mut out = "## Reviews\n\n".to_string();
+        if self.reviews.is_empty() {
+            out.push_str(&format!(
+                "No matching reviews among {}.\n",
+                self.reviews_indexed
+            ));
+            return out;
+        }
+        out.push_str(&format!(
+            "{} of {} review(s) match; showing {}.\n\n",
+            self.total_matches,
+            self.reviews_indexed,
+            self.reviews.len()
+        ));
+        for review in &self.reviews {
+            let date = iso_from_epoch_ms(review.ts_ms as i64);
+            out.push_str(&format!(
+                "- {} `{}` · {}/{} models succeeded",
+                date[..16].replacen('T', " ", 1),
+                review.review_file,
+                review.models_succeeded,
+                review.models_total
+            ));
+            if let Some(branch) = &review.branch {
+                out.push_str(&format!(" · branch `{branch}`"));
+            }
+            push_tags(&mut out, &review.tags);
+            out.push('\n');
+            if let Some(head) = &review.prompt_head {
+                out.push_str(&format!("  > {head}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// ` · tags `a`, `b`` when there are any.
+fn push_tags(out: &mut String, tags: &[String]) {
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags.iter().map(|t| format!("`{t}`")).collect();
+        out.push_str(&format!(" · tags {}", tags.join(", ")));
+    }
+}
+
+impl SearchResults {
+    pub fn to_markdown(&self, query: &SearchQuery) -> String {
+        let mut out = format!("## Review Search: `{}`\n\n", query.query);
+        if self.hits.is_empty() {
+            out.push_str(&format!(
+                "No matches in {} review(s).\n",
+                self.reviews_indexed
+            ));
+            return out;
+        }
+        out.push_str(&format!(
+            "{} match(es) across {} review(s); showing {}.\n",
+            self.total_matches,
+            self.reviews_indexed,
+            self.hits.len()
+        ));
+        for hit in &self.hits {
+            let date = iso_from_epoch_ms(hit.ts_ms as i64);
+            let mut heading = format!("\n### {} — {}", &date[..10], hit.kind.as_str());
+            if let Some(model) = &hit.model {
+                heading.push_str(&format!(" ({model})"));
+            }
+            out.push_str(&heading);
+            out.push('\n');
+            out.push_str(&format!("`{}`", hit.review_file));
+            if let Some(branch) = &hit.branch {
+                out.push_str(&format!(" · branch `{branch}`"));
+            }
+            push_tags(&mut out, &hit.tags);
+            out.push('\n');
+            if let Some(title) = &hit.title {
+                out.push_str(&format!("**{title}**\n"));
+            }
+            out.push_str(&format!("> {}\n", hit.snippet.replace('\n', " ")));
+        }
+        out
+    }
+}
+
+/// Re-read new or changed review files; forget deleted ones.
+async fn refresh(reviews: &mut HashMap<PathBuf, IndexedReview>, reviews_dir: &Path) {
+    let mut seen = HashSet::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(reviews_dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Some(ts_ms) = review_timestamp(&path) else {
+                continue;
+            };
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            let modified = meta.modified().ok();
+            seen.insert(path.clone());
+            if reviews
+                .get(&path)
+                .is_some_and(|r| r.modified == modified && r.len == meta.len())
+            {
+                continue;
+            }
+            match index_review(&path).await {
+                Some((review_meta, docs)) => {
+                    reviews.insert(
+                        path,
+                        IndexedReview {
+                            modified,
+                            len: meta.len(),
+                            ts_ms,
+                            meta: review_meta,
+                            docs,
+                        },
+                    );
+                }
+                None => {
+                    reviews.remove(&path);
+                }
+            }
+        }
+    }
+    reviews.retain(|path, _| seen.contains(path));
+}
+
+/// Epoch millis from a review filename (`{ts_ms}_{pid}_{seq}.json`). None for
+/// findings sidecars and anything else.
+fn review_timestamp(path: &Path) -> Option<u64> {
+    let stem = crate::artifact::review_stem(path.file_name()?.to_str()?)?;
+    stem.split('_').next()?.parse().ok()
+}
+
+async fn index_review(path: &Path) -> Option<(ReviewMeta, Vec<Doc>)> {
+    let raw = crate::artifact::read_review(path).await.ok()?;
+    let review: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let results = review["results"].as_array().map_or(&[][..], Vec::as_slice);
+    let meta = ReviewMeta {
+        branch: review["git"]["branch"].as_str().map(str::to_string),
+        tags: review["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect(),
+        prompt_head: review["prompt"].as_str().and_then(prompt_head),
+        models_succeeded: results.iter().filter(|r| r["status"] == "success").count(),
+        models_total: results.len(),
+    };
+
+    let mut docs = Vec::new();
+    let mut push = |kind, model: Option<&str>, title: Option<String>, text: &str| {
+        if !text.trim().is_empty() {
+            docs.push(Doc {
+                kind,
+                model: model.map(str::to_string),
+                title,
+                lower: text.to_lowercase(),
+                text: text.to_string(),
+            });
+        }
+    };
+    if let Some(prompt) = review["prompt"].as_str() {
+        push(DocKind::Prompt, None, None, prompt);
+    }
+    if let Some(notes) = review["investigation_context"].as_str() {
+        push(DocKind::Investigation, None, None, notes);
+    }
+    for result in results {
+        let (Some(model), Some(text)) = (result["model"].as_str(), result["response"].as_str())
+        else {
+            continue;
+        };
+        for f in extract_findings(model, text) {
+            let severity = f.severity.as_ref().map_or("unrated", |s| s.as_str());
+            let body = match &f.file_path {
+                Some(file) => format!("{}\n{file}\n{}", f.summary, f.body),
+                None => format!("{}\n{}", f.summary, f.body),
+            };
+            push(
+                DocKind::Finding,
+                Some(model),
+                Some(format!("[{severity}] {}", f.summary)),
+                &body,
+            );
+        }
+        push(DocKind::Response, Some(model), None, text);
+    }
+    Some((meta, docs))
+}
+
+/// First non-blank line of a prompt, cut to `PROMPT_HEAD_CHARS`.
+fn prompt_head(prompt: &str) -> Option<String> {
+    let line = prompt.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let mut head: String = line.chars().take(PROMPT_HEAD_CHARS).collect();
+    if head.len() < line.len() {
+        head.push('…');
+    }
+    Some(head)
+}
+
+/// Split a query into lowercase terms; `"double quotes"` keep a phrase together.
+fn parse_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.trim().to_lowercase();
+            if !phrase.is_empty() {
+                terms.push(phrase);
+            }
+        } else {
+            terms.extend(part.split_whitespace().map(str::to_lowercase));
+        }
+    }
+    terms
+}
+
+/// Total occurrences of all terms, or None unless every term occurs.
+fn score(lower: &str, terms: &[String]) -> Option<usize> {
+    let mut total = 0;
+    for term in terms {
+        let n = lower.matches(term.as_str()).count();
+        if n == 0 {
+            return None;
+        }
+        total += n;
+    }
+    Some(total)
+}
+
+/// Text around the first occurrence of `term`, trimmed to char boundaries.
+fn snippet(text: &str, lower: &str, term: &str) -> String {
+    // Lowercasing can change byte lengths (rare scripts); fall back to the start.
+    let pos = if lower.len() == text.len() {
+        lower.find(term).unwrap_or(0)
+    } else {
+        0
+    };
+    let start = crate::context::floor_char_boundary(text, pos.saturating_sub(SNIPPET_RADIUS));
+    let end = crate::context::floor_char_boundary(text, pos + term.len() + SNIPPET_RADIUS);
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.push_str(text[start..end].trim());
+    if end < text.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_terms_keeps_quoted_phrases() {
+        assert_eq!(
+            parse_terms(r#"Auth "token refresh" race"#),
+            vec!["auth", "token refresh", "race"]
+        );
+        assert!(parse_terms("  \"\" ").is_empty());
+    }
+
+    #[test]
+    fn score_requires_every_term() {
+        let terms = parse_terms("auth token");
+        assert_eq!(score("auth token auth", &terms), Some(3));
+        assert_eq!(score("auth only", &terms), None);
+    }
+
+    #[test]
+    fn snippet_centers_on_match_at_char_boundaries() {
+        let text = format!("{}needle{}", "é".repeat(200), "x".repeat(300));
+        let lower = text.to_lowercase();
+        let s = snippet(&text, &lower, "needle");
+        assert!(s.starts_with('…') && s.ends_with('…'));
+        assert!(s.contains("needle"));
+    }
+
+    fn write_review(dir: &Path, ts_ms: u64, branch: &str, model: &str, response: &str) {
+        let review = serde_json::json!({
+            "prompt": "Review the session handling",
+            "git": { "branch": branch, "commit_sha": "abc123" },
+            "results": [
+                { "model": model, "status": "success", "response": response },
+            ],
+        });
+        std::fs::write(dir.join(format!("{ts_ms}_1_0.json")), review.to_string()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn search_filters_and_picks_up_new_reviews() {
+        let dir = std::env::temp_dir().join(format!(
+            "squall-test-search-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        // 2026-01-15 and 2026-02-15 (UTC noon)
+        let jan = 1_768_478_400_000;
+        let feb = 1_771_156_800_000;
+        write_review(
+            &dir,
+            jan,
+            "main",
+            "grok",
+            "### [high] Token refresh race in auth module\nTwo refreshes can interleave.",
+        );
+        write_review(
+            &dir,
+            feb,
+            "feature/x",
+            "gemini",
+            "The auth module looks fine.",
+        );
+
+        let index = ReviewIndex::new();
+        let query = |q: &str| SearchQuery {
+            query: q.to_string(),
+            max_results: DEFAULT_MAX_RESULTS,
+            ..Default::default()
+        };
+
+        let all = index.search(&dir, &query("auth module")).await;
+        assert_eq!(all.reviews_indexed, 2);
+        assert_eq!(all.total_matches, 2, "{all:?}");
+        // grok's finding replaces its response hit.
+        assert!(all.hits.iter().any(|h| h.kind == DocKind::Finding
+            && h.title.as_deref() == Some("[high] Token refresh race in auth module")));
+        assert!(
+            !all.hits
+                .iter()
+                .any(|h| h.kind == DocKind::Response && h.model.as_deref() == Some("grok"))
+        );
+
+        let by_model = SearchQuery {
+            models: vec!["Gemini".to_string()],
+            ..query("auth")
+        };
+        let hits = index.search(&dir, &by_model).await.hits;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].model.as_deref(), Some("gemini"));
+
+        let january = SearchQuery {
+            since: Some("2026-01-01".to_string()),
+            until: Some("2026-01-31".to_string()),
+            ..query("auth")
+        };
+        let hits = index.search(&dir, &january).await.hits;
+        assert!(hits.iter().all(|h| h.ts_ms == jan), "{hits:?}");
+
+        let prompt_hits = index.search(&dir, &query("\"session handling\"")).await;
+        assert_eq!(prompt_hits.total_matches, 2);
+        assert!(prompt_hits.hits.iter().all(|h| h.kind == DocKind::Prompt));
+
+        write_review(
+            &dir,
+            feb + 1,
+            "main",
+            "codex",
+            "Found a deadlock in the pool.",
+        );
+        let hits = index.search(&dir, &query("deadlock")).await;
+        assert_eq!(hits.reviews_indexed, 3);
+        assert_eq!(hits.total_matches, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn list_filters_by_tags_newest_first() {
+        let dir = std::env::temp_dir().join(format!("squall-test-list-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let jan = 1_768_478_400_000u64;
+        for (ts, tags, status) in [
+            (jan, vec!["PROJ-142", "pre-merge"], "success"),
+            (jan + 1, vec!["PROJ-142"], "error"),
+            (jan + 2, vec![], "success"),
+        ] {
+            let review = serde_json::json!({
+                "prompt": "\n  Review the retry loop\nin dispatch",
+                "git": { "branch": "main" },
+                "tags": tags,
+                "results": [{ "model": "grok", "status": status, "response": "ok" }],
+            });
+            std::fs::write(dir.join(format!("{ts}_1_0.json")), review.to_string()).unwrap();
+        }
+
+        let index = ReviewIndex::new();
+        let query = |tags: &[&str]| ListQuery {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            max_results: DEFAULT_MAX_RESULTS,
+            ..Default::default()
+        };
+        let all = index.list(&dir, &query(&[])).await;
+        assert_eq!(all.total_matches, 3);
+        assert_eq!(all.reviews[0].ts_ms, jan + 2, "newest first");
+
+        let ticket = index.list(&dir, &query(&["proj-142"])).await.reviews;
+        assert_eq!(ticket.len(), 2);
+        assert_eq!(
+            ticket[1].prompt_head.as_deref(),
+            Some("Review the retry loop")
+        );
+        assert_eq!((ticket[0].models_succeeded, ticket[0].models_total), (0, 1));
+
+        let both = index.list(&dir, &query(&["PROJ-142", "pre-merge"])).await;
+        assert_eq!(both.reviews.len(), 1);
+        assert!(both.to_markdown().contains("tags `PROJ-142`, `pre-merge`"));
+
+        // Search honours the same tag filter.
+        let search = SearchQuery {
+            query: "retry".to_string(),
+            tags: vec!["pre-merge".to_string()],
+            max_results: DEFAULT_MAX_RESULTS,
+            ..Default::default()
+        };
+        assert_eq!(index.search(&dir, &search).await.total_matches, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn review_timestamp_skips_findings_sidecars() {
+        assert_eq!(
+            review_timestamp(Path::new(".squall/reviews/1773157800594_76050_0.json")),
+            Some(1773157800594)
+        );
+        assert_eq!(
+            review_timestamp(Path::new(
+                ".squall/reviews/1773157800594_76050_0_findings.json"
+            )),
+            None
+        );
+    }
+}
@@ -1,31 +1,131 @@
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
+use futures_util::StreamExt;
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
-use rmcp::model::{CallToolResult, Implementation, ServerCapabilities, ServerInfo};
-use rmcp::{ErrorData as McpError, ServerHandler, tool, tool_handler, tool_router};
+use rmcp::model::{
+    CallToolResult, Implementation, InitializeRequestParams, InitializeResult, LoggingLevel,
+    LoggingMessageNotificationParam, ProgressNotificationParam, ReadResourceRequestParams,
+    ReadResourceResult, ResourceContents, ResourceUpdatedNotificationParam, ServerCapabilities,
+    ServerInfo, SetLevelRequestParams,
+};
+use rmcp::service::{Peer, RequestContext};
+use rmcp::{ErrorData as McpError, RoleServer, ServerHandler, tool, tool_handler, tool_router};
+use tokio::sync::mpsc::UnboundedReceiver;
 
-use crate::config::Config;
-use crate::context::{self, GitContextCache};
-use crate::dispatch::ProviderRequest;
-use crate::dispatch::registry::Registry;
+use crate::config::{Config, ServerConfig, ValidationReport};
+use crate::context::{self, FileContextResult, GitContextCache};
+use crate::dispatch::health::HealthState;
+use crate::dispatch::registry::{BackendConfig, Registry};
+use crate::dispatch::{ProviderRequest, TokenUsage};
+use crate::logging::LogLevelHandle;
 use crate::memory::MemoryStore;
+use crate::refine::RefineOptions;
 use crate::response::{PalMetadata, PalToolResponse};
-use crate::review::ReviewExecutor;
+use crate::review::{PromptCache, ReviewExecutor};
+use crate::search::ReviewIndex;
+use crate::summarize::SummarizeOptions;
+use crate::tools::adr::AdrRequest;
+use crate::tools::brainstorm::BrainstormRequest;
 use crate::tools::chat::ChatRequest;
+use crate::tools::classify::ClassifyRequest;
 use crate::tools::clink::ClinkRequest;
-use crate::tools::enums::{ReasoningEffort, ResponseFormat};
+use crate::tools::decompose::DecomposeRequest;
+use crate::tools::describe::DescribeChangeRequest;
+use crate::tools::discover::DiscoverModelsRequest;
+use crate::tools::enums::{OutputFormat, ReasoningEffort, ResponseFormat};
+use crate::tools::gentests::GenTestsRequest;
+use crate::tools::license::LicenseScanRequest;
 use crate::tools::listmodels::{ListModelsResponse, ModelInfo};
-use crate::tools::memory::{FeedbackRequest, FlushRequest, MemorizeRequest, MemoryRequest};
-use crate::tools::review::ReviewRequest;
+use crate::tools::logging::SetLogLevelRequest;
+use crate::tools::memory::{
+    FeedbackRequest, FlushRequest, MemorizeRequest, MemorizeReviewRequest, MemoryRequest,
+};
+use crate::tools::plan::PlanRequest;
+use crate::tools::refine::RefineRequest;
+use crate::tools::regress::ModelRegressRequest;
+use crate::tools::review::{ModelStatus, ReviewModelResult, ReviewRequest, WorkingDirectory};
+use crate::tools::reviews::{ReviewsCompareRequest, ReviewsListRequest, ReviewsSearchRequest};
+use crate::tools::suites::{SuiteCreateRequest, SuiteListRequest, SuiteRunRequest};
+use crate::tools::summarize::SummarizeRequest;
+use crate::tools::usage::UsageRequest;
+use crate::usage::{UsageLedger, UsageTag};
+use crate::workspace::Workspace;
+
+/// Delay before warm-up pings, so the MCP handshake goes first.
+const WARMUP_DELAY: Duration = Duration::from_secs(2);
+
+/// Built-in MCP server instructions. `[server]` in config replaces or extends them.
+const DEFAULT_INSTRUCTIONS: &str = "Squall: parallel AI model dispatch. Each model is an independent consultant.\n\n\
+     FOR CODE REVIEW: Use the `squall-unified-review` skill (invoke via Skill tool), \
+        NOT these MCP tools directly. The skill handles depth detection, ensemble selection, \
+        Opus agent orchestration, synthesis, and memorization. Calling `review` directly \
+        skips all of that.\n\n\
+     FOR DIRECT TOOL USE (chat, clink, research — not code review):\n\
+     1. FIRST: Call `memory` (recommend/patterns/tactics) to check past learnings.\n\
+     2. NEXT: Call `listmodels` for EXACT model names. NEVER hardcode names like \
+        \"claude-sonnet\", \"gpt-4\", \"o4-mini\" — these are NOT Squall models. \
+        Use ONLY names from `listmodels` output.\n\
+     3. ONLY THEN: Call `review`/`chat`/`clink` with the task.\n\
+        - Use falsification framing: 'Attempt to PROVE [issue] exists. Report confidence.'\n\
+        - Set `deep: true` for security/architecture/high-stakes (600s, high reasoning).\n\
+        - `results_file` persists on disk — read it if context compaction loses the response.\n\
+     4. Triangulate model findings with your own investigation.\n\
+     5. Call `memorize` to capture patterns, tactics, and model recommendations \
+        (or `memorize_review` with the `results_file` to save consensus findings in bulk).\n\
+     6. After PR merge: `flush` to graduate branch patterns to codebase scope.\n\n\
+     DO NOT call `review` without calling `memory` and `listmodels` first.\n\n\
+     File context: pass `file_paths` + `working_directory` to include source files.\n\
+     For review, also pass `diff` with unified diff text.\n\
+     Research: `clink` with model \"codex\" for web search, or `review` with models as advisors.";
+
+/// State owned by one MCP session of a shared server (daemon or socket mode).
+/// Handler clones within a session share it; `for_session` starts a new one.
+#[derive(Default)]
+struct Session {
+    /// Off for the single stdio session, whose project is the process CWD.
+    isolated: bool,
+    /// The project this session is bound to: the repo of the first
+    /// `working_directory` it sends. Unset until then.
+    project: OnceLock<SessionProject>,
+    /// Client name for the usage ledger, from `initialize` (see `usage::client_name`).
+    client: OnceLock<Arc<str>>,
+}
+
+/// What an isolated session keeps under its own project instead of the CWD.
+struct SessionProject {
+    root: PathBuf,
+    memory: Arc<MemoryStore>,
+    /// `[review]` defaults with the project's own `.squall/config.toml` layered in.
+    review_config: crate::config::ReviewConfig,
+}
 
 #[derive(Clone)]
 pub struct SquallServer {
     registry: Arc<Registry>,
+    /// Memory rooted at the process CWD, for sessions not bound to a project.
     memory: Arc<MemoryStore>,
+    session: Arc<Session>,
     git_cache: Arc<GitContextCache>,
+    review_index: Arc<ReviewIndex>,
+    prompt_cache: Arc<PromptCache>,
     review_config: crate::config::ReviewConfig,
+    /// Problems found loading config, reported by `doctor`.
+    validation: Arc<ValidationReport>,
+    /// `SQUALL_PROFILE` the config was loaded with, reported by `doctor`.
+    profile: Option<String>,
+    /// Ping providers after start (`[settings] warmup`).
+    warmup: bool,
+    /// `[settings] adr_dir`, where `adr` writes records by default.
+    adr_dir: Option<String>,
+    allowed_roots: context::AllowedRoots,
+    instructions: String,
+    /// None when the process didn't install `crate::logging` (tests, embedders).
+    log_level: Option<LogLevelHandle>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -33,19 +133,38 @@ pub struct SquallServer {
 impl SquallServer {
     pub fn new(config: Config) -> Self {
         let review_config = config.review.clone(); // Clone BEFORE from_config() move
+        let server_config = config.server.clone();
+        let validation = Arc::new(config.validation.clone());
+        let profile = config.profile.clone();
+        let warmup = config.warmup;
+        let adr_dir = config.adr_dir.clone();
+        let allowed_roots = match &config.security.allowed_roots {
+            Some(roots) => context::AllowedRoots::new(roots),
+            None => context::AllowedRoots::unrestricted(),
+        };
 
         // Build global writer before config is moved into Registry.
         #[cfg(feature = "global-memory")]
         let global_memory_config = config.global_memory.clone();
 
-        let registry = Arc::new(Registry::from_config(config));
+        let registry = Arc::new(
+            Registry::from_config(config)
+                .with_usage_ledger(UsageLedger::new(crate::usage::USAGE_LEDGER)),
+        );
 
         #[cfg_attr(not(feature = "global-memory"), allow(unused_mut))]
         let mut store = MemoryStore::new().with_id_to_key(registry.model_id_to_key());
 
         #[cfg(feature = "global-memory")]
         if global_memory_config.enabled {
-            match crate::memory::global::GlobalWriter::new(global_memory_config.db_path.into()) {
+            let sync = global_memory_config
+                .sync
+                .as_ref()
+                .and_then(crate::memory::sync::GlobalSync::from_config);
+            match crate::memory::global::GlobalWriter::with_sync(
+                global_memory_config.db_path.into(),
+                sync,
+            ) {
                 Some(writer) => {
                     tracing::info!("global memory: enabled");
                     store = store.with_global(writer);
@@ -54,8 +173,13 @@ impl SquallServer {
                     tracing::warn!(
                         "global memory: failed to initialize, continuing with local only"
                     );
+                    store = store.with_global_unavailable(
+                        "failed to initialize (see server logs); using local memory only",
+                    );
                 }
             }
+        } else {
+            store = store.with_global_unavailable("disabled in config ([global_memory] enabled)");
         }
 
         let memory = Arc::new(store);
@@ -63,10 +187,185 @@ impl SquallServer {
         Self {
             registry,
             memory,
+            session: Arc::default(),
             git_cache,
+            review_index: Arc::new(ReviewIndex::new()),
+            prompt_cache: Arc::new(PromptCache::new()),
             review_config,
-            tool_router: Self::tool_router(),
+            validation,
+            profile,
+            warmup,
+            adr_dir,
+            allowed_roots,
+            instructions: server_config.instructions(DEFAULT_INSTRUCTIONS),
+            log_level: None,
+            tool_router: configured_tool_router(Self::tool_router(), &server_config),
+        }
+    }
+
+    /// Let `set_log_level` and MCP `logging/setLevel` change the process log filter.
+    pub fn with_log_level(mut self, handle: LogLevelHandle) -> Self {
+        self.log_level = Some(handle);
+        self
+    }
+
+    /// With `[settings] warmup`, ping every HTTP provider shortly after start so
+    /// the first review doesn't pay for connection and auth setup, and backend
+    /// health is known up front (see `Registry::warm_up`). Call from the runtime.
+    pub fn spawn_warmup(&self) {
+        if !self.warmup {
+            return;
+        }
+        let registry = self.registry.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(WARMUP_DELAY).await;
+            let outcomes = registry.warm_up().await;
+            let failed: Vec<&str> = outcomes
+                .iter()
+                .filter(|(_, r)| r.is_err())
+                .map(|(p, _)| p.as_str())
+                .collect();
+            tracing::info!(
+                "warm-up: pinged {} provider(s), {} failed{}",
+                outcomes.len(),
+                failed.len(),
+                if failed.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", failed.join(", "))
+                }
+            );
+        });
+    }
+
+    /// A handler for a new client of a shared server. Registry, pools and the
+    /// global writer are shared; memory, persisted reviews and review defaults
+    /// follow the session's own project instead of the process CWD, so projects
+    /// sharing a daemon stay apart.
+    pub fn for_session(&self) -> Self {
+        Self {
+            session: Arc::new(Session {
+                isolated: true,
+                ..Default::default()
+            }),
+            review_index: Arc::new(ReviewIndex::new()),
+            ..self.clone()
+        }
+    }
+
+    /// Ledger tag for a call made by `tool` in this session.
+    fn usage_tag(&self, tool: &'static str) -> UsageTag {
+        let client = self
+            .session
+            .client
+            .get_or_init(|| crate::usage::client_name(None).into());
+        UsageTag {
+            client: client.clone(),
+            tool,
+        }
+    }
+
+    /// The memory store for this session's project.
+    fn memory_store(&self) -> &Arc<MemoryStore> {
+        self.session
+            .project
+            .get()
+            .map_or(&self.memory, |project| &project.memory)
+    }
+
+    /// Review defaults for this session's project.
+    fn review_config(&self) -> &crate::config::ReviewConfig {
+        self.session
+            .project
+            .get()
+            .map_or(&self.review_config, |project| &project.review_config)
+    }
+
+    /// Where this session's reviews are persisted and read back from.
+    fn reviews_dir(&self) -> PathBuf {
+        match self.session.project.get() {
+            Some(project) => project.root.join(crate::review::REVIEWS_DIR),
+            None => PathBuf::from(crate::review::REVIEWS_DIR),
+        }
+    }
+
+    /// Validate a tool's `working_directory` against the allowed roots. The
+    /// first one an isolated session sends binds it to that project.
+    async fn validate_working_directory(
+        &self,
+        path: &str,
+    ) -> Result<PathBuf, crate::error::SquallError> {
+        let canonical = context::validate_working_directory_in(path, &self.allowed_roots).await?;
+        if self.session.isolated && self.session.project.get().is_none() {
+            let root = project_root(&canonical).to_path_buf();
+            let config_root = root.clone();
+            let review_config =
+                tokio::task::spawn_blocking(move || Config::review_for_project(&config_root))
+                    .await
+                    .unwrap_or_else(|_| self.review_config.clone());
+            let project = SessionProject {
+                memory: Arc::new(self.memory.for_project(&root)),
+                root,
+                review_config,
+            };
+            let root = project.root.display().to_string();
+            if self.session.project.set(project).is_ok() {
+                tracing::info!("session bound to project {root}");
+            }
+        }
+        Ok(canonical)
+    }
+
+    /// Resolve a review's `working_directory` to its repositories, validating each
+    /// root. A single directory with a workspace manifest expands to the
+    /// repositories the manifest lists.
+    async fn resolve_workspace(&self, wd: &WorkingDirectory) -> Result<Workspace, McpError> {
+        let invalid = |e: String| McpError::invalid_params(e, None);
+        let dirs = match wd {
+            WorkingDirectory::One(dir) => {
+                let root = self
+                    .validate_working_directory(dir)
+                    .await
+                    .map_err(|e| invalid(e.to_string()))?;
+                match crate::workspace::read_manifest(&root)
+                    .await
+                    .map_err(invalid)?
+                {
+                    Some(repos) => repos,
+                    None => return Ok(Workspace::single(root)),
+                }
+            }
+            WorkingDirectory::Many(dirs) => dirs.iter().map(PathBuf::from).collect(),
+        };
+        let mut roots = Vec::with_capacity(dirs.len());
+        for dir in dirs {
+            roots.push(
+                self.validate_working_directory(&dir.to_string_lossy())
+                    .await
+                    .map_err(|e| invalid(e.to_string()))?,
+            );
+        }
+        Workspace::new(roots).map_err(invalid)
+    }
+
+    /// Resolve memorize scope: explicit > auto-detected from git > None (store default).
+    async fn resolve_memorize_scope(
+        &self,
+        scope: Option<&str>,
+        working_directory: Option<&str>,
+    ) -> Result<Option<String>, McpError> {
+        if let Some(scope) = scope {
+            return Ok(Some(scope.to_string()));
         }
+        let Some(wd) = working_directory else {
+            return Ok(None);
+        };
+        // Validate working directory before using it for git detection.
+        let validated = self.validate_working_directory(wd).await.map_err(|e| {
+            McpError::invalid_params(format!("invalid working_directory: {e}"), None)
+        })?;
+        let git_ctx = self.git_cache.get_or_detect(&validated).await;
+        Ok(Some(context::default_scope_from_git(git_ctx.as_ref())))
     }
 
     #[tool(
@@ -81,6 +380,8 @@ impl SquallServer {
         context::validate_prompt(&req.prompt).map_err(|msg| McpError::invalid_params(msg, None))?;
         context::validate_temperature(req.temperature)
             .map_err(|msg| McpError::invalid_params(msg, None))?;
+        context::validate_extra_body(req.extra_body.as_ref())
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
 
         let model = req.model_or_default().to_string();
         let start = Instant::now();
@@ -94,7 +395,8 @@ impl SquallServer {
                     None,
                 )
             })?;
-            let base_dir = context::validate_working_directory(wd)
+            let base_dir = self
+                .validate_working_directory(wd)
                 .await
                 .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
             let fmt = req.context_format.unwrap_or_default();
@@ -106,10 +408,22 @@ impl SquallServer {
             )
             .await
             .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            for hit in &file_result.injection_suspected {
+                tracing::warn!("chat: injection_suspected: {hit}");
+            }
             if let Some(ctx) = file_result.context {
                 prompt = format!("{ctx}\n{prompt}");
             }
         }
+        if let Some(ref urls) = req.urls {
+            let url_result = crate::urls::resolve_url_context(urls)
+                .await
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            for warning in url_result.warnings() {
+                tracing::warn!("chat: {warning}");
+            }
+            prompt = url_result.prepend_to(prompt);
+        }
 
         let deadline_secs = if self.registry.get(&model).is_some_and(|e| e.is_async_poll())
             || reasoning_needs_extended_deadline(req.reasoning_effort.as_ref())
@@ -129,16 +443,29 @@ impl SquallServer {
             reasoning_effort: req.reasoning_effort.map(|e| e.as_str().to_string()),
             cancellation_token: None,
             stall_timeout: None,
+            max_output_bytes: None,
+            priority: req.priority,
+            extra_body: req.extra_body,
+            usage: Some(self.usage_tag("chat")),
         };
 
         let response = match self.registry.query(&provider_req).await {
             Ok(result) => PalToolResponse::success(
-                result.text,
+                if result.secrets_redacted.is_empty() {
+                    result.text
+                } else {
+                    format!(
+                        "{}\n\n---\nWarning: redacted {} from the response.",
+                        result.text,
+                        crate::secrets::summarize(&result.secrets_redacted)
+                    )
+                },
                 PalMetadata {
                     tool_name: "chat".to_string(),
                     model_used: result.model,
                     provider_used: result.provider,
                     duration_seconds: start.elapsed().as_secs_f64(),
+                    usage: result.usage,
                 },
             ),
             Err(e) => {
@@ -151,6 +478,7 @@ impl SquallServer {
                         model_used: model,
                         provider_used: provider,
                         duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
                     },
                 )
             }
@@ -173,7 +501,11 @@ impl SquallServer {
             .collect();
         models.sort_by(|a, b| a.name.cmp(&b.name));
 
-        let list = ListModelsResponse { models };
+        let list = ListModelsResponse {
+            models,
+            gate: self.review_config().gate,
+            backends: self.registry.backend_health(),
+        };
         let content = list.to_markdown();
 
         let response = PalToolResponse::success(
@@ -183,6 +515,109 @@ impl SquallServer {
                 model_used: "none".to_string(),
                 provider_used: "none".to_string(),
                 duration_seconds: 0.0,
+                usage: None,
+            },
+        );
+
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "doctor",
+        description = "Check Squall's configuration: every problem found while loading config (unknown api_format, unset API keys, bad provider URLs, duplicate model IDs, unreadable config files), the number of models available, and the effective hard-gate thresholds. Run this when a model is missing from listmodels or fails at dispatch.",
+        annotations(read_only_hint = true)
+    )]
+    async fn doctor(&self) -> Result<CallToolResult, McpError> {
+        let errors = self.validation.errors().count();
+        let profile = match self.profile {
+            Some(ref name) => format!("Profile: {name} (SQUALL_PROFILE).\n"),
+            None => String::new(),
+        };
+        let backends: String = self
+            .registry
+            .backend_health()
+            .iter()
+            .filter(|b| b.state != HealthState::Healthy)
+            .map(|b| format!("Backend {}.\n", b.describe()))
+            .collect();
+        let content = format!(
+            "## Squall doctor\n\n{profile}{} model(s) available, {errors} config error(s), \
+             {} warning(s).\nHard gate: reviews drop models with {} in memory.\n{backends}\n{}",
+            self.registry.list_models().len(),
+            self.validation.warnings().count(),
+            self.review_config().gate.describe(),
+            self.validation.to_markdown()
+        );
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "doctor".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: 0.0,
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "discover_models",
+        description = "List models each configured HTTP provider serves for your API keys (via its /models endpoint) that are not yet in the registry. Set snippets: true for ready-to-paste config entries. Narrow with providers and filter. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn discover_models(
+        &self,
+        Parameters(req): Parameters<DiscoverModelsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+        if let Some(ref wanted) = req.providers {
+            let configured: Vec<&str> = self
+                .registry
+                .providers()
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            let unknown: Vec<&str> = wanted
+                .iter()
+                .map(String::as_str)
+                .filter(|w| !configured.contains(w))
+                .collect();
+            if !unknown.is_empty() {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "unknown or keyless providers: {}. Configured: {}",
+                        unknown.join(", "),
+                        configured.join(", ")
+                    ),
+                    None,
+                ));
+            }
+        }
+
+        let catalogs = crate::discover::discover(
+            &self.registry,
+            req.providers.as_deref(),
+            req.filter.as_deref(),
+        )
+        .await;
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => {
+                crate::discover::to_markdown(&catalogs, req.snippets.unwrap_or(false))
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "providers": catalogs,
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "discover_models".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "multi".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
             },
         );
 
@@ -216,7 +651,8 @@ impl SquallServer {
                     None,
                 )
             })?;
-            let base_dir = context::validate_working_directory(wd)
+            let base_dir = self
+                .validate_working_directory(wd)
                 .await
                 .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
             if let Some(manifest) = context::resolve_file_manifest(file_paths, &base_dir)
@@ -227,7 +663,8 @@ impl SquallServer {
             }
             Some(base_dir.to_string_lossy().to_string())
         } else if let Some(ref wd) = req.working_directory {
-            let base_dir = context::validate_working_directory(wd)
+            let base_dir = self
+                .validate_working_directory(wd)
                 .await
                 .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
             Some(base_dir.to_string_lossy().to_string())
@@ -246,6 +683,10 @@ impl SquallServer {
             reasoning_effort: req.reasoning_effort.map(|e| e.as_str().to_string()),
             cancellation_token: None,
             stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: Some(self.usage_tag("clink")),
         };
 
         let response = match self.registry.query(&provider_req).await {
@@ -256,6 +697,7 @@ impl SquallServer {
                     model_used: result.model,
                     provider_used: result.provider,
                     duration_seconds: start.elapsed().as_secs_f64(),
+                    usage: result.usage,
                 },
             ),
             Err(e) => {
@@ -268,6 +710,7 @@ impl SquallServer {
                         model_used: model,
                         provider_used: provider,
                         duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
                     },
                 )
             }
@@ -277,212 +720,2268 @@ impl SquallServer {
     }
 
     #[tool(
-        name = "review",
-        description = "For code review: use the `squall-unified-review` skill instead of calling this tool directly. PREREQUISITE: call `memory` then `listmodels` BEFORE calling this tool. Do NOT hardcode model names — use ONLY names from `listmodels` output.\n\nConsult multiple models in parallel with straggler cutoff. Assign expertise lenses via per_model_system_prompts — falsification framing ('attempt to PROVE X') produces the best results.",
+        name = "brainstorm",
+        description = "Brainstorm with several models at high temperature, merge near-identical ideas, and return the K most mutually distinct ones with the models that proposed each. Use `listmodels` for model names. format: markdown (default) or json.",
         annotations(read_only_hint = true)
     )]
-    async fn review(
+    async fn brainstorm(
         &self,
-        Parameters(req): Parameters<ReviewRequest>,
+        Parameters(req): Parameters<BrainstormRequest>,
     ) -> Result<CallToolResult, McpError> {
         context::validate_prompt(&req.prompt).map_err(|msg| McpError::invalid_params(msg, None))?;
         context::validate_temperature(req.temperature)
             .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let start = Instant::now();
 
-        let start = std::time::Instant::now();
+        let models = req
+            .models
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.review_config().default_models.clone());
+        let k = req.ideas();
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(ReviewRequest::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let prompt: Arc<str> = crate::brainstorm::brainstorm_prompt(&req.prompt, k).into();
+        let temperature = req
+            .temperature
+            .unwrap_or(crate::brainstorm::DEFAULT_TEMPERATURE);
 
-        // Resolve file context and working directory (same pattern as clink handler).
-        // Use canonical path from validate_working_directory() to prevent TOCTOU.
-        let mut prompt = req.prompt.clone();
-        let mut files_skipped = None;
-        let mut files_errors = None;
-        // When both file_paths and diff are provided, reserve MIN_DIFF_BUDGET
-        // for the diff so it's never starved by large file context.
-        let file_budget = if req.diff.is_some() {
-            context::MAX_FILE_CONTEXT_BYTES.saturating_sub(context::MIN_DIFF_BUDGET)
-        } else {
-            context::MAX_FILE_CONTEXT_BYTES
-        };
-        let working_directory = if let Some(ref file_paths) = req.file_paths {
-            let wd = req.working_directory.as_deref().ok_or_else(|| {
-                McpError::invalid_params(
-                    "working_directory is required when file_paths is set",
-                    None,
-                )
-            })?;
-            let base_dir = context::validate_working_directory(wd)
-                .await
-                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-            let fmt = req.context_format.unwrap_or_default();
-            let file_result =
-                context::resolve_file_context(file_paths, &base_dir, file_budget, fmt)
-                    .await
-                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-            if !file_result.skipped.is_empty() {
-                files_skipped = Some(
-                    file_result
-                        .skipped
-                        .iter()
-                        .map(|(name, sz)| format!("{name} ({sz}B)"))
-                        .collect(),
-                );
-            }
-            if !file_result.errors.is_empty() {
-                files_errors = Some(file_result.errors);
-            }
-            if let Some(ctx) = file_result.context {
-                prompt = format!("{ctx}\n{prompt}");
-            }
-            Some(base_dir.to_string_lossy().to_string())
-        } else if let Some(ref wd) = req.working_directory {
-            let base_dir = context::validate_working_directory(wd)
-                .await
-                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-            Some(base_dir.to_string_lossy().to_string())
-        } else {
-            None
-        };
+        let requests: Vec<ProviderRequest> = models
+            .iter()
+            .flat_map(|model| std::iter::repeat_n(model, req.samples_per_model()))
+            .map(|model| ProviderRequest {
+                prompt: prompt.clone(),
+                model: model.clone(),
+                deadline,
+                working_directory: None,
+                system_prompt: req.system_prompt.clone(),
+                temperature: Some(temperature),
+                max_tokens: None,
+                reasoning_effort: None,
+                cancellation_token: None,
+                stall_timeout: None,
+                max_output_bytes: None,
+                priority: None,
+                extra_body: None,
+                usage: Some(self.usage_tag("brainstorm")),
+            })
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
 
-        // Inject diff context (shared budget with file context)
-        if let Some(ref diff_text) = req.diff {
-            let file_context_used = prompt.len() - req.prompt.len();
-            let diff_budget = context::MAX_FILE_CONTEXT_BYTES.saturating_sub(file_context_used);
-            if let Some(wrapped) = context::wrap_diff_context(diff_text, diff_budget) {
-                prompt = format!("{wrapped}\n{prompt}");
+        let mut candidates = Vec::new();
+        let mut errors = Vec::new();
+        let mut usages = Vec::new();
+        for (r, result) in requests.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    usages.extend(result.usage);
+                    candidates.extend(
+                        crate::brainstorm::split_ideas(&result.text)
+                            .into_iter()
+                            .map(|idea| (r.model.clone(), idea)),
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("brainstorm: {} failed: {e}", r.model);
+                    errors.push(format!("{}: {}", r.model, e.user_message()));
+                }
             }
         }
+        errors.dedup();
+        if candidates.is_empty() {
+            let response = PalToolResponse::error(
+                format!("no ideas returned. {}", errors.join("; ")),
+                PalMetadata {
+                    tool_name: "brainstorm".to_string(),
+                    model_used: "multi".to_string(),
+                    provider_used: "multi".to_string(),
+                    duration_seconds: start.elapsed().as_secs_f64(),
+                    usage: TokenUsage::sum(&usages),
+                },
+            );
+            return Ok(response.into_call_tool_result());
+        }
 
-        let executor = ReviewExecutor::new(self.registry.clone());
-        let prompt_len = prompt.len();
-        let wd_for_memory = working_directory.clone();
-        let review_response = executor
-            .execute(
-                &req,
-                prompt,
-                &self.memory,
-                working_directory,
-                files_skipped,
-                files_errors,
-                Some(&self.review_config),
-            )
-            .await;
-
-        // Log model metrics to memory (non-blocking, fire-and-forget)
-        let memory = self.memory.clone();
-        let results_for_memory = review_response.results.clone();
-        let id_to_key = self.registry.model_id_to_key();
-        tokio::spawn(async move {
-            memory
-                .log_model_metrics(
-                    &results_for_memory,
-                    prompt_len,
-                    Some(&id_to_key),
-                    wd_for_memory.as_deref(),
-                )
-                .await;
-        });
-
-        // Render the review response as markdown for MCP (disk file stays JSON)
-        let concise = matches!(req.response_format, Some(ResponseFormat::Concise));
-        let content = review_response.to_markdown(concise);
-
+        let selection = crate::brainstorm::select(candidates, k);
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => {
+                let mut out = selection.to_markdown();
+                if !errors.is_empty() {
+                    out.push_str("\n### Errors\n");
+                    for e in &errors {
+                        out.push_str(&format!("- {e}\n"));
+                    }
+                }
+                out
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "ideas": selection.ideas,
+                "candidates": selection.candidates,
+                "duplicates": selection.duplicates,
+                "errors": errors,
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
         let response = PalToolResponse::success(
             content,
             PalMetadata {
-                tool_name: "review".to_string(),
+                tool_name: "brainstorm".to_string(),
                 model_used: "multi".to_string(),
                 provider_used: "multi".to_string(),
                 duration_seconds: start.elapsed().as_secs_f64(),
+                usage: TokenUsage::sum(&usages),
             },
         );
-
         Ok(response.into_call_tool_result())
     }
 
     #[tool(
-        name = "memorize",
-        description = "Save your synthesized findings after a review: recurring patterns, effective tactics, and model recommendations."
+        name = "classify",
+        description = "Route a task: a fast model classifies a short task description into a depth (quick/standard/deep) and focus lenses, then the routing policy and memory stats pick models, per-model lens prompts, and a timeout — ready to pass to `review`. Use instead of hard-coding model lists. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
     )]
-    async fn memorize(
+    async fn classify(
         &self,
-        Parameters(req): Parameters<MemorizeRequest>,
+        Parameters(req): Parameters<ClassifyRequest>,
     ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.task).map_err(|msg| McpError::invalid_params(msg, None))?;
         let start = Instant::now();
 
-        // Resolve scope: explicit > auto-detected from git > default "codebase"
-        let auto_scope;
-        let scope = if req.scope.is_some() {
-            req.scope.as_deref()
-        } else if let Some(ref wd) = req.working_directory {
-            // Validate working directory before using it for git detection.
-            let validated = context::validate_working_directory(wd).await.map_err(|e| {
-                McpError::invalid_params(format!("invalid working_directory: {e}"), None)
-            })?;
-            let git_ctx = self.git_cache.get_or_detect(&validated).await;
-            auto_scope = context::default_scope_from_git(git_ctx.as_ref());
-            Some(auto_scope.as_str())
-        } else {
-            None
+        let model = req.model_or_default().to_string();
+        let provider_req = ProviderRequest {
+            prompt: crate::routing::classify_prompt(&req.task).into(),
+            model: model.clone(),
+            deadline: Instant::now() + Duration::from_secs(crate::routing::CLASSIFY_TIMEOUT_SECS),
+            working_directory: None,
+            system_prompt: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: Some(self.usage_tag("classify")),
         };
-
-        match self
-            .memory
-            .memorize(
-                req.category.as_str(),
-                &req.content,
-                req.model.as_deref(),
-                req.tags.as_deref(),
-                scope,
-                req.metadata.as_ref(),
-            )
-            .await
-        {
-            Ok(path) => {
-                let response = PalToolResponse::success(
-                    format!("Saved to {path}"),
-                    PalMetadata {
-                        tool_name: "memorize".to_string(),
-                        model_used: "none".to_string(),
-                        provider_used: "none".to_string(),
-                        duration_seconds: start.elapsed().as_secs_f64(),
-                    },
-                );
-                Ok(response.into_call_tool_result())
+        let mut usage = None;
+        let (mut classification, provider) = match self.registry.query(&provider_req).await {
+            Ok(result) => {
+                usage = result.usage;
+                match crate::routing::parse_classification(&result.text, &model) {
+                    Some(c) => (c, result.provider),
+                    None => {
+                        tracing::warn!(
+                            "classify: unparseable answer from {model}, using heuristic"
+                        );
+                        (crate::routing::heuristic(&req.task), "none".to_string())
+                    }
+                }
             }
-            Err(msg) => Err(McpError::invalid_params(msg, None)),
+            Err(e) => {
+                tracing::warn!("classify: {model} failed ({e}), using heuristic");
+                (crate::routing::heuristic(&req.task), "none".to_string())
+            }
+        };
+        if let Some(depth) = req.depth {
+            classification.depth = depth;
         }
+
+        let id_to_key = self.registry.model_id_to_key();
+        let stats = self.memory_store().get_model_stats(Some(&id_to_key)).await;
+        let models = self.registry.list_models();
+        let classified_by = classification.classified_by.clone();
+        let recommendation = crate::routing::recommend(
+            classification,
+            &models,
+            &self.review_config().default_models,
+            stats.as_ref(),
+            &self.review_config().gate,
+        );
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => recommendation.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&recommendation)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "classify".to_string(),
+                model_used: if provider == "none" {
+                    "none".to_string()
+                } else {
+                    classified_by
+                },
+                provider_used: provider,
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage,
+            },
+        );
+        Ok(response.into_call_tool_result())
     }
 
     #[tool(
-        name = "memory",
-        description = "Read prior patterns, tactics, and model recommendations to inform model selection and review lenses.",
+        name = "describe_change",
+        description = "Draft a commit message and PR description from git. Describes the staged changes, or the branch's changes since `base` (git diff base...HEAD). Two fast models draft in parallel and the fastest merges the drafts. Set conventional: true for a Conventional Commits subject. format: markdown (default) or json.",
         annotations(read_only_hint = true)
     )]
-    async fn memory(
+    async fn describe_change(
         &self,
-        Parameters(req): Parameters<MemoryRequest>,
+        Parameters(req): Parameters<DescribeChangeRequest>,
     ) -> Result<CallToolResult, McpError> {
         let start = Instant::now();
-
-        match self
-            .memory
-            .read_memory(
-                req.category.as_ref().map(|c| c.as_str()),
-                req.model.as_deref(),
-                req.max_chars(),
-                req.scope.as_deref(),
-            )
+        let base_dir = self
+            .validate_working_directory(&req.working_directory)
             .await
-        {
-            Ok(content) => {
-                let response = PalToolResponse::success(
-                    content,
-                    PalMetadata {
-                        tool_name: "memory".to_string(),
-                        model_used: "none".to_string(),
-                        provider_used: "none".to_string(),
-                        duration_seconds: start.elapsed().as_secs_f64(),
-                    },
+            .map_err(|e| {
+                McpError::invalid_params(format!("invalid working_directory: {e}"), None)
+            })?;
+        let source = match req.base.as_deref().map(str::trim) {
+            Some(base) if !base.is_empty() => {
+                if !crate::describe::is_valid_base(base) {
+                    return Err(McpError::invalid_params(
+                        format!("invalid base ref: {base}"),
+                        None,
+                    ));
+                }
+                crate::describe::DiffSource::Branch(base.to_string())
+            }
+            _ => crate::describe::DiffSource::Staged,
+        };
+        let packages = context::validate_packages(req.packages.as_deref().unwrap_or_default())
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let mut diff = crate::describe::collect_diff(&base_dir, &source, &packages)
+            .await
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        if let Some(ig) = context::load_squallignore(&base_dir) {
+            diff = context::filter_ignored_diff(&diff, &ig).0;
+        }
+        let Some(wrapped) = context::wrap_diff_context(&diff, context::MAX_FILE_CONTEXT_BYTES)
+        else {
+            return Err(McpError::invalid_params(
+                format!("no {} to describe", source.label()),
+                None,
+            ));
+        };
+
+        let models = match req.models.clone().filter(|m| !m.is_empty()) {
+            Some(models) => models,
+            None => crate::describe::fast_models(
+                &self.registry.list_models(),
+                crate::describe::DEFAULT_DRAFTERS,
+            ),
+        };
+        if models.is_empty() {
+            return Err(McpError::invalid_params(
+                "no HTTP models configured to draft with",
+                None,
+            ));
+        }
+        let conventional = req.conventional.unwrap_or(false);
+        let hint = req.hint.as_deref().filter(|h| !h.trim().is_empty());
+        let query = |prompt: Arc<str>, model: &str, secs: u64| ProviderRequest {
+            prompt,
+            model: model.to_string(),
+            deadline: Instant::now() + Duration::from_secs(secs),
+            working_directory: None,
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: Some(self.usage_tag("describe_change")),
+        };
+
+        let prompt: Arc<str> = crate::describe::draft_prompt(&wrapped, conventional, hint).into();
+        let requests: Vec<ProviderRequest> = models
+            .iter()
+            .map(|m| query(prompt.clone(), m, 120))
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
+        let mut drafts = Vec::new();
+        let mut notes = Vec::new();
+        let mut usages = Vec::new();
+        for (model, result) in models.iter().zip(results) {
+            match result {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    match crate::describe::parse(&r.text) {
+                        Some(desc) => drafts.push((model.clone(), desc)),
+                        None => notes.push(format!("{model}: reply had no <commit> section")),
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("describe_change: {model} failed: {e}");
+                    notes.push(format!("{model}: {}", e.user_message()));
+                }
+            }
+        }
+        let metadata = |model_used: String, usages: &[TokenUsage]| PalMetadata {
+            tool_name: "describe_change".to_string(),
+            model_used,
+            provider_used: "multi".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: TokenUsage::sum(usages),
+        };
+        let Some((first_model, first)) = drafts.first().cloned() else {
+            let response = PalToolResponse::error(
+                format!("no model returned a description. {}", notes.join("; ")),
+                metadata(models.join(","), &usages),
+            );
+            return Ok(response.into_call_tool_result());
+        };
+
+        // Models are in speed order, so the first drafter is the fastest that answered.
+        let (desc, synthesized_by) = if drafts.len() == 1 {
+            (first, first_model)
+        } else {
+            let prompt =
+                crate::describe::synthesis_prompt(&wrapped, &drafts, conventional, hint).into();
+            match self.registry.query(&query(prompt, &first_model, 90)).await {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    match crate::describe::parse(&r.text) {
+                        Some(merged) => (merged, first_model),
+                        None => {
+                            notes.push(format!(
+                                "{first_model}: merge reply unparseable, using its draft"
+                            ));
+                            (first, first_model)
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("describe_change: merge by {first_model} failed: {e}");
+                    notes.push(format!(
+                        "merge by {first_model} failed ({}), using its draft",
+                        e.user_message()
+                    ));
+                    (first, first_model)
+                }
+            }
+        };
+        notes.extend(crate::describe::warnings(&desc, conventional));
+        let drafters: Vec<String> = drafts.iter().map(|(m, _)| m.clone()).collect();
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => {
+                crate::describe::to_markdown(&desc, &source, &synthesized_by, &drafters, &notes)
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "commit_message": desc.commit_message,
+                "pr_title": desc.pr_title,
+                "pr_description": desc.pr_description,
+                "source": source.label(),
+                "synthesized_by": synthesized_by,
+                "drafts": drafts
+                    .iter()
+                    .map(|(m, d)| (m.clone(), d))
+                    .collect::<HashMap<_, _>>(),
+                "notes": notes,
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(content, metadata(drafters.join(","), &usages));
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "decompose",
+        description = "Answer a complex question by decomposition: a planner model splits it into sub-questions and assigns each to a named model group, every model of that group answers it in parallel, and a synthesizer merges the answers into one, citing sub-questions as [Q1], [Q2]. Returns the answer plus which models answered each sub-question. Use `listmodels` for model names. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn decompose(
+        &self,
+        Parameters(req): Parameters<DecomposeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.prompt).map_err(|msg| McpError::invalid_params(msg, None))?;
+        let start = Instant::now();
+
+        let groups = match req.groups.clone().filter(|g| !g.is_empty()) {
+            Some(groups) => groups,
+            None => [(
+                crate::decompose::DEFAULT_GROUP.to_string(),
+                self.review_config().default_models.clone(),
+            )]
+            .into(),
+        };
+        if let Some((name, _)) = groups.iter().find(|(_, models)| models.is_empty()) {
+            return Err(McpError::invalid_params(
+                format!("model group '{name}' has no models"),
+                None,
+            ));
+        }
+        let planner = match req.planner.clone().filter(|p| !p.trim().is_empty()) {
+            Some(planner) => planner,
+            None => crate::describe::fast_models(&self.registry.list_models(), 1)
+                .into_iter()
+                .next()
+                .or_else(|| groups.values().flatten().next().cloned())
+                .ok_or_else(|| McpError::invalid_params("no planner model available", None))?,
+        };
+        let synthesizer = req
+            .synthesizer
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| planner.clone());
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(ReviewRequest::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let query = |prompt: Arc<str>, model: &str, deadline: Instant| ProviderRequest {
+            prompt,
+            model: model.to_string(),
+            deadline,
+            working_directory: None,
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: Some(self.usage_tag("decompose")),
+        };
+        let mut notes = Vec::new();
+        let mut usages = Vec::new();
+
+        // Plan. Each group is described to the planner by its models' strengths.
+        let described: Vec<(String, String)> = groups
+            .iter()
+            .map(|(name, models)| {
+                let about: Vec<String> = models
+                    .iter()
+                    .map(|m| match self.registry.get(m) {
+                        Some(entry) if !entry.strengths.is_empty() => {
+                            format!("{m} ({})", entry.strengths.join(", "))
+                        }
+                        _ => m.clone(),
+                    })
+                    .collect();
+                (name.clone(), about.join("; "))
+            })
+            .collect();
+        let names: Vec<String> = groups.keys().cloned().collect();
+        let max = req.max_sub_questions();
+        let plan_prompt = crate::decompose::plan_prompt(&req.prompt, &described, max).into();
+        let plan_deadline = Instant::now() + Duration::from_secs(90);
+        let mut plan = match self
+            .registry
+            .query(&query(plan_prompt, &planner, plan_deadline))
+            .await
+        {
+            Ok(r) => {
+                usages.extend(r.usage);
+                crate::decompose::parse_plan(&r.text, &names, max)
+            }
+            Err(e) => {
+                tracing::warn!("decompose: planner {planner} failed: {e}");
+                notes.push(format!("planner {planner} failed: {}", e.user_message()));
+                Vec::new()
+            }
+        };
+        if plan.is_empty() {
+            notes.push("no usable plan; the question was answered whole".to_string());
+            plan.push(crate::decompose::SubQuestion {
+                question: req.prompt.clone(),
+                group: names[0].clone(),
+            });
+        }
+
+        // Fan each sub-question out to every model of its group.
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let requests: Vec<(usize, ProviderRequest)> = plan
+            .iter()
+            .enumerate()
+            .flat_map(|(i, sub)| {
+                let prompt: Arc<str> = sub.question.as_str().into();
+                groups[&sub.group]
+                    .iter()
+                    .map(move |model| (i, prompt.clone(), model))
+            })
+            .map(|(i, prompt, model)| {
+                let mut r = query(prompt, model, deadline);
+                r.system_prompt = req.system_prompt.clone();
+                (i, r)
+            })
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|(_, r)| self.registry.query(r)))
+                .await;
+        let mut answered: Vec<crate::decompose::Answered> = plan
+            .into_iter()
+            .map(|sub| crate::decompose::Answered {
+                sub,
+                answers: Vec::new(),
+                errors: Vec::new(),
+            })
+            .collect();
+        for ((i, r), result) in requests.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    usages.extend(result.usage);
+                    answered[*i].answers.push(crate::decompose::Answer {
+                        model: r.model.clone(),
+                        text: result.text,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("decompose: {} failed on Q{}: {e}", r.model, i + 1);
+                    answered[*i]
+                        .errors
+                        .push(format!("{}: {}", r.model, e.user_message()));
+                }
+            }
+        }
+        let metadata = |usages: &[TokenUsage]| PalMetadata {
+            tool_name: "decompose".to_string(),
+            model_used: "multi".to_string(),
+            provider_used: "multi".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: TokenUsage::sum(usages),
+        };
+        if answered.iter().all(|a| a.answers.is_empty()) {
+            let errors: Vec<String> = answered.iter().flat_map(|a| a.errors.clone()).collect();
+            let response = PalToolResponse::error(
+                format!("no sub-question was answered. {}", errors.join("; ")),
+                metadata(&usages),
+            );
+            return Ok(response.into_call_tool_result());
+        }
+
+        // Synthesize.
+        let prompt = crate::decompose::synthesis_prompt(&req.prompt, &answered).into();
+        let synthesis_deadline = Instant::now() + Duration::from_secs(120);
+        let (answer, synthesized_by) = match self
+            .registry
+            .query(&query(prompt, &synthesizer, synthesis_deadline))
+            .await
+        {
+            Ok(r) if !r.text.trim().is_empty() => {
+                usages.extend(r.usage);
+                (r.text, synthesizer)
+            }
+            Ok(r) => {
+                usages.extend(r.usage);
+                notes.push(format!("{synthesizer} returned an empty synthesis"));
+                (crate::decompose::concatenate(&answered), "none".to_string())
+            }
+            Err(e) => {
+                tracing::warn!("decompose: synthesis by {synthesizer} failed: {e}");
+                notes.push(format!(
+                    "synthesis by {synthesizer} failed ({}), answers listed as-is",
+                    e.user_message()
+                ));
+                (crate::decompose::concatenate(&answered), "none".to_string())
+            }
+        };
+        let decomposition = crate::decompose::Decomposition {
+            answer,
+            planned_by: planner,
+            synthesized_by,
+            sub_questions: answered,
+            notes,
+        };
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => decomposition.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&decomposition)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(content, metadata(&usages));
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "gentests",
+        description = "Generate unit tests for files and/or a diff in the project's detected test framework (cargo test, pytest, go test, JUnit, Jest/Vitest/Mocha/node:test). Returns per-file test code blocks and a consolidated file-write plan (create vs append, best candidate across models). Does not write files. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn gentests(
+        &self,
+        Parameters(req): Parameters<GenTestsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_temperature(req.temperature)
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let has_files = req.file_paths.as_ref().is_some_and(|p| !p.is_empty());
+        let has_diff = req.diff.as_ref().is_some_and(|d| !d.trim().is_empty());
+        if !has_files && !has_diff {
+            return Err(McpError::invalid_params(
+                "file_paths or diff is required",
+                None,
+            ));
+        }
+        let start = Instant::now();
+
+        let base_dir = match req.working_directory.as_deref() {
+            Some(wd) => Some(self.validate_working_directory(wd).await.map_err(|e| {
+                McpError::invalid_params(format!("invalid working_directory: {e}"), None)
+            })?),
+            None if has_files => {
+                return Err(McpError::invalid_params(
+                    "working_directory is required when file_paths is set",
+                    None,
+                ));
+            }
+            None => None,
+        };
+
+        let mut diff = req.diff.as_deref().filter(|_| has_diff).map(Cow::Borrowed);
+        if let Some(ref diff_text) = diff
+            && let Some(ref dir) = base_dir
+            && let Some(ig) = context::load_squallignore(dir)
+        {
+            let (filtered, dropped) = context::filter_ignored_diff(diff_text, &ig);
+            if !dropped.is_empty() {
+                diff = Some(Cow::Owned(filtered));
+            }
+        }
+
+        let mut targets = req.file_paths.clone().unwrap_or_default();
+        if let Some(ref d) = diff {
+            targets.extend(crate::gentests::diff_paths(d));
+        }
+        let framework = crate::gentests::detect_framework(base_dir.as_deref(), &targets).await;
+
+        let mut prompt = crate::gentests::instructions(&framework, diff.is_some());
+        if let Some(extra) = req.prompt.as_deref().filter(|p| !p.trim().is_empty()) {
+            prompt = format!("{prompt}\n\n{extra}");
+        }
+        let budget = context::MAX_FILE_CONTEXT_BYTES;
+        let mut context_used = 0;
+        if let Some(ref file_paths) = req.file_paths
+            && let Some(ref dir) = base_dir
+        {
+            let file_budget = if diff.is_some() {
+                budget.saturating_sub(context::MIN_DIFF_BUDGET.min(budget / 2))
+            } else {
+                budget
+            };
+            let fmt = req.context_format.unwrap_or_default();
+            let result = context::resolve_file_context(file_paths, dir, file_budget, fmt)
+                .await
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            if let Some(ctx) = result.context {
+                context_used = ctx.len();
+                prompt = format!("{ctx}\n{prompt}");
+            }
+        }
+        if let Some(ref diff_text) = diff
+            && let Some(wrapped) =
+                context::wrap_diff_context(diff_text, budget.saturating_sub(context_used))
+        {
+            prompt = format!("{wrapped}\n{prompt}");
+        }
+
+        let models = req.models_or_default();
+        let slow = models.iter().any(|m| {
+            self.registry
+                .get(m)
+                .is_some_and(|e| !matches!(e.backend, BackendConfig::Http { .. }))
+        });
+        let deadline = Instant::now() + Duration::from_secs(if slow { 600 } else { 300 });
+        let prompt: Arc<str> = prompt.into();
+        let requests: Vec<ProviderRequest> = models
+            .iter()
+            .map(|model| ProviderRequest {
+                prompt: prompt.clone(),
+                model: model.clone(),
+                deadline,
+                working_directory: base_dir.as_ref().map(|d| d.to_string_lossy().to_string()),
+                system_prompt: None,
+                temperature: Some(
+                    req.temperature
+                        .unwrap_or(crate::tools::gentests::DEFAULT_TEMPERATURE),
+                ),
+                max_tokens: None,
+                reasoning_effort: None,
+                cancellation_token: None,
+                stall_timeout: None,
+                max_output_bytes: None,
+                priority: None,
+                extra_body: None,
+                usage: Some(self.usage_tag("gentests")),
+            })
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
+
+        let mut per_model = Vec::new();
+        let mut errors = Vec::new();
+        let mut usages = Vec::new();
+        for (model, result) in models.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    usages.extend(result.usage);
+                    per_model.push((model.clone(), crate::gentests::parse_response(&result.text)));
+                }
+                Err(e) => {
+                    tracing::warn!("gentests: {model} failed: {e}");
+                    errors.push(format!("{model}: {}", e.user_message()));
+                }
+            }
+        }
+        if per_model.is_empty() {
+            let response = PalToolResponse::error(
+                format!("no model returned tests. {}", errors.join("; ")),
+                PalMetadata {
+                    tool_name: "gentests".to_string(),
+                    model_used: models.join(","),
+                    provider_used: "multi".to_string(),
+                    duration_seconds: start.elapsed().as_secs_f64(),
+                    usage: TokenUsage::sum(&usages),
+                },
+            );
+            return Ok(response.into_call_tool_result());
+        }
+
+        let plan = crate::gentests::plan(&framework, &per_model, |path| {
+            base_dir.as_ref().is_some_and(|d| d.join(path).is_file())
+        });
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => {
+                crate::gentests::to_markdown(&framework, &plan, &per_model, &errors)
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "framework": framework.name,
+                "plan": plan,
+                "per_model": per_model
+                    .iter()
+                    .map(|(m, parsed)| (m.clone(), parsed))
+                    .collect::<HashMap<_, _>>(),
+                "errors": errors,
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "gentests".to_string(),
+                model_used: models.join(","),
+                provider_used: "multi".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: TokenUsage::sum(&usages),
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "license_scan",
+        description = "Check dependency licenses and new-file headers. Sends the project's license file, its dependency manifests, and the first lines of files added since `base` (or not yet committed) to the models, which flag incompatible or unknown licenses and missing headers. Set `header` to require a header line; it is also checked directly. Returns findings with `passed: false` when any is an error, for CI gating. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn license_scan(
+        &self,
+        Parameters(req): Parameters<LicenseScanRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+        let base_dir = self
+            .validate_working_directory(&req.working_directory)
+            .await
+            .map_err(|e| {
+                McpError::invalid_params(format!("invalid working_directory: {e}"), None)
+            })?;
+        let mut new_files = match (req.file_paths.clone(), req.base.as_deref().map(str::trim)) {
+            (Some(paths), _) => {
+                for path in &paths {
+                    context::validate_path(path)
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                }
+                paths
+            }
+            (None, Some(base)) if !base.is_empty() => {
+                if !crate::describe::is_valid_base(base) {
+                    return Err(McpError::invalid_params(
+                        format!("invalid base ref: {base}"),
+                        None,
+                    ));
+                }
+                crate::license::new_files(&base_dir, Some(base))
+                    .await
+                    .map_err(|e| McpError::invalid_params(e, None))?
+            }
+            (None, _) => crate::license::new_files(&base_dir, None)
+                .await
+                .map_err(|e| McpError::invalid_params(e, None))?,
+        };
+        if let Some(ig) = context::load_squallignore(&base_dir) {
+            new_files.retain(|path| !context::is_squallignored(&ig, path));
+        }
+        let scan = crate::license::Scan::collect(&base_dir, &new_files).await;
+        if scan.is_empty() {
+            return Err(McpError::invalid_params(
+                "no dependency manifest or new file to scan",
+                None,
+            ));
+        }
+        let mut errors = Vec::new();
+        if new_files.len() > crate::license::MAX_NEW_FILES {
+            errors.push(format!(
+                "{} new file(s) past the first {} not checked",
+                new_files.len() - crate::license::MAX_NEW_FILES,
+                crate::license::MAX_NEW_FILES
+            ));
+        }
+
+        let models = req
+            .models
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.review_config().default_models.clone());
+        if models.is_empty() {
+            return Err(McpError::invalid_params(
+                "no models given and no [review] default_models configured",
+                None,
+            ));
+        }
+        let header = req
+            .header
+            .as_deref()
+            .map(str::trim)
+            .filter(|h| !h.is_empty());
+        let prompt: Arc<str> = crate::license::prompt(
+            &scan,
+            header,
+            req.prompt.as_deref().filter(|p| !p.trim().is_empty()),
+        )
+        .into();
+        let deadline = Instant::now()
+            + Duration::from_secs(
+                req.timeout_secs
+                    .unwrap_or(crate::license::DEFAULT_TIMEOUT_SECS)
+                    .min(crate::review::MAX_TIMEOUT_SECS),
+            );
+        let requests: Vec<ProviderRequest> = models
+            .iter()
+            .map(|model| ProviderRequest {
+                prompt: prompt.clone(),
+                model: model.clone(),
+                deadline,
+                working_directory: None,
+                system_prompt: None,
+                temperature: Some(0.0),
+                max_tokens: None,
+                reasoning_effort: None,
+                cancellation_token: None,
+                stall_timeout: None,
+                max_output_bytes: None,
+                priority: None,
+                extra_body: None,
+                usage: Some(self.usage_tag("license_scan")),
+            })
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
+
+        let mut findings = header
+            .map(|h| crate::license::missing_headers(&scan, h))
+            .unwrap_or_default();
+        let mut answered = 0;
+        let mut usages = Vec::new();
+        for (model, result) in models.iter().zip(results) {
+            match result {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    match crate::license::parse(model, &r.text) {
+                        Some(found) => {
+                            answered += 1;
+                            findings.extend(found);
+                        }
+                        None => errors.push(format!("{model}: reply had no findings JSON")),
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("license_scan: {model} failed: {e}");
+                    errors.push(format!("{model}: {}", e.user_message()));
+                }
+            }
+        }
+        let metadata = PalMetadata {
+            tool_name: "license_scan".to_string(),
+            model_used: models.join(","),
+            provider_used: "multi".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: TokenUsage::sum(&usages),
+        };
+        // A scan nobody answered must not pass a CI gate.
+        if answered == 0 {
+            let response = PalToolResponse::error(
+                format!("no model returned findings. {}", errors.join("; ")),
+                metadata,
+            );
+            return Ok(response.into_call_tool_result());
+        }
+
+        let report =
+            crate::license::LicenseReport::new(&scan, crate::license::merge(findings), errors);
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => crate::license::to_markdown(&report),
+            OutputFormat::Json => serde_json::to_string_pretty(&report)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        Ok(PalToolResponse::success(content, metadata).into_call_tool_result())
+    }
+
+    #[tool(
+        name = "adr",
+        description = "Write an architecture decision record. Strong models (default: the high precision tier) each propose options, trade-offs and a recommendation for the design question, with file_paths as context. A synthesizer merges them into an ADR (context, decision drivers, considered options, decision, consequences), noting disagreements. The record is numbered after the existing ones and saved as docs/adr/NNNN-title.md (`docs_dir` or `[settings] adr_dir` to change; persist: false to preview). format: markdown (default) or json."
+    )]
+    async fn adr(
+        &self,
+        Parameters(req): Parameters<AdrRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.question)
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let start = Instant::now();
+        let base_dir = self
+            .validate_working_directory(&req.working_directory)
+            .await
+            .map_err(|e| {
+                McpError::invalid_params(format!("invalid working_directory: {e}"), None)
+            })?;
+        let docs_dir = req
+            .docs_dir
+            .clone()
+            .or_else(|| self.adr_dir.clone())
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| crate::adr::DEFAULT_DIR.to_string());
+        context::validate_path(&docs_dir)
+            .map_err(|e| McpError::invalid_params(format!("docs_dir: {e}"), None))?;
+
+        let mut file_context = None;
+        if let Some(ref file_paths) = req.file_paths {
+            file_context = context::resolve_file_context(
+                file_paths,
+                &base_dir,
+                context::MAX_FILE_CONTEXT_BYTES,
+                req.context_format.unwrap_or_default(),
+            )
+            .await
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?
+            .context;
+        }
+
+        let models = match req.models.clone().filter(|m| !m.is_empty()) {
+            Some(models) => models,
+            None => {
+                let available: Vec<(String, String)> = self
+                    .registry
+                    .list_models()
+                    .into_iter()
+                    .map(|(name, entry)| (name.clone(), entry.precision_tier.clone()))
+                    .collect();
+                crate::adr::strong_models(&available, &self.review_config().default_models)
+            }
+        };
+        if models.is_empty() {
+            return Err(McpError::invalid_params(
+                "no models given and no [review] default_models configured",
+                None,
+            ));
+        }
+        if models.len() > crate::review::MAX_MODELS {
+            return Err(McpError::invalid_params(
+                format!("at most {} models", crate::review::MAX_MODELS),
+                None,
+            ));
+        }
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(crate::adr::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let query = |prompt: Arc<str>, model: &str| ProviderRequest {
+            prompt,
+            model: model.to_string(),
+            deadline,
+            working_directory: None,
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+            reasoning_effort: None,
+            cancellation_token: None,
+            stall_timeout: None,
+            max_output_bytes: None,
+            priority: None,
+            extra_body: None,
+            usage: Some(self.usage_tag("adr")),
+        };
+
+        let prompt: Arc<str> =
+            crate::adr::options_prompt(&req.question, file_context.as_deref()).into();
+        let requests: Vec<ProviderRequest> =
+            models.iter().map(|m| query(prompt.clone(), m)).collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
+        let mut proposals = Vec::new();
+        let mut notes = Vec::new();
+        let mut usages = Vec::new();
+        for (model, result) in models.iter().zip(results) {
+            match result {
+                Ok(r) if !r.text.trim().is_empty() => {
+                    usages.extend(r.usage);
+                    proposals.push((model.clone(), r.text));
+                }
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    notes.push(format!("{model}: empty reply"));
+                }
+                Err(e) => {
+                    tracing::warn!("adr: {model} failed: {e}");
+                    notes.push(format!("{model}: {}", e.user_message()));
+                }
+            }
+        }
+        let metadata = |model_used: String, usages: &[TokenUsage]| PalMetadata {
+            tool_name: "adr".to_string(),
+            model_used,
+            provider_used: "multi".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: TokenUsage::sum(usages),
+        };
+        if proposals.is_empty() {
+            let response = PalToolResponse::error(
+                format!("no model proposed options. {}", notes.join("; ")),
+                metadata(models.join(","), &usages),
+            );
+            return Ok(response.into_call_tool_result());
+        }
+        let proposed_by: Vec<String> = proposals.iter().map(|(m, _)| m.clone()).collect();
+
+        // The named synthesizer first, then each proposer in turn until one writes a record.
+        let mut synthesizers: Vec<String> = req
+            .synthesizer
+            .iter()
+            .filter(|s| !s.trim().is_empty())
+            .cloned()
+            .collect();
+        for model in &proposed_by {
+            if !synthesizers.contains(model) {
+                synthesizers.push(model.clone());
+            }
+        }
+        let prompt: Arc<str> =
+            crate::adr::synthesis_prompt(&req.question, file_context.as_deref(), &proposals).into();
+        let mut record = None;
+        for model in &synthesizers {
+            if Instant::now() >= deadline {
+                notes.push("deadline reached before a record was synthesized".to_string());
+                break;
+            }
+            match self.registry.query(&query(prompt.clone(), model)).await {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    match crate::adr::parse_body(&r.text) {
+                        Some(body) => {
+                            record = Some((model.clone(), body));
+                            break;
+                        }
+                        None => notes.push(format!(
+                            "{model}: synthesis reply had no Context/Decision sections"
+                        )),
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("adr: synthesis by {model} failed: {e}");
+                    notes.push(format!("synthesis by {model} failed: {}", e.user_message()));
+                }
+            }
+        }
+        let Some((synthesized_by, body)) = record else {
+            let response = PalToolResponse::error(
+                format!("no model synthesized a record. {}", notes.join("; ")),
+                metadata(proposed_by.join(","), &usages),
+            );
+            return Ok(response.into_call_tool_result());
+        };
+
+        let title = req
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| crate::adr::title_from(&req.question));
+        let mut adr = crate::adr::Adr::new(title, proposed_by.clone(), synthesized_by, body);
+        let path = if req.persist.unwrap_or(true) {
+            let path = crate::adr::save(&base_dir.join(&docs_dir), &mut adr)
+                .await
+                .map_err(|e| McpError::internal_error(format!("adr: {e}"), None))?;
+            Some(
+                path.strip_prefix(&base_dir)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string(),
+            )
+        } else {
+            None
+        };
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => crate::adr::to_markdown(&adr, path.as_deref(), &notes),
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "adr": adr,
+                "markdown": adr.to_markdown(),
+                "path": path,
+                "proposals": proposals
+                    .iter()
+                    .map(|(m, text)| (m.clone(), text.clone()))
+                    .collect::<HashMap<_, _>>(),
+                "notes": notes,
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(content, metadata(proposed_by.join(","), &usages));
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "plan",
+        description = "Plan an implementation with several models. Each model proposes its own plan for the task (with file_paths as context), then a synthesizer merges them into one ordered step list: each step has files, dependencies on earlier steps, the models that proposed it, and any disagreement between the plans. Returns structured steps ready to hand to a coding agent. Use `listmodels` for model names. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn plan(
+        &self,
+        Parameters(req): Parameters<PlanRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.task).map_err(|msg| McpError::invalid_params(msg, None))?;
+        let start = Instant::now();
+
+        let mut file_context = None;
+        if let Some(ref file_paths) = req.file_paths {
+            let wd = req.working_directory.as_deref().ok_or_else(|| {
+                McpError::invalid_params(
+                    "working_directory is required when file_paths is set",
+                    None,
+                )
+            })?;
+            let base_dir = self
+                .validate_working_directory(wd)
+                .await
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            file_context = context::resolve_file_context(
+                file_paths,
+                &base_dir,
+                context::MAX_FILE_CONTEXT_BYTES,
+                req.context_format.unwrap_or_default(),
+            )
+            .await
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?
+            .context;
+        }
+
+        let models = req
+            .models
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.review_config().default_models.clone());
+        if models.is_empty() {
+            return Err(McpError::invalid_params(
+                "no models given and no [review] default_models configured",
+                None,
+            ));
+        }
+        if models.len() > crate::review::MAX_MODELS {
+            return Err(McpError::invalid_params(
+                format!("at most {} models", crate::review::MAX_MODELS),
+                None,
+            ));
+        }
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(crate::plan::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let query =
+            |prompt: Arc<str>, model: &str, system_prompt: Option<String>| ProviderRequest {
+                prompt,
+                model: model.to_string(),
+                deadline,
+                working_directory: None,
+                system_prompt,
+                temperature: None,
+                max_tokens: None,
+                reasoning_effort: None,
+                cancellation_token: None,
+                stall_timeout: None,
+                max_output_bytes: None,
+                priority: None,
+                extra_body: None,
+                usage: Some(self.usage_tag("plan")),
+            };
+
+        let prompt: Arc<str> =
+            crate::plan::proposal_prompt(&req.task, file_context.as_deref()).into();
+        let requests: Vec<ProviderRequest> = models
+            .iter()
+            .map(|m| query(prompt.clone(), m, req.system_prompt.clone()))
+            .collect();
+        let results =
+            futures_util::future::join_all(requests.iter().map(|r| self.registry.query(r))).await;
+        let mut proposals = Vec::new();
+        let mut notes = Vec::new();
+        let mut usages = Vec::new();
+        for (model, result) in models.iter().zip(results) {
+            match result {
+                Ok(r) if !r.text.trim().is_empty() => {
+                    usages.extend(r.usage);
+                    proposals.push((model.clone(), r.text));
+                }
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    notes.push(format!("{model}: empty reply"));
+                }
+                Err(e) => {
+                    tracing::warn!("plan: {model} failed: {e}");
+                    notes.push(format!("{model}: {}", e.user_message()));
+                }
+            }
+        }
+        let metadata = |model_used: String, usages: &[TokenUsage]| PalMetadata {
+            tool_name: "plan".to_string(),
+            model_used,
+            provider_used: "multi".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: TokenUsage::sum(usages),
+        };
+        if proposals.is_empty() {
+            let response = PalToolResponse::error(
+                format!("no model proposed a plan. {}", notes.join("; ")),
+                metadata(models.join(","), &usages),
+            );
+            return Ok(response.into_call_tool_result());
+        }
+        let proposed_by: Vec<String> = proposals.iter().map(|(m, _)| m.clone()).collect();
+
+        // The named synthesizer first, then each proposer in turn until one merges the plans.
+        let mut synthesizers: Vec<String> = req
+            .synthesizer
+            .iter()
+            .filter(|s| !s.trim().is_empty())
+            .cloned()
+            .collect();
+        for model in &proposed_by {
+            if !synthesizers.contains(model) {
+                synthesizers.push(model.clone());
+            }
+        }
+        let prompt: Arc<str> =
+            crate::plan::synthesis_prompt(&req.task, file_context.as_deref(), &proposals).into();
+        let mut merged = None;
+        for model in &synthesizers {
+            if Instant::now() >= deadline {
+                notes.push("deadline reached before the plans were merged".to_string());
+                break;
+            }
+            match self
+                .registry
+                .query(&query(prompt.clone(), model, None))
+                .await
+            {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    match crate::plan::parse(&r.text, &proposed_by, model) {
+                        Some(plan) => {
+                            merged = Some(plan);
+                            break;
+                        }
+                        None => notes.push(format!("{model}: merge reply had no plan steps")),
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("plan: merge by {model} failed: {e}");
+                    notes.push(format!("merge by {model} failed: {}", e.user_message()));
+                }
+            }
+        }
+        let Some(mut plan) = merged else {
+            let response = PalToolResponse::error(
+                format!("no model merged the plans. {}", notes.join("; ")),
+                metadata(proposed_by.join(","), &usages),
+            );
+            return Ok(response.into_call_tool_result());
+        };
+        plan.notes = notes;
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => plan.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "plan": plan,
+                "proposals": proposals
+                    .iter()
+                    .map(|(m, text)| (m.clone(), text.clone()))
+                    .collect::<HashMap<_, _>>(),
+            }))
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(content, metadata(proposed_by.join(","), &usages));
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "refine",
+        description = "Critique-and-revise chain: an author model drafts, a critic model reviews the draft against a rubric, and the author revises, for 1-3 rounds (stops early when the critic approves). Returns the final draft and last critique; every intermediate draft and critique is saved under .squall/refine/. Use `listmodels` for model names.",
+        annotations(read_only_hint = true)
+    )]
+    async fn refine(
+        &self,
+        Parameters(req): Parameters<RefineRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.prompt).map_err(|msg| McpError::invalid_params(msg, None))?;
+        context::validate_temperature(req.temperature)
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let start = Instant::now();
+
+        let mut file_context = None;
+        if let Some(ref file_paths) = req.file_paths {
+            let wd = req.working_directory.as_deref().ok_or_else(|| {
+                McpError::invalid_params(
+                    "working_directory is required when file_paths is set",
+                    None,
+                )
+            })?;
+            let base_dir = self
+                .validate_working_directory(wd)
+                .await
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            let fmt = req.context_format.unwrap_or_default();
+            file_context = context::resolve_file_context(
+                file_paths,
+                &base_dir,
+                context::MAX_FILE_CONTEXT_BYTES,
+                fmt,
+            )
+            .await
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?
+            .context;
+        }
+
+        let opts = RefineOptions {
+            author_model: req.author_model_or_default().to_string(),
+            critic_model: req.critic_model_or_default().to_string(),
+            rubric: req
+                .rubric
+                .clone()
+                .filter(|r| !r.trim().is_empty())
+                .unwrap_or_else(|| crate::refine::DEFAULT_RUBRIC.to_string()),
+            rounds: req.rounds(),
+            author_system_prompt: req.author_system_prompt.clone(),
+            critic_system_prompt: req.critic_system_prompt.clone(),
+            temperature: req.temperature,
+            // The whole chain shares one MCP call: use the full 10 min ceiling.
+            deadline: Instant::now() + Duration::from_secs(600),
+            usage: Some(self.usage_tag("refine")),
+        };
+
+        let response =
+            match crate::refine::run(&self.registry, &req.prompt, file_context.as_deref(), &opts)
+                .await
+            {
+                Ok(outcome) => {
+                    let results_file =
+                        match crate::refine::persist(&req.prompt, &opts, &outcome).await {
+                            Ok(path) => Some(path),
+                            Err(e) => {
+                                tracing::warn!("refine: failed to persist chain: {e}");
+                                None
+                            }
+                        };
+                    PalToolResponse::success(
+                        outcome.to_markdown(results_file.as_deref()),
+                        PalMetadata {
+                            tool_name: "refine".to_string(),
+                            model_used: opts.author_model.clone(),
+                            provider_used: "multi".to_string(),
+                            duration_seconds: start.elapsed().as_secs_f64(),
+                            usage: outcome.usage(),
+                        },
+                    )
+                }
+                Err(e) => {
+                    tracing::warn!("refine: first draft failed: {e}");
+                    let provider = e.provider().unwrap_or("unknown").to_string();
+                    PalToolResponse::error(
+                        e.user_message(),
+                        PalMetadata {
+                            tool_name: "refine".to_string(),
+                            model_used: opts.author_model.clone(),
+                            provider_used: provider,
+                            duration_seconds: start.elapsed().as_secs_f64(),
+                            usage: None,
+                        },
+                    )
+                }
+            };
+
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "summarize",
+        description = "Summarize large content (text or files) to a target length. Splits it into chunks summarized by a fast model, then merges them with a stronger model. Use to condense tool output or long documents before further analysis.",
+        annotations(read_only_hint = true)
+    )]
+    async fn summarize(
+        &self,
+        Parameters(req): Parameters<SummarizeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let mut content = String::new();
+        if let Some(ref file_paths) = req.file_paths {
+            let wd = req.working_directory.as_deref().ok_or_else(|| {
+                McpError::invalid_params(
+                    "working_directory is required when file_paths is set",
+                    None,
+                )
+            })?;
+            let base_dir = self
+                .validate_working_directory(wd)
+                .await
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            let file_result = context::resolve_file_context(
+                file_paths,
+                &base_dir,
+                context::MAX_FILE_CONTEXT_BYTES,
+                context::ContextFormat::Xml,
+            )
+            .await
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            if let Some(ctx) = file_result.context {
+                content = ctx;
+            }
+        }
+        if let Some(ref text) = req.content {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(text);
+        }
+        if content.trim().is_empty() {
+            return Err(McpError::invalid_params(
+                "content or file_paths is required and must not be empty",
+                None,
+            ));
+        }
+
+        let opts = SummarizeOptions {
+            map_model: req.map_model_or_default().to_string(),
+            reduce_model: req.reduce_model_or_default().to_string(),
+            target_words: req.target_words_or_default(),
+            focus: req.focus.clone(),
+            chunk_bytes: req.chunk_bytes_or_default(),
+            // Map and reduce share one MCP call: use the full 10 min ceiling.
+            deadline: Instant::now() + Duration::from_secs(600),
+            usage: Some(self.usage_tag("summarize")),
+        };
+        let chunks = crate::summarize::chunk(&content, opts.chunk_bytes).len();
+        if chunks > crate::summarize::MAX_CHUNKS {
+            return Err(McpError::invalid_params(
+                format!(
+                    "content splits into {chunks} chunks (max {}); raise chunk_bytes",
+                    crate::summarize::MAX_CHUNKS
+                ),
+                None,
+            ));
+        }
+
+        let response = match crate::summarize::run(&self.registry, &content, &opts).await {
+            Ok(outcome) => {
+                let mut text = outcome.text;
+                if !outcome.failed_chunks.is_empty() {
+                    text.push_str(&format!(
+                        "\n\n_Note: {} of {} chunks could not be summarized by {}._",
+                        outcome.failed_chunks.len(),
+                        outcome.chunks,
+                        opts.map_model
+                    ));
+                }
+                PalToolResponse::success(
+                    text,
+                    PalMetadata {
+                        tool_name: "summarize".to_string(),
+                        model_used: outcome.model,
+                        provider_used: outcome.provider,
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: outcome.usage,
+                    },
+                )
+            }
+            Err(e) => {
+                tracing::warn!("summarize failed: {e}");
+                let provider = e.provider().unwrap_or("unknown").to_string();
+                PalToolResponse::error(
+                    e.user_message(),
+                    PalMetadata {
+                        tool_name: "summarize".to_string(),
+                        model_used: opts.reduce_model,
+                        provider_used: provider,
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
+                    },
+                )
+            }
+        };
+
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "review",
+        description = "For code review: use the `squall-unified-review` skill instead of calling this tool directly. PREREQUISITE: call `memory` then `listmodels` BEFORE calling this tool. Do NOT hardcode model names — use ONLY names from `listmodels` output.\n\nConsult multiple models in parallel with straggler cutoff. Assign expertise lenses via per_model_system_prompts — falsification framing ('attempt to PROVE X') produces the best results.",
+        annotations(read_only_hint = true)
+    )]
+    async fn review(
+        &self,
+        Parameters(mut req): Parameters<ReviewRequest>,
+        request_ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        context::validate_prompt(&req.prompt).map_err(|msg| McpError::invalid_params(msg, None))?;
+        context::validate_temperature(req.temperature)
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        context::validate_extra_body(req.extra_body.as_ref())
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        if let Some(ref per_model) = req.per_model_temperature {
+            for (model, temp) in per_model {
+                context::validate_temperature(Some(*temp)).map_err(|msg| {
+                    McpError::invalid_params(format!("per_model_temperature[{model}]: {msg}"), None)
+                })?;
+            }
+        }
+        if let Some(ref rubric) = req.rubric {
+            crate::rubric::validate(rubric)
+                .map_err(|msg| McpError::invalid_params(format!("rubric: {msg}"), None))?;
+        }
+
+        let start = std::time::Instant::now();
+
+        // Resolve working directory (same pattern as clink handler).
+        // Use canonical path from validate_working_directory() to prevent TOCTOU.
+        if req.file_paths.is_some() && req.working_directory.is_none() {
+            return Err(McpError::invalid_params(
+                "working_directory is required when file_paths is set",
+                None,
+            ));
+        }
+        let workspace = match req.working_directory {
+            Some(ref wd) => Some(self.resolve_workspace(wd).await?),
+            None => None,
+        };
+        let base_dir = workspace.as_ref().map(|ws| ws.primary().to_path_buf());
+        let working_directory = base_dir
+            .as_ref()
+            .map(|dir| dir.to_string_lossy().to_string());
+
+        // Branch + commit are persisted with every review so `incremental` can find it later.
+        let git_ctx = match base_dir {
+            Some(ref dir) => self.git_cache.get_or_detect(dir).await,
+            None => None,
+        };
+
+        // Incremental: narrow file_paths and diff to files changed since the last review
+        // of this branch; findings on unchanged files carry forward.
+        let mut prep_warnings = Vec::new();
+        let mut incremental = None;
+        if req.incremental == Some(true) {
+            if workspace.as_ref().is_some_and(Workspace::is_multi) {
+                return Err(McpError::invalid_params(
+                    "incremental reviews one repository; pass a single working_directory",
+                    None,
+                ));
+            }
+            let dir = base_dir.as_deref().ok_or_else(|| {
+                McpError::invalid_params("incremental requires working_directory", None)
+            })?;
+            let branch = git_ctx
+                .as_ref()
+                .and_then(|g| g.branch.clone())
+                .ok_or_else(|| {
+                    McpError::invalid_params(
+                        "incremental requires a git branch (not a repo, or detached HEAD)",
+                        None,
+                    )
+                })?;
+            match crate::incremental::find_last_review(&self.reviews_dir(), &branch).await {
+                None => prep_warnings.push(format!(
+                    "incremental: no previous review for branch {branch}; reviewed everything"
+                )),
+                Some(previous) => {
+                    match crate::incremental::changed_files_since(dir, &previous.commit_sha).await {
+                        Err(e) => {
+                            prep_warnings.push(format!("incremental: {e}; reviewed everything"))
+                        }
+                        Ok(changed) => {
+                            let carried_forward =
+                                crate::incremental::carry_forward(&previous.findings, &changed);
+                            if changed.is_empty() {
+                                return Ok(incremental_unchanged_response(
+                                    &previous,
+                                    &carried_forward,
+                                    start,
+                                ));
+                            }
+                            req.file_paths = match req.file_paths.take() {
+                                Some(paths) => Some(
+                                    paths.into_iter().filter(|p| changed.contains(p)).collect(),
+                                ),
+                                // Deleted files have nothing left to read.
+                                None => Some(
+                                    changed
+                                        .iter()
+                                        .filter(|f| dir.join(f).is_file())
+                                        .take(context::MAX_FILE_PATHS)
+                                        .cloned()
+                                        .collect(),
+                                ),
+                            }
+                            .filter(|paths: &Vec<String>| !paths.is_empty());
+                            if let Some(ref diff_text) = req.diff {
+                                let (filtered, _) = context::filter_diff_sections(diff_text, |p| {
+                                    changed.iter().any(|c| c == p)
+                                });
+                                req.diff = Some(filtered).filter(|d| !d.trim().is_empty());
+                            }
+                            incremental = Some(crate::incremental::IncrementalReview {
+                                base_review: previous.results_file,
+                                base_commit: previous.commit_sha,
+                                changed_files: changed,
+                                carried_forward,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Hot spots first: file context is budgeted in order.
+        if self.review_config().hotspot_boost
+            && let Some(ref mut paths) = req.file_paths
+            && paths.len() > 1
+        {
+            let hotspots = self.memory_store().get_hotspots().await;
+            let moved = crate::hotspots::prioritize(paths, &hotspots);
+            if !moved.is_empty() {
+                tracing::info!("hotspot_boost: budgeting {} first", moved.join(", "));
+            }
+        }
+
+        let url_result = match req.urls {
+            Some(ref urls) => Some(
+                crate::urls::resolve_url_context(urls)
+                    .await
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?,
+            ),
+            None => None,
+        };
+
+        // Sections for `.squallignore`d files are dropped from the diff before budgeting.
+        let mut files_skipped: Option<Vec<String>> = None;
+        let mut files_errors = None;
+        let mut diff = req.diff.as_deref().map(Cow::Borrowed);
+        if let Some(ref diff_text) = diff
+            && let Some(ref ws) = workspace
+        {
+            let (filtered, dropped) = ws.filter_ignored_diff(diff_text);
+            if !dropped.is_empty() {
+                files_skipped = Some(
+                    dropped
+                        .into_iter()
+                        .map(|name| format!("{name} (diff, {})", context::SQUALLIGNORE_FILE))
+                        .collect(),
+                );
+                diff = Some(Cow::Owned(filtered));
+            }
+        }
+
+        // Packages narrow a monorepo review to the chosen subtrees.
+        let packages = context::validate_packages(req.packages.as_deref().unwrap_or_default())
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        if !packages.is_empty() {
+            let mut outside = Vec::new();
+            if let Some(paths) = req.file_paths.take() {
+                let (kept, dropped): (Vec<String>, Vec<String>) = paths
+                    .into_iter()
+                    .partition(|p| context::in_packages(p, &packages));
+                outside.extend(
+                    dropped
+                        .into_iter()
+                        .map(|name| format!("{name} (outside packages)")),
+                );
+                req.file_paths = (!kept.is_empty()).then_some(kept);
+            }
+            if let Some(ref diff_text) = diff {
+                let (filtered, dropped) = context::filter_diff_sections(diff_text, |path| {
+                    context::in_packages(path, &packages)
+                });
+                if !dropped.is_empty() {
+                    outside.extend(
+                        dropped
+                            .into_iter()
+                            .map(|name| format!("{name} (diff, outside packages)")),
+                    );
+                    diff = (!filtered.trim().is_empty()).then_some(Cow::Owned(filtered));
+                }
+            }
+            if req.file_paths.is_none() && diff.is_none() {
+                prep_warnings.push(format!(
+                    "packages: no file_paths or diff sections under {}",
+                    packages.join(", ")
+                ));
+            }
+            if !outside.is_empty() {
+                files_skipped.get_or_insert_with(Vec::new).extend(outside);
+            }
+        }
+
+        // Triage-then-deep: a cheap first pass picks the riskiest files; the main
+        // models then review only those, in deep mode.
+        let mut triage = None;
+        if let Some(spec) = req.triage_then_deep.clone() {
+            if spec.models.is_empty() {
+                return Err(McpError::invalid_params(
+                    "triage_then_deep.models must name at least one model",
+                    None,
+                ));
+            }
+            let mut candidates = req.file_paths.clone().unwrap_or_default();
+            for path in diff
+                .as_deref()
+                .map(crate::gentests::diff_paths)
+                .unwrap_or_default()
+            {
+                if !candidates.contains(&path) {
+                    candidates.push(path);
+                }
+            }
+            if candidates.is_empty() {
+                prep_warnings.push(
+                    "triage_then_deep: no file_paths or diff to triage; reviewed as usual"
+                        .to_string(),
+                );
+            } else {
+                let max_files = crate::triage::max_files(&spec);
+                let mut triage_req = crate::triage::triage_request(&req, &spec);
+                triage_req.diff = diff.as_deref().map(str::to_string);
+                let (triage_prompt, _) = assemble_review_prompt(
+                    &self.prompt_cache,
+                    &triage_req,
+                    workspace.as_ref(),
+                    diff.as_deref(),
+                    context::MAX_FILE_CONTEXT_BYTES,
+                )
+                .await?;
+                let response = ReviewExecutor::new(self.registry.clone())
+                    .with_git_context(git_ctx.clone())
+                    .with_usage(self.usage_tag("review"))
+                    .with_reviews_dir(self.reviews_dir())
+                    .execute(
+                        &triage_req,
+                        crate::triage::triage_prompt(&triage_prompt, &candidates, max_files),
+                        self.memory_store(),
+                        working_directory.clone(),
+                        None,
+                        None,
+                        Some(self.review_config()),
+                    )
+                    .await;
+                let risky = crate::triage::rank(&response.results, &candidates, max_files);
+                if risky.is_empty() {
+                    prep_warnings.push(
+                        "triage_then_deep: the triage pass flagged no file; reviewed everything"
+                            .to_string(),
+                    );
+                } else {
+                    let picked = |path: &str| risky.iter().any(|f| f.path == path);
+                    let passed_over: Vec<String> = candidates
+                        .iter()
+                        .filter(|path| !picked(path.as_str()))
+                        .map(|path| format!("{path} (not picked by triage)"))
+                        .collect();
+                    req.file_paths = req
+                        .file_paths
+                        .take()
+                        .map(|paths| paths.into_iter().filter(|p| picked(p.as_str())).collect())
+                        .filter(|paths: &Vec<String>| !paths.is_empty());
+                    if let Some(ref diff_text) = diff {
+                        let (filtered, _) = context::filter_diff_sections(diff_text, picked);
+                        diff = (!filtered.trim().is_empty()).then_some(Cow::Owned(filtered));
+                    }
+                    if !passed_over.is_empty() {
+                        files_skipped
+                            .get_or_insert_with(Vec::new)
+                            .extend(passed_over);
+                    }
+                }
+                req.deep = Some(true);
+                triage = Some(crate::triage::TriageOutcome {
+                    models: response
+                        .results
+                        .iter()
+                        .filter(|r| r.status == ModelStatus::Success)
+                        .map(|r| r.model.clone())
+                        .collect(),
+                    review_id: response.review_id,
+                    results_file: response.results_file,
+                    candidates: candidates.len(),
+                    risky,
+                });
+            }
+        }
+        let triage_block = triage
+            .as_ref()
+            .filter(|t| !t.risky.is_empty())
+            .map(|t| crate::triage::prompt_block(&t.risky));
+
+        // Policy rules for the files that made it this far add models and lenses.
+        let policy_root = base_dir.as_deref().unwrap_or(Path::new("."));
+        let policy = match crate::policy::Policy::load(policy_root)
+            .await
+            .map_err(|e| McpError::invalid_params(format!("policy: {e}"), None))?
+        {
+            Some(policy) => {
+                let mut changed = req.file_paths.clone().unwrap_or_default();
+                for path in diff
+                    .as_deref()
+                    .map(crate::gentests::diff_paths)
+                    .unwrap_or_default()
+                {
+                    if !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+                let mut available: Vec<(String, String)> = self
+                    .registry
+                    .list_models()
+                    .into_iter()
+                    .map(|(name, entry)| (name.clone(), entry.precision_tier.clone()))
+                    .collect();
+                available.sort();
+                let mut plan = policy.apply(
+                    &req,
+                    &changed,
+                    &self.review_config().default_models,
+                    &available,
+                );
+                plan.augment(&mut req.models, &mut req.per_model_system_prompts);
+                (!plan.is_empty()).then_some(plan)
+            }
+            None => None,
+        };
+
+        // Dependency-change risk: the lockfile/manifest delta leads the prompt.
+        let dependencies = match (req.dependency_risk, diff.as_deref()) {
+            (Some(true), Some(diff_text)) => {
+                let mut delta = crate::dependencies::DependencyDelta {
+                    changes: crate::dependencies::extract(diff_text),
+                    advisories_checked: false,
+                };
+                if delta.changes.is_empty() {
+                    prep_warnings.push(
+                        "dependency_risk: the diff changes no lockfile or manifest dependencies"
+                            .to_string(),
+                    );
+                    None
+                } else {
+                    if req.dependency_advisories == Some(true) {
+                        match crate::dependencies::fetch_advisories(&mut delta.changes).await {
+                            Ok(_) => delta.advisories_checked = true,
+                            Err(e) => prep_warnings.push(format!("dependency_advisories: {e}")),
+                        }
+                    }
+                    Some(delta)
+                }
+            }
+            (Some(true), None) => {
+                prep_warnings.push("dependency_risk requires a diff".to_string());
+                None
+            }
+            _ => None,
+        };
+        let dependency_block = dependencies.as_ref().map(crate::dependencies::prompt_block);
+
+        // Blame context has its own cap too; it annotates the diff as filtered above.
+        let blame = match (req.blame, workspace.as_ref(), diff.as_deref()) {
+            (Some(true), Some(ws), Some(_)) if ws.is_multi() => {
+                prep_warnings.push("blame is not supported for multi-repo reviews".to_string());
+                None
+            }
+            (Some(true), Some(ws), Some(diff_text)) => {
+                let blame = crate::blame::blame_context(ws.primary(), diff_text).await;
+                if blame.is_none() {
+                    prep_warnings.push("blame: no diff hunk could be blamed at HEAD".to_string());
+                }
+                blame
+            }
+            (Some(true), _, _) => {
+                prep_warnings.push("blame requires working_directory and diff".to_string());
+                None
+            }
+            _ => None,
+        };
+
+        // Multi-repo reviews tell the models which repository each path prefix names.
+        let repos = match workspace {
+            Some(ref ws) if ws.is_multi() => ws.git_contexts().await,
+            _ => Vec::new(),
+        };
+        let workspace_header = (!repos.is_empty()).then(|| crate::workspace::prompt_header(&repos));
+
+        // Every prompt (shared and budgeted) carries the same extra context around its files.
+        let decorate = |mut prompt: Arc<str>| {
+            if let Some(ref blame) = blame {
+                prompt = Arc::from(format!("{blame}\n{prompt}"));
+            }
+            if let Some(ref block) = triage_block {
+                prompt = Arc::from(format!("{block}\n{prompt}"));
+            }
+            if let Some(ref block) = dependency_block {
+                prompt = Arc::from(format!("{block}\n{prompt}"));
+            }
+            if let Some(ref header) = workspace_header {
+                prompt = Arc::from(format!("{header}\n{prompt}"));
+            }
+            // URL context has its own cap, so every budgeted prompt gets the same copy.
+            if let Some(ref url_result) = url_result {
+                prompt = Arc::from(url_result.prepend_to(prompt.to_string()));
+            }
+            prompt
+        };
+
+        // Shared prompt at the full budget; skipped/errored files are reported from it.
+        let (prompt, file_result) = assemble_review_prompt(
+            &self.prompt_cache,
+            &req,
+            workspace.as_ref(),
+            diff.as_deref(),
+            context::MAX_FILE_CONTEXT_BYTES,
+        )
+        .await?;
+        let prompt = decorate(prompt);
+        if let Some(ref url_result) = url_result {
+            prep_warnings.extend(url_result.warnings());
+        }
+        prep_warnings.extend(crate::review::injection_warnings(
+            file_result.as_ref(),
+            diff.as_deref(),
+        ));
+        let mut executor = ReviewExecutor::new(self.registry.clone())
+            .with_git_context(git_ctx)
+            .with_repos(repos)
+            .with_usage(self.usage_tag("review"))
+            .with_reviews_dir(self.reviews_dir());
+        if let Some(incremental) = incremental {
+            executor = executor.with_incremental(incremental);
+        }
+        if let Some(triage) = triage {
+            executor = executor.with_triage(triage);
+        }
+        if let Some(policy) = policy {
+            executor = executor.with_policy(policy);
+        }
+        if let Some(dependencies) = dependencies {
+            executor = executor.with_dependencies(dependencies);
+        }
+        for warning in prep_warnings {
+            executor = executor.with_warning(warning);
+        }
+        if let Some(file_result) = file_result {
+            let skipped = files_skipped.get_or_insert_with(Vec::new);
+            skipped.extend(
+                file_result
+                    .skipped
+                    .iter()
+                    .map(|(name, sz)| format!("{name} ({sz}B)")),
+            );
+            skipped.extend(
+                file_result
+                    .ignored
+                    .iter()
+                    .map(|name| format!("{name} ({})", context::SQUALLIGNORE_FILE)),
+            );
+            if skipped.is_empty() {
+                files_skipped = None;
+            }
+            if !file_result.errors.is_empty() {
+                files_errors = Some(file_result.errors.clone());
+            }
+            executor = executor.with_files_sent(crate::review::files_sent(&file_result));
+        }
+
+        // Models with another context budget get their own prompt, sized to fit.
+        if req.file_paths.is_some() || diff.is_some() {
+            let budgeted = crate::review::budgeted_prompts(
+                &self.registry,
+                req.models.as_deref(),
+                Some(self.review_config()),
+                |budget| {
+                    let (cache, req, decorate) = (&self.prompt_cache, &req, &decorate);
+                    let (workspace, diff) = (workspace.as_ref(), diff.as_deref());
+                    async move {
+                        let (prompt, file_result) =
+                            assemble_review_prompt(cache, req, workspace, diff, budget).await?;
+                        Ok::<_, McpError>((decorate(prompt), file_result))
+                    }
+                },
+            )
+            .await?;
+            executor = executor.with_budgeted_prompts(budgeted);
+        }
+
+        // Background stragglers report in after this call returns.
+        let (late_tx, late_rx) = tokio::sync::mpsc::unbounded_channel();
+        executor = executor.with_late_results(late_tx);
+
+        let prompt_len = prompt.len();
+        let wd_for_memory = working_directory.clone();
+        let mut stream = executor.execute_streaming(
+            &req,
+            prompt,
+            self.memory_store(),
+            working_directory,
+            files_skipped,
+            files_errors,
+            Some(self.review_config()),
+        );
+        // Clients that sent a progress token get a notification as each model lands.
+        let progress_token = request_ctx.meta.get_progress_token();
+        let mut landed = 0u32;
+        while let Some(result) = stream.next().await {
+            landed += 1;
+            if let Some(ref token) = progress_token
+                && let Err(e) = request_ctx
+                    .peer
+                    .notify_progress(ProgressNotificationParam {
+                        progress_token: token.clone(),
+                        progress: f64::from(landed),
+                        total: None,
+                        message: Some(progress_message(&result)),
+                    })
+                    .await
+            {
+                tracing::debug!("review: progress notification failed: {e}");
+            }
+        }
+        let review_response = stream
+            .into_response()
+            .ok_or_else(|| McpError::internal_error("review ended without a response", None))?;
+
+        if review_response
+            .results
+            .iter()
+            .any(|r| r.reason.as_deref() == Some("background"))
+        {
+            tokio::spawn(notify_late_results(
+                request_ctx.peer.clone(),
+                review_response.review_id.clone(),
+                review_response.results_file.clone(),
+                late_rx,
+            ));
+        }
+
+        // Log model metrics to memory (non-blocking, fire-and-forget)
+        let memory = self.memory_store().clone();
+        let results_for_memory = review_response.results.clone();
+        // Samples ("grok#2") count toward their model's stats.
+        let mut id_to_key = self.registry.model_id_to_key();
+        id_to_key.extend(results_for_memory.iter().filter_map(|r| {
+            crate::consistency::sample_of(&r.model)
+                .map(|(model, _)| (r.model.clone(), model.to_string()))
+        }));
+        let scope = crate::review::memory_scope(review_response.git.as_ref());
+        tokio::spawn(async move {
+            memory
+                .log_model_metrics_scoped(
+                    &results_for_memory,
+                    prompt_len,
+                    Some(&id_to_key),
+                    wd_for_memory.as_deref(),
+                    scope.as_deref(),
+                )
+                .await;
+        });
+
+        // Render the review response as markdown for MCP (disk file stays JSON)
+        let concise = matches!(req.response_format, Some(ResponseFormat::Concise));
+        let content = review_response.to_markdown(concise);
+
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "review".to_string(),
+                model_used: "multi".to_string(),
+                provider_used: "multi".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: TokenUsage::sum(
+                    review_response
+                        .results
+                        .iter()
+                        .filter_map(|r| r.usage.as_ref()),
+                ),
+            },
+        );
+
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "memorize",
+        description = "Save your synthesized findings after a review: recurring patterns, effective tactics, and model recommendations."
+    )]
+    async fn memorize(
+        &self,
+        Parameters(req): Parameters<MemorizeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let scope = self
+            .resolve_memorize_scope(req.scope.as_deref(), req.working_directory.as_deref())
+            .await?;
+
+        match self
+            .memory_store()
+            .memorize(
+                req.category.as_str(),
+                &req.content,
+                req.model.as_deref(),
+                req.tags.as_deref(),
+                scope.as_deref(),
+                req.metadata.as_ref(),
+            )
+            .await
+        {
+            Ok(path) => {
+                let response = PalToolResponse::success(
+                    format!("Saved to {path}"),
+                    PalMetadata {
+                        tool_name: "memorize".to_string(),
+                        model_used: "none".to_string(),
+                        provider_used: "none".to_string(),
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
+                    },
+                );
+                Ok(response.into_call_tool_result())
+            }
+            Err(msg) => Err(McpError::invalid_params(msg, None)),
+        }
+    }
+
+    #[tool(
+        name = "memorize_review",
+        description = "Bulk-memorize a persisted review: extracts findings from each model's response, groups the same issue across models, and saves every finding reported by at least min_consensus models (default 2) as a pattern with models/consensus/diff_size metadata."
+    )]
+    async fn memorize_review(
+        &self,
+        Parameters(req): Parameters<MemorizeReviewRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let scope = self
+            .resolve_memorize_scope(req.scope.as_deref(), req.working_directory.as_deref())
+            .await?;
+
+        match self
+            .memory_store()
+            .memorize_from_review(&req.review_id, req.min_consensus(), scope.as_deref())
+            .await
+        {
+            Ok(report) => {
+                let response = PalToolResponse::success(
+                    report,
+                    PalMetadata {
+                        tool_name: "memorize_review".to_string(),
+                        model_used: "none".to_string(),
+                        provider_used: "none".to_string(),
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
+                    },
+                );
+                Ok(response.into_call_tool_result())
+            }
+            Err(msg) => Err(McpError::invalid_params(msg, None)),
+        }
+    }
+
+    #[tool(
+        name = "memory",
+        description = "Read prior patterns, tactics, and model recommendations to inform model selection and review lenses. Category disagreements lists findings models contradicted each other on and which side feedback backed. Narrow patterns with tags, scope/scope_prefix, since (YYYY-MM-DD), and min_evidence to fit a tight max_chars budget.",
+        annotations(read_only_hint = true)
+    )]
+    async fn memory(
+        &self,
+        Parameters(req): Parameters<MemoryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let query = req.query();
+        query
+            .validate()
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+
+        match self
+            .memory_store()
+            .read_memory_query(
+                req.category.as_ref().map(|c| c.as_str()),
+                req.max_chars(),
+                &query,
+            )
+            .await
+        {
+            Ok(content) => {
+                let response = PalToolResponse::success(
+                    content,
+                    PalMetadata {
+                        tool_name: "memory".to_string(),
+                        model_used: "none".to_string(),
+                        provider_used: "none".to_string(),
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
+                    },
+                );
+                Ok(response.into_call_tool_result())
+            }
+            Err(msg) => Err(McpError::internal_error(msg, None)),
+        }
+    }
+
+    #[tool(
+        name = "flush",
+        description = "Flush working memory after PR merge. Graduates high-evidence patterns (>=3 occurrences) from branch scope to codebase scope. Archives low-evidence branch patterns. Prunes model events older than 30 days."
+    )]
+    async fn flush(
+        &self,
+        Parameters(req): Parameters<FlushRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        if req.branch.trim().is_empty() {
+            return Err(McpError::invalid_params("branch must not be empty", None));
+        }
+
+        match self.memory_store().flush_branch(&req.branch).await {
+            Ok(report) => {
+                let response = PalToolResponse::success(
+                    report,
+                    PalMetadata {
+                        tool_name: "flush".to_string(),
+                        model_used: "none".to_string(),
+                        provider_used: "none".to_string(),
+                        duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
+                    },
                 );
                 Ok(response.into_call_tool_result())
             }
@@ -491,34 +2990,388 @@ impl SquallServer {
     }
 
     #[tool(
-        name = "flush",
-        description = "Flush working memory after PR merge. Graduates high-evidence patterns (>=3 occurrences) from branch scope to codebase scope. Archives low-evidence branch patterns. Prunes model events older than 30 days."
+        name = "global_memory_status",
+        description = "Report cross-project (global) memory health: bootstrap progress, queued events, last merge time, DB size, and recent worker errors. Use when recommendations look stale or empty.",
+        annotations(read_only_hint = true)
+    )]
+    async fn global_memory_status(&self) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+        let response = PalToolResponse::success(
+            self.memory_store().global_status_report(),
+            PalMetadata {
+                tool_name: "global_memory_status".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "reviews_search",
+        description = "Keyword search across persisted reviews (prompts, investigation notes, extracted findings, and model responses). Filter by models, since/until (YYYY-MM-DD), branch, and tags. Returns ranked snippets with review file paths.",
+        annotations(read_only_hint = true)
     )]
-    async fn flush(
+    async fn reviews_search(
         &self,
-        Parameters(req): Parameters<FlushRequest>,
+        Parameters(req): Parameters<ReviewsSearchRequest>,
     ) -> Result<CallToolResult, McpError> {
         let start = Instant::now();
 
-        if req.branch.trim().is_empty() {
-            return Err(McpError::invalid_params("branch must not be empty", None));
+        let query = req.query();
+        query
+            .validate()
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+
+        let results = self.review_index.search(&self.reviews_dir(), &query).await;
+        let response = PalToolResponse::success(
+            results.to_markdown(&query),
+            PalMetadata {
+                tool_name: "reviews_search".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "reviews_list",
+        description = "List persisted reviews, newest first, with branch, tags, model outcomes, and the start of each prompt. Filter by tags (all must match), branch, and since/until (YYYY-MM-DD) to find the reviews that validated a ticket or PR.",
+        annotations(read_only_hint = true)
+    )]
+    async fn reviews_list(
+        &self,
+        Parameters(req): Parameters<ReviewsListRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let query = req.query();
+        query
+            .validate()
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+
+        let list = self.review_index.list(&self.reviews_dir(), &query).await;
+        let response = PalToolResponse::success(
+            list.to_markdown(),
+            PalMetadata {
+                tool_name: "reviews_list".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "reviews_compare",
+        description = "Compare two persisted reviews (e.g. before and after a fix): findings that are new, resolved, or persisting, overall and per model, plus severity and model-outcome deltas. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn reviews_compare(
+        &self,
+        Parameters(req): Parameters<ReviewsCompareRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let comparison = crate::compare::compare_reviews(&self.reviews_dir(), &req.id_a, &req.id_b)
+            .await
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => comparison.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&comparison)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "reviews_compare".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "suite_create",
+        description = "Create a prompt suite: a named set of prompts with optional expected-property checks (contains, not_contains, min_words, max_words, json), saved as .squall/suites/{name}.toml. Suites are shared by suite_run and model_regress. Refuses to replace an existing suite unless overwrite: true."
+    )]
+    async fn suite_create(
+        &self,
+        Parameters(req): Parameters<SuiteCreateRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let suite = req.suite();
+        let path = crate::suites::save(
+            Path::new(crate::suites::SUITES_DIR),
+            &suite,
+            req.overwrite.unwrap_or(false),
+        )
+        .await
+        .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let response = PalToolResponse::success(
+            format!(
+                "Saved suite `{}` ({} prompt(s)) to {}.",
+                suite.name,
+                suite.prompts.len(),
+                path.display()
+            ),
+            PalMetadata {
+                tool_name: "suite_create".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "suite_list",
+        description = "List prompt suites in .squall/suites/ with their prompt counts and how many prompts carry checks. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn suite_list(
+        &self,
+        Parameters(req): Parameters<SuiteListRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let suites = crate::suites::list(Path::new(crate::suites::SUITES_DIR)).await;
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => crate::suites::list_to_markdown(&suites),
+            OutputFormat::Json => serde_json::to_string_pretty(&suites)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "suite_list".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "suite_run",
+        description = "Run a prompt suite on one or more models at temperature 0 and check each answer against its prompt's expected properties. Returns passed/total per model and every failed check. Use `listmodels` for model names. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn suite_run(
+        &self,
+        Parameters(req): Parameters<SuiteRunRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let mut models = req
+            .models
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.review_config().default_models.clone());
+        models.sort();
+        models.dedup();
+        if let Some(unknown) = models.iter().find(|m| self.registry.get(m).is_none()) {
+            return Err(McpError::invalid_params(
+                crate::error::SquallError::ModelNotFound {
+                    model: unknown.clone(),
+                    suggestions: self.registry.suggest_models(unknown),
+                }
+                .user_message(),
+                None,
+            ));
         }
+        if models.is_empty() {
+            return Err(McpError::invalid_params(
+                "no models to run the suite on",
+                None,
+            ));
+        }
+        let suite = crate::suites::load(Path::new(crate::suites::SUITES_DIR), &req.suite)
+            .await
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(ReviewRequest::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let run = crate::suites::run(
+            &self.registry,
+            &suite,
+            &models,
+            Instant::now() + Duration::from_secs(timeout_secs),
+            Some(self.usage_tag("suite_run")),
+        )
+        .await;
 
-        match self.memory.flush_branch(&req.branch).await {
-            Ok(report) => {
-                let response = PalToolResponse::success(
-                    report,
-                    PalMetadata {
-                        tool_name: "flush".to_string(),
-                        model_used: "none".to_string(),
-                        provider_used: "none".to_string(),
-                        duration_seconds: start.elapsed().as_secs_f64(),
-                    },
-                );
-                Ok(response.into_call_tool_result())
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => run.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&run)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "suite_run".to_string(),
+                model_used: "multi".to_string(),
+                provider_used: "multi".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: run.usage,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "model_regress",
+        description = "Regression watch: replay a pinned prompt suite (.squall/suites/{suite}.toml) against one model at temperature 0 and diff the answers with that model's stored baseline (word similarity, length, and the model version the provider reports). Flags a provider silently swapping or degrading a model behind the same name. The first run records the baseline; update_baseline: true re-records it. format: markdown (default) or json."
+    )]
+    async fn model_regress(
+        &self,
+        Parameters(req): Parameters<ModelRegressRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+        let invalid = |msg: String| McpError::invalid_params(msg, None);
+
+        let model = self
+            .registry
+            .canonical_name(&req.model)
+            .ok_or_else(|| {
+                invalid(
+                    crate::error::SquallError::ModelNotFound {
+                        model: req.model.clone(),
+                        suggestions: self.registry.suggest_models(&req.model),
+                    }
+                    .user_message(),
+                )
+            })?
+            .to_string();
+        crate::suites::validate_name("model name", &model).map_err(invalid)?;
+        let suites_dir = Path::new(crate::suites::SUITES_DIR);
+        let suite = crate::suites::load(suites_dir, &req.suite)
+            .await
+            .map_err(invalid)?;
+        let baseline = crate::regress::load_baseline(suites_dir, &suite.name, &model)
+            .await
+            .map_err(invalid)?;
+
+        let timeout_secs = req
+            .timeout_secs
+            .unwrap_or(ReviewRequest::DEFAULT_TIMEOUT_SECS)
+            .min(crate::review::MAX_TIMEOUT_SECS);
+        let replayed = crate::regress::replay(
+            &self.registry,
+            &suite,
+            &model,
+            Instant::now() + Duration::from_secs(timeout_secs),
+            Some(self.usage_tag("model_regress")),
+        )
+        .await;
+        let usage = TokenUsage::sum(replayed.iter().filter_map(|r| r.usage.as_ref()));
+
+        let min_similarity = req.min_similarity();
+        let mut report = match &baseline {
+            Some(baseline) => {
+                crate::regress::compare(&suite.name, &model, baseline, &replayed, min_similarity)
+            }
+            None => crate::regress::first_run(&suite.name, &model, &replayed, min_similarity),
+        };
+        if baseline.is_none() || req.update_baseline == Some(true) {
+            match crate::regress::baseline_from(
+                &suite.name,
+                &model,
+                &replayed,
+                crate::memory::iso_from_epoch_ms(crate::dispatch::ratelimit::now_ms() as i64),
+            ) {
+                Some(new_baseline) => {
+                    match crate::regress::save_baseline(suites_dir, &new_baseline).await {
+                        Ok(_) => report.baseline_written = true,
+                        Err(e) => report
+                            .alerts
+                            .push(format!("failed to save the baseline: {e}")),
+                    }
+                }
+                None => report
+                    .alerts
+                    .push("baseline not saved: some prompts failed".to_string()),
             }
-            Err(msg) => Err(McpError::internal_error(msg, None)),
         }
+        if report.regressed {
+            tracing::warn!(
+                "model_regress: {model} on suite {}: {}",
+                suite.name,
+                report.alerts.join("; ")
+            );
+        }
+
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => report.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&report)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let provider = self
+            .registry
+            .get(&model)
+            .map_or_else(|| "unknown".to_string(), |e| e.provider.clone());
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "model_regress".to_string(),
+                model_used: model,
+                provider_used: provider,
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+
+    #[tool(
+        name = "usage",
+        description = "Report model usage from the usage ledger over a date window (default: last 30 days): calls, failures, tokens, and estimated cost per model, per day, and per MCP client (claude-code, cursor, ci, ...). Filter by client. format: markdown (default) or json.",
+        annotations(read_only_hint = true)
+    )]
+    async fn usage(
+        &self,
+        Parameters(req): Parameters<UsageRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+
+        let query = req.query();
+        query
+            .validate()
+            .map_err(|msg| McpError::invalid_params(msg, None))?;
+        let records = UsageLedger::new(crate::usage::USAGE_LEDGER).read().await;
+        let report = crate::usage::report(&records, &query, &crate::memory::iso_date());
+        let content = match req.format.unwrap_or_default() {
+            OutputFormat::Markdown => report.to_markdown(),
+            OutputFormat::Json => serde_json::to_string_pretty(&report)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "usage".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
     }
 
     #[tool(
@@ -532,7 +3385,7 @@ impl SquallServer {
         let start = Instant::now();
 
         match self
-            .memory
+            .memory_store()
             .record_feedback(&req.review_file, &req.scores, req.note.as_deref())
             .await
         {
@@ -544,6 +3397,7 @@ impl SquallServer {
                         model_used: "none".to_string(),
                         provider_used: "none".to_string(),
                         duration_seconds: start.elapsed().as_secs_f64(),
+                        usage: None,
                     },
                 );
                 Ok(response.into_call_tool_result())
@@ -551,6 +3405,109 @@ impl SquallServer {
             Err(msg) => Err(McpError::invalid_params(msg, None)),
         }
     }
+
+    #[tool(
+        name = "set_log_level",
+        description = "Change the server's log filter without a restart, e.g. \"debug\" or \"info,squall::dispatch=trace\" (RUST_LOG syntax). Logs go to the server's stderr. Omit level to report the current filter."
+    )]
+    async fn set_log_level(
+        &self,
+        Parameters(req): Parameters<SetLogLevelRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let start = Instant::now();
+        let Some(ref handle) = self.log_level else {
+            return Err(McpError::invalid_request(
+                "log level is not adjustable in this process",
+                None,
+            ));
+        };
+        let content = match req.level.as_deref().filter(|l| !l.trim().is_empty()) {
+            Some(level) => {
+                let previous = handle
+                    .set(level)
+                    .map_err(|msg| McpError::invalid_params(msg, None))?;
+                format!("log filter: {previous} -> {}", handle.current())
+            }
+            None => format!("log filter: {}", handle.current()),
+        };
+        let response = PalToolResponse::success(
+            content,
+            PalMetadata {
+                tool_name: "set_log_level".to_string(),
+                model_used: "none".to_string(),
+                provider_used: "none".to_string(),
+                duration_seconds: start.elapsed().as_secs_f64(),
+                usage: None,
+            },
+        );
+        Ok(response.into_call_tool_result())
+    }
+}
+
+/// Tell the client as each background straggler of `review_id` lands in the
+/// results file: `notifications/resources/updated` for the review's resource URI,
+/// plus a log message naming the model, for clients that don't track resources.
+async fn notify_late_results(
+    peer: Peer<RoleServer>,
+    review_id: String,
+    results_file: Option<String>,
+    mut late: UnboundedReceiver<ReviewModelResult>,
+) {
+    let uri = crate::artifact::review_uri(&review_id);
+    while let Some(result) = late.recv().await {
+        let updated = peer
+            .notify_resource_updated(ResourceUpdatedNotificationParam { uri: uri.clone() })
+            .await;
+        let logged = peer
+            .notify_logging_message(LoggingMessageNotificationParam {
+                level: LoggingLevel::Info,
+                logger: Some("squall".to_string()),
+                data: serde_json::json!({
+                    "event": "late_result",
+                    "review_id": review_id,
+                    "resource": uri,
+                    "results_file": results_file,
+                    "model": result.model,
+                    "status": result.status,
+                }),
+            })
+            .await;
+        if let Err(e) = updated.and(logged) {
+            // The session is gone; the result is still in the results file.
+            tracing::debug!("late result notification for {review_id} failed: {e}");
+            return;
+        }
+    }
+}
+
+/// The repository containing `dir` (nearest ancestor with `.git`), else `dir`.
+fn project_root(dir: &Path) -> &Path {
+    dir.ancestors()
+        .find(|d| d.join(".git").exists())
+        .unwrap_or(dir)
+}
+
+/// Apply `[server]` tool description overrides to the generated router.
+fn configured_tool_router(
+    mut router: ToolRouter<SquallServer>,
+    config: &ServerConfig,
+) -> ToolRouter<SquallServer> {
+    let named = config
+        .tool_descriptions
+        .keys()
+        .chain(config.tool_notes.keys());
+    for name in named {
+        if !router.map.contains_key(name.as_str()) {
+            tracing::warn!("[server] config names unknown tool '{name}', ignoring");
+        }
+    }
+    for (name, route) in router.map.iter_mut() {
+        let builtin = route.attr.description.as_deref().unwrap_or_default();
+        if let Some(description) = config.tool_description(name, builtin) {
+            route.attr.description = Some(description.into());
+        }
+    }
+    router
 }
 
 /// Returns true if reasoning_effort warrants an extended deadline.
@@ -561,6 +3518,64 @@ pub fn reasoning_needs_extended_deadline(effort: Option<&ReasoningEffort>) -> bo
     )
 }
 
+/// One-line progress message for a model's result, e.g. "grok: success in 12.3s".
+fn progress_message(result: &ReviewModelResult) -> String {
+    let outcome = match (&result.status, result.reason.as_deref()) {
+        (ModelStatus::Success, _) if result.partial => "partial",
+        (ModelStatus::Success, _) => "success",
+        (ModelStatus::Error, Some(reason)) => reason,
+        (ModelStatus::Error, None) => "error",
+    };
+    format!(
+        "{}: {outcome} in {:.1}s",
+        result.model,
+        result.latency_ms as f64 / 1000.0
+    )
+}
+
+/// `PromptCache::assemble` with errors mapped to invalid params.
+async fn assemble_review_prompt(
+    cache: &PromptCache,
+    req: &ReviewRequest,
+    workspace: Option<&Workspace>,
+    diff: Option<&str>,
+    budget: usize,
+) -> Result<(Arc<str>, Option<FileContextResult>), McpError> {
+    cache
+        .assemble(req, workspace, diff, budget)
+        .await
+        .map_err(|e| McpError::invalid_params(e.to_string(), None))
+}
+
+/// Response for an incremental review when nothing changed since the base review.
+fn incremental_unchanged_response(
+    previous: &crate::incremental::PreviousReview,
+    carried_forward: &[crate::findings::Finding],
+    start: Instant,
+) -> CallToolResult {
+    let mut content = format!(
+        "## Review Summary\nNo files changed since `{}` ({}); no models dispatched.\n",
+        previous.commit_sha, previous.results_file
+    );
+    if !carried_forward.is_empty() {
+        content.push_str(&format!(
+            "\n{} finding(s) from that review still apply.\n",
+            carried_forward.len()
+        ));
+    }
+    PalToolResponse::success(
+        content,
+        PalMetadata {
+            tool_name: "review".to_string(),
+            model_used: "none".to_string(),
+            provider_used: "none".to_string(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            usage: None,
+        },
+    )
+    .into_call_tool_result()
+}
+
 #[tool_handler]
 impl ServerHandler for SquallServer {
     fn get_info(&self) -> ServerInfo {
@@ -570,32 +3585,162 @@ impl ServerHandler for SquallServer {
                 version: env!("CARGO_PKG_VERSION").to_string(),
                 ..Default::default()
             },
-            instructions: Some(
-                "Squall: parallel AI model dispatch. Each model is an independent consultant.\n\n\
-                 FOR CODE REVIEW: Use the `squall-unified-review` skill (invoke via Skill tool), \
-                    NOT these MCP tools directly. The skill handles depth detection, ensemble selection, \
-                    Opus agent orchestration, synthesis, and memorization. Calling `review` directly \
-                    skips all of that.\n\n\
-                 FOR DIRECT TOOL USE (chat, clink, research — not code review):\n\
-                 1. FIRST: Call `memory` (recommend/patterns/tactics) to check past learnings.\n\
-                 2. NEXT: Call `listmodels` for EXACT model names. NEVER hardcode names like \
-                    \"claude-sonnet\", \"gpt-4\", \"o4-mini\" — these are NOT Squall models. \
-                    Use ONLY names from `listmodels` output.\n\
-                 3. ONLY THEN: Call `review`/`chat`/`clink` with the task.\n\
-                    - Use falsification framing: 'Attempt to PROVE [issue] exists. Report confidence.'\n\
-                    - Set `deep: true` for security/architecture/high-stakes (600s, high reasoning).\n\
-                    - `results_file` persists on disk — read it if context compaction loses the response.\n\
-                 4. Triangulate model findings with your own investigation.\n\
-                 5. Call `memorize` to capture patterns, tactics, and model recommendations.\n\
-                 6. After PR merge: `flush` to graduate branch patterns to codebase scope.\n\n\
-                 DO NOT call `review` without calling `memory` and `listmodels` first.\n\n\
-                 File context: pass `file_paths` + `working_directory` to include source files.\n\
-                 For review, also pass `diff` with unified diff text.\n\
-                 Research: `clink` with model \"codex\" for web search, or `review` with models as advisors."
-                    .into(),
-            ),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            instructions: Some(self.instructions.clone()),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_logging()
+                .enable_resources()
+                .build(),
             ..Default::default()
         }
     }
+
+    /// MCP `initialize`: remember the client's name for the usage ledger, then
+    /// answer as usual.
+    async fn initialize(
+        &self,
+        request: InitializeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        let client = crate::usage::client_name(Some(request.client_info.name.as_str()));
+        if self.session.client.set(client.as_str().into()).is_ok() {
+            tracing::info!("client: {client}");
+        }
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        Ok(self.get_info())
+    }
+
+    /// MCP `resources/read` for `squall://reviews/{review_id}`: the persisted review
+    /// JSON, including late results appended since the review returned.
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let Some(review_id) = request.uri.strip_prefix(crate::artifact::REVIEW_URI_PREFIX) else {
+            return Err(McpError::resource_not_found(
+                format!("unknown resource: {}", request.uri),
+                None,
+            ));
+        };
+        let path = crate::artifact::review_path(&self.reviews_dir(), review_id)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let json = crate::artifact::read_review(&path)
+            .await
+            .map_err(|e| McpError::resource_not_found(format!("review {review_id}: {e}"), None))?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(json, request.uri)],
+        })
+    }
+
+    /// MCP `logging/setLevel`: map the RFC 5424 level onto the tracing filter.
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let Some(ref handle) = self.log_level else {
+            return Ok(());
+        };
+        let level = serde_json::to_value(request.level)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let filter = crate::logging::filter_for_mcp_level(&level).ok_or_else(|| {
+            McpError::invalid_params(format!("unknown logging level: {level}"), None)
+        })?;
+        handle
+            .set(filter)
+            .map(|_| ())
+            .map_err(|msg| McpError::internal_error(msg, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sessions_keep_reviews_and_defaults_in_their_own_project() {
+        let base = std::env::temp_dir().join(format!("squall_sessions_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let (a, b) = (base.join("a"), base.join("b"));
+        for dir in [&a, &b] {
+            std::fs::create_dir_all(dir.join(".git")).unwrap();
+            std::fs::create_dir_all(dir.join(".squall")).unwrap();
+        }
+        std::fs::write(
+            a.join(".squall/config.toml"),
+            "[review]\ndefault_models = [\"alpha\"]\n",
+        )
+        .unwrap();
+
+        let server = SquallServer::new(Config::default());
+        let (one, two) = (server.for_session(), server.for_session());
+        let a = one
+            .validate_working_directory(&a.to_string_lossy())
+            .await
+            .unwrap();
+        let b = two
+            .validate_working_directory(&b.to_string_lossy())
+            .await
+            .unwrap();
+        assert_eq!(one.review_config().default_models, ["alpha"]);
+        assert_ne!(two.review_config().default_models, ["alpha"]);
+
+        let req: ReviewRequest =
+            serde_json::from_value(serde_json::json!({"prompt": "zanzibar", "models": ["none"]}))
+                .unwrap();
+        let response = ReviewExecutor::new(one.registry.clone())
+            .with_reviews_dir(one.reviews_dir())
+            .execute(
+                &req,
+                "zanzibar",
+                one.memory_store(),
+                None,
+                None,
+                None,
+                Some(one.review_config()),
+            )
+            .await;
+        let file = PathBuf::from(response.results_file.expect("persisted"));
+        assert!(file.starts_with(a.join(".squall/reviews")), "{file:?}");
+        assert!(!b.join(".squall/reviews").exists());
+
+        let text = |result: CallToolResult| serde_json::to_string(&result).unwrap();
+        let search = |server: SquallServer| async move {
+            let req = serde_json::from_value(serde_json::json!({"query": "zanzibar"})).unwrap();
+            text(server.reviews_search(Parameters(req)).await.unwrap())
+        };
+        let list = |server: SquallServer| async move {
+            let req = serde_json::from_value(serde_json::json!({})).unwrap();
+            text(server.reviews_list(Parameters(req)).await.unwrap())
+        };
+        let id = response.review_id;
+        assert!(search(one.clone()).await.contains(&id));
+        assert!(list(one.clone()).await.contains(&id));
+        assert!(!search(two.clone()).await.contains(&id));
+        assert!(!list(two.clone()).await.contains(&id));
+        let compare = serde_json::from_value(serde_json::json!({"id_a": id, "id_b": id})).unwrap();
+        assert!(two.reviews_compare(Parameters(compare)).await.is_err());
+
+        let missing = two
+            .memory_store()
+            .memorize_from_review(&id, 1, None)
+            .await
+            .unwrap_err();
+        assert!(missing.contains("failed to read review"), "{missing}");
+        let found = one.memory_store().memorize_from_review(&id, 1, None).await;
+        assert!(
+            found
+                .as_ref()
+                .err()
+                .is_none_or(|e| !e.contains("failed to read review")),
+            "{found:?}"
+        );
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
 }
@@ -0,0 +1,493 @@
+//! Prompt suites: named sets of pinned prompts in `.squall/suites/{name}.toml`,
+//! each with optional expected-property checks. `suite_create`, `suite_list`
+//! and `suite_run` manage and run them; `model_regress` replays a suite against
+//! a model and compares the answers with a stored baseline (see
+//! `crate::regress`). Every suite feature reads this one format.
+//!
+//! ```toml
+//! description = "Smoke prompts for code models"
+//!
+//! [[prompts]]
+//! id = "fizzbuzz"
+//! prompt = "Write FizzBuzz in Rust."
+//! system_prompt = "Reply with code only."  # optional
+//!
+//! [prompts.expect]                 # optional; every check must pass
+//! contains = ["fn main"]           # case-insensitive
+//! not_contains = ["unsafe"]
+//! min_words = 10
+//! max_words = 400
+//! json = false                     # answer must be (salvageable) JSON
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::TokenUsage;
+use crate::dispatch::registry::Registry;
+use crate::usage::UsageTag;
+
+/// Directory holding suites, relative to the server's working directory.
+pub const SUITES_DIR: &str = ".squall/suites";
+
+/// Most prompts in one suite; each is a model call per run.
+pub const MAX_SUITE_PROMPTS: usize = 50;
+
+/// Longest suite name or prompt id.
+const MAX_NAME_LEN: usize = 64;
+
+/// A named set of prompts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suite {
+    /// File stem; not stored in the file.
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub prompts: Vec<SuitePrompt>,
+}
+
+/// One pinned prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SuitePrompt {
+    /// Stable id, unique within the suite; baselines are keyed by it.
+    pub id: String,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Checks every answer must pass (optional).
+    #[serde(default, skip_serializing_if = "Expect::is_empty")]
+    pub expect: Expect,
+}
+
+/// Properties an answer must have. Empty = any answer passes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Expect {
+    /// Substrings the answer must contain (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contains: Vec<String>,
+    /// Substrings the answer must not contain (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_contains: Vec<String>,
+    /// Fewest whitespace-separated words.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_words: Option<usize>,
+    /// Most whitespace-separated words.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_words: Option<usize>,
+    /// The answer must be a JSON document, possibly fenced or needing repair
+    /// (see `crate::json_repair`).
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub json: bool,
+}
+
+impl Expect {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Checks `answer` fails, one message each. Empty = passed.
+    pub fn failures(&self, answer: &str) -> Vec<String> {
+        let lower = answer.to_lowercase();
+        let mut failed = Vec::new();
+        for s in &self.contains {
+            if !lower.contains(&s.to_lowercase()) {
+                failed.push(format!("missing \"{s}\""));
+            }
+        }
+        for s in &self.not_contains {
+            if lower.contains(&s.to_lowercase()) {
+                failed.push(format!("contains \"{s}\""));
+            }
+        }
+        let words = answer.split_whitespace().count();
+        if let Some(min) = self.min_words
+            && words < min
+        {
+            failed.push(format!("{words} words, expected at least {min}"));
+        }
+        if let Some(max) = self.max_words
+            && words > max
+        {
+            failed.push(format!("{words} words, expected at most {max}"));
+        }
+        if self.json && crate::json_repair::parse(answer).is_none() {
+            failed.push("not JSON".to_string());
+        }
+        failed
+    }
+}
+
+impl Suite {
+    /// Parse suite TOML read from the file for `name`.
+    pub fn parse(name: &str, toml_text: &str) -> Result<Self, String> {
+        let mut suite: Suite =
+            toml::from_str(toml_text).map_err(|e| format!("suite '{name}': {e}"))?;
+        suite.name = name.to_string();
+        suite.validate()?;
+        Ok(suite)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name("suite name", &self.name)?;
+        if self.prompts.is_empty() {
+            return Err(format!("suite '{}' has no prompts", self.name));
+        }
+        if self.prompts.len() > MAX_SUITE_PROMPTS {
+            return Err(format!(
+                "suite '{}' has {} prompts (max {MAX_SUITE_PROMPTS})",
+                self.name,
+                self.prompts.len()
+            ));
+        }
+        let mut ids = HashSet::new();
+        for p in &self.prompts {
+            validate_name("prompt id", &p.id)?;
+            if !ids.insert(p.id.as_str()) {
+                return Err(format!(
+                    "suite '{}': duplicate prompt id '{}'",
+                    self.name, p.id
+                ));
+            }
+            if p.prompt.trim().is_empty() {
+                return Err(format!("suite '{}': prompt '{}' is empty", self.name, p.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+/// Save `suite` to `dir`. Refuses to replace an existing suite unless
+/// `overwrite`. Returns the path written.
+pub async fn save(dir: &Path, suite: &Suite, overwrite: bool) -> Result<PathBuf, String> {
+    suite.validate()?;
+    let path = suite_path(dir, &suite.name);
+    if !overwrite && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Err(format!(
+            "suite '{}' already exists; pass overwrite: true to replace it",
+            suite.name
+        ));
+    }
+    let text = toml::to_string_pretty(suite).map_err(|e| e.to_string())?;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("{}: {e}", dir.display()))?;
+    tokio::fs::write(&path, text)
+        .await
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// One suite in a listing.
+#[derive(Debug, Serialize)]
+pub struct SuiteSummary {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub prompts: usize,
+    /// Prompts with expected-property checks.
+    pub checked: usize,
+    /// Why the file failed to load, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Every `*.toml` suite in `dir`, sorted by name. A missing directory is an
+/// empty listing.
+pub async fn list(dir: &Path) -> Vec<SuiteSummary> {
+    let mut names = Vec::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file_name = entry.file_name();
+            if let Some(name) = file_name.to_str().and_then(|n| n.strip_suffix(".toml")) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    let mut out = Vec::new();
+    for name in names {
+        out.push(match load(dir, &name).await {
+            Ok(suite) => SuiteSummary {
+                description: suite.description,
+                prompts: suite.prompts.len(),
+                checked: suite
+                    .prompts
+                    .iter()
+                    .filter(|p| !p.expect.is_empty())
+                    .count(),
+                error: None,
+                name,
+            },
+            Err(e) => SuiteSummary {
+                name,
+                description: None,
+                prompts: 0,
+                checked: 0,
+                error: Some(e),
+            },
+        });
+    }
+    out
+}
+
+/// Markdown for a `suite_list` response.
+pub fn list_to_markdown(suites: &[SuiteSummary]) -> String {
+    let mut out = "## Prompt suites\n\n".to_string();
+    if suites.is_empty() {
+        out.push_str(&format!(
+            "No suites in `{SUITES_DIR}/`. Create one with `suite_create`.\n"
+        ));
+        return out;
+    }
+    for s in suites {
+        match &s.error {
+            Some(e) => out.push_str(&format!("- `{}` \u{2014} invalid: {e}\n", s.name)),
+            None => {
+                out.push_str(&format!(
+                    "- `{}` \u{2014} {} prompt(s), {} with checks",
+                    s.name, s.prompts, s.checked
+                ));
+                if let Some(d) = &s.description {
+                    out.push_str(&format!(": {d}"));
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// One model's answer to one suite prompt, checked.
+#[derive(Debug, Serialize)]
+pub struct PromptOutcome {
+    pub id: String,
+    pub passed: bool,
+    /// Failed checks, or the dispatch error.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A suite run on one model.
+#[derive(Debug, Serialize)]
+pub struct ModelRun {
+    pub model: String,
+    pub passed: usize,
+    pub total: usize,
+    pub prompts: Vec<PromptOutcome>,
+}
+
+/// Result of `suite_run`.
+#[derive(Debug, Serialize)]
+pub struct SuiteRun {
+    pub suite: String,
+    pub models: Vec<ModelRun>,
+    #[serde(skip)]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Run `suite` on each of `models` (at temperature 0, like `model_regress`)
+/// and check every answer against its prompt's expectations.
+pub async fn run(
+    registry: &Registry,
+    suite: &Suite,
+    models: &[String],
+    deadline: Instant,
+    usage: Option<UsageTag>,
+) -> SuiteRun {
+    let replays = futures_util::future::join_all(
+        models
+            .iter()
+            .map(|model| crate::regress::replay(registry, suite, model, deadline, usage.clone())),
+    )
+    .await;
+    let mut usages = Vec::new();
+    let mut runs = Vec::new();
+    for (model, replayed) in models.iter().zip(replays) {
+        let prompts: Vec<PromptOutcome> = suite
+            .prompts
+            .iter()
+            .zip(replayed)
+            .map(|(p, r)| {
+                usages.extend(r.usage);
+                match r.result {
+                    Ok(answer) => {
+                        let failures = p.expect.failures(&answer.text);
+                        PromptOutcome {
+                            id: r.id,
+                            passed: failures.is_empty(),
+                            failures,
+                            error: None,
+                        }
+                    }
+                    Err(e) => PromptOutcome {
+                        id: r.id,
+                        passed: false,
+                        failures: Vec::new(),
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect();
+        runs.push(ModelRun {
+            model: model.clone(),
+            passed: prompts.iter().filter(|p| p.passed).count(),
+            total: prompts.len(),
+            prompts,
+        });
+    }
+    SuiteRun {
+        suite: suite.name.clone(),
+        models: runs,
+        usage: TokenUsage::sum(&usages),
+    }
+}
+
+impl SuiteRun {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Suite `{}`\n\n| Model | Passed |\n|---|---|\n",
+            self.suite
+        );
+        for m in &self.models {
+            out.push_str(&format!("| {} | {}/{} |\n", m.model, m.passed, m.total));
+        }
+        for m in &self.models {
+            let failed: Vec<&PromptOutcome> = m.prompts.iter().filter(|p| !p.passed).collect();
+            if failed.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n### {} failures\n", m.model));
+            for p in failed {
+                let why = match &p.error {
+                    Some(e) => format!("error: {e}"),
+                    None => p.failures.join("; "),
+                };
+                out.push_str(&format!("- `{}`: {why}\n", p.id));
+            }
+        }
+        out
+    }
+}
+
+/// Names and ids are used in file paths: letters, digits, `-`, `_`, `.` only,
+/// not starting with `.`.
+pub fn validate_name(what: &str, name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "{what} must be 1-{MAX_NAME_LEN} letters, digits, '-', '_' or '.', got '{name}'"
+        ))
+    }
+}
+
+/// Path of suite `name` under `dir`.
+pub fn suite_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.toml"))
+}
+
+/// Load suite `name` from `dir`.
+pub async fn load(dir: &Path, name: &str) -> Result<Suite, String> {
+    validate_name("suite name", name)?;
+    let path = suite_path(dir, name);
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("suite '{name}' ({}): {e}", path.display()))?;
+    Suite::parse(name, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rejects_duplicate_ids_and_bad_names() {
+        let suite = Suite::parse(
+            "smoke",
+            "[[prompts]]\nid = \"a\"\nprompt = \"Say hi.\"\n\n[[prompts]]\nid = \"b\"\nprompt = \"Say bye.\"\n",
+        )
+        .unwrap();
+        assert_eq!(suite.name, "smoke");
+        assert_eq!(suite.prompts.len(), 2);
+
+        let dup =
+            "[[prompts]]\nid = \"a\"\nprompt = \"x\"\n\n[[prompts]]\nid = \"a\"\nprompt = \"y\"\n";
+        assert!(
+            Suite::parse("smoke", dup)
+                .unwrap_err()
+                .contains("duplicate")
+        );
+        assert!(Suite::parse("smoke", "prompts = []\n").is_err());
+        assert!(validate_name("suite name", "../etc").is_err());
+        assert!(validate_name("suite name", "code-v2").is_ok());
+    }
+
+    #[test]
+    fn expect_reports_each_failed_check() {
+        let suite = Suite::parse(
+            "smoke",
+            "[[prompts]]\nid = \"a\"\nprompt = \"x\"\n\n[prompts.expect]\n\
+             contains = [\"FN MAIN\"]\nnot_contains = [\"unsafe\"]\nmax_words = 5\n",
+        )
+        .unwrap();
+        let expect = &suite.prompts[0].expect;
+        assert!(expect.failures("fn main() {}").is_empty());
+        let failures = expect.failures("unsafe fn helper() { one two three four }");
+        assert_eq!(failures.len(), 3, "{failures:?}");
+
+        let json = Expect {
+            json: true,
+            ..Default::default()
+        };
+        assert!(json.failures("```json\n{\"ok\": true}\n```").is_empty());
+        assert_eq!(json.failures("no document here"), ["not JSON"]);
+        assert!(Expect::default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn save_and_list_round_trip() {
+        let dir = std::env::temp_dir().join(format!("squall_suites_test_{}", std::process::id()));
+        let suite = Suite {
+            name: "smoke".into(),
+            description: Some("basics".into()),
+            prompts: vec![SuitePrompt {
+                id: "hi".into(),
+                prompt: "Say hi.".into(),
+                system_prompt: None,
+                expect: Expect {
+                    contains: vec!["hi".into()],
+                    ..Default::default()
+                },
+            }],
+        };
+        save(&dir, &suite, false).await.unwrap();
+        assert!(save(&dir, &suite, false).await.is_err());
+        tokio::fs::write(dir.join("broken.toml"), "prompts = 3")
+            .await
+            .unwrap();
+
+        assert_eq!(load(&dir, "smoke").await.unwrap(), suite);
+        let listed = list(&dir).await;
+        assert_eq!(listed.len(), 2);
+        assert!(listed[0].error.is_some());
+        assert_eq!((listed[1].prompts, listed[1].checked), (1, 1));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,274 @@
+//! Map-reduce summarization for content too large to summarize well in one pass.
+//!
+//! The input is split at line boundaries into chunks of at most `chunk_bytes`.
+//! Each chunk is summarized by a fast map model (concurrently, in order), then a
+//! stronger reduce model merges the partial summaries into one of roughly
+//! `target_words`. Content that fits in a single chunk skips the map step and
+//! goes straight to the reduce model. Chunks whose map call fails are reported
+//! to the reduce model as gaps rather than failing the whole summary.
+
+use std::time::Instant;
+
+use futures_util::StreamExt;
+
+use crate::dispatch::registry::Registry;
+use crate::dispatch::{ProviderRequest, ProviderResult, TokenUsage};
+use crate::error::SquallError;
+use crate::usage::UsageTag;
+
+/// Default chunk size: a comfortable single-pass input for fast models.
+pub const DEFAULT_CHUNK_BYTES: usize = 48 * 1024;
+
+/// Smallest chunk size accepted; smaller chunks lose too much local context.
+pub const MIN_CHUNK_BYTES: usize = 4 * 1024;
+
+/// Default length of the final summary.
+pub const DEFAULT_TARGET_WORDS: u32 = 300;
+
+/// Most chunks summarized per request. Larger inputs must use bigger chunks.
+pub const MAX_CHUNKS: usize = 64;
+
+/// Map calls in flight at once (the registry's backend semaphores apply too).
+const MAP_CONCURRENCY: usize = 4;
+
+/// Settings for one summarize run.
+pub struct SummarizeOptions {
+    pub map_model: String,
+    pub reduce_model: String,
+    pub target_words: u32,
+    pub focus: Option<String>,
+    pub chunk_bytes: usize,
+    pub deadline: Instant,
+    /// Tag for the usage ledger, applied to every map and reduce call.
+    pub usage: Option<UsageTag>,
+}
+
+/// Result of a summarize run.
+#[derive(Debug)]
+pub struct SummaryOutcome {
+    /// Final summary from the reduce model.
+    pub text: String,
+    /// Model and provider that produced the final summary.
+    pub model: String,
+    pub provider: String,
+    /// Number of chunks the input was split into.
+    pub chunks: usize,
+    /// Chunks whose map call failed and were left out.
+    pub failed_chunks: Vec<usize>,
+    /// Tokens summed over the map and reduce calls.
+    pub usage: Option<TokenUsage>,
+}
+
+/// Split `text` into chunks of at most `max_bytes`, breaking after a newline
+/// where possible and otherwise at a char boundary.
+pub fn chunk(text: &str, max_bytes: usize) -> Vec<&str> {
+    let max_bytes = max_bytes.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        // Prefer a line break, unless it would leave the chunk under half full.
+        if let Some(nl) = rest[..end].rfind('\n')
+            && nl + 1 >= max_bytes / 2
+        {
+            end = nl + 1;
+        }
+        if end == 0 {
+            // A single char wider than max_bytes.
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        chunks.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    if !rest.trim().is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+fn focus_line(focus: Option<&str>) -> String {
+    match focus.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(f) => format!("Focus on: {f}\n"),
+        None => String::new(),
+    }
+}
+
+/// Prompt for summarizing chunk `index` (0-based) of `total`.
+pub fn map_prompt(chunk: &str, index: usize, total: usize, focus: Option<&str>) -> String {
+    format!(
+        "You are summarizing part {} of {total} of a larger document. Other parts are \
+         summarized separately and merged later, so capture every distinct point, name, \
+         number, and decision in this part as terse bullet points. Do not add an \
+         introduction or conclusion.\n{}\n<content>\n{chunk}\n</content>",
+        index + 1,
+        focus_line(focus),
+    )
+}
+
+/// Prompt for merging partial summaries (or summarizing a single-chunk input).
+pub fn reduce_prompt(
+    parts: &[(usize, String)],
+    total: usize,
+    failed: &[usize],
+    target_words: u32,
+    focus: Option<&str>,
+) -> String {
+    let mut prompt = if total == 1 {
+        format!("Summarize the following content in about {target_words} words.\n")
+    } else {
+        format!(
+            "The following are summaries of {total} consecutive parts of one document. \
+             Merge them into a single coherent summary of about {target_words} words. \
+             Remove repetition across parts and keep the original order of topics.\n"
+        )
+    };
+    prompt.push_str(&focus_line(focus));
+    if !failed.is_empty() {
+        let list: Vec<String> = failed.iter().map(|i| (i + 1).to_string()).collect();
+        prompt.push_str(&format!(
+            "Parts {} could not be summarized; note that the summary has gaps there.\n",
+            list.join(", ")
+        ));
+    }
+    for (index, text) in parts {
+        if total == 1 {
+            prompt.push_str(&format!("\n<content>\n{text}\n</content>\n"));
+        } else {
+            prompt.push_str(&format!(
+                "\n<part index=\"{}\">\n{}\n</part>\n",
+                index + 1,
+                text.trim()
+            ));
+        }
+    }
+    prompt
+}
+
+fn request(prompt: String, model: &str, opts: &SummarizeOptions) -> ProviderRequest {
+    ProviderRequest {
+        prompt: prompt.into(),
+        model: model.to_string(),
+        deadline: opts.deadline,
+        working_directory: None,
+        system_prompt: None,
+        temperature: Some(0.2),
+        max_tokens: None,
+        reasoning_effort: None,
+        cancellation_token: None,
+        stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: opts.usage.clone(),
+    }
+}
+
+/// Summarize `content` with a map step over chunks and a reduce step.
+pub async fn run(
+    registry: &Registry,
+    content: &str,
+    opts: &SummarizeOptions,
+) -> Result<SummaryOutcome, SquallError> {
+    let chunks = chunk(content, opts.chunk_bytes);
+    let total = chunks.len();
+    if total == 0 {
+        return Err(SquallError::Other("nothing to summarize".to_string()));
+    }
+    let focus = opts.focus.as_deref();
+
+    let mut parts = Vec::with_capacity(total);
+    let mut failed = Vec::new();
+    let mut usages = Vec::new();
+    if total == 1 {
+        parts.push((0, chunks[0].to_string()));
+    } else {
+        let requests: Vec<ProviderRequest> = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, c)| request(map_prompt(c, i, total, focus), &opts.map_model, opts))
+            .collect();
+        let results: Vec<Result<ProviderResult, SquallError>> = futures_util::stream::iter(
+            requests
+                .into_iter()
+                .map(|req| async move { registry.query(&req).await }),
+        )
+        .buffered(MAP_CONCURRENCY)
+        .collect()
+        .await;
+        let mut first_err = None;
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(r) => {
+                    usages.extend(r.usage);
+                    parts.push((i, r.text));
+                }
+                Err(e) => {
+                    tracing::warn!("summarize: map of chunk {} failed: {e}", i + 1);
+                    failed.push(i);
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        if parts.is_empty()
+            && let Some(e) = first_err
+        {
+            return Err(e);
+        }
+    }
+
+    // Length is steered by the prompt, not max_tokens: a hard cap truncates
+    // mid-sentence (and reasoning models spend it on thinking).
+    let prompt = reduce_prompt(&parts, total, &failed, opts.target_words, focus);
+    let result = registry
+        .query(&request(prompt, &opts.reduce_model, opts))
+        .await?;
+    usages.extend(result.usage);
+    Ok(SummaryOutcome {
+        text: result.text,
+        model: result.model,
+        provider: result.provider,
+        chunks: total,
+        failed_chunks: failed,
+        usage: TokenUsage::sum(&usages),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_break_at_lines_and_cover_everything() {
+        let text = "alpha line\n".repeat(100);
+        let chunks = chunk(&text, 128);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 128 && c.ends_with('\n')));
+        assert_eq!(chunks.concat(), text);
+
+        // No newline to break at: falls back to a char boundary.
+        let wide = "é".repeat(100);
+        let chunks = chunk(&wide, 15);
+        assert!(chunks.iter().all(|c| c.len() <= 15));
+        assert_eq!(chunks.concat(), wide);
+
+        assert!(chunk("  \n", 128).is_empty());
+    }
+
+    #[test]
+    fn reduce_prompt_reports_gaps_and_target() {
+        let parts = vec![(0, "- first".to_string()), (2, "- third".to_string())];
+        let prompt = reduce_prompt(&parts, 3, &[1], 150, Some("security"));
+        assert!(prompt.contains("3 consecutive parts"));
+        assert!(prompt.contains("about 150 words"));
+        assert!(prompt.contains("Focus on: security"));
+        assert!(prompt.contains("Parts 2 could not be summarized"));
+        assert!(prompt.contains("<part index=\"3\">\n- third\n</part>"));
+
+        let single = reduce_prompt(&[(0, "body".to_string())], 1, &[], 50, None);
+        assert!(single.starts_with("Summarize the following content in about 50 words."));
+        assert!(single.contains("<content>\nbody\n</content>"));
+    }
+}
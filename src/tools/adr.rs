@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::context::ContextFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AdrRequest {
+    /// The design question to decide, with any constraints (e.g. "Should sessions move
+    /// from Postgres to Redis? Three replicas, p99 login under 200ms.").
+    pub question: String,
+    /// Record title. Defaults to the first line of the question.
+    pub title: Option<String>,
+    /// Absolute path to the project root. The record is written under it, and file_paths
+    /// are resolved against it.
+    pub working_directory: String,
+    /// Relative file paths to include as context (design docs, the code in question).
+    pub file_paths: Option<Vec<String>>,
+    /// File context format: "xml" (default, full content) or "hashline".
+    pub context_format: Option<ContextFormat>,
+    /// Models that propose options, from `listmodels`. Defaults to the high precision tier
+    /// (up to 3), or `[review] default_models` if none is configured.
+    pub models: Option<Vec<String>>,
+    /// Model that writes the record from the proposals (default: the first model that
+    /// answered; the next one is tried if its reply is not a usable record).
+    pub synthesizer: Option<String>,
+    /// Directory for the record, relative to working_directory (default: `[settings]
+    /// adr_dir`, else "docs/adr").
+    pub docs_dir: Option<String>,
+    /// Write the record to docs_dir (default true). Set false to preview it.
+    pub persist: Option<bool>,
+    /// Seconds for proposals and synthesis together (default 300, max 600).
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::brainstorm::{DEFAULT_IDEAS, MAX_IDEAS, MAX_SAMPLES_PER_MODEL};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BrainstormRequest {
+    /// What to brainstorm (problem statement, constraints, goal).
+    pub prompt: String,
+    /// Model names from `listmodels`. Defaults to `[review] default_models` from config.
+    pub models: Option<Vec<String>>,
+    /// Responses requested from each model (default 1, max 3). More samples give more
+    /// candidates to choose from.
+    pub samples_per_model: Option<usize>,
+    /// Number of distinct ideas to return (default 5, max 20).
+    pub ideas: Option<usize>,
+    /// Sampling temperature (default 1.0). High values give more varied ideas.
+    pub temperature: Option<f64>,
+    /// System prompt to set model persona/behavior.
+    pub system_prompt: Option<String>,
+    /// Seconds to wait for responses (default 180, max 600). Late models are dropped.
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+impl BrainstormRequest {
+    pub fn samples_per_model(&self) -> usize {
+        self.samples_per_model
+            .unwrap_or(1)
+            .clamp(1, MAX_SAMPLES_PER_MODEL)
+    }
+
+    pub fn ideas(&self) -> usize {
+        self.ideas.unwrap_or(DEFAULT_IDEAS).clamp(1, MAX_IDEAS)
+    }
+}
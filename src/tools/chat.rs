@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-use super::enums::ReasoningEffort;
+use super::enums::{Priority, ReasoningEffort};
 use crate::context::ContextFormat;
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -14,6 +14,9 @@ pub struct ChatRequest {
     pub file_paths: Option<Vec<String>>,
     /// Absolute path to the project root for resolving file_paths. Required when file_paths is set.
     pub working_directory: Option<String>,
+    /// http(s) URLs (docs pages, API specs) fetched server-side and prepended as context.
+    /// Text, HTML, JSON, XML and YAML only; each must resolve to a public address. Max 10.
+    pub urls: Option<Vec<String>>,
     /// System prompt to set model persona/behavior (e.g. "You are an expert security auditor").
     pub system_prompt: Option<String>,
     /// Sampling temperature: 0.0 = deterministic (best for analysis/code), 1.0 = creative/diverse.
@@ -26,6 +29,13 @@ pub struct ChatRequest {
     /// File context format: "xml" (default, full content) or "hashline" (line_num:hash|content,
     /// compact for large files). Hashline lets models reference lines by number+hash.
     pub context_format: Option<ContextFormat>,
+    /// Scheduling priority when provider concurrency is saturated: "low", "normal"
+    /// (default), or "high".
+    pub priority: Option<Priority>,
+    /// JSON object merged into the request body for provider-specific parameters
+    /// (e.g. {"search_parameters": {"mode": "auto"}} for Grok). HTTP models only;
+    /// overrides the model's configured extra_body key by key.
+    pub extra_body: Option<serde_json::Value>,
 }
 
 pub const DEFAULT_MODEL: &str = "grok-4-1-fast-reasoning";
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::{Depth, OutputFormat};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ClassifyRequest {
+    /// Short description of the task (e.g. "review the new JWT refresh middleware, ~300 lines").
+    /// Mention changed files, size, and risk areas for a better route.
+    pub task: String,
+    /// Fast model used to classify the task (default "grok"). Falls back to a keyword
+    /// heuristic if it fails.
+    pub model: Option<String>,
+    /// Force a depth ("quick", "standard", "deep") instead of the classified one,
+    /// e.g. when the user asked for a "deep review". Lenses are still classified.
+    pub depth: Option<Depth>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+pub const DEFAULT_MODEL: &str = "grok";
+
+impl ClassifyRequest {
+    pub fn model_or_default(&self) -> &str {
+        self.model
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_MODEL)
+    }
+}
@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::decompose::{DEFAULT_SUBQUESTIONS, MAX_SUBQUESTIONS};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DecomposeRequest {
+    /// The complex question to split up and answer.
+    pub prompt: String,
+    /// Named model groups the planner assigns sub-questions to, e.g.
+    /// {"reasoning": ["deepseek-r1"], "fast": ["grok", "gemini"]}. Every model of a group
+    /// answers its sub-questions. Defaults to one group of `[review] default_models`.
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+    /// Model that splits the question (default: the fastest HTTP model).
+    pub planner: Option<String>,
+    /// Model that merges the answers (default: the planner).
+    pub synthesizer: Option<String>,
+    /// Most sub-questions to plan (default 4, max 8).
+    pub max_sub_questions: Option<usize>,
+    /// System prompt for the models answering sub-questions.
+    pub system_prompt: Option<String>,
+    /// Seconds to wait for sub-question answers (default 180, max 600). Late models are
+    /// dropped.
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+impl DecomposeRequest {
+    pub fn max_sub_questions(&self) -> usize {
+        self.max_sub_questions
+            .unwrap_or(DEFAULT_SUBQUESTIONS)
+            .clamp(1, MAX_SUBQUESTIONS)
+    }
+}
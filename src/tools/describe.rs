@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DescribeChangeRequest {
+    /// Absolute path to the git repository.
+    pub working_directory: String,
+    /// Base ref (e.g. "main", "origin/main"). When set, describes the branch's changes since
+    /// it forked from base (`git diff base...HEAD`); otherwise describes the staged changes.
+    pub base: Option<String>,
+    /// Model names from `listmodels` that draft descriptions (default: the 2 fastest HTTP
+    /// models). The fastest one that answers merges the drafts.
+    pub models: Option<Vec<String>>,
+    /// Use a Conventional Commits subject line, e.g. "feat(cli): add --dry-run" (default false).
+    pub conventional: Option<bool>,
+    /// What the change is for, when the diff alone doesn't say (e.g. "fixes #42, users hit
+    /// timeouts on large repos").
+    pub hint: Option<String>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+    /// Monorepo subtrees to describe (e.g. ["crates/foo", "services/bar"]), relative to
+    /// working_directory. Changes elsewhere on the branch are left out.
+    pub packages: Option<Vec<String>>,
+}
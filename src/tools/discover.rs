@@ -0,0 +1,17 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiscoverModelsRequest {
+    /// Provider names from config (e.g. ["xai", "openrouter"]). Defaults to every
+    /// HTTP provider with an API key set.
+    pub providers: Option<Vec<String>>,
+    /// Keep only model IDs containing this text (case-insensitive), e.g. "grok".
+    pub filter: Option<String>,
+    /// Include a ready-to-paste `[models.*]` config block for each new model (default false).
+    pub snippets: Option<bool>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
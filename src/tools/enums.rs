@@ -24,6 +24,113 @@ impl ReasoningEffort {
     }
 }
 
+/// Dispatch priority when backend concurrency is saturated: waiting dispatches of a
+/// higher priority go first (see `crate::dispatch::priority`).
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Background work (deep reviews default to this).
+    Low,
+    #[default]
+    Normal,
+    /// Interactive work that should not queue behind background reviews.
+    High,
+}
+
+impl Priority {
+    pub const LEVELS: usize = 3;
+
+    pub fn level(self) -> usize {
+        self as usize
+    }
+}
+
+/// What a review does with models still running at the straggler cutoff.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StragglerPolicy {
+    /// Cancel them, keep what they streamed within the grace window, then abort.
+    #[default]
+    Kill,
+    /// Let them finish after the response is returned and append their results
+    /// to the persisted review file.
+    Background,
+}
+
+/// How a review applies the memory-based hard gate to its models.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GatingMode {
+    /// Drop models whose recorded success rate is below the threshold.
+    #[default]
+    On,
+    /// Dispatch every requested model regardless of its record.
+    Off,
+    /// Dispatch every requested model, but warn about those the gate would drop.
+    ReportOnly,
+}
+
+/// Prompt-size bucket for memory analytics. A model that does well on small prompts
+/// can time out on large ones, so stats are also kept per bucket.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptSize {
+    /// Under 8KB.
+    S,
+    /// 8KB to 32KB.
+    M,
+    /// 32KB to 128KB.
+    L,
+    /// 128KB and over.
+    Xl,
+}
+
+impl PromptSize {
+    pub const ALL: [PromptSize; 4] = [Self::S, Self::M, Self::L, Self::Xl];
+
+    /// Upper bounds (exclusive, bytes) of S, M, and L; XL is everything above.
+    pub const BOUNDS: [usize; 3] = [8 * 1024, 32 * 1024, 128 * 1024];
+
+    pub fn from_len(prompt_len: usize) -> Self {
+        match Self::BOUNDS.iter().position(|&bound| prompt_len < bound) {
+            Some(0) => Self::S,
+            Some(1) => Self::M,
+            Some(_) => Self::L,
+            None => Self::Xl,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::S => "s",
+            Self::M => "m",
+            Self::L => "l",
+            Self::Xl => "xl",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::S => "S",
+            Self::M => "M",
+            Self::L => "L",
+            Self::Xl => "XL",
+        }
+    }
+
+    /// Human-readable byte range, e.g. `32-128KB`.
+    pub fn range(&self) -> &'static str {
+        match self {
+            Self::S => "<8KB",
+            Self::M => "8-32KB",
+            Self::L => "32-128KB",
+            Self::Xl => ">=128KB",
+        }
+    }
+}
+
 /// Category for the memorize tool (save a learning).
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -37,6 +144,13 @@ pub enum MemorizeCategory {
     /// Model recommendation based on observed performance.
     #[serde(alias = "recommendation", alias = "recommendations")]
     Recommend,
+    /// Read findings models contradicted each other on, with how feedback
+    /// resolved them. Also accepts "disagreement".
+    #[serde(alias = "disagreement")]
+    Disagreements,
+    /// Read the files that accumulated the most confirmed findings. Also accepts "hotspot".
+    #[serde(alias = "hotspot")]
+    Hotspots,
 }
 
 impl MemorizeCategory {
@@ -45,6 +159,8 @@ impl MemorizeCategory {
             Self::Pattern => "pattern",
             Self::Tactic => "tactic",
             Self::Recommend => "recommend",
+            Self::Disagreements => "disagreements",
+            Self::Hotspots => "hotspots",
         }
     }
 }
@@ -63,6 +179,13 @@ pub enum MemoryCategory {
     /// Read model recommendations from memory. Also accepts "recommendation"/"recommendations".
     #[serde(alias = "recommendation", alias = "recommendations")]
     Recommend,
+    /// Read findings models contradicted each other on, with how feedback
+    /// resolved them. Also accepts "disagreement".
+    #[serde(alias = "disagreement")]
+    Disagreements,
+    /// Read the files that accumulated the most confirmed findings. Also accepts "hotspot".
+    #[serde(alias = "hotspot")]
+    Hotspots,
 }
 
 impl MemoryCategory {
@@ -72,6 +195,8 @@ impl MemoryCategory {
             Self::Patterns => "patterns",
             Self::Tactics => "tactics",
             Self::Recommend => "recommend",
+            Self::Disagreements => "disagreements",
+            Self::Hotspots => "hotspots",
         }
     }
 }
@@ -86,3 +211,92 @@ pub enum ResponseFormat {
     /// Summary + results_file path only (no per-model text).
     Concise,
 }
+
+/// Output encoding for tools that can return structured data.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+/// Output structure a review prompt asks models for, checked after the fact.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpectedFormat {
+    /// A JSON object or array, ideally with nothing around it.
+    Json,
+    /// A markdown list of findings.
+    Bullets,
+    /// Markdown sections under headings.
+    Headings,
+}
+
+impl ExpectedFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Bullets => "bullets",
+            Self::Headings => "headings",
+        }
+    }
+}
+
+/// Review intensity recommended by `classify`.
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Depth {
+    /// Small, low-risk change: one fast model.
+    Quick,
+    /// Normal change: core ensemble plus models matched to the lenses.
+    Standard,
+    /// Security-sensitive or critical: standard ensemble in deep mode.
+    Deep,
+}
+
+impl Depth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quick => "quick",
+            Self::Standard => "standard",
+            Self::Deep => "deep",
+        }
+    }
+}
+
+/// Review focus, mapped to a falsification-framed system prompt.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Lens {
+    Security,
+    Correctness,
+    Concurrency,
+    Defects,
+    Architecture,
+    Performance,
+}
+
+impl Lens {
+    pub const ALL: [Lens; 6] = [
+        Self::Security,
+        Self::Correctness,
+        Self::Concurrency,
+        Self::Defects,
+        Self::Architecture,
+        Self::Performance,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Security => "security",
+            Self::Correctness => "correctness",
+            Self::Concurrency => "concurrency",
+            Self::Defects => "defects",
+            Self::Architecture => "architecture",
+            Self::Performance => "performance",
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::context::ContextFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GenTestsRequest {
+    /// Extra guidance for the tests (e.g. "focus on the retry logic", "no network access").
+    pub prompt: Option<String>,
+    /// Model names from `listmodels` (default ["grok"]). With several models, the file-write
+    /// plan picks the candidate with the most test cases per file.
+    pub models: Option<Vec<String>>,
+    /// Relative paths of the code to test (read and inlined server-side). Requires working_directory.
+    pub file_paths: Option<Vec<String>>,
+    /// Absolute path to the project root. Used for file_paths and test framework detection.
+    pub working_directory: Option<String>,
+    /// Unified diff (e.g. `git diff` output): tests focus on the changed behavior.
+    pub diff: Option<String>,
+    /// File context format: "xml" (default, full content) or "hashline".
+    pub context_format: Option<ContextFormat>,
+    /// Sampling temperature (default 0.2).
+    pub temperature: Option<f64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+pub const DEFAULT_MODEL: &str = "grok";
+pub const DEFAULT_TEMPERATURE: f64 = 0.2;
+
+impl GenTestsRequest {
+    pub fn models_or_default(&self) -> Vec<String> {
+        self.models
+            .clone()
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| vec![DEFAULT_MODEL.to_string()])
+    }
+}
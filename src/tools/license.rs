@@ -0,0 +1,27 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LicenseScanRequest {
+    /// Absolute path to the git repository. Its license file and dependency manifests
+    /// (Cargo.toml, package.json, pyproject.toml, go.mod, ...) are read from the root.
+    pub working_directory: String,
+    /// Base ref (e.g. "origin/main"). When set, new files are those added since the branch
+    /// forked from base (`git diff base...HEAD`); otherwise the staged and untracked ones.
+    pub base: Option<String>,
+    /// New files to check instead of asking git, relative to working_directory.
+    pub file_paths: Option<Vec<String>>,
+    /// Model names from `listmodels` (default: `[review] default_models`).
+    pub models: Option<Vec<String>>,
+    /// Header every new file must carry in its first 20 lines (e.g.
+    /// "SPDX-License-Identifier: Apache-2.0"). Checked directly as well as by the models.
+    pub header: Option<String>,
+    /// Extra guidance (e.g. "we ship binaries to customers; LGPL is fine, AGPL is not").
+    pub prompt: Option<String>,
+    /// Cutoff for the models in seconds (default 180, max 600).
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
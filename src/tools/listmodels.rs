@@ -1,6 +1,8 @@
 use serde::Serialize;
 
+use crate::dispatch::health::{BackendHealth, HealthState};
 use crate::dispatch::registry::ModelEntry;
+use crate::review::GateThresholds;
 
 #[derive(Serialize)]
 pub struct ModelInfo {
@@ -12,8 +14,26 @@ pub struct ModelInfo {
     pub weaknesses: Vec<String>,
     pub speed_tier: String,
     pub precision_tier: String,
+    /// Former names that still resolve to this model.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Last day the provider serves this model (YYYY-MM-DD).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated_after: Option<String>,
+    /// Days until `deprecated_after` as of the listing; negative once past.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_until_deprecation: Option<i64>,
+    /// USD per million input tokens, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_mtok_in: Option<f64>,
+    /// USD per million output tokens, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_mtok_out: Option<f64>,
 }
 
+/// Models this close to their `deprecated_after` date (or past it) get a warning.
+pub const DEPRECATION_WARNING_DAYS: i64 = 30;
+
 impl From<(&String, &ModelEntry)> for ModelInfo {
     fn from((key, entry): (&String, &ModelEntry)) -> Self {
         Self {
@@ -25,6 +45,11 @@ impl From<(&String, &ModelEntry)> for ModelInfo {
             weaknesses: entry.weaknesses.clone(),
             speed_tier: entry.speed_tier.clone(),
             precision_tier: entry.precision_tier.clone(),
+            aliases: entry.aliases.clone(),
+            deprecated_after: entry.deprecated_after.clone(),
+            days_until_deprecation: entry.days_until_deprecation(&crate::memory::iso_date()),
+            price_per_mtok_in: entry.price_per_mtok_in,
+            price_per_mtok_out: entry.price_per_mtok_out,
         }
     }
 }
@@ -32,6 +57,10 @@ impl From<(&String, &ModelEntry)> for ModelInfo {
 #[derive(Serialize)]
 pub struct ListModelsResponse {
     pub models: Vec<ModelInfo>,
+    /// Effective hard-gate thresholds (`[review.gate]`).
+    pub gate: GateThresholds,
+    /// Health of each provider's backend, from recent calls and probes.
+    pub backends: Vec<BackendHealth>,
 }
 
 /// Escape pipe, newline, and carriage-return characters for markdown table cells.
@@ -43,20 +72,76 @@ impl ListModelsResponse {
     /// Render the model list as a markdown table.
     pub fn to_markdown(&self) -> String {
         let mut md = String::from(
-            "| Model | Provider | Backend | Speed | Precision | Description |\n\
-             |-------|----------|---------|-------|-----------|-------------|\n",
+            "| Model | Provider | Backend | Speed | Precision | $/Mtok in/out | Description |\n\
+             |-------|----------|---------|-------|-----------|---------------|-------------|\n",
         );
         for m in &self.models {
+            let description = if m.aliases.is_empty() {
+                m.description.clone()
+            } else {
+                format!("{} (formerly: {})", m.description, m.aliases.join(", "))
+            };
+            let price = match (m.price_per_mtok_in, m.price_per_mtok_out) {
+                (Some(input), Some(output)) => format!("{input:.2} / {output:.2}"),
+                _ => "-".to_string(),
+            };
             md.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {} |\n",
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
                 escape_cell(&m.name),
                 escape_cell(&m.provider),
                 escape_cell(&m.backend),
                 escape_cell(&m.speed_tier),
                 escape_cell(&m.precision_tier),
-                escape_cell(&m.description),
+                price,
+                escape_cell(&description),
             ));
         }
+        md.push_str(&format!(
+            "\n**Hard gate:** reviews drop models with {} in memory.\n",
+            self.gate.describe()
+        ));
+        let unhealthy: Vec<&BackendHealth> = self
+            .backends
+            .iter()
+            .filter(|b| b.state != HealthState::Healthy)
+            .collect();
+        if !unhealthy.is_empty() {
+            md.push_str("\n**Backend health:**\n");
+            for backend in unhealthy {
+                md.push_str(&format!("- {}\n", backend.describe()));
+            }
+        }
+        let warnings = self.deprecation_warnings();
+        if !warnings.is_empty() {
+            md.push_str("\n**Deprecation warnings:**\n");
+            for warning in &warnings {
+                md.push_str(&format!("- {warning}\n"));
+            }
+        }
         md
     }
+
+    /// One line per model that is past, or within `DEPRECATION_WARNING_DAYS` of,
+    /// its `deprecated_after` date.
+    pub fn deprecation_warnings(&self) -> Vec<String> {
+        self.models
+            .iter()
+            .filter_map(|m| {
+                let days = m.days_until_deprecation?;
+                let date = m.deprecated_after.as_deref().unwrap_or_default();
+                let when = match days {
+                    ..0 => format!("was deprecated after {date} and may already fail"),
+                    0 => format!("is deprecated after today ({date})"),
+                    1..=DEPRECATION_WARNING_DAYS => {
+                        format!("is deprecated after {date} ({days} days left)")
+                    }
+                    _ => return None,
+                };
+                Some(format!(
+                    "{} {when}; move reviews and `review_config` defaults to a replacement.",
+                    m.name
+                ))
+            })
+            .collect()
+    }
 }
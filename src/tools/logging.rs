@@ -0,0 +1,9 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetLogLevelRequest {
+    /// New filter in RUST_LOG syntax: a level ("debug", "info", "warn", "error", "trace")
+    /// or directives ("info,squall::dispatch=debug"). Omit to report the current filter.
+    pub level: Option<String>,
+}
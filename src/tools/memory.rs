@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-use super::enums::{MemorizeCategory, MemoryCategory};
+use super::enums::{MemorizeCategory, MemoryCategory, PromptSize};
+use crate::memory::MemoryQuery;
 
 /// Request to save a learning to Squall's memory.
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -26,6 +27,27 @@ pub struct MemorizeRequest {
     pub metadata: Option<HashMap<String, String>>,
 }
 
+/// Request to bulk-memorize consensus findings from a persisted review.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MemorizeReviewRequest {
+    /// Review results file stem or path (e.g. "1773157800594_76050_0" or
+    /// ".squall/reviews/1773157800594_76050_0.json").
+    pub review_id: String,
+    /// Minimum number of models that must report a finding for it to be saved (default 2).
+    pub min_consensus: Option<usize>,
+    /// Scope for the saved patterns. Auto-detected from git context if working_directory
+    /// is set and scope is not provided.
+    pub scope: Option<String>,
+    /// Working directory for auto-detecting git context (branch/commit).
+    pub working_directory: Option<String>,
+}
+
+impl MemorizeReviewRequest {
+    pub fn min_consensus(&self) -> usize {
+        self.min_consensus.unwrap_or(2)
+    }
+}
+
 /// Request to read Squall's memory.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct MemoryRequest {
@@ -38,12 +60,37 @@ pub struct MemoryRequest {
     /// Filter patterns by scope (exact match). E.g. "branch:feature/x", "codebase".
     /// None returns all entries.
     pub scope: Option<String>,
+    /// Filter patterns by scope prefix. E.g. "branch:" for every branch-scoped entry.
+    pub scope_prefix: Option<String>,
+    /// Filter patterns to entries carrying all of these tags (case-insensitive).
+    pub tags: Option<Vec<String>>,
+    /// Filter patterns to entries last seen on or after this date (YYYY-MM-DD).
+    pub since: Option<String>,
+    /// Filter patterns to entries with at least this many occurrences.
+    pub min_evidence: Option<usize>,
+    /// Recommendations: only count reviews whose prompt was this size — "s" (<8KB),
+    /// "m" (8-32KB), "l" (32-128KB), or "xl" (>=128KB). Pass the bucket of the review
+    /// you are about to run; a model that is reliable on small prompts may not be on large.
+    pub prompt_size: Option<PromptSize>,
 }
 
 impl MemoryRequest {
     pub fn max_chars(&self) -> usize {
         self.max_chars.unwrap_or(4000)
     }
+
+    /// Structured query built from the filter parameters.
+    pub fn query(&self) -> MemoryQuery {
+        MemoryQuery {
+            model: self.model.clone(),
+            scope: self.scope.clone(),
+            scope_prefix: self.scope_prefix.clone(),
+            tags: self.tags.clone().unwrap_or_default(),
+            since: self.since.clone(),
+            min_evidence: self.min_evidence,
+            prompt_size: self.prompt_size,
+        }
+    }
 }
 
 /// Request to flush branch-scoped memory after PR merge.
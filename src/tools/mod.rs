@@ -1,6 +1,22 @@
+pub mod adr;
+pub mod brainstorm;
 pub mod chat;
+pub mod classify;
 pub mod clink;
+pub mod decompose;
+pub mod describe;
+pub mod discover;
 pub mod enums;
+pub mod gentests;
+pub mod license;
 pub mod listmodels;
+pub mod logging;
 pub mod memory;
+pub mod plan;
+pub mod refine;
+pub mod regress;
 pub mod review;
+pub mod reviews;
+pub mod suites;
+pub mod summarize;
+pub mod usage;
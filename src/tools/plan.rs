@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::context::ContextFormat;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PlanRequest {
+    /// The task to plan (what to build or change, constraints, definition of done).
+    pub task: String,
+    /// Model names from `listmodels` that each propose a plan. Defaults to
+    /// `[review] default_models` from config.
+    pub models: Option<Vec<String>>,
+    /// Model that merges the plans (default: the first model that answered; the next one
+    /// is tried if its reply has no usable steps).
+    pub synthesizer: Option<String>,
+    /// Relative file paths to include as context for every model. Requires working_directory.
+    pub file_paths: Option<Vec<String>>,
+    /// Absolute path to the project root for resolving file_paths. Required when file_paths is set.
+    pub working_directory: Option<String>,
+    /// File context format: "xml" (default, full content) or "hashline".
+    pub context_format: Option<ContextFormat>,
+    /// System prompt for the models proposing plans.
+    pub system_prompt: Option<String>,
+    /// Seconds for proposals and synthesis together (default 300, max 600).
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json". JSON returns the steps as structured data.
+    pub format: Option<OutputFormat>,
+}
@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::context::ContextFormat;
+use crate::refine::{DEFAULT_ROUNDS, MAX_ROUNDS};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RefineRequest {
+    /// The task for the author model. File context from file_paths is prepended automatically.
+    pub prompt: String,
+    /// Model that writes and revises the draft (default "grok"). Use exact names from `listmodels`.
+    pub author_model: Option<String>,
+    /// Model that critiques each draft (default "deepseek-v3.1"). Pick one other than the author.
+    pub critic_model: Option<String>,
+    /// Criteria the critic checks the draft against. Defaults to correctness, completeness,
+    /// clarity, and specificity.
+    pub rubric: Option<String>,
+    /// Critique-and-revise rounds (default 1, max 3). Stops early when the critic approves.
+    pub rounds: Option<usize>,
+    /// System prompt for the author (e.g. "You are a senior technical writer").
+    pub author_system_prompt: Option<String>,
+    /// System prompt for the critic (e.g. "You are a demanding staff engineer").
+    pub critic_system_prompt: Option<String>,
+    /// Sampling temperature for both roles: 0.0 = deterministic, 1.0 = creative/diverse.
+    pub temperature: Option<f64>,
+    /// Relative file paths to include as context for both roles. Requires working_directory.
+    pub file_paths: Option<Vec<String>>,
+    /// Absolute path to the project root for resolving file_paths. Required when file_paths is set.
+    pub working_directory: Option<String>,
+    /// File context format: "xml" (default, full content) or "hashline".
+    pub context_format: Option<ContextFormat>,
+}
+
+pub const DEFAULT_AUTHOR_MODEL: &str = "grok";
+pub const DEFAULT_CRITIC_MODEL: &str = "deepseek-v3.1";
+
+impl RefineRequest {
+    pub fn author_model_or_default(&self) -> &str {
+        self.author_model
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_AUTHOR_MODEL)
+    }
+
+    pub fn critic_model_or_default(&self) -> &str {
+        self.critic_model
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_CRITIC_MODEL)
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds.unwrap_or(DEFAULT_ROUNDS).clamp(1, MAX_ROUNDS)
+    }
+}
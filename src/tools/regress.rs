@@ -0,0 +1,32 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::regress::DEFAULT_MIN_SIMILARITY;
+
+/// Request to replay a prompt suite against a model and diff it with its baseline.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ModelRegressRequest {
+    /// Suite name: `.squall/suites/{suite}.toml`.
+    pub suite: String,
+    /// Model name from `listmodels`.
+    pub model: String,
+    /// Save this run as the model's new baseline after comparing (default false).
+    /// The first run of a suite on a model always records the baseline.
+    pub update_baseline: Option<bool>,
+    /// Word similarity to the baseline answer below which a prompt counts as
+    /// changed (0-1, default 0.5).
+    pub min_similarity: Option<f64>,
+    /// Seconds to wait for the model (default 180, max 600).
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+impl ModelRegressRequest {
+    pub fn min_similarity(&self) -> f64 {
+        self.min_similarity
+            .filter(|s| s.is_finite())
+            .map_or(DEFAULT_MIN_SIMILARITY, |s| s.clamp(0.0, 1.0))
+    }
+}
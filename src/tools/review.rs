@@ -1,10 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::enums::{ReasoningEffort, ResponseFormat};
-use crate::context::ContextFormat;
+use super::enums::{
+    ExpectedFormat, GatingMode, Priority, ReasoningEffort, ResponseFormat, StragglerPolicy,
+};
+use crate::calibration::ModelCalibration;
+use crate::consistency::SampleVote;
+use crate::context::{ContextFormat, GitContext};
+use crate::dependencies::SupplyChainReview;
+use crate::dispatch::{DispatchTiming, ProviderMetadata, TokenUsage};
+use crate::incremental::IncrementalReview;
+use crate::policy::PolicyCheck;
+use crate::questions::QuestionCoverage;
+use crate::redteam::RedTeamReview;
+use crate::rubric::RubricScore;
+use crate::triage::TriageOutcome;
+use crate::workspace::RepoContext;
 
 /// Request to dispatch a prompt to multiple models with straggler cutoff.
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -27,10 +40,16 @@ pub struct ReviewRequest {
     pub reasoning_effort: Option<ReasoningEffort>,
     /// Relative file paths to include as context (read and inlined server-side). Requires working_directory.
     pub file_paths: Option<Vec<String>>,
-    /// Absolute path to the project root for resolving file_paths.
-    pub working_directory: Option<String>,
+    /// Absolute path to the project root for resolving file_paths. A list of
+    /// repository roots (or a directory with `.squall/workspace.toml`) reviews
+    /// several repos at once; file_paths then start with the repo's directory name.
+    pub working_directory: Option<WorkingDirectory>,
     /// Unified diff text (e.g. `git diff` output) to include as review context. Shares budget with file_paths.
     pub diff: Option<String>,
+    /// http(s) URLs (docs pages, API specs) fetched server-side and included as context.
+    /// Text, HTML, JSON, XML and YAML only; each is capped at 512KB and must resolve to a
+    /// public address. Max 10.
+    pub urls: Option<Vec<String>>,
     /// Per-model system prompt overrides for different review lenses. Key = exact model name from
     /// `listmodels`, value = system prompt. Models not in this map use the shared system_prompt.
     /// Example lenses: security auditor, architecture reviewer, correctness checker.
@@ -43,6 +62,13 @@ pub struct ReviewRequest {
     /// Each model's task deadline is min(per_model_timeout, global cutoff).
     /// Values clamped to MAX_TIMEOUT_SECS (600s).
     pub per_model_timeout_secs: Option<HashMap<String, u64>>,
+    /// Per-model temperature overrides. Key = model name, value = temperature (0.0-2.0).
+    /// Models not in this map use the shared temperature. Lets one fan-out mix reasoning
+    /// models (which want 1.0) with models that want a low temperature (e.g. 0.2).
+    pub per_model_temperature: Option<HashMap<String, f64>>,
+    /// Per-model max_tokens overrides. Key = model name, value = max tokens.
+    /// Models not in this map use the shared max_tokens (or the deep-mode default).
+    pub per_model_max_tokens: Option<HashMap<String, u64>>,
     /// Deep review mode: sets timeout=600s, reasoning_effort="high", max_tokens=16384.
     /// Use for security audits, complex architecture reviews, or high-stakes changes.
     /// Individual fields (timeout_secs, reasoning_effort, max_tokens) override deep defaults.
@@ -59,6 +85,152 @@ pub struct ReviewRequest {
         description = "Pre-review investigation notes for traceability. Persisted alongside results but not sent to models. Max 32KB."
     )]
     pub investigation_context: Option<String>,
+    /// Re-review only files changed since the last review of this branch (requires
+    /// working_directory in a git repo). Findings on unchanged files are carried forward.
+    pub incremental: Option<bool>,
+    /// Output structure the prompt asks for: "json", "bullets", or "headings". Each
+    /// response is scored for compliance (0-1), reported per model, and logged to memory.
+    pub expected_format: Option<ExpectedFormat>,
+    /// Headings (bullets/headings) or top-level JSON keys (json) each response must
+    /// contain. Scored only when expected_format is set.
+    pub expected_sections: Option<Vec<String>>,
+    /// Scheduling priority when provider concurrency is saturated: "low", "normal", or
+    /// "high". Waiting high-priority dispatches go before queued lower-priority ones.
+    /// Defaults to "low" in deep mode (background work) and "normal" otherwise.
+    pub priority: Option<Priority>,
+    /// JSON object merged into every HTTP model's request body for provider-specific
+    /// parameters. Overrides each model's configured extra_body key by key.
+    pub extra_body: Option<serde_json::Value>,
+    /// Seconds a model gets after the cutoff to flush what it has streamed before it
+    /// is aborted (default: `[review] straggler_grace_secs`, 3). Clamped to 60.
+    pub straggler_grace_secs: Option<u64>,
+    /// Per-model grace overrides in seconds. Key = model name, value = grace.
+    pub per_model_straggler_grace_secs: Option<HashMap<String, u64>>,
+    /// "kill" (default: `[review] straggler_policy`) cancels models still running at
+    /// the cutoff. "background" returns without them and appends each one's result
+    /// to `results_file` when it finishes.
+    pub straggler_policy: Option<StragglerPolicy>,
+    /// Labels persisted with the review (ticket ID, PR number, "pre-merge") to tie
+    /// it back to the work item it validated. Filter on them with `reviews_list`
+    /// and `reviews_search`. Up to 20, 64 chars each.
+    pub tags: Option<Vec<String>>,
+    /// Annotate the diff's hunks with `git blame` ownership: who last changed the
+    /// touched lines and how long ago, so models can tell fresh code from code
+    /// that has been stable for years. Requires working_directory and diff.
+    pub blame: Option<bool>,
+    /// Merge the review checklist for the predominant language of file_paths and
+    /// the diff (Rust, Python, TypeScript/JavaScript, Go) into every model's system
+    /// prompt. Default: true.
+    pub language_profile: Option<bool>,
+    /// Monorepo subtrees to review (e.g. ["crates/foo", "services/bar"]), relative to
+    /// working_directory. file_paths and diff sections outside them are dropped and
+    /// listed in files_skipped, so unrelated packages touched on the same branch
+    /// aren't reviewed. Max 32.
+    pub packages: Option<Vec<String>>,
+    /// Give each model a different reviewer persona (security researcher,
+    /// correctness auditor, performance engineer, API ergonomics, reliability,
+    /// maintainability) so the fan-out covers more angles. The persona leads the
+    /// shared system_prompt; models with a per_model_system_prompts entry keep it.
+    /// Assignment rotates across reviews. Default: false.
+    pub personas: Option<bool>,
+    /// Self-consistency: dispatch every model this many times (max 5) at temperature
+    /// 0.7 unless one is set, and vote on the samples' findings. Per model, write
+    /// `models: ["grok#3"]` instead. Samples are reported as `grok#1`..`grok#3`.
+    pub samples: Option<usize>,
+    /// Two-stage review: these fast, cheap models first skim every file under
+    /// review and flag the riskiest; only those files (and their diff sections)
+    /// then go to `models` in deep mode. Both stages are persisted.
+    pub triage_then_deep: Option<TriageThenDeep>,
+    /// Memory hard gate for this review: "on" (default) drops models with a poor
+    /// success record, "off" dispatches them anyway, "report_only" dispatches them
+    /// and warns which would have been dropped. Use "off" to bring a gated model
+    /// back into rotation without deleting memory files.
+    pub gating: Option<GatingMode>,
+    /// Grade every complete response against weighted criteria with a judge
+    /// model. Scores (0.0-1.0) are returned per model as `rubric` and logged to
+    /// memory, where recommendations rank models by them.
+    pub rubric: Option<Rubric>,
+    /// Dependency-change risk review: when the diff touches lockfiles or manifests
+    /// (Cargo, npm/yarn, Python, Go), list the packages it adds, updates and removes
+    /// and ask every model about their supply-chain risk. Those findings are
+    /// reported apart from the code findings, as `supply_chain`. Default: false.
+    pub dependency_risk: Option<bool>,
+    /// With dependency_risk: look up each added or updated package version in the
+    /// OSV advisory database (api.osv.dev) and show the models what it finds.
+    /// Default: false.
+    pub dependency_advisories: Option<bool>,
+    /// Red-team review: some of the models construct exploit scenarios and abuse cases
+    /// for the code instead of reviewing it, under their own system prompt. Their
+    /// scenarios are reported apart from the conventional findings, as `red_team`.
+    /// Default: false.
+    pub red_team: Option<bool>,
+    /// With red_team: the models that attack. Default: a third of the models (at least
+    /// one), rotating across reviews.
+    pub red_team_models: Option<Vec<String>>,
+    /// When the prompt asks numbered questions ("1. ...?", "2) ...?"), check each
+    /// response answers all of them and re-ask models the ones they skipped, if the
+    /// cutoff leaves time. Follow-up answers are appended under a marked heading.
+    /// Default: true.
+    pub reprompt_unanswered: Option<bool>,
+}
+
+/// The first stage of a `triage_then_deep` review.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct TriageThenDeep {
+    /// Fast, cheap models that pick the files worth a deep review.
+    pub models: Vec<String>,
+    /// Files passed to the deep stage, riskiest first. Default: 5, max 20.
+    pub max_files: Option<usize>,
+    /// Cutoff for the triage pass in seconds. Default: 120.
+    pub timeout_secs: Option<u64>,
+}
+
+/// Weighted criteria a judge model scores each response on (see `crate::rubric`).
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct Rubric {
+    /// What to grade, 1-10 criteria.
+    pub criteria: Vec<RubricCriterion>,
+    /// Model that grades every response. A reviewer can be its own judge, but a
+    /// model outside the fan-out is the fairer choice.
+    pub judge: String,
+    /// Cutoff for the grading pass in seconds, counted after the review's own.
+    /// Default: 120.
+    pub timeout_secs: Option<u64>,
+}
+
+/// One rubric criterion.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct RubricCriterion {
+    /// Short name the judge scores under, e.g. "correctness".
+    pub name: String,
+    /// What a high score means, e.g. "every claim about the code is true".
+    pub description: Option<String>,
+    /// Relative weight in the overall score. Default: 1.
+    pub weight: Option<f64>,
+}
+
+/// `working_directory`: one project root, or the roots of a multi-repo review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum WorkingDirectory {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl WorkingDirectory {
+    /// The single root, or the first of several.
+    pub fn primary(&self) -> Option<&str> {
+        match self {
+            Self::One(dir) => Some(dir),
+            Self::Many(dirs) => dirs.first().map(String::as_str),
+        }
+    }
+}
+
+impl From<String> for WorkingDirectory {
+    fn from(dir: String) -> Self {
+        Self::One(dir)
+    }
 }
 
 /// Maximum size for investigation_context in bytes (32KB).
@@ -92,6 +264,15 @@ impl ReviewRequest {
         }
     }
 
+    /// Effective priority: deep reviews default to Low so interactive calls go first.
+    pub fn effective_priority(&self) -> Priority {
+        match self.priority {
+            Some(priority) => priority,
+            None if self.deep == Some(true) => Priority::Low,
+            None => Priority::Normal,
+        }
+    }
+
     /// Effective max tokens: deep mode defaults to 16384.
     pub fn effective_max_tokens(&self) -> Option<u64> {
         if self.deep == Some(true) && self.max_tokens.is_none() {
@@ -118,12 +299,79 @@ pub struct ReviewModelResult {
     /// True if the response was truncated (cancellation, deadline, or stall).
     #[serde(default, skip_serializing_if = "is_false")]
     pub partial: bool,
+    /// Dispatch phase breakdown (queue / headers / TTFB / streaming). Omitted when unknown.
+    #[serde(skip_serializing_if = "DispatchTiming::is_empty")]
+    pub timing: DispatchTiming,
+    /// Verbatim code quotes checked against the supplied files and diff.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub quotes_checked: usize,
+    /// Problems found in the response text itself (see `crate::quotes`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quality_flags: Vec<QualityFlag>,
+    /// How closely the response followed `expected_format` (0.0-1.0). Omitted when
+    /// no format was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_compliance: Option<f64>,
+    /// The judge's grade against the request's `rubric`. Omitted when no rubric
+    /// was given or the response could not be graded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rubric: Option<RubricScore>,
+    /// Tokens the provider reported for this call. Omitted when not reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Request/response IDs, served model version, and `system_fingerprint` as
+    /// returned by the provider, for support tickets. Omitted when none were sent.
+    #[serde(skip_serializing_if = "ProviderMetadata::is_empty")]
+    pub provider_metadata: ProviderMetadata,
+    /// True for a background straggler's result, appended to the persisted review
+    /// after the response was returned.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub late: bool,
+    /// For partial results only: whether the text is worth using despite the cut
+    /// (enough complete prose, or JSON that parses once closed; see `crate::partial`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usable: Option<bool>,
+    /// With `expected_format: json`: the response didn't parse as-is and was
+    /// replaced by the document salvaged from it (see `crate::json_repair`).
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub repaired: bool,
+    /// Set when this response is near-identical to that model's (sibling
+    /// checkpoints often agree word for word); the markdown shows it once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+impl ReviewModelResult {
+    /// Kinds of secrets redacted from `response`.
+    pub fn redacted_secrets(&self) -> Vec<&str> {
+        self.quality_flags
+            .iter()
+            .filter_map(|flag| match flag {
+                QualityFlag::SecretRedacted { secret } => Some(secret.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 fn is_false(b: &bool) -> bool {
     !*b
 }
 
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// A quality problem detected in a model's response.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QualityFlag {
+    /// Code quoted as if verbatim that appears nowhere in the supplied context.
+    FabricatedQuote { quote: String },
+    /// A secret in the response, replaced with `[REDACTED:<secret>]`.
+    SecretRedacted { secret: String },
+}
+
 /// Status of an individual model in a review.
 #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -160,6 +408,9 @@ pub struct ReviewSummary {
 /// Full review response (rendered as markdown for MCP, persisted as JSON to disk).
 #[derive(Debug, Serialize)]
 pub struct ReviewResponse {
+    /// Tags this review's log lines (`review_id` span field) and names its
+    /// `results_file`, so logs and persisted results can be matched up.
+    pub review_id: String,
     pub results: Vec<ReviewModelResult>,
     pub not_started: Vec<String>,
     pub cutoff_seconds: u64,
@@ -173,11 +424,61 @@ pub struct ReviewResponse {
     /// File read errors (non-existent files, permission errors). Non-fatal.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub files_errors: Option<Vec<String>>,
+    /// Files each model received, per its context budget. Trimmed files carry a
+    /// ` (trimmed)` suffix. Empty when no file context was sent.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files_sent: BTreeMap<String, Vec<String>>,
     /// Actionable warnings about the review execution (unknown keys, truncation, etc.).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<String>,
     /// Quick summary of model outcomes.
     pub summary: ReviewSummary,
+    /// Size of the reviewed diff as `"+added -removed"` (None when no diff was sent).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_size: Option<String>,
+    /// Patterns saved by auto-memorize (`[review] auto_memorize_model`) for this review.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_memorized: Vec<String>,
+    /// Branch and commit the review was taken at (used by `incremental` lookups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitContext>,
+    /// Each repository's git context, for reviews spanning several repos.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repos: Vec<RepoContext>,
+    /// Caller-supplied labels (`ReviewRequest::tags`), trimmed and deduplicated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Language whose review profile was merged into the system prompts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_profile: Option<String>,
+    /// Model → reviewer persona it was assigned (`ReviewRequest::personas`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub personas: BTreeMap<String, String>,
+    /// Per sampled model: findings most of its samples agreed on, and the rest.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub consistency: Vec<SampleVote>,
+    /// Confidence calibration of the reviewed models with enough rated findings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calibration: Vec<ModelCalibration>,
+    /// The triage pass of a `triage_then_deep` review, and the files it picked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<TriageOutcome>,
+    /// Compliance with each `.squall/policy.toml` rule the review fell under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy: Vec<PolicyCheck>,
+    /// Dependency delta and supply-chain findings of a `dependency_risk` review.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supply_chain: Option<SupplyChainReview>,
+    /// Red-team models and their exploit scenarios (`ReviewRequest::red_team`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub red_team: Option<RedTeamReview>,
+    /// Per model, which of the prompt's numbered questions it answered, answered
+    /// only when re-asked, or skipped (`ReviewRequest::reprompt_unanswered`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub question_coverage: BTreeMap<String, QuestionCoverage>,
+    /// Base review, changed files, and carried-forward findings for incremental reviews.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incremental: Option<IncrementalReview>,
 }
 
 impl ReviewResponse {
@@ -199,6 +500,45 @@ impl ReviewResponse {
         if let Some(ref file) = self.results_file {
             md.push_str(&format!("\nResults saved: `{file}`\n"));
         }
+        if !self.tags.is_empty() {
+            md.push_str(&format!("Tags: {}\n", self.tags.join(", ")));
+        }
+        if !self.repos.is_empty() {
+            let repos: Vec<String> = self
+                .repos
+                .iter()
+                .map(|repo| match repo.git {
+                    Some(ref git) => format!(
+                        "{} ({}@{})",
+                        repo.name,
+                        git.branch.as_deref().unwrap_or("detached"),
+                        git.commit_sha.as_deref().unwrap_or("?")
+                    ),
+                    None => repo.name.clone(),
+                })
+                .collect();
+            md.push_str(&format!("Repos: {}\n", repos.join(", ")));
+        }
+        if let Some(ref language) = self.language_profile {
+            md.push_str(&format!("Language profile: {language}\n"));
+        }
+        if !self.personas.is_empty() {
+            let personas: Vec<String> = self
+                .personas
+                .iter()
+                .map(|(model, persona)| format!("{model}: {persona}"))
+                .collect();
+            md.push_str(&format!("Personas: {}\n", personas.join(", ")));
+        }
+        if let Some(line) = crate::policy::summary_line(&self.policy) {
+            md.push_str(&format!("{line}\n"));
+        }
+        if let Some(ref red_team) = self.red_team {
+            md.push_str(&format!("Red team: {}\n", red_team.models.join(", ")));
+        }
+        if let Some(line) = crate::questions::summary_line(&self.question_coverage) {
+            md.push_str(&format!("{line}\n"));
+        }
 
         // Persistence error — critical in concise mode where model text is omitted
         if let Some(ref err) = self.persist_error {
@@ -219,6 +559,15 @@ impl ReviewResponse {
         {
             md.push_str(&format!("\n**File errors**: {}\n", errors.join(", ")));
         }
+        // Only worth showing when some model got less than the others.
+        let mut distinct_sends = self.files_sent.values().collect::<Vec<_>>();
+        distinct_sends.dedup();
+        if distinct_sends.len() > 1 {
+            md.push_str("\n**Files sent per model**:\n");
+            for (model, files) in &self.files_sent {
+                md.push_str(&format!("- {model}: {}\n", files.join(", ")));
+            }
+        }
 
         // Warnings
         if !self.warnings.is_empty() {
@@ -228,6 +577,183 @@ impl ReviewResponse {
             }
         }
 
+        // Quotes that don't appear in the supplied context
+        let flagged: Vec<_> = self
+            .results
+            .iter()
+            .filter(|r| !r.quality_flags.is_empty())
+            .collect();
+        if !flagged.is_empty() {
+            md.push_str("\n### Quality flags\n");
+            for res in flagged {
+                let quotes: Vec<&str> = res
+                    .quality_flags
+                    .iter()
+                    .filter_map(|flag| match flag {
+                        QualityFlag::FabricatedQuote { quote } => Some(quote.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                if !quotes.is_empty() {
+                    md.push_str(&format!(
+                        "- **{}**: {} of {} quoted snippets not found in the supplied context\n",
+                        res.model,
+                        quotes.len(),
+                        res.quotes_checked
+                    ));
+                    for quote in quotes {
+                        md.push_str(&format!("  - `{quote}`\n"));
+                    }
+                }
+                let secrets = res.redacted_secrets();
+                if !secrets.is_empty() {
+                    md.push_str(&format!(
+                        "- **{}**: redacted {} from the response\n",
+                        res.model,
+                        crate::secrets::summarize(&secrets)
+                    ));
+                }
+            }
+        }
+
+        // Self-consistency votes
+        for vote in &self.consistency {
+            md.push_str(&format!(
+                "\n### Self-consistency: {} ({} of {} samples responded)\n",
+                vote.model, vote.responded, vote.samples
+            ));
+            if vote.majority.is_empty() && vote.minority.is_empty() {
+                md.push_str("No findings.\n");
+            }
+            if !vote.majority.is_empty() {
+                md.push_str("\n**Majority**:\n");
+                for finding in &vote.majority {
+                    md.push_str(&format!("- {finding}\n"));
+                }
+            }
+            if !vote.minority.is_empty() {
+                md.push_str("\n**Some samples only**:\n");
+                for finding in &vote.minority {
+                    md.push_str(&format!("- {finding}\n"));
+                }
+            }
+        }
+
+        if let Some(line) = crate::calibration::summary_line(&self.calibration) {
+            md.push_str(&format!("\n{line}\n"));
+        }
+
+        // Rubric grades, best first
+        let mut graded: Vec<(&str, &RubricScore)> = self
+            .results
+            .iter()
+            .filter_map(|r| r.rubric.as_ref().map(|g| (r.model.as_str(), g)))
+            .collect();
+        graded.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+        if let Some((_, first)) = graded.first() {
+            md.push_str(&format!("\n### Rubric scores (judge: {})\n", first.judge));
+            for (model, grade) in &graded {
+                let criteria: Vec<String> = grade
+                    .criteria
+                    .iter()
+                    .map(|(name, score)| format!("{name} {score}"))
+                    .collect();
+                md.push_str(&format!(
+                    "- **{model}**: {:.0}% ({})",
+                    grade.score * 100.0,
+                    criteria.join(", ")
+                ));
+                if let Some(ref comment) = grade.comment {
+                    md.push_str(&format!(" — {comment}"));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Auto-memorized patterns
+        if !self.auto_memorized.is_empty() {
+            md.push_str("\n### Auto-memorized\n");
+            for entry in &self.auto_memorized {
+                md.push_str(&format!("- {entry}\n"));
+            }
+        }
+
+        // Triage: which files the first pass picked for the deep review
+        if let Some(ref triage) = self.triage {
+            md.push_str(&format!(
+                "\n### Triage\n{} of {} file(s) picked by {} (`{}`) for the deep review:\n",
+                triage.risky.len(),
+                triage.candidates,
+                if triage.models.is_empty() {
+                    "no model".to_string()
+                } else {
+                    triage.models.join(", ")
+                },
+                triage.results_file.as_deref().unwrap_or(&triage.review_id),
+            ));
+            for file in &triage.risky {
+                md.push_str(&format!(
+                    "- {} — {} ({})\n",
+                    file.path,
+                    file.reason,
+                    file.models.join(", ")
+                ));
+            }
+        }
+
+        // Policy: which rules applied and who satisfied them
+        if !self.policy.is_empty() {
+            md.push_str("\n### Policy\n");
+            for check in &self.policy {
+                md.push_str(&format!(
+                    "- **{}** ({}): {} — {}",
+                    check.rule,
+                    if check.compliant { "met" } else { "NOT met" },
+                    check.requirement,
+                    check.files.join(", ")
+                ));
+                if !check.satisfied_by.is_empty() {
+                    md.push_str(&format!("; satisfied by {}", check.satisfied_by.join(", ")));
+                }
+                if !check.added.is_empty() {
+                    md.push_str(&format!("; added {}", check.added.join(", ")));
+                }
+                md.push('\n');
+            }
+        }
+
+        // Red team: exploit scenarios, apart from the conventional findings
+        if let Some(ref red_team) = self.red_team {
+            md.push_str(&red_team.to_markdown());
+        }
+
+        // Supply chain: the dependency delta and its risk findings
+        if let Some(ref supply_chain) = self.supply_chain {
+            md.push_str(&supply_chain.to_markdown());
+        }
+
+        // Incremental: what was re-reviewed and what carried over
+        if let Some(ref inc) = self.incremental {
+            md.push_str(&format!(
+                "\n### Incremental\nSince `{}` ({}): {} changed file(s) re-reviewed.\n",
+                inc.base_commit,
+                inc.base_review,
+                inc.changed_files.len()
+            ));
+            if !inc.carried_forward.is_empty() {
+                md.push_str("\n**Carried forward (unchanged files)**:\n");
+                for f in &inc.carried_forward {
+                    let severity = f.severity.as_ref().map_or("unrated", |s| s.as_str());
+                    md.push_str(&format!(
+                        "- [{severity}] {} ({}) — {}\n",
+                        f.summary,
+                        f.file_path.as_deref().unwrap_or_default(),
+                        f.model_key
+                    ));
+                }
+            }
+        }
+
         // Not started
         if !self.not_started.is_empty() {
             md.push_str(&format!(
@@ -249,13 +775,42 @@ impl ReviewResponse {
                 .filter(|r| r.status != ModelStatus::Success)
                 .collect();
 
+            // The red team's responses come after the conventional reviews.
+            let red_team = self
+                .red_team
+                .as_ref()
+                .map(|r| r.models.as_slice())
+                .unwrap_or_default();
+            let (attacks, reviews): (Vec<&&ReviewModelResult>, Vec<_>) = succeeded
+                .iter()
+                .filter(|r| r.duplicate_of.is_none())
+                .partition(|r| red_team.contains(&r.model));
             if !succeeded.is_empty() {
-                for res in &succeeded {
+                for res in reviews.into_iter().chain(attacks) {
+                    let compliance = res
+                        .format_compliance
+                        .map(|s| format!(", format {:.0}%", s * 100.0))
+                        .unwrap_or_default();
+                    let partial = match (res.partial, res.usable) {
+                        (false, _) => "",
+                        (true, Some(false)) => ", partial, not usable",
+                        (true, Some(true)) => ", partial, usable",
+                        (true, None) => ", partial",
+                    };
+                    let repaired = if res.repaired { ", repaired JSON" } else { "" };
+                    let same: String = succeeded
+                        .iter()
+                        .filter(|r| r.duplicate_of.as_deref() == Some(res.model.as_str()))
+                        .map(|r| format!(", same as {} ({}ms)", r.model, r.latency_ms))
+                        .collect();
+                    let role = if red_team.contains(&res.model) {
+                        "red team, "
+                    } else {
+                        ""
+                    };
                     md.push_str(&format!(
-                        "\n### {} ({}ms{})\n",
-                        res.model,
-                        res.latency_ms,
-                        if res.partial { ", partial" } else { "" },
+                        "\n### {} ({role}{}ms{partial}{compliance}{repaired}{same})\n",
+                        res.model, res.latency_ms,
                     ));
                     if let Some(ref text) = res.response {
                         md.push_str(text.trim());
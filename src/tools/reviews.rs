@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::search::{DEFAULT_MAX_RESULTS, ListQuery, SearchQuery};
+
+/// Request to search persisted reviews.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReviewsSearchRequest {
+    /// Keywords, all of which must appear in one prompt, finding, or response
+    /// (case-insensitive). Wrap phrases in double quotes: `"auth module" race`.
+    pub query: String,
+    /// Only hits from these models (config keys, e.g. ["grok", "gemini"]).
+    pub models: Option<Vec<String>>,
+    /// Only reviews on or after this date (YYYY-MM-DD, UTC).
+    pub since: Option<String>,
+    /// Only reviews on or before this date (YYYY-MM-DD, UTC).
+    pub until: Option<String>,
+    /// Only reviews taken on this git branch.
+    pub branch: Option<String>,
+    /// Only reviews tagged with all of these (see `review`'s `tags`).
+    pub tags: Option<Vec<String>>,
+    /// Maximum hits to return (default 10, max 50).
+    pub max_results: Option<usize>,
+}
+
+impl ReviewsSearchRequest {
+    pub fn query(&self) -> SearchQuery {
+        SearchQuery {
+            query: self.query.clone(),
+            models: self.models.clone().unwrap_or_default(),
+            since: self.since.clone(),
+            until: self.until.clone(),
+            branch: self.branch.clone(),
+            tags: self.tags.clone().unwrap_or_default(),
+            max_results: self.max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+        }
+    }
+}
+
+/// Request to list persisted reviews.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReviewsListRequest {
+    /// Only reviews tagged with all of these (e.g. ["PROJ-142", "pre-merge"]).
+    pub tags: Option<Vec<String>>,
+    /// Only reviews taken on this git branch.
+    pub branch: Option<String>,
+    /// Only reviews on or after this date (YYYY-MM-DD, UTC).
+    pub since: Option<String>,
+    /// Only reviews on or before this date (YYYY-MM-DD, UTC).
+    pub until: Option<String>,
+    /// Maximum reviews to return, newest first (default 10, max 50).
+    pub max_results: Option<usize>,
+}
+
+impl ReviewsListRequest {
+    pub fn query(&self) -> ListQuery {
+        ListQuery {
+            since: self.since.clone(),
+            until: self.until.clone(),
+            branch: self.branch.clone(),
+            tags: self.tags.clone().unwrap_or_default(),
+            max_results: self.max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+        }
+    }
+}
+
+/// Request to compare two persisted reviews.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReviewsCompareRequest {
+    /// Earlier review: results file stem or path (e.g. "1773157800594_76050_0" or
+    /// ".squall/reviews/1773157800594_76050_0.json").
+    pub id_a: String,
+    /// Later review, same forms as `id_a`.
+    pub id_b: String,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
@@ -0,0 +1,49 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::suites::{Suite, SuitePrompt};
+
+/// Request to create (or replace) a prompt suite.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SuiteCreateRequest {
+    /// Suite name: letters, digits, '-', '_' or '.'. Saved as `.squall/suites/{name}.toml`.
+    pub name: String,
+    /// What the suite exercises.
+    pub description: Option<String>,
+    /// Prompts, each with a unique `id` and optional `expect` checks
+    /// (`contains`, `not_contains`, `min_words`, `max_words`, `json`).
+    pub prompts: Vec<SuitePrompt>,
+    /// Replace an existing suite of the same name (default false).
+    pub overwrite: Option<bool>,
+}
+
+impl SuiteCreateRequest {
+    pub fn suite(&self) -> Suite {
+        Suite {
+            name: self.name.clone(),
+            description: self.description.clone().filter(|d| !d.trim().is_empty()),
+            prompts: self.prompts.clone(),
+        }
+    }
+}
+
+/// Request to list prompt suites.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SuiteListRequest {
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+/// Request to run a prompt suite and check the answers.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SuiteRunRequest {
+    /// Suite name from `suite_list`.
+    pub suite: String,
+    /// Model names from `listmodels`. Defaults to `[review] default_models` from config.
+    pub models: Option<Vec<String>>,
+    /// Seconds to wait for the models (default 180, max 600).
+    pub timeout_secs: Option<u64>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
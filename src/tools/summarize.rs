@@ -0,0 +1,54 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::summarize::{DEFAULT_CHUNK_BYTES, DEFAULT_TARGET_WORDS, MIN_CHUNK_BYTES};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SummarizeRequest {
+    /// Text to summarize (e.g. output of another tool). Combined with file_paths if both are set.
+    pub content: Option<String>,
+    /// Relative file paths to summarize (read server-side). Requires working_directory.
+    pub file_paths: Option<Vec<String>>,
+    /// Absolute path to the project root for resolving file_paths. Required when file_paths is set.
+    pub working_directory: Option<String>,
+    /// What the summary should emphasize (e.g. "security findings", "API changes").
+    pub focus: Option<String>,
+    /// Approximate length of the final summary in words (default 300).
+    pub target_words: Option<u32>,
+    /// Fast model that summarizes each chunk (default "grok"). Use exact names from `listmodels`.
+    pub map_model: Option<String>,
+    /// Stronger model that merges chunk summaries (default "deepseek-v3.1").
+    pub reduce_model: Option<String>,
+    /// Chunk size in bytes for the map step (default 48KB, minimum 4KB).
+    pub chunk_bytes: Option<usize>,
+}
+
+pub const DEFAULT_MAP_MODEL: &str = "grok";
+pub const DEFAULT_REDUCE_MODEL: &str = "deepseek-v3.1";
+
+impl SummarizeRequest {
+    pub fn map_model_or_default(&self) -> &str {
+        self.map_model
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_MAP_MODEL)
+    }
+
+    pub fn reduce_model_or_default(&self) -> &str {
+        self.reduce_model
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_REDUCE_MODEL)
+    }
+
+    pub fn target_words_or_default(&self) -> u32 {
+        self.target_words
+            .filter(|&w| w > 0)
+            .unwrap_or(DEFAULT_TARGET_WORDS)
+    }
+
+    pub fn chunk_bytes_or_default(&self) -> usize {
+        self.chunk_bytes
+            .map_or(DEFAULT_CHUNK_BYTES, |b| b.max(MIN_CHUNK_BYTES))
+    }
+}
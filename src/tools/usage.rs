@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::enums::OutputFormat;
+use crate::usage::UsageQuery;
+
+/// Request for a usage report from the usage ledger.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UsageRequest {
+    /// First day of the window (YYYY-MM-DD, UTC). Default: 30 days before `until`.
+    pub since: Option<String>,
+    /// Last day of the window (YYYY-MM-DD, UTC). Default: today.
+    pub until: Option<String>,
+    /// Only calls from this MCP client (e.g. "claude-code", "cursor", "ci").
+    pub client: Option<String>,
+    /// "markdown" (default) or "json".
+    pub format: Option<OutputFormat>,
+}
+
+impl UsageRequest {
+    pub fn query(&self) -> UsageQuery {
+        UsageQuery {
+            since: self.since.clone(),
+            until: self.until.clone(),
+            client: self.client.clone(),
+        }
+    }
+}
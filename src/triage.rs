@@ -0,0 +1,245 @@
+//! Two-stage review (`triage_then_deep`): a fast, cheap model set skims
+//! everything under review and names the riskiest files; only those files go
+//! to the main models, in deep mode. Both stages run in one tool call and both
+//! are persisted: the triage pass is a review of its own, linked from the main
+//! review's `triage` field.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::review::ReviewOptions;
+use crate::tools::enums::ReasoningEffort;
+use crate::tools::review::{ModelStatus, ReviewModelResult, ReviewRequest, TriageThenDeep};
+
+/// Files passed on to the deep stage unless the request says otherwise.
+pub const DEFAULT_RISKY_FILES: usize = 5;
+
+/// Most files the deep stage can be narrowed to.
+pub const MAX_RISKY_FILES: usize = 20;
+
+/// Cutoff for the triage pass unless the request says otherwise.
+pub const DEFAULT_TRIAGE_TIMEOUT_SECS: u64 = 120;
+
+const TRIAGE_SYSTEM_PROMPT: &str = "You triage code for review. Skim everything you are \
+    given and pick the files most likely to hide bugs, security holes, or risky behavior \
+    changes. Do not review them in detail; a slower, more thorough reviewer will.";
+
+/// A file the triage pass flagged for the deep stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskyFile {
+    pub path: String,
+    /// Why the first model to flag it thinks it is risky.
+    pub reason: String,
+    /// Triage models that flagged it.
+    pub models: Vec<String>,
+}
+
+/// The triage stage of a review, as reported in `ReviewResponse::triage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageOutcome {
+    /// The triage pass's own persisted review.
+    pub review_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results_file: Option<String>,
+    /// Triage models that answered.
+    pub models: Vec<String>,
+    /// Files under review before triage.
+    pub candidates: usize,
+    /// Files passed to the deep stage, riskiest first.
+    pub risky: Vec<RiskyFile>,
+}
+
+/// Number of files the deep stage is narrowed to.
+pub fn max_files(spec: &TriageThenDeep) -> usize {
+    spec.max_files
+        .unwrap_or(DEFAULT_RISKY_FILES)
+        .clamp(1, MAX_RISKY_FILES)
+}
+
+/// The triage pass's request: the triage models, a short cutoff and low
+/// reasoning effort, with the main request's files, diff and tags.
+pub fn triage_request(req: &ReviewRequest, spec: &TriageThenDeep) -> ReviewRequest {
+    let mut tags = req.tags.clone().unwrap_or_default();
+    tags.push("triage".to_string());
+    let mut options = ReviewOptions::new(req.prompt.clone());
+    options.models = spec.models.clone();
+    options.timeout = Some(std::time::Duration::from_secs(
+        spec.timeout_secs.unwrap_or(DEFAULT_TRIAGE_TIMEOUT_SECS),
+    ));
+    options.system_prompt = Some(TRIAGE_SYSTEM_PROMPT.to_string());
+    options.reasoning_effort = Some(ReasoningEffort::Low);
+    options.file_paths = req.file_paths.clone().unwrap_or_default();
+    options.diff = req.diff.clone();
+    options.tags = tags;
+    let mut triage = options.into_request();
+    triage.working_directory = req.working_directory.clone();
+    triage.context_format = req.context_format;
+    triage.language_profile = Some(false);
+    triage
+}
+
+/// The assembled review prompt with the triage question appended.
+pub fn triage_prompt(prompt: &str, candidates: &[String], max_files: usize) -> String {
+    format!(
+        "{prompt}\n\n---\nDo not review yet. Of the files above ({}), which {max_files} or fewer \
+         most deserve a thorough review? Reply with ONLY a JSON array, riskiest first: \
+         [{{\"path\": \"<path exactly as listed>\", \"reason\": \"<one sentence>\"}}]. \
+         Reply [] if nothing looks risky.",
+        candidates.join(", ")
+    )
+}
+
+#[derive(Deserialize)]
+struct Flagged {
+    path: String,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Paths and reasons from one triage answer, tolerating prose or fences around
+/// the JSON array. Anything unparseable yields nothing.
+fn parse_flagged(text: &str) -> Vec<Flagged> {
+    crate::json_repair::parse(text)
+        .and_then(|(doc, _)| serde_json::from_value(doc).ok())
+        .unwrap_or_default()
+}
+
+/// Rank the candidates the triage models flagged: by how many models flagged
+/// them, then by how early they were listed. Paths outside `candidates` are
+/// ignored.
+pub fn rank(
+    results: &[ReviewModelResult],
+    candidates: &[String],
+    max_files: usize,
+) -> Vec<RiskyFile> {
+    let mut flagged: Vec<RiskyFile> = Vec::new();
+    let mut best_position: HashMap<String, usize> = HashMap::new();
+    for result in results {
+        if result.status != ModelStatus::Success {
+            continue;
+        }
+        let Some(text) = result.response.as_deref() else {
+            continue;
+        };
+        for (position, item) in parse_flagged(text).into_iter().enumerate() {
+            let path = item.path.trim().trim_matches('`').trim_start_matches("./");
+            let Some(path) = candidates.iter().find(|c| c.as_str() == path) else {
+                continue;
+            };
+            let best = best_position.entry(path.clone()).or_insert(position);
+            *best = (*best).min(position);
+            match flagged.iter_mut().find(|f| &f.path == path) {
+                Some(file) if !file.models.contains(&result.model) => {
+                    file.models.push(result.model.clone())
+                }
+                Some(_) => {}
+                None => flagged.push(RiskyFile {
+                    path: path.clone(),
+                    reason: item.reason.trim().to_string(),
+                    models: vec![result.model.clone()],
+                }),
+            }
+        }
+    }
+    flagged.sort_by_key(|f| (std::cmp::Reverse(f.models.len()), best_position[&f.path]));
+    flagged.truncate(max_files);
+    flagged
+}
+
+/// The `<triage>` block telling the deep stage why it got these files.
+pub fn prompt_block(risky: &[RiskyFile]) -> String {
+    let mut out = String::from(
+        "<triage note=\"a first pass flagged these files as the riskiest; review them in depth\">\n",
+    );
+    for file in risky {
+        let line = if file.reason.is_empty() {
+            file.path.clone()
+        } else {
+            format!("{}: {}", file.path, file.reason)
+        };
+        out.push_str(&crate::context::escape_xml_content(&line));
+        out.push('\n');
+    }
+    out.push_str("</triage>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(model: &str, text: &str) -> ReviewModelResult {
+        ReviewModelResult {
+            model: model.to_string(),
+            provider: "test".to_string(),
+            status: ModelStatus::Success,
+            response: Some(text.to_string()),
+            error: None,
+            reason: None,
+            latency_ms: 100,
+            partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
+        }
+    }
+
+    #[test]
+    fn ranks_flagged_candidates_by_votes_then_position() {
+        let candidates: Vec<String> = ["src/auth.rs", "src/pool.rs", "src/util.rs", "README.md"]
+            .map(String::from)
+            .to_vec();
+        let results = vec![
+            answer(
+                "fast-a",
+                r#"```json
+[{"path": "src/util.rs", "reason": "string slicing on user input"},
+ {"path": "./src/auth.rs", "reason": "token comparison is not constant-time"}]
+```"#,
+            ),
+            answer(
+                "fast-b",
+                r#"Here you go: [{"path": "`src/auth.rs`", "reason": "session fixation"},
+                   {"path": "src/other.rs", "reason": "not under review"}]"#,
+            ),
+            answer("fast-c", "I could not decide."),
+        ];
+        let risky = rank(&results, &candidates, 5);
+        let paths: Vec<&str> = risky.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/auth.rs", "src/util.rs"]);
+        assert_eq!(risky[0].models, vec!["fast-a", "fast-b"]);
+        assert_eq!(risky[0].reason, "token comparison is not constant-time");
+
+        assert_eq!(rank(&results, &candidates, 1).len(), 1);
+        let block = prompt_block(&risky);
+        assert!(block.starts_with("<triage "), "{block}");
+        assert!(block.contains("src/util.rs: string slicing on user input\n"));
+    }
+
+    #[test]
+    fn triage_request_uses_the_triage_models() {
+        let spec = TriageThenDeep {
+            models: vec!["fast-a".to_string()],
+            max_files: Some(100),
+            timeout_secs: None,
+        };
+        assert_eq!(max_files(&spec), MAX_RISKY_FILES);
+        let mut options = ReviewOptions::new("Review the session handling.");
+        options.models = vec!["slow-opus".to_string()];
+        options.file_paths = vec!["src/auth.rs".to_string()];
+        let triage = triage_request(&options.into_request(), &spec);
+        assert_eq!(triage.models, Some(vec!["fast-a".to_string()]));
+        assert_eq!(triage.timeout_secs, Some(DEFAULT_TRIAGE_TIMEOUT_SECS));
+        assert_eq!(triage.tags, Some(vec!["triage".to_string()]));
+        assert_eq!(triage.file_paths, Some(vec!["src/auth.rs".to_string()]));
+    }
+}
@@ -0,0 +1,547 @@
+//! URL context: fetch http(s) documents (docs pages, API specs) and inline them
+//! into prompts as `<url>` entries, alongside `<file>` context.
+//!
+//! Fetches are bounded: a per-document download cap, a total context cap, a
+//! timeout, and a content-type allowlist (text, HTML, JSON, XML, YAML). Hosts must
+//! resolve to public addresses, checked on every redirect hop and pinned for the
+//! connection, so a prompt cannot point the server at loopback, the LAN, or a
+//! cloud metadata endpoint.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use reqwest::Url;
+
+use crate::context::{escape_xml_attr, escape_xml_content, floor_char_boundary};
+use crate::error::SquallError;
+
+/// Maximum number of URLs per request.
+pub const MAX_URLS: usize = 10;
+
+/// Maximum bytes downloaded per URL. Longer bodies are cut and marked truncated.
+pub const MAX_URL_BYTES: usize = 512 * 1024;
+
+/// Maximum bytes of rendered URL context per prompt, on top of file context.
+pub const MAX_URL_CONTEXT_BYTES: usize = 256 * 1024;
+
+/// Whole-fetch deadline per URL, redirects included.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Redirect hops followed before giving up.
+const MAX_REDIRECTS: usize = 5;
+
+/// Result of fetching URL context.
+#[derive(Debug, Default)]
+pub struct UrlContextResult {
+    /// The XML-formatted URL context string (None if nothing was fetched).
+    pub context: Option<String>,
+    /// URLs included in `context`, in request order.
+    pub included: Vec<String>,
+    /// URLs cut to `MAX_URL_BYTES` or to fit `MAX_URL_CONTEXT_BYTES`.
+    pub truncated: Vec<String>,
+    /// URLs that could not be fetched (non-fatal unless all fail).
+    pub errors: Vec<String>,
+    /// Suspected prompt injections in fetched text, as `url: line N: reason`.
+    pub injection_suspected: Vec<String>,
+}
+
+impl UrlContextResult {
+    /// Prepend the fetched context to `prompt`.
+    pub fn prepend_to(&self, prompt: String) -> String {
+        match self.context {
+            Some(ref ctx) => format!("{ctx}\n{prompt}"),
+            None => prompt,
+        }
+    }
+
+    /// Fetch failures, truncations and suspected injections, for a response's `warnings`.
+    pub fn warnings(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .map(|e| format!("url not fetched: {e}"))
+            .chain(self.truncated.iter().map(|u| format!("url truncated: {u}")))
+            .chain(
+                self.injection_suspected
+                    .iter()
+                    .map(|hit| format!("injection_suspected: {hit}")),
+            )
+            .collect()
+    }
+}
+
+/// How a fetched body is turned into prompt text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentKind {
+    Html,
+    Text,
+}
+
+/// Fetch `urls` and render them as `<url>` context entries.
+/// Malformed or non-http(s) URLs reject the whole request; fetch failures are
+/// reported in `errors` and only fail the request when every URL fails.
+pub async fn resolve_url_context(urls: &[String]) -> Result<UrlContextResult, SquallError> {
+    if urls.is_empty() {
+        return Ok(UrlContextResult::default());
+    }
+    if urls.len() > MAX_URLS {
+        return Err(SquallError::FileContext(format!(
+            "too many urls: {} (max {MAX_URLS})",
+            urls.len()
+        )));
+    }
+    let parsed = urls
+        .iter()
+        .map(|raw| validate_url(raw).map_err(SquallError::FileContext))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let fetched = futures_util::future::join_all(parsed.into_iter().map(|url| async move {
+        let shown = url.to_string();
+        let outcome = tokio::time::timeout(FETCH_TIMEOUT, fetch(url))
+            .await
+            .unwrap_or_else(|_| Err(format!("timed out after {}s", FETCH_TIMEOUT.as_secs())));
+        (shown, outcome)
+    }))
+    .await;
+
+    let mut result = UrlContextResult::default();
+    let mut output = String::new();
+    for (url, outcome) in fetched {
+        let (text, mut truncated) = match outcome {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                result.errors.push(format!("{url}: {e}"));
+                continue;
+            }
+        };
+        let remaining = MAX_URL_CONTEXT_BYTES.saturating_sub(output.len());
+        let escaped = escape_xml_content(&text);
+        let body = if escaped.len() + url.len() + 64 > remaining {
+            truncated = true;
+            let keep = floor_char_boundary(&escaped, remaining.saturating_sub(url.len() + 64));
+            &escaped[..keep]
+        } else {
+            &escaped[..]
+        };
+        if body.trim().is_empty() {
+            result.errors.push(format!(
+                "{url}: {}",
+                if text.trim().is_empty() {
+                    "no text content"
+                } else {
+                    "URL context budget exhausted"
+                }
+            ));
+            continue;
+        }
+        let truncated_attr = if truncated { " truncated=\"true\"" } else { "" };
+        output.push_str(&format!(
+            "<url href=\"{}\"{truncated_attr}>\n{}\n</url>\n",
+            escape_xml_attr(&url),
+            body.trim_end()
+        ));
+        if truncated {
+            result.truncated.push(url.clone());
+        }
+        result.injection_suspected.extend(
+            crate::injection::scan(&text)
+                .into_iter()
+                .map(|hit| format!("{url}: {hit}")),
+        );
+        result.included.push(url);
+    }
+
+    if result.included.is_empty() {
+        return Err(SquallError::FileContext(format!(
+            "no URLs could be fetched: {}",
+            result.errors.join("; ")
+        )));
+    }
+    result.context = Some(output);
+    Ok(result)
+}
+
+/// Parse `raw` and check it is an http(s) URL with a host and no credentials.
+pub fn validate_url(raw: &str) -> Result<Url, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("invalid url '{raw}': {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("unsupported url scheme '{}': {raw}", url.scheme()));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(format!("urls with credentials are not allowed: {raw}"));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(format!("url has no host: {raw}"));
+    }
+    Ok(url)
+}
+
+/// True if `ip` is routable on the public internet.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                // 100.64.0.0/10 carrier-grade NAT
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // fc00::/7 unique local, fe80::/10 link-local
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolve `url`'s host, rejecting it unless every address is public.
+async fn resolve_public(url: &Url) -> Result<(String, SocketAddr), String> {
+    let host = url
+        .host_str()
+        .ok_or("url has no host")?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url.port_or_known_default().ok_or("url has no port")?;
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| format!("cannot resolve {host}: {e}"))?
+        .collect();
+    if let Some(private) = addrs.iter().find(|a| !is_public_ip(a.ip())) {
+        return Err(format!(
+            "{host} resolves to non-public address {}",
+            private.ip()
+        ));
+    }
+    let addr = addrs
+        .first()
+        .copied()
+        .ok_or_else(|| format!("cannot resolve {host}"))?;
+    Ok((host, addr))
+}
+
+/// Fetch one URL, following redirects by hand so each hop is re-checked.
+/// Returns the prompt text and whether the body was cut at `MAX_URL_BYTES`.
+async fn fetch(mut url: Url) -> Result<(String, bool), String> {
+    for _ in 0..=MAX_REDIRECTS {
+        let (host, addr) = resolve_public(&url).await?;
+        // Pin the checked address so a second DNS lookup cannot swap it out.
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve(&host, addr)
+            .user_agent(concat!("squall/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {e}"))?;
+        let response = client
+            .get(url.clone())
+            .header(
+                reqwest::header::ACCEPT,
+                "text/html, text/plain, application/json, application/yaml, application/xml;q=0.9, */*;q=0.1",
+            )
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| format!("HTTP {status} without a Location header"))?;
+            url = url
+                .join(location)
+                .map_err(|e| format!("bad redirect '{location}': {e}"))
+                .and_then(|next| validate_url(next.as_str()))?;
+            continue;
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {status}"));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let kind = content_kind(&content_type).ok_or_else(|| {
+            if content_type.is_empty() {
+                "no content-type".to_string()
+            } else {
+                format!("unsupported content-type '{content_type}'")
+            }
+        })?;
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("read failed: {e}"))?;
+            let room = MAX_URL_BYTES - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let text = String::from_utf8_lossy(&body);
+        let text = match kind {
+            ContentKind::Html => html_to_text(&text),
+            ContentKind::Text => text.into_owned(),
+        };
+        return Ok((text, truncated));
+    }
+    Err(format!("more than {MAX_REDIRECTS} redirects"))
+}
+
+/// Classify a `Content-Type` header value; None means it is not allowed.
+fn content_kind(content_type: &str) -> Option<ContentKind> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "text/html" | "application/xhtml+xml" => Some(ContentKind::Html),
+        "application/json"
+        | "application/xml"
+        | "application/yaml"
+        | "application/x-yaml"
+        | "application/toml"
+        | "application/javascript" => Some(ContentKind::Text),
+        m if m.starts_with("text/") || m.ends_with("+json") || m.ends_with("+xml") => {
+            Some(ContentKind::Text)
+        }
+        _ => None,
+    }
+}
+
+/// Tags whose boundaries start a new line in the extracted text.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Reduce an HTML page to readable text: drops scripts, styles and comments,
+/// breaks lines at block elements, strips the remaining tags, and decodes entities.
+pub fn html_to_text(html: &str) -> String {
+    // ASCII lowercasing keeps byte offsets identical to `html`.
+    let lower = html.to_ascii_lowercase();
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut i = 0;
+    while let Some(offset) = html[i..].find('<') {
+        out.push_str(&decode_entities(&html[i..i + offset]));
+        let start = i + offset;
+        if lower[start..].starts_with("<!--") {
+            i = lower[start..]
+                .find("-->")
+                .map_or(html.len(), |e| start + e + 3);
+            continue;
+        }
+        let Some(end) = html[start..].find('>') else {
+            i = html.len();
+            break;
+        };
+        let tag_end = start + end + 1;
+        let closing = lower[start + 1..].starts_with('/');
+        let name: String = lower[start + 1..tag_end]
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        if !closing && matches!(name.as_str(), "script" | "style" | "noscript" | "template") {
+            let close = format!("</{name}");
+            i = lower[tag_end..]
+                .find(&close)
+                .and_then(|c| html[tag_end + c..].find('>').map(|e| tag_end + c + e + 1))
+                .unwrap_or(html.len());
+            continue;
+        }
+        if BLOCK_TAGS.contains(&name.as_str()) {
+            out.push('\n');
+        }
+        i = tag_end;
+    }
+    out.push_str(&decode_entities(&html[i..]));
+
+    // Collapse whitespace within lines and drop blank lines.
+    let mut text = String::with_capacity(out.len());
+    for line in out.lines() {
+        let mut words = line.split_whitespace();
+        if let Some(first) = words.next() {
+            text.push_str(first);
+            for word in words {
+                text.push(' ');
+                text.push_str(word);
+            }
+            text.push('\n');
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// Decode the common named entities and numeric character references.
+pub(crate) fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest
+            .get(1..12.min(rest.len()))
+            .and_then(|window| window.find(';'))
+            .and_then(|semi| {
+                let entity = &rest[1..semi + 1];
+                let ch = match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" | "#39" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                        .map(|hex| u32::from_str_radix(hex, 16))
+                        .or_else(|| entity.strip_prefix('#').map(str::parse))
+                        .and_then(Result::ok)
+                        .and_then(char::from_u32),
+                };
+                ch.map(|c| (c, semi + 2))
+            });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_url_accepts_only_plain_http_urls() {
+        assert!(validate_url("https://docs.rs/tokio").is_ok());
+        assert!(validate_url("http://example.com:8080/spec.yaml").is_ok());
+        assert!(validate_url("file:///etc/passwd").is_err());
+        assert!(validate_url("ftp://example.com/x").is_err());
+        assert!(validate_url("https://user:pw@example.com/").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+
+    #[test]
+    fn non_public_addresses_are_rejected() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip} should be blocked");
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+        assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn loopback_url_is_not_fetched() {
+        let err = resolve_url_context(&["http://127.0.0.1:9/spec.json".to_string()])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("non-public address"), "{err}");
+    }
+
+    #[test]
+    fn html_to_text_keeps_readable_text() {
+        let html = "<html><head><title>Spec</title><style>p{color:red}</style>\
+                    <script>alert('x<p>')</script></head><body><!-- nav -->\
+                    <h1>API</h1><p>Use <code>GET /v1</code> &amp; retry&nbsp;on &#34;429&#x22;.</p>\
+                    <ul><li>one</li><li>two</li></ul></body></html>";
+        assert_eq!(
+            html_to_text(html),
+            "Spec\nAPI\nUse GET /v1 & retry on \"429\".\none\ntwo"
+        );
+    }
+
+    #[test]
+    fn content_type_allowlist() {
+        assert_eq!(
+            content_kind("text/html; charset=utf-8"),
+            Some(ContentKind::Html)
+        );
+        assert_eq!(content_kind("application/json"), Some(ContentKind::Text));
+        assert_eq!(
+            content_kind("application/vnd.oai.openapi+json"),
+            Some(ContentKind::Text)
+        );
+        assert_eq!(content_kind("text/markdown"), Some(ContentKind::Text));
+        assert_eq!(content_kind("application/octet-stream"), None);
+        assert_eq!(content_kind("image/png"), None);
+    }
+}
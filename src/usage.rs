@@ -0,0 +1,526 @@
+//! Usage ledger: one JSON line per model call in `.squall/usage.jsonl`, tagged
+//! with the MCP client that asked (its `clientInfo.name` from `initialize`,
+//! e.g. `claude-code` or `cursor`) and the tool it called. Tokens and cost
+//! come from the provider's usage report and the model's configured prices.
+//!
+//! `SQUALL_CLIENT` overrides the client name, so a CI job can label its calls
+//! (`SQUALL_CLIENT=ci`) whatever MCP client it drives Squall with.
+//!
+//! The `usage` tool aggregates the ledger over a date window per model, day,
+//! and client (`report`).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dispatch::ratelimit::now_ms;
+use crate::dispatch::{ProviderResult, TokenUsage};
+use crate::error::SquallError;
+use crate::memory::{date_to_days, iso_from_epoch_ms};
+
+/// Ledger written by the server, relative to its working directory.
+pub const USAGE_LEDGER: &str = ".squall/usage.jsonl";
+
+/// Overrides the client name reported at `initialize`.
+pub const CLIENT_ENV: &str = "SQUALL_CLIENT";
+
+/// Client name when the client sent none and `SQUALL_CLIENT` is unset.
+pub const UNKNOWN_CLIENT: &str = "unknown";
+
+/// Days a report covers when the request gives no `since`.
+pub const DEFAULT_WINDOW_DAYS: u64 = 30;
+
+/// Size at which the ledger rolls over to `usage.jsonl.1` (the previous
+/// rollover is dropped).
+const MAX_LEDGER_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Who a call is made for. Requests without one are not recorded.
+#[derive(Debug, Clone)]
+pub struct UsageTag {
+    pub client: Arc<str>,
+    pub tool: &'static str,
+}
+
+/// One model call in the ledger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub ts_ms: u64,
+    pub client: String,
+    pub tool: String,
+    /// Squall model name (config key).
+    pub model: String,
+    pub provider: String,
+    /// "success", "partial", or "error".
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    /// Estimated USD cost. None when the model's prices or usage are unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageRecord {
+    /// Record for a finished call to `model` (on `provider`).
+    pub fn new(
+        tag: &UsageTag,
+        model: &str,
+        provider: &str,
+        result: &Result<ProviderResult, SquallError>,
+    ) -> Self {
+        let (status, usage): (&str, Option<&TokenUsage>) = match result {
+            Ok(r) if r.partial => ("partial", r.usage.as_ref()),
+            Ok(r) => ("success", r.usage.as_ref()),
+            Err(_) => ("error", None),
+        };
+        Self {
+            ts_ms: now_ms(),
+            client: tag.client.to_string(),
+            tool: tag.tool.to_string(),
+            model: model.to_string(),
+            provider: provider.to_string(),
+            status: status.to_string(),
+            input_tokens: usage.map(|u| u.input_tokens),
+            output_tokens: usage.map(|u| u.output_tokens),
+            cost_usd: usage.and_then(|u| u.estimated_cost),
+        }
+    }
+}
+
+/// Client name for the ledger: `SQUALL_CLIENT` if set, else the name the
+/// client reported, else `UNKNOWN_CLIENT`.
+pub fn client_name(reported: Option<&str>) -> String {
+    std::env::var(CLIENT_ENV)
+        .ok()
+        .as_deref()
+        .or(reported)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(UNKNOWN_CLIENT)
+        .chars()
+        .take(64)
+        .collect()
+}
+
+/// Append-only JSONL ledger of model calls.
+#[derive(Debug)]
+pub struct UsageLedger {
+    path: PathBuf,
+    /// Serializes appends and rollovers within the process.
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl UsageLedger {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `record`. Failures are logged; usage accounting never fails a call.
+    pub async fn append(&self, record: &UsageRecord) {
+        use tokio::io::AsyncWriteExt;
+
+        let Ok(mut line) = serde_json::to_string(record) else {
+            return;
+        };
+        line.push('\n');
+        let _guard = self.lock.lock().await;
+        if let Some(dir) = self.path.parent()
+            && let Err(e) = tokio::fs::create_dir_all(dir).await
+        {
+            tracing::warn!("usage ledger: failed to create {}: {e}", dir.display());
+            return;
+        }
+        if let Ok(meta) = tokio::fs::metadata(&self.path).await
+            && meta.len() > MAX_LEDGER_BYTES
+        {
+            let _ = tokio::fs::rename(&self.path, rolled_path(&self.path)).await;
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await;
+        let result = match file {
+            Ok(mut file) => match file.write_all(line.as_bytes()).await {
+                // tokio's File writes in the background; flush before the next read.
+                Ok(()) => file.flush().await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                "usage ledger: failed to append to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+
+    /// Every record in the ledger and its rollover, oldest first. Unparseable
+    /// lines (a torn write) are skipped.
+    pub async fn read(&self) -> Vec<UsageRecord> {
+        let mut records = Vec::new();
+        for path in [rolled_path(&self.path), self.path.clone()] {
+            let Ok(text) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            records.extend(
+                text.lines()
+                    .filter_map(|line| serde_json::from_str::<UsageRecord>(line).ok()),
+            );
+        }
+        records
+    }
+}
+
+/// Window and filter for a usage report. Dates are YYYY-MM-DD, UTC, inclusive.
+#[derive(Debug, Default)]
+pub struct UsageQuery {
+    /// Default: `DEFAULT_WINDOW_DAYS` days up to `until`.
+    pub since: Option<String>,
+    /// Default: today.
+    pub until: Option<String>,
+    /// Only calls from this client.
+    pub client: Option<String>,
+}
+
+impl UsageQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, date) in [("since", &self.since), ("until", &self.until)] {
+            if let Some(date) = date
+                && (date.len() != 10 || date_to_days(date).is_none())
+            {
+                return Err(format!("{name} must be a YYYY-MM-DD date, got '{date}'"));
+            }
+        }
+        if let (Some(since), Some(until)) = (&self.since, &self.until)
+            && since > until
+        {
+            return Err(format!("since ({since}) is after until ({until})"));
+        }
+        Ok(())
+    }
+
+    /// First and last day of the window, given today's date.
+    fn window(&self, today: &str) -> (String, String) {
+        let until = self.until.clone().unwrap_or_else(|| today.to_string());
+        let since = self.since.clone().unwrap_or_else(|| {
+            let last = date_to_days(&until).unwrap_or(0);
+            day_string(last.saturating_sub(DEFAULT_WINDOW_DAYS - 1))
+        });
+        (since, until)
+    }
+}
+
+/// Sums over a set of ledger records.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageTotals {
+    pub calls: u64,
+    /// Calls that returned an error.
+    pub errors: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Estimated USD over the calls that have an estimate.
+    pub cost_usd: f64,
+    /// Calls without a cost estimate (unknown prices, or no usage reported).
+    pub unpriced_calls: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.calls += 1;
+        if record.status == "error" {
+            self.errors += 1;
+        }
+        self.input_tokens += record.input_tokens.unwrap_or(0);
+        self.output_tokens += record.output_tokens.unwrap_or(0);
+        match record.cost_usd {
+            Some(cost) => self.cost_usd += cost,
+            None => self.unpriced_calls += 1,
+        }
+    }
+}
+
+/// Totals for one model, day, or client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageRow {
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Ledger records over a window, broken down per model, day, and client.
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub since: String,
+    pub until: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    pub total: UsageTotals,
+    /// Most expensive first.
+    pub by_model: Vec<UsageRow>,
+    /// Oldest first; days without calls are left out.
+    pub by_day: Vec<UsageRow>,
+    /// Most expensive first.
+    pub by_client: Vec<UsageRow>,
+}
+
+/// Aggregate `records` over `query`'s window, which ends today (`today`,
+/// YYYY-MM-DD) unless it says otherwise.
+pub fn report(records: &[UsageRecord], query: &UsageQuery, today: &str) -> UsageReport {
+    let (since, until) = query.window(today);
+    let mut total = UsageTotals::default();
+    let mut by_model: HashMap<&str, UsageTotals> = HashMap::new();
+    let mut by_day: HashMap<String, UsageTotals> = HashMap::new();
+    let mut by_client: HashMap<&str, UsageTotals> = HashMap::new();
+    for record in records {
+        let day = day_of(record.ts_ms);
+        if day < since || day > until || query.client.as_ref().is_some_and(|c| *c != record.client)
+        {
+            continue;
+        }
+        total.add(record);
+        by_model
+            .entry(record.model.as_str())
+            .or_default()
+            .add(record);
+        by_client
+            .entry(record.client.as_str())
+            .or_default()
+            .add(record);
+        by_day.entry(day).or_default().add(record);
+    }
+
+    let rows = |map: HashMap<&str, UsageTotals>| {
+        let mut rows: Vec<UsageRow> = map
+            .into_iter()
+            .map(|(key, totals)| UsageRow {
+                key: key.to_string(),
+                totals,
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.totals
+                .cost_usd
+                .total_cmp(&a.totals.cost_usd)
+                .then(b.totals.calls.cmp(&a.totals.calls))
+                .then(a.key.cmp(&b.key))
+        });
+        rows
+    };
+    let mut by_day: Vec<UsageRow> = by_day
+        .into_iter()
+        .map(|(key, totals)| UsageRow { key, totals })
+        .collect();
+    by_day.sort_by(|a, b| a.key.cmp(&b.key));
+
+    UsageReport {
+        since,
+        until,
+        client: query.client.clone(),
+        total,
+        by_model: rows(by_model),
+        by_day,
+        by_client: rows(by_client),
+    }
+}
+
+impl UsageReport {
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## Usage: {} to {}", self.since, self.until);
+        if let Some(client) = &self.client {
+            out.push_str(&format!(" (client `{client}`)"));
+        }
+        out.push_str("\n\n");
+        let total = &self.total;
+        if total.calls == 0 {
+            out.push_str("No model calls recorded in this window.\n");
+            return out;
+        }
+        out.push_str(&format!(
+            "{} call(s), {} failed. {} input / {} output tokens. Estimated cost ${:.4}",
+            total.calls, total.errors, total.input_tokens, total.output_tokens, total.cost_usd
+        ));
+        if total.unpriced_calls > 0 {
+            out.push_str(&format!(
+                " ({} call(s) without an estimate)",
+                total.unpriced_calls
+            ));
+        }
+        out.push_str(".\n");
+        for (title, label, rows) in [
+            ("By model", "Model", &self.by_model),
+            ("By day", "Day", &self.by_day),
+            ("By client", "Client", &self.by_client),
+        ] {
+            out.push_str(&format!(
+                "\n### {title}\n\n| {label} | Calls | Failed | Input tokens | Output tokens | Cost (USD) |\n\
+                 |---|---|---|---|---|---|\n"
+            ));
+            for row in rows {
+                let t = &row.totals;
+                let cost = if t.unpriced_calls == t.calls {
+                    "\u{2014}".to_string()
+                } else {
+                    format!("{:.4}", t.cost_usd)
+                };
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {cost} |\n",
+                    row.key, t.calls, t.errors, t.input_tokens, t.output_tokens
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// YYYY-MM-DD (UTC) of an epoch-ms timestamp.
+fn day_of(ts_ms: u64) -> String {
+    iso_from_epoch_ms(ts_ms as i64)[..10].to_string()
+}
+
+/// YYYY-MM-DD of a day count since the epoch.
+fn day_string(days: u64) -> String {
+    day_of(days * 86_400_000)
+}
+
+fn rolled_path(path: &Path) -> PathBuf {
+    let mut rolled = path.as_os_str().to_owned();
+    rolled.push(".1");
+    PathBuf::from(rolled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ledger_round_trips_records() {
+        let dir = std::env::temp_dir().join(format!("squall_usage_test_{}", std::process::id()));
+        let ledger = UsageLedger::new(dir.join("usage.jsonl"));
+        let tag = UsageTag {
+            client: "claude-code".into(),
+            tool: "review",
+        };
+        let ok = Ok(ProviderResult {
+            text: "fine".into(),
+            model: "grok-4".into(),
+            provider: "xai".into(),
+            partial: false,
+            output_capped: false,
+            timing: Default::default(),
+            usage: Some(TokenUsage {
+                input_tokens: 1200,
+                output_tokens: 300,
+                estimated_cost: Some(0.0021),
+            }),
+            provider_metadata: Default::default(),
+            secrets_redacted: Vec::new(),
+        });
+        let failed = Err(SquallError::Timeout(30_000));
+        ledger
+            .append(&UsageRecord::new(&tag, "grok", "xai", &ok))
+            .await;
+        ledger
+            .append(&UsageRecord::new(&tag, "grok", "xai", &failed))
+            .await;
+        tokio::fs::write(rolled_path(ledger.path()), "not json\n")
+            .await
+            .unwrap();
+
+        let records = ledger.read().await;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].client, "claude-code");
+        assert_eq!(records[0].tool, "review");
+        assert_eq!(records[0].input_tokens, Some(1200));
+        assert_eq!(records[0].cost_usd, Some(0.0021));
+        assert_eq!(records[1].status, "error");
+        assert_eq!(records[1].cost_usd, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn client_name_falls_back_to_unknown() {
+        // SQUALL_CLIENT is never set by the test harness.
+        assert_eq!(client_name(Some(" cursor ")), "cursor");
+        assert_eq!(client_name(Some("")), UNKNOWN_CLIENT);
+        assert_eq!(client_name(None), UNKNOWN_CLIENT);
+    }
+
+    fn record(day: &str, client: &str, model: &str, cost: Option<f64>) -> UsageRecord {
+        UsageRecord {
+            ts_ms: date_to_days(day).unwrap() * 86_400_000 + 3_600_000,
+            client: client.to_string(),
+            tool: "review".to_string(),
+            model: model.to_string(),
+            provider: "xai".to_string(),
+            status: if cost.is_some() { "success" } else { "error" }.to_string(),
+            input_tokens: cost.map(|_| 1000),
+            output_tokens: cost.map(|_| 100),
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn report_aggregates_window_per_model_day_and_client() {
+        let records = vec![
+            record("2026-08-01", "cursor", "grok", Some(5.0)),
+            record("2026-10-01", "claude-code", "grok", Some(0.25)),
+            record("2026-10-01", "cursor", "kimi", Some(0.5)),
+            record("2026-10-15", "ci", "grok", None),
+        ];
+        let query = UsageQuery::default();
+        let all = report(&records, &query, "2026-10-16");
+
+        assert_eq!(all.since, "2026-09-17");
+        assert_eq!(all.until, "2026-10-16");
+        assert_eq!(all.total.calls, 3);
+        assert_eq!(all.total.errors, 1);
+        assert_eq!(all.total.unpriced_calls, 1);
+        assert_eq!(all.total.input_tokens, 2000);
+        assert_eq!(all.by_model[0].key, "kimi");
+        assert_eq!(all.by_model[1].key, "grok");
+        assert_eq!(all.by_model[1].totals.calls, 2);
+        let days: Vec<&str> = all.by_day.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(days, ["2026-10-01", "2026-10-15"]);
+        assert_eq!(all.by_client.len(), 3);
+        assert!(
+            all.to_markdown()
+                .contains("| ci | 1 | 1 | 0 | 0 | \u{2014} |")
+        );
+
+        let query = UsageQuery {
+            since: Some("2026-08-01".into()),
+            client: Some("cursor".into()),
+            ..Default::default()
+        };
+        let cursor = report(&records, &query, "2026-10-16");
+        assert_eq!(cursor.total.calls, 2);
+        assert_eq!(cursor.total.cost_usd, 5.5);
+    }
+
+    #[test]
+    fn query_rejects_bad_dates() {
+        let bad = UsageQuery {
+            since: Some("2026-10-1".into()),
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        let reversed = UsageQuery {
+            since: Some("2026-10-02".into()),
+            until: Some("2026-10-01".into()),
+            ..Default::default()
+        };
+        assert!(reversed.validate().is_err());
+    }
+}
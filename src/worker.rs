@@ -0,0 +1,302 @@
+//! Worker mode (`squall worker`): serve this instance's registry to remote
+//! coordinators over HTTP, so API keys and CLIs can stay on one host (e.g. a
+//! bastion) while developers run Squall without credentials.
+//!
+//! The protocol is one JSON request per connection (`POST /v1/dispatch`, bearer
+//! token auth) — see `crate::dispatch::remote` for the wire types. Plain HTTP:
+//! expose a worker beyond loopback only behind a TLS proxy or an SSH tunnel.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+
+use crate::dispatch::ProviderRequest;
+use crate::dispatch::registry::{BackendConfig, Registry};
+use crate::dispatch::remote::{DISPATCH_PATH, RemoteRequest, RemoteResponse};
+use crate::error::SquallError;
+
+/// Default listen address for `squall worker`.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7420";
+
+/// Max simultaneous connections. Backend semaphores bound actual dispatch.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Max size of the request line plus headers.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Max request body (prompt + file context, JSON-escaped).
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// Time allowed to receive the full request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Upper bound on a forwarded deadline (async-poll deep research runs up to an hour).
+const MAX_DEADLINE: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Accept connections until the listener fails. `token` is the shared bearer token.
+pub async fn serve(listener: TcpListener, registry: Arc<Registry>, token: String) {
+    let token: Arc<str> = token.into();
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("worker: accept failed: {e}");
+                continue;
+            }
+        };
+        let Ok(permit) = connections.clone().try_acquire_owned() else {
+            tracing::warn!("worker: connection limit reached, dropping {peer}");
+            continue;
+        };
+        let registry = registry.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            if let Err(e) = handle_connection(socket, &registry, &token).await {
+                tracing::debug!("worker: connection from {peer}: {e}");
+            }
+        });
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    content_length: Option<usize>,
+    authorization: Option<String>,
+}
+
+async fn handle_connection(
+    mut socket: TcpStream,
+    registry: &Registry,
+    token: &str,
+) -> std::io::Result<()> {
+    let (head, mut body) = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut socket)).await {
+        Ok(Ok(Some(parsed))) => parsed,
+        Ok(Ok(None)) => return respond(&mut socket, 400, "{\"error\":\"bad request\"}").await,
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return respond(&mut socket, 408, "{\"error\":\"request timeout\"}").await,
+    };
+
+    match (head.method.as_str(), head.path.as_str()) {
+        ("GET", "/healthz") => respond(&mut socket, 200, "{\"status\":\"ok\"}").await,
+        ("POST", DISPATCH_PATH) => {
+            let authorized = head
+                .authorization
+                .as_deref()
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .is_some_and(|presented| tokens_match(presented.trim(), token))
+                && !token.is_empty();
+            if !authorized {
+                return respond(&mut socket, 401, "{\"error\":\"unauthorized\"}").await;
+            }
+            let Some(len) = head.content_length else {
+                return respond(&mut socket, 411, "{\"error\":\"length required\"}").await;
+            };
+            if len > MAX_BODY_BYTES {
+                return respond(&mut socket, 413, "{\"error\":\"request too large\"}").await;
+            }
+            if body.len() < len {
+                let mut rest = vec![0u8; len - body.len()];
+                match tokio::time::timeout(READ_TIMEOUT, socket.read_exact(&mut rest)).await {
+                    Ok(Ok(_)) => body.extend_from_slice(&rest),
+                    Ok(Err(e)) => return Err(e),
+                    Err(_) => {
+                        return respond(&mut socket, 408, "{\"error\":\"request timeout\"}").await;
+                    }
+                }
+            }
+            body.truncate(len);
+            let request = match serde_json::from_slice::<RemoteRequest>(&body) {
+                Ok(r) => r,
+                Err(_) => {
+                    return respond(&mut socket, 400, "{\"error\":\"invalid dispatch request\"}")
+                        .await;
+                }
+            };
+
+            let model = request.model.clone();
+            let start = Instant::now();
+            let (mut reader, mut writer) = socket.split();
+            // The coordinator closes the connection on cancellation; drop the query
+            // (and its upstream call) rather than finish work nobody will read.
+            let outcome = tokio::select! {
+                r = dispatch(registry, request) => r,
+                _ = closed(&mut reader) => {
+                    tracing::info!(model = %model, "worker: coordinator went away, dispatch dropped");
+                    return Ok(());
+                }
+            };
+            tracing::info!(
+                model = %model,
+                ok = outcome.is_ok(),
+                elapsed_ms = start.elapsed().as_millis() as u64,
+                "worker: dispatch complete"
+            );
+            let response = match outcome {
+                Ok(result) => RemoteResponse::Success {
+                    text: result.text,
+                    provider: result.provider,
+                    partial: result.partial,
+                    output_capped: result.output_capped,
+                    timing: result.timing,
+                    usage: result.usage,
+                    provider_metadata: result.provider_metadata,
+                },
+                Err(e) => RemoteResponse::from_error(&e),
+            };
+            let json = serde_json::to_string(&response).map_err(std::io::Error::other)?;
+            respond(&mut writer, 200, &json).await
+        }
+        _ => respond(&mut socket, 404, "{\"error\":\"not found\"}").await,
+    }
+}
+
+/// Run a forwarded request against the local registry.
+async fn dispatch(
+    registry: &Registry,
+    request: RemoteRequest,
+) -> Result<crate::dispatch::ProviderResult, SquallError> {
+    // A worker only serves models it can run itself; forwarding again could loop.
+    if registry
+        .get(&request.model)
+        .is_some_and(|e| matches!(e.backend, BackendConfig::Remote { .. }))
+    {
+        return Err(SquallError::Other(format!(
+            "{} is routed to another worker on this host",
+            request.model
+        )));
+    }
+    let timeout = Duration::from_millis(request.timeout_ms).min(MAX_DEADLINE);
+    let req = ProviderRequest {
+        prompt: request.prompt.into(),
+        model: request.model,
+        deadline: Instant::now() + timeout,
+        // Paths on the coordinator's machine mean nothing here.
+        working_directory: None,
+        system_prompt: request.system_prompt,
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        reasoning_effort: request.reasoning_effort,
+        cancellation_token: None,
+        stall_timeout: request.stall_timeout_ms.map(Duration::from_millis),
+        max_output_bytes: request.max_output_bytes,
+        priority: None,
+        extra_body: request.extra_body,
+        usage: None,
+    };
+    registry.query(&req).await
+}
+
+/// Read until the end of headers. Returns the parsed head and any body bytes
+/// already received, or None for a malformed or oversized head.
+async fn read_head(socket: &mut TcpStream) -> std::io::Result<Option<(RequestHead, Vec<u8>)>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    let end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+        let n = socket.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let Ok(head) = std::str::from_utf8(&buf[..end]) else {
+        return Ok(None);
+    };
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split(' ');
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let mut parsed = RequestHead {
+        method: method.to_string(),
+        path: path.to_string(),
+        content_length: None,
+        authorization: None,
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            match value.parse() {
+                Ok(len) => parsed.content_length = Some(len),
+                Err(_) => return Ok(None),
+            }
+        } else if name.eq_ignore_ascii_case("authorization") {
+            parsed.authorization = Some(value.to_string());
+        }
+    }
+    Ok(Some((parsed, buf[end + 4..].to_vec())))
+}
+
+/// Resolves when the peer closes its side of the connection.
+async fn closed<R: AsyncRead + Unpin>(reader: &mut R) {
+    let mut sink = [0u8; 512];
+    while let Ok(n) = reader.read(&mut sink).await {
+        if n == 0 {
+            return;
+        }
+    }
+}
+
+async fn respond<W: tokio::io::AsyncWrite + Unpin>(
+    writer: &mut W,
+    status: u16,
+    body: &str,
+) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.flush().await
+}
+
+/// Constant-time token comparison (length is not secret).
+pub(crate) fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_match_requires_exact_token() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cres", "s3cret"));
+        assert!(!tokens_match("s3cre", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+}
@@ -0,0 +1,329 @@
+//! Multi-repo reviews: one review spanning several repositories (a service and
+//! the shared library it uses). `working_directory` is then a list of repository
+//! roots, or a directory whose `.squall/workspace.toml` lists them:
+//!
+//! ```toml
+//! repos = ["../api", "../shared-lib"]
+//! ```
+//!
+//! Each repository is named after its directory. File paths, in `file_paths`,
+//! the prompt, and therefore in findings, are namespaced `{repo}/{path}`; each
+//! repository's `.squallignore` applies to its own files, and its git context
+//! is collected and shown to the models.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::{self, ContextFormat, FileContextResult, GitContext};
+use crate::error::SquallError;
+
+/// Manifest listing a workspace's repositories, relative to the directory
+/// holding `.squall/`.
+pub const WORKSPACE_MANIFEST: &str = ".squall/workspace.toml";
+
+/// Repositories one review can span.
+pub const MAX_WORKSPACE_REPOS: usize = 8;
+
+/// One repository of a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    /// Directory name; the prefix of this repository's paths.
+    pub name: String,
+    pub root: PathBuf,
+}
+
+/// A repository's git context, as reported in `ReviewResponse::repos`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoContext {
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitContext>,
+}
+
+/// The repositories a review reads from. A single repository takes paths as
+/// they are; with several, every path starts with a repository name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    repos: Vec<Repo>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    repos: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// A workspace of one repository, with paths relative to `root`.
+    pub fn single(root: PathBuf) -> Self {
+        Self {
+            repos: vec![Repo {
+                name: repo_name(&root),
+                root,
+            }],
+        }
+    }
+
+    /// A workspace of validated repository roots. Fails if there are none, too
+    /// many, or two share a directory name.
+    pub fn new(roots: Vec<PathBuf>) -> Result<Self, String> {
+        if roots.is_empty() {
+            return Err("working_directory lists no repositories".to_string());
+        }
+        if roots.len() > MAX_WORKSPACE_REPOS {
+            return Err(format!(
+                "too many repositories: {} (max {MAX_WORKSPACE_REPOS})",
+                roots.len()
+            ));
+        }
+        let mut repos: Vec<Repo> = Vec::with_capacity(roots.len());
+        for root in roots {
+            let name = repo_name(&root);
+            if let Some(other) = repos.iter().find(|r| r.name == name) {
+                if other.root == root {
+                    continue;
+                }
+                return Err(format!(
+                    "repositories {} and {} are both named '{name}'",
+                    other.root.display(),
+                    root.display()
+                ));
+            }
+            repos.push(Repo { name, root });
+        }
+        Ok(Self { repos })
+    }
+
+    pub fn repos(&self) -> &[Repo] {
+        &self.repos
+    }
+
+    /// The first repository: persistence, memory and incremental state follow it.
+    pub fn primary(&self) -> &Path {
+        &self.repos[0].root
+    }
+
+    pub fn is_multi(&self) -> bool {
+        self.repos.len() > 1
+    }
+
+    /// The repository a path belongs to and the path within it. Single-repo
+    /// paths are taken as they are; multi-repo paths must start with a
+    /// repository name.
+    pub fn locate<'a>(&self, path: &'a str) -> Option<(&Repo, &'a str)> {
+        if !self.is_multi() {
+            return Some((&self.repos[0], path));
+        }
+        let (name, rest) = path.trim_start_matches("./").split_once('/')?;
+        let repo = self.repos.iter().find(|r| r.name == name)?;
+        Some((repo, rest))
+    }
+
+    /// File context for `paths`, sharing `budget` across repositories in
+    /// request order. Multi-repo paths that name no repository are reported in
+    /// `errors`.
+    pub async fn resolve_file_context(
+        &self,
+        paths: &[String],
+        budget: usize,
+        format: ContextFormat,
+        trim: bool,
+    ) -> Result<FileContextResult, SquallError> {
+        if !self.is_multi() {
+            let root = self.primary();
+            return if trim {
+                context::resolve_file_context_trimmed(paths, root, budget, format).await
+            } else {
+                context::resolve_file_context(paths, root, budget, format).await
+            };
+        }
+
+        let mut merged = FileContextResult::default();
+        let mut per_repo: Vec<(&Repo, Vec<String>)> = Vec::new();
+        for path in paths {
+            let Some((repo, rel)) = self.locate(path) else {
+                merged.errors.push(format!(
+                    "{path}: does not start with a repository name ({})",
+                    self.names().join(", ")
+                ));
+                continue;
+            };
+            match per_repo.iter_mut().find(|(r, _)| r.name == repo.name) {
+                Some((_, rels)) => rels.push(rel.to_string()),
+                None => per_repo.push((repo, vec![rel.to_string()])),
+            }
+        }
+
+        let mut context_text = String::new();
+        for (repo, rels) in per_repo {
+            let remaining = budget.saturating_sub(context_text.len());
+            let result = context::resolve_repo_file_context(
+                &rels, &repo.root, &repo.name, remaining, format, trim,
+            )
+            .await?;
+            if let Some(ctx) = result.context {
+                context_text.push_str(&ctx);
+            }
+            merged.skipped.extend(result.skipped);
+            merged.errors.extend(result.errors);
+            merged.ignored.extend(result.ignored);
+            merged.included.extend(result.included);
+            merged.trimmed.extend(result.trimmed);
+            merged
+                .injection_suspected
+                .extend(result.injection_suspected);
+        }
+        merged.context = (!context_text.is_empty()).then_some(context_text);
+        Ok(merged)
+    }
+
+    /// Drop diff sections for files ignored by their repository's
+    /// `.squallignore`. Returns the filtered diff and the dropped paths.
+    pub fn filter_ignored_diff(&self, diff: &str) -> (String, Vec<String>) {
+        let ignores: Vec<_> = self
+            .repos
+            .iter()
+            .map(|r| context::load_squallignore(&r.root))
+            .collect();
+        context::filter_diff_sections(diff, |path| {
+            let Some((repo, rel)) = self.locate(path) else {
+                return true;
+            };
+            let i = self.repos.iter().position(|r| r == repo).unwrap_or(0);
+            !ignores[i]
+                .as_ref()
+                .is_some_and(|ig| context::is_squallignored(ig, rel))
+        })
+    }
+
+    /// Git context of every repository, in workspace order.
+    pub async fn git_contexts(&self) -> Vec<RepoContext> {
+        let mut contexts = Vec::with_capacity(self.repos.len());
+        for repo in &self.repos {
+            contexts.push(RepoContext {
+                name: repo.name.clone(),
+                path: repo.root.to_string_lossy().to_string(),
+                git: context::detect_git_context(&repo.root).await,
+            });
+        }
+        contexts
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.repos.iter().map(|r| r.name.as_str()).collect()
+    }
+}
+
+/// The `<workspace>` block telling models which repositories the namespaced
+/// paths belong to.
+pub fn prompt_header(repos: &[RepoContext]) -> String {
+    let mut out = String::from(
+        "<workspace note=\"this review spans several repositories; file paths start with the repository name\">\n",
+    );
+    for repo in repos {
+        let attr = |value: &str| context::escape_xml_attr(value);
+        out.push_str(&format!("<repo name=\"{}\"", attr(&repo.name)));
+        if let Some(ref git) = repo.git {
+            if let Some(ref branch) = git.branch {
+                out.push_str(&format!(" branch=\"{}\"", attr(branch)));
+            }
+            if let Some(ref sha) = git.commit_sha {
+                out.push_str(&format!(" commit=\"{}\"", attr(sha)));
+            }
+        }
+        out.push_str("/>\n");
+    }
+    out.push_str("</workspace>");
+    out
+}
+
+/// Repository roots listed by `dir`'s workspace manifest, joined onto `dir`
+/// (not yet validated). None when `dir` has no manifest.
+pub async fn read_manifest(dir: &Path) -> Result<Option<Vec<PathBuf>>, String> {
+    let path = dir.join(WORKSPACE_MANIFEST);
+    let raw = match tokio::fs::read_to_string(&path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("cannot read {WORKSPACE_MANIFEST}: {e}")),
+    };
+    let manifest: Manifest =
+        toml::from_str(&raw).map_err(|e| format!("invalid {WORKSPACE_MANIFEST}: {e}"))?;
+    Ok(Some(manifest.repos.iter().map(|r| dir.join(r)).collect()))
+}
+
+fn repo_name(root: &Path) -> String {
+    root.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "root".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_repo_paths_are_namespaced_by_directory_name() {
+        let ws = Workspace::new(vec!["/work/api".into(), "/work/shared-lib".into()]).unwrap();
+        assert!(ws.is_multi());
+        let (repo, rel) = ws.locate("shared-lib/src/pool.rs").unwrap();
+        assert_eq!((repo.name.as_str(), rel), ("shared-lib", "src/pool.rs"));
+        assert!(ws.locate("web/index.ts").is_none());
+        assert!(ws.locate("README.md").is_none());
+
+        let single = Workspace::single("/work/api".into());
+        assert_eq!(single.locate("src/main.rs").unwrap().1, "src/main.rs");
+
+        let err = Workspace::new(vec!["/a/lib".into(), "/b/lib".into()]).unwrap_err();
+        assert!(err.contains("both named 'lib'"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn file_context_spans_repositories() {
+        let base = std::env::temp_dir().join(format!("squall_workspace_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        for (repo, file) in [("api", "main.rs"), ("shared", "pool.rs")] {
+            std::fs::create_dir_all(base.join(repo)).unwrap();
+            std::fs::write(base.join(repo).join(file), format!("// {repo}\n")).unwrap();
+        }
+        std::fs::write(base.join("shared/.squallignore"), "secret.rs\n").unwrap();
+        std::fs::create_dir_all(base.join("hub/.squall")).unwrap();
+        std::fs::write(
+            base.join("hub").join(WORKSPACE_MANIFEST),
+            "repos = [\"../api\", \"../shared\"]\n",
+        )
+        .unwrap();
+
+        let roots = read_manifest(&base.join("hub")).await.unwrap().unwrap();
+        let ws = Workspace::new(roots.iter().map(|r| r.canonicalize().unwrap()).collect()).unwrap();
+        let paths: Vec<String> = [
+            "api/main.rs",
+            "shared/pool.rs",
+            "shared/secret.rs",
+            "web/x.ts",
+        ]
+        .map(String::from)
+        .to_vec();
+        let result = ws
+            .resolve_file_context(&paths, 64 * 1024, ContextFormat::Xml, false)
+            .await
+            .unwrap();
+        assert_eq!(result.included, vec!["api/main.rs", "shared/pool.rs"]);
+        assert_eq!(result.ignored, vec!["shared/secret.rs"]);
+        assert!(
+            result.errors[0].starts_with("web/x.ts:"),
+            "{:?}",
+            result.errors
+        );
+        let ctx = result.context.unwrap();
+        assert!(ctx.contains("<file path=\"shared/pool.rs\">"), "{ctx}");
+
+        let diff = "diff --git a/shared/secret.rs b/shared/secret.rs\n+x\n\
+                    diff --git a/api/main.rs b/api/main.rs\n+y\n";
+        let (filtered, dropped) = ws.filter_ignored_diff(diff);
+        assert_eq!(dropped, vec!["shared/secret.rs"]);
+        assert!(filtered.contains("api/main.rs"));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -537,6 +537,10 @@ fn make_request(
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     }
 }
 
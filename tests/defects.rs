@@ -53,6 +53,16 @@ fn p0_1_server_version_matches_cargo() {
     );
 }
 
+#[test]
+fn server_instructions_include_configured_house_rules() {
+    let mut config = Config::default();
+    config.server.extra_instructions = Some("Security reviews include gemini.".to_string());
+    let info = SquallServer::new(config).get_info();
+    let instructions = info.instructions.expect("instructions set");
+    assert!(instructions.starts_with("Squall: parallel AI model dispatch."));
+    assert!(instructions.ends_with("\n\nSecurity reviews include gemini."));
+}
+
 // ---------------------------------------------------------------------------
 // P0-2: Error responses must NOT set isError=true at MCP level.
 // Claude Code cascades sibling tool call failures when is_error=true.
@@ -68,6 +78,7 @@ fn p0_2_error_response_sets_is_error_true() {
             model_used: "bad-model".to_string(),
             provider_used: "unknown".to_string(),
             duration_seconds: 0.0,
+            usage: None,
         },
     );
     let result = response.into_call_tool_result();
@@ -86,6 +97,7 @@ fn p0_2_success_response_sets_is_error_false() {
             model_used: "grok".to_string(),
             provider_used: "xai".to_string(),
             duration_seconds: 1.0,
+            usage: None,
         },
     );
     let result = response.into_call_tool_result();
@@ -109,11 +121,8 @@ fn p0_3_model_entry_debug_redacts_api_key() {
             api_key: "sk-super-secret-key-12345".to_string(),
             api_format: ApiFormat::OpenAi,
         },
-        description: String::new(),
-        strengths: vec![],
-        weaknesses: vec![],
         speed_tier: "fast".to_string(),
-        precision_tier: "medium".to_string(),
+        ..Default::default()
     };
     let debug_output = format!("{:?}", entry);
     assert!(
@@ -138,6 +147,7 @@ fn p1_4_into_content_nan_does_not_panic() {
             model_used: "test".to_string(),
             provider_used: "test".to_string(),
             duration_seconds: f64::NAN,
+            usage: None,
         },
     );
     let result = catch_unwind(AssertUnwindSafe(|| response.into_call_tool_result()));
@@ -156,6 +166,7 @@ fn p1_4_into_content_infinity_does_not_panic() {
             model_used: "test".to_string(),
             provider_used: "test".to_string(),
             duration_seconds: f64::INFINITY,
+            usage: None,
         },
     );
     let result = catch_unwind(AssertUnwindSafe(|| response.into_call_tool_result()));
@@ -227,6 +238,9 @@ fn p1_8_empty_model_string_uses_default() {
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
     };
     assert_eq!(
         req.model_or_default(),
@@ -247,6 +261,9 @@ fn p1_8_whitespace_model_string_uses_default() {
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
     };
     assert_eq!(
         req.model_or_default(),
@@ -332,6 +349,11 @@ fn p0_3_collect_result_success_uses_display_name() {
         model: "deepseek-reasoner".to_string(), // provider model_id (substituted)
         provider: "deepseek".to_string(),
         partial: false,
+        output_capped: false,
+        timing: Default::default(),
+        usage: None,
+        secrets_redacted: Vec::new(),
+        provider_metadata: Default::default(),
     };
     let result = collect_result(
         Ok(provider_result),
@@ -387,6 +409,9 @@ fn p1_4_chat_request_has_working_directory_field() {
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
     };
     // The field exists and is Some — this is the input side.
     // The bug is that server.rs:101 ignores it. After fix, this test
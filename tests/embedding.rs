@@ -0,0 +1,173 @@
+//! Tests for the library-facing review API: `ReviewExecutor::builder()`,
+//! `ReviewOptions` with quorum early return, and `execute_streaming`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use squall::config::Config;
+use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry, Registry};
+use squall::memory::MemoryStore;
+use squall::review::{ReviewExecutor, ReviewOptions};
+use squall::tools::review::{ModelStatus, ReviewRequest};
+
+fn entry(port: u16) -> ModelEntry {
+    ModelEntry {
+        model_id: "test".to_string(),
+        provider: "test".to_string(),
+        backend: BackendConfig::Http {
+            base_url: format!("http://127.0.0.1:{port}/v1/chat/completions"),
+            api_key: "key".to_string(),
+            api_format: ApiFormat::OpenAi,
+        },
+        speed_tier: "fast".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Mock provider: answers with a short SSE stream, or accepts and never answers.
+async fn mock_provider(answer: bool) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                if !answer {
+                    tokio::time::sleep(Duration::from_secs(120)).await;
+                    return;
+                }
+                let _ = socket
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\n\
+                          Content-Type: text/event-stream\r\n\
+                          Connection: close\r\n\r\n\
+                          data: {\"choices\":[{\"delta\":{\"content\":\"No issues found.\"}}]}\n\n\
+                          data: [DONE]\n\n",
+                    )
+                    .await;
+            });
+        }
+    });
+    port
+}
+
+/// Per-test scratch directory, so persisted reviews stay out of the repository.
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("squall-test-{test}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+async fn registry_with(models: &[(&str, bool)]) -> Arc<Registry> {
+    let mut entries = HashMap::new();
+    for (name, answer) in models {
+        entries.insert(name.to_string(), entry(mock_provider(*answer).await));
+    }
+    Arc::new(Registry::from_config(Config {
+        models: entries,
+        ..Default::default()
+    }))
+}
+
+#[test]
+fn builder_requires_registry_and_positive_quorum() {
+    assert!(ReviewExecutor::builder().build().is_err());
+    let registry = Arc::new(Registry::from_config(Config::default()));
+    assert!(
+        ReviewExecutor::builder()
+            .registry(registry.clone())
+            .quorum(0)
+            .build()
+            .is_err()
+    );
+    assert!(
+        ReviewExecutor::builder()
+            .registry(registry)
+            .quorum(1)
+            .build()
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn quorum_returns_without_waiting_for_stragglers() {
+    let registry = registry_with(&[("fast", true), ("hung", false)]).await;
+    let executor = ReviewExecutor::builder()
+        .registry(registry)
+        .quorum(1)
+        .build()
+        .unwrap();
+
+    let mut options = ReviewOptions::new("Review this.");
+    options.models = vec!["fast".to_string(), "hung".to_string()];
+    options.timeout = Some(Duration::from_secs(60));
+    let dir = scratch_dir("quorum");
+    options.working_directory = Some(dir.clone());
+    let start = Instant::now();
+    let response = executor.run(options).await.unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(20));
+    let by_model: HashMap<&str, _> = response
+        .results
+        .iter()
+        .map(|r| (r.model.as_str(), r))
+        .collect();
+    assert_eq!(by_model["fast"].status, ModelStatus::Success);
+    assert_eq!(by_model["hung"].reason.as_deref(), Some("quorum"));
+    assert_eq!(response.summary.models_succeeded, 1);
+    assert_eq!(response.summary.models_failed, 0);
+    let persisted = PathBuf::from(response.results_file.expect("persisted"));
+    assert!(
+        persisted.starts_with(dir.join(".squall/reviews")),
+        "{persisted:?}"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn streaming_yields_completions_before_cutoffs() {
+    let registry = registry_with(&[("fast", true), ("hung", false)]).await;
+    let executor = ReviewExecutor::builder()
+        .registry(registry)
+        .build()
+        .unwrap()
+        .with_reviews_dir(scratch_dir("streaming"));
+    let req: ReviewRequest = serde_json::from_value(serde_json::json!({
+        "prompt": "Review this.",
+        "models": ["fast", "hung"],
+        "timeout_secs": 2,
+    }))
+    .unwrap();
+    let memory = MemoryStore::with_base_dir(
+        std::env::temp_dir().join(format!("squall-test-streaming-{}", std::process::id())),
+    );
+
+    let mut stream =
+        executor.execute_streaming(&req, req.prompt.clone(), &memory, None, None, None, None);
+    let mut landed = Vec::new();
+    while let Some(result) = stream.next().await {
+        landed.push((result.model, result.reason));
+    }
+    assert_eq!(
+        landed,
+        vec![
+            ("fast".to_string(), None),
+            ("hung".to_string(), Some("cutoff".to_string())),
+        ]
+    );
+    let response = stream.into_response().unwrap();
+    assert_eq!(response.results.len(), 2);
+    let results_file = response.results_file.expect("persisted");
+    assert!(
+        results_file.contains(&response.review_id),
+        "{results_file} should be named after review {}",
+        response.review_id
+    );
+}
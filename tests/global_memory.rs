@@ -18,6 +18,8 @@ use std::path::PathBuf;
 use squall::memory::MemoryStore;
 use squall::memory::global::GlobalWriter;
 use squall::memory::schema::{self, CURRENT_VERSION, ModelEvent};
+use squall::memory::sync::GlobalSync;
+use squall::tools::enums::PromptSize;
 use squall::tools::review::{ModelStatus, ReviewModelResult};
 
 // ===========================================================================
@@ -62,6 +64,17 @@ fn make_result(model: &str, latency_ms: u64, status: ModelStatus) -> ReviewModel
         reason: None,
         latency_ms,
         partial: false,
+        timing: Default::default(),
+        quotes_checked: 0,
+        quality_flags: Vec::new(),
+        format_compliance: None,
+        rubric: None,
+        usage: None,
+        provider_metadata: Default::default(),
+        late: false,
+        usable: None,
+        repaired: false,
+        duplicate_of: None,
     }
 }
 
@@ -80,6 +93,17 @@ fn make_result_with_reason(
         reason: Some(reason.to_string()),
         latency_ms,
         partial: false,
+        timing: Default::default(),
+        quotes_checked: 0,
+        quality_flags: Vec::new(),
+        format_compliance: None,
+        rubric: None,
+        usage: None,
+        provider_metadata: Default::default(),
+        late: false,
+        usable: None,
+        repaired: false,
+        duplicate_of: None,
     }
 }
 
@@ -89,7 +113,16 @@ fn await_query(
     writer: &GlobalWriter,
     exclude: Option<&str>,
 ) -> Result<squall::memory::global::GlobalRecommendations, String> {
-    let rx = writer.query_recommendations(exclude)?;
+    await_query_sized(writer, exclude, None)
+}
+
+/// Like `await_query`, restricted to one prompt-size bucket.
+fn await_query_sized(
+    writer: &GlobalWriter,
+    exclude: Option<&str>,
+    prompt_size: Option<PromptSize>,
+) -> Result<squall::memory::global::GlobalRecommendations, String> {
+    let rx = writer.query_recommendations(exclude, prompt_size)?;
     let rt = tokio::runtime::Runtime::new().map_err(|e| format!("runtime: {e}"))?;
     rt.block_on(async {
         tokio::time::timeout(std::time::Duration::from_secs(15), rx)
@@ -161,11 +194,11 @@ fn schema_migration_idempotent() {
     assert_eq!(v1, v2);
     assert_eq!(v1, CURRENT_VERSION);
 
-    // Only one version record should exist
+    // One version record per migration
     let mut stmt = conn.prepare("SELECT COUNT(*) FROM schema_version").unwrap();
     let count: i32 = stmt.query_row([], |row| row.get(0)).unwrap();
     assert_eq!(
-        count, 1,
+        count, CURRENT_VERSION,
         "Re-running migrations should not add duplicate version rows"
     );
 }
@@ -345,6 +378,69 @@ fn actor_write_merge_query_e2e() {
     let _ = std::fs::remove_dir_all(&dir);
 }
 
+/// Status reflects writes, merges, and bootstrap progress without any errors.
+#[test]
+fn actor_status_reports_writes_merge_and_bootstrap() {
+    use squall::memory::global::BootstrapState;
+
+    let dir = test_dir("actor-status");
+    let writer = GlobalWriter::new(dir.join("global.duckdb")).expect("GlobalWriter::new");
+
+    let status = writer.status();
+    assert!(status.worker_running);
+    assert_eq!(status.bootstrap, BootstrapState::NotStarted);
+
+    let results = vec![make_result("grok", 25000, ModelStatus::Success)];
+    writer.log_events(&results, 1000, "test:status", Some("/tmp"), None);
+    // Bootstrap from a models.md that doesn't exist is skipped, not failed.
+    writer.send_bootstrap(
+        dir.join("missing-models.md"),
+        "test:status".into(),
+        HashMap::new(),
+    );
+    writer.trigger_merge();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(15);
+    let status = loop {
+        let status = writer.status();
+        if status.queued_commands == 0 && status.last_merge_ms.is_some() {
+            break status;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "worker never drained: {status:?}"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    assert_eq!(status.event_files_written, 1);
+    assert_eq!(status.pending_event_files, 0);
+    assert!(status.db_size_bytes.is_some_and(|b| b > 0));
+    assert!(matches!(status.bootstrap, BootstrapState::Skipped { .. }));
+    assert!(
+        status.recent_errors.is_empty(),
+        "{:?}",
+        status.recent_errors
+    );
+
+    let report = status.to_markdown();
+    assert!(report.contains("**Bootstrap:** skipped"), "{report}");
+    assert!(report.contains("No worker errors"), "{report}");
+
+    drop(writer);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Without a writer, the report explains why global memory isn't running.
+#[test]
+fn composite_status_report_without_writer() {
+    let store = MemoryStore::new().with_global_unavailable("disabled in config");
+    assert!(
+        store
+            .global_status_report()
+            .contains("not running: disabled in config")
+    );
+}
+
 /// Drop should trigger shutdown without panic.
 /// The worker receives Shutdown via Drop and exits cleanly.
 #[test]
@@ -424,6 +520,95 @@ fn actor_query_excludes_project() {
     let _ = std::fs::remove_dir_all(&dir);
 }
 
+/// A prompt-size filter counts only events in that bucket, in pending Parquet files
+/// and in the compacted table alike.
+#[test]
+fn actor_query_filters_by_prompt_size() {
+    let dir = test_dir("actor-prompt-size");
+    let db_path = dir.join("global.duckdb");
+
+    let writer = GlobalWriter::new(db_path).expect("GlobalWriter::new should succeed");
+
+    // "small" only sees 2KB prompts, "large" only 200KB prompts.
+    for i in 0..6 {
+        let small = vec![make_result("small", 10000 + i * 100, ModelStatus::Success)];
+        writer.log_events(&small, 2_000, "test:proj", Some("/tmp"), None);
+        let large = vec![make_result("large", 90000 + i * 100, ModelStatus::Success)];
+        writer.log_events(&large, 200_000, "test:proj", Some("/tmp"), None);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    let keys = |size| -> Vec<String> {
+        let recs = await_query_sized(&writer, None, size).expect("query should succeed");
+        recs.models.into_iter().map(|m| m.model_key).collect()
+    };
+    // Pending Parquet files
+    assert_eq!(keys(Some(PromptSize::Xl)), vec!["large"]);
+    assert_eq!(keys(Some(PromptSize::S)), vec!["small"]);
+
+    // Compacted table
+    writer.trigger_merge();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    assert_eq!(keys(Some(PromptSize::Xl)), vec!["large"]);
+    assert!(keys(Some(PromptSize::M)).is_empty());
+    assert_eq!(keys(None).len(), 2);
+
+    drop(writer);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Two machines sharing a sync remote: the second sees the first's events.
+#[test]
+fn sync_shares_events_through_remote_snapshot() {
+    let dir = test_dir("sync-remote");
+    let remote = dir.join("team-global.parquet");
+    let sync = || {
+        Some(GlobalSync::new(
+            remote.to_str().unwrap(),
+            None,
+            std::time::Duration::from_secs(3600),
+        ))
+    };
+
+    let laptop_a =
+        GlobalWriter::with_sync(dir.join("a").join("global.duckdb"), sync()).expect("writer A");
+    for i in 0..6 {
+        let results = vec![make_result("grok", 25000 + i * 100, ModelStatus::Success)];
+        laptop_a.log_events(
+            &results,
+            1000,
+            &format!("test:proj-{i}"),
+            Some("/tmp"),
+            None,
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    // Shutdown merges and pushes.
+    drop(laptop_a);
+    assert!(remote.exists(), "shutdown should push a snapshot");
+
+    // Startup pulls.
+    let laptop_b =
+        GlobalWriter::with_sync(dir.join("b").join("global.duckdb"), sync()).expect("writer B");
+    let recs = await_query(&laptop_b, None).expect("query should succeed");
+    assert!(
+        recs.models.iter().any(|m| m.model_key == "grok"),
+        "B should see A's events: {:?}",
+        recs.models
+    );
+    let status = laptop_b.status();
+    assert!(status.last_sync_ms.is_some(), "{status:?}");
+    assert!(
+        status.recent_errors.is_empty(),
+        "{:?}",
+        status.recent_errors
+    );
+
+    drop(laptop_b);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 // ===========================================================================
 // 4. MERGE: Parquet ingested into DuckDB, dedup, cleanup
 // ===========================================================================
@@ -9,10 +9,11 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use squall::config::Config;
+use squall::config::{Config, ReviewConfig};
 use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry, Registry};
 use squall::memory::MemoryStore;
-use squall::review::ReviewExecutor;
+use squall::review::{GateThresholds, ReviewExecutor};
+use squall::tools::enums::GatingMode;
 use squall::tools::review::ReviewRequest;
 
 static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -36,6 +37,11 @@ fn store_with_events(events: &str) -> (MemoryStore, PathBuf) {
     (store, dir)
 }
 
+/// Per-process directory for persisted reviews, so tests don't write into the repository.
+fn reviews_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("squall-test-reviews-{}", std::process::id()))
+}
+
 /// Build a minimal registry with the given model names (all HTTP backends).
 fn test_registry(model_names: &[&str]) -> Arc<Registry> {
     let mut models = HashMap::new();
@@ -50,11 +56,8 @@ fn test_registry(model_names: &[&str]) -> Arc<Registry> {
                     api_key: "key".to_string(),
                     api_format: ApiFormat::OpenAi,
                 },
-                description: String::new(),
-                strengths: vec![],
-                weaknesses: vec![],
                 speed_tier: "fast".to_string(),
-                precision_tier: "medium".to_string(),
+                ..Default::default()
             },
         );
     }
@@ -79,10 +82,35 @@ fn make_request(models: Vec<&str>) -> ReviewRequest {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     }
 }
 
@@ -118,7 +146,7 @@ async fn hard_gate_excludes_model_below_threshold() {
 
     let (store, _dir) = store_with_events(events);
     let registry = test_registry(&["bad-model", "good-model"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["bad-model", "good-model"]);
 
     let resp = executor
@@ -149,6 +177,140 @@ async fn hard_gate_excludes_model_below_threshold() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// Test: Per-request gating override
+// ---------------------------------------------------------------------------
+
+/// bad-model: 2/6 success, failures are errors (no exploration slot).
+/// good-model: 6/6 success.
+fn flaky_events() -> String {
+    let mut events = String::new();
+    for (i, status) in ["success", "success", "error", "error", "error", "error"]
+        .iter()
+        .enumerate()
+    {
+        events.push_str(&format!(
+            "| 2026-02-23T10:0{i}:00Z | bad-model | 10.0s | {status} | no | — | 1000 |\n"
+        ));
+        events.push_str(&format!(
+            "| 2026-02-23T10:0{i}:00Z | good-model | 5.0s | success | no | — | 1000 |\n"
+        ));
+    }
+    events.trim_end().to_string()
+}
+
+#[tokio::test]
+async fn gating_override_dispatches_gated_model() {
+    let (store, _dir) = store_with_events(&flaky_events());
+    let executor = ReviewExecutor::new(test_registry(&["bad-model", "good-model"]))
+        .with_reviews_dir(reviews_dir());
+
+    let mut req = make_request(vec!["bad-model", "good-model"]);
+    let resp = executor
+        .execute(&req, req.prompt.clone(), &store, None, None, None, None)
+        .await;
+    let warning = resp
+        .warnings
+        .iter()
+        .find(|w| w.contains("excluded by hard gate"))
+        .expect("gate should fire by default");
+    assert!(
+        warning.contains("bad-model: 33.3% success (6 samples"),
+        "warning should carry the triggering stats: {warning}"
+    );
+
+    for (mode, reported) in [(GatingMode::Off, false), (GatingMode::ReportOnly, true)] {
+        req.gating = Some(mode);
+        let resp = executor
+            .execute(&req, req.prompt.clone(), &store, None, None, None, None)
+            .await;
+        let dispatched: Vec<&str> = resp.results.iter().map(|r| r.model.as_str()).collect();
+        assert!(
+            dispatched.contains(&"bad-model"),
+            "{mode:?} should dispatch bad-model. Got: {dispatched:?}"
+        );
+        assert!(
+            !resp
+                .warnings
+                .iter()
+                .any(|w| w.contains("excluded by hard gate")),
+            "{mode:?} must not exclude: {:?}",
+            resp.warnings
+        );
+        assert_eq!(
+            resp.warnings
+                .iter()
+                .any(|w| w.contains("report only") && w.contains("bad-model")),
+            reported,
+            "{mode:?}: {:?}",
+            resp.warnings
+        );
+    }
+}
+
+#[tokio::test]
+async fn configured_thresholds_replace_the_defaults() {
+    let (store, _dir) = store_with_events(&flaky_events());
+    let executor = ReviewExecutor::new(test_registry(&["bad-model", "good-model"]))
+        .with_reviews_dir(reviews_dir());
+    let req = make_request(vec!["bad-model", "good-model"]);
+
+    // 33.3% clears a 30% bar.
+    let lenient = ReviewConfig {
+        gate: GateThresholds {
+            min_success_rate: 0.3,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &store,
+            None,
+            None,
+            None,
+            Some(&lenient),
+        )
+        .await;
+    let dispatched: Vec<&str> = resp.results.iter().map(|r| r.model.as_str()).collect();
+    assert!(
+        dispatched.contains(&"bad-model"),
+        "bad-model clears a 30% bar. Got: {dispatched:?}"
+    );
+
+    // ...but not a cap of 3 failures.
+    let capped = ReviewConfig {
+        gate: GateThresholds {
+            min_success_rate: 0.3,
+            max_failures: Some(3),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &store,
+            None,
+            None,
+            None,
+            Some(&capped),
+        )
+        .await;
+    let dispatched: Vec<&str> = resp.results.iter().map(|r| r.model.as_str()).collect();
+    assert_eq!(dispatched, vec!["good-model"]);
+    assert!(
+        resp.warnings
+            .iter()
+            .any(|w| w.contains("(<30.0% success, >=5 samples, or >3 failures)")),
+        "warning should state the configured thresholds: {:?}",
+        resp.warnings
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Test: Model with insufficient samples bypasses gate
 // ---------------------------------------------------------------------------
@@ -163,7 +325,7 @@ async fn hard_gate_bypasses_model_with_insufficient_samples() {
 
     let (store, _dir) = store_with_events(events);
     let registry = test_registry(&["new-model"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["new-model"]);
 
     let resp = executor
@@ -205,7 +367,7 @@ async fn hard_gate_fallback_when_all_models_gated() {
 
     let (store, _dir) = store_with_events(&events);
     let registry = test_registry(&["model-a", "model-b"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["model-a", "model-b"]);
 
     let resp = executor
@@ -252,7 +414,7 @@ async fn hard_gate_passes_model_above_threshold() {
 
     let (store, _dir) = store_with_events(&events);
     let registry = test_registry(&["border-model"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["border-model"]);
 
     let resp = executor
@@ -283,7 +445,7 @@ async fn hard_gate_passes_unknown_model() {
 
     let (store, _dir) = store_with_events(events);
     let registry = test_registry(&["model-a", "model-b"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["model-a", "model-b"]);
 
     let resp = executor
@@ -309,7 +471,7 @@ async fn hard_gate_noop_without_memory_data() {
     // Use default MemoryStore (no models.md exists)
     let store = MemoryStore::new();
     let registry = test_registry(&["some-model"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["some-model"]);
 
     let resp = executor
@@ -711,7 +873,7 @@ async fn exploration_slot_only_for_timeout_dominant() {
 
     let (store, _dir) = store_with_events(&events);
     let registry = test_registry(&["timeout-model", "error-model", "good-model"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["timeout-model", "error-model", "good-model"]);
 
     let resp = executor
@@ -779,7 +941,7 @@ async fn exploration_slot_skipped_when_all_gated() {
 
     let (store, _dir) = store_with_events(&events);
     let registry = test_registry(&["model-a", "model-b"]);
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
     let req = make_request(vec!["model-a", "model-b"]);
 
     let resp = executor
@@ -93,11 +93,8 @@ async fn semaphore_acquire_respects_deadline() {
                 executable: "nonexistent-binary-12345".to_string(),
                 args_template: vec!["--".to_string()],
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -118,6 +115,10 @@ async fn semaphore_acquire_respects_deadline() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     // The query should fail (nonexistent binary), but it should fail FAST,
@@ -252,6 +253,10 @@ async fn cli_oversized_output_completes_without_deadlock() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let start = Instant::now();
@@ -307,6 +312,10 @@ async fn cli_oversized_stderr_completes_without_deadlock() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let start = Instant::now();
@@ -367,6 +376,10 @@ async fn cli_cap_kills_process_group_not_just_leader() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let start = Instant::now();
@@ -458,6 +471,10 @@ async fn http_oversized_response_gives_clear_error() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = dispatch
@@ -517,6 +534,10 @@ async fn cli_large_prompt_does_not_deadlock() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let start = Instant::now();
@@ -573,6 +594,10 @@ async fn cli_prompt_delivered_via_stdin() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     // `cat` reads stdin and echoes to stdout. Empty args = read from stdin.
@@ -636,6 +661,10 @@ async fn http_chunk_error_not_silently_swallowed() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = dispatch
@@ -996,6 +1025,10 @@ async fn cli_cross_stream_cap_kills_process() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let start = Instant::now();
@@ -1132,6 +1165,10 @@ async fn cli_overflow_by_one_byte_is_rejected() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     // Output exactly MAX_OUTPUT_BYTES + 1. Process exits cleanly (status 0).
@@ -1194,6 +1231,10 @@ async fn cli_stderr_overflow_is_rejected() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     // Small stdout (valid exit), huge stderr (N+1 bytes).
@@ -1246,11 +1287,8 @@ fn suggest_models_substring_match() {
                 api_key: "k".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     models.insert(
@@ -1262,11 +1300,8 @@ fn suggest_models_substring_match() {
                 executable: "echo".to_string(),
                 args_template: vec![],
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let registry = Registry::from_config(Config {
@@ -1302,11 +1337,8 @@ fn suggest_models_reverse_match() {
                 api_key: "k".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let registry = Registry::from_config(Config {
@@ -1339,11 +1371,8 @@ fn suggest_models_no_match() {
                 api_key: "k".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let registry = Registry::from_config(Config {
@@ -1375,11 +1404,8 @@ fn suggest_models_empty_query() {
                 api_key: "k".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let registry = Registry::from_config(Config {
@@ -1420,11 +1446,8 @@ fn suggest_models_sorted_and_capped() {
                     api_key: "key".to_string(),
                     api_format: ApiFormat::OpenAi,
                 },
-                description: String::new(),
-                strengths: vec![],
-                weaknesses: vec![],
                 speed_tier: "fast".to_string(),
-                precision_tier: "medium".to_string(),
+                ..Default::default()
             },
         );
     }
@@ -1509,6 +1532,10 @@ async fn cli_exact_limit_output_not_killed() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     // Use head to output exactly MAX_OUTPUT_BYTES of 'y\n' data.
@@ -1570,11 +1597,8 @@ async fn review_none_branch_model_selection_is_sorted() {
                     api_key: "key".to_string(),
                     api_format: ApiFormat::OpenAi,
                 },
-                description: String::new(),
-                strengths: vec![],
-                weaknesses: vec![],
                 speed_tier: "fast".to_string(),
-                precision_tier: "medium".to_string(),
+                ..Default::default()
             },
         );
     }
@@ -1583,7 +1607,9 @@ async fn review_none_branch_model_selection_is_sorted() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(
+        std::env::temp_dir().join(format!("squall-test-reviews-{}", std::process::id())),
+    );
 
     let req = ReviewRequest {
         prompt: "test".into(),
@@ -1596,12 +1622,37 @@ async fn review_none_branch_model_selection_is_sorted() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
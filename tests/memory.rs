@@ -13,9 +13,11 @@
 //! A global mutex serializes all tests that need to change the process cwd.
 
 use squall::memory::{
-    MAX_MEMORIZE_CONTENT_LEN, MAX_PATTERN_ENTRIES, MAX_TACTICS_BYTES, MemoryStore, VALID_CATEGORIES,
+    MAX_MEMORIZE_CONTENT_LEN, MAX_PATTERN_ENTRIES, MAX_TACTICS_BYTES, MemoryQuery, MemoryStore,
+    VALID_CATEGORIES,
 };
-use squall::tools::review::{ModelStatus, ReviewModelResult};
+use squall::rubric::RubricScore;
+use squall::tools::review::{ModelStatus, QualityFlag, ReviewModelResult};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
@@ -63,6 +65,17 @@ fn make_result(model: &str, latency_ms: u64, status: ModelStatus) -> ReviewModel
         reason: None,
         latency_ms,
         partial: false,
+        timing: Default::default(),
+        quotes_checked: 0,
+        quality_flags: Vec::new(),
+        format_compliance: None,
+        rubric: None,
+        usage: None,
+        provider_metadata: Default::default(),
+        late: false,
+        usable: None,
+        repaired: false,
+        duplicate_of: None,
     }
 }
 
@@ -120,6 +133,144 @@ fn write_log_metrics_creates_models_md() {
     teardown(&dir, &orig);
 }
 
+#[test]
+fn write_log_metrics_records_ttfb() {
+    let (dir, orig, _guard) = setup_test_env("w1b-ttfb");
+    run_async(async {
+        let store = MemoryStore::new();
+        let mut fast = make_result("grok", 22000, ModelStatus::Success);
+        fast.timing.headers_ms = Some(400);
+        fast.timing.ttfb_ms = Some(1500);
+        fast.timing.stream_ms = Some(20000);
+        let mut slow = make_result("grok", 30000, ModelStatus::Success);
+        slow.timing.ttfb_ms = Some(2500);
+        let no_timing = make_result("codex", 40000, ModelStatus::Success);
+        store
+            .log_model_metrics(&[fast, slow, no_timing], 4200, None, None)
+            .await;
+
+        let content = tokio::fs::read_to_string(memory_dir(&dir).join("models.md"))
+            .await
+            .unwrap();
+        assert!(content.contains("| Prompt Len | TTFB |"));
+        assert!(content.contains("| 4200 | 1.5s |"));
+
+        let stats = store.get_model_stats(None).await.unwrap();
+        let grok_ttfb = stats["grok"].avg_ttfb_secs.expect("grok has TTFB samples");
+        assert!(
+            (grok_ttfb - 2.0).abs() < 1e-9,
+            "avg TTFB should be 2.0s: {grok_ttfb}"
+        );
+        assert_eq!(stats["codex"].avg_ttfb_secs, None);
+        // Headers and stream were reported by one grok event; nothing queued.
+        assert_eq!(stats["grok"].avg_headers_secs, Some(0.4));
+        assert_eq!(stats["grok"].avg_stream_secs, Some(20.0));
+        assert_eq!(stats["grok"].avg_queue_secs, None);
+    });
+    teardown(&dir, &orig);
+}
+
+#[test]
+fn write_log_metrics_records_fabricated_quotes() {
+    let (dir, orig, _guard) = setup_test_env("w1c-quotes");
+    run_async(async {
+        let store = MemoryStore::new();
+        let mut honest = make_result("grok", 22000, ModelStatus::Success);
+        honest.quotes_checked = 6;
+        let mut inventive = make_result("grok", 25000, ModelStatus::Success);
+        inventive.quotes_checked = 2;
+        inventive.quality_flags = vec![QualityFlag::FabricatedQuote {
+            quote: "validate_checksum(&raw, expected)".to_string(),
+        }];
+        let unchecked = make_result("codex", 40000, ModelStatus::Success);
+        store
+            .log_model_metrics(&[honest, inventive, unchecked], 4200, None, None)
+            .await;
+
+        let content = tokio::fs::read_to_string(memory_dir(&dir).join("models.md"))
+            .await
+            .unwrap();
+        assert!(content.contains("| TTFB | Quotes |"));
+        assert!(content.contains("| 1/2 |"));
+
+        let stats = store.get_model_stats(None).await.unwrap();
+        let rate = stats["grok"]
+            .fabricated_quote_rate
+            .expect("grok had quotes checked");
+        assert!((rate - 0.125).abs() < 1e-9, "1 of 8 quotes: {rate}");
+        assert_eq!(stats["codex"].fabricated_quote_rate, None);
+    });
+    teardown(&dir, &orig);
+}
+
+#[test]
+fn write_log_metrics_records_format_compliance() {
+    let (dir, orig, _guard) = setup_test_env("w1d-format");
+    run_async(async {
+        let store = MemoryStore::new();
+        let mut strict = make_result("grok", 22000, ModelStatus::Success);
+        strict.format_compliance = Some(1.0);
+        let mut loose = make_result("grok", 25000, ModelStatus::Success);
+        loose.format_compliance = Some(0.5);
+        let unscored = make_result("codex", 40000, ModelStatus::Success);
+        store
+            .log_model_metrics(&[strict, loose, unscored], 4200, None, None)
+            .await;
+
+        let stats = store.get_model_stats(None).await.unwrap();
+        let avg = stats["grok"]
+            .avg_format_compliance
+            .expect("grok was scored");
+        assert!((avg - 0.75).abs() < 1e-9, "avg compliance: {avg}");
+        assert_eq!(stats["codex"].avg_format_compliance, None);
+
+        let rec = store
+            .read_memory(Some("recommend"), None, 10000, None)
+            .await
+            .unwrap();
+        assert!(rec.contains("**Format compliance**"), "rec: {rec}");
+        assert!(rec.contains("grok 75% (2)"), "rec: {rec}");
+    });
+    teardown(&dir, &orig);
+}
+
+#[test]
+fn write_log_metrics_records_rubric_scores() {
+    let (dir, orig, _guard) = setup_test_env("w1e-rubric");
+    run_async(async {
+        let store = MemoryStore::new();
+        let grade = |score: f64| RubricScore {
+            score,
+            criteria: Default::default(),
+            judge: "judge".to_string(),
+            comment: None,
+        };
+        let mut good = make_result("grok", 22000, ModelStatus::Success);
+        good.rubric = Some(grade(0.9));
+        let mut poor = make_result("codex", 25000, ModelStatus::Success);
+        poor.rubric = Some(grade(0.4));
+        let ungraded = make_result("gemini", 40000, ModelStatus::Success);
+        store
+            .log_model_metrics(&[good, poor, ungraded], 4200, None, None)
+            .await;
+
+        let stats = store.get_model_stats(None).await.unwrap();
+        assert_eq!(stats["grok"].avg_rubric_score, Some(0.9));
+        assert_eq!(stats["gemini"].avg_rubric_score, None);
+        assert_eq!(stats["grok"].avg_format_compliance, None);
+
+        let rec = store
+            .read_memory(Some("recommend"), None, 10000, None)
+            .await
+            .unwrap();
+        assert!(
+            rec.contains("**Rubric score** (judged answer quality): grok 90% (1), codex 40% (1)"),
+            "rec: {rec}"
+        );
+    });
+    teardown(&dir, &orig);
+}
+
 #[test]
 fn write_log_metrics_appends_events() {
     let (dir, orig, _guard) = setup_test_env("w2-append");
@@ -985,6 +1136,17 @@ fn bug2_pipe_in_model_name_does_not_corrupt_summary() {
             reason: None,
             latency_ms: 5000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }];
 
         // Write 10 events to force summary computation (COMPACTION_INTERVAL=10)
@@ -1024,6 +1186,17 @@ fn bug2b_pipe_in_error_does_not_corrupt_summary() {
             reason: None,
             latency_ms: 5000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }];
 
         store.log_model_metrics(&results, 1000, None, None).await;
@@ -1049,12 +1222,12 @@ fn bug2b_pipe_in_error_does_not_corrupt_summary() {
             1,
             "Should have exactly 1 event line for grok: {event_lines:?}"
         );
-        // Verify the event line has exactly 8 pipe-delimited columns (not more from unescaped pipes)
+        // Verify the event line has exactly 16 pipe-delimited columns (not more from unescaped pipes)
         let cols: Vec<&str> = event_lines[0].split('|').collect();
         assert_eq!(
             cols.len(),
-            10,
-            "Event row should have 8 data columns (10 parts after split): {cols:?}"
+            18,
+            "Event row should have 16 data columns (18 parts after split): {cols:?}"
         );
     });
     teardown(&dir, &orig);
@@ -1264,6 +1437,184 @@ fn scope_exact_match_does_not_prefix_match() {
     teardown(&dir, &orig);
 }
 
+#[test]
+fn memorize_from_review_saves_consensus_findings() {
+    let (dir, orig, _guard) = setup_test_env("memorize-from-review");
+    run_async(async {
+        let store = MemoryStore::new();
+        std::fs::create_dir_all(".squall/reviews").unwrap();
+        let review = serde_json::json!({
+            "results": [
+                {
+                    "model": "grok",
+                    "status": "success",
+                    "response": "### [high] Race condition in cache refresh\n- File: src/cache.rs:40\n\n### [low] Unused import in cache module\n",
+                },
+                {
+                    "model": "gemini",
+                    "status": "success",
+                    "response": "### [critical] Cache refresh is not atomic\n- File: src/cache.rs:42\n",
+                },
+                {
+                    "model": "kimi",
+                    "status": "error",
+                    "error": "timeout",
+                },
+            ],
+            "diff_size": "+120 -45",
+        });
+        std::fs::write(
+            ".squall/reviews/1773157800594_1_0.json",
+            serde_json::to_string(&review).unwrap(),
+        )
+        .unwrap();
+
+        let report = store
+            .memorize_from_review(".squall/reviews/1773157800594_1_0.json", 2, None)
+            .await
+            .unwrap();
+        assert!(report.contains("Memorized 1 pattern"), "{report}");
+
+        let patterns = std::fs::read_to_string(memory_dir(&dir).join("patterns.md")).unwrap();
+        assert!(
+            patterns.contains("Race condition in cache refresh (src/cache.rs)"),
+            "{patterns}"
+        );
+        assert!(patterns.contains("- consensus: 2/2"), "{patterns}");
+        assert!(patterns.contains("- models: grok, gemini"), "{patterns}");
+        assert!(patterns.contains("- diff_size: +120 -45"), "{patterns}");
+        assert!(patterns.contains("- Tags: review, critical"), "{patterns}");
+        assert!(!patterns.contains("Unused import"), "{patterns}");
+
+        // Single-model findings pass with min_consensus 1.
+        let report = store
+            .memorize_from_review("1773157800594_1_0", 1, None)
+            .await
+            .unwrap();
+        assert!(report.contains("Memorized 2 pattern"), "{report}");
+    });
+    teardown(&dir, &orig);
+}
+
+#[test]
+fn memorize_from_review_rejects_bad_input() {
+    let (dir, orig, _guard) = setup_test_env("memorize-from-review-bad");
+    run_async(async {
+        let store = MemoryStore::new();
+        assert!(
+            store
+                .memorize_from_review("../etc/passwd", 2, None)
+                .await
+                .is_err()
+        );
+        assert!(
+            store
+                .memorize_from_review("missing", 2, None)
+                .await
+                .is_err()
+        );
+        assert!(
+            store
+                .memorize_from_review("missing", 0, None)
+                .await
+                .is_err()
+        );
+    });
+    teardown(&dir, &orig);
+}
+
+#[test]
+fn structured_query_combines_pattern_filters() {
+    let (dir, orig, _guard) = setup_test_env("structured-query");
+    run_async(async {
+        let store = MemoryStore::new();
+
+        for _ in 0..3 {
+            store
+                .memorize(
+                    "pattern",
+                    "Auth token leak",
+                    None,
+                    Some(&["security".to_string(), "auth".to_string()]),
+                    Some("branch:feature/auth"),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        store
+            .memorize(
+                "pattern",
+                "Auth retry storm",
+                None,
+                Some(&["auth".to_string()]),
+                Some("branch:feature/retry"),
+                None,
+            )
+            .await
+            .unwrap();
+        store
+            .memorize(
+                "pattern",
+                "Codebase security note",
+                None,
+                Some(&["security".to_string()]),
+                Some("codebase"),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let store = &store;
+        let read = move |query: MemoryQuery| async move {
+            store
+                .read_memory_query(Some("patterns"), 10000, &query)
+                .await
+                .unwrap()
+        };
+
+        // Scope prefix keeps both branch entries, drops codebase.
+        let result = read(MemoryQuery {
+            scope_prefix: Some("branch:".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert!(result.contains("Auth token leak"), "{result}");
+        assert!(result.contains("Auth retry storm"), "{result}");
+        assert!(!result.contains("Codebase security note"), "{result}");
+
+        // Tags must all be present (case-insensitive).
+        let result = read(MemoryQuery {
+            tags: vec!["AUTH".to_string(), "security".to_string()],
+            ..Default::default()
+        })
+        .await;
+        assert!(result.contains("Auth token leak"), "{result}");
+        assert!(!result.contains("Auth retry storm"), "{result}");
+        assert!(!result.contains("Codebase security note"), "{result}");
+
+        // Evidence threshold keeps only the repeated entry.
+        let result = read(MemoryQuery {
+            scope_prefix: Some("branch:".to_string()),
+            min_evidence: Some(3),
+            ..Default::default()
+        })
+        .await;
+        assert!(result.contains("Auth token leak"), "{result}");
+        assert!(!result.contains("Auth retry storm"), "{result}");
+
+        // A future `since` date filters everything out.
+        let result = read(MemoryQuery {
+            since: Some("2999-01-01".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert!(!result.contains("Auth"), "{result}");
+        assert!(result.contains("No memory found"), "{result}");
+    });
+    teardown(&dir, &orig);
+}
+
 /// scope=None should return all entries regardless of scope.
 #[test]
 fn scope_none_returns_all_entries() {
@@ -1624,6 +1975,39 @@ fn evidence_confirmed_at_threshold() {
 }
 
 /// Flush should graduate high-evidence branch patterns to codebase scope.
+#[test]
+fn flush_graduates_branch_scoped_model_events() {
+    let (dir, orig, _guard) = setup_test_env("p2-flush-events");
+    run_async(async {
+        let store = MemoryStore::new();
+        let results = [make_result("grok", 22000, ModelStatus::Success)];
+        store
+            .log_model_metrics_scoped(&results, 1000, None, None, Some("branch:feature/auth"))
+            .await;
+        store
+            .log_model_metrics_scoped(&results, 1000, None, None, Some("branch:other"))
+            .await;
+
+        let report = store.flush_branch("feature/auth").await.unwrap();
+        assert!(
+            report.contains("1 model events graduated"),
+            "Report: {report}"
+        );
+
+        let content = tokio::fs::read_to_string(memory_dir(&dir).join("models.md"))
+            .await
+            .unwrap();
+        assert!(content.contains("| Rubric | Headers | Stream | Queue | Scope |"));
+        assert!(content.contains("| codebase |"), "{content}");
+        assert!(content.contains("| branch:other |"), "{content}");
+        assert!(!content.contains("branch:feature/auth"), "{content}");
+        // The extra column doesn't disturb stats.
+        let stats = store.get_model_stats(None).await.unwrap();
+        assert_eq!(stats["grok"].sample_count, 2);
+    });
+    teardown(&dir, &orig);
+}
+
 #[test]
 fn flush_graduates_high_evidence_to_codebase() {
     let (dir, orig, _guard) = setup_test_env("p2-flush-graduate");
@@ -1892,6 +2276,17 @@ async fn read_memory_recommend_returns_recommendations() {
             reason: None,
             latency_ms: 5000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         },
         ReviewModelResult {
             model: "slow-model".to_string(),
@@ -1902,6 +2297,17 @@ async fn read_memory_recommend_returns_recommendations() {
             reason: None,
             latency_ms: 120000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         },
     ];
     store.log_model_metrics(&results, 1000, None, None).await;
@@ -1963,6 +2369,17 @@ async fn recommend_quick_triage_prefers_fastest() {
                         reason: None,
                         latency_ms: 15000,
                         partial: false,
+                        timing: Default::default(),
+                        quotes_checked: 0,
+                        quality_flags: Vec::new(),
+                        format_compliance: None,
+                        rubric: None,
+                        usage: None,
+                        provider_metadata: Default::default(),
+                        late: false,
+                        usable: None,
+                        repaired: false,
+                        duplicate_of: None,
                     },
                     ReviewModelResult {
                         model: "thorough".to_string(),
@@ -1973,6 +2390,17 @@ async fn recommend_quick_triage_prefers_fastest() {
                         reason: None,
                         latency_ms: 90000,
                         partial: false,
+                        timing: Default::default(),
+                        quotes_checked: 0,
+                        quality_flags: Vec::new(),
+                        format_compliance: None,
+                        rubric: None,
+                        usage: None,
+                        provider_metadata: Default::default(),
+                        late: false,
+                        usable: None,
+                        repaired: false,
+                        duplicate_of: None,
                     },
                 ],
                 500,
@@ -2105,11 +2533,8 @@ fn defect_listmodels_must_return_map_key_not_model_id() {
                 api_key: "test".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2198,6 +2623,10 @@ async fn defect_sse_keepalive_must_reset_stall_timer() {
         cancellation_token: None,
         // 3 second stall timeout — shorter than the 4s of keep-alives
         stall_timeout: Some(Duration::from_secs(3)),
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = http
@@ -2428,3 +2857,27 @@ fn memorize_tactic_model_newline_sanitized() {
     });
     teardown(&dir, &orig);
 }
+
+/// Stores derived for different projects (daemon sessions) write under each
+/// project's own `.squall/memory`, never into each other's.
+#[tokio::test]
+async fn for_project_stores_write_to_their_own_project() {
+    let root = std::env::temp_dir().join("squall-test").join("for-project");
+    let _ = tokio::fs::remove_dir_all(&root).await;
+    let (alpha, beta) = (root.join("alpha"), root.join("beta"));
+    let base = MemoryStore::with_base_dir(root.join("cwd/.squall/memory"));
+
+    for (project, content) in [(&alpha, "Alpha pattern"), (&beta, "Beta pattern")] {
+        base.for_project(project)
+            .memorize("pattern", content, None, None, None, None)
+            .await
+            .unwrap();
+    }
+
+    let read = |p: &std::path::Path| std::fs::read_to_string(p.join(".squall/memory/patterns.md"));
+    let (alpha_patterns, beta_patterns) = (read(&alpha).unwrap(), read(&beta).unwrap());
+    assert!(alpha_patterns.contains("Alpha pattern") && !alpha_patterns.contains("Beta"));
+    assert!(beta_patterns.contains("Beta pattern") && !beta_patterns.contains("Alpha"));
+    assert!(!root.join("cwd").exists(), "the base store is untouched");
+    let _ = tokio::fs::remove_dir_all(&root).await;
+}
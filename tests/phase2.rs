@@ -121,11 +121,8 @@ fn backend_config_cli_stores_executable_and_args() {
             executable: "/usr/local/bin/gemini".to_string(),
             args_template: vec!["-o".to_string(), "json".to_string()],
         },
-        description: String::new(),
-        strengths: vec![],
-        weaknesses: vec![],
         speed_tier: "fast".to_string(),
-        precision_tier: "medium".to_string(),
+        ..Default::default()
     };
 
     assert!(matches!(entry.backend, BackendConfig::Cli { .. }));
@@ -148,11 +145,8 @@ fn backend_config_http_redacts_api_key_in_debug() {
             api_key: "sk-secret-key".to_string(),
             api_format: ApiFormat::OpenAi,
         },
-        description: String::new(),
-        strengths: vec![],
-        weaknesses: vec![],
         speed_tier: "fast".to_string(),
-        precision_tier: "medium".to_string(),
+        ..Default::default()
     };
 
     let debug = format!("{:?}", entry);
@@ -248,10 +248,35 @@ fn find4_10_deep_mode_respects_explicit_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(true),
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     // Doc says: "Individual fields (timeout_secs, reasoning_effort, max_tokens)
@@ -280,10 +305,35 @@ fn find4_10b_deep_mode_defaults_to_600_when_not_set() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(true),
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     // When timeout_secs is NOT set, deep mode should default to 600.
@@ -1,10 +1,12 @@
 //! Tests for the review tool — multi-model dispatch with straggler cutoff.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
 use squall::config::Config;
+use squall::dispatch::health::HealthState;
 use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry, Registry};
 use squall::memory::MemoryStore;
 use squall::review::ReviewExecutor;
@@ -12,6 +14,11 @@ use squall::tools::review::{
     ModelStatus, ReviewModelResult, ReviewRequest, ReviewResponse, ReviewSummary,
 };
 
+/// Per-process directory for persisted reviews, so tests don't write into the repository.
+fn reviews_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("squall-test-reviews-{}", std::process::id()))
+}
+
 // ---------------------------------------------------------------------------
 // Helper: resolve per-model system prompt (mirrors executor logic exactly)
 // ---------------------------------------------------------------------------
@@ -43,12 +50,37 @@ fn review_request_default_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
     assert_eq!(req.timeout_secs(), 180);
 }
@@ -66,12 +98,37 @@ fn review_request_custom_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
     assert_eq!(req.timeout_secs(), 60);
 }
@@ -83,6 +140,7 @@ fn review_request_custom_timeout() {
 #[test]
 fn review_response_serializes_to_json() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![squall::tools::review::ReviewModelResult {
             model: "grok".to_string(),
             provider: "xai".to_string(),
@@ -92,6 +150,17 @@ fn review_response_serializes_to_json() {
             reason: None,
             latency_ms: 1234,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -100,8 +169,24 @@ fn review_response_serializes_to_json() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let json = serde_json::to_string(&resp).unwrap();
@@ -117,6 +202,7 @@ fn review_response_serializes_to_json() {
 #[test]
 fn review_response_omits_none_fields() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![squall::tools::review::ReviewModelResult {
             model: "test".to_string(),
             provider: "test".to_string(),
@@ -126,6 +212,17 @@ fn review_response_omits_none_fields() {
             reason: Some("cutoff".to_string()),
             latency_ms: 180000,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -134,8 +231,24 @@ fn review_response_omits_none_fields() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let json = serde_json::to_string(&resp).unwrap();
@@ -154,6 +267,7 @@ fn review_response_omits_none_fields() {
 #[test]
 fn review_response_includes_persist_error_when_set() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -162,8 +276,24 @@ fn review_response_includes_persist_error_when_set() {
         persist_error: Some("permission denied".to_string()),
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
     let json = serde_json::to_string(&resp).unwrap();
     assert!(json.contains("\"persist_error\":\"permission denied\""));
@@ -172,6 +302,7 @@ fn review_response_includes_persist_error_when_set() {
 #[test]
 fn review_response_includes_files_skipped_when_set() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -180,8 +311,24 @@ fn review_response_includes_files_skipped_when_set() {
         persist_error: None,
         files_skipped: Some(vec!["large_file.rs (50000B)".to_string()]),
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
     let json = serde_json::to_string(&resp).unwrap();
     assert!(json.contains("\"files_skipped\""));
@@ -191,6 +338,7 @@ fn review_response_includes_files_skipped_when_set() {
 #[test]
 fn review_response_omits_files_skipped_when_none() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -199,8 +347,24 @@ fn review_response_omits_files_skipped_when_none() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
     let json = serde_json::to_string(&resp).unwrap();
     assert!(
@@ -228,7 +392,7 @@ async fn executor_unknown_models_go_to_not_started() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -241,12 +405,37 @@ async fn executor_unknown_models_go_to_not_started() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -282,11 +471,8 @@ async fn executor_none_models_uses_all_configured() {
                 api_key: "fake-key".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -294,7 +480,7 @@ async fn executor_none_models_uses_all_configured() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -307,12 +493,37 @@ async fn executor_none_models_uses_all_configured() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -337,6 +548,49 @@ async fn executor_none_models_uses_all_configured() {
 // ReviewExecutor: straggler cutoff aborts slow models
 // ---------------------------------------------------------------------------
 
+#[test]
+fn context_budgets_only_cover_the_reviews_models() {
+    let entry = |name: &str, budget: Option<usize>| ModelEntry {
+        model_id: name.to_string(),
+        provider: "test-provider".to_string(),
+        backend: BackendConfig::Http {
+            base_url: "http://127.0.0.1:1/v1/chat".to_string(),
+            api_key: "fake-key".to_string(),
+            api_format: ApiFormat::OpenAi,
+        },
+        speed_tier: "fast".to_string(),
+        context_budget_bytes: budget,
+        ..Default::default()
+    };
+    let mut models = HashMap::new();
+    models.insert("big".to_string(), entry("big", None));
+    models.insert("small".to_string(), entry("small", Some(32_000)));
+    models.insert("tiny".to_string(), entry("tiny", Some(8_000)));
+    models.insert("huge".to_string(), entry("huge", Some(4_000_000)));
+    let registry = Registry::from_config(Config {
+        models,
+        ..Default::default()
+    });
+    let budgets = |models: Option<&[String]>, cfg: Option<&squall::config::ReviewConfig>| {
+        squall::review::context_budgets(&registry, models, cfg)
+            .into_iter()
+            .collect::<Vec<_>>()
+    };
+
+    let requested = vec!["big".to_string(), "small#2".to_string()];
+    assert_eq!(budgets(Some(&requested), None), [32_000]);
+    let cfg = squall::config::ReviewConfig {
+        default_models: vec!["tiny".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(budgets(None, Some(&cfg)), [8_000]);
+    // A budget above the default is a model's own too, not capped to it.
+    let requested = vec!["big".to_string(), "huge".to_string()];
+    assert_eq!(budgets(Some(&requested), None), [4_000_000]);
+    // Without a config every model is a candidate.
+    assert_eq!(budgets(None, None), [8_000, 32_000, 4_000_000]);
+}
+
 #[tokio::test]
 async fn executor_cutoff_aborts_slow_models() {
     // Register a model pointing at a black-hole address — connection will hang
@@ -352,11 +606,8 @@ async fn executor_cutoff_aborts_slow_models() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -364,7 +615,7 @@ async fn executor_cutoff_aborts_slow_models() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -377,12 +628,37 @@ async fn executor_cutoff_aborts_slow_models() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let start = Instant::now();
@@ -410,6 +686,456 @@ async fn executor_cutoff_aborts_slow_models() {
     assert_eq!(resp.results[0].status, ModelStatus::Error);
 }
 
+#[tokio::test]
+async fn executor_reports_models_still_queued_at_cutoff() {
+    // Ten hanging HTTP models against eight HTTP permits: two never start.
+    // A local listener that accepts and never responds holds the eight slots.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            held.push(socket);
+        }
+    });
+    let base_url = format!("http://127.0.0.1:{port}/v1/chat");
+    let names: Vec<String> = (0..10).map(|i| format!("hang-{i}")).collect();
+    let models = names
+        .iter()
+        .map(|name| {
+            let entry = ModelEntry {
+                model_id: name.clone(),
+                provider: "test".to_string(),
+                backend: BackendConfig::Http {
+                    base_url: base_url.clone(),
+                    api_key: "fake".to_string(),
+                    api_format: ApiFormat::OpenAi,
+                },
+                speed_tier: "fast".to_string(),
+                ..Default::default()
+            };
+            (name.clone(), entry)
+        })
+        .collect();
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let executor = ReviewExecutor::new(Arc::new(Registry::from_config(config)))
+        .with_reviews_dir(reviews_dir());
+
+    let req = ReviewRequest {
+        prompt: "hello".to_string(),
+        models: Some(names),
+        timeout_secs: Some(2),
+        system_prompt: None,
+        temperature: None,
+        file_paths: None,
+        working_directory: None,
+        diff: None,
+        per_model_system_prompts: None,
+        per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
+        deep: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        context_format: None,
+        response_format: None,
+        investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
+    };
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &MemoryStore::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+
+    assert_eq!(resp.results.len(), 10);
+    let queued: Vec<_> = resp
+        .results
+        .iter()
+        .filter(|r| r.reason.as_deref() == Some("queued_timeout"))
+        .collect();
+    assert_eq!(queued.len(), 2, "{:?}", resp.results);
+    for r in &queued {
+        assert_eq!(r.status, ModelStatus::Error);
+        assert!(r.timing.queue_ms.is_some_and(|ms| ms >= 1500), "{r:?}");
+        assert!(r.error.as_deref().unwrap().contains("not started"));
+    }
+    // Models that held a permit are stragglers, not capacity failures.
+    assert!(
+        resp.results
+            .iter()
+            .filter(|r| r.reason.as_deref() != Some("queued_timeout"))
+            .all(|r| r.timing.queue_ms.is_none())
+    );
+}
+
+#[tokio::test]
+async fn executor_background_stragglers_append_late_results() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // A model that answers 3s after the request, well past a 1s cutoff.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut data = Vec::new();
+        let mut buf = [0u8; 8192];
+        while !data.windows(4).any(|w| w == b"\r\n\r\n") || !data.ends_with(b"}") {
+            let n = socket.read(&mut buf).await.unwrap();
+            if n == 0 {
+                return;
+            }
+            data.extend_from_slice(&buf[..n]);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let body = "data: {\"choices\":[{\"delta\":{\"content\":\"late answer\"}}]}\n\n\
+                    data: [DONE]\n\n";
+        let _ = socket
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                     Connection: close\r\n\r\n{body}"
+                )
+                .as_bytes(),
+            )
+            .await;
+    });
+
+    let mut models = HashMap::new();
+    models.insert(
+        "late-model".to_string(),
+        ModelEntry {
+            model_id: "late-model".to_string(),
+            provider: "test".to_string(),
+            backend: BackendConfig::Http {
+                base_url: format!("http://127.0.0.1:{port}/v1/chat/completions"),
+                api_key: "fake".to_string(),
+                api_format: ApiFormat::OpenAi,
+            },
+            speed_tier: "fast".to_string(),
+            ..Default::default()
+        },
+    );
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let (late_tx, mut late_rx) = tokio::sync::mpsc::unbounded_channel();
+    let executor = ReviewExecutor::new(Arc::new(Registry::from_config(config)))
+        .with_reviews_dir(reviews_dir())
+        .with_late_results(late_tx);
+
+    let req = ReviewRequest {
+        prompt: "hello".to_string(),
+        models: Some(vec!["late-model".to_string()]),
+        timeout_secs: Some(1),
+        system_prompt: None,
+        temperature: None,
+        file_paths: None,
+        working_directory: None,
+        diff: None,
+        per_model_system_prompts: None,
+        per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
+        deep: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        context_format: None,
+        response_format: None,
+        investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: Some(squall::tools::enums::StragglerPolicy::Background),
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
+    };
+    let start = Instant::now();
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &MemoryStore::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+    assert!(
+        start.elapsed().as_secs_f64() < 2.5,
+        "returned at the cutoff"
+    );
+    assert_eq!(resp.results.len(), 1);
+    assert_eq!(resp.results[0].reason.as_deref(), Some("background"));
+    assert_eq!(resp.summary.models_cutoff, 1);
+    assert_eq!(resp.summary.models_failed, 0);
+
+    let path = std::path::PathBuf::from(resp.results_file.expect("persisted"));
+    let reported = tokio::time::timeout(std::time::Duration::from_secs(10), late_rx.recv())
+        .await
+        .expect("late result reported")
+        .unwrap();
+    assert_eq!(reported.model, "late-model");
+    assert!(reported.late);
+
+    // Reported only once it is in the results file.
+    let json = squall::artifact::read_review(&path).await.unwrap();
+    let review: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let late = &review["results"][0];
+    assert_eq!(late["late"], true);
+    assert_eq!(late["model"], "late-model");
+    assert_eq!(late["status"], "success");
+    assert_eq!(late["response"], "late answer");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn executor_leads_with_a_warning_for_rejected_keys() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // A provider that rejects every request's key.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                let body = "{\"error\":\"invalid api key\"}";
+                let _ = socket
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 401 Unauthorized\r\nContent-Length: {}\r\n\
+                             Connection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .await;
+            });
+        }
+    });
+
+    let mut models = HashMap::new();
+    for name in ["key-a", "key-b"] {
+        models.insert(
+            name.to_string(),
+            ModelEntry {
+                model_id: name.to_string(),
+                provider: "revoked".to_string(),
+                backend: BackendConfig::Http {
+                    base_url: format!("http://127.0.0.1:{port}/v1/chat/completions"),
+                    api_key: "sk-revoked".to_string(),
+                    api_format: ApiFormat::OpenAi,
+                },
+                speed_tier: "fast".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let executor = ReviewExecutor::new(Arc::new(Registry::from_config(config)))
+        .with_reviews_dir(reviews_dir());
+
+    let req = ReviewRequest {
+        prompt: "hello".to_string(),
+        models: Some(vec!["key-a".to_string(), "key-b".to_string()]),
+        timeout_secs: Some(10),
+        system_prompt: None,
+        temperature: None,
+        file_paths: None,
+        working_directory: None,
+        diff: None,
+        per_model_system_prompts: None,
+        per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
+        deep: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        context_format: None,
+        response_format: None,
+        investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
+    };
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &MemoryStore::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+    assert_eq!(resp.summary.models_failed, 2);
+    let warning = &resp.warnings[0];
+    assert!(
+        warning.starts_with("API key for revoked is being rejected (2 auth failures since "),
+        "{warning}"
+    );
+    assert!(warning.contains("never worked this session"), "{warning}");
+    assert!(warning.contains("invalid api key"), "{warning}");
+    assert_eq!(
+        resp.warnings
+            .iter()
+            .filter(|w| w.starts_with("API key for"))
+            .count(),
+        1
+    );
+    if let Some(path) = resp.results_file {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[tokio::test]
+async fn executor_skips_models_on_down_backends() {
+    // Nothing listens on port 1: every call fails to connect.
+    let mut models = HashMap::new();
+    for name in ["down-a", "down-b"] {
+        models.insert(
+            name.to_string(),
+            ModelEntry {
+                model_id: name.to_string(),
+                provider: "unreachable".to_string(),
+                backend: BackendConfig::Http {
+                    base_url: "http://127.0.0.1:1/v1/chat/completions".to_string(),
+                    api_key: "fake".to_string(),
+                    api_format: ApiFormat::OpenAi,
+                },
+                speed_tier: "fast".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let registry = Arc::new(Registry::from_config(config));
+    let executor = ReviewExecutor::new(registry.clone()).with_reviews_dir(reviews_dir());
+    let req: ReviewRequest = serde_json::from_value(serde_json::json!({
+        "prompt": "hello",
+        "models": ["down-a", "down-b"],
+        "timeout_secs": 10,
+    }))
+    .unwrap();
+    let memory = MemoryStore::new();
+    let review = || executor.execute(&req, req.prompt.clone(), &memory, None, None, None, None);
+
+    // Connection failures walk the backend to down...
+    let mut rounds = 0;
+    while registry.backend_state("unreachable") != HealthState::Down {
+        rounds += 1;
+        assert!(rounds <= 3, "still not down after {rounds} reviews");
+        let resp = review().await;
+        assert_eq!(resp.summary.models_failed, 2);
+        if let Some(path) = resp.results_file {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    // ...after which its models are skipped without a dispatch.
+    let start = Instant::now();
+    let resp = review().await;
+    assert!(start.elapsed().as_secs() < 2);
+    assert_eq!(resp.results.len(), 2);
+    for r in &resp.results {
+        assert_eq!(r.reason.as_deref(), Some("backend_down"), "{r:?}");
+        assert_eq!(
+            r.error.as_deref(),
+            Some("skipped: backend unreachable is down")
+        );
+    }
+    assert_eq!(resp.summary.models_failed, 2);
+    assert!(
+        resp.warnings.iter().any(|w| w.starts_with(
+            "Skipped 2 model(s) on backends that are down: unreachable: down, probed again in"
+        )),
+        "{:?}",
+        resp.warnings
+    );
+    if let Some(path) = resp.results_file {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ReviewExecutor: fast models complete before cutoff
 // ---------------------------------------------------------------------------
@@ -428,11 +1154,8 @@ async fn executor_fast_models_complete_before_cutoff() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -440,7 +1163,7 @@ async fn executor_fast_models_complete_before_cutoff() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -453,12 +1176,37 @@ async fn executor_fast_models_complete_before_cutoff() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let start = Instant::now();
@@ -502,11 +1250,8 @@ async fn executor_mixed_fast_and_slow() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     // Slow (black-hole address)
@@ -520,11 +1265,8 @@ async fn executor_mixed_fast_and_slow() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -532,7 +1274,7 @@ async fn executor_mixed_fast_and_slow() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -545,12 +1287,37 @@ async fn executor_mixed_fast_and_slow() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let start = Instant::now();
@@ -589,7 +1356,7 @@ async fn executor_persists_results_to_disk() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -602,12 +1369,37 @@ async fn executor_persists_results_to_disk() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -629,7 +1421,7 @@ async fn executor_persists_results_to_disk() {
     );
 
     let path = resp.results_file.as_ref().unwrap();
-    assert!(path.starts_with(".squall/reviews/"));
+    assert!(path.starts_with(&*reviews_dir().to_string_lossy()));
     assert!(path.ends_with(".json"));
 
     // File should exist and be valid JSON
@@ -666,6 +1458,7 @@ fn review_response_wraps_in_pal_without_is_error() {
             model_used: "multi".to_string(),
             provider_used: "multi".to_string(),
             duration_seconds: 0.1,
+            usage: None,
         },
     );
 
@@ -720,7 +1513,7 @@ async fn executor_clamps_huge_timeout() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -733,12 +1526,37 @@ async fn executor_clamps_huge_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     // Should not panic — timeout is clamped internally
@@ -778,11 +1596,8 @@ async fn executor_deduplicates_model_ids() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -790,7 +1605,7 @@ async fn executor_deduplicates_model_ids() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -803,12 +1618,37 @@ async fn executor_deduplicates_model_ids() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -833,6 +1673,102 @@ async fn executor_deduplicates_model_ids() {
     );
 }
 
+#[tokio::test]
+async fn executor_expands_sampled_models() {
+    // A fast-fail model (connection refused immediately)
+    let mut models = HashMap::new();
+    models.insert(
+        "dupe-model".to_string(),
+        ModelEntry {
+            model_id: "dupe-model".to_string(),
+            provider: "test".to_string(),
+            backend: BackendConfig::Http {
+                base_url: "http://127.0.0.1:1/v1/chat".to_string(),
+                api_key: "fake".to_string(),
+                api_format: ApiFormat::OpenAi,
+            },
+            speed_tier: "fast".to_string(),
+            ..Default::default()
+        },
+    );
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let registry = Arc::new(Registry::from_config(config));
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
+
+    let req = ReviewRequest {
+        prompt: "hello".to_string(),
+        models: Some(vec!["dupe-model#3".to_string(), "dupe-model#9".to_string()]),
+        timeout_secs: Some(5),
+        system_prompt: None,
+        temperature: None,
+        file_paths: None,
+        working_directory: None,
+        diff: None,
+        per_model_system_prompts: None,
+        per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
+        deep: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        context_format: None,
+        response_format: None,
+        investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
+    };
+
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &MemoryStore::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+
+    // Three samples, each its own result; the out-of-range count is skipped.
+    let mut labels: Vec<&str> = resp.results.iter().map(|r| r.model.as_str()).collect();
+    labels.sort();
+    assert_eq!(labels, vec!["dupe-model#1", "dupe-model#2", "dupe-model#3"]);
+    assert!(resp.not_started.is_empty(), "{:?}", resp.not_started);
+    assert!(
+        resp.warnings.iter().any(|w| w.contains("dupe-model#9")),
+        "{:?}",
+        resp.warnings
+    );
+    // Failed samples leave nothing to vote on.
+    assert_eq!(resp.consistency.len(), 1);
+    assert_eq!(resp.consistency[0].responded, 0);
+}
+
 // ---------------------------------------------------------------------------
 // Bug C2: MAX_MODELS not enforced on None branch (all configured models)
 // ---------------------------------------------------------------------------
@@ -853,11 +1789,8 @@ async fn executor_caps_all_configured_models() {
                     api_key: "fake".to_string(),
                     api_format: ApiFormat::OpenAi,
                 },
-                description: String::new(),
-                strengths: vec![],
-                weaknesses: vec![],
                 speed_tier: "fast".to_string(),
-                precision_tier: "medium".to_string(),
+                ..Default::default()
             },
         );
     }
@@ -866,7 +1799,7 @@ async fn executor_caps_all_configured_models() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     // models: None → use all configured → should still be capped
     let req = ReviewRequest {
@@ -880,12 +1813,37 @@ async fn executor_caps_all_configured_models() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -923,7 +1881,7 @@ async fn persist_filename_includes_pid() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -936,12 +1894,37 @@ async fn persist_filename_includes_pid() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1040,11 +2023,8 @@ async fn executor_with_per_model_system_prompts() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1052,7 +2032,7 @@ async fn executor_with_per_model_system_prompts() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "review this".to_string(),
@@ -1068,12 +2048,37 @@ async fn executor_with_per_model_system_prompts() {
             "You are a security expert".to_string(),
         )])),
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1117,12 +2122,37 @@ fn deep_mode_sets_600s_effective_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(true),
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
     assert_eq!(
         req.effective_timeout_secs(),
@@ -1154,12 +2184,37 @@ fn deep_mode_does_not_override_explicit_values() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(true),
         max_tokens: Some(4096),
         reasoning_effort: Some(squall::tools::enums::ReasoningEffort::Medium),
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
     // Explicit timeout_secs overrides deep default (fix: was clamped to 600).
     assert_eq!(req.effective_timeout_secs(), 300);
@@ -1185,12 +2240,37 @@ fn deep_false_uses_normal_defaults() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(false),
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
     assert_eq!(req.effective_timeout_secs(), 180);
     assert_eq!(req.effective_reasoning_effort(), None);
@@ -1215,11 +2295,8 @@ async fn deep_mode_executor_uses_effective_timeout() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1227,7 +2304,7 @@ async fn deep_mode_executor_uses_effective_timeout() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1240,12 +2317,37 @@ async fn deep_mode_executor_uses_effective_timeout() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: Some(true), // should raise to 600s
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1287,11 +2389,8 @@ async fn per_model_timeout_does_not_extend_global_cutoff() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     models.insert(
@@ -1304,11 +2403,8 @@ async fn per_model_timeout_does_not_extend_global_cutoff() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1316,7 +2412,7 @@ async fn per_model_timeout_does_not_extend_global_cutoff() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let mut per_model = HashMap::new();
     per_model.insert("slow-model".to_string(), 600u64); // per-model: 600s
@@ -1332,12 +2428,37 @@ async fn per_model_timeout_does_not_extend_global_cutoff() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: Some(per_model),
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let start = Instant::now();
@@ -1401,11 +2522,8 @@ async fn warnings_surface_unknown_per_model_system_prompt_keys() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1413,7 +2531,7 @@ async fn warnings_surface_unknown_per_model_system_prompt_keys() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1429,12 +2547,37 @@ async fn warnings_surface_unknown_per_model_system_prompt_keys() {
             ("typo-model".to_string(), "orphan lens".to_string()),
         ])),
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1474,11 +2617,8 @@ async fn warnings_surface_unknown_per_model_timeout_keys() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1486,7 +2626,7 @@ async fn warnings_surface_unknown_per_model_timeout_keys() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1502,12 +2642,37 @@ async fn warnings_surface_unknown_per_model_timeout_keys() {
             ("real-model".to_string(), 60u64),
             ("ghost-model".to_string(), 120u64),
         ])),
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1530,6 +2695,116 @@ async fn warnings_surface_unknown_per_model_timeout_keys() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// 2b. per_model_temperature / per_model_max_tokens keys resolve like the others
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn warnings_surface_per_model_sampling_keys() {
+    let mut models = HashMap::new();
+    models.insert(
+        "real-model".to_string(),
+        ModelEntry {
+            model_id: "real-model".to_string(),
+            provider: "test".to_string(),
+            backend: BackendConfig::Http {
+                base_url: "http://127.0.0.1:1/v1/chat".to_string(),
+                api_key: "fake".to_string(),
+                api_format: ApiFormat::OpenAi,
+            },
+            speed_tier: "fast".to_string(),
+            ..Default::default()
+        },
+    );
+    let config = Config {
+        models,
+        ..Default::default()
+    };
+    let registry = Arc::new(Registry::from_config(config));
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
+
+    let req = ReviewRequest {
+        prompt: "hello".to_string(),
+        models: Some(vec!["real-model".to_string()]),
+        timeout_secs: Some(5),
+        system_prompt: None,
+        temperature: None,
+        file_paths: None,
+        working_directory: None,
+        diff: None,
+        per_model_system_prompts: None,
+        per_model_timeout_secs: None,
+        per_model_temperature: Some(HashMap::from([
+            ("Real-Model".to_string(), 1.0),
+            ("ghost-model".to_string(), 0.2),
+        ])),
+        per_model_max_tokens: Some(HashMap::from([("phantom-model".to_string(), 4096u64)])),
+        deep: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        context_format: None,
+        response_format: None,
+        investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
+    };
+
+    let resp = executor
+        .execute(
+            &req,
+            req.prompt.clone(),
+            &MemoryStore::new(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+    assert!(
+        resp.warnings
+            .iter()
+            .any(|w| w.contains("per_model_temperature")
+                && w.contains("'Real-Model' resolved to 'real-model'")),
+        "Should report fuzzy-resolved per_model_temperature key. Warnings: {:?}",
+        resp.warnings,
+    );
+    assert!(
+        resp.warnings
+            .iter()
+            .any(|w| w.contains("per_model_temperature") && w.contains("ghost-model")),
+        "Should warn about unknown per_model_temperature key. Warnings: {:?}",
+        resp.warnings,
+    );
+    assert!(
+        resp.warnings
+            .iter()
+            .any(|w| w.contains("per_model_max_tokens") && w.contains("phantom-model")),
+        "Should warn about unknown per_model_max_tokens key. Warnings: {:?}",
+        resp.warnings,
+    );
+}
+
 // ---------------------------------------------------------------------------
 // 3. MAX_MODELS truncation surfaces as warning with dropped model names
 // ---------------------------------------------------------------------------
@@ -1541,7 +2816,7 @@ async fn warnings_surface_max_models_truncation() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     // Request MAX_MODELS + 3 unique models (all unknown — that's fine, we're testing the warning)
     let model_names: Vec<String> = (0..squall::review::MAX_MODELS + 3)
@@ -1559,12 +2834,37 @@ async fn warnings_surface_max_models_truncation() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1609,11 +2909,8 @@ async fn summary_counts_match_results() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -1621,7 +2918,7 @@ async fn summary_counts_match_results() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1634,12 +2931,37 @@ async fn summary_counts_match_results() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1682,11 +3004,8 @@ async fn summary_buckets_reconcile() {
                     api_key: "fake".to_string(),
                     api_format: ApiFormat::OpenAi,
                 },
-                description: String::new(),
-                strengths: vec![],
-                weaknesses: vec![],
                 speed_tier: "fast".to_string(),
-                precision_tier: "medium".to_string(),
+                ..Default::default()
             },
         );
     }
@@ -1695,7 +3014,7 @@ async fn summary_buckets_reconcile() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1712,12 +3031,37 @@ async fn summary_buckets_reconcile() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1758,7 +3102,7 @@ async fn investigation_context_persisted() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -1771,12 +3115,37 @@ async fn investigation_context_persisted() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: Some("Found potential race condition in auth flow".to_string()),
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1813,7 +3182,7 @@ async fn investigation_context_clamped() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     // Create a context larger than 32KB
     let big_context = "x".repeat(40_000);
@@ -1829,12 +3198,37 @@ async fn investigation_context_clamped() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: Some(big_context),
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -1879,7 +3273,7 @@ async fn investigation_context_clamped_utf8_boundary() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     // Build a string where the 32KB boundary falls inside a multi-byte char.
     // U+1F600 (😀) is 4 bytes in UTF-8. Fill up to just before 32KB, then add emoji
@@ -1901,12 +3295,37 @@ async fn investigation_context_clamped_utf8_boundary() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: Some(big_context),
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     // This should NOT panic (previously would on &ctx[..MAX])
@@ -1957,6 +3376,7 @@ async fn investigation_context_clamped_utf8_boundary() {
 #[test]
 fn empty_warnings_omitted_in_json() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -1965,8 +3385,24 @@ fn empty_warnings_omitted_in_json() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
     let json = serde_json::to_string(&resp).unwrap();
     assert!(
@@ -2003,11 +3439,8 @@ async fn persisted_json_contains_files_skipped() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2015,7 +3448,7 @@ async fn persisted_json_contains_files_skipped() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2028,12 +3461,37 @@ async fn persisted_json_contains_files_skipped() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let skipped = Some(vec!["big_file.rs (50000B)".to_string()]);
@@ -2082,7 +3540,7 @@ async fn truncation_warning_reports_actual_boundary() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     // Force a walkback: 32766 ASCII bytes + 4-byte emoji = 32770 bytes.
     // Truncation at 32768 falls inside the emoji, walks back to 32766.
@@ -2101,12 +3559,37 @@ async fn truncation_warning_reports_actual_boundary() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: Some(big_context.clone()),
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -2181,11 +3664,8 @@ async fn zero_per_model_timeout_warns() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2193,7 +3673,7 @@ async fn zero_per_model_timeout_warns() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2206,12 +3686,37 @@ async fn zero_per_model_timeout_warns() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: Some(HashMap::from([("test-model".to_string(), 0u64)])),
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -2258,11 +3763,8 @@ async fn persisted_json_contains_files_errors() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2270,7 +3772,7 @@ async fn persisted_json_contains_files_errors() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2283,12 +3785,37 @@ async fn persisted_json_contains_files_errors() {
         diff: None,
         per_model_system_prompts: None,
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let file_errors = Some(vec![
@@ -2350,11 +3877,8 @@ async fn per_model_system_prompt_case_insensitive_resolution() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2362,7 +3886,7 @@ async fn per_model_system_prompt_case_insensitive_resolution() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2378,12 +3902,37 @@ async fn per_model_system_prompt_case_insensitive_resolution() {
             "security lens".to_string(),
         )])),
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -2425,11 +3974,8 @@ async fn per_model_system_prompt_resolves_provider_model_id() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2437,7 +3983,7 @@ async fn per_model_system_prompt_resolves_provider_model_id() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2453,12 +3999,37 @@ async fn per_model_system_prompt_resolves_provider_model_id() {
             "architecture lens".to_string(),
         )])),
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -2494,11 +4065,8 @@ async fn per_model_system_prompt_exact_match_no_warning() {
                 api_key: "fake".to_string(),
                 api_format: ApiFormat::OpenAi,
             },
-            description: String::new(),
-            strengths: vec![],
-            weaknesses: vec![],
             speed_tier: "fast".to_string(),
-            precision_tier: "medium".to_string(),
+            ..Default::default()
         },
     );
     let config = Config {
@@ -2506,7 +4074,7 @@ async fn per_model_system_prompt_exact_match_no_warning() {
         ..Default::default()
     };
     let registry = Arc::new(Registry::from_config(config));
-    let executor = ReviewExecutor::new(registry);
+    let executor = ReviewExecutor::new(registry).with_reviews_dir(reviews_dir());
 
     let req = ReviewRequest {
         prompt: "hello".to_string(),
@@ -2522,12 +4090,37 @@ async fn per_model_system_prompt_exact_match_no_warning() {
             "exact lens".to_string(),
         )])),
         per_model_timeout_secs: None,
+        per_model_temperature: None,
+        per_model_max_tokens: None,
         deep: None,
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
         response_format: None,
         investigation_context: None,
+        incremental: None,
+        expected_format: None,
+        expected_sections: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
+        straggler_grace_secs: None,
+        per_model_straggler_grace_secs: None,
+        straggler_policy: None,
+        tags: None,
+        blame: None,
+        language_profile: None,
+        packages: None,
+        personas: None,
+        samples: None,
+        triage_then_deep: None,
+        gating: None,
+        rubric: None,
+        dependency_risk: None,
+        dependency_advisories: None,
+        red_team: None,
+        red_team_models: None,
+        reprompt_unanswered: None,
     };
 
     let resp = executor
@@ -2556,6 +4149,7 @@ async fn per_model_system_prompt_exact_match_no_warning() {
 #[test]
 fn review_to_markdown_returns_summary_header() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -2564,6 +4158,7 @@ fn review_to_markdown_returns_summary_header() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary {
             models_requested: 3,
@@ -2576,6 +4171,21 @@ fn review_to_markdown_returns_summary_header() {
             auto_selected: false,
             selection_reasoning: None,
         },
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let md = resp.to_markdown(false);
@@ -2596,6 +4206,7 @@ fn review_to_markdown_returns_summary_header() {
 #[test]
 fn review_to_markdown_concise_omits_model_text() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![ReviewModelResult {
             model: "grok".to_string(),
             provider: "xai".to_string(),
@@ -2605,6 +4216,17 @@ fn review_to_markdown_concise_omits_model_text() {
             reason: None,
             latency_ms: 500,
             partial: false,
+            timing: Default::default(),
+            quotes_checked: 0,
+            quality_flags: Vec::new(),
+            format_compliance: None,
+            rubric: None,
+            usage: None,
+            provider_metadata: Default::default(),
+            late: false,
+            usable: None,
+            repaired: false,
+            duplicate_of: None,
         }],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -2613,6 +4235,7 @@ fn review_to_markdown_concise_omits_model_text() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary {
             models_requested: 1,
@@ -2625,6 +4248,21 @@ fn review_to_markdown_concise_omits_model_text() {
             auto_selected: false,
             selection_reasoning: None,
         },
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let concise = resp.to_markdown(true);
@@ -2648,9 +4286,83 @@ fn review_to_markdown_concise_omits_model_text() {
     );
 }
 
+#[test]
+fn near_identical_responses_are_shown_once() {
+    let result = |model: &str, text: &str| ReviewModelResult {
+        model: model.to_string(),
+        provider: "test".to_string(),
+        status: ModelStatus::Success,
+        response: Some(text.to_string()),
+        error: None,
+        reason: None,
+        latency_ms: 700,
+        partial: false,
+        timing: Default::default(),
+        quotes_checked: 0,
+        quality_flags: Vec::new(),
+        format_compliance: None,
+        rubric: None,
+        usage: None,
+        provider_metadata: Default::default(),
+        late: false,
+        usable: None,
+        repaired: false,
+        duplicate_of: None,
+    };
+    let review = "The parser ignores trailing whitespace inside quoted strings, \
+                  which breaks round-tripping of configuration values.";
+    let mut results = vec![
+        result("model-a", review),
+        result("model-b", "Looks fine overall; consider adding tests."),
+        result("model-a-next", &format!("{review} ")),
+    ];
+    squall::review::mark_duplicate_responses(&mut results);
+    assert_eq!(results[1].duplicate_of, None);
+    assert_eq!(results[2].duplicate_of.as_deref(), Some("model-a"));
+
+    let resp = ReviewResponse {
+        review_id: String::new(),
+        results,
+        not_started: vec![],
+        cutoff_seconds: 180,
+        elapsed_ms: 800,
+        results_file: None,
+        persist_error: None,
+        files_skipped: None,
+        files_errors: None,
+        files_sent: Default::default(),
+        warnings: vec![],
+        summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
+    };
+    let md = resp.to_markdown(false);
+    assert!(
+        md.contains("### model-a (700ms, same as model-a-next (700ms))"),
+        "{md}"
+    );
+    assert!(!md.contains("### model-a-next"), "{md}");
+    assert_eq!(md.matches("round-tripping").count(), 1, "{md}");
+    assert!(md.contains("### model-b"), "{md}");
+}
+
 #[test]
 fn review_to_markdown_shows_warnings() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec!["missing-model".to_string()],
         cutoff_seconds: 180,
@@ -2659,8 +4371,24 @@ fn review_to_markdown_shows_warnings() {
         persist_error: None,
         files_skipped: None,
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec!["Unknown key 'typo' in per_model_system_prompts".to_string()],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let md = resp.to_markdown(false);
@@ -2679,6 +4407,7 @@ fn review_to_markdown_shows_warnings() {
 #[test]
 fn review_to_markdown_shows_files_skipped() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -2687,8 +4416,24 @@ fn review_to_markdown_shows_files_skipped() {
         persist_error: None,
         files_skipped: Some(vec!["large_file.rs".to_string(), "huge.rs".to_string()]),
         files_errors: None,
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let md = resp.to_markdown(false);
@@ -2705,6 +4450,7 @@ fn review_to_markdown_shows_files_skipped() {
 #[test]
 fn review_to_markdown_shows_files_errors() {
     let resp = ReviewResponse {
+        review_id: String::new(),
         results: vec![],
         not_started: vec![],
         cutoff_seconds: 180,
@@ -2713,8 +4459,24 @@ fn review_to_markdown_shows_files_errors() {
         persist_error: None,
         files_skipped: None,
         files_errors: Some(vec!["missing.rs: not found".to_string()]),
+        files_sent: Default::default(),
         warnings: vec![],
         summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
     };
 
     let md = resp.to_markdown(false);
@@ -2723,3 +4485,98 @@ fn review_to_markdown_shows_files_errors() {
         "Markdown should surface file errors"
     );
 }
+
+#[test]
+fn review_to_markdown_shows_files_sent_only_when_models_differ() {
+    let mut resp = ReviewResponse {
+        review_id: String::new(),
+        results: vec![],
+        not_started: vec![],
+        cutoff_seconds: 180,
+        elapsed_ms: 100,
+        results_file: None,
+        persist_error: None,
+        files_skipped: None,
+        files_errors: None,
+        files_sent: [
+            ("grok".to_string(), vec!["a.rs".to_string()]),
+            ("gemini".to_string(), vec!["a.rs".to_string()]),
+        ]
+        .into(),
+        warnings: vec![],
+        summary: ReviewSummary::default(),
+        diff_size: None,
+        auto_memorized: vec![],
+        git: None,
+        incremental: None,
+        tags: Vec::new(),
+        language_profile: None,
+        repos: Vec::new(),
+        personas: Default::default(),
+        consistency: Vec::new(),
+        calibration: Vec::new(),
+        triage: None,
+        policy: Vec::new(),
+        supply_chain: None,
+        red_team: None,
+        question_coverage: Default::default(),
+    };
+    assert!(!resp.to_markdown(false).contains("Files sent per model"));
+
+    resp.files_sent.insert(
+        "mistral-large".to_string(),
+        vec!["a.rs (trimmed)".to_string()],
+    );
+    let md = resp.to_markdown(false);
+    assert!(md.contains("Files sent per model"));
+    assert!(md.contains("- mistral-large: a.rs (trimmed)"));
+
+    let json = serde_json::to_value(&resp).unwrap();
+    assert_eq!(json["files_sent"]["grok"][0], "a.rs");
+}
+
+// ---------------------------------------------------------------------------
+// Auto-memorize: parsing the cheap model's proposals
+// ---------------------------------------------------------------------------
+
+#[test]
+fn auto_memorize_parses_fenced_json_and_caps_entries() {
+    let reply = "Here you go:\n```json\n[\n\
+        {\"content\": \"Cache refresh races with invalidation\", \"tags\": [\"concurrency\"]},\n\
+        {\"content\": \"Upstream calls lack timeouts\"},\n\
+        {\"content\": \"  \"},\n\
+        {\"content\": \"Config reload is not atomic\", \"tags\": [\"auto\"]},\n\
+        {\"content\": \"Fourth lesson\"}\n\
+        ]\n```";
+    let entries = squall::review::parse_auto_memorize_entries(reply);
+    assert_eq!(entries.len(), squall::review::AUTO_MEMORIZE_MAX_ENTRIES);
+    assert_eq!(entries[0].content, "Cache refresh races with invalidation");
+    assert_eq!(entries[0].tags, vec!["concurrency", "auto"]);
+    assert_eq!(entries[1].tags, vec!["auto"]);
+    // Blank entries are dropped; an existing `auto` tag is not duplicated.
+    assert_eq!(entries[2].content, "Config reload is not atomic");
+    assert_eq!(entries[2].tags, vec!["auto"]);
+}
+
+#[test]
+fn auto_memorize_ignores_unparseable_replies() {
+    assert!(squall::review::parse_auto_memorize_entries("[]").is_empty());
+    assert!(squall::review::parse_auto_memorize_entries("Nothing worth remembering.").is_empty());
+    assert!(squall::review::parse_auto_memorize_entries("] not json [").is_empty());
+    assert!(squall::review::parse_auto_memorize_entries("[{\"text\": \"x\"}]").is_empty());
+}
+
+#[test]
+fn review_tags_are_trimmed_deduplicated_and_capped() {
+    use squall::review::{MAX_REVIEW_TAG_CHARS, MAX_REVIEW_TAGS, review_tags};
+    let tags: Vec<String> = [" PROJ-142 ", "pre-merge", "proj-142", "", "   "]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    assert_eq!(review_tags(&tags), vec!["PROJ-142", "pre-merge"]);
+
+    let many: Vec<String> = (0..30).map(|i| format!("t{i}")).collect();
+    assert_eq!(review_tags(&many).len(), MAX_REVIEW_TAGS);
+    let long = vec!["x".repeat(100)];
+    assert_eq!(review_tags(&long)[0].len(), MAX_REVIEW_TAG_CHARS);
+}
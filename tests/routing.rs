@@ -0,0 +1,135 @@
+//! Tests for the `classify` routing policy: core ensemble, hard-gate exclusion,
+//! lens-matched extras, and memory-driven timeouts.
+
+use std::collections::HashMap;
+
+use squall::config::Config;
+use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry, Registry};
+use squall::memory::MemoryStore;
+use squall::review::GateThresholds;
+use squall::routing::{Classification, recommend};
+use squall::tools::enums::{Depth, Lens};
+
+fn entry(speed_tier: &str, strengths: &[&str]) -> ModelEntry {
+    ModelEntry {
+        provider: "test".to_string(),
+        backend: BackendConfig::Http {
+            base_url: "http://127.0.0.1:1/v1/chat".to_string(),
+            api_key: "key".to_string(),
+            api_format: ApiFormat::OpenAi,
+        },
+        strengths: strengths.iter().map(|s| s.to_string()).collect(),
+        speed_tier: speed_tier.to_string(),
+        ..Default::default()
+    }
+}
+
+fn registry() -> Registry {
+    let mut models = HashMap::new();
+    for (name, speed, strengths) in [
+        ("slow-core", "medium", &[][..]),
+        ("fast-core", "fast", &[][..]),
+        ("flaky", "fast", &[][..]),
+        (
+            "sec-model",
+            "medium",
+            &["adversarial scenarios", "security"][..],
+        ),
+        (
+            "arch-model",
+            "medium",
+            &["clear architectural analysis"][..],
+        ),
+        ("plain-model", "medium", &[][..]),
+    ] {
+        let mut e = entry(speed, strengths);
+        e.model_id = name.to_string();
+        models.insert(name.to_string(), e);
+    }
+    Registry::from_config(Config {
+        models,
+        ..Default::default()
+    })
+}
+
+async fn stats_from(events: &str) -> HashMap<String, squall::memory::ModelGateStats> {
+    let dir = std::env::temp_dir().join(format!("squall-test-routing-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let content = format!(
+        "# Model Performance Profiles\n\n\
+         ## Summary\n(auto-generated)\n\n\
+         ## Recent Events\n\
+         | Timestamp | Model | Latency | Status | Partial | Reason | Tokens |\n\
+         |-----------|-------|---------|--------|---------|--------|--------|\n\
+         {events}"
+    );
+    std::fs::write(dir.join("models.md"), content).unwrap();
+    let stats = MemoryStore::with_base_dir(dir.clone())
+        .get_model_stats(None)
+        .await
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    stats
+}
+
+#[tokio::test]
+async fn recommend_applies_gate_lenses_and_latency() {
+    let mut events = String::new();
+    for i in 0..5 {
+        events.push_str(&format!(
+            "| 2026-02-23T10:0{i}:00Z | flaky | 10.0s | error | no | timeout | 1000 |\n\
+             | 2026-02-23T10:0{i}:00Z | slow-core | 250.0s | success | no | — | 1000 |\n"
+        ));
+    }
+    let stats = stats_from(&events).await;
+    let registry = registry();
+    let models = registry.list_models();
+    let core: Vec<String> = ["slow-core", "fast-core", "flaky"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let standard = recommend(
+        Classification {
+            depth: Depth::Standard,
+            lenses: vec![Lens::Security],
+            classified_by: "grok".to_string(),
+        },
+        &models,
+        &core,
+        Some(&stats),
+        &GateThresholds::default(),
+    );
+    // flaky is gated; sec-model matches the lens; arch-model wins the tie by name.
+    assert_eq!(
+        standard.models,
+        vec!["slow-core", "fast-core", "sec-model", "arch-model"]
+    );
+    assert!(
+        standard
+            .rationale
+            .iter()
+            .any(|r| r.starts_with("flaky: skipped (gated"))
+    );
+    assert_eq!(
+        standard.per_model_system_prompts["sec-model"],
+        Lens::Security.system_prompt()
+    );
+    // slow-core's p95 (250s) plus headroom beats the 180s default.
+    assert_eq!(standard.timeout_secs, 300);
+    assert!(!standard.deep);
+
+    let quick = recommend(
+        Classification {
+            depth: Depth::Quick,
+            lenses: vec![Lens::Correctness],
+            classified_by: "heuristic".to_string(),
+        },
+        &models,
+        &core,
+        Some(&stats),
+        &GateThresholds::default(),
+    );
+    assert_eq!(quick.models, vec!["fast-core"]);
+    assert_eq!(quick.timeout_secs, 180);
+}
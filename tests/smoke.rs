@@ -1,3 +1,4 @@
+use squall::dispatch::TokenUsage;
 use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry};
 use squall::response::{PalMetadata, PalToolResponse};
 
@@ -10,6 +11,7 @@ fn pal_response_success_serializes_correctly() {
             model_used: "grok-4-1-fast-reasoning".to_string(),
             provider_used: "xai".to_string(),
             duration_seconds: 4.2,
+            usage: None,
         },
     );
 
@@ -34,6 +36,7 @@ fn pal_response_error_serializes_correctly() {
             model_used: "foo".to_string(),
             provider_used: "unknown".to_string(),
             duration_seconds: 0.001,
+            usage: None,
         },
     );
 
@@ -44,6 +47,45 @@ fn pal_response_error_serializes_correctly() {
     assert_eq!(parsed["content"], "model not found: foo");
 }
 
+#[test]
+fn pal_metadata_flattens_token_usage() {
+    let response = PalToolResponse::success(
+        "ok".to_string(),
+        PalMetadata {
+            tool_name: "review".to_string(),
+            model_used: "multi".to_string(),
+            provider_used: "multi".to_string(),
+            duration_seconds: 1.0,
+            usage: Some(TokenUsage {
+                input_tokens: 1200,
+                output_tokens: 340,
+                estimated_cost: Some(0.0123),
+            }),
+        },
+    );
+    let parsed: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+    assert_eq!(parsed["metadata"]["input_tokens"], 1200);
+    assert_eq!(parsed["metadata"]["output_tokens"], 340);
+    assert_eq!(parsed["metadata"]["estimated_cost"], 0.0123);
+    assert!(parsed["metadata"].get("usage").is_none());
+
+    // Calls with no reported usage omit the fields entirely.
+    let response = PalToolResponse::success(
+        "ok".to_string(),
+        PalMetadata {
+            tool_name: "clink".to_string(),
+            model_used: "codex".to_string(),
+            provider_used: "codex".to_string(),
+            duration_seconds: 1.0,
+            usage: None,
+        },
+    );
+    let parsed: serde_json::Value =
+        serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+    assert!(parsed["metadata"].get("input_tokens").is_none());
+}
+
 #[test]
 fn model_entry_backend_types() {
     let http_entry = ModelEntry {
@@ -54,11 +96,8 @@ fn model_entry_backend_types() {
             api_key: "test-key".to_string(),
             api_format: ApiFormat::OpenAi,
         },
-        description: String::new(),
-        strengths: vec![],
-        weaknesses: vec![],
         speed_tier: "fast".to_string(),
-        precision_tier: "medium".to_string(),
+        ..Default::default()
     };
 
     assert!(matches!(http_entry.backend, BackendConfig::Http { .. }));
@@ -71,11 +110,8 @@ fn model_entry_backend_types() {
             executable: "gemini".to_string(),
             args_template: vec!["-o".to_string(), "json".to_string()],
         },
-        description: String::new(),
-        strengths: vec![],
-        weaknesses: vec![],
         speed_tier: "fast".to_string(),
-        precision_tier: "medium".to_string(),
+        ..Default::default()
     };
 
     assert!(matches!(cli_entry.backend, BackendConfig::Cli { .. }));
@@ -96,6 +132,9 @@ fn chat_request_default_model() {
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
     };
     assert_eq!(req.model_or_default(), "grok-4-1-fast-reasoning");
 
@@ -109,6 +148,9 @@ fn chat_request_default_model() {
         max_tokens: None,
         reasoning_effort: None,
         context_format: None,
+        urls: None,
+        priority: None,
+        extra_body: None,
     };
     assert_eq!(req.model_or_default(), "moonshotai/kimi-k2.5");
 }
@@ -161,6 +203,167 @@ fn listmodels_returns_capability_fields() {
     }
 }
 
+#[test]
+fn registry_resolves_aliases_and_maps_history() {
+    use squall::config::Config;
+    use squall::dispatch::registry::Registry;
+
+    let entry = ModelEntry {
+        model_id: "grok-4-1-fast-reasoning".to_string(),
+        provider: "xai".to_string(),
+        backend: BackendConfig::Http {
+            base_url: "https://api.x.ai/v1/chat/completions".to_string(),
+            api_key: "test-key".to_string(),
+            api_format: ApiFormat::OpenAi,
+        },
+        speed_tier: "fast".to_string(),
+        aliases: vec!["grok-3".to_string(), "grok-3-mini".to_string()],
+        deprecated_after: Some("2026-03-01".to_string()),
+        ..Default::default()
+    };
+    let registry = Registry::from_config(Config {
+        models: [("grok".to_string(), entry)].into(),
+        ..Default::default()
+    });
+
+    assert_eq!(registry.canonical_name("grok-3"), Some("grok"));
+    assert_eq!(registry.canonical_name("grok"), Some("grok"));
+    assert!(registry.canonical_name("grok-2").is_none());
+    assert_eq!(
+        registry.get("grok-3-mini").map(|e| e.model_id.as_str()),
+        Some("grok-4-1-fast-reasoning")
+    );
+    // Memory events logged under the old name count toward the current key.
+    let id_to_key = registry.model_id_to_key();
+    assert_eq!(id_to_key.get("grok-3").map(String::as_str), Some("grok"));
+
+    let entry = registry.get("grok").unwrap();
+    assert_eq!(entry.days_until_deprecation("2026-02-20"), Some(9));
+    assert_eq!(entry.days_until_deprecation("2026-03-04"), Some(-3));
+}
+
+#[test]
+fn listmodels_warns_about_deprecated_models() {
+    use squall::tools::listmodels::{ListModelsResponse, ModelInfo};
+
+    let model = |name: &str, days: Option<i64>| ModelInfo {
+        name: name.to_string(),
+        provider: "prov".to_string(),
+        backend: "http".to_string(),
+        description: "desc".to_string(),
+        strengths: vec![],
+        weaknesses: vec![],
+        speed_tier: "fast".to_string(),
+        precision_tier: "medium".to_string(),
+        aliases: vec![],
+        deprecated_after: days.map(|_| "2026-03-01".to_string()),
+        price_per_mtok_in: None,
+        price_per_mtok_out: None,
+        days_until_deprecation: days,
+    };
+    let response = ListModelsResponse {
+        models: vec![
+            model("soon", Some(9)),
+            model("gone", Some(-3)),
+            model("later", Some(120)),
+            model("stable", None),
+        ],
+        gate: Default::default(),
+        backends: vec![],
+    };
+
+    let warnings = response.deprecation_warnings();
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].starts_with("soon is deprecated after 2026-03-01 (9 days left)"));
+    assert!(warnings[1].starts_with("gone was deprecated after 2026-03-01"));
+    let md = response.to_markdown();
+    assert!(md.contains("**Deprecation warnings:**\n- soon"), "{md}");
+}
+
+#[tokio::test]
+async fn discover_models_reports_unconfigured_catalog_entries() {
+    use squall::config::{Config, ProviderEndpoint};
+    use squall::dispatch::registry::Registry;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Mock catalog: answers only requests carrying the expected key.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 8192];
+            let n = socket.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            let response = if !request.starts_with("get /v1/models ") {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            } else if request.contains("authorization: bearer good-key") {
+                let body = r#"{"data":[{"id":"grok-4"},{"id":"grok-3"},{"id":"grok-5"},{"id":"vision-1"}]}"#;
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            } else {
+                "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            };
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    let base_url = format!("http://127.0.0.1:{port}/v1/chat/completions");
+    let provider = |key: &str| ProviderEndpoint {
+        base_url: base_url.clone(),
+        api_key: key.to_string(),
+        api_format: ApiFormat::OpenAi,
+    };
+    let entry = ModelEntry {
+        model_id: "grok-4".to_string(),
+        provider: "good".to_string(),
+        backend: BackendConfig::Http {
+            base_url: base_url.clone(),
+            api_key: "good-key".to_string(),
+            api_format: ApiFormat::OpenAi,
+        },
+        speed_tier: "fast".to_string(),
+        aliases: vec!["grok-3".to_string()],
+        ..Default::default()
+    };
+    let registry = Registry::from_config(Config {
+        models: [("grok".to_string(), entry)].into(),
+        providers: [
+            ("good".to_string(), provider("good-key")),
+            ("revoked".to_string(), provider("old-key")),
+        ]
+        .into(),
+        ..Default::default()
+    });
+
+    let catalogs = squall::discover::discover(&registry, None, None).await;
+    assert_eq!(catalogs.len(), 2);
+    assert_eq!(catalogs[0].provider, "good");
+    assert_eq!(catalogs[0].total, 4);
+    assert_eq!(catalogs[0].known, 2, "model_id and alias are both known");
+    assert_eq!(catalogs[0].new_models, vec!["grok-5", "vision-1"]);
+    assert!(catalogs[0].error.is_none());
+    assert_eq!(catalogs[1].provider, "revoked");
+    assert!(catalogs[1].error.as_deref().unwrap().contains("HTTP 401"));
+
+    let only_good = ["good".to_string()];
+    let catalogs = squall::discover::discover(&registry, Some(&only_good), Some("GROK")).await;
+    assert_eq!(catalogs.len(), 1);
+    assert_eq!(catalogs[0].new_models, vec!["grok-5"]);
+
+    let md = squall::discover::to_markdown(&catalogs, true);
+    assert!(
+        md.contains("3 models available, 2 already configured, 1 new."),
+        "{md}"
+    );
+    assert!(md.contains("```toml\n[models.grok-5]\nmodel_id = \"grok-5\"\nprovider = \"good\""));
+}
+
 // ===========================================================================
 // Markdown responses
 // ===========================================================================
@@ -179,7 +382,14 @@ fn listmodels_returns_markdown_table() {
             weaknesses: vec![],
             speed_tier: "fast".to_string(),
             precision_tier: "medium".to_string(),
+            aliases: vec![],
+            deprecated_after: None,
+            price_per_mtok_in: None,
+            price_per_mtok_out: None,
+            days_until_deprecation: None,
         }],
+        gate: Default::default(),
+        backends: vec![],
     };
 
     let md = response.to_markdown();
@@ -187,6 +397,10 @@ fn listmodels_returns_markdown_table() {
     assert!(md.contains("test-model"), "Should contain model name");
     assert!(md.contains("test-provider"), "Should contain provider");
     assert!(!md.contains('{'), "Should not contain JSON braces");
+    assert!(
+        md.contains("**Hard gate:** reviews drop models with <70.0% success, >=5 samples"),
+        "Should show the effective gate thresholds: {md}"
+    );
 }
 
 #[test]
@@ -203,7 +417,14 @@ fn listmodels_markdown_escapes_pipes() {
             weaknesses: vec![],
             speed_tier: "fast".to_string(),
             precision_tier: "medium".to_string(),
+            aliases: vec![],
+            deprecated_after: None,
+            price_per_mtok_in: None,
+            price_per_mtok_out: None,
+            days_until_deprecation: None,
         }],
+        gate: Default::default(),
+        backends: vec![],
     };
 
     let md = response.to_markdown();
@@ -231,12 +452,23 @@ fn listmodels_markdown_escapes_newlines() {
             weaknesses: vec![],
             speed_tier: "fast".to_string(),
             precision_tier: "medium".to_string(),
+            aliases: vec![],
+            deprecated_after: None,
+            price_per_mtok_in: None,
+            price_per_mtok_out: None,
+            days_until_deprecation: None,
         }],
+        gate: Default::default(),
+        backends: vec![],
     };
 
     let md = response.to_markdown();
     // Each model must be a single table row — no raw newlines in cells
-    let data_lines: Vec<&str> = md.lines().skip(2).collect(); // skip header + separator
+    let data_lines: Vec<&str> = md
+        .lines()
+        .skip(2) // skip header + separator
+        .take_while(|l| l.starts_with('|'))
+        .collect();
     assert_eq!(data_lines.len(), 1, "Model entry should be a single row");
     assert!(
         !data_lines[0].contains('\n'),
@@ -258,7 +490,14 @@ fn listmodels_markdown_strips_carriage_returns() {
             weaknesses: vec![],
             speed_tier: "fast".to_string(),
             precision_tier: "medium".to_string(),
+            aliases: vec![],
+            deprecated_after: None,
+            price_per_mtok_in: None,
+            price_per_mtok_out: None,
+            days_until_deprecation: None,
         }],
+        gate: Default::default(),
+        backends: vec![],
     };
 
     let md = response.to_markdown();
@@ -266,7 +505,11 @@ fn listmodels_markdown_strips_carriage_returns() {
         !md.contains('\r'),
         "Markdown should not contain carriage returns"
     );
-    let data_lines: Vec<&str> = md.lines().skip(2).collect();
+    let data_lines: Vec<&str> = md
+        .lines()
+        .skip(2)
+        .take_while(|l| l.starts_with('|'))
+        .collect();
     assert_eq!(
         data_lines.len(),
         1,
@@ -274,6 +517,42 @@ fn listmodels_markdown_strips_carriage_returns() {
     );
 }
 
+#[test]
+fn listmodels_markdown_lists_unhealthy_backends() {
+    use squall::dispatch::health::{BackendHealth, HealthState};
+    use squall::tools::listmodels::ListModelsResponse;
+
+    let backend = |provider: &str, state, failures| BackendHealth {
+        provider: provider.to_string(),
+        state,
+        consecutive_failures: failures,
+        retry_in_secs: (state == HealthState::Down).then_some(42),
+        last_error: (failures > 0).then(|| "request timed out after 30000ms".to_string()),
+    };
+    let response = ListModelsResponse {
+        models: vec![],
+        gate: Default::default(),
+        backends: vec![
+            backend("together", HealthState::Healthy, 0),
+            backend("xai", HealthState::Down, 5),
+            backend("zai", HealthState::Degraded, 2),
+        ],
+    };
+
+    let md = response.to_markdown();
+    assert!(md.contains(
+        "**Backend health:**\n\
+         - xai: down, probed again in 42s (5 consecutive failures; \
+         last: request timed out after 30000ms)\n\
+         - zai: degraded (2 consecutive failures; last: request timed out after 30000ms)\n"
+    ));
+    assert!(!md.contains("together:"));
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["backends"][1]["state"], "down");
+    assert_eq!(json["backends"][0].get("retry_in_secs"), None);
+}
+
 // ===========================================================================
 // CLI name backward compatibility
 // ===========================================================================
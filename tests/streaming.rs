@@ -46,6 +46,10 @@ fn make_req(deadline_secs: u64) -> ProviderRequest {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     }
 }
 
@@ -61,6 +65,10 @@ fn make_req_with_cancel(deadline_secs: u64, token: CancellationToken) -> Provide
         reasoning_effort: None,
         cancellation_token: Some(token),
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     }
 }
 
@@ -110,6 +118,568 @@ async fn streaming_complete_response() {
     server.await.unwrap();
 }
 
+// ---------------------------------------------------------------------------
+// Timing breakdown: headers / TTFB / streaming phases are recorded
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn streaming_records_ttfb_breakdown() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        // Queueing delay between headers and first token
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        socket
+            .write_all(sse_chunk("Hello ").as_bytes())
+            .await
+            .unwrap();
+        // Generation time between first and last token
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        socket
+            .write_all(sse_chunk("world!").as_bytes())
+            .await
+            .unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+    });
+
+    let dispatch = HttpDispatch::new();
+    let req = make_req(30);
+
+    let result = dispatch
+        .query_model(
+            &req,
+            "test",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    let headers = result.timing.headers_ms.expect("headers_ms recorded");
+    let ttfb = result.timing.ttfb_ms.expect("ttfb_ms recorded");
+    let stream = result.timing.stream_ms.expect("stream_ms recorded");
+    assert!(
+        ttfb >= 300,
+        "TTFB should include queueing delay, got {ttfb}ms"
+    );
+    assert!(
+        headers <= ttfb,
+        "headers precede first byte: {headers} > {ttfb}"
+    );
+    assert!(
+        stream >= 200,
+        "streaming phase should span generation, got {stream}ms"
+    );
+
+    server.await.unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Token usage: OpenAI reports it in a final chunk, Anthropic across events
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn provider_client_pins_host_to_configured_address() {
+    use squall::dispatch::http::{HttpClientSettings, ResolveOverride};
+    use std::collections::HashMap;
+
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let n = socket.read(&mut buf).await.unwrap();
+        let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+        assert!(request.contains("host: gateway.corp.invalid"), "{request}");
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket
+            .write_all(sse_chunk("pinned").as_bytes())
+            .await
+            .unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+    });
+
+    // `.invalid` never resolves, so only the pinned address can answer.
+    let settings = HttpClientSettings {
+        resolve: Some(ResolveOverride {
+            host: "gateway.corp.invalid".to_string(),
+            addrs: vec!["127.0.0.1".parse().unwrap()],
+        }),
+        ..Default::default()
+    };
+    let dispatch =
+        HttpDispatch::new().with_client_settings(&HashMap::from([("corp".to_string(), settings)]));
+    let result = dispatch
+        .query_model(
+            &make_req(30),
+            "corp",
+            &format!("http://gateway.corp.invalid:{port}/v1/chat"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.text, "pinned");
+    server.await.unwrap();
+
+    // Other providers keep the system resolver.
+    let err = dispatch
+        .query_model(
+            &make_req(30),
+            "public",
+            &format!("http://gateway.corp.invalid:{port}/v1/chat"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await;
+    assert!(err.is_err());
+}
+
+/// Read one HTTP request: lowercased head and raw body (by Content-Length).
+async fn read_request(socket: &mut tokio::net::TcpStream) -> (String, Vec<u8>) {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        if n == 0 {
+            return (String::from_utf8_lossy(&data).to_lowercase(), Vec::new());
+        }
+        data.extend_from_slice(&buf[..n]);
+        let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&data[..end]).to_lowercase();
+        let len = head
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length: "))
+            .map_or(0, |v| v.trim().parse().unwrap());
+        if data.len() >= end + 4 + len {
+            return (head, data[end + 4..].to_vec());
+        }
+    }
+}
+
+#[tokio::test]
+async fn provider_extra_headers_are_sent_with_every_request() {
+    use squall::dispatch::http::HttpClientSettings;
+    use std::collections::HashMap;
+
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (head, _) = read_request(&mut socket).await;
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket.write_all(sse_chunk("ok").as_bytes()).await.unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+        head
+    });
+
+    let settings = HttpClientSettings {
+        extra_headers: [
+            ("OpenAI-Organization".to_string(), "org-42".to_string()),
+            ("Authorization".to_string(), "Bearer other".to_string()),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    HttpDispatch::new()
+        .with_client_settings(&HashMap::from([("gw".to_string(), settings)]))
+        .query_model(
+            &make_req(30),
+            "gw",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "key",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    let head = server.await.unwrap();
+    assert!(head.contains("openai-organization: org-42"), "{head}");
+    assert!(
+        head.contains("authorization: bearer key"),
+        "request headers win: {head}"
+    );
+    assert!(!head.contains("bearer other"), "{head}");
+}
+
+#[tokio::test]
+async fn ping_gets_the_catalog_and_fails_only_on_broken_backends() {
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let mut heads = Vec::new();
+        for status in [
+            "404 Not Found",
+            "401 Unauthorized",
+            "503 Service Unavailable",
+        ] {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let (head, _) = read_request(&mut socket).await;
+            heads.push(head);
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+        heads
+    });
+
+    let http = HttpDispatch::new();
+    let base_url = format!("http://127.0.0.1:{port}/v1/chat/completions");
+    let ping = || {
+        http.ping(
+            "gw",
+            &base_url,
+            "key",
+            &ApiFormat::OpenAi,
+            Duration::from_secs(10),
+        )
+    };
+    // No catalog still means the backend answered.
+    ping().await.unwrap();
+    assert!(matches!(ping().await, Err(SquallError::AuthFailed { .. })));
+    assert!(matches!(
+        ping().await,
+        Err(SquallError::Upstream {
+            status: Some(503),
+            ..
+        })
+    ));
+
+    let heads = server.await.unwrap();
+    assert!(heads[0].starts_with("get /v1/models "), "{}", heads[0]);
+    assert!(
+        heads[0].contains("authorization: bearer key"),
+        "{}",
+        heads[0]
+    );
+}
+
+#[tokio::test]
+async fn extra_body_is_merged_into_payload_except_reserved_keys() {
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (_, body) = read_request(&mut socket).await;
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket.write_all(sse_chunk("ok").as_bytes()).await.unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+        body
+    });
+
+    let mut req = make_req(30);
+    req.temperature = Some(0.5);
+    req.extra_body = Some(serde_json::json!({
+        "search_parameters": {"mode": "auto"},
+        "temperature": 0.1,
+        "model": "other-model",
+        "stream": false,
+    }));
+    HttpDispatch::new()
+        .query_model(
+            &req,
+            "xai",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "key",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    let body: serde_json::Value = serde_json::from_slice(&server.await.unwrap()).unwrap();
+    assert_eq!(body["search_parameters"]["mode"], "auto");
+    assert_eq!(
+        body["temperature"], 0.1,
+        "extra_body overrides built fields"
+    );
+    assert_eq!(
+        body["model"], "test-model",
+        "reserved keys are not overridden"
+    );
+    assert_eq!(body["stream"], true);
+
+    // Request extra_body over model config: nested objects merge, null removes.
+    let mut merged = serde_json::json!({"provider": {"order": ["a"], "sort": "price"}, "x": 1});
+    squall::dispatch::http::merge_json(
+        &mut merged,
+        &serde_json::json!({"provider": {"sort": "latency"}, "x": null}),
+    );
+    assert_eq!(
+        merged,
+        serde_json::json!({"provider": {"order": ["a"], "sort": "latency"}})
+    );
+}
+
+#[tokio::test]
+async fn oauth_signer_replaces_static_key_and_caches_token() {
+    use squall::dispatch::signing::OAuthClientCredentials;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Token endpoint: counts fetches.
+    let (token_listener, token_port) = mock_listener().await;
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let counter = fetches.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = token_listener.accept().await {
+            let (_, body) = read_request(&mut socket).await;
+            assert!(String::from_utf8_lossy(&body).contains("grant_type=client_credentials"));
+            counter.fetch_add(1, Ordering::SeqCst);
+            let json = r#"{"access_token":"tok-1","token_type":"bearer","expires_in":3600}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{json}",
+                json.len()
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    // Gateway: answers only the OAuth token, never the static key.
+    let (listener, port) = mock_listener().await;
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let (head, _) = read_request(&mut socket).await;
+            assert!(head.contains("authorization: bearer tok-1"), "{head}");
+            assert!(!head.contains("static-key"), "{head}");
+            let _ = socket.write_all(SSE_HEADERS).await;
+            let _ = socket.write_all(sse_chunk("signed").as_bytes()).await;
+            let _ = socket.write_all(SSE_DONE).await;
+        }
+    });
+
+    let signer = OAuthClientCredentials::new(
+        format!("http://127.0.0.1:{token_port}/oauth/token"),
+        "squall".to_string(),
+        "client-secret".to_string(),
+        Some("llm".to_string()),
+    );
+    let dispatch = HttpDispatch::new().with_signer("gateway", Arc::new(signer));
+    for _ in 0..2 {
+        let result = dispatch
+            .query_model(
+                &make_req(30),
+                "gateway",
+                &format!("http://127.0.0.1:{port}/v1/chat"),
+                "static-key",
+                &ApiFormat::OpenAi,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.text, "signed");
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 1, "token is cached");
+}
+
+#[tokio::test]
+async fn hmac_signer_signs_request_body() {
+    use squall::dispatch::signing::HmacSigner;
+    use std::sync::Arc;
+
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (head, body) = read_request(&mut socket).await;
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket.write_all(sse_chunk("ok").as_bytes()).await.unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+        (head, body)
+    });
+
+    let signer = HmacSigner::new(b"shared", "x-gw-signature", "x-gw-timestamp").unwrap();
+    let dispatch = HttpDispatch::new().with_signer(
+        "gateway",
+        Arc::new(HmacSigner::new(b"shared", "x-gw-signature", "x-gw-timestamp").unwrap()),
+    );
+    dispatch
+        .query_model(
+            &make_req(30),
+            "gateway",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "key",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    let (head, body) = server.await.unwrap();
+    let header = |name: &str| {
+        head.lines()
+            .find_map(|l| l.strip_prefix(&format!("{name}: ")))
+            .unwrap_or_else(|| panic!("missing {name}: {head}"))
+            .to_string()
+    };
+    let timestamp: u64 = header("x-gw-timestamp").parse().unwrap();
+    assert_eq!(header("x-gw-signature"), signer.signature(timestamp, &body));
+}
+
+#[tokio::test]
+async fn streaming_captures_openai_usage() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket.write_all(sse_chunk("Hi").as_bytes()).await.unwrap();
+        socket
+            .write_all(
+                b"data: {\"choices\":[],\"usage\":{\"prompt_tokens\":120,\"completion_tokens\":7}}\n\n",
+            )
+            .await
+            .unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+    });
+
+    let result = HttpDispatch::new()
+        .query_model(
+            &make_req(30),
+            "test",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.text, "Hi");
+    let usage = result.usage.expect("usage captured");
+    assert_eq!((usage.input_tokens, usage.output_tokens), (120, 7));
+    assert_eq!(usage.estimated_cost, None);
+
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn streaming_captures_anthropic_usage() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        socket
+            .write_all(
+                b"event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":42,\"output_tokens\":1}}}\n\n\
+                  event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n\
+                  event: message_delta\ndata: {\"type\":\"message_delta\",\"usage\":{\"output_tokens\":15}}\n\n\
+                  event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+            )
+            .await
+            .unwrap();
+    });
+
+    let result = HttpDispatch::new()
+        .query_model(
+            &make_req(30),
+            "test",
+            &format!("http://127.0.0.1:{port}/v1/messages"),
+            "fake",
+            &ApiFormat::Anthropic,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.text, "Hi");
+    let usage = result.usage.expect("usage captured");
+    assert_eq!((usage.input_tokens, usage.output_tokens), (42, 15));
+
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn streaming_captures_openai_provider_metadata() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  x-request-id: req_abc123\r\nConnection: close\r\n\r\n\
+                  data: {\"id\":\"chatcmpl-9x\",\"model\":\"gpt-4o-2024-08-06\",\"system_fingerprint\":\"fp_44709d6fcb\",\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+                  data: {\"id\":\"chatcmpl-other\",\"model\":\"other\",\"choices\":[{\"delta\":{\"content\":\"ok\"}}]}\n\n",
+            )
+            .await
+            .unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+    });
+
+    let result = HttpDispatch::new()
+        .query_model(
+            &make_req(30),
+            "openai",
+            &format!("http://127.0.0.1:{port}/v1/chat/completions"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.text, "ok");
+    let meta = result.provider_metadata;
+    assert_eq!(meta.request_id.as_deref(), Some("req_abc123"));
+    assert_eq!(meta.response_id.as_deref(), Some("chatcmpl-9x"));
+    assert_eq!(meta.served_model.as_deref(), Some("gpt-4o-2024-08-06"));
+    assert_eq!(meta.system_fingerprint.as_deref(), Some("fp_44709d6fcb"));
+
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn streaming_captures_anthropic_provider_metadata() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  request-id: req_011CQ\r\nConnection: close\r\n\r\n\
+                  event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_01X\",\"model\":\"claude-opus-4-6-20260101\"}}\n\n\
+                  event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n\
+                  event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+            )
+            .await
+            .unwrap();
+    });
+
+    let result = HttpDispatch::new()
+        .query_model(
+            &make_req(30),
+            "anthropic",
+            &format!("http://127.0.0.1:{port}/v1/messages"),
+            "fake",
+            &ApiFormat::Anthropic,
+        )
+        .await
+        .unwrap();
+
+    let meta = result.provider_metadata;
+    assert_eq!(meta.request_id.as_deref(), Some("req_011CQ"));
+    assert_eq!(meta.response_id.as_deref(), Some("msg_01X"));
+    assert_eq!(
+        meta.served_model.as_deref(),
+        Some("claude-opus-4-6-20260101")
+    );
+    assert_eq!(meta.system_fingerprint, None);
+
+    server.await.unwrap();
+}
+
 // ---------------------------------------------------------------------------
 // Partial result on deadline expiry
 // ---------------------------------------------------------------------------
@@ -672,6 +1242,10 @@ async fn streaming_reasoning_model_survives_stall() {
         reasoning_effort: Some("high".to_string()),
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = dispatch
@@ -751,6 +1325,10 @@ async fn streaming_reasoning_model_survives_pre_first_token_silence() {
         reasoning_effort: Some("high".to_string()),
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = dispatch
@@ -854,6 +1432,10 @@ async fn anthropic_content_block_delta_parsing() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = http
@@ -902,6 +1484,10 @@ async fn anthropic_stream_done_on_message_stop() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = http
@@ -964,6 +1550,10 @@ async fn anthropic_request_uses_x_api_key_header() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = http
@@ -1121,6 +1711,10 @@ async fn openai_sends_max_completion_tokens() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let _ = dispatch
@@ -1174,6 +1768,10 @@ async fn non_openai_sends_max_tokens() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let _ = dispatch
@@ -1217,6 +1815,10 @@ async fn openai_parser_unchanged_after_refactor() {
         reasoning_effort: None,
         cancellation_token: None,
         stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
     };
 
     let result = http
@@ -1317,3 +1919,119 @@ async fn sse_overflow_returns_partial_not_error() {
 
     server.abort();
 }
+
+// ---------------------------------------------------------------------------
+// Per-model output cap: stop reading once the cap is reached, cut at a line
+// boundary, and mark the result as capped rather than failing.
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn output_cap_stops_stream_at_line_boundary() {
+    let (listener, port) = mock_listener().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+
+        socket.write_all(SSE_HEADERS).await.unwrap();
+        for i in 0..200 {
+            let line = format!("finding {i:03}: the model keeps dumping prose\\n");
+            socket.write_all(sse_chunk(&line).as_bytes()).await.unwrap();
+        }
+        // Never send [DONE]: returning before the deadline proves the reader stopped.
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    });
+
+    let dispatch = HttpDispatch::new();
+    let req = ProviderRequest {
+        max_output_bytes: Some(1000),
+        ..make_req(30)
+    };
+
+    let start = Instant::now();
+    let result = dispatch
+        .query_model(
+            &req,
+            "test",
+            &format!("http://127.0.0.1:{port}/v1/chat"),
+            "fake",
+            &ApiFormat::OpenAi,
+        )
+        .await
+        .expect("capped output should be Ok(partial)");
+
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(result.partial);
+    assert!(result.output_capped);
+    assert!(result.text.len() <= 1000, "got {} bytes", result.text.len());
+    assert!(result.text.len() >= 500, "should keep most of the cap");
+    assert!(result.text.ends_with('\n'), "should cut at a line boundary");
+
+    server.abort();
+}
+
+// ---------------------------------------------------------------------------
+// Shared rate-limit windows: an exhausted window recorded by one dispatcher
+// makes another (e.g. in a different process) fail fast without a request.
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn exhausted_rate_limit_window_is_shared() {
+    use squall::dispatch::ratelimit::RateLimitStore;
+
+    let dir = std::env::temp_dir().join(format!(
+        "squall-test-shared-ratelimit-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let (listener, port) = mock_listener().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  Content-Type: text/event-stream\r\n\
+                  x-ratelimit-remaining-requests: 0\r\n\
+                  x-ratelimit-reset-requests: 10m0s\r\n\
+                  Connection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        socket
+            .write_all(sse_chunk("last one").as_bytes())
+            .await
+            .unwrap();
+        socket.write_all(SSE_DONE).await.unwrap();
+        // A second request would hang here: the listener accepts only once.
+    });
+    let url = format!("http://127.0.0.1:{port}/v1/chat");
+
+    let first = HttpDispatch::new().with_rate_limits(RateLimitStore::new(&dir));
+    let result = first
+        .query_model(&make_req(30), "limited", &url, "fake", &ApiFormat::OpenAi)
+        .await
+        .unwrap();
+    assert_eq!(result.text, "last one");
+    server.await.unwrap();
+
+    let second = HttpDispatch::new().with_rate_limits(RateLimitStore::new(&dir));
+    let start = Instant::now();
+    let err = second
+        .query_model(&make_req(30), "limited", &url, "fake", &ApiFormat::OpenAi)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, SquallError::RateLimited { .. }),
+        "got {err:?}"
+    );
+    assert!(start.elapsed() < Duration::from_secs(2), "should fail fast");
+
+    // Other providers are unaffected by this provider's window.
+    assert!(RateLimitStore::new(&dir).wait_for("other").await.is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,164 @@
+//! Tests for distributed dispatch: a coordinator registry forwarding requests to
+//! a `squall worker` that holds the real backend (here, a mock SSE provider).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use squall::config::Config;
+use squall::dispatch::ProviderRequest;
+use squall::dispatch::registry::{ApiFormat, BackendConfig, ModelEntry, Registry};
+use squall::error::SquallError;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const TOKEN: &str = "test-worker-token";
+
+fn entry(model_id: &str, backend: BackendConfig) -> ModelEntry {
+    ModelEntry {
+        model_id: model_id.to_string(),
+        provider: "mock".to_string(),
+        backend,
+        speed_tier: "fast".to_string(),
+        ..Default::default()
+    }
+}
+
+fn registry(models: Vec<(&str, ModelEntry)>) -> Registry {
+    let models: HashMap<String, ModelEntry> = models
+        .into_iter()
+        .map(|(name, e)| (name.to_string(), e))
+        .collect();
+    Registry::from_config(Config {
+        models,
+        ..Default::default()
+    })
+}
+
+fn make_req(model: &str) -> ProviderRequest {
+    ProviderRequest {
+        prompt: "test".into(),
+        model: model.to_string(),
+        deadline: Instant::now() + Duration::from_secs(30),
+        working_directory: None,
+        system_prompt: None,
+        temperature: None,
+        max_tokens: None,
+        reasoning_effort: None,
+        cancellation_token: None,
+        stall_timeout: None,
+        max_output_bytes: None,
+        priority: None,
+        extra_body: None,
+        usage: None,
+    }
+}
+
+/// Mock OpenAI-compatible provider: answers one request with a short SSE stream.
+async fn mock_provider() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+                  data: {\"choices\":[{\"delta\":{\"content\":\"from worker\"}}]}\n\n\
+                  data: [DONE]\n\n",
+            )
+            .await
+            .unwrap();
+    });
+    port
+}
+
+/// Start a worker serving `worker_registry`; returns its base URL.
+async fn start_worker(worker_registry: Registry) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(squall::worker::serve(
+        listener,
+        Arc::new(worker_registry),
+        TOKEN.to_string(),
+    ));
+    url
+}
+
+fn remote(url: &str, token: &str) -> BackendConfig {
+    BackendConfig::Remote {
+        url: url.to_string(),
+        token: token.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn coordinator_forwards_to_worker() {
+    let provider_port = mock_provider().await;
+    let worker_url = start_worker(registry(vec![(
+        "mock-model",
+        entry(
+            "provider-model-v1",
+            BackendConfig::Http {
+                base_url: format!("http://127.0.0.1:{provider_port}/v1/chat"),
+                api_key: "worker-only-key".to_string(),
+                api_format: ApiFormat::OpenAi,
+            },
+        ),
+    )]))
+    .await;
+
+    let coordinator = registry(vec![(
+        "mock-model",
+        entry("mock-model", remote(&worker_url, TOKEN)),
+    )]);
+    let result = coordinator.query(&make_req("mock-model")).await.unwrap();
+
+    assert_eq!(result.text, "from worker");
+    assert_eq!(result.provider, "mock");
+    assert!(!result.partial);
+}
+
+#[tokio::test]
+async fn worker_rejects_wrong_token() {
+    let worker_url = start_worker(registry(vec![])).await;
+    let coordinator = registry(vec![(
+        "mock-model",
+        entry("mock-model", remote(&worker_url, "wrong-token")),
+    )]);
+
+    let err = coordinator
+        .query(&make_req("mock-model"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SquallError::AuthFailed { .. }), "got {err:?}");
+    assert!(!err.user_message().contains("wrong-token"));
+}
+
+#[tokio::test]
+async fn worker_unknown_model_maps_to_model_not_found() {
+    let worker_url = start_worker(registry(vec![])).await;
+    let coordinator = registry(vec![(
+        "absent",
+        entry("absent", remote(&worker_url, TOKEN)),
+    )]);
+
+    let err = coordinator.query(&make_req("absent")).await.unwrap_err();
+    assert!(
+        matches!(&err, SquallError::ModelNotFound { model, .. } if model == "absent"),
+        "got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn worker_healthz_needs_no_token() {
+    let worker_url = start_worker(registry(vec![])).await;
+    let body = reqwest::get(format!("{worker_url}/healthz"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(body.contains("ok"), "got {body}");
+}